@@ -31,8 +31,11 @@ fn codegen_benchmark(c: &mut Criterion) {
                     deps: Default::default(),
                     scopes: vec![],
                     transform_asset_urls: TransformAssetUrlsConfig::default(),
+                    template_plugins: Default::default(),
                     errors: vec![],
                     warnings: vec![],
+                    strip_client_only: false,
+                    module_loader: None,
                 };
 
                 fervid_transform::template::transform_and_record_template(template_block, &mut ctx);
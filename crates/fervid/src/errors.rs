@@ -1,8 +1,11 @@
 //! Error definitions for the glue code of `fervid`
 
 use fervid_parser::ParseError as SfcParseError;
+pub use fervid_transform::error::Severity;
 use fervid_transform::error::TransformError;
-use swc_core::common::Spanned;
+use std::fmt::Write;
+
+use swc_core::common::{BytePos, Span, Spanned};
 
 #[derive(Debug)]
 pub enum CompileError {
@@ -21,9 +24,179 @@ pub enum CompileError {
     TransformError(TransformError),
 }
 
+/// Line and column of a position in the source.
+/// Line is 1-based and column is 0-based, in characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// How many lines are shown before and after the highlighted ones
+const CODE_FRAME_CONTEXT_LINES: usize = 2;
+
+/// How many lines of a multi-line span are highlighted before the rest is cut
+const CODE_FRAME_MAX_HIGHLIGHTED_LINES: usize = 5;
+
+impl CompileError {
+    /// Stable code of the error, e.g. `duplicate-define-props`
+    pub fn code(&self) -> &'static str {
+        match self {
+            CompileError::SfcParse(e) => e.code(),
+            CompileError::TransformError(e) => e.code(),
+        }
+    }
+
+    /// Warnings, e.g. invalid HTML nesting, do not affect the generated code
+    pub fn severity(&self) -> Severity {
+        match self {
+            CompileError::SfcParse(_) => Severity::Error,
+            CompileError::TransformError(e) => e.severity(),
+        }
+    }
+
+    /// Renders the error with its severity, code, location and the code frame.
+    /// Column in the location is 1-based, as expected by the editors, e.g.
+    ///
+    /// ```text
+    /// error[duplicate-define-props]: duplicate defineProps() call
+    ///  --> App.vue:3:11
+    ///   |
+    /// 2 | const a = defineProps()
+    /// 3 | const b = defineProps()
+    ///   |           ^^^^^^^^^^^^^
+    /// ```
+    pub fn render(&self, source: &str, filename: &str) -> String {
+        let span = self.span();
+        let severity: &str = self.severity().into();
+        let mut result = format!("{}[{}]: {}", severity, self.code(), self);
+
+        if span.lo.0 == 0 {
+            return result;
+        }
+
+        let location = lookup_location(source, span.lo);
+        let frame = render_code_frame(source, span);
+        let gutter_width = frame.find('|').unwrap_or(1);
+        let _ = write!(
+            result,
+            "\n{:gutter_width$}--> {}:{}:{}\n{}",
+            "",
+            filename,
+            location.line,
+            location.column + 1,
+            frame,
+            gutter_width = gutter_width.saturating_sub(1)
+        );
+
+        result
+    }
+}
+
+/// Finds the line and column of a position in the source.
+/// Positions are 1-based as in the spans produced by `fervid`.
+pub fn lookup_location(source: &str, pos: BytePos) -> SourceLocation {
+    let offset = to_offset(source, pos);
+    let line_start = source[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+
+    SourceLocation {
+        line: source[..line_start].matches('\n').count() + 1,
+        column: source[line_start..offset].chars().count(),
+    }
+}
+
+/// Renders the lines of `source` around `span` with the span underlined, e.g.
+///
+/// ```text
+///   |
+/// 1 | <template>
+/// 2 |   <div :[foo></div>
+///   |        ^^^^^
+/// 3 | </template>
+/// ```
+///
+/// Returns an empty string for a dummy span.
+pub fn render_code_frame(source: &str, span: Span) -> String {
+    if span.lo.0 == 0 {
+        return String::new();
+    }
+
+    let lo = to_offset(source, span.lo);
+    let hi = to_offset(source, span.hi).max(lo);
+    let lines: Vec<&str> = source.split('\n').collect();
+    let start = lookup_location(source, span.lo).line;
+    // Line of the last character, the span end is exclusive
+    let end = lookup_location(source, BytePos(hi.max(lo + 1) as u32)).line;
+
+    let first_line = start.saturating_sub(CODE_FRAME_CONTEXT_LINES).max(1);
+    let last_highlighted = end.min(start + CODE_FRAME_MAX_HIGHLIGHTED_LINES - 1);
+    let last_line = if last_highlighted < end {
+        last_highlighted
+    } else {
+        (end + CODE_FRAME_CONTEXT_LINES).min(lines.len())
+    };
+
+    let gutter_width = last_line.to_string().len();
+    let mut result = format!("{:gutter_width$} |", "");
+
+    // Offset of the start of the current line
+    let mut line_offset: usize = lines[..first_line - 1].iter().map(|l| l.len() + 1).sum();
+
+    for line_number in first_line..=last_line {
+        let line = lines[line_number - 1];
+        let line_end = line_offset + line.len();
+        let text = line.strip_suffix('\r').unwrap_or(line);
+        let _ = write!(result, "\n{line_number:>gutter_width$} | {text}");
+
+        if (start..=last_highlighted).contains(&line_number) {
+            // `text` may be shorter than the line because of the stripped `\r`
+            let from = floor_char_boundary(text, lo.max(line_offset) - line_offset);
+            let to = floor_char_boundary(text, hi.min(line_end) - line_offset).max(from);
+
+            // Keep tabs, so that the underline is aligned with the text
+            let padding: String = text[..from]
+                .chars()
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let width = text[from..to].chars().count().max(1);
+            let _ = write!(
+                result,
+                "\n{:gutter_width$} | {padding}{}",
+                "",
+                "^".repeat(width)
+            );
+        }
+
+        line_offset = line_end + 1;
+    }
+
+    if last_highlighted < end {
+        let _ = write!(result, "\n{:gutter_width$} | ...", "");
+    }
+
+    result
+}
+
+/// Converts a 1-based position to a 0-based offset at a char boundary
+fn to_offset(source: &str, pos: BytePos) -> usize {
+    floor_char_boundary(source, (pos.0 as usize).saturating_sub(1))
+}
+
+/// Clamps `offset` to the length of `text` and moves it back to the nearest char boundary
+fn floor_char_boundary(text: &str, offset: usize) -> usize {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
 impl std::fmt::Display for CompileError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            CompileError::SfcParse(e) => e.fmt(f),
+            CompileError::TransformError(e) => e.fmt(f),
+        }
     }
 }
 
@@ -47,3 +220,87 @@ impl Spanned for CompileError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_renders_lf_source() {
+        let source = "a\nbcd\ne";
+        let span = Span::new(BytePos(4), BytePos(6));
+
+        assert_eq!(
+            SourceLocation { line: 2, column: 1 },
+            lookup_location(source, span.lo)
+        );
+        assert_eq!(
+            "  |\n1 | a\n2 | bcd\n  |  ^^\n3 | e",
+            render_code_frame(source, span)
+        );
+    }
+
+    #[test]
+    fn it_renders_crlf_source() {
+        let source = "a\r\nbcd\r\ne";
+        let span = Span::new(BytePos(5), BytePos(7));
+
+        assert_eq!(
+            SourceLocation { line: 2, column: 1 },
+            lookup_location(source, span.lo)
+        );
+        assert_eq!(
+            "  |\n1 | a\n2 | bcd\n  |  ^^\n3 | e",
+            render_code_frame(source, span)
+        );
+    }
+
+    #[test]
+    fn it_renders_span_at_end_of_file() {
+        let source = "ab\ncd";
+        let end = BytePos(source.len() as u32 + 1);
+        let span = Span::new(end, end);
+
+        assert_eq!(
+            SourceLocation { line: 2, column: 2 },
+            lookup_location(source, span.lo)
+        );
+        assert_eq!(
+            "  |\n1 | ab\n2 | cd\n  |   ^",
+            render_code_frame(source, span)
+        );
+    }
+
+    #[test]
+    fn it_renders_multibyte_characters() {
+        let source = "é = 1\nπ + x";
+
+        // `π`
+        let span = Span::new(BytePos(8), BytePos(10));
+        assert_eq!(
+            SourceLocation { line: 2, column: 0 },
+            lookup_location(source, span.lo)
+        );
+        assert_eq!(
+            "  |\n1 | é = 1\n2 | π + x\n  | ^",
+            render_code_frame(source, span)
+        );
+
+        // `x`, columns are counted in characters
+        let span = Span::new(BytePos(13), BytePos(14));
+        assert_eq!(
+            SourceLocation { line: 2, column: 4 },
+            lookup_location(source, span.lo)
+        );
+        assert_eq!(
+            "  |\n1 | é = 1\n2 | π + x\n  |     ^",
+            render_code_frame(source, span)
+        );
+
+        // Position inside of `π` does not panic
+        assert_eq!(
+            SourceLocation { line: 2, column: 0 },
+            lookup_location(source, BytePos(9))
+        );
+    }
+}
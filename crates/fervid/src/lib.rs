@@ -27,6 +27,14 @@
 //!   scope_id: "filehash",
 //!   filename: "input.vue",
 //!   transform_asset_urls: fervid_transform::TransformAssetUrlsConfig::default(),
+//!   template_plugins: fervid_transform::TemplatePlugins::default(),
+//!   custom_block_handlers: fervid_transform::CustomBlockHandlers::default(),
+//!   source: input,
+//!   source_map: false,
+//!   inline_template: None,
+//!   strip_client_only: false,
+//!   target: fervid_core::CompileTarget::Vue3,
+//!   module_loader: None,
 //! };
 //! let transform_result = fervid_transform::transform_sfc(sfc, transform_options, &mut transform_errors);
 //!
@@ -47,32 +55,52 @@
 //! );
 //!
 //! // (Optional) Stringify the code
-//! let compiled_code = fervid_codegen::CodegenContext::stringify(input, &sfc_module, FileName::Custom("input.vue".into()), false, false);
+//! let compiled_code = fervid_codegen::CodegenContext::stringify(input, &sfc_module, Some(parser.comments()), FileName::Custom("input.vue".into()), false, false);
 //! ```
 
 extern crate lazy_static;
 
 pub mod errors;
+mod migrate;
+mod module_loader;
 #[deprecated]
 pub mod parser_old;
 
 use errors::CompileError;
-use fervid_codegen::CodegenContext;
+pub use fervid_codegen::{
+    print_sfc, PrintSfcOptions, PrintWhitespace, VirtualTsMapping, VirtualTsResult,
+};
+use fervid_codegen::{CodegenContext, VirtualTsOptions};
 pub use fervid_core::*;
+pub use fervid_parser::ErrorRecoveryMode;
 use fervid_parser::SfcParser;
-use fervid_transform::TransformAssetUrlsConfig;
 pub use fervid_transform::{
-    style::should_transform_style_block, transform_sfc, PropsDestructureConfig, SetupBinding,
-    TransformSfcOptions,
+    style::should_transform_style_block, transform_sfc, transform_sfc_template, ComponentEventMeta,
+    ComponentExposedMeta, ComponentMeta, ComponentModelMeta, ComponentPropMeta, ComponentSlotMeta,
+    CustomBlockContext, CustomBlockHandler, CustomBlockHandlers, DirectiveTransform,
+    I18nBlockHandler, LoadedModule, ModuleLoader, NodeTransform, PropsDestructureConfig,
+    SetupBinding, TemplatePluginContext, TemplatePlugins, TransformSfcOptions,
+};
+use fervid_transform::{
+    style::{create_style_scope, transform_style_blocks},
+    BindingsHelper, TransformAssetUrlsConfig,
 };
 use fxhash::FxHasher32;
+pub use migrate::{migrate_to_script_setup, MigrateResult};
+pub use module_loader::{CachedModuleLoader, FsModuleLoader};
 use std::{
     borrow::Cow,
     hash::{Hash, Hasher},
+    sync::Arc,
+};
+use swc_core::{
+    common::{
+        comments::{Comments, SingleThreadedComments},
+        FileName,
+    },
+    ecma::ast::Expr,
 };
-use swc_core::{common::FileName, ecma::ast::Expr};
 
-// TODO Add severity to errors
 // TODO Better structs
 
 #[derive(Debug, Clone)]
@@ -85,6 +113,11 @@ pub struct CompileOptions<'o> {
     pub is_prod: Option<bool>,
     pub is_custom_element: Option<bool>,
     pub ssr: Option<bool>,
+    /// Together with `ssr`, drops the client-only lifecycle hooks (e.g. `onMounted`)
+    /// of `<script setup>` and the `v-on` listeners of the plain elements
+    pub strip_client_only: Option<bool>,
+    /// Vue version the component is compiled for. Defaults to `Vue3`
+    pub target: Option<CompileTarget>,
     pub props_destructure: Option<PropsDestructureConfig>,
     // pub ssrCssVars?: string[],
     // pub inMap?: RawSourceMap,
@@ -101,11 +134,27 @@ pub struct CompileOptions<'o> {
     // or disable the transform altogether with `false`.
     pub transform_asset_urls: Option<TransformAssetUrlsConfig>,
 
+    // Compile-time transforms of the template, e.g. custom directives.
+    pub template_plugins: Option<TemplatePlugins>,
+
+    // Compilers of the custom blocks by the tag name, e.g. `<i18n>`.
+    // Compiled blocks are applied to the exported component.
+    pub custom_blocks: Option<CustomBlockHandlers>,
+
+    // Loads the modules of the imported Options API `mixins` and `extends`,
+    // e.g. `FsModuleLoader`, so that their bindings are resolved in the template.
+    pub module_loader: Option<Arc<dyn ModuleLoader>>,
+
     // script
     pub gen_default_as: Option<Cow<'o, str>>,
+    /// Inline the template into `<script setup>`. Defaults to `is_prod`
+    pub inline_template: Option<bool>,
 
     // fervid-specific
     pub source_map: Option<bool>,
+    /// How the parser proceeds after encountering an error. Defaults to `SoftRecoverSafe`.
+    /// With `SoftReportAll`, any parsing error fails the compilation and no code is generated
+    pub error_recovery_mode: Option<ErrorRecoveryMode>,
 }
 
 pub struct CompileResult {
@@ -123,6 +172,9 @@ pub struct CompileEmittedStyle {
     pub is_compiled: bool,
     pub lang: String,
     pub is_scoped: bool,
+    /// Source map of the compiled style, relative to the SFC.
+    /// Only present for compiled styles when `source_map` option is enabled.
+    pub source_map: Option<String>,
 }
 
 pub struct CompileEmittedAsset {
@@ -137,50 +189,78 @@ pub struct CompileEmittedAsset {
 pub fn compile(source: &str, options: CompileOptions) -> Result<CompileResult, CompileError> {
     let mut all_errors = Vec::<CompileError>::new();
 
-    // Options
-    let is_prod = options.is_prod.unwrap_or_default();
-    let is_custom_element = options.is_custom_element.unwrap_or_default();
+    // For scopes
+    // TODO Research if it's better to compute that on the caller site or here
+    let file_hash = compute_file_hash(source);
 
     // Parse
+    let error_recovery_mode = options.error_recovery_mode.unwrap_or_default();
     let mut sfc_parsing_errors = Vec::new();
     let mut parser = SfcParser::new(source, &mut sfc_parsing_errors);
-    let sfc = parser.parse_sfc()?;
-    all_errors.extend(sfc_parsing_errors.into_iter().map(From::from));
-
-    // For scopes
-    // TODO Research if it's better to compute that on the caller site or here
-    let file_hash = {
-        let mut hasher = FxHasher32::default();
-        source.hash(&mut hasher);
-        let num = hasher.finish();
-        format!("{:x}", num)
+    parser.error_recovery_mode = error_recovery_mode;
+    let parse_result = parser.parse_sfc();
+    let comments = parser.comments().clone();
+    let sfc = match parse_result {
+        Ok(sfc) => sfc,
+
+        // Nothing is generated, but all the errors are reported
+        Err(e) if error_recovery_mode == ErrorRecoveryMode::SoftReportAll => {
+            all_errors.push(e.into());
+            all_errors.extend(sfc_parsing_errors.into_iter().map(From::from));
+
+            return Ok(CompileResult {
+                code: String::new(),
+                file_hash,
+                errors: all_errors,
+                styles: Vec::new(),
+                other_assets: Vec::new(),
+                source_map: None,
+                setup_bindings: Vec::new(),
+            });
+        }
+
+        Err(e) => return Err(e.into()),
     };
+    all_errors.extend(sfc_parsing_errors.into_iter().map(From::from));
 
     // Transform
+    let source_map = options.source_map.unwrap_or(false);
     let mut transform_errors = Vec::new();
-    let transform_options = TransformSfcOptions {
-        is_prod,
-        is_ce: is_custom_element,
-        props_destructure: options.props_destructure.unwrap_or_default(),
-        scope_id: &file_hash,
-        filename: &options.filename,
-        transform_asset_urls: options.transform_asset_urls.unwrap_or_default(),
-    };
+    let transform_options = create_transform_options(source, &options, &file_hash);
     let transform_result = transform_sfc(sfc, transform_options, &mut transform_errors);
     all_errors.extend(transform_errors.into_iter().map(From::from));
 
     // Codegen
     let mut ctx = CodegenContext::with_bindings_helper(transform_result.bindings_helper);
 
-    let template_expr: Option<Expr> = transform_result
-        .template_block
-        .and_then(|template_block| ctx.generate_sfc_template(&template_block));
-
-    let sfc_module = ctx.generate_module(
-        template_expr,
-        *transform_result.module,
-        transform_result.exported_obj,
-        transform_result.setup_fn,
+    let mut sfc_module = if ctx.bindings_helper.is_vapor {
+        let vapor_render = transform_result
+            .template_block
+            .and_then(|template_block| ctx.generate_vapor_template(&template_block));
+
+        ctx.generate_vapor_module(
+            vapor_render,
+            *transform_result.module,
+            transform_result.exported_obj,
+            transform_result.setup_fn,
+            options.gen_default_as.as_deref(),
+        )
+    } else {
+        let template_expr: Option<Expr> = transform_result
+            .template_block
+            .and_then(|template_block| ctx.generate_sfc_template(&template_block));
+
+        ctx.generate_module(
+            template_expr,
+            *transform_result.module,
+            transform_result.exported_obj,
+            transform_result.setup_fn,
+            options.gen_default_as.as_deref(),
+        )
+    };
+    ctx.generate_custom_blocks(
+        &mut sfc_module,
+        transform_result.custom_block_modules,
         options.gen_default_as.as_deref(),
     );
 
@@ -188,11 +268,13 @@ pub fn compile(source: &str, options: CompileOptions) -> Result<CompileResult, C
     let (code, source_map) = CodegenContext::stringify(
         source,
         &sfc_module,
+        Some(&comments),
         FileName::Custom(options.filename.to_string()),
-        options.source_map.unwrap_or(false),
+        source_map,
         false,
     );
 
+    let mut style_source_maps = transform_result.style_source_maps.into_iter();
     let styles = transform_result
         .style_blocks
         .into_iter()
@@ -201,19 +283,18 @@ pub fn compile(source: &str, options: CompileOptions) -> Result<CompileResult, C
             is_compiled: should_transform_style_block(&style_block),
             lang: style_block.lang.to_string(),
             is_scoped: style_block.is_scoped,
+            source_map: style_source_maps.next().flatten(),
         })
         .collect();
 
     let other_assets = transform_result
         .custom_blocks
         .into_iter()
-        .map(|block| {
-            CompileEmittedAsset {
-                lo: 0, // todo
-                hi: 0, // todo
-                tag_name: block.starting_tag.tag_name.to_string(),
-                content: block.content.to_string(),
-            }
+        .map(|block| CompileEmittedAsset {
+            lo: block.span.lo.0,
+            hi: block.span.hi.0,
+            tag_name: block.starting_tag.tag_name.to_string(),
+            content: block.content.to_string(),
         })
         .collect();
 
@@ -228,6 +309,496 @@ pub fn compile(source: &str, options: CompileOptions) -> Result<CompileResult, C
     })
 }
 
+pub struct ParseResult {
+    /// Parsed SFC. When transformed, its `template` and `styles` are the transformed ones
+    pub descriptor: SfcDescriptor,
+    /// Comments of the scripts and the template expressions,
+    /// to be passed to [`compile_script`] and [`compile_template`]
+    pub comments: SingleThreadedComments,
+    /// Bindings collected from the scripts, only present when transformed
+    pub setup_bindings: Vec<SetupBinding>,
+    pub errors: Vec<CompileError>,
+}
+
+/// Parses an SFC into a descriptor without generating the code.
+///
+/// When `transform_options` are provided, the template and styles are transformed
+/// the same way [`compile`] does it, e.g. to inspect the optimized template IR.
+/// With `ErrorRecoveryMode::SoftReportAll`, a failed parse returns an empty descriptor
+/// together with all the errors.
+/// Enable the `serde` feature to serialize the result.
+pub fn parse(
+    source: &str,
+    transform_options: Option<&CompileOptions>,
+) -> Result<ParseResult, CompileError> {
+    let mut all_errors = Vec::<CompileError>::new();
+
+    // Parse
+    let error_recovery_mode = transform_options
+        .and_then(|o| o.error_recovery_mode)
+        .unwrap_or_default();
+    let mut sfc_parsing_errors = Vec::new();
+    let mut parser = SfcParser::new(source, &mut sfc_parsing_errors);
+    parser.error_recovery_mode = error_recovery_mode;
+    let parse_result = parser.parse_sfc();
+    let comments = parser.comments().clone();
+    let sfc = match parse_result {
+        Ok(sfc) => sfc,
+
+        // Nothing is transformed, but all the errors are reported
+        Err(e) if error_recovery_mode == ErrorRecoveryMode::SoftReportAll => {
+            all_errors.push(e.into());
+            all_errors.extend(sfc_parsing_errors.into_iter().map(From::from));
+
+            return Ok(ParseResult {
+                descriptor: SfcDescriptor::default(),
+                comments,
+                setup_bindings: Vec::new(),
+                errors: all_errors,
+            });
+        }
+
+        Err(e) => return Err(e.into()),
+    };
+    all_errors.extend(sfc_parsing_errors.into_iter().map(From::from));
+
+    let Some(options) = transform_options else {
+        return Ok(ParseResult {
+            descriptor: sfc,
+            comments,
+            setup_bindings: Vec::new(),
+            errors: all_errors,
+        });
+    };
+
+    // Scripts are merged into a single module by the transform, thus originals are kept
+    let script_legacy = sfc.script_legacy.clone();
+    let script_setup = sfc.script_setup.clone();
+    let vapor = sfc.vapor;
+
+    // Transform
+    let file_hash = compute_file_hash(source);
+    let mut transform_errors = Vec::new();
+    let transform_result = transform_sfc(
+        sfc,
+        create_transform_options(source, options, &file_hash),
+        &mut transform_errors,
+    );
+    all_errors.extend(transform_errors.into_iter().map(From::from));
+
+    Ok(ParseResult {
+        descriptor: SfcDescriptor {
+            template: transform_result.template_block,
+            script_legacy,
+            script_setup,
+            styles: transform_result.style_blocks,
+            custom_blocks: transform_result.custom_blocks,
+            vapor,
+        },
+        comments,
+        setup_bindings: transform_result.bindings_helper.setup_bindings,
+        errors: all_errors,
+    })
+}
+
+pub struct CompileScriptResult {
+    pub code: String,
+    pub source_map: Option<String>,
+    /// Bindings of both `<script>` and `<script setup>`, to be passed to [`compile_template`]
+    pub bindings: Vec<SetupBinding>,
+    pub errors: Vec<CompileError>,
+}
+
+pub struct CompileTemplateResult {
+    pub code: String,
+    pub source_map: Option<String>,
+    pub errors: Vec<CompileError>,
+}
+
+pub struct CompileStyleResult {
+    pub style: CompileEmittedStyle,
+    pub errors: Vec<CompileError>,
+}
+
+/// Compiles the scripts of a parsed SFC into the component module,
+/// similar to `compileScript` of `@vue/compiler-sfc`.
+///
+/// When the template is inlined (see [`CompileOptions::inline_template`]), it is rendered from `setup`.
+/// Otherwise, `setup` returns the bindings and the template is meant to be compiled
+/// by [`compile_template`] using the returned `bindings`.
+/// Vapor components (`<script setup vapor>`) always render from `setup`, the same way as in [`compile`].
+/// Styles only contribute the `__scopeId`, use [`compile_style`] to compile them.
+/// Without the `comments` returned from [`parse`], the comments of the scripts are dropped.
+pub fn compile_script(
+    source: &str,
+    descriptor: &SfcDescriptor,
+    comments: Option<&SingleThreadedComments>,
+    options: &CompileOptions,
+) -> CompileScriptResult {
+    let mut all_errors = Vec::<CompileError>::new();
+
+    // Transform. The template is kept even when not inlined,
+    // because bindings unused in the template are not returned from `setup`
+    let file_hash = compute_file_hash(source);
+    let mut transform_errors = Vec::new();
+    let transform_result = transform_sfc(
+        descriptor.clone(),
+        create_transform_options(source, options, &file_hash),
+        &mut transform_errors,
+    );
+    all_errors.extend(transform_errors.into_iter().map(From::from));
+
+    // Codegen
+    let mut ctx = CodegenContext::with_bindings_helper(transform_result.bindings_helper);
+
+    let sfc_module = if ctx.bindings_helper.is_vapor {
+        let vapor_render = transform_result
+            .template_block
+            .and_then(|template_block| ctx.generate_vapor_template(&template_block));
+
+        ctx.generate_vapor_module(
+            vapor_render,
+            *transform_result.module,
+            transform_result.exported_obj,
+            transform_result.setup_fn,
+            options.gen_default_as.as_deref(),
+        )
+    } else {
+        let is_inline = matches!(
+            ctx.bindings_helper.template_generation_mode,
+            TemplateGenerationMode::Inline
+        );
+        let template_expr: Option<Expr> = transform_result
+            .template_block
+            .filter(|_| is_inline)
+            .and_then(|template_block| ctx.generate_sfc_template(&template_block));
+
+        ctx.generate_module(
+            template_expr,
+            *transform_result.module,
+            transform_result.exported_obj,
+            transform_result.setup_fn,
+            options.gen_default_as.as_deref(),
+        )
+    };
+
+    let (code, source_map) = CodegenContext::stringify(
+        source,
+        &sfc_module,
+        comments.map(|comments| comments as &dyn Comments),
+        FileName::Custom(options.filename.to_string()),
+        options.source_map.unwrap_or(false),
+        false,
+    );
+
+    CompileScriptResult {
+        code,
+        source_map,
+        bindings: collect_bindings(ctx.bindings_helper),
+        errors: all_errors,
+    }
+}
+
+/// Compiles the template of a parsed SFC into a module exporting the `render` function,
+/// similar to `compileTemplate` of `@vue/compiler-sfc`.
+///
+/// `bindings` are the ones returned from [`compile_script`],
+/// `comments` are the ones returned from [`parse`].
+pub fn compile_template(
+    source: &str,
+    descriptor: &SfcDescriptor,
+    comments: Option<&SingleThreadedComments>,
+    options: &CompileOptions,
+    mut bindings: Vec<SetupBinding>,
+) -> CompileTemplateResult {
+    let mut all_errors = Vec::<CompileError>::new();
+
+    // Template module does not share the scope with the script module,
+    // thus imports are accessed from `$setup` the same way as other bindings
+    for binding in bindings.iter_mut() {
+        binding.binding_type = match binding.binding_type {
+            BindingTypes::Imported => BindingTypes::SetupMaybeRef,
+            BindingTypes::Component => BindingTypes::SetupConst,
+            binding_type => binding_type,
+        };
+    }
+
+    // Transform
+    let file_hash = compute_file_hash(source);
+    let mut transform_errors = Vec::new();
+    let (bindings_helper, template_block) = match descriptor.template {
+        Some(ref template) => {
+            let transform_result = transform_sfc_template(
+                template.clone(),
+                bindings,
+                create_transform_options(source, options, &file_hash),
+                &mut transform_errors,
+            );
+            (
+                transform_result.bindings_helper,
+                transform_result.template_block,
+            )
+        }
+        None => (BindingsHelper::default(), None),
+    };
+    all_errors.extend(transform_errors.into_iter().map(From::from));
+
+    // Codegen
+    let mut ctx = CodegenContext::with_bindings_helper(bindings_helper);
+    let template_expr =
+        template_block.and_then(|template_block| ctx.generate_sfc_template(&template_block));
+    let template_module = ctx.generate_template_module(template_expr);
+
+    let (code, source_map) = CodegenContext::stringify(
+        source,
+        &template_module,
+        comments.map(|comments| comments as &dyn Comments),
+        FileName::Custom(options.filename.to_string()),
+        options.source_map.unwrap_or(false),
+        false,
+    );
+
+    CompileTemplateResult {
+        code,
+        source_map,
+        errors: all_errors,
+    }
+}
+
+/// Compiles a single style block of a parsed SFC,
+/// similar to `compileStyle` of `@vue/compiler-sfc`.
+///
+/// Scoped CSS blocks use the same scope as the one attached by [`compile_script`].
+/// Blocks using a preprocessor are returned as-is with `is_compiled: false`.
+pub fn compile_style(
+    source: &str,
+    style_block: &SfcStyleBlock,
+    options: &CompileOptions,
+) -> CompileStyleResult {
+    let scope = create_style_scope(&compute_file_hash(source));
+    let source_map =
+        options
+            .source_map
+            .unwrap_or(false)
+            .then_some(fervid_css::CssSourceMapConfig {
+                filename: &options.filename,
+                source,
+            });
+
+    let mut style_blocks = [style_block.clone()];
+    let mut source_maps = Vec::new();
+    let mut transform_errors = Vec::new();
+    transform_style_blocks(
+        &mut style_blocks,
+        &scope,
+        source_map,
+        &mut source_maps,
+        &mut transform_errors,
+    );
+
+    let [style_block] = style_blocks;
+    CompileStyleResult {
+        style: CompileEmittedStyle {
+            code: style_block.content.to_string(),
+            is_compiled: should_transform_style_block(&style_block),
+            lang: style_block.lang.to_string(),
+            is_scoped: style_block.is_scoped,
+            source_map: source_maps.pop().flatten(),
+        },
+        errors: transform_errors.into_iter().map(From::from).collect(),
+    }
+}
+
+pub struct ExtractComponentMetaResult {
+    pub meta: ComponentMeta,
+    pub errors: Vec<CompileError>,
+}
+
+/// Parses an SFC and extracts the public interface of the component,
+/// i.e. its props, events, slots, models and exposed members.
+///
+/// Meant for documentation tooling, no code is generated.
+pub fn extract_component_meta(
+    source: &str,
+    filename: &str,
+) -> Result<ExtractComponentMetaResult, CompileError> {
+    let mut all_errors = Vec::<CompileError>::new();
+
+    // Parse
+    let mut sfc_parsing_errors = Vec::new();
+    let mut parser = SfcParser::new(source, &mut sfc_parsing_errors);
+    let sfc = parser.parse_sfc()?;
+    let comments = parser.comments().clone();
+    all_errors.extend(sfc_parsing_errors.into_iter().map(From::from));
+
+    // Extract
+    let mut transform_errors = Vec::new();
+    let meta = fervid_transform::extract_component_meta(
+        &sfc,
+        fervid_transform::ComponentMetaOptions {
+            filename,
+            source,
+            comments: Some(&comments),
+        },
+        &mut transform_errors,
+    );
+    all_errors.extend(transform_errors.into_iter().map(From::from));
+
+    Ok(ExtractComponentMetaResult {
+        meta,
+        errors: all_errors,
+    })
+}
+
+pub struct GenerateVirtualTsResult {
+    pub virtual_ts: VirtualTsResult,
+    pub errors: Vec<CompileError>,
+}
+
+/// Parses an SFC and generates a virtual TypeScript file for type-checking it,
+/// along with the mappings of the generated code back to the `source`.
+///
+/// The generated code is meant to be consumed by `tsc` or a language server, not executed.
+pub fn generate_virtual_ts(
+    source: &str,
+    filename: &str,
+) -> Result<GenerateVirtualTsResult, CompileError> {
+    let mut all_errors = Vec::<CompileError>::new();
+
+    // Parse
+    let mut sfc_parsing_errors = Vec::new();
+    let mut parser = SfcParser::new(source, &mut sfc_parsing_errors);
+    let sfc = parser.parse_sfc()?;
+    let comments = parser.comments().clone();
+    all_errors.extend(sfc_parsing_errors.into_iter().map(From::from));
+
+    // Props and models are needed in the template context
+    let mut transform_errors = Vec::new();
+    let meta = fervid_transform::extract_component_meta(
+        &sfc,
+        fervid_transform::ComponentMetaOptions {
+            filename,
+            source,
+            comments: Some(&comments),
+        },
+        &mut transform_errors,
+    );
+    all_errors.extend(transform_errors.into_iter().map(From::from));
+
+    // Generate
+    let virtual_ts = fervid_codegen::generate_virtual_ts(&sfc, &meta, &VirtualTsOptions { source });
+
+    Ok(GenerateVirtualTsResult {
+        virtual_ts,
+        errors: all_errors,
+    })
+}
+
+/// Hash of the SFC source, used as a scope id
+fn compute_file_hash(source: &str) -> String {
+    let mut hasher = FxHasher32::default();
+    source.hash(&mut hasher);
+    let num = hasher.finish();
+    format!("{:x}", num)
+}
+
+/// Flattens the bindings of both scripts, the same way the template resolves them
+fn collect_bindings(bindings_helper: BindingsHelper) -> Vec<SetupBinding> {
+    let mut bindings = bindings_helper.setup_bindings;
+
+    let Some(options_api_bindings) = bindings_helper.options_api_bindings else {
+        return bindings;
+    };
+    let options_api_bindings = *options_api_bindings;
+
+    bindings.extend(options_api_bindings.setup);
+    let options_api_vars = [
+        (options_api_bindings.data, BindingTypes::Data),
+        (options_api_bindings.props, BindingTypes::Props),
+        (options_api_bindings.computed, BindingTypes::Options),
+        (options_api_bindings.methods, BindingTypes::Options),
+        (options_api_bindings.inject, BindingTypes::Options),
+    ];
+    for (vars, binding_type) in options_api_vars {
+        bindings.extend(
+            vars.into_iter()
+                .map(|sym| SetupBinding::new(sym, binding_type)),
+        );
+    }
+    bindings.extend(options_api_bindings.imports.into_iter().map(|binding| {
+        SetupBinding::new_spanned(binding.sym, BindingTypes::SetupMaybeRef, binding.span)
+    }));
+
+    bindings
+}
+
+fn create_transform_options<'o>(
+    source: &'o str,
+    options: &'o CompileOptions,
+    file_hash: &'o str,
+) -> TransformSfcOptions<'o> {
+    TransformSfcOptions {
+        is_prod: options.is_prod.unwrap_or_default(),
+        is_ce: options.is_custom_element.unwrap_or_default(),
+        props_destructure: options.props_destructure.unwrap_or_default(),
+        scope_id: file_hash,
+        filename: &options.filename,
+        transform_asset_urls: options.transform_asset_urls.clone().unwrap_or_default(),
+        template_plugins: options.template_plugins.clone().unwrap_or_default(),
+        custom_block_handlers: options.custom_blocks.clone().unwrap_or_default(),
+        source,
+        source_map: options.source_map.unwrap_or(false),
+        inline_template: options.inline_template,
+        strip_client_only: options.ssr.unwrap_or(false)
+            && options.strip_client_only.unwrap_or(false),
+        target: options.target.unwrap_or_default(),
+        module_loader: options.module_loader.clone(),
+    }
+}
+
+/// Compiles multiple SFCs in parallel using the global `rayon` thread pool.
+///
+/// Each input is an SFC source paired with its [`CompileOptions`].
+/// Results are returned in the same order as the inputs,
+/// so that a failure of one file does not affect the others.
+///
+/// The modules loaded by the [`CompileOptions::module_loader`] (e.g. the imported `mixins`)
+/// are shared between the files, see [`CachedModuleLoader`].
+/// Type scopes are not shared, because type resolution does not load other files.
+#[cfg(feature = "parallel")]
+pub fn compile_many<'o>(
+    mut inputs: Vec<(&'o str, CompileOptions<'o>)>,
+) -> Vec<Result<CompileResult, CompileError>> {
+    use rayon::prelude::*;
+
+    // One cache per distinct loader, usually all the files share the same one
+    let mut cached_loaders: Vec<(Arc<dyn ModuleLoader>, Arc<dyn ModuleLoader>)> = Vec::new();
+    for (_, options) in inputs.iter_mut() {
+        let Some(ref loader) = options.module_loader else {
+            continue;
+        };
+
+        let existing = cached_loaders
+            .iter()
+            .find(|(original, _)| std::ptr::addr_eq(Arc::as_ptr(original), Arc::as_ptr(loader)));
+        let cached = match existing {
+            Some((_, cached)) => cached.clone(),
+            None => {
+                let cached: Arc<dyn ModuleLoader> =
+                    Arc::new(CachedModuleLoader::new(loader.clone()));
+                cached_loaders.push((loader.clone(), cached.clone()));
+                cached
+            }
+        };
+        options.module_loader = Some(cached);
+    }
+
+    inputs
+        .into_par_iter()
+        .map(|(source, options)| compile(source, options))
+        .collect()
+}
+
 /// Naive implementation of the SFC compilation, meaning that:
 /// - it handles the standard flow without plugins;
 /// - it compiles to `String` instead of SWC module;
@@ -244,6 +815,7 @@ pub fn compile_sync_naive(source: &str, is_prod: bool) -> Result<String, String>
     let mut errors = Vec::new();
     let mut parser = SfcParser::new(source, &mut errors);
     let sfc = parser.parse_sfc().map_err(|err| err.to_string())?;
+    let comments = parser.comments().clone();
 
     // For scopes
     let file_hash = {
@@ -262,6 +834,14 @@ pub fn compile_sync_naive(source: &str, is_prod: bool) -> Result<String, String>
         scope_id: &file_hash,
         filename: "anonymous.vue",
         transform_asset_urls: TransformAssetUrlsConfig::default(),
+        template_plugins: TemplatePlugins::default(),
+        custom_block_handlers: CustomBlockHandlers::default(),
+        source,
+        source_map: false,
+        inline_template: None,
+        strip_client_only: false,
+        target: CompileTarget::Vue3,
+        module_loader: None,
     };
     let transform_result = transform_sfc(sfc, transform_options, &mut transform_errors);
 
@@ -280,8 +860,131 @@ pub fn compile_sync_naive(source: &str, is_prod: bool) -> Result<String, String>
         None,
     );
 
-    let (compiled_code, _map) =
-        CodegenContext::stringify(source, &sfc_module, FileName::Anon, false, false);
+    let (compiled_code, _map) = CodegenContext::stringify(
+        source,
+        &sfc_module,
+        Some(&comments),
+        FileName::Anon,
+        false,
+        false,
+    );
 
     Ok(compiled_code)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_compiles_vapor_script_as_compile() {
+        let source = r#"<script setup vapor>
+import { ref } from 'vue'
+const msg = ref('hello')
+</script>
+<template><div @click="msg = 'bye'">{{ msg }}</div></template>"#;
+        let options = compile_options();
+
+        let parsed = parse(source, None).expect("Should parse");
+        let script = compile_script(source, &parsed.descriptor, Some(&parsed.comments), &options);
+        let compiled = compile(source, compile_options()).expect("Should compile");
+
+        assert!(script.errors.is_empty());
+        assert!(script.code.contains("_defineVaporComponent("));
+        assert!(!script.code.contains("_openBlock"));
+        assert_eq!(compiled.code, script.code);
+    }
+
+    #[test]
+    fn it_keeps_comments_in_split_compilation() {
+        let source = r#"<script setup>
+// Store of the component
+const store = /*#__PURE__*/ createStore()
+</script>
+<template><div :title="/* hint */ store.title">{{ store.name }}</div></template>"#;
+        let options = compile_options();
+
+        let parsed = parse(source, None).expect("Should parse");
+        let script = compile_script(source, &parsed.descriptor, Some(&parsed.comments), &options);
+        let template = compile_template(
+            source,
+            &parsed.descriptor,
+            Some(&parsed.comments),
+            &options,
+            script.bindings,
+        );
+
+        assert!(script.code.contains("// Store of the component"));
+        assert!(script.code.contains("/*#__PURE__*/ createStore()"));
+        assert!(template.code.contains("/* hint */"));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn it_resolves_shared_loader_once_per_batch() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Default)]
+        struct CountingLoader {
+            loads: AtomicUsize,
+        }
+
+        impl ModuleLoader for CountingLoader {
+            fn load(&self, specifier: &str, _importer: &str) -> Option<LoadedModule> {
+                self.loads.fetch_add(1, Ordering::SeqCst);
+                (specifier == "./mixin").then(|| LoadedModule {
+                    filename: "/src/mixin.js".to_string(),
+                    source: "export default { data: () => ({ foo: 1 }) }".to_string(),
+                })
+            }
+        }
+
+        let source = r#"<script>
+import mixin from './mixin'
+export default { mixins: [mixin] }
+</script>
+<template>{{ foo }}</template>"#;
+
+        let loader = Arc::new(CountingLoader::default());
+        let shared: Arc<dyn ModuleLoader> = loader.clone();
+        let inputs = ["/src/A.vue", "/src/B.vue", "/src/C.vue"]
+            .into_iter()
+            .map(|filename| {
+                let options = CompileOptions {
+                    filename: Cow::Borrowed(filename),
+                    module_loader: Some(shared.clone()),
+                    ..compile_options()
+                };
+                (source, options)
+            })
+            .collect();
+
+        for result in compile_many(inputs) {
+            let result = result.expect("Should compile");
+            assert!(result.errors.is_empty());
+            assert!(result.code.contains("$data.foo"));
+        }
+        assert_eq!(1, loader.loads.load(Ordering::SeqCst));
+    }
+
+    fn compile_options<'o>() -> CompileOptions<'o> {
+        CompileOptions {
+            filename: Cow::Borrowed("Comp.vue"),
+            id: Cow::Borrowed("xxxxxxxx"),
+            is_prod: None,
+            is_custom_element: None,
+            ssr: None,
+            strip_client_only: None,
+            target: None,
+            props_destructure: None,
+            transform_asset_urls: None,
+            template_plugins: None,
+            custom_blocks: None,
+            module_loader: None,
+            gen_default_as: None,
+            inline_template: None,
+            source_map: None,
+            error_recovery_mode: None,
+        }
+    }
+}
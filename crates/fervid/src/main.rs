@@ -1,16 +1,102 @@
 extern crate swc_core;
 extern crate swc_ecma_codegen;
 extern crate swc_ecma_parser;
-use std::time::Instant;
+use std::{borrow::Cow, sync::Arc, time::Instant};
 
-use fervid::compile_sync_naive;
+use fervid::{
+    compile, compile_sync_naive, migrate_to_script_setup, CompileOptions, FsModuleLoader,
+};
 
 fn main() {
     let n = Instant::now();
-    test_real_compilation();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("migrate") => migrate_files(&args[1..]),
+        Some(path) => compile_file(path),
+        None => test_real_compilation(),
+    }
     println!("Time took: {:?}", n.elapsed());
 }
 
+/// Compiles an SFC from disk and prints the code along with the diagnostics
+fn compile_file(path: &str) {
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("Could not read {path}: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let options = CompileOptions {
+        filename: Cow::Borrowed(path),
+        id: Cow::Borrowed("xxxxxxxx"),
+        is_prod: None,
+        is_custom_element: None,
+        ssr: None,
+        strip_client_only: None,
+        target: None,
+        props_destructure: None,
+        transform_asset_urls: None,
+        template_plugins: None,
+        custom_blocks: None,
+        module_loader: Some(Arc::new(FsModuleLoader)),
+        gen_default_as: None,
+        inline_template: None,
+        source_map: None,
+        error_recovery_mode: None,
+    };
+
+    match compile(&source, options) {
+        Ok(result) => {
+            println!("{}", result.code);
+            for error in result.errors {
+                eprintln!("{}\n", error.render(&source, path));
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e.render(&source, path));
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Migrates the Options API SFCs to `<script setup>`.
+/// Usage: `fervid migrate [--write] <files...>`, without `--write` the result is printed
+fn migrate_files(args: &[String]) {
+    let write = args.iter().any(|arg| arg == "--write");
+
+    for path in args.iter().filter(|arg| *arg != "--write") {
+        let source = match std::fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("Could not read {path}: {e}");
+                continue;
+            }
+        };
+
+        let result = match migrate_to_script_setup(&source, path) {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("{}", e.render(&source, path));
+                continue;
+            }
+        };
+
+        for error in result.errors.iter() {
+            eprintln!("{}\n", error.render(&source, path));
+        }
+
+        if !write {
+            println!("{}", result.code);
+        } else if result.code != source {
+            if let Err(e) = std::fs::write(path, &result.code) {
+                eprintln!("Could not write {path}: {e}");
+            }
+        }
+    }
+}
+
 fn test_real_compilation() {
     let test = include_str!("../benches/fixtures/input.vue");
 
@@ -0,0 +1,104 @@
+//! Migration of the Options API components to `<script setup>`
+
+use fervid_codegen::CodegenContext;
+use fervid_core::SfcScriptLang;
+use fervid_parser::SfcParser;
+use fervid_transform::{
+    error::{MigrationError, MigrationErrorKind, TransformError},
+    script::migrate::{migrate_options_api, template_reference_prefixes},
+};
+use swc_core::common::FileName;
+
+use crate::errors::CompileError;
+
+pub struct MigrateResult {
+    /// Migrated SFC. Same as the source when the component could not be migrated
+    pub code: String,
+    /// Code which could not be migrated and needs a manual review
+    pub errors: Vec<CompileError>,
+}
+
+/// Rewrites the Options API `<script>` of an SFC into `<script setup>`
+/// and updates the `$data` and `$options` references of `<template>`.
+///
+/// The rest of the SFC is kept as-is.
+pub fn migrate_to_script_setup(
+    source: &str,
+    filename: &str,
+) -> Result<MigrateResult, CompileError> {
+    let mut all_errors = Vec::<CompileError>::new();
+
+    // Parse
+    let mut sfc_parsing_errors = Vec::new();
+    let mut parser = SfcParser::new(source, &mut sfc_parsing_errors);
+    let sfc = parser.parse_sfc()?;
+    let comments = parser.comments().clone();
+    all_errors.extend(sfc_parsing_errors.into_iter().map(From::from));
+
+    let unchanged = |errors| MigrateResult {
+        code: source.to_owned(),
+        errors,
+    };
+
+    let Some(script) = sfc.script_legacy else {
+        return Ok(unchanged(all_errors));
+    };
+    if let Some(script_setup) = sfc.script_setup {
+        all_errors.push(CompileError::TransformError(
+            TransformError::MigrationError(MigrationError {
+                span: script_setup.span,
+                kind: MigrationErrorKind::ScriptSetupExists,
+            }),
+        ));
+        return Ok(unchanged(all_errors));
+    }
+
+    // Migrate
+    let mut module = script.content;
+    let mut transform_errors = Vec::new();
+    let bindings = migrate_options_api(&mut module, &mut transform_errors);
+    all_errors.extend(transform_errors.into_iter().map(From::from));
+    let Some(bindings) = bindings else {
+        return Ok(unchanged(all_errors));
+    };
+
+    let (code, _) = CodegenContext::stringify(
+        source,
+        module.as_ref(),
+        Some(&comments),
+        FileName::Custom(filename.to_owned()),
+        false,
+        false,
+    );
+    let lang = match script.lang {
+        SfcScriptLang::Es => "",
+        SfcScriptLang::Typescript => " lang=\"ts\"",
+    };
+
+    // Replacements of the source ranges, applied from the end to keep the positions valid
+    let mut replacements = vec![(
+        script.span,
+        format!("<script setup{lang}>\n{code}</script>"),
+    )];
+    if let Some(template) = sfc.template {
+        replacements.extend(
+            template_reference_prefixes(&template.roots, &bindings)
+                .into_iter()
+                .map(|span| (span, String::new())),
+        );
+    }
+    replacements.sort_by_key(|(span, _)| std::cmp::Reverse(span.lo));
+
+    let mut migrated = source.to_owned();
+    for (span, replacement) in replacements {
+        let range = span.lo.0 as usize - 1..span.hi.0 as usize - 1;
+        if migrated.get(range.clone()).is_some() {
+            migrated.replace_range(range, &replacement);
+        }
+    }
+
+    Ok(MigrateResult {
+        code: migrated,
+        errors: all_errors,
+    })
+}
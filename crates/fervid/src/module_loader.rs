@@ -0,0 +1,125 @@
+//! File system implementation of the [`ModuleLoader`] and a cache shared between the compiled files
+
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use fervid_core::block_content_span;
+use fervid_parser::SfcParser;
+use fervid_transform::{LoadedModule, ModuleLoader};
+use fxhash::FxHashMap;
+
+/// Extensions tried when the specifier has none, same as the default `resolve.extensions` of Vite
+const EXTENSIONS: &[&str] = &["mjs", "js", "mts", "ts", "jsx", "tsx"];
+
+/// Loads the relative imports, e.g. `./mixins/foo`, from the file system.
+/// Bare specifiers (packages, aliases) are left unresolved.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FsModuleLoader;
+
+impl ModuleLoader for FsModuleLoader {
+    fn load(&self, specifier: &str, importer: &str) -> Option<LoadedModule> {
+        if !specifier.starts_with("./") && !specifier.starts_with("../") {
+            return None;
+        }
+
+        let path = resolve_path(&Path::new(importer).parent()?.join(specifier))?;
+        let source = std::fs::read_to_string(&path).ok()?;
+        let source = if path.extension().is_some_and(|ext| ext == "vue") {
+            read_sfc_script(&source)?
+        } else {
+            source
+        };
+
+        Some(LoadedModule {
+            filename: path.to_string_lossy().into_owned(),
+            source,
+        })
+    }
+}
+
+/// Tries the path as-is, then with the extensions, then as a directory with an `index` file
+fn resolve_path(path: &Path) -> Option<PathBuf> {
+    if path.is_file() {
+        return Some(path.to_owned());
+    }
+
+    let with_extensions = EXTENSIONS.iter().map(|ext| {
+        let mut with_extension = OsString::from(path);
+        with_extension.push(".");
+        with_extension.push(ext);
+        PathBuf::from(with_extension)
+    });
+    let indexes = EXTENSIONS
+        .iter()
+        .map(|ext| path.join(format!("index.{ext}")));
+
+    with_extensions.chain(indexes).find(|path| path.is_file())
+}
+
+/// Content of `<script>` of an SFC, `<script setup>` is not relevant for the Options API
+fn read_sfc_script(source: &str) -> Option<String> {
+    let mut errors = Vec::new();
+    let sfc = SfcParser::new(source, &mut errors).parse_sfc().ok()?;
+    let content_span = block_content_span(source, sfc.script_legacy?.span)?;
+
+    source
+        .get(content_span.lo.0 as usize - 1..content_span.hi.0 as usize - 1)
+        .map(ToOwned::to_owned)
+}
+
+/// Remembers the results of another [`ModuleLoader`], including the unresolved imports,
+/// so that a module imported by many components is resolved and read only once.
+/// Same as the bundlers, the resolution is assumed to depend on the directory of the importer
+/// and not on the importer itself.
+///
+/// Used by [`crate::compile_many`] to share the loaded modules between the files.
+/// The cache is never invalidated, thus it should not outlive a single build.
+pub struct CachedModuleLoader {
+    inner: Arc<dyn ModuleLoader>,
+    /// Results by `(specifier, directory of the importer)`
+    cache: RwLock<FxHashMap<(String, String), Option<LoadedModule>>>,
+}
+
+impl CachedModuleLoader {
+    pub fn new(inner: Arc<dyn ModuleLoader>) -> Self {
+        CachedModuleLoader {
+            inner,
+            cache: Default::default(),
+        }
+    }
+}
+
+impl ModuleLoader for CachedModuleLoader {
+    fn load(&self, specifier: &str, importer: &str) -> Option<LoadedModule> {
+        let importer_dir = Path::new(importer).parent().unwrap_or(Path::new(""));
+        let key = (
+            specifier.to_owned(),
+            importer_dir.to_string_lossy().into_owned(),
+        );
+        if let Some(cached) = self
+            .cache
+            .read()
+            .ok()
+            .and_then(|cache| cache.get(&key).cloned())
+        {
+            return cached;
+        }
+
+        // Loading happens outside of the lock, two files may load the same module concurrently
+        let loaded = self.inner.load(specifier, importer);
+        if let Ok(mut cache) = self.cache.write() {
+            cache.insert(key, loaded.clone());
+        }
+
+        loaded
+    }
+}
+
+impl std::fmt::Debug for CachedModuleLoader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachedModuleLoader").finish_non_exhaustive()
+    }
+}
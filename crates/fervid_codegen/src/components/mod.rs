@@ -211,6 +211,9 @@ impl CodegenContext {
 
         // Key is a component as used in template, value is the assigned Js identifier
         for (component_name, component_identifier) in sorted_components.iter() {
+            // Resolve is mapped to the first usage of the component
+            let span = component_identifier.span;
+
             // _component_ident_name = resolveComponent("component-name")
             result.push(VarDeclarator {
                 span: DUMMY_SP,
@@ -219,7 +222,7 @@ impl CodegenContext {
                     type_ann: None,
                 }),
                 init: Some(Box::new(Expr::Call(CallExpr {
-                    span: DUMMY_SP,
+                    span,
                     ctxt: Default::default(),
                     callee: Callee::Expr(Box::new(Expr::Ident(Ident {
                         span: DUMMY_SP,
@@ -271,7 +274,7 @@ impl CodegenContext {
         // Process directives and hints wrt the createVNode
 
         ObjectLit {
-            span: DUMMY_SP, // todo from the component_node
+            span: component_node.span,
             props: result_props,
         }
     }
@@ -289,7 +292,7 @@ impl CodegenContext {
         }
 
         // Prepare the necessities.
-        let component_span = DUMMY_SP; // todo
+        let component_span = component_node.span;
         let mut default_slot_children: Vec<Expr> = Vec::new();
 
         // `SlottedIterator` will iterate over sequences of default or named slots,
@@ -508,7 +511,7 @@ impl CodegenContext {
     }
 
     /// Creates the SWC identifier from a tag name. Will fetch from cache if present
-    fn get_component_identifier(&mut self, tag_name: &FervidAtom, span: Span) -> Expr {
+    pub(crate) fn get_component_identifier(&mut self, tag_name: &FervidAtom, span: Span) -> Expr {
         // Cached
         let existing_component_binding = self.bindings_helper.components.get(tag_name);
         match existing_component_binding {
@@ -562,7 +565,7 @@ impl CodegenContext {
         let mut out: Vec<Option<ExprOrSpread>> = Vec::new();
 
         self.generate_directives_to_array(directives, &mut out);
-        self.maybe_generate_with_directives(create_component_expr, out)
+        self.maybe_generate_with_directives(create_component_expr, out, component_node.span)
     }
 
     /// Generates `_slotName_: withCtx((_maybeCtx_) => [slot, children])`
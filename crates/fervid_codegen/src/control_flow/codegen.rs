@@ -146,11 +146,15 @@ impl CodegenContext {
                 text_nodes.push(generated);
 
                 // Save span
-                // TODO real spans
+                let node_span = match node {
+                    Node::Text(_, span) => *span,
+                    Node::Interpolation(interpolation) => interpolation.span,
+                    _ => unreachable!(),
+                };
                 if text_nodes_span[0].is_dummy() {
-                    text_nodes_span[0] = BytePos(0);
+                    text_nodes_span[0] = node_span.lo;
                 }
-                text_nodes_span[1] = BytePos(0);
+                text_nodes_span[1] = node_span.hi;
             } else {
                 // Process the text nodes from before
                 maybe_concatenate_text_nodes!();
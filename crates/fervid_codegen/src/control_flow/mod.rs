@@ -3,4 +3,7 @@ mod conditional_seq;
 mod sfc;
 mod slotted_iterator;
 
+pub(crate) use sfc::{
+    add_setup_method, generate_const_decl, generate_default_export, push_to_setup,
+};
 pub use slotted_iterator::SlottedIterator;
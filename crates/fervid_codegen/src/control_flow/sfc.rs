@@ -1,18 +1,23 @@
 use fervid_core::{
-    fervid_atom, BindingTypes, FervidAtom, IntoIdent, SfcTemplateBlock, TemplateGenerationMode,
-    VueImports,
+    fervid_atom, BindingTypes, CompileTarget, FervidAtom, IntoIdent, SfcTemplateBlock,
+    TemplateGenerationMode, VueImports, PURE_SP,
 };
 use fxhash::FxHashMap;
 use swc_core::{
     atoms::Atom,
-    common::{source_map::SourceMapGenConfig, sync::Lrc, BytePos, FileName, SourceMap, DUMMY_SP},
+    common::{
+        comments::Comments, source_map::SourceMapGenConfig, sync::Lrc, BytePos, FileName,
+        SourceMap, DUMMY_SP,
+    },
     ecma::{
         ast::{
-            ArrowExpr, AssignExpr, BindingIdent, BlockStmt, BlockStmtOrExpr, CallExpr, Callee,
-            Decl, ExportDefaultExpr, Expr, ExprOrSpread, ExprStmt, Function, GetterProp, Ident,
-            IdentName, ImportDecl, MethodProp, Module, ModuleDecl, ModuleItem, ObjectLit, Param,
-            Pat, Prop, PropName, PropOrSpread, ReturnStmt, SetterProp, Stmt, Str, VarDecl,
-            VarDeclKind, VarDeclarator,
+            ArrowExpr, AssignExpr, BinExpr, BinaryOp, BindingIdent, BlockStmt, BlockStmtOrExpr,
+            CallExpr, Callee, Decl, DefaultDecl, ExportDecl, ExportDefaultExpr, Expr, ExprOrSpread,
+            ExprStmt, FnDecl, Function, GetterProp, Ident, IdentName, IfStmt, ImportDecl,
+            ImportSpecifier, KeyValueProp, Lit, MethodProp, Module, ModuleDecl, ModuleExportName,
+            ModuleItem, Null, ObjectLit, Param, ParenExpr, Pat, Prop, PropName, PropOrSpread,
+            ReturnStmt, SetterProp, Stmt, Str, UnaryExpr, UnaryOp, VarDecl, VarDeclKind,
+            VarDeclarator,
         },
         visit::{noop_visit_type, Visit, VisitWith},
     },
@@ -25,6 +30,10 @@ impl CodegenContext {
     // TODO Generation mode? Is it relevant?
     // TODO Generating module? Or instead taking a module? Or generating an expression and merging?
     pub fn generate_sfc_template(&mut self, sfc_template: &SfcTemplateBlock) -> Option<Expr> {
+        if matches!(self.bindings_helper.target, CompileTarget::Vue2) {
+            return self.generate_vue2_template(sfc_template);
+        }
+
         // #11: Optimization: multiple template roots
         // and all are text nodes (must be ensured by Transformer),
         // generate node sequence
@@ -65,30 +74,13 @@ impl CodegenContext {
                 TemplateGenerationMode::Inline => {
                     let render_arrow = self.generate_render_arrow(template_expr);
 
-                    let setup_function = synthetic_setup_fn.get_or_insert_with(|| {
-                        Box::new(Function {
-                            params: vec![],
-                            decorators: vec![],
+                    push_to_setup(
+                        &mut synthetic_setup_fn,
+                        [Stmt::Return(ReturnStmt {
                             span: DUMMY_SP,
-                            ctxt: Default::default(),
-                            body: None,
-                            is_generator: false,
-                            is_async: false,
-                            type_params: None,
-                            return_type: None,
-                        })
-                    });
-
-                    let setup_body = setup_function.body.get_or_insert_with(|| BlockStmt {
-                        span: DUMMY_SP,
-                        ctxt: Default::default(),
-                        stmts: Vec::with_capacity(1),
-                    });
-
-                    setup_body.stmts.push(Stmt::Return(ReturnStmt {
-                        span: DUMMY_SP,
-                        arg: Some(Box::new(Expr::Arrow(render_arrow))),
-                    }));
+                            arg: Some(Box::new(Expr::Arrow(render_arrow))),
+                        })],
+                    );
                 }
 
                 // Generates the render Function to be used as a property in exported object.
@@ -143,35 +135,13 @@ impl CodegenContext {
         }
 
         // Add the `setup` function to the exported object
-        if let Some(setup_fn) = synthetic_setup_fn {
-            match setup_fn.body {
-                // Append only when function has a body and it is not empty
-                Some(ref b) if !b.stmts.is_empty() => {
-                    sfc_export_obj
-                        .props
-                        .push(PropOrSpread::Prop(Box::new(Prop::Method(MethodProp {
-                            key: PropName::Ident(IdentName {
-                                span: DUMMY_SP,
-                                sym: FervidAtom::from("setup"),
-                            }),
-                            function: setup_fn,
-                        }))));
-                }
-
-                _ => {}
-            }
-        }
+        add_setup_method(&mut sfc_export_obj, synthetic_setup_fn);
 
         // Either use export object as-is or inside `defineComponent`
         let sfc_exported = if self.bindings_helper.is_ts {
-            // Add /*#__PURE__*/ comment
-            let obj_span = sfc_export_obj.span;
-            if !obj_span.is_dummy() {
-                // TODO also try to make sure that span is not dummy
-            }
-
+            // `PURE_SP` makes the emitter add a `/*#__PURE__*/` comment
             Box::new(Expr::Call(CallExpr {
-                span: obj_span,
+                span: PURE_SP,
                 ctxt: Default::default(),
                 callee: Callee::Expr(Box::new(Expr::Ident(Ident {
                     span: DUMMY_SP,
@@ -190,58 +160,255 @@ impl CodegenContext {
         };
 
         // Do `export default` or `const _smth = ` where variable name is passed
-        let gen_default_as = if let Some(options_gen_default_as) = gen_default_as {
-            ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
+        let gen_default_as = generate_default_export(sfc_exported, gen_default_as);
+
+        // Append the Vue imports
+        // TODO Smart merging with user imports?
+        script.body.extend(self.generate_vue_import_decl());
+
+        // Append the default export/const
+        script.body.push(gen_default_as);
+
+        script
+    }
+
+    /// Generates a standalone module exporting the `render` function, i.e.
+    /// `export function render(_ctx, _cache, $props, $setup, $data, $options) { /*...*/ }`.
+    ///
+    /// Used when the template is compiled separately from the scripts.
+    /// Asset URL imports collected during the template transformation are added to the module.
+    pub fn generate_template_module(&mut self, template_expr: Option<Expr>) -> Module {
+        let mut body: Vec<ModuleItem> = self
+            .bindings_helper
+            .imports
+            .drain(..)
+            .map(|v| ModuleItem::ModuleDecl(ModuleDecl::Import(v)))
+            .collect();
+
+        // Empty template renders nothing
+        let template_expr = template_expr.unwrap_or(Expr::Lit(Lit::Null(Null { span: DUMMY_SP })));
+
+        // Generate the function first, because it adds to the Vue imports
+        let render_fn = self.generate_render_fn(template_expr);
+        body.extend(self.generate_vue_import_decl());
+
+        body.push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl: Decl::Fn(FnDecl {
+                ident: fervid_atom!("render").into_ident(),
+                declare: false,
+                function: Box::new(render_fn),
+            }),
+        })));
+
+        Module {
+            span: DUMMY_SP,
+            body,
+            shebang: None,
+        }
+    }
+
+    /// Inlines the modules compiled from the custom blocks and applies them to the component,
+    /// the same way `@vitejs/plugin-vue` applies the imported blocks:
+    /// `const _sfc_block0 = (() => { /*...*/ })(); if (typeof _sfc_block0 === "function") _sfc_block0(_sfc_main)`.
+    ///
+    /// `module` is the one generated by [`CodegenContext::generate_module`] with the same `gen_default_as`.
+    /// Imports of the block modules are hoisted, and their named exports are not exported.
+    pub fn generate_custom_blocks(
+        &mut self,
+        module: &mut Module,
+        custom_block_modules: Vec<Module>,
+        gen_default_as: Option<&str>,
+    ) {
+        if custom_block_modules.is_empty() {
+            return;
+        }
+
+        // `export default {}` -> `const _sfc_main = {}`, exported after the blocks are applied
+        let (component, needs_export) = match gen_default_as {
+            Some(gen_default_as) => (FervidAtom::from(gen_default_as), false),
+            None => {
+                let Some(ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export_default))) =
+                    module.body.pop()
+                else {
+                    unreachable!("generate_module always ends with `export default`")
+                };
+
+                let component = fervid_atom!("_sfc_main");
+                module.body.push(ModuleItem::Stmt(generate_const_decl(
+                    component.to_owned(),
+                    export_default.expr,
+                )));
+                (component, true)
+            }
+        };
+
+        let mut block_imports = Vec::new();
+        for (idx, block_module) in custom_block_modules.into_iter().enumerate() {
+            let block_ident = FervidAtom::from(format!("_sfc_block{idx}"));
+            let default_ident = fervid_atom!("__default__");
+
+            // Default export is returned after all the statements were executed
+            let mut stmts = Vec::with_capacity(block_module.body.len() + 1);
+            let mut has_default = false;
+            for item in block_module.body {
+                let default_expr = match item {
+                    ModuleItem::Stmt(stmt) => {
+                        stmts.push(stmt);
+                        continue;
+                    }
+                    ModuleItem::ModuleDecl(ModuleDecl::Import(mut import)) => {
+                        // Blocks may import the same names, thus the locals are prefixed
+                        // and re-declared inside the block, e.g. `const _resource = _sfc_block0_resource`
+                        for specifier in import.specifiers.iter_mut() {
+                            let local = match specifier {
+                                ImportSpecifier::Named(named) => {
+                                    named.imported.get_or_insert_with(|| {
+                                        ModuleExportName::Ident(named.local.to_owned())
+                                    });
+                                    &mut named.local
+                                }
+                                ImportSpecifier::Default(default) => &mut default.local,
+                                ImportSpecifier::Namespace(namespace) => &mut namespace.local,
+                            };
+
+                            let prefixed = FervidAtom::from(format!("{block_ident}_{}", local.sym));
+                            let original = std::mem::replace(&mut local.sym, prefixed.to_owned());
+                            stmts.push(generate_const_decl(
+                                original,
+                                Box::new(Expr::Ident(prefixed.into_ident())),
+                            ));
+                        }
+
+                        block_imports.push(ModuleItem::ModuleDecl(ModuleDecl::Import(import)));
+                        continue;
+                    }
+                    ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+                        stmts.push(Stmt::Decl(export_decl.decl));
+                        continue;
+                    }
+                    ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export_default)) => {
+                        export_default.expr
+                    }
+                    ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export_default)) => {
+                        match export_default.decl {
+                            DefaultDecl::Fn(fn_expr) => Box::new(Expr::Fn(fn_expr)),
+                            DefaultDecl::Class(class_expr) => Box::new(Expr::Class(class_expr)),
+                            DefaultDecl::TsInterfaceDecl(_) => continue,
+                        }
+                    }
+                    // Re-exports are not applied to the component
+                    ModuleItem::ModuleDecl(_) => continue,
+                };
+
+                has_default = true;
+                stmts.push(generate_const_decl(default_ident.to_owned(), default_expr));
+            }
+
+            if has_default {
+                stmts.push(Stmt::Return(ReturnStmt {
+                    span: DUMMY_SP,
+                    arg: Some(Box::new(Expr::Ident(default_ident.into_ident()))),
+                }));
+            }
+
+            // `(() => { /*...*/ })()`
+            let block_iife = Expr::Call(CallExpr {
                 span: DUMMY_SP,
                 ctxt: Default::default(),
-                kind: VarDeclKind::Const,
-                declare: false,
-                decls: vec![VarDeclarator {
+                callee: Callee::Expr(Box::new(Expr::Paren(ParenExpr {
                     span: DUMMY_SP,
-                    name: Pat::Ident(BindingIdent {
-                        id: Ident {
+                    expr: Box::new(Expr::Arrow(ArrowExpr {
+                        span: DUMMY_SP,
+                        ctxt: Default::default(),
+                        params: vec![],
+                        body: Box::new(BlockStmtOrExpr::BlockStmt(BlockStmt {
                             span: DUMMY_SP,
                             ctxt: Default::default(),
-                            sym: FervidAtom::from(options_gen_default_as),
-                            optional: false,
-                        },
-                        type_ann: None,
-                    }),
-                    init: Some(sfc_exported),
-                    definite: false,
-                }],
-            }))))
-        } else {
-            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(ExportDefaultExpr {
+                            stmts,
+                        })),
+                        is_async: false,
+                        is_generator: false,
+                        type_params: None,
+                        return_type: None,
+                    })),
+                }))),
+                args: vec![],
+                type_args: None,
+            });
+            module.body.push(ModuleItem::Stmt(generate_const_decl(
+                block_ident.to_owned(),
+                Box::new(block_iife),
+            )));
+
+            // `if (typeof _sfc_block0 === "function") _sfc_block0(_sfc_main)`
+            module.body.push(ModuleItem::Stmt(Stmt::If(IfStmt {
                 span: DUMMY_SP,
-                expr: sfc_exported,
-            }))
-        };
-
-        // Append the Vue imports
-        // TODO Smart merging with user imports?
-        let used_imports = self.generate_imports();
-        if !used_imports.is_empty() {
-            script
-                .body
-                .push(ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+                test: Box::new(Expr::Bin(BinExpr {
                     span: DUMMY_SP,
-                    specifiers: used_imports,
-                    src: Box::new(Str {
+                    op: BinaryOp::EqEqEq,
+                    left: Box::new(Expr::Unary(UnaryExpr {
                         span: DUMMY_SP,
-                        value: FervidAtom::from("vue"),
+                        op: UnaryOp::TypeOf,
+                        arg: Box::new(Expr::Ident(block_ident.to_owned().into_ident())),
+                    })),
+                    right: Box::new(Expr::Lit(Lit::Str(Str {
+                        span: DUMMY_SP,
+                        value: fervid_atom!("function"),
                         raw: None,
-                    }),
-                    type_only: false,
-                    with: None,
-                    phase: Default::default(),
-                })));
+                    }))),
+                })),
+                cons: Box::new(Stmt::Expr(ExprStmt {
+                    span: DUMMY_SP,
+                    expr: Box::new(Expr::Call(CallExpr {
+                        span: DUMMY_SP,
+                        ctxt: Default::default(),
+                        callee: Callee::Expr(Box::new(Expr::Ident(block_ident.into_ident()))),
+                        args: vec![ExprOrSpread {
+                            spread: None,
+                            expr: Box::new(Expr::Ident(component.to_owned().into_ident())),
+                        }],
+                        type_args: None,
+                    })),
+                })),
+                alt: None,
+            })));
         }
 
-        // Append the default export/const
-        script.body.push(gen_default_as);
+        // Imports of the blocks go first
+        module.body.splice(0..0, block_imports);
 
-        script
+        if needs_export {
+            module
+                .body
+                .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(
+                    ExportDefaultExpr {
+                        span: DUMMY_SP,
+                        expr: Box::new(Expr::Ident(component.into_ident())),
+                    },
+                )));
+        }
+    }
+
+    /// Generates `import { ... } from "vue"` when any of the Vue imports were used
+    pub(crate) fn generate_vue_import_decl(&self) -> Option<ModuleItem> {
+        let used_imports = self.generate_imports();
+        if used_imports.is_empty() {
+            return None;
+        }
+
+        Some(ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+            span: DUMMY_SP,
+            specifiers: used_imports,
+            src: Box::new(Str {
+                span: DUMMY_SP,
+                value: FervidAtom::from("vue"),
+                raw: None,
+            }),
+            type_only: false,
+            with: None,
+            phase: Default::default(),
+        })))
     }
 
     /// Wraps the render function in an arrow expression
@@ -313,6 +480,10 @@ impl CodegenContext {
     /// It always includes the provided `template_expr` as the last return statement.
     /// When components and/or directives are present, their corresponding `resolve`s are generated here.
     pub fn generate_render_fn(&mut self, template_expr: Expr) -> Function {
+        if matches!(self.bindings_helper.target, CompileTarget::Vue2) {
+            return self.generate_vue2_render_fn(template_expr);
+        }
+
         let mut fn_body_stmts: Vec<Stmt> = Vec::with_capacity(3);
 
         // Compute component and directive resolves
@@ -527,15 +698,36 @@ impl CodegenContext {
             }
         }
 
+        // Vue 2.7 unwraps the refs of the bindings marked as coming from an SFC
+        if matches!(self.bindings_helper.target, CompileTarget::Vue2) {
+            props.push(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(IdentName {
+                    span: DUMMY_SP,
+                    sym: fervid_atom!("__sfc"),
+                }),
+                value: Box::new(Expr::Lit(Lit::Bool(true.into()))),
+            }))));
+        }
+
         ObjectLit {
             span: DUMMY_SP,
             props,
         }
     }
 
+    /// Converts the generated module to a string.
+    ///
+    /// When `generate_source_map` is `true`, also produces a source map.
+    /// The map covers `<script>`, `<script setup>` and `<template>` at once,
+    /// because all of them use spans relative to the `source` SFC.
+    /// The `source` is always included as `sourcesContent`.
+    ///
+    /// `comments` are the ones collected when parsing the `source`, e.g. by `SfcParser`.
+    /// They are attached to the nodes by spans and taken out once emitted.
     pub fn stringify<T>(
         source: &str,
         module: &T,
+        comments: Option<&dyn Comments>,
         filename: FileName,
         generate_source_map: bool,
         minify: bool,
@@ -564,7 +756,7 @@ impl CodegenContext {
 
             let mut emitter = Emitter {
                 cfg: emitter_cfg,
-                comments: None,
+                comments,
                 wr: writer,
                 cm: cm.clone(),
             };
@@ -574,6 +766,12 @@ impl CodegenContext {
         };
 
         let map = if generate_source_map {
+            // SWC maps the closing braces to `span.hi - 1`, which is inside a character
+            // when a span ends with a multibyte one, e.g. an element of a Pug template
+            for (pos, _) in source_map_buf.iter_mut() {
+                *pos = floor_char_boundary(source, *pos);
+            }
+
             let source_map_names = {
                 let mut v = IdentCollector {
                     names: Default::default(),
@@ -604,6 +802,23 @@ impl CodegenContext {
     }
 }
 
+/// Moves a 1-based position inside a multibyte character of `source` to the start of that character
+fn floor_char_boundary(source: &str, pos: BytePos) -> BytePos {
+    if pos.is_dummy() || pos.is_reserved_for_comments() {
+        return pos;
+    }
+
+    let mut offset = pos.0 as usize - 1;
+    if offset > source.len() {
+        return pos;
+    }
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    BytePos(offset as u32 + 1)
+}
+
 struct SourceMapConfig<'a> {
     source_file_name: Option<&'a str>,
     names: &'a FxHashMap<BytePos, FervidAtom>,
@@ -627,6 +842,110 @@ impl SourceMapGenConfig for SourceMapConfig<'_> {
     }
 }
 
+/// Appends the statements to the `setup` function, creating the function when it is absent
+pub(crate) fn push_to_setup(
+    synthetic_setup_fn: &mut Option<Box<Function>>,
+    stmts: impl IntoIterator<Item = Stmt>,
+) {
+    let setup_function = synthetic_setup_fn.get_or_insert_with(|| {
+        Box::new(Function {
+            params: vec![],
+            decorators: vec![],
+            span: DUMMY_SP,
+            ctxt: Default::default(),
+            body: None,
+            is_generator: false,
+            is_async: false,
+            type_params: None,
+            return_type: None,
+        })
+    });
+
+    let setup_body = setup_function.body.get_or_insert_with(|| BlockStmt {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        stmts: Vec::with_capacity(1),
+    });
+
+    setup_body.stmts.extend(stmts);
+}
+
+/// Adds the `setup` method to the exported object
+pub(crate) fn add_setup_method(
+    sfc_export_obj: &mut ObjectLit,
+    synthetic_setup_fn: Option<Box<Function>>,
+) {
+    let Some(setup_fn) = synthetic_setup_fn else {
+        return;
+    };
+
+    // Append only when function has a body and it is not empty
+    if setup_fn.body.as_ref().is_some_and(|b| !b.stmts.is_empty()) {
+        sfc_export_obj
+            .props
+            .push(PropOrSpread::Prop(Box::new(Prop::Method(MethodProp {
+                key: PropName::Ident(IdentName {
+                    span: DUMMY_SP,
+                    sym: FervidAtom::from("setup"),
+                }),
+                function: setup_fn,
+            }))));
+    }
+}
+
+/// Generates `export default sfc_exported` or `const gen_default_as = sfc_exported`
+pub(crate) fn generate_default_export(
+    sfc_exported: Box<Expr>,
+    gen_default_as: Option<&str>,
+) -> ModuleItem {
+    if let Some(options_gen_default_as) = gen_default_as {
+        ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
+            span: DUMMY_SP,
+            ctxt: Default::default(),
+            kind: VarDeclKind::Const,
+            declare: false,
+            decls: vec![VarDeclarator {
+                span: DUMMY_SP,
+                name: Pat::Ident(BindingIdent {
+                    id: Ident {
+                        span: DUMMY_SP,
+                        ctxt: Default::default(),
+                        sym: FervidAtom::from(options_gen_default_as),
+                        optional: false,
+                    },
+                    type_ann: None,
+                }),
+                init: Some(sfc_exported),
+                definite: false,
+            }],
+        }))))
+    } else {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(ExportDefaultExpr {
+            span: DUMMY_SP,
+            expr: sfc_exported,
+        }))
+    }
+}
+
+/// `const name = init`
+pub(crate) fn generate_const_decl(name: FervidAtom, init: Box<Expr>) -> Stmt {
+    Stmt::Decl(Decl::Var(Box::new(VarDecl {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        kind: VarDeclKind::Const,
+        declare: false,
+        decls: vec![VarDeclarator {
+            span: DUMMY_SP,
+            name: Pat::Ident(BindingIdent {
+                id: name.into_ident(),
+                type_ann: None,
+            }),
+            init: Some(init),
+            definite: false,
+        }],
+    })))
+}
+
 // Adapted from `swc_compiler_base`
 pub struct IdentCollector {
     pub names: FxHashMap<BytePos, Atom>,
@@ -639,3 +958,145 @@ impl Visit for IdentCollector {
         self.names.insert(ident.span.lo, ident.sym.clone());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fervid_parser::SfcParser;
+    use swc_core::common::comments::SingleThreadedComments;
+
+    use crate::test_utils::{js_module, js_module_with_comments, to_str};
+
+    use super::*;
+
+    fn generate(module: &str, custom_blocks: &[&str], gen_default_as: Option<&str>) -> String {
+        let mut module = js_module(module);
+        let custom_block_modules = custom_blocks.iter().map(|code| js_module(code)).collect();
+
+        let mut ctx = CodegenContext::default();
+        ctx.generate_custom_blocks(&mut module, custom_block_modules, gen_default_as);
+        to_str(module)
+    }
+
+    #[test]
+    fn it_applies_custom_blocks_to_the_default_export() {
+        assert_eq!(
+            generate(
+                "import{ref}from\"vue\";export default {name:\"Comp\"}",
+                &["export default function(Component){Component.__docs=docs}const docs=\"Docs\";"],
+                None
+            ),
+            r#"import{ref}from"vue";const _sfc_main={name:"Comp"};const _sfc_block0=(()=>{const __default__=function(Component){Component.__docs=docs;};const docs="Docs";return __default__;})();if(typeof _sfc_block0==="function")_sfc_block0(_sfc_main);export default _sfc_main;"#
+        );
+
+        // No blocks, no changes
+        assert_eq!(
+            generate("export default {}", &[], None),
+            "export default{};"
+        );
+    }
+
+    #[test]
+    fn it_applies_custom_blocks_to_gen_default_as() {
+        assert_eq!(
+            generate(
+                "const _sfc_main={}",
+                &["export const unused=1;export default (Component)=>{Component.__route=unused}"],
+                Some("_sfc_main")
+            ),
+            r#"const _sfc_main={};const _sfc_block0=(()=>{const unused=1;const __default__=Component=>{Component.__route=unused;};return __default__;})();if(typeof _sfc_block0==="function")_sfc_block0(_sfc_main);"#
+        );
+    }
+
+    #[test]
+    fn it_hoists_custom_block_imports() {
+        assert_eq!(
+            generate(
+                "export default {}",
+                &[
+                    "import messages from \"./en.json\";export default (C)=>{C.__i18n=[messages]}",
+                    "import messages,{extra} from \"./ja.json\";export default (C)=>{C.__i18n.push(messages,extra)}",
+                ],
+                None
+            ),
+            r#"import _sfc_block0_messages from"./en.json";import _sfc_block1_messages,{extra as _sfc_block1_extra}from"./ja.json";const _sfc_main={};const _sfc_block0=(()=>{const messages=_sfc_block0_messages;const __default__=C=>{C.__i18n=[messages];};return __default__;})();if(typeof _sfc_block0==="function")_sfc_block0(_sfc_main);const _sfc_block1=(()=>{const messages=_sfc_block1_messages;const extra=_sfc_block1_extra;const __default__=C=>{C.__i18n.push(messages,extra);};return __default__;})();if(typeof _sfc_block1==="function")_sfc_block1(_sfc_main);export default _sfc_main;"#
+        );
+    }
+
+    #[test]
+    fn it_annotates_define_component_as_pure() {
+        let mut ctx = CodegenContext::default();
+        ctx.bindings_helper.is_ts = true;
+
+        let module = ctx.generate_module(
+            None,
+            js_module(""),
+            ObjectLit {
+                span: DUMMY_SP,
+                props: vec![],
+            },
+            None,
+            Some("_sfc_main"),
+        );
+        assert_eq!(
+            to_str(module),
+            r#"import{defineComponent as _defineComponent}from"vue";const _sfc_main=/*#__PURE__*/_defineComponent({});"#
+        );
+    }
+
+    #[test]
+    fn it_keeps_comments_when_stringifying() {
+        let source = r#"/*! License */ import { foo } from "./foo";
+/** Docs */ export const bar = import(/* webpackChunkName: "baz" */ "./baz");
+// @ts-expect-error
+foo(bar);
+"#;
+        let comments = SingleThreadedComments::default();
+        let module = js_module_with_comments(source, &comments);
+
+        let (code, _) = CodegenContext::stringify(
+            source,
+            &module,
+            Some(&comments),
+            FileName::Anon,
+            false,
+            false,
+        );
+        assert_eq!(code, source);
+
+        // Without comments
+        let module = js_module(source);
+        let (code, _) =
+            CodegenContext::stringify(source, &module, None, FileName::Anon, false, false);
+        assert_eq!(
+            code,
+            "import { foo } from \"./foo\";\nexport const bar = import(\"./baz\");\nfoo(bar);\n"
+        );
+    }
+
+    #[test]
+    fn it_generates_source_maps_for_non_ascii_pug() {
+        for template in [
+            "div.контент тест",
+            "div#контент",
+            "my-компонент(title=\"ё\")\n  span тест",
+            "ul\n  li.é: b ё",
+        ] {
+            let source = format!("<template lang=\"pug\">\n{template}\n</template>\n");
+
+            let mut errors = Vec::new();
+            let mut parser = SfcParser::new(&source, &mut errors);
+            let sfc = parser.parse_sfc().expect("Should parse");
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            let mut ctx = CodegenContext::default();
+            let template_expr = sfc
+                .template
+                .and_then(|template_block| ctx.generate_sfc_template(&template_block));
+            let module = ctx.generate_template_module(template_expr);
+
+            let (_, source_map) =
+                CodegenContext::stringify(&source, &module, None, FileName::Anon, true, false);
+            assert!(source_map.is_some_and(|map| map.contains("\"mappings\"")));
+        }
+    }
+}
@@ -2,7 +2,7 @@ use fervid_core::{
     str_to_propname, CustomDirectiveBinding, FervidAtom, StrOrExpr, VueDirectives, VueImports,
 };
 use swc_core::{
-    common::{Span, DUMMY_SP},
+    common::{Span, Spanned, DUMMY_SP},
     ecma::ast::{
         ArrayLit, BindingIdent, Bool, CallExpr, Callee, Expr, ExprOrSpread, Ident, KeyValueProp,
         Lit, Number, ObjectLit, Pat, Prop, PropOrSpread, Str, UnaryExpr, UnaryOp, VarDeclarator,
@@ -40,7 +40,7 @@ impl CodegenContext {
 
         // v-show
         if let Some(ref v_show) = directives.v_show {
-            let span = DUMMY_SP; // TODO Span
+            let span = v_show.span();
             let v_show_identifier = Expr::Ident(Ident {
                 span,
                 ctxt: Default::default(),
@@ -62,7 +62,10 @@ impl CodegenContext {
 
         // Generate custom directives last
         for custom_directive in directives.custom.iter() {
-            let span = DUMMY_SP; // TODO Span
+            let span = custom_directive
+                .value
+                .as_ref()
+                .map_or(DUMMY_SP, |value| value.span());
             let directive_ident = self.get_custom_directive_ident(&custom_directive.name, span);
 
             out.push(Some(ExprOrSpread {
@@ -83,13 +86,14 @@ impl CodegenContext {
         &mut self,
         expr: Expr,
         directives_arr: Vec<Option<ExprOrSpread>>,
+        span: Span,
     ) -> Expr {
         if directives_arr.is_empty() {
             return expr;
         }
 
         Expr::Call(CallExpr {
-            span: DUMMY_SP, // TODO Span
+            span,
             ctxt: Default::default(),
             callee: Callee::Expr(Box::new(Expr::Ident(Ident {
                 span: DUMMY_SP,
@@ -220,7 +224,11 @@ impl CodegenContext {
         Expr::Array(directive_arr)
     }
 
-    fn get_custom_directive_ident(&mut self, directive_name: &FervidAtom, span: Span) -> Expr {
+    pub(crate) fn get_custom_directive_ident(
+        &mut self,
+        directive_name: &FervidAtom,
+        span: Span,
+    ) -> Expr {
         // Check directive existence and early exit
         let existing_directive_binding = self.bindings_helper.custom_directives.get(directive_name);
         match existing_directive_binding {
@@ -1,6 +1,6 @@
 use fervid_core::{fervid_atom, IntoIdent};
 use swc_core::{
-    common::DUMMY_SP,
+    common::{Spanned, DUMMY_SP},
     ecma::ast::{ArrowExpr, CallExpr, Callee, Expr, ExprOrSpread, Number},
 };
 
@@ -17,6 +17,7 @@ impl CodegenContext {
     /// _withMemo([], () => (_openBlock(), _createElementBlock("div")), _cache, 0)
     /// ```
     pub fn generate_v_memo(&mut self, memo_expr: Box<Expr>, item_render_expr: Box<Expr>) -> Expr {
+        let span = memo_expr.span();
         let cache_idx = self.allocate_next_cache_entry();
 
         // 1. Memo
@@ -57,7 +58,7 @@ impl CodegenContext {
         };
 
         Expr::Call(CallExpr {
-            span: DUMMY_SP,
+            span,
             ctxt: Default::default(),
             callee: Callee::Expr(Box::new(Expr::Ident(
                 self.get_and_add_import_ident(fervid_core::VueImports::WithMemo)
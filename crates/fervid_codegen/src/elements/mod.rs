@@ -18,7 +18,7 @@ impl CodegenContext {
         element_node: &ElementNode,
         wrap_in_block: bool,
     ) -> Expr {
-        let span = DUMMY_SP;
+        let span = element_node.span;
         let starting_tag = &element_node.starting_tag;
 
         // Generate attributes
@@ -301,14 +301,14 @@ impl CodegenContext {
                         Some(&v_model.value),
                         v_model.argument.as_ref(),
                         &v_model.modifiers,
-                        DUMMY_SP,
+                        v_model.span,
                     )),
                 }));
             }
         }
 
         self.generate_directives_to_array(directives, &mut out);
-        self.maybe_generate_with_directives(create_element_expr, out)
+        self.maybe_generate_with_directives(create_element_expr, out, element_node.span)
     }
 
     fn get_element_vmodel_directive_name(&mut self, starting_tag: &StartingTag) -> FervidAtom {
@@ -366,6 +366,7 @@ impl CodegenContext {
 #[cfg(test)]
 mod tests {
     use fervid_core::{ElementKind, Interpolation, Node, StartingTag};
+    use swc_core::common::{BytePos, Span, Spanned};
 
     use super::*;
     use crate::test_utils::{js, regular_attribute, v_bind_attribute, v_on_attribute};
@@ -538,6 +539,51 @@ mod tests {
         )
     }
 
+    #[test]
+    fn it_propagates_spans() {
+        // <div>hello {{ world }}</div>
+        let element_span = Span::new(BytePos(1), BytePos(29));
+        let text_span = Span::new(BytePos(6), BytePos(12));
+        let interpolation_span = Span::new(BytePos(12), BytePos(23));
+
+        let mut ctx = CodegenContext::default();
+        let out = ctx.generate_element_vnode(
+            &ElementNode {
+                starting_tag: StartingTag {
+                    tag_name: "div".into(),
+                    attributes: vec![],
+                    directives: None,
+                },
+                children: vec![
+                    Node::Text("hello ".into(), text_span),
+                    Node::Interpolation(Interpolation {
+                        value: js("world"),
+                        template_scope: 0,
+                        patch_flag: true,
+                        span: interpolation_span,
+                    }),
+                ],
+                template_scope: 0,
+                kind: ElementKind::Element,
+                patch_hints: Default::default(),
+                span: element_span,
+            },
+            false,
+        );
+
+        let Expr::Call(ref create_element_call) = out else {
+            panic!("Expected a call expression")
+        };
+        assert_eq!(element_span, create_element_call.span);
+
+        // Text and interpolation are concatenated and span both nodes
+        let children_arg = create_element_call.args.get(2).expect("Expected children");
+        assert_eq!(
+            Span::new(text_span.lo, interpolation_span.hi),
+            children_arg.expr.span()
+        );
+    }
+
     fn test_out(input: ElementNode, expected: &str, wrap_in_block: bool) {
         let mut ctx = CodegenContext::default();
         let out = ctx.generate_element_vnode(&input, wrap_in_block);
@@ -16,10 +16,17 @@ mod directives;
 mod elements;
 mod imports;
 mod interpolation;
+mod printer;
 mod text;
 mod utils;
+mod vapor;
+mod virtual_ts;
+mod vue2;
 
 #[cfg(test)]
 mod test_utils;
 
 pub use context::CodegenContext;
+pub use printer::{print_sfc, PrintSfcOptions, PrintWhitespace};
+pub use vapor::VaporRender;
+pub use virtual_ts::{generate_virtual_ts, VirtualTsMapping, VirtualTsOptions, VirtualTsResult};
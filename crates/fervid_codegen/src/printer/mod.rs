@@ -0,0 +1,669 @@
+//! Printing an [`SfcDescriptor`] back to the `.vue` source.
+//!
+//! The printer is meant for the tools which modify the descriptor produced by the parser,
+//! e.g. codemods and formatters. The template is re-serialized from the IR,
+//! the scripts are emitted by the SWC codegen and the styles and custom blocks are printed as-is.
+//!
+//! When the original source is provided, it is parsed again and the parts of the descriptor
+//! equal to the parsed ones are copied from the source: the scripts, the start tags
+//! and the texts of the template. Modified start tags keep the template expressions
+//! and the shorthands of the directives (e.g. `:foo` vs `v-bind:foo`) which were not modified.
+//! A template expression is considered modified when one of its nodes has a dummy span
+//! or does not match the source at its span, e.g. a renamed identifier.
+
+use std::{cell::RefCell, rc::Rc};
+
+use fervid_core::{
+    block_content_span, start_tag_len, FervidAtom, Node as TemplateNode, SfcCustomBlock,
+    SfcDescriptor, SfcScriptBlock, SfcScriptLang, SfcStyleBlock, SfcTemplateBlock, StartingTag,
+};
+use fervid_parser::SfcParser;
+use fxhash::FxHashMap;
+use swc_core::common::{
+    comments::{Comments, SingleThreadedComments},
+    sync::Lrc,
+    FileName, SourceMap, Span,
+};
+use swc_ecma_codegen::{text_writer::JsWriter, Emitter, Node};
+
+use self::template::TemplatePrinter;
+
+mod template;
+
+#[derive(Default)]
+pub struct PrintSfcOptions<'s> {
+    /// Source of the SFC which the descriptor was parsed from.
+    /// The unmodified parts are copied from it, so it must match the spans of the descriptor
+    pub source: Option<&'s str>,
+    /// Comments of the scripts collected by the parser, see `SfcParser::comments`.
+    /// Without them, the comments of `<script>` and `<script setup>` are dropped
+    pub comments: Option<&'s SingleThreadedComments>,
+    /// How the whitespace of the template is printed
+    pub whitespace: PrintWhitespace,
+}
+
+/// Whitespace handling of the template, same as the `whitespace` compiler option of Vue
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PrintWhitespace {
+    /// Texts are printed as they are
+    #[default]
+    Preserve,
+    /// Whitespace-only texts containing a newline are removed between the elements
+    /// and the consecutive whitespace characters are collapsed into a single space.
+    /// `<pre>` and `<textarea>` are printed as they are
+    Condense,
+}
+
+/// Prints the blocks of an SFC. Blocks are printed in their source order,
+/// new blocks (with a dummy span) follow the previous block of the
+/// `<script>`, `<script setup>`, `<template>`, `<style>`, custom blocks order.
+pub fn print_sfc(sfc_descriptor: &SfcDescriptor, options: &PrintSfcOptions) -> String {
+    // Emitter consumes the comments, therefore the ones of the caller are copied
+    let comments = options.comments.map(|comments| {
+        let (leading, trailing) = comments.borrow_all();
+        SingleThreadedComments::from_leading_and_trailing(
+            Rc::new(RefCell::new((*leading).clone())),
+            Rc::new(RefCell::new((*trailing).clone())),
+        )
+    });
+
+    let original = options.source.map(Original::new);
+    let printer = SfcPrinter {
+        source: options.source,
+        original: original.as_ref(),
+        comments: comments.as_ref(),
+        whitespace: options.whitespace,
+    };
+
+    let mut blocks: Vec<(Span, Block)> = Vec::new();
+    blocks.extend(
+        sfc_descriptor
+            .script_legacy
+            .iter()
+            .map(|script| (script.span, Block::Script(script, false))),
+    );
+    blocks.extend(
+        sfc_descriptor
+            .script_setup
+            .iter()
+            .map(|script| (script.span, Block::Script(script, sfc_descriptor.vapor))),
+    );
+    blocks.extend(
+        sfc_descriptor
+            .template
+            .iter()
+            .map(|template| (template.span, Block::Template(template))),
+    );
+    blocks.extend(
+        sfc_descriptor
+            .styles
+            .iter()
+            .map(|style| (style.span, Block::Style(style))),
+    );
+    blocks.extend(
+        sfc_descriptor
+            .custom_blocks
+            .iter()
+            .map(|custom_block| (custom_block.span, Block::Custom(custom_block))),
+    );
+
+    // New blocks take the position of the previous one
+    let mut position = 0;
+    let mut ordered: Vec<(u32, usize, Block)> = blocks
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (span, block))| {
+            if !span.is_dummy() {
+                position = span.lo.0;
+            }
+            (position, idx, block)
+        })
+        .collect();
+    ordered.sort_by_key(|(position, idx, _)| (*position, *idx));
+
+    let mut out = String::with_capacity(options.source.map_or(1024, str::len));
+    for (_, _, block) in ordered {
+        if !out.is_empty() {
+            out.push_str("\n\n");
+        }
+        match block {
+            Block::Script(script, vapor) => printer.print_script(script, vapor, &mut out),
+            Block::Template(template) => printer.print_template(template, &mut out),
+            Block::Style(style) => printer.print_style(style, &mut out),
+            Block::Custom(custom_block) => printer.print_custom_block(custom_block, &mut out),
+        }
+    }
+    out.push('\n');
+
+    out
+}
+
+enum Block<'d> {
+    /// Script and whether it is `vapor`
+    Script(&'d SfcScriptBlock, bool),
+    Template(&'d SfcTemplateBlock),
+    Style(&'d SfcStyleBlock),
+    Custom(&'d SfcCustomBlock),
+}
+
+struct SfcPrinter<'s, 'c> {
+    source: Option<&'s str>,
+    original: Option<&'s Original<'s>>,
+    comments: Option<&'c SingleThreadedComments>,
+    whitespace: PrintWhitespace,
+}
+
+/// The descriptor parsed again from the source of the printed one.
+/// The printed parts equal to the parsed ones were not modified and are copied from the source,
+/// this keeps e.g. `of` in `v-for`, the order of the attributes and the character references
+pub struct Original<'s> {
+    source: &'s str,
+    descriptor: Option<SfcDescriptor>,
+    /// Start tags of the elements and custom blocks, printed without the source
+    pub(super) start_tags: FxHashMap<Span, String>,
+    /// Texts of the template as decoded by the parser
+    pub(super) texts: FxHashMap<Span, FervidAtom>,
+}
+
+impl<'s> Original<'s> {
+    pub fn new(source: &'s str) -> Self {
+        let mut errors = Vec::new();
+        let descriptor = SfcParser::new(source, &mut errors).parse_sfc().ok();
+
+        let mut original = Original {
+            source,
+            descriptor: None,
+            start_tags: FxHashMap::default(),
+            texts: FxHashMap::default(),
+        };
+        if let Some(ref descriptor) = descriptor {
+            if let Some(ref template) = descriptor.template {
+                original.collect_nodes(&template.roots);
+            }
+            for custom_block in descriptor.custom_blocks.iter() {
+                original.collect_start_tag(&custom_block.starting_tag, custom_block.span);
+            }
+        }
+        original.descriptor = descriptor;
+
+        original
+    }
+
+    fn collect_nodes(&mut self, nodes: &[TemplateNode]) {
+        for node in nodes {
+            match node {
+                TemplateNode::Element(element_node) => {
+                    self.collect_start_tag(&element_node.starting_tag, element_node.span);
+                    self.collect_nodes(&element_node.children);
+                }
+                TemplateNode::Text(text, span) => {
+                    self.texts.insert(*span, text.to_owned());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn collect_start_tag(&mut self, starting_tag: &StartingTag, span: Span) {
+        let mut printer = TemplatePrinter::new(None, PrintWhitespace::Preserve);
+        printer.print_starting_tag(starting_tag, span, None);
+        self.start_tags.insert(span, printer.finish());
+    }
+
+    /// Source of the script content when the script was not modified
+    fn script_content(&self, script: &SfcScriptBlock) -> Option<&'s str> {
+        let descriptor = self.descriptor.as_ref()?;
+        let parsed = if script.is_setup {
+            descriptor.script_setup.as_ref()
+        } else {
+            descriptor.script_legacy.as_ref()
+        }?;
+        if parsed.span != script.span || parsed.content != script.content {
+            return None;
+        }
+
+        let content_span = block_content_span(self.source, script.span)?;
+        self.source
+            .get(content_span.lo.0 as usize - 1..content_span.hi.0 as usize - 1)
+    }
+}
+
+impl SfcPrinter<'_, '_> {
+    fn print_script(&self, script: &SfcScriptBlock, vapor: bool, out: &mut String) {
+        let is_ts = matches!(script.lang, SfcScriptLang::Typescript);
+
+        // Other attributes, e.g. `generic`, are only known from the source
+        let original_start_tag = self.original_start_tag(script.span).filter(|start_tag| {
+            let attrs = start_tag_attributes(start_tag);
+            let has_attr = |name: &str| attrs.iter().any(|(attr, _)| *attr == name);
+            let lang = attrs
+                .iter()
+                .find_map(|(attr, value)| (*attr == "lang").then_some(*value))
+                .flatten();
+
+            has_attr("setup") == script.is_setup
+                && (!script.is_setup || has_attr("vapor") == vapor)
+                && matches!(lang, Some("ts" | "tsx")) == is_ts
+        });
+
+        match original_start_tag {
+            Some(start_tag) => out.push_str(start_tag),
+            None => {
+                out.push_str("<script");
+                if script.is_setup {
+                    out.push_str(" setup");
+                    if vapor {
+                        out.push_str(" vapor");
+                    }
+                }
+                if is_ts {
+                    out.push_str(" lang=\"ts\"");
+                }
+                out.push('>');
+            }
+        }
+
+        match self
+            .original
+            .and_then(|original| original.script_content(script))
+        {
+            Some(content) => out.push_str(content),
+            None => {
+                out.push('\n');
+                out.push_str(&emit(script.content.as_ref(), self.source, self.comments));
+            }
+        }
+        out.push_str("</script>");
+    }
+
+    fn print_template(&self, template: &SfcTemplateBlock, out: &mut String) {
+        let is_html = template.lang == "html";
+
+        // Only HTML can be printed from the IR, other languages are kept as-is
+        if !is_html {
+            if let Some(original) = self.original(template.span) {
+                out.push_str(original);
+                return;
+            }
+        }
+
+        let original_start_tag = self.original_start_tag(template.span).filter(|start_tag| {
+            start_tag_attributes(start_tag)
+                .iter()
+                .all(|(attr, value)| *attr != "lang" || *value == Some("html"))
+        });
+        out.push_str(original_start_tag.unwrap_or("<template>"));
+
+        let mut template_printer = TemplatePrinter::new(self.original, self.whitespace);
+        template_printer.print_nodes(&template.roots);
+        let content = template_printer.finish();
+
+        // Content is placed on its own lines
+        if !content.starts_with('\n') {
+            out.push('\n');
+        }
+        out.push_str(&content);
+        if !content.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str("</template>");
+    }
+
+    fn print_style(&self, style: &SfcStyleBlock, out: &mut String) {
+        let original_start_tag = self
+            .original_style_start_tag(style.span)
+            .filter(|start_tag| {
+                let attrs = start_tag_attributes(start_tag);
+                let has_attr = |name: &str| attrs.iter().any(|(attr, _)| *attr == name);
+                let lang = attrs
+                    .iter()
+                    .find_map(|(attr, value)| (*attr == "lang").then_some(*value))
+                    .flatten()
+                    .unwrap_or("css");
+
+                has_attr("scoped") == style.is_scoped
+                    && has_attr("module") == style.is_module
+                    && lang == style.lang.as_str()
+            });
+
+        match original_start_tag {
+            Some(start_tag) => out.push_str(start_tag),
+            None => {
+                out.push_str("<style");
+                if style.lang != "css" {
+                    out.push_str(" lang=\"");
+                    out.push_str(&style.lang);
+                    out.push('"');
+                }
+                if style.is_scoped {
+                    out.push_str(" scoped");
+                }
+                if style.is_module {
+                    out.push_str(" module");
+                }
+                out.push('>');
+            }
+        }
+
+        out.push_str(&style.content);
+        out.push_str("</style>");
+    }
+
+    fn print_custom_block(&self, custom_block: &SfcCustomBlock, out: &mut String) {
+        let mut template_printer = TemplatePrinter::new(self.original, self.whitespace);
+        let original_start_tag = template_printer.original_start_tag(
+            &custom_block.starting_tag,
+            custom_block.span,
+            None,
+        );
+        match original_start_tag {
+            Some(start_tag) => out.push_str(start_tag),
+            None => {
+                template_printer.print_starting_tag(
+                    &custom_block.starting_tag,
+                    custom_block.span,
+                    None,
+                );
+                out.push_str(&template_printer.finish());
+                out.push('>');
+            }
+        }
+        out.push_str(&custom_block.content);
+        out.push_str("</");
+        out.push_str(&custom_block.starting_tag.tag_name);
+        out.push('>');
+    }
+
+    /// Source of the whole block
+    fn original(&self, span: Span) -> Option<&str> {
+        if span.is_dummy() {
+            return None;
+        }
+        let lo = (span.lo.0 as usize).checked_sub(1)?;
+        let hi = (span.hi.0 as usize).checked_sub(1)?;
+        self.source?.get(lo..hi)
+    }
+
+    /// Span of `<style>` only covers its content, the start tag is the one before it
+    fn original_style_start_tag(&self, content_span: Span) -> Option<&str> {
+        if content_span.is_dummy() {
+            return None;
+        }
+        let lo = (content_span.lo.0 as usize).checked_sub(1)?;
+        let before = self.source?.get(..lo)?;
+        let start_tag = &before[before.rfind("<style")?..];
+        start_tag.ends_with('>').then_some(start_tag)
+    }
+
+    /// Source of the start tag of the block, e.g. `<script setup lang="ts">`
+    fn original_start_tag(&self, span: Span) -> Option<&str> {
+        let original = self.original(span)?;
+        original.get(..start_tag_len(original)?)
+    }
+}
+
+/// Attributes of a start tag, e.g. `[("setup", None), ("lang", Some("ts"))]` for `<script setup lang="ts">`
+fn start_tag_attributes(start_tag: &str) -> Vec<(&str, Option<&str>)> {
+    let mut attributes = Vec::new();
+
+    // Skip `<` and the tag name
+    let Some(mut rest) = start_tag
+        .trim_start_matches('<')
+        .find(|c: char| c.is_ascii_whitespace())
+        .map(|idx| &start_tag.trim_start_matches('<')[idx..])
+    else {
+        return attributes;
+    };
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '/');
+        if rest.is_empty() || rest.starts_with('>') {
+            break;
+        }
+
+        let name_len = rest
+            .find(|c: char| c.is_ascii_whitespace() || matches!(c, '=' | '>' | '/'))
+            .unwrap_or(rest.len());
+        let name = &rest[..name_len];
+        rest = rest[name_len..].trim_start();
+
+        let Some(after_eq) = rest.strip_prefix('=') else {
+            attributes.push((name, None));
+            continue;
+        };
+        let after_eq = after_eq.trim_start();
+
+        let (value, value_end) = match after_eq.chars().next() {
+            Some(quote @ ('"' | '\'')) => match after_eq[1..].find(quote) {
+                Some(len) => (&after_eq[1..len + 1], len + 2),
+                None => (&after_eq[1..], after_eq.len()),
+            },
+            _ => {
+                let len = after_eq
+                    .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                    .unwrap_or(after_eq.len());
+                (&after_eq[..len], len)
+            }
+        };
+        attributes.push((name, Some(value)));
+        rest = &after_eq[value_end..];
+    }
+
+    attributes
+}
+
+/// Emits a JavaScript node with the comments attached to its spans
+fn emit<T: Node>(
+    node: &T,
+    source: Option<&str>,
+    comments: Option<&SingleThreadedComments>,
+) -> String {
+    let cm: Lrc<SourceMap> = Default::default();
+    if let Some(source) = source {
+        cm.new_source_file(Lrc::new(FileName::Anon), source.to_owned());
+    }
+
+    let mut buff: Vec<u8> = Vec::new();
+    let writer: JsWriter<&mut Vec<u8>> = JsWriter::new(cm.clone(), "\n", &mut buff, None);
+
+    let mut emitter = Emitter {
+        cfg: swc_ecma_codegen::Config::default(),
+        comments: comments.map(|comments| comments as &dyn Comments),
+        wr: writer,
+        cm,
+    };
+
+    node.emit_with(&mut emitter).expect("Failed to emit");
+    String::from_utf8(buff).expect("Invalid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::{AttributeOrBinding, Node, StrOrExpr};
+    use fervid_parser::SfcParser;
+    use swc_core::{
+        common::DUMMY_SP,
+        ecma::ast::{Expr, Ident},
+    };
+
+    use super::*;
+
+    #[test]
+    fn it_prints_unmodified_sfc_as_is() {
+        let source = r#"<script setup lang="ts" generic="T">
+// Greeting
+import { ref } from 'vue'
+/** The counter */ const count = ref<T>(0)
+const items = ref([{ name: 'a' }])
+</script>
+
+<template>
+  <ul class="list"   :class="{ active: count > 0 }">
+    <li :key="idx" v-for="(item, idx) of items" @click.stop="select(item)" v-bind:title="item.title">{{item.name}} &amp; more</li>
+    <MyComponent v-model:value.trim="text" #default="{ value }" v-if="count"><b>{{ value }}</b></MyComponent>
+    <!-- comment -->
+    <input disabled v-else title="&quot;quoted&quot; &lt;tag&gt;" :value="count" />
+  </ul>
+</template>
+
+<style scoped lang="scss">
+.list { color: red }
+</style>
+
+<i18n lang="json">{ "en": {} }</i18n>
+"#;
+
+        let (sfc, comments) = parse(source);
+        let printed = print_sfc(
+            &sfc,
+            &PrintSfcOptions {
+                source: Some(source),
+                comments: Some(&comments),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(printed, source);
+    }
+
+    #[test]
+    fn it_prints_modified_template() {
+        let source = r#"<template><div :foo="bar" @click="baz"/></template>"#;
+
+        let (mut sfc, _) = parse(source);
+        let template = sfc.template.as_mut().expect("Should exist");
+        let Node::Element(ref mut div) = template.roots[0] else {
+            panic!("Should be an element")
+        };
+        let AttributeOrBinding::VBind(ref mut v_bind) = div.starting_tag.attributes[0] else {
+            panic!("Should be v-bind")
+        };
+        v_bind.argument = Some(StrOrExpr::Str("qux".into()));
+        v_bind.value = Box::new(Expr::Ident(Ident::new_no_ctxt("quux".into(), DUMMY_SP)));
+
+        let with_source = print_sfc(
+            &sfc,
+            &PrintSfcOptions {
+                source: Some(source),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            with_source,
+            "<template>\n<div :qux=\"quux\" @click=\"baz\"/>\n</template>\n"
+        );
+
+        let without_source = print_sfc(&sfc, &Default::default());
+        assert_eq!(
+            without_source,
+            "<template>\n<div :qux=\"quux\" @click=\"baz\"></div>\n</template>\n"
+        );
+    }
+
+    #[test]
+    fn it_prints_only_modified_nodes() {
+        let source = r#"<script>
+export default { name: 'Foo' }
+const unused = 1
+</script>
+
+<template>
+  <ul>
+    <li :key="idx" v-for="(item, idx) of items" @click="go">{{ item }} &amp;</li>
+    <li title="&quot;a&quot;" :key="idx">b</li>
+  </ul>
+</template>
+"#;
+
+        let (mut sfc, comments) = parse(source);
+        let script = sfc.script_legacy.as_mut().expect("Should exist");
+        script.content.body.pop();
+
+        // First `<li>`, the nodes around are the whitespace texts
+        let template = sfc.template.as_mut().expect("Should exist");
+        let Node::Element(ref mut ul) = template.roots[1] else {
+            panic!("Should be an element")
+        };
+        let Node::Element(ref mut li) = ul.children[1] else {
+            panic!("Should be an element")
+        };
+        let AttributeOrBinding::VBind(ref mut v_bind) = li.starting_tag.attributes[0] else {
+            panic!("Should be v-bind")
+        };
+        v_bind.value = Box::new(Expr::Ident(Ident::new_no_ctxt("item".into(), DUMMY_SP)));
+
+        let printed = print_sfc(
+            &sfc,
+            &PrintSfcOptions {
+                source: Some(source),
+                comments: Some(&comments),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            printed,
+            r#"<script>
+export default {
+    name: 'Foo'
+};
+</script>
+
+<template>
+  <ul>
+    <li v-for="(item, idx) of items" :key="item" @click="go">{{ item }} &amp;</li>
+    <li title="&quot;a&quot;" :key="idx">b</li>
+  </ul>
+</template>
+"#
+        );
+    }
+
+    #[test]
+    fn it_condenses_whitespace() {
+        let source = "<template>\n  <div>\n    <span>a   b</span>\n    <pre>  x\n  y</pre>\n  </div>\n</template>";
+
+        let (sfc, _) = parse(source);
+        let printed = print_sfc(
+            &sfc,
+            &PrintSfcOptions {
+                source: Some(source),
+                whitespace: PrintWhitespace::Condense,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            printed,
+            "<template>\n<div><span>a b</span><pre>  x\n  y</pre></div>\n</template>\n"
+        );
+    }
+
+    #[test]
+    fn it_prints_new_blocks() {
+        let source = "<template><div></div></template>\n<style>a {}</style>";
+
+        let (mut sfc, _) = parse(source);
+        let (script_sfc, _) = parse("<script setup>\nconst foo = 1\n</script>");
+        sfc.script_setup = script_sfc.script_setup.map(|mut script| {
+            script.span = DUMMY_SP;
+            script
+        });
+        sfc.styles[0].is_scoped = true;
+
+        let printed = print_sfc(&sfc, &Default::default());
+        assert_eq!(
+            printed,
+            "<script setup>\nconst foo = 1;\n</script>\n\n<template>\n<div></div>\n</template>\n\n<style scoped>a {}</style>\n"
+        );
+    }
+
+    fn parse(source: &str) -> (SfcDescriptor, SingleThreadedComments) {
+        let mut errors = Vec::new();
+        let mut parser = SfcParser::new(source, &mut errors);
+        let sfc = parser.parse_sfc().expect("Should parse");
+        let comments = parser.comments().clone();
+        assert!(errors.is_empty(), "{:?}", errors);
+        (sfc, comments)
+    }
+}
@@ -0,0 +1,775 @@
+use std::borrow::Cow;
+
+use fervid_core::{
+    is_html_tag, start_tag_len, AttributeOrBinding, ConditionalNodeSequence, ElementNode, Node,
+    StartingTag, StrOrExpr, VBindDirective, VOnDirective,
+};
+use swc_core::{
+    common::{Span, Spanned},
+    ecma::{
+        ast::{Expr, Ident, IdentName, Number, Pat, Str},
+        visit::{Visit, VisitWith},
+    },
+};
+
+use super::{emit, Original, PrintWhitespace};
+
+/// Elements which never have content
+const VOID_TAGS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// `v-if`, `v-else-if` or `v-else` of a node in a [`ConditionalNodeSequence`]
+#[derive(Clone, Copy)]
+pub enum ConditionalDirective<'n> {
+    If(&'n Expr),
+    ElseIf(&'n Expr),
+    Else,
+}
+
+pub struct TemplatePrinter<'s> {
+    original: Option<&'s Original<'s>>,
+    whitespace: PrintWhitespace,
+    out: String,
+    /// Inside `<pre>` or `<textarea>`, where the whitespace is significant
+    pre_depth: usize,
+}
+
+impl<'s> TemplatePrinter<'s> {
+    pub fn new(original: Option<&'s Original<'s>>, whitespace: PrintWhitespace) -> Self {
+        TemplatePrinter {
+            original,
+            whitespace,
+            out: String::new(),
+            pre_depth: 0,
+        }
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+
+    pub fn print_nodes(&mut self, nodes: &[Node]) {
+        let is_condense = self.whitespace == PrintWhitespace::Condense && self.pre_depth == 0;
+
+        for (idx, node) in nodes.iter().enumerate() {
+            match node {
+                Node::Element(element_node) => self.print_element(element_node, None),
+
+                Node::Text(text, _) if is_condense => {
+                    let is_whitespace = text.chars().all(|c| c.is_ascii_whitespace());
+                    if !is_whitespace {
+                        self.push_text(&condense(text));
+                        continue;
+                    }
+
+                    // Same as Vue, whitespace at the edges and between the elements is removed
+                    let is_edge = idx == 0 || idx == nodes.len() - 1;
+                    let is_between_elements = matches!(
+                        (nodes.get(idx.wrapping_sub(1)), nodes.get(idx + 1)),
+                        (
+                            Some(Node::Element(_) | Node::Comment(..) | Node::ConditionalSeq(_)),
+                            Some(Node::Element(_) | Node::Comment(..) | Node::ConditionalSeq(_))
+                        )
+                    );
+                    let is_removed = is_edge || (is_between_elements && text.contains('\n'));
+                    if !is_removed {
+                        self.out.push(' ');
+                    }
+                }
+
+                // Untouched text keeps its character references
+                Node::Text(text, span) => match self.original(*span) {
+                    Some(original) if self.is_original_text(text, *span) => {
+                        self.out.push_str(original)
+                    }
+                    _ => self.push_text(text),
+                },
+
+                Node::Interpolation(interpolation) => {
+                    // The original keeps the whitespace around the expression
+                    let original = self
+                        .original_node(interpolation.value.as_ref())
+                        .and_then(|_| self.original(interpolation.span));
+
+                    self.out.push_str("{{");
+                    match original {
+                        Some(original) => self.out.push_str(original),
+                        None => {
+                            self.out.push(' ');
+                            let code = self.expr_code(&interpolation.value);
+                            self.out.push_str(&code);
+                            self.out.push(' ');
+                        }
+                    }
+                    self.out.push_str("}}");
+                }
+
+                Node::Comment(comment, _) => {
+                    self.out.push_str("<!--");
+                    self.out.push_str(comment);
+                    self.out.push_str("-->");
+                }
+
+                Node::ConditionalSeq(conditional_seq) => {
+                    self.print_conditional_seq(conditional_seq)
+                }
+            }
+        }
+    }
+
+    fn print_conditional_seq(&mut self, conditional_seq: &ConditionalNodeSequence) {
+        let if_node = &conditional_seq.if_node;
+        self.print_element(
+            &if_node.node,
+            Some(ConditionalDirective::If(&if_node.condition)),
+        );
+
+        for else_if_node in conditional_seq.else_if_nodes.iter() {
+            self.print_element(
+                &else_if_node.node,
+                Some(ConditionalDirective::ElseIf(&else_if_node.condition)),
+            );
+        }
+
+        if let Some(ref else_node) = conditional_seq.else_node {
+            self.print_element(else_node, Some(ConditionalDirective::Else));
+        }
+    }
+
+    fn print_element(
+        &mut self,
+        element_node: &ElementNode,
+        conditional: Option<ConditionalDirective>,
+    ) {
+        let tag_name = element_node.starting_tag.tag_name.as_str();
+        let is_void = VOID_TAGS.contains(&tag_name);
+
+        // Self-closing start tag cannot be kept when children were added
+        let original_start_tag = self
+            .original_start_tag(&element_node.starting_tag, element_node.span, conditional)
+            .filter(|start_tag| element_node.children.is_empty() || !start_tag.ends_with("/>"));
+
+        if let Some(start_tag) = original_start_tag {
+            self.out.push_str(start_tag);
+            if start_tag.ends_with("/>") {
+                return;
+            }
+        } else {
+            self.print_starting_tag(&element_node.starting_tag, element_node.span, conditional);
+
+            if element_node.children.is_empty() {
+                let self_closing = match self.original(element_node.span) {
+                    Some(original) if original.ends_with(" />") => Some(" />"),
+                    Some(original) => original.ends_with("/>").then_some("/>"),
+                    None => (!is_void && !is_html_tag(tag_name)).then_some(" />"),
+                };
+
+                if let Some(self_closing) = self_closing {
+                    self.out.push_str(self_closing);
+                    return;
+                }
+            }
+
+            self.out.push('>');
+        }
+
+        if element_node.children.is_empty() {
+            if !is_void {
+                self.push_end_tag(tag_name);
+            }
+            return;
+        }
+
+        let is_pre = matches!(tag_name, "pre" | "textarea");
+        if is_pre {
+            self.pre_depth += 1;
+
+            // The first newline of `<pre>` is ignored by the parser
+            let is_newline_ignored = matches!(
+                element_node.children.first(),
+                Some(Node::Text(text, _)) if text.starts_with('\n')
+            ) || self
+                .original(element_node.span)
+                .and_then(|original| original.get(start_tag_len(original)?..))
+                .is_some_and(|content| content.starts_with('\n'));
+            if is_newline_ignored {
+                self.out.push('\n');
+            }
+        }
+
+        // Content of `<script>` and `<style>` is raw
+        if matches!(tag_name, "script" | "style") {
+            for child in element_node.children.iter() {
+                if let Node::Text(text, _) = child {
+                    self.out.push_str(text);
+                }
+            }
+        } else {
+            self.print_nodes(&element_node.children);
+        }
+
+        if is_pre {
+            self.pre_depth -= 1;
+        }
+
+        self.push_end_tag(tag_name);
+    }
+
+    /// Source of the start tag including `>` when the tag was not modified after parsing,
+    /// i.e. it prints the same as the start tag parsed from the source at `span`
+    pub fn original_start_tag(
+        &self,
+        starting_tag: &StartingTag,
+        span: Span,
+        conditional: Option<ConditionalDirective>,
+    ) -> Option<&'s str> {
+        let parsed = self.original?.start_tags.get(&span)?;
+
+        let mut printer = TemplatePrinter::new(None, self.whitespace);
+        printer.print_starting_tag(starting_tag, span, conditional);
+        if printer.out != *parsed {
+            return None;
+        }
+
+        let original = self.original(span)?;
+        original.get(..start_tag_len(original)?)
+    }
+
+    /// Prints `<tag` with the attributes, the caller closes it.
+    ///
+    /// Directives and attributes are kept in separate collections by the parser,
+    /// therefore they are printed in the order recommended by the Vue style guide:
+    /// `v-for`, conditionals, render modifiers, `v-slot`, `v-model`, then the attributes
+    /// and bindings in their original order, then custom directives, `v-html` and `v-text`.
+    /// `span` is the one of the element, it is used to keep the form of `v-slot`
+    pub fn print_starting_tag(
+        &mut self,
+        starting_tag: &StartingTag,
+        span: Span,
+        conditional: Option<ConditionalDirective>,
+    ) {
+        self.out.push('<');
+        self.out.push_str(&starting_tag.tag_name);
+
+        let directives = starting_tag.directives.as_deref();
+
+        if let Some(v_for) = directives.and_then(|d| d.v_for.as_ref()) {
+            // `of` is kept from the source
+            let separator = self
+                .original(Span::new(v_for.itervar.span().hi, v_for.iterable.span().lo))
+                .filter(|separator| separator.trim() == "of")
+                .map_or("in", |_| "of");
+            let itervar = self.expr_code(&v_for.itervar);
+            let iterable = self.expr_code(&v_for.iterable);
+            self.push_attribute("v-for", Some(&format!("{itervar} {separator} {iterable}")));
+        }
+
+        match conditional {
+            Some(ConditionalDirective::If(condition)) => {
+                self.push_expr_attribute("v-if", condition)
+            }
+            Some(ConditionalDirective::ElseIf(condition)) => {
+                self.push_expr_attribute("v-else-if", condition)
+            }
+            Some(ConditionalDirective::Else) => self.push_attribute("v-else", None),
+            None => {
+                if let Some(v_if) = directives.and_then(|d| d.v_if.as_ref()) {
+                    self.push_expr_attribute("v-if", v_if);
+                }
+                if let Some(v_else_if) = directives.and_then(|d| d.v_else_if.as_ref()) {
+                    self.push_expr_attribute("v-else-if", v_else_if);
+                }
+                if directives.is_some_and(|d| d.v_else.is_some()) {
+                    self.push_attribute("v-else", None);
+                }
+            }
+        }
+
+        if let Some(directives) = directives {
+            if let Some(ref v_show) = directives.v_show {
+                self.push_expr_attribute("v-show", v_show);
+            }
+            if directives.v_cloak.is_some() {
+                self.push_attribute("v-cloak", None);
+            }
+            if directives.v_pre.is_some() {
+                self.push_attribute("v-pre", None);
+            }
+            if directives.v_once.is_some() {
+                self.push_attribute("v-once", None);
+            }
+            if let Some(ref v_memo) = directives.v_memo {
+                self.push_expr_attribute("v-memo", v_memo);
+            }
+
+            if let Some(ref v_slot) = directives.v_slot {
+                // `v-slot` has no span, its form is looked up in the source of the starting tag
+                let is_long = self
+                    .original(span)
+                    .and_then(|original| original.get(..start_tag_len(original)?))
+                    .is_some_and(|original| original.contains("v-slot"));
+
+                let name = match v_slot.slot_name {
+                    Some(ref slot_name) => {
+                        let argument = self.argument(slot_name);
+                        if is_long {
+                            format!("v-slot:{argument}")
+                        } else {
+                            format!("#{argument}")
+                        }
+                    }
+                    None => String::from("v-slot"),
+                };
+                let value = v_slot.value.as_deref().map(|pat| self.pat_code(pat));
+                self.push_attribute(&name, value.as_deref());
+            }
+
+            for v_model in directives.v_model.iter() {
+                let mut name = String::from("v-model");
+                if let Some(ref argument) = v_model.argument {
+                    name.push(':');
+                    name.push_str(&self.argument(argument));
+                }
+                push_modifiers(&mut name, &v_model.modifiers);
+                self.push_expr_attribute(&name, &v_model.value);
+            }
+        }
+
+        for attribute in starting_tag.attributes.iter() {
+            match attribute {
+                AttributeOrBinding::RegularAttribute { name, value, .. } => {
+                    let value = (!value.is_empty()).then_some(value.as_str());
+                    self.push_attribute(name, value)
+                }
+                AttributeOrBinding::VBind(v_bind) => self.print_v_bind(v_bind),
+                AttributeOrBinding::VOn(v_on) => self.print_v_on(v_on),
+            }
+        }
+
+        if let Some(directives) = directives {
+            for custom in directives.custom.iter() {
+                let mut name = format!("v-{}", custom.name);
+                if let Some(ref argument) = custom.argument {
+                    name.push(':');
+                    name.push_str(&self.argument(argument));
+                }
+                push_modifiers(&mut name, &custom.modifiers);
+                match custom.value {
+                    Some(ref value) => self.push_expr_attribute(&name, value),
+                    None => self.push_attribute(&name, None),
+                }
+            }
+
+            if let Some(ref v_html) = directives.v_html {
+                self.push_expr_attribute("v-html", v_html);
+            }
+            if let Some(ref v_text) = directives.v_text {
+                self.push_expr_attribute("v-text", v_text);
+            }
+        }
+    }
+
+    /// `:foo`, `.foo` or `v-bind:foo`, the form is kept from the source when possible
+    fn print_v_bind(&mut self, v_bind: &VBindDirective) {
+        let original = self.original(v_bind.span);
+        let is_long = original.is_some_and(|original| original.starts_with("v-bind"));
+        let is_prop_shorthand = original.is_some_and(|original| original.starts_with('.'))
+            && v_bind.is_prop
+            && v_bind.argument.is_some();
+
+        let mut name = String::new();
+        match v_bind.argument {
+            Some(ref argument) => {
+                name.push_str(if is_prop_shorthand {
+                    "."
+                } else if is_long {
+                    "v-bind:"
+                } else {
+                    ":"
+                });
+                name.push_str(&self.argument(argument));
+            }
+            None => name.push_str("v-bind"),
+        }
+
+        if v_bind.is_camel {
+            name.push_str(".camel");
+        }
+        if v_bind.is_prop && !is_prop_shorthand {
+            name.push_str(".prop");
+        }
+        if v_bind.is_attr {
+            name.push_str(".attr");
+        }
+
+        self.push_expr_attribute(&name, &v_bind.value);
+    }
+
+    /// `@foo` or `v-on:foo`, the form is kept from the source when possible
+    fn print_v_on(&mut self, v_on: &VOnDirective) {
+        let is_long = self
+            .original(v_on.span)
+            .is_some_and(|original| original.starts_with("v-on"));
+
+        let mut name = String::new();
+        match v_on.event {
+            Some(ref event) => {
+                name.push_str(if is_long { "v-on:" } else { "@" });
+                name.push_str(&self.argument(event));
+            }
+            None => name.push_str("v-on"),
+        }
+        push_modifiers(&mut name, &v_on.modifiers);
+
+        match v_on.handler {
+            Some(ref handler) => self.push_expr_attribute(&name, handler),
+            None => self.push_attribute(&name, None),
+        }
+    }
+
+    /// `foo` or `[foo]`
+    fn argument(&self, argument: &StrOrExpr) -> String {
+        match argument {
+            StrOrExpr::Str(s) => s.to_string(),
+            StrOrExpr::Expr(expr) => format!("[{}]", self.expr_code(expr)),
+        }
+    }
+
+    fn push_expr_attribute(&mut self, name: &str, expr: &Expr) {
+        let code = self.expr_code(expr);
+        self.push_attribute(name, Some(&code));
+    }
+
+    /// Pushes ` name="value"`, choosing the quotes which do not appear in the value
+    fn push_attribute(&mut self, name: &str, value: Option<&str>) {
+        self.out.push(' ');
+        self.out.push_str(name);
+
+        let Some(value) = value else {
+            return;
+        };
+
+        let quote = if value.contains('"') && !value.contains('\'') {
+            '\''
+        } else {
+            '"'
+        };
+
+        self.out.push('=');
+        self.out.push(quote);
+        for (idx, c) in value.char_indices() {
+            match c {
+                '"' if quote == '"' => self.out.push_str("&quot;"),
+                '&' if is_char_reference(&value[idx..]) => self.out.push_str("&amp;"),
+                _ => self.out.push(c),
+            }
+        }
+        self.out.push(quote);
+    }
+
+    fn push_end_tag(&mut self, tag_name: &str) {
+        self.out.push_str("</");
+        self.out.push_str(tag_name);
+        self.out.push('>');
+    }
+
+    /// Escapes the text, so that it is not parsed as markup
+    fn push_text(&mut self, text: &str) {
+        for (idx, c) in text.char_indices() {
+            match c {
+                '<' => self.out.push_str("&lt;"),
+                '&' if is_char_reference(&text[idx..]) => self.out.push_str("&amp;"),
+                _ => self.out.push(c),
+            }
+        }
+    }
+
+    /// Source of an unmodified expression, or the code emitted by SWC
+    fn expr_code(&self, expr: &Expr) -> Cow<'s, str> {
+        match self.original_node(expr) {
+            Some(original) => Cow::Borrowed(original),
+            None => Cow::Owned(emit(expr, None, None)),
+        }
+    }
+
+    fn pat_code(&self, pat: &Pat) -> String {
+        match self.original_node(pat) {
+            Some(original) => original.to_owned(),
+            None => emit(pat, None, None),
+        }
+    }
+
+    /// Whether the text is the same as the one parsed from the source at `span`
+    fn is_original_text(&self, text: &str, span: Span) -> bool {
+        self.original
+            .and_then(|original| original.texts.get(&span))
+            .is_some_and(|parsed| parsed == text)
+    }
+
+    /// Source of a node when none of its parts were modified
+    fn original_node<N>(&self, node: &N) -> Option<&'s str>
+    where
+        N: Spanned + VisitWith<ModificationDetector<'s>>,
+    {
+        let source = self.original?.source;
+        let mut detector = ModificationDetector {
+            source,
+            is_modified: false,
+        };
+        node.visit_with(&mut detector);
+
+        if detector.is_modified {
+            return None;
+        }
+        self.original(node.span())
+    }
+
+    /// Source at the span, spans are 1-based
+    fn original(&self, span: Span) -> Option<&'s str> {
+        if span.is_dummy() {
+            return None;
+        }
+        let lo = (span.lo.0 as usize).checked_sub(1)?;
+        let hi = (span.hi.0 as usize).checked_sub(1)?;
+        self.original?.source.get(lo..hi)
+    }
+}
+
+/// Detects the nodes which were created or changed after parsing
+struct ModificationDetector<'s> {
+    source: &'s str,
+    is_modified: bool,
+}
+
+impl ModificationDetector<'_> {
+    fn check(&mut self, span: Span, expected: Option<&str>) {
+        if self.is_modified {
+            return;
+        }
+
+        let original = (!span.is_dummy())
+            .then(|| {
+                let lo = (span.lo.0 as usize).checked_sub(1)?;
+                let hi = (span.hi.0 as usize).checked_sub(1)?;
+                self.source.get(lo..hi)
+            })
+            .flatten();
+
+        self.is_modified = match (original, expected) {
+            (None, _) => true,
+            (Some(original), Some(expected)) => original != expected,
+            (Some(_), None) => false,
+        };
+    }
+}
+
+impl Visit for ModificationDetector<'_> {
+    fn visit_span(&mut self, span: &Span) {
+        self.check(*span, None);
+    }
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.check(ident.span, Some(&ident.sym));
+    }
+
+    fn visit_ident_name(&mut self, ident_name: &IdentName) {
+        self.check(ident_name.span, Some(&ident_name.sym));
+    }
+
+    fn visit_str(&mut self, s: &Str) {
+        self.check(s.span, s.raw.as_deref().or(Some("")));
+    }
+
+    fn visit_number(&mut self, n: &Number) {
+        self.check(n.span, n.raw.as_deref().or(Some("")));
+    }
+}
+
+fn push_modifiers(name: &mut String, modifiers: &[fervid_core::FervidAtom]) {
+    for modifier in modifiers {
+        name.push('.');
+        name.push_str(modifier);
+    }
+}
+
+/// Collapses the consecutive whitespace characters into a single space
+fn condense(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut is_prev_whitespace = false;
+
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            if !is_prev_whitespace {
+                result.push(' ');
+            }
+            is_prev_whitespace = true;
+        } else {
+            result.push(c);
+            is_prev_whitespace = false;
+        }
+    }
+
+    result
+}
+
+/// Whether `&` at the start of `s` would be decoded as a character reference, e.g. `&amp;` or `&#123;`
+fn is_char_reference(s: &str) -> bool {
+    let rest = &s[1..];
+    let len = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '#'))
+        .unwrap_or(rest.len());
+    len > 0 && rest[len..].starts_with(';')
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::{Conditional, SfcTemplateBlock};
+    use fervid_parser::SfcParser;
+    use swc_core::common::DUMMY_SP;
+
+    use super::*;
+
+    #[test]
+    fn it_reserializes_directives() {
+        let source = r#"<div class="a" v-html="html" :foo="bar" v-focus:arg.mod="x" v-if="ok" v-for="item in items" v-show="visible" v-once v-memo="deps" v-model:title.trim="text" @click.stop="go" v-text="t"></div>"#;
+
+        // Directives are kept separately from the attributes, their order is the recommended one
+        assert_eq!(
+            print(source, PrintWhitespace::Preserve, false),
+            r#"<div v-for="item in items" v-if="ok" v-show="visible" v-once v-memo="deps" v-model:title.trim="text" class="a" :foo="bar" @click.stop="go" v-focus:arg.mod="x" v-html="html" v-text="t"></div>"#
+        );
+    }
+
+    #[test]
+    fn it_preserves_shorthands() {
+        let source = r#"<MyComponent :a="1" v-bind:b="2" .c="3" v-bind="attrs" @d="e" v-on:f="g" v-on="listeners"><template #item="{ value }">{{ value }}</template><template v-slot:footer></template></MyComponent>"#;
+
+        assert_eq!(print(source, PrintWhitespace::Preserve, true), source);
+
+        // Without the source, the shortest forms are used
+        assert_eq!(
+            print(source, PrintWhitespace::Preserve, false),
+            r#"<MyComponent :a="1" :b="2" :c.prop="3" v-bind="attrs" @d="e" @f="g" v-on="listeners"><template #item="{ value }">{{ value }}</template><template #footer></template></MyComponent>"#
+        );
+    }
+
+    #[test]
+    fn it_prints_comments() {
+        let source = "<div>\n  <!-- first -->\n  <span>a</span>\n  <!--second-->\n</div>";
+
+        assert_eq!(print(source, PrintWhitespace::Preserve, true), source);
+        assert_eq!(
+            print(source, PrintWhitespace::Condense, true),
+            "<div><!-- first --><span>a</span><!--second--></div>"
+        );
+    }
+
+    #[test]
+    fn it_prints_whitespace() {
+        let source = "<div>\n  <b>a</b> <i>b</i>\n  <p>  lots   of\n  space  </p>\n  <pre>\n  keep\n    this</pre>\n  <textarea>  x  </textarea>\n</div>";
+
+        assert_eq!(print(source, PrintWhitespace::Preserve, true), source);
+        assert_eq!(
+            print(source, PrintWhitespace::Condense, true),
+            "<div><b>a</b> <i>b</i><p> lots of space </p><pre>\n  keep\n    this</pre><textarea>  x  </textarea></div>"
+        );
+    }
+
+    #[test]
+    fn it_escapes_text_and_attributes() {
+        let source = r#"<p title='say "hi"' :a="'&amp;'">1 &lt; 2 &amp;&amp; 3</p>"#;
+
+        // Untouched nodes keep their character references
+        assert_eq!(print(source, PrintWhitespace::Preserve, true), source);
+
+        // The entities are decoded by the parser, only the ambiguous characters are escaped back
+        assert_eq!(
+            print(source, PrintWhitespace::Preserve, false),
+            r#"<p title='say "hi"' :a="'&'">1 &lt; 2 && 3</p>"#
+        );
+    }
+
+    #[test]
+    fn it_prints_modified_expressions() {
+        let source = r#"<template><div :title="foo + bar" @click="select(item)">{{  foo  }} {{ bar }}</div></template>"#;
+
+        let mut roots = parse(source).roots;
+        let Node::Element(ref mut div) = roots[0] else {
+            panic!("Should be an element")
+        };
+
+        // Renamed identifier, its span still points to `bar`
+        let AttributeOrBinding::VBind(ref mut v_bind) = div.starting_tag.attributes[0] else {
+            panic!("Should be v-bind")
+        };
+        let Expr::Bin(ref mut bin_expr) = *v_bind.value else {
+            panic!("Should be a binary expression")
+        };
+        let Expr::Ident(ref mut right) = *bin_expr.right else {
+            panic!("Should be an identifier")
+        };
+        right.sym = "baz".into();
+
+        // New expression
+        let Node::Interpolation(ref mut interpolation) = div.children[2] else {
+            panic!("Should be an interpolation")
+        };
+        interpolation.value = Box::new(Expr::Ident(Ident::new_no_ctxt("qux".into(), DUMMY_SP)));
+
+        // Unmodified nodes keep their source, including the whitespace of the interpolation
+        assert_eq!(
+            print_nodes(&roots, Some(source), PrintWhitespace::Preserve),
+            r#"<div :title="foo + baz" @click="select(item)">{{  foo  }} {{ qux }}</div>"#
+        );
+    }
+
+    #[test]
+    fn it_prints_conditional_sequences() {
+        let source = r#"<template><p>a</p><p>b</p><p>c</p></template>"#;
+        let mut elements = parse(source).roots.into_iter().map(|node| match node {
+            Node::Element(element_node) => element_node,
+            _ => panic!("Should be an element"),
+        });
+        let mut next = || elements.next().expect("Should exist");
+
+        let conditional_seq = Node::ConditionalSeq(ConditionalNodeSequence {
+            if_node: Box::new(Conditional {
+                condition: Expr::Ident(Ident::new_no_ctxt("foo".into(), DUMMY_SP)),
+                node: next(),
+            }),
+            else_if_nodes: vec![Conditional {
+                condition: Expr::Ident(Ident::new_no_ctxt("bar".into(), DUMMY_SP)),
+                node: next(),
+            }],
+            else_node: Some(Box::new(next())),
+        });
+
+        assert_eq!(
+            print_nodes(&[conditional_seq], Some(source), PrintWhitespace::Preserve),
+            r#"<p v-if="foo">a</p><p v-else-if="bar">b</p><p v-else>c</p>"#
+        );
+    }
+
+    fn print(source: &str, whitespace: PrintWhitespace, with_source: bool) -> String {
+        let template = format!("<template>{source}</template>");
+        let roots = parse(&template).roots;
+        print_nodes(&roots, with_source.then_some(template.as_str()), whitespace)
+    }
+
+    fn print_nodes(nodes: &[Node], source: Option<&str>, whitespace: PrintWhitespace) -> String {
+        let original = source.map(Original::new);
+        let mut printer = TemplatePrinter::new(original.as_ref(), whitespace);
+        printer.print_nodes(nodes);
+        printer.finish()
+    }
+
+    fn parse(template: &str) -> SfcTemplateBlock {
+        let mut errors = Vec::new();
+        let mut parser = SfcParser::new(template, &mut errors);
+        let sfc = parser.parse_sfc().expect("Should parse");
+        assert!(errors.is_empty(), "{:?}", errors);
+        sfc.template.expect("Should have a template")
+    }
+}
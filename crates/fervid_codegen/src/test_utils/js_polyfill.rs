@@ -1,4 +1,7 @@
-use swc_core::{common::BytePos, ecma::ast::Expr};
+use swc_core::{
+    common::{comments::Comments, BytePos},
+    ecma::ast::{Expr, Module},
+};
 use swc_ecma_parser::{lexer::Lexer, PResult, Parser, StringInput, Syntax};
 
 /// Parses js as a temporary measure
@@ -16,3 +19,32 @@ pub fn parse_js(expr: &str) -> PResult<Box<Expr>> {
 
     parser.parse_expr()
 }
+
+/// Parses a js module
+pub fn parse_js_module(module: &str) -> PResult<Module> {
+    let lexer = Lexer::new(
+        Syntax::Es(Default::default()),
+        Default::default(),
+        StringInput::new(module, BytePos(0), BytePos(module.len() as u32)),
+        None,
+    );
+
+    let mut parser = Parser::new_from(lexer);
+
+    parser.parse_module()
+}
+
+/// Parses a js module collecting the comments.
+/// Positions are 1-based, the same as in the SFC parser
+pub fn parse_js_module_with_comments(module: &str, comments: &dyn Comments) -> PResult<Module> {
+    let lexer = Lexer::new(
+        Syntax::Es(Default::default()),
+        Default::default(),
+        StringInput::new(module, BytePos(1), BytePos(module.len() as u32 + 1)),
+        Some(comments),
+    );
+
+    let mut parser = Parser::new_from(lexer);
+
+    parser.parse_module()
+}
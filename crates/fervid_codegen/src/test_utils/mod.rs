@@ -1,7 +1,7 @@
 use fervid_core::{AttributeOrBinding, VBindDirective, VOnDirective};
 use swc_core::{
-    common::{SourceMap, DUMMY_SP},
-    ecma::ast::Expr,
+    common::{comments::Comments, SourceMap, DUMMY_SP},
+    ecma::ast::{Expr, Module},
 };
 use swc_ecma_codegen::{text_writer::JsWriter, Emitter, Node};
 
@@ -32,6 +32,15 @@ pub fn js(raw: &str) -> Box<Expr> {
     js_polyfill::parse_js(raw).expect("input must be a valid js")
 }
 
+pub fn js_module(raw: &str) -> Module {
+    js_polyfill::parse_js_module(raw).expect("input must be a valid js module")
+}
+
+pub fn js_module_with_comments(raw: &str, comments: &dyn Comments) -> Module {
+    js_polyfill::parse_js_module_with_comments(raw, comments)
+        .expect("input must be a valid js module")
+}
+
 /// TEST ONLY
 #[inline]
 pub fn regular_attribute(name: &str, value: &str) -> AttributeOrBinding {
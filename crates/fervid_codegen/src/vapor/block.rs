@@ -0,0 +1,1010 @@
+use fervid_core::{
+    check_attribute_name, str_or_expr_to_propname, AttributeOrBinding, BuiltinType,
+    ComponentBinding, Conditional, ConditionalNodeSequence, ElementKind, ElementNode, FervidAtom,
+    Node, StrOrExpr, VBindDirective, VueImports,
+};
+use swc_core::{
+    common::{Spanned, DUMMY_SP},
+    ecma::ast::{
+        ArrayLit, BinExpr, BinaryOp, BlockStmtOrExpr, Bool, Expr, ExprOrSpread, IdentName,
+        KeyValueProp, Lit, Null, ObjectLit, ParenExpr, Pat, Prop, PropName, PropOrSpread, Stmt,
+    },
+};
+
+use super::{
+    arrow, arrow_block, call_expr, expr_stmt, getter, ident,
+    scope::{collect_expr_vars, collect_pat_vars, replace_scope_vars, value_of, ScopeVars},
+    str_lit, Block, VaporGenerator,
+};
+use crate::{control_flow::generate_const_decl, text::shorten_whitespace};
+
+/// A child of a block or an element.
+/// Comments are skipped and `<template>`s without directives are unwrapped.
+pub(super) enum TemplateChild<'n> {
+    /// Adjacent texts and interpolations, which become a single text node
+    Text(Vec<&'n Node>),
+    Element(&'n ElementNode),
+    Conditional(&'n ConditionalNodeSequence),
+}
+
+pub(super) fn group_children<'n>(
+    nodes: impl IntoIterator<Item = &'n Node>,
+) -> Vec<TemplateChild<'n>> {
+    let mut out = Vec::new();
+    for node in nodes {
+        push_child(node, &mut out);
+    }
+    out
+}
+
+fn push_child<'n>(node: &'n Node, out: &mut Vec<TemplateChild<'n>>) {
+    match node {
+        Node::Text(..) | Node::Interpolation(_) => {
+            if let Some(TemplateChild::Text(parts)) = out.last_mut() {
+                parts.push(node);
+            } else {
+                out.push(TemplateChild::Text(vec![node]));
+            }
+        }
+        Node::Comment(..) => {}
+        Node::Element(element_node) if is_fragment_template(element_node) => {
+            for child in element_node.children.iter() {
+                push_child(child, out);
+            }
+        }
+        Node::Element(element_node) => out.push(TemplateChild::Element(element_node)),
+        Node::ConditionalSeq(conditional_seq) => {
+            out.push(TemplateChild::Conditional(conditional_seq))
+        }
+    }
+}
+
+/// `<template>` which only groups its children
+pub(super) fn is_fragment_template(element_node: &ElementNode) -> bool {
+    is_template(element_node)
+        && element_node
+            .starting_tag
+            .directives
+            .as_ref()
+            .is_none_or(|directives| directives.v_for.is_none() && directives.v_slot.is_none())
+}
+
+pub(super) fn is_template(element_node: &ElementNode) -> bool {
+    matches!(element_node.kind, ElementKind::Element)
+        && element_node.starting_tag.tag_name == "template"
+}
+
+pub(super) fn has_v_for(element_node: &ElementNode) -> bool {
+    element_node
+        .starting_tag
+        .directives
+        .as_ref()
+        .is_some_and(|directives| directives.v_for.is_some())
+}
+
+pub(super) fn is_static_text(parts: &[&Node]) -> bool {
+    parts.iter().all(|part| matches!(part, Node::Text(..)))
+}
+
+pub(super) fn static_text(parts: &[&Node]) -> String {
+    let mut out = String::new();
+    for part in parts {
+        if let Node::Text(text, _) = part {
+            out.push_str(&shorten_whitespace(text));
+        }
+    }
+    out
+}
+
+/// `VaporVForFlags.ONCE` of `createFor`
+const V_FOR_ONCE: usize = 1 << 2;
+
+/// Attributes which are not passed to components in Vapor mode
+fn is_component_prop(attr: &AttributeOrBinding, excluded: &str) -> bool {
+    !["ref", "ref_key", "ref_for", "key", excluded]
+        .iter()
+        .any(|name| check_attribute_name(attr, name))
+}
+
+fn is_whitespace(node: &Node) -> bool {
+    match node {
+        Node::Text(text, _) => text.trim().is_empty(),
+        Node::Comment(..) => true,
+        _ => false,
+    }
+}
+
+fn null() -> Box<Expr> {
+    Box::new(Expr::Lit(Lit::Null(Null { span: DUMMY_SP })))
+}
+
+fn bool_lit(value: bool) -> Box<Expr> {
+    Box::new(Expr::Lit(Lit::Bool(Bool {
+        span: DUMMY_SP,
+        value,
+    })))
+}
+
+impl VaporGenerator<'_> {
+    /// Generates the nodes of a block,
+    /// i.e. the roots of the template, a `v-if` branch, a `v-for` item or a slot.
+    pub(super) fn generate_block<'n>(
+        &mut self,
+        nodes: impl IntoIterator<Item = &'n Node>,
+        is_root: bool,
+    ) -> Block {
+        let mut children = group_children(nodes);
+
+        // Whitespace between the block nodes is insignificant
+        if children.len() > 1 {
+            children.retain(|child| match child {
+                TemplateChild::Text(parts) => {
+                    !is_static_text(parts) || !static_text(parts).trim().is_empty()
+                }
+                _ => true,
+            });
+        }
+
+        let is_single_root = is_root && children.len() == 1;
+        let mut block = Block::default();
+        for child in children.iter() {
+            let node = match child {
+                TemplateChild::Text(parts) => self.generate_text_node(parts, &mut block.stmts),
+                TemplateChild::Element(element_node) => self.generate_root_element(
+                    element_node,
+                    is_root,
+                    is_single_root,
+                    &mut block.stmts,
+                ),
+                TemplateChild::Conditional(conditional_seq) => {
+                    self.generate_if(conditional_seq, &mut block.stmts)
+                }
+            };
+            block.nodes.push(node);
+        }
+
+        block
+    }
+
+    /// Generates a block from a single element, e.g. a `v-if` branch
+    fn generate_element_block(&mut self, element_node: &ElementNode, is_for_item: bool) -> Block {
+        if is_template(element_node) && (is_for_item || !has_v_for(element_node)) {
+            return self.generate_block(element_node.children.iter(), false);
+        }
+
+        let mut block = Block::default();
+        let node = if is_for_item {
+            self.generate_element_node(element_node, false, false, &mut block.stmts)
+        } else {
+            self.generate_root_element(element_node, false, false, &mut block.stmts)
+        };
+        block.nodes.push(node);
+        block
+    }
+
+    /// `const n0 = t0()` for a static text or
+    /// `const n0 = t0(); _renderEffect(() => _setText(n0, ...))` for a dynamic one
+    fn generate_text_node(&mut self, parts: &[&Node], stmts: &mut Vec<Stmt>) -> FervidAtom {
+        let node = self.next_node("n");
+
+        if is_static_text(parts) {
+            let mut html = String::new();
+            push_escaped(&static_text(parts), &mut html, false);
+            let template = self.add_template(html, false);
+            stmts.push(generate_const_decl(
+                node.to_owned(),
+                call_expr(template, vec![]),
+            ));
+            return node;
+        }
+
+        let template = self.add_template(String::from(" "), false);
+        stmts.push(generate_const_decl(
+            node.to_owned(),
+            call_expr(template, vec![]),
+        ));
+
+        let text_value = self.generate_text_value(parts);
+        let set_text = self.call(
+            VueImports::SetText,
+            vec![ident(node.to_owned()), text_value],
+        );
+        self.push_effect(vec![expr_stmt(set_text)], stmts);
+
+        node
+    }
+
+    /// `"text " + _toDisplayString(value)`
+    pub(super) fn generate_text_value(&mut self, parts: &[&Node]) -> Box<Expr> {
+        let mut result: Option<Box<Expr>> = None;
+        for part in parts {
+            let expr = match part {
+                Node::Text(text, _) => str_lit(shorten_whitespace(text)),
+                Node::Interpolation(interpolation) => self.call(
+                    VueImports::ToDisplayString,
+                    vec![interpolation.value.to_owned()],
+                ),
+                _ => continue,
+            };
+
+            result = Some(match result {
+                Some(left) => Box::new(Expr::Bin(BinExpr {
+                    span: DUMMY_SP,
+                    op: BinaryOp::Add,
+                    left,
+                    right: expr,
+                })),
+                None => expr,
+            });
+        }
+
+        result.unwrap_or_else(|| str_lit(""))
+    }
+
+    /// `_renderEffect(() => { stmts })`, or just the `stmts` inside of `v-once`
+    pub(super) fn push_effect(&mut self, mut stmts: Vec<Stmt>, out: &mut Vec<Stmt>) {
+        if self.in_once {
+            out.extend(stmts);
+            return;
+        }
+
+        let effect = match stmts.pop() {
+            Some(Stmt::Expr(expr_stmt)) if stmts.is_empty() => {
+                arrow(vec![], BlockStmtOrExpr::Expr(expr_stmt.expr))
+            }
+            Some(last) => {
+                stmts.push(last);
+                arrow_block(vec![], stmts)
+            }
+            None => arrow_block(vec![], vec![]),
+        };
+
+        out.push(expr_stmt(self.call(VueImports::RenderEffect, vec![effect])));
+    }
+
+    pub(super) fn generate_root_element(
+        &mut self,
+        element_node: &ElementNode,
+        is_root: bool,
+        is_single_root: bool,
+        stmts: &mut Vec<Stmt>,
+    ) -> FervidAtom {
+        if has_v_for(element_node) {
+            return self.generate_for(element_node, stmts);
+        }
+
+        self.generate_element_node(element_node, is_root, is_single_root, stmts)
+    }
+
+    fn generate_element_node(
+        &mut self,
+        element_node: &ElementNode,
+        is_root: bool,
+        is_single_root: bool,
+        stmts: &mut Vec<Stmt>,
+    ) -> FervidAtom {
+        match element_node.kind {
+            ElementKind::Builtin(BuiltinType::Slot) => {
+                self.generate_slot_outlet(element_node, stmts)
+            }
+            ElementKind::Builtin(_) | ElementKind::Component => {
+                self.generate_component(element_node, is_single_root, stmts)
+            }
+            ElementKind::Element => {
+                let node = self.next_node("n");
+                let mut html = String::new();
+                let mut ops = Vec::new();
+                self.generate_element(element_node, &node, &mut html, &mut ops);
+
+                let template = self.add_template(html, is_root);
+                stmts.push(generate_const_decl(
+                    node.to_owned(),
+                    call_expr(template, vec![]),
+                ));
+                stmts.extend(ops);
+                node
+            }
+        }
+    }
+
+    /// `const n0 = _createIf(() => (cond), () => { /*...*/ }, () => { /*...*/ })`
+    pub(super) fn generate_if(
+        &mut self,
+        conditional_seq: &ConditionalNodeSequence,
+        stmts: &mut Vec<Stmt>,
+    ) -> FervidAtom {
+        let node = self.next_node("n");
+        let was_in_once = self.enter_once(&conditional_seq.if_node.node);
+        let create_if = self.generate_conditional(
+            &conditional_seq.if_node,
+            &conditional_seq.else_if_nodes,
+            conditional_seq.else_node.as_deref(),
+        );
+        self.in_once = was_in_once;
+        stmts.push(generate_const_decl(node.to_owned(), create_if));
+        node
+    }
+
+    /// `v-else-if` branches become nested `_createIf`s
+    fn generate_conditional(
+        &mut self,
+        conditional: &Conditional,
+        else_if_nodes: &[Conditional],
+        else_node: Option<&ElementNode>,
+    ) -> Box<Expr> {
+        let condition = getter(Box::new(conditional.condition.to_owned()));
+        let positive = self.generate_element_block(&conditional.node, false);
+        let mut args = vec![condition, arrow_block(vec![], positive.into_stmts())];
+
+        if let Some((next, rest)) = else_if_nodes.split_first() {
+            let nested = self.generate_conditional(next, rest, else_node);
+            args.push(arrow(vec![], BlockStmtOrExpr::Expr(nested)));
+        } else if let Some(else_node) = else_node {
+            let negative = self.generate_element_block(else_node, false);
+            args.push(arrow_block(vec![], negative.into_stmts()));
+        }
+
+        // The condition is only evaluated once
+        if self.in_once {
+            if args.len() == 2 {
+                args.push(null());
+            }
+            args.push(bool_lit(true));
+        }
+
+        self.call(VueImports::CreateIf, args)
+    }
+
+    /// `const n0 = _createFor(() => (list), (_for_item0) => { /*...*/ }, (_for_item0) => (key))`
+    fn generate_for(&mut self, element_node: &ElementNode, stmts: &mut Vec<Stmt>) -> FervidAtom {
+        let Some(v_for) = element_node
+            .starting_tag
+            .directives
+            .as_ref()
+            .and_then(|directives| directives.v_for.as_ref())
+        else {
+            unreachable!("generate_for is only called for v-for elements")
+        };
+
+        let node = self.next_node("n");
+        let for_idx = self.next_for_idx;
+        self.next_for_idx += 1;
+        let was_in_once = self.enter_once(element_node);
+
+        // `(item, key, index)` -> `(_for_item0, _for_key0, _for_index0)`
+        let itervar_parts = match unwrap_parens(&v_for.itervar) {
+            Expr::Seq(seq) => seq.exprs.iter().map(|expr| expr.as_ref()).collect(),
+            itervar => vec![itervar],
+        };
+        let params: Vec<FervidAtom> = ["_for_item", "_for_key", "_for_index"]
+            .iter()
+            .take(itervar_parts.len())
+            .map(|prefix| FervidAtom::from(format!("{prefix}{for_idx}")))
+            .collect();
+
+        // The item sources are refs, while the key function receives plain values
+        let mut item_vars = ScopeVars::new();
+        let mut key_vars = ScopeVars::new();
+        for (part, param) in itervar_parts.iter().zip(params.iter()) {
+            collect_expr_vars(part, value_of(param.to_owned()), &mut item_vars);
+            collect_expr_vars(part, ident(param.to_owned()), &mut key_vars);
+        }
+
+        let item_block = self.generate_element_block(element_node, true);
+        let mut render_item = arrow_block(params.to_owned(), item_block.into_stmts());
+        replace_scope_vars(render_item.as_mut(), &item_vars);
+
+        let mut args = vec![getter(v_for.iterable.to_owned()), render_item];
+
+        let key = element_node
+            .starting_tag
+            .attributes
+            .iter()
+            .find_map(|attr| match attr {
+                AttributeOrBinding::VBind(VBindDirective {
+                    argument: Some(StrOrExpr::Str(argument)),
+                    value,
+                    ..
+                }) if argument == "key" => Some(value),
+                _ => None,
+            });
+        if let Some(key) = key {
+            let mut get_key = arrow(
+                params,
+                BlockStmtOrExpr::Expr(Box::new(Expr::Paren(ParenExpr {
+                    span: DUMMY_SP,
+                    expr: key.to_owned(),
+                }))),
+            );
+            replace_scope_vars(get_key.as_mut(), &key_vars);
+            args.push(get_key);
+        }
+
+        // The list is only rendered once
+        if self.in_once {
+            if args.len() == 2 {
+                args.push(null());
+            }
+            args.push(Box::new(Expr::Lit(Lit::Num(V_FOR_ONCE.into()))));
+        }
+        self.in_once = was_in_once;
+
+        let create_for = self.call(VueImports::CreateFor, args);
+        stmts.push(generate_const_decl(node.to_owned(), create_for));
+        node
+    }
+
+    /// `const n0 = _createComponent(Comp, { msg: () => (msg.value) }, { default: () => { /*...*/ } })`
+    fn generate_component(
+        &mut self,
+        element_node: &ElementNode,
+        is_single_root: bool,
+        stmts: &mut Vec<Stmt>,
+    ) -> FervidAtom {
+        let node = self.next_node("n");
+        let tag_name = &element_node.starting_tag.tag_name;
+        let was_in_once = self.enter_once(element_node);
+
+        let (create_component, component, excluded) = match element_node.kind {
+            // `<component :is="comp">`
+            ElementKind::Builtin(BuiltinType::Component) => {
+                let is = element_node
+                    .starting_tag
+                    .attributes
+                    .iter()
+                    .find_map(|attr| match attr {
+                        AttributeOrBinding::RegularAttribute { name, value, .. }
+                            if name == "is" =>
+                        {
+                            Some(str_lit(value.to_owned()))
+                        }
+                        AttributeOrBinding::VBind(VBindDirective {
+                            argument: Some(StrOrExpr::Str(argument)),
+                            value,
+                            ..
+                        }) if argument == "is" => Some(value.to_owned()),
+                        _ => None,
+                    })
+                    .unwrap_or_else(null);
+
+                (VueImports::CreateDynamicComponent, getter(is), "is")
+            }
+
+            ElementKind::Builtin(builtin_type) => {
+                let builtin_import = match builtin_type {
+                    BuiltinType::KeepAlive => VueImports::KeepAlive,
+                    BuiltinType::Suspense => VueImports::Suspense,
+                    BuiltinType::Teleport => VueImports::Teleport,
+                    BuiltinType::Transition => VueImports::Transition,
+                    BuiltinType::TransitionGroup => VueImports::TransitionGroup,
+                    BuiltinType::Component | BuiltinType::Slot => unreachable!(),
+                };
+                let builtin = self.ctx.get_and_add_import_ident(builtin_import);
+                (VueImports::CreateComponent, ident(builtin), "")
+            }
+
+            _ => {
+                let component = self
+                    .ctx
+                    .get_component_identifier(tag_name, element_node.span);
+
+                // Components resolved at runtime may also be plain elements
+                let is_runtime_resolved = matches!(
+                    self.ctx.bindings_helper.components.get(tag_name),
+                    Some(ComponentBinding::RuntimeResolved(_))
+                );
+                let create_component = if is_runtime_resolved {
+                    VueImports::CreateComponentWithFallback
+                } else {
+                    VueImports::CreateComponent
+                };
+
+                (create_component, Box::new(component), "")
+            }
+        };
+
+        let props = self.generate_component_props(element_node, excluded);
+        let slots = self.generate_component_slots(element_node);
+
+        let mut args = vec![
+            component,
+            props.unwrap_or_else(null),
+            slots.unwrap_or_else(null),
+        ];
+        if is_single_root || self.in_once {
+            args.push(bool_lit(is_single_root));
+            if self.in_once {
+                args.push(bool_lit(true));
+            }
+        } else {
+            // Trailing `null`s are not needed
+            while args.len() > 1
+                && matches!(
+                    args.last().map(|arg| arg.as_ref()),
+                    Some(Expr::Lit(Lit::Null(_)))
+                )
+            {
+                args.pop();
+            }
+        }
+
+        let create_component = self.call(create_component, args);
+        stmts.push(generate_const_decl(node.to_owned(), create_component));
+        self.generate_template_ref(element_node, &node, stmts);
+        self.generate_custom_directives(element_node, &node, stmts);
+
+        // `_applyVShow(n0, () => (visible))`
+        if let Some(v_show) = element_node
+            .starting_tag
+            .directives
+            .as_ref()
+            .and_then(|directives| directives.v_show.as_ref())
+        {
+            let apply_v_show = self.call(
+                VueImports::ApplyVShow,
+                vec![ident(node.to_owned()), getter(v_show.to_owned())],
+            );
+            stmts.push(expr_stmt(apply_v_show));
+        }
+
+        self.in_once = was_in_once;
+        node
+    }
+
+    /// Props of a component are getters, e.g. `{ msg: () => (msg.value) }`.
+    /// `v-bind="obj"` becomes a dynamic source `$: [() => (obj)]`.
+    fn generate_component_props(
+        &mut self,
+        element_node: &ElementNode,
+        excluded: &str,
+    ) -> Option<Box<Expr>> {
+        let attributes: Vec<AttributeOrBinding> = element_node
+            .starting_tag
+            .attributes
+            .iter()
+            .filter(|attr| is_component_prop(attr, excluded))
+            .cloned()
+            .collect();
+
+        let mut props = Vec::new();
+        let result_hints = self.ctx.generate_attributes(&attributes, &mut props);
+        let v_bind_no_arg = result_hints
+            .v_bind_no_arg
+            .map(|v_bind| v_bind.value.to_owned());
+
+        if let Some(ref directives) = element_node.starting_tag.directives {
+            for v_model in directives.v_model.iter() {
+                self.ctx.generate_v_model_for_component(v_model, &mut props);
+            }
+        }
+
+        for prop in props.iter_mut() {
+            if let PropOrSpread::Prop(prop) = prop {
+                if let Prop::KeyValue(key_value) = prop.as_mut() {
+                    let value = std::mem::replace(&mut key_value.value, null());
+                    key_value.value = getter(value);
+                }
+            }
+        }
+
+        if let Some(v_bind_no_arg) = v_bind_no_arg {
+            props.push(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(IdentName {
+                    span: DUMMY_SP,
+                    sym: FervidAtom::from("$"),
+                }),
+                value: Box::new(Expr::Array(ArrayLit {
+                    span: DUMMY_SP,
+                    elems: vec![Some(ExprOrSpread {
+                        spread: None,
+                        expr: getter(v_bind_no_arg),
+                    })],
+                })),
+            }))));
+        }
+
+        if props.is_empty() {
+            return None;
+        }
+
+        Some(Box::new(Expr::Object(ObjectLit {
+            span: DUMMY_SP,
+            props,
+        })))
+    }
+
+    /// Slots of a component, e.g. `{ default: () => { /*...*/ }, header: (_slotProps0) => { /*...*/ } }`
+    fn generate_component_slots(&mut self, element_node: &ElementNode) -> Option<Box<Expr>> {
+        let component_v_slot = element_node
+            .starting_tag
+            .directives
+            .as_ref()
+            .and_then(|directives| directives.v_slot.as_ref());
+
+        // `<template v-slot:name>` are named slots, the rest goes to the default one
+        let mut named_slots = Vec::new();
+        let mut default_slot_nodes = Vec::new();
+        for child in element_node.children.iter() {
+            match child {
+                Node::Element(child_element) if is_template(child_element) => {
+                    match child_element
+                        .starting_tag
+                        .directives
+                        .as_ref()
+                        .and_then(|directives| directives.v_slot.as_ref())
+                    {
+                        Some(v_slot) => named_slots.push((v_slot, child_element)),
+                        None => default_slot_nodes.push(child),
+                    }
+                }
+                _ => default_slot_nodes.push(child),
+            }
+        }
+
+        let mut slots = Vec::new();
+        if component_v_slot.is_some() || !default_slot_nodes.iter().all(|node| is_whitespace(node))
+        {
+            let slot_name = component_v_slot.and_then(|v_slot| v_slot.slot_name.to_owned());
+            let slot_props = component_v_slot.and_then(|v_slot| v_slot.value.as_deref());
+            let slot_fn = self.generate_slot_fn(default_slot_nodes, slot_props);
+            slots.push(slot_prop(slot_name, slot_fn));
+        }
+
+        for (v_slot, template) in named_slots {
+            let slot_fn = self.generate_slot_fn(template.children.iter(), v_slot.value.as_deref());
+            slots.push(slot_prop(v_slot.slot_name.to_owned(), slot_fn));
+        }
+
+        if slots.is_empty() {
+            return None;
+        }
+
+        Some(Box::new(Expr::Object(ObjectLit {
+            span: DUMMY_SP,
+            props: slots,
+        })))
+    }
+
+    /// `(_slotProps0) => { /*...*/ }`
+    fn generate_slot_fn<'n>(
+        &mut self,
+        nodes: impl IntoIterator<Item = &'n Node>,
+        slot_props: Option<&Pat>,
+    ) -> Box<Expr> {
+        let mut params = Vec::new();
+        let mut vars = ScopeVars::new();
+        if let Some(slot_props) = slot_props {
+            let param = FervidAtom::from(format!("_slotProps{}", self.next_slot_idx));
+            self.next_slot_idx += 1;
+            collect_pat_vars(slot_props, ident(param.to_owned()), &mut vars);
+            params.push(param);
+        }
+
+        let block = self.generate_block(nodes, false);
+        let mut slot_fn = arrow_block(params, block.into_stmts());
+        replace_scope_vars(slot_fn.as_mut(), &vars);
+        slot_fn
+    }
+
+    /// `const n0 = _createSlot("name", { prop: () => (value) }, () => { /*fallback*/ })`
+    fn generate_slot_outlet(
+        &mut self,
+        element_node: &ElementNode,
+        stmts: &mut Vec<Stmt>,
+    ) -> FervidAtom {
+        let node = self.next_node("n");
+
+        let name = element_node
+            .starting_tag
+            .attributes
+            .iter()
+            .find_map(|attr| match attr {
+                AttributeOrBinding::RegularAttribute { name, value, .. } if name == "name" => {
+                    Some(str_lit(value.to_owned()))
+                }
+                AttributeOrBinding::VBind(VBindDirective {
+                    argument: Some(StrOrExpr::Str(argument)),
+                    value,
+                    ..
+                }) if argument == "name" => Some(getter(value.to_owned())),
+                _ => None,
+            })
+            .unwrap_or_else(|| str_lit("default"));
+
+        let props = self.generate_component_props(element_node, "name");
+
+        let mut args = vec![name];
+        if !element_node.children.iter().all(is_whitespace) {
+            let fallback = self.generate_block(element_node.children.iter(), false);
+            args.push(props.unwrap_or_else(null));
+            args.push(arrow_block(vec![], fallback.into_stmts()));
+        } else if let Some(props) = props {
+            args.push(props);
+        }
+
+        let create_slot = self.call(VueImports::CreateSlot, args);
+        stmts.push(generate_const_decl(node.to_owned(), create_slot));
+        node
+    }
+
+    /// `_setTemplateRef(n0, "name")` for `ref="name"`
+    pub(super) fn generate_template_ref(
+        &mut self,
+        element_node: &ElementNode,
+        node: &FervidAtom,
+        stmts: &mut Vec<Stmt>,
+    ) {
+        for attr in element_node.starting_tag.attributes.iter() {
+            let value = match attr {
+                AttributeOrBinding::RegularAttribute { name, value, .. } if name == "ref" => {
+                    str_lit(value.to_owned())
+                }
+                AttributeOrBinding::VBind(VBindDirective {
+                    argument: Some(StrOrExpr::Str(argument)),
+                    value,
+                    ..
+                }) if argument == "ref" => value.to_owned(),
+                _ => continue,
+            };
+
+            self.uses_template_refs = true;
+            stmts.push(expr_stmt(call_expr(
+                FervidAtom::from("_setTemplateRef"),
+                vec![ident(node.to_owned()), value],
+            )));
+        }
+    }
+
+    /// `_withVaporDirectives(n0, [[vFocus, () => (value), "arg", { modifier: true }]])`
+    pub(super) fn generate_custom_directives(
+        &mut self,
+        element_node: &ElementNode,
+        node: &FervidAtom,
+        stmts: &mut Vec<Stmt>,
+    ) {
+        let Some(ref directives) = element_node.starting_tag.directives else {
+            return;
+        };
+        if directives.custom.is_empty() {
+            return;
+        }
+
+        let mut directives_arr = Vec::with_capacity(directives.custom.len());
+        for custom_directive in directives.custom.iter() {
+            let span = custom_directive
+                .value
+                .as_ref()
+                .map_or(DUMMY_SP, |value| value.span());
+            let directive_ident = self
+                .ctx
+                .get_custom_directive_ident(&custom_directive.name, span);
+
+            // Values are passed as getters, same as the props
+            let value = custom_directive
+                .value
+                .as_ref()
+                .map(|value| getter(value.to_owned()));
+
+            let directive = self.ctx.generate_directive_from_parts(
+                directive_ident,
+                value.as_deref(),
+                custom_directive.argument.as_ref(),
+                &custom_directive.modifiers,
+                span,
+            );
+            directives_arr.push(Some(ExprOrSpread {
+                spread: None,
+                expr: Box::new(directive),
+            }));
+        }
+
+        let with_directives = self.call(
+            VueImports::WithVaporDirectives,
+            vec![
+                ident(node.to_owned()),
+                Box::new(Expr::Array(ArrayLit {
+                    span: DUMMY_SP,
+                    elems: directives_arr,
+                })),
+            ],
+        );
+        stmts.push(expr_stmt(with_directives));
+    }
+}
+
+fn slot_prop(slot_name: Option<StrOrExpr>, slot_fn: Box<Expr>) -> PropOrSpread {
+    let slot_name = slot_name.unwrap_or_else(|| "default".into());
+    PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+        key: str_or_expr_to_propname(slot_name, DUMMY_SP),
+        value: slot_fn,
+    })))
+}
+
+fn unwrap_parens(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Paren(paren) => unwrap_parens(&paren.expr),
+        _ => expr,
+    }
+}
+
+pub(super) fn push_escaped(text: &str, html: &mut String, is_attribute: bool) {
+    for ch in text.chars() {
+        match ch {
+            '&' => html.push_str("&amp;"),
+            '<' if !is_attribute => html.push_str("&lt;"),
+            '"' if is_attribute => html.push_str("&quot;"),
+            _ => html.push(ch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::{
+        BuiltinType, Conditional, ConditionalNodeSequence, ElementKind, Node, VCustomDirective,
+        VForDirective, VueDirectives,
+    };
+    use swc_core::common::DUMMY_SP;
+
+    use crate::{
+        test_utils::{js, regular_attribute, v_bind_attribute},
+        vapor::tests::{component, element, interpolation, test_out},
+    };
+
+    #[test]
+    fn it_skips_whitespace_between_roots() {
+        // <span>a</span> <span>b</span>
+        test_out(
+            vec![
+                Node::Text("\n".into(), DUMMY_SP),
+                Node::Element(element(
+                    "span",
+                    vec![],
+                    vec![Node::Text("a".into(), DUMMY_SP)],
+                )),
+                Node::Text(" ".into(), DUMMY_SP),
+                Node::Element(element(
+                    "span",
+                    vec![],
+                    vec![Node::Text("b".into(), DUMMY_SP)],
+                )),
+            ],
+            r#"const t0=_template("<span>a</span>",true);const t1=_template("<span>b</span>",true);"#,
+            r#"const n0=t0();const n1=t1();return[n0,n1];"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_v_else_if() {
+        // <p v-if="a">a</p><p v-else-if="b">b</p>
+        let conditional_seq = ConditionalNodeSequence {
+            if_node: Box::new(Conditional {
+                condition: *js("a"),
+                node: element("p", vec![], vec![Node::Text("a".into(), DUMMY_SP)]),
+            }),
+            else_if_nodes: vec![Conditional {
+                condition: *js("b"),
+                node: element("p", vec![], vec![Node::Text("b".into(), DUMMY_SP)]),
+            }],
+            else_node: None,
+        };
+
+        test_out(
+            vec![Node::ConditionalSeq(conditional_seq)],
+            r#"const t0=_template("<p>a</p>");const t1=_template("<p>b</p>");"#,
+            r#"const n0=_createIf(()=>(a),()=>{const n1=t0();return n1;},()=>_createIf(()=>(b),()=>{const n2=t1();return n2;}));return n0;"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_v_if_once() {
+        // <p v-if="ok" v-once>{{ msg }}</p>
+        let mut p = element("p", vec![], vec![interpolation("msg")]);
+        p.starting_tag.directives = Some(Box::new(VueDirectives {
+            v_once: Some(()),
+            ..Default::default()
+        }));
+        let conditional_seq = ConditionalNodeSequence {
+            if_node: Box::new(Conditional {
+                condition: *js("ok"),
+                node: p,
+            }),
+            else_if_nodes: vec![],
+            else_node: None,
+        };
+
+        test_out(
+            vec![Node::ConditionalSeq(conditional_seq)],
+            r#"const t0=_template("<p> </p>");"#,
+            r#"const n0=_createIf(()=>(ok),()=>{const n1=t0();const x2=_txt(n1);_setText(x2,_toDisplayString(msg));return n1;},null,true);return n0;"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_v_for_once() {
+        // <li v-for="item in list" v-once>{{ item }}</li>
+        let mut li = element("li", vec![], vec![interpolation("item")]);
+        li.starting_tag.directives = Some(Box::new(VueDirectives {
+            v_for: Some(VForDirective {
+                iterable: js("list"),
+                itervar: js("item"),
+                patch_flags: Default::default(),
+                span: DUMMY_SP,
+            }),
+            v_once: Some(()),
+            ..Default::default()
+        }));
+
+        test_out(
+            vec![Node::Element(li)],
+            r#"const t0=_template("<li> </li>");"#,
+            r#"const n0=_createFor(()=>(list),_for_item0=>{const n1=t0();const x2=_txt(n1);_setText(x2,_toDisplayString(_for_item0.value));return n1;},null,4);return n0;"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_slot_outlet() {
+        // <slot name="header" :title="title">fallback</slot>
+        let slot = fervid_core::ElementNode {
+            kind: ElementKind::Builtin(BuiltinType::Slot),
+            ..element(
+                "slot",
+                vec![
+                    regular_attribute("name", "header"),
+                    v_bind_attribute("title", "title"),
+                ],
+                vec![Node::Text("fallback".into(), DUMMY_SP)],
+            )
+        };
+
+        test_out(
+            vec![Node::Element(slot)],
+            r#"const t0=_template("fallback");"#,
+            r#"const n0=_createSlot("header",{title:()=>(title)},()=>{const n1=t0();return n1;});return n0;"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_dynamic_component() {
+        // <component :is="view" :msg="msg" />
+        let dynamic = fervid_core::ElementNode {
+            kind: ElementKind::Builtin(BuiltinType::Component),
+            ..element(
+                "component",
+                vec![
+                    v_bind_attribute("is", "view"),
+                    v_bind_attribute("msg", "msg"),
+                ],
+                vec![],
+            )
+        };
+
+        test_out(
+            vec![Node::Element(dynamic)],
+            r#""#,
+            r#"const n0=_createDynamicComponent(()=>(view),{msg:()=>(msg)},null,true);return n0;"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_component_once_with_directives() {
+        // <Comp v-once v-focus :msg="msg" />
+        let mut comp = component("Comp", vec![v_bind_attribute("msg", "msg")], vec![]);
+        comp.starting_tag.directives = Some(Box::new(VueDirectives {
+            v_once: Some(()),
+            custom: vec![VCustomDirective {
+                name: "focus".into(),
+                argument: None,
+                modifiers: vec![],
+                value: None,
+            }],
+            ..Default::default()
+        }));
+
+        test_out(
+            vec![Node::Element(comp)],
+            r#""#,
+            r#"const _directive_focus=_resolveDirective("focus");const n0=_createComponent(Comp,{msg:()=>(msg)},null,true,true);_withVaporDirectives(n0,[[_directive_focus]]);return n0;"#,
+        );
+    }
+}
@@ -0,0 +1,793 @@
+use fervid_core::{
+    AttributeOrBinding, ElementNode, FervidAtom, Node, StrOrExpr, VBindDirective, VModelDirective,
+    VOnDirective, VueImports,
+};
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::ast::{
+        ArrayLit, AssignExpr, AssignOp, AssignTarget, Bool, Callee, ComputedPropName, Expr,
+        ExprOrSpread, IdentName, KeyValueProp, Lit, MemberExpr, MemberProp, ObjectLit, Prop,
+        PropName, PropOrSpread, SimpleAssignTarget, Stmt,
+    },
+};
+
+use super::{
+    arrow_block,
+    block::{
+        group_children, has_v_for, is_static_text, is_template, push_escaped, static_text,
+        TemplateChild,
+    },
+    expr_stmt, getter, ident, str_lit, VaporGenerator,
+};
+use crate::control_flow::generate_const_decl;
+
+/// Events which are handled by a single listener on the document,
+/// https://github.com/vuejs/core/blob/minor/packages/compiler-vapor/src/generators/event.ts
+const DELEGATED_EVENTS: &[&str] = &[
+    "beforeinput",
+    "click",
+    "dblclick",
+    "contextmenu",
+    "focusin",
+    "focusout",
+    "input",
+    "keydown",
+    "keyup",
+    "mousedown",
+    "mousemove",
+    "mouseout",
+    "mouseover",
+    "mouseup",
+    "pointerdown",
+    "pointermove",
+    "pointerout",
+    "pointerover",
+    "pointerup",
+    "touchend",
+    "touchmove",
+    "touchstart",
+];
+
+/// `v-on` modifiers which are passed as the listener options
+const EVENT_OPTION_MODIFIERS: &[&str] = &["once", "capture", "passive"];
+
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+impl VaporGenerator<'_> {
+    /// Generates the HTML of a plain element and the operations on its dynamic parts.
+    /// `node` is the variable holding the element.
+    pub(super) fn generate_element(
+        &mut self,
+        element_node: &ElementNode,
+        node: &FervidAtom,
+        html: &mut String,
+        ops: &mut Vec<Stmt>,
+    ) {
+        let tag_name = &element_node.starting_tag.tag_name;
+        let directives = element_node.starting_tag.directives.as_deref();
+        let was_in_once = self.enter_once(element_node);
+
+        html.push('<');
+        html.push_str(tag_name);
+        let mut effects = Vec::new();
+        self.generate_element_attributes(element_node, node, html, ops, &mut effects);
+        html.push('>');
+
+        let v_html = directives.and_then(|directives| directives.v_html.as_ref());
+        let v_text = directives.and_then(|directives| directives.v_text.as_ref());
+
+        if let Some(v_html) = v_html {
+            let set_html = self.call(
+                VueImports::SetHtml,
+                vec![ident(node.to_owned()), v_html.to_owned()],
+            );
+            effects.push(expr_stmt(set_html));
+        } else if let Some(v_text) = v_text {
+            // `<div v-text="msg">` -> `<div> </div>`
+            html.push(' ');
+            let text_node = self.next_node("x");
+            let txt = self.call(VueImports::Txt, vec![ident(node.to_owned())]);
+            ops.push(generate_const_decl(text_node.to_owned(), txt));
+
+            let text_value = self.call(VueImports::ToDisplayString, vec![v_text.to_owned()]);
+            let set_text = self.call(VueImports::SetText, vec![ident(text_node), text_value]);
+            effects.push(expr_stmt(set_text));
+        }
+
+        if !VOID_TAGS.contains(&tag_name.as_ref()) {
+            if v_html.is_none() && v_text.is_none() {
+                self.generate_element_children(element_node, node, html, ops);
+            }
+
+            html.push_str("</");
+            html.push_str(tag_name);
+            html.push('>');
+        }
+
+        self.generate_custom_directives(element_node, node, ops);
+
+        if !effects.is_empty() {
+            self.push_effect(effects, ops);
+        }
+        self.in_once = was_in_once;
+    }
+
+    fn generate_element_children(
+        &mut self,
+        element_node: &ElementNode,
+        node: &FervidAtom,
+        html: &mut String,
+        ops: &mut Vec<Stmt>,
+    ) {
+        let children = group_children(element_node.children.iter());
+        let children_len = children.len();
+
+        for (idx, child) in children.iter().enumerate() {
+            match child {
+                TemplateChild::Text(parts) if is_static_text(parts) => {
+                    push_escaped(&static_text(parts), html, false);
+                }
+
+                // `<div>{{ msg }}</div>` -> `<div> </div>`
+                TemplateChild::Text(parts) => {
+                    html.push(' ');
+                    let text_node = self.next_node("x");
+                    let path = if children_len == 1 {
+                        self.call(VueImports::Txt, vec![ident(node.to_owned())])
+                    } else {
+                        self.child_path(node, idx)
+                    };
+                    ops.push(generate_const_decl(text_node.to_owned(), path));
+
+                    let text_value = self.generate_text_value(parts);
+                    let set_text =
+                        self.call(VueImports::SetText, vec![ident(text_node), text_value]);
+                    self.push_effect(vec![expr_stmt(set_text)], ops);
+                }
+
+                TemplateChild::Element(child_element) if is_plain_element(child_element) => {
+                    if is_static_element(child_element) {
+                        push_static_html(child_element, html);
+                        continue;
+                    }
+
+                    let child_node = self.next_node("n");
+                    let path = self.child_path(node, idx);
+                    ops.push(generate_const_decl(child_node.to_owned(), path));
+                    self.generate_element(child_element, &child_node, html, ops);
+                }
+
+                // Components, slots, `v-if` and `v-for` are inserted before the `<!>` anchor
+                TemplateChild::Element(_) | TemplateChild::Conditional(_) => {
+                    html.push_str("<!>");
+                    let anchor = self.next_node("p");
+                    let path = self.child_path(node, idx);
+                    ops.push(generate_const_decl(anchor.to_owned(), path));
+
+                    let inserted = match child {
+                        TemplateChild::Element(child_element) => {
+                            self.generate_root_element(child_element, false, false, ops)
+                        }
+                        TemplateChild::Conditional(conditional_seq) => {
+                            self.generate_if(conditional_seq, ops)
+                        }
+                        TemplateChild::Text(_) => unreachable!(),
+                    };
+
+                    let insert = self.call(
+                        VueImports::Insert,
+                        vec![ident(inserted), ident(node.to_owned()), ident(anchor)],
+                    );
+                    ops.push(expr_stmt(insert));
+                }
+            }
+        }
+    }
+
+    /// `_child(n0)`, `_next(_child(n0))` or `_nthChild(n0, 2)`
+    fn child_path(&mut self, parent: &FervidAtom, idx: usize) -> Box<Expr> {
+        match idx {
+            0 => self.call(VueImports::Child, vec![ident(parent.to_owned())]),
+            1 => {
+                let first_child = self.call(VueImports::Child, vec![ident(parent.to_owned())]);
+                self.call(VueImports::Next, vec![first_child])
+            }
+            _ => self.call(
+                VueImports::NthChild,
+                vec![
+                    ident(parent.to_owned()),
+                    Box::new(Expr::Lit(Lit::Num(idx.into()))),
+                ],
+            ),
+        }
+    }
+
+    /// Static attributes are written to the HTML,
+    /// bindings become effects and `v-on`, `v-show` and `v-model` are applied once
+    fn generate_element_attributes(
+        &mut self,
+        element_node: &ElementNode,
+        node: &FervidAtom,
+        html: &mut String,
+        ops: &mut Vec<Stmt>,
+        effects: &mut Vec<Stmt>,
+    ) {
+        let attributes = &element_node.starting_tag.attributes;
+
+        let static_value = |attr_name: &str| {
+            attributes.iter().find_map(|attr| match attr {
+                AttributeOrBinding::RegularAttribute { name, value, .. } if name == attr_name => {
+                    Some(value)
+                }
+                _ => None,
+            })
+        };
+        let is_bound = |attr_name: &str| {
+            attributes.iter().any(|attr| {
+                matches!(attr, AttributeOrBinding::VBind(VBindDirective {
+                    argument: Some(StrOrExpr::Str(argument)),
+                    ..
+                }) if argument == attr_name)
+            })
+        };
+        let is_class_bound = is_bound("class");
+        let is_style_bound = is_bound("style");
+
+        let mut dynamic_props = Vec::new();
+
+        for attr in attributes.iter() {
+            match attr {
+                AttributeOrBinding::RegularAttribute { name, value, .. } => match name.as_ref() {
+                    "ref" | "ref_key" => {}
+                    // Merged with the binding
+                    "class" if is_class_bound => {}
+                    "style" if is_style_bound => {}
+                    _ => push_html_attribute(name, value, html),
+                },
+
+                AttributeOrBinding::VBind(v_bind) => match v_bind.argument {
+                    Some(StrOrExpr::Str(ref argument)) => {
+                        let (vue_import, args) = match argument.as_ref() {
+                            "key" | "ref" | "ref_for" => continue,
+
+                            // `class="static" :class="dynamic"` -> `_setClass(n0, ["static", dynamic])`
+                            "class" | "style" => {
+                                let value = match static_value(argument) {
+                                    Some(static_value) => Box::new(Expr::Array(ArrayLit {
+                                        span: DUMMY_SP,
+                                        elems: vec![
+                                            Some(ExprOrSpread {
+                                                spread: None,
+                                                expr: str_lit(static_value.to_owned()),
+                                            }),
+                                            Some(ExprOrSpread {
+                                                spread: None,
+                                                expr: v_bind.value.to_owned(),
+                                            }),
+                                        ],
+                                    })),
+                                    None => v_bind.value.to_owned(),
+                                };
+
+                                let vue_import = if argument == "class" {
+                                    VueImports::SetClass
+                                } else {
+                                    VueImports::SetStyle
+                                };
+                                (vue_import, vec![ident(node.to_owned()), value])
+                            }
+
+                            _ => {
+                                let vue_import = if v_bind.is_prop {
+                                    VueImports::SetDOMProp
+                                } else if v_bind.is_attr {
+                                    VueImports::SetAttr
+                                } else {
+                                    VueImports::SetProp
+                                };
+                                (
+                                    vue_import,
+                                    vec![
+                                        ident(node.to_owned()),
+                                        str_lit(argument.to_owned()),
+                                        v_bind.value.to_owned(),
+                                    ],
+                                )
+                            }
+                        };
+
+                        let set_value = self.call(vue_import, args);
+                        effects.push(expr_stmt(set_value));
+                    }
+
+                    // `:[key]="value"` -> `{ [key]: value }`
+                    Some(StrOrExpr::Expr(ref key)) => {
+                        dynamic_props.push(Box::new(Expr::Object(ObjectLit {
+                            span: DUMMY_SP,
+                            props: vec![PropOrSpread::Prop(Box::new(Prop::KeyValue(
+                                KeyValueProp {
+                                    key: PropName::Computed(ComputedPropName {
+                                        span: DUMMY_SP,
+                                        expr: key.to_owned(),
+                                    }),
+                                    value: v_bind.value.to_owned(),
+                                },
+                            )))],
+                        })));
+                    }
+
+                    // `v-bind="obj"`
+                    None => dynamic_props.push(v_bind.value.to_owned()),
+                },
+
+                AttributeOrBinding::VOn(v_on) => {
+                    self.generate_element_event(v_on, node, ops, effects)
+                }
+            }
+        }
+
+        // `_setDynamicProps(n0, [obj, { [key]: value }])`
+        if !dynamic_props.is_empty() {
+            let props = Box::new(Expr::Array(ArrayLit {
+                span: DUMMY_SP,
+                elems: dynamic_props
+                    .into_iter()
+                    .map(|expr| Some(ExprOrSpread { spread: None, expr }))
+                    .collect(),
+            }));
+            let set_dynamic_props = self.call(
+                VueImports::SetDynamicProps,
+                vec![ident(node.to_owned()), props],
+            );
+            effects.push(expr_stmt(set_dynamic_props));
+        }
+
+        self.generate_template_ref(element_node, node, ops);
+
+        let Some(ref directives) = element_node.starting_tag.directives else {
+            return;
+        };
+
+        // `_applyVShow(n0, () => (visible))`
+        if let Some(ref v_show) = directives.v_show {
+            let apply_v_show = self.call(
+                VueImports::ApplyVShow,
+                vec![ident(node.to_owned()), getter(v_show.to_owned())],
+            );
+            ops.push(expr_stmt(apply_v_show));
+        }
+
+        for v_model in directives.v_model.iter() {
+            if let Some(apply_model) = self.generate_element_model(element_node, v_model, node) {
+                ops.push(apply_model);
+            }
+        }
+    }
+
+    /// `n0.$evtclick = handler` for the delegated events,
+    /// `_on(n0, "scroll", handler, { passive: true })` otherwise
+    fn generate_element_event(
+        &mut self,
+        v_on: &VOnDirective,
+        node: &FervidAtom,
+        ops: &mut Vec<Stmt>,
+        effects: &mut Vec<Stmt>,
+    ) {
+        let Some(ref event) = v_on.event else {
+            // `v-on="handlers"`
+            if let Some(ref handlers) = v_on.handler {
+                let set_dynamic_events = self.call(
+                    VueImports::SetDynamicEvents,
+                    vec![ident(node.to_owned()), handlers.to_owned()],
+                );
+                effects.push(expr_stmt(set_dynamic_events));
+            }
+            return;
+        };
+
+        let handler = v_on
+            .handler
+            .to_owned()
+            .unwrap_or_else(|| arrow_block(vec![], vec![]));
+
+        let (option_modifiers, modifiers): (Vec<&FervidAtom>, Vec<&FervidAtom>) = v_on
+            .modifiers
+            .iter()
+            .partition(|modifier| EVENT_OPTION_MODIFIERS.contains(&modifier.as_ref()));
+
+        // `_withModifiers(handler, ["stop"])`
+        let handler = if modifiers.is_empty() {
+            handler
+        } else {
+            let modifiers = Box::new(Expr::Array(ArrayLit {
+                span: DUMMY_SP,
+                elems: modifiers
+                    .into_iter()
+                    .map(|modifier| {
+                        Some(ExprOrSpread {
+                            spread: None,
+                            expr: str_lit(modifier.to_owned()),
+                        })
+                    })
+                    .collect(),
+            }));
+            self.call(VueImports::WithModifiers, vec![handler, modifiers])
+        };
+
+        let mut options: Vec<&str> = option_modifiers.iter().map(|m| m.as_ref()).collect();
+
+        let event_name = match event {
+            StrOrExpr::Str(handler_key) => {
+                let event_name = to_dom_event_name(handler_key);
+
+                if options.is_empty() && DELEGATED_EVENTS.contains(&event_name.as_str()) {
+                    let event_name = FervidAtom::from(event_name);
+                    if !self.delegated_events.contains(&event_name) {
+                        self.delegated_events.push(event_name.to_owned());
+                    }
+
+                    ops.push(expr_stmt(Box::new(Expr::Assign(AssignExpr {
+                        span: DUMMY_SP,
+                        op: AssignOp::Assign,
+                        left: AssignTarget::Simple(SimpleAssignTarget::Member(MemberExpr {
+                            span: DUMMY_SP,
+                            obj: ident(node.to_owned()),
+                            prop: MemberProp::Ident(IdentName {
+                                span: DUMMY_SP,
+                                sym: FervidAtom::from(format!("$evt{event_name}")),
+                            }),
+                        })),
+                        right: handler,
+                    }))));
+                    return;
+                }
+
+                str_lit(event_name)
+            }
+
+            // The name is changing, thus the listener is re-attached in an effect
+            StrOrExpr::Expr(event) => {
+                if !self.in_once {
+                    options.push("effect");
+                }
+                unwrap_to_handler_key(event)
+            }
+        };
+
+        let mut args = vec![ident(node.to_owned()), event_name, handler];
+        if !options.is_empty() {
+            args.push(Box::new(Expr::Object(ObjectLit {
+                span: DUMMY_SP,
+                props: options
+                    .into_iter()
+                    .map(|option| {
+                        PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                            key: PropName::Ident(IdentName {
+                                span: DUMMY_SP,
+                                sym: FervidAtom::from(option),
+                            }),
+                            value: Box::new(Expr::Lit(Lit::Bool(Bool {
+                                span: DUMMY_SP,
+                                value: true,
+                            }))),
+                        })))
+                    })
+                    .collect(),
+            })));
+        }
+
+        let on = self.call(VueImports::On, args);
+        if matches!(event, StrOrExpr::Expr(_)) {
+            effects.push(expr_stmt(on));
+        } else {
+            ops.push(expr_stmt(on));
+        }
+    }
+
+    /// `_applyTextModel(n0, () => (msg.value), $event => (msg.value = $event), { trim: true })`
+    fn generate_element_model(
+        &mut self,
+        element_node: &ElementNode,
+        v_model: &VModelDirective,
+        node: &FervidAtom,
+    ) -> Option<Stmt> {
+        let update_handler = v_model.update_handler.to_owned()?;
+
+        let vue_import = match element_node.starting_tag.tag_name.as_ref() {
+            "input" => element_node
+                .starting_tag
+                .attributes
+                .iter()
+                .find_map(|attr| match attr {
+                    AttributeOrBinding::RegularAttribute { name, value, .. } if name == "type" => {
+                        Some(match value.as_ref() {
+                            "checkbox" => VueImports::ApplyCheckboxModel,
+                            "radio" => VueImports::ApplyRadioModel,
+                            _ => VueImports::ApplyTextModel,
+                        })
+                    }
+                    AttributeOrBinding::VBind(VBindDirective {
+                        argument: Some(StrOrExpr::Str(argument)),
+                        ..
+                    }) if argument == "type" => Some(VueImports::ApplyDynamicModel),
+                    _ => None,
+                })
+                .unwrap_or(VueImports::ApplyTextModel),
+            "select" => VueImports::ApplySelectModel,
+            _ => VueImports::ApplyTextModel,
+        };
+
+        let mut args = vec![
+            ident(node.to_owned()),
+            getter(v_model.value.to_owned()),
+            update_handler,
+        ];
+        if !v_model.modifiers.is_empty() {
+            args.push(Box::new(Expr::Object(ObjectLit {
+                span: DUMMY_SP,
+                props: v_model
+                    .modifiers
+                    .iter()
+                    .map(|modifier| {
+                        PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                            key: PropName::Ident(IdentName {
+                                span: DUMMY_SP,
+                                sym: modifier.to_owned(),
+                            }),
+                            value: Box::new(Expr::Lit(Lit::Bool(Bool {
+                                span: DUMMY_SP,
+                                value: true,
+                            }))),
+                        })))
+                    })
+                    .collect(),
+            })));
+        }
+
+        Some(expr_stmt(self.call(vue_import, args)))
+    }
+}
+
+/// Elements which are written to the template HTML
+fn is_plain_element(element_node: &ElementNode) -> bool {
+    matches!(element_node.kind, fervid_core::ElementKind::Element)
+        && !has_v_for(element_node)
+        && !is_template(element_node)
+}
+
+/// Elements without any dynamic parts do not need a variable
+fn is_static_element(element_node: &ElementNode) -> bool {
+    is_plain_element(element_node)
+        && element_node.starting_tag.attributes.iter().all(|attr| {
+            matches!(attr, AttributeOrBinding::RegularAttribute { name, .. } if name != "ref")
+        })
+        && element_node
+            .starting_tag
+            .directives
+            .as_ref()
+            .is_none_or(|directives| {
+                directives.v_show.is_none()
+                    && directives.v_model.is_empty()
+                    && directives.v_html.is_none()
+                    && directives.v_text.is_none()
+            })
+        && element_node.children.iter().all(is_static_node)
+}
+
+fn is_static_node(node: &Node) -> bool {
+    match node {
+        Node::Text(..) | Node::Comment(..) => true,
+        Node::Element(element_node) if super::block::is_fragment_template(element_node) => {
+            element_node.children.iter().all(is_static_node)
+        }
+        Node::Element(element_node) => is_static_element(element_node),
+        Node::Interpolation(_) | Node::ConditionalSeq(_) => false,
+    }
+}
+
+fn push_static_html(element_node: &ElementNode, html: &mut String) {
+    let tag_name = &element_node.starting_tag.tag_name;
+
+    html.push('<');
+    html.push_str(tag_name);
+    for attr in element_node.starting_tag.attributes.iter() {
+        if let AttributeOrBinding::RegularAttribute { name, value, .. } = attr {
+            if name != "ref_key" {
+                push_html_attribute(name, value, html);
+            }
+        }
+    }
+    html.push('>');
+
+    if VOID_TAGS.contains(&tag_name.as_ref()) {
+        return;
+    }
+
+    for child in group_children(element_node.children.iter()) {
+        match child {
+            TemplateChild::Text(parts) => push_escaped(&static_text(&parts), html, false),
+            TemplateChild::Element(child_element) => push_static_html(child_element, html),
+            TemplateChild::Conditional(_) => {}
+        }
+    }
+
+    html.push_str("</");
+    html.push_str(tag_name);
+    html.push('>');
+}
+
+fn push_html_attribute(name: &str, value: &str, html: &mut String) {
+    html.push(' ');
+    html.push_str(name);
+    if !value.is_empty() {
+        html.push_str("=\"");
+        push_escaped(value, html, true);
+        html.push('"');
+    }
+}
+
+/// Reverts the handler key made by the transform, e.g. `onClick` -> `click`,
+/// `onMyEvent` -> `my-event` and `on:MyEvent` -> `MyEvent`
+fn to_dom_event_name(handler_key: &str) -> String {
+    let Some(event) = handler_key.strip_prefix("on") else {
+        return handler_key.to_owned();
+    };
+
+    if let Some(event) = event.strip_prefix(':') {
+        return event.to_owned();
+    }
+
+    let mut out = String::with_capacity(event.len() + 2);
+    for (idx, ch) in event.chars().enumerate() {
+        if ch.is_ascii_uppercase() {
+            if idx != 0 {
+                out.push('-');
+            }
+            out.push(ch.to_ascii_lowercase());
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// `_toHandlerKey(event)` -> `event`, the listener needs the plain event name
+fn unwrap_to_handler_key(event: &Expr) -> Box<Expr> {
+    if let Expr::Call(call) = event {
+        if let (Callee::Expr(callee), [arg]) = (&call.callee, call.args.as_slice()) {
+            if matches!(callee.as_ref(), Expr::Ident(ident) if ident.sym == VueImports::ToHandlerKey.as_atom())
+            {
+                return arg.expr.to_owned();
+            }
+        }
+    }
+
+    Box::new(event.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::{
+        AttributeOrBinding, Node, StrOrExpr, VCustomDirective, VModelDirective, VOnDirective,
+        VueDirectives,
+    };
+    use swc_core::common::DUMMY_SP;
+
+    use super::to_dom_event_name;
+    use crate::{
+        test_utils::{js, regular_attribute, v_bind_attribute},
+        vapor::tests::{element, interpolation, test_out},
+    };
+
+    #[test]
+    fn it_generates_v_once_without_effects() {
+        // <div v-once :id="id" @[name]="onDynamic">{{ msg }}</div>
+        let mut div = element(
+            "div",
+            vec![
+                v_bind_attribute("id", "id"),
+                AttributeOrBinding::VOn(VOnDirective {
+                    event: Some(StrOrExpr::Expr(js("_toHandlerKey(name)"))),
+                    handler: Some(js("onDynamic")),
+                    modifiers: vec![],
+                    span: DUMMY_SP,
+                }),
+            ],
+            vec![interpolation("msg")],
+        );
+        div.starting_tag.directives = Some(Box::new(VueDirectives {
+            v_once: Some(()),
+            ..Default::default()
+        }));
+
+        test_out(
+            vec![Node::Element(div)],
+            r#"const t0=_template("<div> </div>",true);"#,
+            r#"const n0=t0();const x1=_txt(n0);_setText(x1,_toDisplayString(msg));_setProp(n0,"id",id);_on(n0,name,onDynamic);return n0;"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_custom_directives() {
+        // <input v-focus v-tooltip:top.lazy="text">
+        let mut input = element("input", vec![], vec![]);
+        input.starting_tag.directives = Some(Box::new(VueDirectives {
+            custom: vec![
+                VCustomDirective {
+                    name: "focus".into(),
+                    argument: None,
+                    modifiers: vec![],
+                    value: None,
+                },
+                VCustomDirective {
+                    name: "tooltip".into(),
+                    argument: Some("top".into()),
+                    modifiers: vec!["lazy".into()],
+                    value: Some(js("text")),
+                },
+            ],
+            ..Default::default()
+        }));
+
+        test_out(
+            vec![Node::Element(input)],
+            r#"const t0=_template("<input>",true);"#,
+            r#"const _directive_focus=_resolveDirective("focus");const _directive_tooltip=_resolveDirective("tooltip");const n0=t0();_withVaporDirectives(n0,[[_directive_focus],[_directive_tooltip,()=>(text),"top",{lazy:true}]]);return n0;"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_checkbox_v_model() {
+        // <input type="checkbox" v-model.lazy="checked">
+        let mut input = element("input", vec![regular_attribute("type", "checkbox")], vec![]);
+        input.starting_tag.directives = Some(Box::new(VueDirectives {
+            v_model: vec![VModelDirective {
+                argument: None,
+                value: js("checked.value"),
+                update_handler: Some(js("$event => (checked.value = $event)")),
+                modifiers: vec!["lazy".into()],
+                span: DUMMY_SP,
+            }],
+            ..Default::default()
+        }));
+
+        test_out(
+            vec![Node::Element(input)],
+            r#"const t0=_template('<input type="checkbox">',true);"#,
+            r#"const n0=t0();_applyCheckboxModel(n0,()=>(checked.value),$event=>(checked.value=$event),{lazy:true});return n0;"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_v_show_v_html_and_v_text() {
+        // <div v-show="visible" v-html="raw"></div><p v-text="msg"></p>
+        let mut div = element("div", vec![], vec![]);
+        div.starting_tag.directives = Some(Box::new(VueDirectives {
+            v_show: Some(js("visible")),
+            v_html: Some(js("raw")),
+            ..Default::default()
+        }));
+        let mut p = element("p", vec![], vec![]);
+        p.starting_tag.directives = Some(Box::new(VueDirectives {
+            v_text: Some(js("msg")),
+            ..Default::default()
+        }));
+
+        test_out(
+            vec![Node::Element(div), Node::Element(p)],
+            r#"const t0=_template("<div></div>",true);const t1=_template("<p> </p>",true);"#,
+            r#"const n0=t0();_applyVShow(n0,()=>(visible));_renderEffect(()=>_setHtml(n0,raw));const n1=t1();const x2=_txt(n1);_renderEffect(()=>_setText(x2,_toDisplayString(msg)));return[n0,n1];"#,
+        );
+    }
+
+    #[test]
+    fn it_converts_handler_keys_to_dom_events() {
+        assert_eq!(to_dom_event_name("onClick"), "click");
+        assert_eq!(to_dom_event_name("onMyEvent"), "my-event");
+        assert_eq!(to_dom_event_name("on:MyEvent"), "MyEvent");
+        assert_eq!(to_dom_event_name("click"), "click");
+    }
+}
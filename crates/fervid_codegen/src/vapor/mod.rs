@@ -0,0 +1,571 @@
+//! Code generation for the Vapor mode, i.e. the components with `<script setup vapor>`.
+//!
+//! Instead of the virtual DOM, the static parts of the template are hoisted as HTML strings
+//! (`const t0 = _template("<div> </div>", true)`) which are cloned during the render.
+//! The dynamic parts are applied directly to the cloned DOM nodes,
+//! e.g. `_renderEffect(() => _setText(x0, _toDisplayString(msg.value)))`.
+//! The render statements are inlined to `setup`, which returns the rendered block.
+
+use fervid_core::{ElementNode, FervidAtom, IntoIdent, SfcTemplateBlock, VueImports, PURE_SP};
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::ast::{
+        ArrayLit, ArrowExpr, BindingIdent, BlockStmt, BlockStmtOrExpr, CallExpr, Callee, Expr,
+        ExprOrSpread, ExprStmt, Function, Lit, Module, ModuleItem, ObjectLit, ParenExpr, Pat,
+        ReturnStmt, Stmt, Str,
+    },
+};
+
+use crate::{
+    control_flow::{add_setup_method, generate_const_decl, generate_default_export, push_to_setup},
+    CodegenContext,
+};
+
+mod block;
+mod element;
+mod scope;
+
+/// The template compiled for the Vapor mode
+pub struct VaporRender {
+    /// Module-level statements, i.e. the templates and the event delegation
+    pub hoisted: Vec<Stmt>,
+    /// Render statements to append to `setup`, the last one returns the root block
+    pub body: Vec<Stmt>,
+}
+
+impl CodegenContext {
+    /// Generates the Vapor render of the template transformed in the inline mode
+    pub fn generate_vapor_template(
+        &mut self,
+        sfc_template: &SfcTemplateBlock,
+    ) -> Option<VaporRender> {
+        if sfc_template.roots.is_empty() {
+            return None;
+        }
+
+        let mut generator = VaporGenerator::new(self);
+        let root_block = generator.generate_block(sfc_template.roots.iter(), true);
+        Some(generator.finish(root_block))
+    }
+
+    /// Generates the module of a Vapor component.
+    /// Unlike [`CodegenContext::generate_module`], the render is always inlined to `setup`
+    /// and the exported object is wrapped in `defineVaporComponent`.
+    pub fn generate_vapor_module(
+        &mut self,
+        vapor_render: Option<VaporRender>,
+        mut script: Module,
+        mut sfc_export_obj: ObjectLit,
+        mut synthetic_setup_fn: Option<Box<Function>>,
+        gen_default_as: Option<&str>,
+    ) -> Module {
+        let mut hoisted = Vec::new();
+        if let Some(vapor_render) = vapor_render {
+            hoisted = vapor_render.hoisted;
+            push_to_setup(&mut synthetic_setup_fn, vapor_render.body);
+        }
+
+        add_setup_method(&mut sfc_export_obj, synthetic_setup_fn);
+
+        // `/*#__PURE__*/_defineVaporComponent({ /*...*/ })`
+        let define_vapor_component =
+            self.get_and_add_import_ident(VueImports::DefineVaporComponent);
+        let mut sfc_exported = call_expr(
+            define_vapor_component,
+            vec![Box::new(Expr::Object(sfc_export_obj))],
+        );
+        if let Expr::Call(ref mut call) = *sfc_exported {
+            call.span = PURE_SP;
+        }
+
+        let default_export = generate_default_export(sfc_exported, gen_default_as);
+
+        script.body.extend(self.generate_vue_import_decl());
+        script
+            .body
+            .extend(hoisted.into_iter().map(ModuleItem::Stmt));
+        script.body.push(default_export);
+
+        script
+    }
+}
+
+/// Statements creating a block and the nodes of the block
+#[derive(Default)]
+struct Block {
+    stmts: Vec<Stmt>,
+    nodes: Vec<FervidAtom>,
+}
+
+impl Block {
+    /// `return n0` or `return [n0, n1]`
+    fn into_stmts(mut self) -> Vec<Stmt> {
+        let returned = if self.nodes.len() == 1 {
+            ident(self.nodes.remove(0))
+        } else {
+            Box::new(Expr::Array(ArrayLit {
+                span: DUMMY_SP,
+                elems: self
+                    .nodes
+                    .into_iter()
+                    .map(|node| {
+                        Some(ExprOrSpread {
+                            spread: None,
+                            expr: ident(node),
+                        })
+                    })
+                    .collect(),
+            }))
+        };
+
+        self.stmts.push(Stmt::Return(ReturnStmt {
+            span: DUMMY_SP,
+            arg: Some(returned),
+        }));
+        self.stmts
+    }
+}
+
+/// Holds the state of a single template generation
+struct VaporGenerator<'c> {
+    ctx: &'c mut CodegenContext,
+    /// HTML of the templates and whether they are roots of the component
+    templates: Vec<(String, bool)>,
+    /// Events handled by the delegation, e.g. `click`
+    delegated_events: Vec<FervidAtom>,
+    next_node_idx: u32,
+    next_for_idx: u32,
+    next_slot_idx: u32,
+    uses_template_refs: bool,
+    /// Inside of a `v-once` element the dynamic parts are set once, without effects
+    in_once: bool,
+}
+
+impl<'c> VaporGenerator<'c> {
+    fn new(ctx: &'c mut CodegenContext) -> Self {
+        VaporGenerator {
+            ctx,
+            templates: Vec::new(),
+            delegated_events: Vec::new(),
+            next_node_idx: 0,
+            next_for_idx: 0,
+            next_slot_idx: 0,
+            uses_template_refs: false,
+            in_once: false,
+        }
+    }
+
+    fn finish(mut self, root_block: Block) -> VaporRender {
+        let mut body = Vec::new();
+
+        // `const _component_foo = _resolveComponent("foo")`
+        let component_resolves = self.ctx.generate_component_resolves();
+        for resolve in component_resolves {
+            let (Pat::Ident(BindingIdent { id, .. }), Some(init)) = (resolve.name, resolve.init)
+            else {
+                continue;
+            };
+            body.push(generate_const_decl(id.sym, init));
+        }
+
+        // `const _directive_focus = _resolveDirective("focus")`
+        let directive_resolves = self.ctx.generate_directive_resolves();
+        for resolve in directive_resolves {
+            let (Pat::Ident(BindingIdent { id, .. }), Some(init)) = (resolve.name, resolve.init)
+            else {
+                continue;
+            };
+            body.push(generate_const_decl(id.sym, init));
+        }
+
+        // `const _setTemplateRef = _createTemplateRefSetter()`
+        if self.uses_template_refs {
+            let create_setter = self.call(VueImports::CreateTemplateRefSetter, vec![]);
+            body.push(generate_const_decl(
+                FervidAtom::from("_setTemplateRef"),
+                create_setter,
+            ));
+        }
+
+        body.extend(root_block.into_stmts());
+
+        // `const t0 = _template("<div></div>", true)`
+        let mut hoisted = Vec::with_capacity(self.templates.len() + 1);
+        let templates = std::mem::take(&mut self.templates);
+        for (idx, (html, is_root)) in templates.into_iter().enumerate() {
+            let mut args = vec![str_lit(html)];
+            if is_root {
+                args.push(Box::new(Expr::Lit(Lit::Bool(true.into()))));
+            }
+
+            let template = self.call(VueImports::Template, args);
+            hoisted.push(generate_const_decl(template_name(idx), template));
+        }
+
+        // `_delegateEvents("click", "input")`
+        if !self.delegated_events.is_empty() {
+            let events = std::mem::take(&mut self.delegated_events);
+            let args = events.into_iter().map(str_lit);
+            let delegate_events = self.call(VueImports::DelegateEvents, args);
+            hoisted.push(expr_stmt(delegate_events));
+        }
+
+        VaporRender { hoisted, body }
+    }
+
+    /// Registers a template, identical templates are reused
+    fn add_template(&mut self, html: String, is_root: bool) -> FervidAtom {
+        let existing = self
+            .templates
+            .iter()
+            .position(|(existing_html, existing_is_root)| {
+                *existing_html == html && *existing_is_root == is_root
+            });
+
+        let idx = existing.unwrap_or_else(|| {
+            self.templates.push((html, is_root));
+            self.templates.len() - 1
+        });
+
+        template_name(idx)
+    }
+
+    /// Marks the content of a `v-once` element, returns the previous state to restore
+    fn enter_once(&mut self, element_node: &ElementNode) -> bool {
+        let was_in_once = self.in_once;
+        self.in_once |= element_node
+            .starting_tag
+            .directives
+            .as_ref()
+            .is_some_and(|directives| directives.v_once.is_some());
+        was_in_once
+    }
+
+    /// Allocates a variable name for a node, e.g. `n0` or `x1`
+    fn next_node(&mut self, prefix: &str) -> FervidAtom {
+        let idx = self.next_node_idx;
+        self.next_node_idx += 1;
+        FervidAtom::from(format!("{prefix}{idx}"))
+    }
+
+    /// `_helper(args)` where the helper is imported from Vue
+    fn call(
+        &mut self,
+        vue_import: VueImports,
+        args: impl IntoIterator<Item = Box<Expr>>,
+    ) -> Box<Expr> {
+        let callee = self.ctx.get_and_add_import_ident(vue_import);
+        call_expr(callee, args)
+    }
+}
+
+fn template_name(idx: usize) -> FervidAtom {
+    FervidAtom::from(format!("t{idx}"))
+}
+
+fn ident(sym: FervidAtom) -> Box<Expr> {
+    Box::new(Expr::Ident(sym.into_ident()))
+}
+
+fn str_lit(value: impl Into<FervidAtom>) -> Box<Expr> {
+    Box::new(Expr::Lit(Lit::Str(Str {
+        span: DUMMY_SP,
+        value: value.into(),
+        raw: None,
+    })))
+}
+
+fn call_expr(callee: FervidAtom, args: impl IntoIterator<Item = Box<Expr>>) -> Box<Expr> {
+    Box::new(Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        callee: Callee::Expr(ident(callee)),
+        args: args
+            .into_iter()
+            .map(|expr| ExprOrSpread { spread: None, expr })
+            .collect(),
+        type_args: None,
+    }))
+}
+
+fn expr_stmt(expr: Box<Expr>) -> Stmt {
+    Stmt::Expr(ExprStmt {
+        span: DUMMY_SP,
+        expr,
+    })
+}
+
+/// `(params) => body`
+fn arrow(params: Vec<FervidAtom>, body: BlockStmtOrExpr) -> Box<Expr> {
+    Box::new(Expr::Arrow(ArrowExpr {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        params: params
+            .into_iter()
+            .map(|param| {
+                Pat::Ident(BindingIdent {
+                    id: param.into_ident(),
+                    type_ann: None,
+                })
+            })
+            .collect(),
+        body: Box::new(body),
+        is_async: false,
+        is_generator: false,
+        type_params: None,
+        return_type: None,
+    }))
+}
+
+/// `(params) => { stmts }`
+fn arrow_block(params: Vec<FervidAtom>, stmts: Vec<Stmt>) -> Box<Expr> {
+    arrow(
+        params,
+        BlockStmtOrExpr::BlockStmt(BlockStmt {
+            span: DUMMY_SP,
+            ctxt: Default::default(),
+            stmts,
+        }),
+    )
+}
+
+/// `() => (expr)`
+fn getter(expr: Box<Expr>) -> Box<Expr> {
+    arrow(
+        vec![],
+        BlockStmtOrExpr::Expr(Box::new(Expr::Paren(ParenExpr {
+            span: DUMMY_SP,
+            expr,
+        }))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::{
+        AttributeOrBinding, ComponentBinding, Conditional, ConditionalNodeSequence, ElementKind,
+        ElementNode, Interpolation, Node, StartingTag, StrOrExpr, VForDirective, VOnDirective,
+        VSlotDirective, VueDirectives,
+    };
+
+    use super::*;
+    use crate::test_utils::{js, regular_attribute, to_str, v_bind_attribute, v_on_attribute};
+
+    #[test]
+    fn it_generates_element_with_text_and_bindings() {
+        // <div class="a" :class="b" @click="onClick">Hello {{ msg }}!</div>
+        let div = element(
+            "div",
+            vec![
+                regular_attribute("class", "a"),
+                v_bind_attribute("class", "b"),
+                v_on_attribute("onClick", "onClick"),
+            ],
+            vec![
+                Node::Text("Hello ".into(), DUMMY_SP),
+                interpolation("msg.value"),
+                Node::Text("!".into(), DUMMY_SP),
+            ],
+        );
+
+        test_out(
+            vec![Node::Element(div)],
+            r#"const t0=_template("<div> </div>",true);_delegateEvents("click");"#,
+            r#"const n0=t0();n0.$evtclick=onClick;const x1=_txt(n0);_renderEffect(()=>_setText(x1,"Hello "+_toDisplayString(msg.value)+"!"));_renderEffect(()=>_setClass(n0,["a",b]));return n0;"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_static_children_and_anchors() {
+        // <div><span>a &amp; b</span><p>{{ msg }}</p><Comp /></div>
+        let div = element(
+            "div",
+            vec![],
+            vec![
+                Node::Element(element(
+                    "span",
+                    vec![],
+                    vec![Node::Text("a & b".into(), DUMMY_SP)],
+                )),
+                Node::Element(element("p", vec![], vec![interpolation("msg")])),
+                Node::Element(component("Comp", vec![], vec![])),
+            ],
+        );
+
+        test_out(
+            vec![Node::Element(div)],
+            r#"const t0=_template("<div><span>a &amp; b</span><p> </p><!></div>",true);"#,
+            r#"const n0=t0();const n1=_next(_child(n0));const x2=_txt(n1);_renderEffect(()=>_setText(x2,_toDisplayString(msg)));const p3=_nthChild(n0,2);const n4=_createComponent(Comp);_insert(n4,n0,p3);return n0;"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_events_with_options() {
+        // <input @scroll.passive="onScroll" @keyup.enter="onEnter" @[name]="onDynamic">
+        let mut input = element(
+            "input",
+            vec![
+                v_on_attribute("onScroll", "onScroll"),
+                v_on_attribute("onKeyup", "onEnter"),
+                AttributeOrBinding::VOn(VOnDirective {
+                    event: Some(StrOrExpr::Expr(js("_toHandlerKey(name)"))),
+                    handler: Some(js("onDynamic")),
+                    modifiers: vec![],
+                    span: DUMMY_SP,
+                }),
+            ],
+            vec![],
+        );
+        let AttributeOrBinding::VOn(ref mut scroll) = input.starting_tag.attributes[0] else {
+            unreachable!()
+        };
+        scroll.modifiers.push("passive".into());
+        let AttributeOrBinding::VOn(ref mut keyup) = input.starting_tag.attributes[1] else {
+            unreachable!()
+        };
+        keyup.modifiers.push("enter".into());
+
+        test_out(
+            vec![Node::Element(input)],
+            r#"const t0=_template("<input>",true);_delegateEvents("keyup");"#,
+            r#"const n0=t0();_on(n0,"scroll",onScroll,{passive:true});n0.$evtkeyup=_withModifiers(onEnter,["enter"]);_renderEffect(()=>_on(n0,name,onDynamic,{effect:true}));return n0;"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_v_if() {
+        // <p v-if="ok">yes</p><p v-else>no</p>
+        let conditional_seq = ConditionalNodeSequence {
+            if_node: Box::new(Conditional {
+                condition: *js("ok.value"),
+                node: element("p", vec![], vec![Node::Text("yes".into(), DUMMY_SP)]),
+            }),
+            else_if_nodes: vec![],
+            else_node: Some(Box::new(element(
+                "p",
+                vec![],
+                vec![Node::Text("no".into(), DUMMY_SP)],
+            ))),
+        };
+
+        test_out(
+            vec![Node::ConditionalSeq(conditional_seq)],
+            r#"const t0=_template("<p>yes</p>");const t1=_template("<p>no</p>");"#,
+            r#"const n0=_createIf(()=>(ok.value),()=>{const n1=t0();return n1;},()=>{const n2=t1();return n2;});return n0;"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_v_for() {
+        // <li v-for="({ id }, index) in list" :key="id">{{ index }}</li>
+        let mut li = element(
+            "li",
+            vec![v_bind_attribute("key", "id")],
+            vec![interpolation("index")],
+        );
+        li.starting_tag.directives = Some(Box::new(VueDirectives {
+            v_for: Some(VForDirective {
+                iterable: js("list.value"),
+                itervar: js("({ id }, index)"),
+                patch_flags: Default::default(),
+                span: DUMMY_SP,
+            }),
+            ..Default::default()
+        }));
+
+        test_out(
+            vec![Node::Element(li)],
+            r#"const t0=_template("<li> </li>");"#,
+            r#"const n0=_createFor(()=>(list.value),(_for_item0,_for_key0)=>{const n1=t0();const x2=_txt(n1);_renderEffect(()=>_setText(x2,_toDisplayString(_for_key0.value)));return n1;},(_for_item0,_for_key0)=>(_for_item0.id));return n0;"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_component_with_slots() {
+        // <Comp :msg="msg"><template #header="{ text }">{{ text }}</template></Comp>
+        let mut header = element("template", vec![], vec![interpolation("text")]);
+        header.starting_tag.directives = Some(Box::new(VueDirectives {
+            v_slot: Some(VSlotDirective {
+                slot_name: Some("header".into()),
+                value: Some(Box::new(Pat::Expr(js("{ text }")))),
+            }),
+            ..Default::default()
+        }));
+        let comp = component(
+            "Comp",
+            vec![v_bind_attribute("msg", "msg")],
+            vec![Node::Element(header)],
+        );
+
+        test_out(
+            vec![Node::Element(comp)],
+            r#"const t0=_template(" ");"#,
+            r#"const n0=_createComponent(Comp,{msg:()=>(msg)},{header:_slotProps0=>{const n1=t0();_renderEffect(()=>_setText(n1,_toDisplayString(_slotProps0.text)));return n1;}},true);return n0;"#,
+        );
+    }
+
+    pub(super) fn test_out(roots: Vec<Node>, expected_hoisted: &str, expected_body: &str) {
+        let mut ctx = CodegenContext::default();
+        ctx.bindings_helper
+            .components
+            .insert("Comp".into(), ComponentBinding::Resolved(js("Comp")));
+
+        let vapor_render = ctx
+            .generate_vapor_template(&SfcTemplateBlock {
+                lang: "html".into(),
+                roots,
+                span: DUMMY_SP,
+            })
+            .expect("template must not be empty");
+
+        assert_eq!(stmts_to_str(vapor_render.hoisted), expected_hoisted);
+        assert_eq!(stmts_to_str(vapor_render.body), expected_body);
+    }
+
+    fn stmts_to_str(stmts: Vec<Stmt>) -> String {
+        to_str(Module {
+            span: DUMMY_SP,
+            body: stmts.into_iter().map(ModuleItem::Stmt).collect(),
+            shebang: None,
+        })
+    }
+
+    pub(super) fn interpolation(value: &str) -> Node {
+        Node::Interpolation(Interpolation {
+            value: js(value),
+            template_scope: 0,
+            patch_flag: true,
+            span: DUMMY_SP,
+        })
+    }
+
+    pub(super) fn element(
+        tag_name: &str,
+        attributes: Vec<AttributeOrBinding>,
+        children: Vec<Node>,
+    ) -> ElementNode {
+        ElementNode {
+            kind: ElementKind::Element,
+            starting_tag: StartingTag {
+                tag_name: tag_name.into(),
+                attributes,
+                directives: None,
+            },
+            children,
+            template_scope: 0,
+            patch_hints: Default::default(),
+            span: DUMMY_SP,
+        }
+    }
+
+    pub(super) fn component(
+        tag_name: &str,
+        attributes: Vec<AttributeOrBinding>,
+        children: Vec<Node>,
+    ) -> ElementNode {
+        ElementNode {
+            kind: ElementKind::Component,
+            ..element(tag_name, attributes, children)
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! In Vapor mode the `v-for` items and the slot props are not plain values.
+//! They are read from the reactive sources passed to a block,
+//! e.g. `item` of `v-for="item in list"` becomes `_for_item0.value`
+//! and `foo` of `v-slot="{ foo }"` becomes `_slotProps0.foo`.
+//! Otherwise the block would not be updated when the item or the slot props change.
+
+use fervid_core::{FervidAtom, IntoIdent};
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::{
+        ast::{
+            ComputedPropName, Expr, IdentName, KeyValueProp, Lit, MemberExpr, MemberProp, Number,
+            ObjectPatProp, Pat, Prop, PropName, Str,
+        },
+        visit::{noop_visit_mut_type, VisitMut, VisitMutWith},
+    },
+};
+
+/// Template scope variables and the expressions they are read from
+pub type ScopeVars = Vec<(FervidAtom, Box<Expr>)>;
+
+/// Collects the variables declared by a `v-for` item expression,
+/// e.g. `item` or `{ id, name }` in `v-for="({ id, name }, index) in list"`
+pub fn collect_expr_vars(expr: &Expr, source: Box<Expr>, out: &mut ScopeVars) {
+    match expr {
+        Expr::Ident(ident) => out.push((ident.sym.to_owned(), source)),
+        Expr::Paren(paren) => collect_expr_vars(&paren.expr, source, out),
+        Expr::Object(object) => {
+            for prop in object.props.iter() {
+                let Some(prop) = prop.as_prop() else {
+                    continue;
+                };
+
+                match prop.as_ref() {
+                    Prop::Shorthand(ident) => {
+                        let source = member_expr(source.to_owned(), &ident.sym);
+                        out.push((ident.sym.to_owned(), source));
+                    }
+                    Prop::KeyValue(key_value) => {
+                        if let Some(source) = prop_name_member(source.to_owned(), &key_value.key) {
+                            collect_expr_vars(&key_value.value, source, out);
+                        }
+                    }
+                    // `{ foo = 1 }`, the default is not supported
+                    Prop::Assign(assign) => {
+                        let source = member_expr(source.to_owned(), &assign.key.sym);
+                        out.push((assign.key.sym.to_owned(), source));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Expr::Array(array) => {
+            for (idx, elem) in array.elems.iter().enumerate() {
+                let Some(elem) = elem else {
+                    continue;
+                };
+
+                if elem.spread.is_none() {
+                    collect_expr_vars(&elem.expr, index_expr(source.to_owned(), idx), out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collects the variables declared by a pattern,
+/// e.g. `foo` and `bar` in `v-slot="{ foo, bar }"`
+pub fn collect_pat_vars(pat: &Pat, source: Box<Expr>, out: &mut ScopeVars) {
+    match pat {
+        Pat::Ident(binding_ident) => out.push((binding_ident.id.sym.to_owned(), source)),
+        Pat::Assign(assign) => collect_pat_vars(&assign.left, source, out),
+        Pat::Expr(expr) => collect_expr_vars(expr, source, out),
+        Pat::Object(object) => {
+            for prop in object.props.iter() {
+                match prop {
+                    ObjectPatProp::KeyValue(key_value) => {
+                        if let Some(source) = prop_name_member(source.to_owned(), &key_value.key) {
+                            collect_pat_vars(&key_value.value, source, out);
+                        }
+                    }
+                    ObjectPatProp::Assign(assign) => {
+                        let source = member_expr(source.to_owned(), &assign.key.sym);
+                        out.push((assign.key.sym.to_owned(), source));
+                    }
+                    ObjectPatProp::Rest(_) => {}
+                }
+            }
+        }
+        Pat::Array(array) => {
+            for (idx, elem) in array.elems.iter().enumerate() {
+                match elem {
+                    Some(Pat::Rest(_)) | None => {}
+                    Some(elem) => collect_pat_vars(elem, index_expr(source.to_owned(), idx), out),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Replaces the usages of the scope variables in the generated block
+pub fn replace_scope_vars<N>(node: &mut N, vars: &ScopeVars)
+where
+    N: for<'v> VisitMutWith<ScopeVarsReplacer<'v>>,
+{
+    if vars.is_empty() {
+        return;
+    }
+
+    node.visit_mut_with(&mut ScopeVarsReplacer { vars });
+}
+
+pub struct ScopeVarsReplacer<'v> {
+    vars: &'v ScopeVars,
+}
+
+impl ScopeVarsReplacer<'_> {
+    fn find(&self, sym: &FervidAtom) -> Option<&Expr> {
+        // The latest declared variable wins
+        self.vars
+            .iter()
+            .rev()
+            .find_map(|(name, source)| (name == sym).then_some(source.as_ref()))
+    }
+}
+
+impl VisitMut for ScopeVarsReplacer<'_> {
+    noop_visit_mut_type!();
+
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        if let Expr::Ident(ident) = expr {
+            if let Some(source) = self.find(&ident.sym) {
+                *expr = source.to_owned();
+            }
+            return;
+        }
+
+        expr.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_prop(&mut self, prop: &mut Prop) {
+        // `{ item }` -> `{ item: _for_item0.value }`
+        if let Prop::Shorthand(ident) = prop {
+            if let Some(source) = self.find(&ident.sym) {
+                *prop = Prop::KeyValue(KeyValueProp {
+                    key: PropName::Ident(IdentName {
+                        span: ident.span,
+                        sym: ident.sym.to_owned(),
+                    }),
+                    value: Box::new(source.to_owned()),
+                });
+            }
+            return;
+        }
+
+        prop.visit_mut_children_with(self);
+    }
+}
+
+/// `source.name`
+fn member_expr(source: Box<Expr>, name: &FervidAtom) -> Box<Expr> {
+    Box::new(Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj: source,
+        prop: MemberProp::Ident(IdentName {
+            span: DUMMY_SP,
+            sym: name.to_owned(),
+        }),
+    }))
+}
+
+/// `source[idx]`
+fn index_expr(source: Box<Expr>, idx: usize) -> Box<Expr> {
+    Box::new(Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj: source,
+        prop: MemberProp::Computed(ComputedPropName {
+            span: DUMMY_SP,
+            expr: Box::new(Expr::Lit(Lit::Num(Number {
+                span: DUMMY_SP,
+                value: idx as f64,
+                raw: None,
+            }))),
+        }),
+    }))
+}
+
+/// Member expression for a destructured key, computed keys are not supported
+fn prop_name_member(source: Box<Expr>, key: &PropName) -> Option<Box<Expr>> {
+    match key {
+        PropName::Ident(ident) => Some(member_expr(source, &ident.sym)),
+        PropName::Str(Str { value, .. }) => Some(Box::new(Expr::Member(MemberExpr {
+            span: DUMMY_SP,
+            obj: source,
+            prop: MemberProp::Computed(ComputedPropName {
+                span: DUMMY_SP,
+                expr: Box::new(Expr::Lit(Lit::Str(Str {
+                    span: DUMMY_SP,
+                    value: value.to_owned(),
+                    raw: None,
+                }))),
+            }),
+        }))),
+        PropName::Num(Number { value, .. }) => Some(index_expr(source, *value as usize)),
+        _ => None,
+    }
+}
+
+/// `source.value`
+pub fn value_of(source: FervidAtom) -> Box<Expr> {
+    member_expr(
+        Box::new(Expr::Ident(source.into_ident())),
+        &FervidAtom::from("value"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_core::ecma::ast::Pat;
+
+    use super::*;
+    use crate::test_utils::{js, to_str};
+
+    #[test]
+    fn it_collects_expr_vars() {
+        // `({ id, user: { name }, 'a-b': ab, c = 1 }, [first, , ...rest])`
+        let mut vars = ScopeVars::new();
+        collect_expr_vars(
+            &js("{ id, user: { name }, 'a-b': ab, c = 1 }"),
+            value_of("_for_item0".into()),
+            &mut vars,
+        );
+        collect_expr_vars(
+            &js("[first, , ...rest]"),
+            value_of("_for_key0".into()),
+            &mut vars,
+        );
+
+        assert_eq!(
+            vars_to_str(&vars),
+            vec![
+                "id=_for_item0.value.id",
+                "name=_for_item0.value.user.name",
+                "ab=_for_item0.value[\"a-b\"]",
+                "c=_for_item0.value.c",
+                "first=_for_key0.value[0]",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_collects_pat_vars() {
+        // `v-slot="{ item, nested: { deep } }"` and `v-slot="props"`
+        let mut vars = ScopeVars::new();
+        let pat = Pat::Expr(js("{ item, nested: { deep } }"));
+        collect_pat_vars(&pat, js("_slotProps0"), &mut vars);
+        collect_pat_vars(
+            &Pat::Ident(FervidAtom::from("props").into_ident().into()),
+            js("_slotProps1"),
+            &mut vars,
+        );
+
+        assert_eq!(
+            vars_to_str(&vars),
+            vec![
+                "item=_slotProps0.item",
+                "deep=_slotProps0.nested.deep",
+                "props=_slotProps1",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_replaces_scope_vars() {
+        let vars: ScopeVars = vec![
+            (FervidAtom::from("item"), value_of("_for_item0".into())),
+            (FervidAtom::from("index"), value_of("_for_key0".into())),
+            // The latest declared variable wins
+            (FervidAtom::from("index"), value_of("_for_key1".into())),
+        ];
+
+        macro_rules! test {
+            ($input: literal, $expected: literal) => {
+                let mut expr = js($input);
+                replace_scope_vars(expr.as_mut(), &vars);
+                assert_eq!(to_str(expr), $expected);
+            };
+        }
+
+        test!("item.id + index", "_for_item0.value.id+_for_key1.value");
+        test!(
+            "{ item, other: index }",
+            "{item:_for_item0.value,other:_for_key1.value}"
+        );
+        test!("obj.item", "obj.item");
+        test!("other", "other");
+    }
+
+    fn vars_to_str(vars: &ScopeVars) -> Vec<String> {
+        vars.iter()
+            .map(|(name, source)| format!("{}={}", name, to_str(source.to_owned())))
+            .collect()
+    }
+}
@@ -0,0 +1,1065 @@
+//! Generation of a virtual TypeScript file for type-checking an SFC.
+//!
+//! The virtual file is never executed. It contains the scripts of an SFC verbatim,
+//! followed by a function in which every template expression is placed in its context:
+//! inside `v-if` branches, `v-for` loops and `v-slot` scopes, with the `<script setup>` bindings
+//! and props in scope. Component usages are converted to calls which check the passed props
+//! against the types of the imported components.
+//!
+//! Each copied piece of code is recorded in [`VirtualTsResult::mappings`],
+//! so that a `tsc`-based checker or a language server can map diagnostics back to the `.vue` file.
+
+use fervid_core::{
+    block_content_span, start_tag_len, AttributeOrBinding, BindingTypes, ConditionalNodeSequence,
+    ElementNode, Node, SfcDescriptor, SfcScriptBlock, StrOrExpr, VBindDirective, VOnDirective,
+    VueDirectives,
+};
+use fervid_transform::{script::common::extract_variables_from_pat, ComponentMeta, SetupBinding};
+use fxhash::FxHashSet;
+use swc_core::{
+    common::{Span, Spanned},
+    ecma::{
+        ast::{Callee, Decl, Expr, ImportSpecifier, Module, ModuleDecl, ModuleItem, Pat, Stmt},
+        visit::{Visit, VisitWith},
+    },
+};
+
+use crate::utils::to_camelcase;
+
+/// Type helpers which are appended to every virtual file
+const HELPERS: &str = r#"
+type __VLS_PropsOf<T> = T extends new (...args: any) => { $props: infer P } ? P : T extends (props: infer P, ...args: any) => any ? P : {};
+type __VLS_SlotsOf<T> = T extends new (...args: any) => { $slots: infer S } ? S : T extends (props: any, ctx: { slots: infer S }, ...args: any) => any ? S : {};
+declare function __VLS_component<T>(component: T): (props: __VLS_PropsOf<T> & Record<string, unknown>) => void;
+declare function __VLS_slotProps<T, N extends PropertyKey>(component: T, name: N): N extends keyof __VLS_SlotsOf<T> ? (NonNullable<__VLS_SlotsOf<T>[N]> extends (props: infer P) => any ? P : any) : any;
+declare function __VLS_vFor<T>(source: T): T extends number ? [number, number][] : T extends string ? [string, number][] : T extends readonly (infer I)[] ? [I, number][] : T extends Iterable<infer I> ? [I, number][] : [T[keyof T], keyof T & string, number][];
+"#;
+
+/// Compiler macros which are imported from `vue` unless the user did it
+const MACROS: [&str; 7] = [
+    "defineEmits",
+    "defineExpose",
+    "defineModel",
+    "defineOptions",
+    "defineProps",
+    "defineSlots",
+    "withDefaults",
+];
+
+pub struct VirtualTsOptions<'s> {
+    /// Source of the SFC, it is used for copying the code and must match the spans of the descriptor
+    pub source: &'s str,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct VirtualTsResult {
+    /// The generated TypeScript code
+    pub code: String,
+    /// Mappings from the generated code to the SFC source, ordered by `generated_offset`
+    pub mappings: Vec<VirtualTsMapping>,
+}
+
+/// A range of the generated code which is a copy of the same-length range in the SFC source.
+/// Offsets are 0-based and in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VirtualTsMapping {
+    pub generated_offset: u32,
+    pub source_offset: u32,
+    pub length: u32,
+}
+
+impl VirtualTsResult {
+    /// Maps an offset in the generated code back to the offset in the SFC source.
+    /// Returns `None` for the generated code which has no counterpart in the source.
+    pub fn to_source_offset(&self, generated_offset: u32) -> Option<u32> {
+        let idx = self
+            .mappings
+            .partition_point(|m| m.generated_offset <= generated_offset);
+        let mapping = self.mappings.get(idx.checked_sub(1)?)?;
+
+        let delta = generated_offset - mapping.generated_offset;
+        (delta <= mapping.length).then_some(mapping.source_offset + delta)
+    }
+}
+
+/// Generates a virtual TypeScript file for type-checking the SFC.
+///
+/// `sfc_descriptor` must be the one directly produced by the parser (not transformed),
+/// because the template expressions are copied from the `options.source` using their spans.
+/// `meta` is used for the props and models available in the template,
+/// see [`fervid_transform::extract_component_meta`].
+pub fn generate_virtual_ts(
+    sfc_descriptor: &SfcDescriptor,
+    meta: &ComponentMeta,
+    options: &VirtualTsOptions,
+) -> VirtualTsResult {
+    let mut generator = VirtualTsGenerator {
+        source: options.source,
+        code: String::with_capacity(options.source.len() * 2),
+        mappings: Vec::new(),
+        setup_bindings: Vec::new(),
+        setup_bindings_set: FxHashSet::default(),
+        indent: 0,
+    };
+
+    generator.generate_scripts(sfc_descriptor);
+    generator.generate_context(sfc_descriptor, meta);
+
+    // Template
+    generator.code.push_str("function __VLS_template() {");
+    generator.indent += 1;
+    generator.generate_template_context(meta);
+    if let Some(ref template) = sfc_descriptor.template {
+        // Only HTML and Pug templates are parsed
+        if template.lang == "html" || template.lang == "pug" || template.lang == "jade" {
+            generator.generate_nodes(&template.roots, None);
+        }
+    }
+    generator.indent -= 1;
+    generator.code.push_str("\n}\n\nexport {};\n");
+
+    VirtualTsResult {
+        code: generator.code,
+        mappings: generator.mappings,
+    }
+}
+
+struct VirtualTsGenerator<'s> {
+    source: &'s str,
+    code: String,
+    mappings: Vec<VirtualTsMapping>,
+    /// Value bindings of the scripts in order of declaration
+    setup_bindings: Vec<String>,
+    setup_bindings_set: FxHashSet<String>,
+    indent: usize,
+}
+
+impl<'s> VirtualTsGenerator<'s> {
+    /// Copies the scripts and imports the compiler macros
+    fn generate_scripts(&mut self, sfc_descriptor: &SfcDescriptor) {
+        let scripts = [
+            sfc_descriptor.script_legacy.as_ref(),
+            sfc_descriptor.script_setup.as_ref(),
+        ];
+
+        // Macros which are used, but not declared or imported
+        if let Some(script_setup) = sfc_descriptor.script_setup.as_ref() {
+            let mut collector = MacroCallCollector::default();
+            script_setup.content.visit_with(&mut collector);
+
+            let mut declared = Vec::new();
+            for script in scripts.iter().flatten() {
+                collect_module_bindings(&script.content, &mut declared);
+            }
+
+            let missing_macros: Vec<&str> = MACROS
+                .into_iter()
+                .filter(|m| collector.used.contains(*m) && !declared.iter().any(|d| d.sym == *m))
+                .collect();
+            if !missing_macros.is_empty() {
+                self.code.push_str("import { ");
+                self.code.push_str(&missing_macros.join(", "));
+                self.code.push_str(" } from 'vue';\n");
+            }
+        }
+
+        for script in scripts.into_iter().flatten() {
+            if let Some(generic) = script_generic(self.source, script) {
+                generic_params_to_types(generic, &mut self.code);
+            }
+
+            if let Some(content_span) = block_content_span(self.source, script.span) {
+                self.push_mapped(content_span);
+                self.code.push('\n');
+            }
+
+            let mut bindings = Vec::new();
+            collect_module_bindings(&script.content, &mut bindings);
+            for binding in bindings {
+                if self.setup_bindings_set.insert(binding.sym.to_string()) {
+                    self.setup_bindings.push(binding.sym.to_string());
+                }
+            }
+        }
+
+        self.code.push_str(HELPERS);
+    }
+
+    /// Generates `__VLS_setup` with the script bindings and `__VLS_props`
+    fn generate_context(&mut self, sfc_descriptor: &SfcDescriptor, meta: &ComponentMeta) {
+        self.code.push_str("\nconst __VLS_setup = { ");
+        self.code.push_str(&self.setup_bindings.join(", "));
+        self.code.push_str(" };\n");
+
+        // The `defineProps()` call is duplicated to get the type of props
+        let define_props = sfc_descriptor
+            .script_setup
+            .as_ref()
+            .and_then(|script_setup| find_define_props(&script_setup.content));
+
+        self.code.push_str("const __VLS_props = ");
+        match define_props.and_then(|expr| self.source_text(expr.span())) {
+            Some(text) => self.code.push_str(text),
+            None if meta.props.is_empty() => self.code.push_str("{}"),
+            None => self.code.push_str("{} as Record<string, any>"),
+        }
+        self.code.push_str(";\n\n");
+    }
+
+    /// Declares the bindings available to the template expressions.
+    /// Setup bindings and models are declared with `let`, as the template may assign to them.
+    fn generate_template_context(&mut self, meta: &ComponentMeta) {
+        if !self.setup_bindings.is_empty() {
+            self.newline();
+            self.code.push_str("let { ");
+            self.code.push_str(&self.setup_bindings.join(", "));
+            self.code
+                .push_str(" } = {} as import('vue').ShallowUnwrapRef<typeof __VLS_setup>;");
+        }
+
+        // Setup bindings take precedence over props
+        let props: Vec<&str> = meta
+            .props
+            .iter()
+            .map(|p| p.name.as_str())
+            .filter(|name| !self.setup_bindings_set.contains(*name))
+            .collect();
+        if !props.is_empty() {
+            self.newline();
+            self.code.push_str("const { ");
+            self.code.push_str(&props.join(", "));
+            self.code.push_str(" } = __VLS_props;");
+        }
+
+        let models: Vec<&_> = meta
+            .models
+            .iter()
+            .filter(|m| !self.setup_bindings_set.contains(m.name.as_str()))
+            .collect();
+        if !models.is_empty() {
+            self.newline();
+            self.code.push_str("let { ");
+            for (idx, model) in models.iter().enumerate() {
+                if idx != 0 {
+                    self.code.push_str(", ");
+                }
+                self.code.push_str(&model.name);
+            }
+            self.code.push_str(" } = {} as { ");
+            for model in models.iter() {
+                self.code.push_str(&model.name);
+                self.code.push_str(": ");
+                self.code
+                    .push_str(model.ts_type.as_deref().unwrap_or("any"));
+                self.code.push_str("; ");
+            }
+            self.code.push_str("};");
+        }
+
+        self.newline();
+        self.code.push_str("const $props = __VLS_props;");
+        self.newline();
+        self.code
+            .push_str("const $attrs = {} as import('vue').SetupContext['attrs'];");
+        self.newline();
+        self.code
+            .push_str("const $slots = {} as import('vue').SetupContext['slots'];");
+        self.newline();
+        self.code
+            .push_str("const $emit = {} as import('vue').SetupContext['emit'];");
+    }
+
+    fn generate_nodes(&mut self, nodes: &[Node], parent_component: Option<&str>) {
+        // Whether the previous element was `v-if` or `v-else-if`
+        let mut is_prev_conditional = false;
+
+        for node in nodes {
+            match node {
+                Node::Element(element) => {
+                    is_prev_conditional =
+                        self.generate_element(element, parent_component, is_prev_conditional);
+                }
+
+                Node::Interpolation(interpolation) => {
+                    self.newline();
+                    self.push_expr_statement(&interpolation.value);
+                    is_prev_conditional = false;
+                }
+
+                Node::ConditionalSeq(conditional_seq) => {
+                    self.generate_conditional_seq(conditional_seq, parent_component);
+                    is_prev_conditional = false;
+                }
+
+                // Whitespace and comments may be in-between the conditional elements
+                Node::Text(..) | Node::Comment(..) => {}
+            }
+        }
+    }
+
+    /// Returns `true` if the element is `v-if` or `v-else-if`
+    fn generate_element(
+        &mut self,
+        element: &ElementNode,
+        parent_component: Option<&str>,
+        is_prev_conditional: bool,
+    ) -> bool {
+        let directives = element.starting_tag.directives.as_deref();
+        let mut blocks = 0;
+        let mut is_conditional = false;
+
+        // `v-if` has a higher priority than `v-for`
+        if let Some(directives) = directives {
+            if let Some(ref condition) = directives.v_if {
+                self.newline();
+                self.code.push_str("if (");
+                self.push_expr(condition);
+                self.code.push_str(") {");
+                is_conditional = true;
+                blocks += 1;
+            } else if let Some(ref condition) = directives.v_else_if {
+                self.newline();
+                self.code.push_str(if is_prev_conditional {
+                    "else if ("
+                } else {
+                    "if ("
+                });
+                self.push_expr(condition);
+                self.code.push_str(") {");
+                is_conditional = true;
+                blocks += 1;
+            } else if directives.v_else.is_some() {
+                self.newline();
+                self.code
+                    .push_str(if is_prev_conditional { "else {" } else { "{" });
+                blocks += 1;
+            }
+            self.indent += blocks;
+
+            if let Some(ref v_for) = directives.v_for {
+                self.newline();
+                self.code.push_str("for (const [");
+                self.push_itervar(&v_for.itervar);
+                self.code.push_str("] of __VLS_vFor(");
+                self.push_expr(&v_for.iterable);
+                self.code.push_str(")) {");
+                blocks += 1;
+                self.indent += 1;
+            }
+        }
+
+        let component = self.resolve_component(&element.starting_tag.tag_name);
+        match component {
+            Some(ref component) => self.generate_component_props(component, element),
+            None => self.generate_element_bindings(element),
+        }
+
+        if let Some(directives) = directives {
+            self.generate_directive_values(directives, component.is_some());
+
+            // `v-slot` on a component or on a `<template>` inside a component
+            if let Some(ref v_slot) = directives.v_slot {
+                self.newline();
+                self.code.push('{');
+                blocks += 1;
+                self.indent += 1;
+
+                if let Some(ref pat) = v_slot.value {
+                    self.newline();
+                    self.code.push_str("const ");
+                    self.push_pat(pat);
+                    self.code.push_str(" = ");
+                    match component.as_deref().or(parent_component) {
+                        Some(slot_owner) => {
+                            self.code.push_str("__VLS_slotProps(");
+                            self.code.push_str(slot_owner);
+                            self.code.push_str(", ");
+                            match v_slot.slot_name {
+                                Some(StrOrExpr::Str(ref name)) => self.push_string_literal(name),
+                                Some(StrOrExpr::Expr(ref expr)) => self.push_expr(expr),
+                                None => self.code.push_str("\"default\""),
+                            }
+                            self.code.push_str(");");
+                        }
+                        None => self.code.push_str("{} as any;"),
+                    }
+                }
+            }
+        }
+
+        self.generate_nodes(&element.children, component.as_deref().or(parent_component));
+
+        for _ in 0..blocks {
+            self.indent -= 1;
+            self.newline();
+            self.code.push('}');
+        }
+
+        is_conditional
+    }
+
+    fn generate_conditional_seq(
+        &mut self,
+        conditional_seq: &ConditionalNodeSequence,
+        parent_component: Option<&str>,
+    ) {
+        let conditionals =
+            std::iter::once(&*conditional_seq.if_node).chain(conditional_seq.else_if_nodes.iter());
+        for (idx, conditional) in conditionals.enumerate() {
+            self.newline();
+            self.code
+                .push_str(if idx == 0 { "if (" } else { "else if (" });
+            self.push_expr(&conditional.condition);
+            self.code.push_str(") {");
+            self.indent += 1;
+            self.generate_element(&conditional.node, parent_component, false);
+            self.indent -= 1;
+            self.newline();
+            self.code.push('}');
+        }
+
+        if let Some(ref else_node) = conditional_seq.else_node {
+            self.newline();
+            self.code.push_str("else {");
+            self.indent += 1;
+            self.generate_element(else_node, parent_component, false);
+            self.indent -= 1;
+            self.newline();
+            self.code.push('}');
+        }
+    }
+
+    /// Generates `__VLS_component(Comp)({ ... })` to check the props of a component
+    fn generate_component_props(&mut self, component: &str, element: &ElementNode) {
+        self.newline();
+        self.code.push_str("__VLS_component(");
+        self.code.push_str(component);
+        self.code.push_str(")({");
+
+        let mut is_first = true;
+        let mut separator = |code: &mut String| {
+            code.push_str(if is_first { " " } else { ", " });
+            is_first = false;
+        };
+
+        for attr in element.starting_tag.attributes.iter() {
+            match attr {
+                AttributeOrBinding::RegularAttribute { name, value, .. } => {
+                    separator(&mut self.code);
+                    self.push_prop_key(name);
+                    self.code.push_str(": ");
+                    // Boolean casting, e.g. `<Comp disabled />`
+                    if value.is_empty() {
+                        self.code.push_str("true");
+                    } else {
+                        self.push_string_literal(value);
+                    }
+                }
+
+                AttributeOrBinding::VBind(VBindDirective {
+                    argument, value, ..
+                }) => {
+                    separator(&mut self.code);
+                    match argument {
+                        Some(StrOrExpr::Str(name)) => {
+                            self.push_prop_key(name);
+                            self.code.push_str(": ");
+                        }
+                        Some(StrOrExpr::Expr(expr)) => {
+                            self.code.push('[');
+                            self.push_expr(expr);
+                            self.code.push_str("]: ");
+                        }
+                        None => self.code.push_str("..."),
+                    }
+                    self.push_expr(value);
+                }
+
+                AttributeOrBinding::VOn(VOnDirective {
+                    event: Some(StrOrExpr::Str(event)),
+                    handler: Some(handler),
+                    ..
+                }) => {
+                    separator(&mut self.code);
+                    // Same as `toHandlerKey(camelize(event))`, e.g. `update-value` -> `onUpdateValue`
+                    let mut camel = String::with_capacity(event.len());
+                    let _ = to_camelcase(event, &mut camel);
+                    let mut key = String::with_capacity(camel.len() + 2);
+                    key.push_str("on");
+                    let mut chars = camel.chars();
+                    if let Some(first) = chars.next() {
+                        key.extend(first.to_uppercase());
+                        key.push_str(chars.as_str());
+                    }
+                    self.push_prop_key(&key);
+                    self.code.push_str(": ");
+                    self.push_handler(handler);
+                }
+
+                // Dynamic events and object syntax are checked as expressions
+                AttributeOrBinding::VOn(_) => {}
+            }
+        }
+
+        if let Some(directives) = element.starting_tag.directives.as_deref() {
+            for v_model in directives.v_model.iter() {
+                separator(&mut self.code);
+                match v_model.argument {
+                    Some(StrOrExpr::Str(ref name)) => {
+                        self.push_prop_key(name);
+                        self.code.push_str(": ");
+                    }
+                    Some(StrOrExpr::Expr(ref expr)) => {
+                        self.code.push('[');
+                        self.push_expr(expr);
+                        self.code.push_str("]: ");
+                    }
+                    None => self.code.push_str("modelValue: "),
+                }
+                self.push_expr(&v_model.value);
+            }
+        }
+
+        self.code.push_str(if is_first { "});" } else { " });" });
+
+        // Events which were not a part of the props object
+        for attr in element.starting_tag.attributes.iter() {
+            if let AttributeOrBinding::VOn(VOnDirective {
+                event: None | Some(StrOrExpr::Expr(_)),
+                ..
+            }) = attr
+            {
+                self.generate_v_on(attr);
+            }
+        }
+    }
+
+    /// Generates the bindings of an element as standalone expressions
+    fn generate_element_bindings(&mut self, element: &ElementNode) {
+        for attr in element.starting_tag.attributes.iter() {
+            match attr {
+                AttributeOrBinding::RegularAttribute { .. } => {}
+                AttributeOrBinding::VBind(v_bind) => {
+                    if let Some(StrOrExpr::Expr(ref argument)) = v_bind.argument {
+                        self.newline();
+                        self.push_expr_statement(argument);
+                    }
+                    self.newline();
+                    self.push_expr_statement(&v_bind.value);
+                }
+                AttributeOrBinding::VOn(_) => self.generate_v_on(attr),
+            }
+        }
+
+        if let Some(directives) = element.starting_tag.directives.as_deref() {
+            for v_model in directives.v_model.iter() {
+                self.newline();
+                self.push_expr_statement(&v_model.value);
+            }
+        }
+    }
+
+    fn generate_v_on(&mut self, attr: &AttributeOrBinding) {
+        let AttributeOrBinding::VOn(v_on) = attr else {
+            return;
+        };
+
+        if let Some(StrOrExpr::Expr(ref event)) = v_on.event {
+            self.newline();
+            self.push_expr_statement(event);
+        }
+        if let Some(ref handler) = v_on.handler {
+            self.newline();
+            self.push_handler(handler);
+            self.code.push(';');
+        }
+    }
+
+    /// Generates the values of the directives not covered by other functions
+    fn generate_directive_values(&mut self, directives: &VueDirectives, is_component: bool) {
+        let values = [
+            directives.v_show.as_deref(),
+            directives.v_html.as_deref(),
+            directives.v_text.as_deref(),
+            directives.v_memo.as_deref(),
+        ];
+        let custom_values = directives.custom.iter().flat_map(|custom| {
+            let argument = match custom.argument {
+                Some(StrOrExpr::Expr(ref expr)) => Some(&**expr),
+                _ => None,
+            };
+            argument.into_iter().chain(custom.value.as_deref())
+        });
+        // `v-model` arguments are already generated for components
+        let v_model_arguments = directives
+            .v_model
+            .iter()
+            .filter(|_| !is_component)
+            .filter_map(|v_model| match v_model.argument {
+                Some(StrOrExpr::Expr(ref expr)) => Some(&**expr),
+                _ => None,
+            });
+
+        for expr in values
+            .into_iter()
+            .flatten()
+            .chain(custom_values)
+            .chain(v_model_arguments)
+        {
+            self.newline();
+            self.push_expr_statement(expr);
+        }
+    }
+
+    /// Finds the component binding used by a tag,
+    /// e.g. `MyComp` for `<my-comp>`, `<myComp>` and `<MyComp>`
+    fn resolve_component(&self, tag_name: &str) -> Option<String> {
+        // Namespaced components, e.g. `<Form.Input>`
+        if let Some((namespace, _)) = tag_name.split_once('.') {
+            return self
+                .setup_bindings_set
+                .contains(namespace)
+                .then(|| tag_name.to_owned());
+        }
+
+        if self.setup_bindings_set.contains(tag_name) {
+            return Some(tag_name.to_owned());
+        }
+
+        let mut camel = String::with_capacity(tag_name.len());
+        to_camelcase(tag_name, &mut camel).ok()?;
+        if self.setup_bindings_set.contains(camel.as_str()) {
+            return Some(camel);
+        }
+
+        let mut chars = camel.chars();
+        let first = chars.next()?;
+        let pascal: String = first.to_uppercase().chain(chars).collect();
+        self.setup_bindings_set
+            .contains(pascal.as_str())
+            .then_some(pascal)
+    }
+
+    /// Generates `(expr);` or a handler for inline statements
+    fn push_expr_statement(&mut self, expr: &Expr) {
+        self.code.push('(');
+        self.push_expr(expr);
+        self.code.push_str(");");
+    }
+
+    /// Event handlers are either function expressions or inline statements,
+    /// the latter are wrapped into an arrow function with `$event` in scope
+    fn push_handler(&mut self, handler: &Expr) {
+        let is_function = matches!(
+            handler.unwrap_parens(),
+            Expr::Ident(_) | Expr::Member(_) | Expr::OptChain(_) | Expr::Arrow(_) | Expr::Fn(_)
+        );
+
+        if is_function {
+            self.code.push('(');
+            self.push_expr(handler);
+            self.code.push(')');
+        } else {
+            self.code.push_str("(($event: any) => { ");
+            self.push_expr(handler);
+            self.code.push_str("; })");
+        }
+    }
+
+    /// Converts `(item, index)` to `item, index` for using in an array pattern
+    fn push_itervar(&mut self, itervar: &Expr) {
+        let itervar = itervar.unwrap_parens();
+        match itervar {
+            Expr::Seq(seq_expr) => {
+                for (idx, expr) in seq_expr.exprs.iter().enumerate() {
+                    if idx != 0 {
+                        self.code.push_str(", ");
+                    }
+                    self.push_expr(expr);
+                }
+            }
+            _ => self.push_expr(itervar),
+        }
+    }
+
+    fn push_expr(&mut self, expr: &Expr) {
+        // Identifiers may be synthesized, e.g. `fooBar` in the shorthand `:foo-bar`
+        if let Expr::Ident(ident) = expr {
+            if self.source_text(ident.span) != Some(ident.sym.as_str()) {
+                self.code.push_str(&ident.sym);
+                return;
+            }
+        }
+
+        if !self.push_mapped(expr.span()) {
+            self.code.push_str("undefined");
+        }
+    }
+
+    fn push_pat(&mut self, pat: &Pat) {
+        if !self.push_mapped(pat.span()) {
+            self.code.push_str("{}");
+        }
+    }
+
+    fn push_prop_key(&mut self, name: &str) {
+        let mut camel = String::with_capacity(name.len());
+        if to_camelcase(name, &mut camel).is_ok() {
+            self.push_string_literal(&camel);
+        }
+    }
+
+    fn push_string_literal(&mut self, value: &str) {
+        self.code.push('"');
+        for ch in value.chars() {
+            match ch {
+                '"' => self.code.push_str("\\\""),
+                '\\' => self.code.push_str("\\\\"),
+                '\n' => self.code.push_str("\\n"),
+                '\r' => self.code.push_str("\\r"),
+                _ => self.code.push(ch),
+            }
+        }
+        self.code.push('"');
+    }
+
+    /// Copies the source code at `span` and records a mapping.
+    /// Returns `false` if the span is not valid for the source.
+    fn push_mapped(&mut self, span: Span) -> bool {
+        let Some(text) = self.source_text(span) else {
+            return false;
+        };
+
+        self.mappings.push(VirtualTsMapping {
+            generated_offset: self.code.len() as u32,
+            source_offset: span.lo.0 - 1,
+            length: text.len() as u32,
+        });
+        self.code.push_str(text);
+        true
+    }
+
+    /// Spans are 1-based
+    fn source_text(&self, span: Span) -> Option<&'s str> {
+        let lo = (span.lo.0 as usize).checked_sub(1)?;
+        let hi = (span.hi.0 as usize).checked_sub(1)?;
+        self.source.get(lo..hi)
+    }
+
+    fn newline(&mut self) {
+        self.code.push('\n');
+        for _ in 0..self.indent {
+            self.code.push_str("    ");
+        }
+    }
+}
+
+/// Finds the value of `generic` attribute, e.g. `T extends string` in `<script setup generic="T extends string">`
+fn script_generic<'s>(source: &'s str, script: &SfcScriptBlock) -> Option<&'s str> {
+    let raw = script_raw(source, script)?;
+    let start_tag = &raw[..start_tag_len(raw)?];
+
+    let attr_idx = start_tag.find(" generic=")?;
+    let value = &start_tag[attr_idx + " generic=".len()..];
+    let quote = value.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+    let value = &value[1..];
+    value.find(quote).map(|end| &value[..end])
+}
+
+/// Generic parameters cannot be declared at the top level,
+/// thus `T extends Foo` is approximated as `type T = Foo;`
+fn generic_params_to_types(generic: &str, out: &mut String) {
+    let mut depth = 0;
+    let mut param_start = 0;
+    let mut params = Vec::new();
+    for (idx, ch) in generic.char_indices() {
+        match ch {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' | ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                params.push(&generic[param_start..idx]);
+                param_start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    params.push(&generic[param_start..]);
+
+    for param in params {
+        let param = param.trim();
+        let (name, constraint) = match param.split_once(" extends ") {
+            Some((name, constraint)) => (name.trim(), constraint.trim()),
+            None => (
+                param.split('=').next().unwrap_or_default().trim(),
+                "unknown",
+            ),
+        };
+        if name.is_empty() {
+            continue;
+        }
+
+        out.push_str("type ");
+        out.push_str(name);
+        out.push_str(" = ");
+        out.push_str(constraint);
+        out.push_str(";\n");
+    }
+}
+
+fn script_raw<'s>(source: &'s str, script: &SfcScriptBlock) -> Option<&'s str> {
+    let lo = (script.span.lo.0 as usize).checked_sub(1)?;
+    let hi = (script.span.hi.0 as usize).checked_sub(1)?;
+    source.get(lo..hi)
+}
+
+/// Collects the value bindings declared at the top level of a module
+fn collect_module_bindings(module: &Module, out: &mut Vec<SetupBinding>) {
+    for module_item in module.body.iter() {
+        let decl = match module_item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) => {
+                if import_decl.type_only {
+                    continue;
+                }
+
+                for specifier in import_decl.specifiers.iter() {
+                    let local = match specifier {
+                        ImportSpecifier::Named(named) if named.is_type_only => continue,
+                        ImportSpecifier::Named(named) => &named.local,
+                        ImportSpecifier::Default(default) => &default.local,
+                        ImportSpecifier::Namespace(namespace) => &namespace.local,
+                    };
+                    out.push(SetupBinding::new_spanned(
+                        local.sym.to_owned(),
+                        BindingTypes::SetupMaybeRef,
+                        local.span,
+                    ));
+                }
+                continue;
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => &export_decl.decl,
+            ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+            _ => continue,
+        };
+
+        match decl {
+            Decl::Class(class_decl) => out.push(SetupBinding::new_spanned(
+                class_decl.ident.sym.to_owned(),
+                BindingTypes::SetupConst,
+                class_decl.ident.span,
+            )),
+            Decl::Fn(fn_decl) if !fn_decl.declare => out.push(SetupBinding::new_spanned(
+                fn_decl.ident.sym.to_owned(),
+                BindingTypes::SetupConst,
+                fn_decl.ident.span,
+            )),
+            Decl::Var(var_decl) if !var_decl.declare => {
+                for declarator in var_decl.decls.iter() {
+                    extract_variables_from_pat(&declarator.name, out, true);
+                }
+            }
+            Decl::TsEnum(ts_enum) if !ts_enum.declare => out.push(SetupBinding::new_spanned(
+                ts_enum.id.sym.to_owned(),
+                BindingTypes::SetupConst,
+                ts_enum.id.span,
+            )),
+            _ => {}
+        }
+    }
+}
+
+/// Finds `defineProps()` or `withDefaults(defineProps())` at the top level of `<script setup>`
+fn find_define_props(module: &Module) -> Option<&Expr> {
+    let is_define_props = |expr: &Expr| {
+        let Expr::Call(call_expr) = expr else {
+            return false;
+        };
+        let Callee::Expr(ref callee) = call_expr.callee else {
+            return false;
+        };
+        matches!(**callee, Expr::Ident(ref ident) if ident.sym == "defineProps" || ident.sym == "withDefaults")
+    };
+
+    module
+        .body
+        .iter()
+        .find_map(|module_item| match module_item {
+            ModuleItem::Stmt(Stmt::Expr(expr_stmt)) if is_define_props(&expr_stmt.expr) => {
+                Some(&*expr_stmt.expr)
+            }
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))) => var_decl
+                .decls
+                .iter()
+                .filter_map(|declarator| declarator.init.as_deref())
+                .find(|init| is_define_props(init)),
+            _ => None,
+        })
+}
+
+#[derive(Default)]
+struct MacroCallCollector {
+    used: FxHashSet<String>,
+}
+
+impl Visit for MacroCallCollector {
+    fn visit_callee(&mut self, callee: &Callee) {
+        if let Callee::Expr(expr) = callee {
+            if let Expr::Ident(ident) = &**expr {
+                if MACROS.contains(&ident.sym.as_str()) {
+                    self.used.insert(ident.sym.to_string());
+                }
+            }
+        }
+        callee.visit_children_with(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_parser::SfcParser;
+    use fervid_transform::{extract_component_meta, ComponentMetaOptions};
+
+    use super::*;
+
+    #[test]
+    fn it_generates_script_setup_and_template() {
+        let source = r#"<script setup lang="ts">
+import { ref } from 'vue'
+import Child from './Child.vue'
+defineProps<{ title: string }>()
+const items = ref([{ id: 1, label: 'one' }])
+</script>
+
+<template>
+  <h1 v-if="title">{{ title.toUpperCase() }}</h1>
+  <div v-else>no title</div>
+  <Child v-for="(item, index) in items" :key="item.id" :label="item.label" @select="onSelect(item, $event)">
+    <template #row="{ row }">{{ row }}</template>
+  </Child>
+</template>"#;
+
+        let result = generate(source);
+        let code = &result.code;
+
+        assert!(code.contains("import { defineProps } from 'vue';"));
+        assert!(code.contains("const __VLS_setup = { ref, Child, items };"));
+        assert!(code.contains("const __VLS_props = defineProps<{ title: string }>();"));
+        assert!(code.contains("const { title } = __VLS_props;"));
+        assert!(code.contains("if (title) {"));
+        assert!(code.contains("(title.toUpperCase());"));
+        assert!(code.contains("else {"));
+        assert!(code.contains("for (const [item, index] of __VLS_vFor(items)) {"));
+        assert!(code.contains(
+            r#"__VLS_component(Child)({ "key": item.id, "label": item.label, "onSelect": (($event: any) => { onSelect(item, $event); }) });"#
+        ));
+        assert!(code.contains(r#"const { row } = __VLS_slotProps(Child, "row");"#));
+        assert!(code.contains("(row);"));
+        assert!(code.ends_with("export {};\n"));
+    }
+
+    #[test]
+    fn it_camelizes_event_handler_keys() {
+        let source = r#"<script setup>
+import Child from './Child.vue'
+</script>
+<template><Child @update-value="onUpdate" @close="onClose" /></template>"#;
+
+        let result = generate(source);
+        assert!(result.code.contains(
+            r#"__VLS_component(Child)({ "onUpdateValue": (onUpdate), "onClose": (onClose) });"#
+        ));
+    }
+
+    #[test]
+    fn it_maps_back_to_source() {
+        let source = r#"<script setup>
+const msg = 'hi'
+</script>
+<template><input :value="msg.length" @input="onInput"></template>"#;
+
+        let result = generate(source);
+
+        // Every mapping points to the same text
+        assert!(!result.mappings.is_empty());
+        for mapping in result.mappings.iter() {
+            let generated = mapping.generated_offset as usize;
+            let original = mapping.source_offset as usize;
+            let len = mapping.length as usize;
+            assert_eq!(
+                &result.code[generated..generated + len],
+                &source[original..original + len]
+            );
+        }
+
+        let generated_offset = result.code.find("msg.length").unwrap() as u32;
+        let source_offset = source.find("msg.length").unwrap() as u32;
+        assert_eq!(
+            Some(source_offset + 4),
+            result.to_source_offset(generated_offset + 4)
+        );
+        let helpers_offset = result.code.find("__VLS_PropsOf").unwrap() as u32;
+        assert_eq!(None, result.to_source_offset(helpers_offset));
+    }
+
+    #[test]
+    fn it_copies_script_with_generic() {
+        let source = r#"<script setup lang="ts" generic="T extends Array<string>">
+defineProps<{ items: T }>()
+</script>
+<template><span v-for="item in items">{{ item }}</span></template>"#;
+
+        let result = generate(source);
+        assert!(result.code.contains("\ndefineProps<{ items: T }>()\n\n"));
+        assert!(result
+            .code
+            .contains("for (const [item] of __VLS_vFor(items)) {"));
+        assert!(result.code.contains("type T = Array<string>;"));
+        assert!(!result.code.contains("generic="));
+    }
+
+    #[test]
+    fn it_allows_assignments_in_template() {
+        let source = r#"<script setup lang="ts">
+import { ref } from 'vue'
+const msg = ref('')
+const count = ref(0)
+const items = ref([{ name: 'one' }])
+const model = defineModel<string>()
+</script>
+<template>
+  <button v-for="item in items" @click="msg = item.name">{{ msg }}</button>
+  <button @click="count++">{{ count }}</button>
+  <input @change="(v) => model = v">
+</template>"#;
+
+        let result = generate(source);
+        let code = &result.code;
+
+        assert!(code.contains(
+            "let { ref, msg, count, items, model } = {} as import('vue').ShallowUnwrapRef<typeof __VLS_setup>;"
+        ));
+        assert!(!code.contains("const { msg"));
+        assert!(code.contains("msg = item.name"));
+        assert!(code.contains("count++"));
+    }
+
+    fn generate(source: &str) -> VirtualTsResult {
+        let mut parse_errors = Vec::new();
+        let mut parser = SfcParser::new(source, &mut parse_errors);
+        let sfc_descriptor = parser.parse_sfc().expect("Should parse");
+        let comments = parser.comments().clone();
+        assert!(parse_errors.is_empty(), "{:?}", parse_errors);
+
+        let mut errors = Vec::new();
+        let meta = extract_component_meta(
+            &sfc_descriptor,
+            ComponentMetaOptions {
+                filename: "Comp.vue",
+                source,
+                comments: Some(&comments),
+            },
+            &mut errors,
+        );
+
+        generate_virtual_ts(&sfc_descriptor, &meta, &VirtualTsOptions { source })
+    }
+}
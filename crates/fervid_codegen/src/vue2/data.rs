@@ -0,0 +1,1196 @@
+use fervid_core::{
+    AttributeOrBinding, BuiltinType, ElementKind, ElementNode, FervidAtom, StrOrExpr,
+    VCustomDirective, VModelDirective, VOnDirective,
+};
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::{
+        ast::{
+            BinaryOp, BlockStmt, BlockStmtOrExpr, ComputedPropName, CondExpr, Decl, Expr, ExprStmt,
+            IfStmt, KeyValueProp, MemberExpr, MemberProp, Pat, Prop, PropName, PropOrSpread,
+            ReturnStmt, Stmt, Str, UnaryExpr, UnaryOp, VarDecl, VarDeclKind, VarDeclarator,
+        },
+        visit::{VisitMut, VisitMutWith},
+    },
+};
+
+use super::{
+    array, bin, bool_lit, call, element::camelize, function, ident, key_value, member, null,
+    num_lit, object, param, paren, return_stmt, str_lit, vm_call, Vue2Generator,
+};
+
+/// Key codes and key names of the `v-on` key modifiers, as checked by `_vm._k`
+const KEY_ALIASES: &[(&str, &[u32], &[&str])] = &[
+    ("esc", &[27], &["Esc", "Escape"]),
+    ("tab", &[9], &["Tab"]),
+    ("enter", &[13], &["Enter"]),
+    ("space", &[32], &[" ", "Spacebar"]),
+    ("up", &[38], &["Up", "ArrowUp"]),
+    ("left", &[37], &["Left", "ArrowLeft"]),
+    ("right", &[39], &["Right", "ArrowRight"]),
+    ("down", &[40], &["Down", "ArrowDown"]),
+    ("delete", &[8, 46], &["Backspace", "Delete", "Del"]),
+];
+
+const SYSTEM_MODIFIERS: &[&str] = &["ctrl", "shift", "alt", "meta"];
+
+/// The second argument of `_c`, e.g. `{ staticClass: "a", attrs: { id: id }, on: { click: onClick } }`
+#[allow(clippy::vec_box)]
+#[derive(Default)]
+struct DataObject {
+    directives: Vec<Box<Expr>>,
+    key: Option<Box<Expr>>,
+    ref_value: Option<Box<Expr>>,
+    static_class: Option<Box<Expr>>,
+    class: Option<Box<Expr>>,
+    static_style: Option<Box<Expr>>,
+    style: Option<Box<Expr>>,
+    attrs: Properties,
+    dom_props: Properties,
+    on: Properties,
+    native_on: Properties,
+    model: Option<Box<Expr>>,
+    /// `v-bind="obj"` and whether it has the `.prop` modifier
+    bind_object: Option<(Box<Expr>, bool)>,
+    /// `v-on="obj"`
+    listeners_object: Option<Box<Expr>>,
+}
+
+/// Static and dynamic keys of an object.
+/// The values of the repeated keys are merged to an array, as Vue 2 does for the listeners
+#[allow(clippy::vec_box)]
+#[derive(Default)]
+struct Properties {
+    static_props: Vec<(FervidAtom, Vec<Box<Expr>>)>,
+    /// Key-value pairs for `_vm._d`
+    dynamic_props: Vec<Box<Expr>>,
+}
+
+impl Properties {
+    fn add(&mut self, key: FervidAtom, value: Box<Expr>) {
+        match self.static_props.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, values)) => values.push(value),
+            None => self.static_props.push((key, vec![value])),
+        }
+    }
+
+    fn add_dynamic(&mut self, key: Box<Expr>, value: Box<Expr>) {
+        self.dynamic_props.push(key);
+        self.dynamic_props.push(value);
+    }
+
+    /// `{ key: value }` or `_vm._d({ key: value }, [dynamicKey, dynamicValue])`
+    fn into_expr(self) -> Option<Box<Expr>> {
+        if self.static_props.is_empty() && self.dynamic_props.is_empty() {
+            return None;
+        }
+
+        let static_props = object(
+            self.static_props
+                .into_iter()
+                .map(|(key, mut values)| {
+                    let value = if values.len() == 1 {
+                        values.remove(0)
+                    } else {
+                        array(values)
+                    };
+                    key_value(key, value)
+                })
+                .collect(),
+        );
+
+        if self.dynamic_props.is_empty() {
+            return Some(static_props);
+        }
+
+        Some(vm_call("_d", [static_props, array(self.dynamic_props)]))
+    }
+}
+
+impl Vue2Generator<'_> {
+    /// Generates the data object of an element or a component, `None` when it is empty
+    pub(super) fn generate_data(
+        &mut self,
+        element_node: &ElementNode,
+        is_component: bool,
+        scoped_slots: Option<Box<Expr>>,
+    ) -> Option<Box<Expr>> {
+        let tag_name = &element_node.starting_tag.tag_name;
+        let is_dynamic_component = matches!(
+            element_node.kind,
+            ElementKind::Builtin(BuiltinType::Component)
+        );
+        let input_type = element_node
+            .starting_tag
+            .attributes
+            .iter()
+            .find_map(|attr| match attr {
+                AttributeOrBinding::RegularAttribute { name, value, .. } if name == "type" => {
+                    Some(value.as_ref())
+                }
+                _ => None,
+            });
+
+        let mut data = DataObject::default();
+
+        for attr in element_node.starting_tag.attributes.iter() {
+            match attr {
+                AttributeOrBinding::RegularAttribute { name, value, .. } => match name.as_ref() {
+                    "class" => data.static_class = Some(str_lit(value.to_owned())),
+                    "style" => data.static_style = Some(parse_static_style(value)),
+                    "ref" => data.ref_value = Some(str_lit(value.to_owned())),
+                    "key" => data.key = Some(str_lit(value.to_owned())),
+                    "ref_key" | "ref_for" => {}
+                    "is" if is_dynamic_component => {}
+                    _ => data.attrs.add(name.to_owned(), str_lit(value.to_owned())),
+                },
+
+                AttributeOrBinding::VBind(v_bind) => match v_bind.argument {
+                    Some(StrOrExpr::Str(ref argument)) => {
+                        let value = v_bind.value.to_owned();
+                        match argument.as_ref() {
+                            "class" => data.class = Some(value),
+                            "style" => data.style = Some(value),
+                            "ref" => data.ref_value = Some(value),
+                            "key" => data.key = Some(value),
+                            "ref_key" | "ref_for" => {}
+                            "is" if is_dynamic_component => {}
+                            _ => {
+                                let name = if v_bind.is_camel {
+                                    camelize(argument)
+                                } else {
+                                    argument.to_owned()
+                                };
+
+                                let is_dom_prop = v_bind.is_prop
+                                    || (!is_component
+                                        && must_use_prop(tag_name, input_type, &name));
+                                if is_dom_prop {
+                                    data.dom_props.add(name, value);
+                                } else {
+                                    data.attrs.add(name, value);
+                                }
+                            }
+                        }
+                    }
+
+                    // `:[key]="value"`
+                    Some(StrOrExpr::Expr(ref key)) => {
+                        let props = if v_bind.is_prop {
+                            &mut data.dom_props
+                        } else {
+                            &mut data.attrs
+                        };
+                        props.add_dynamic(key.to_owned(), v_bind.value.to_owned());
+                    }
+
+                    // `v-bind="obj"`
+                    None => data.bind_object = Some((v_bind.value.to_owned(), v_bind.is_prop)),
+                },
+
+                AttributeOrBinding::VOn(v_on) => {
+                    self.generate_listener(v_on, is_component, &mut data)
+                }
+            }
+        }
+
+        if let Some(ref directives) = element_node.starting_tag.directives {
+            // `{ name: "show", rawName: "v-show", value: visible }`
+            if let Some(ref v_show) = directives.v_show {
+                data.directives.push(object(vec![
+                    key_value("name", str_lit("show")),
+                    key_value("rawName", str_lit("v-show")),
+                    key_value("value", v_show.to_owned()),
+                ]));
+            }
+
+            for v_model in directives.v_model.iter() {
+                if is_component {
+                    generate_component_model(v_model, &mut data);
+                } else {
+                    generate_element_model(tag_name, input_type, v_model, &mut data);
+                }
+            }
+
+            for custom_directive in directives.custom.iter() {
+                data.directives
+                    .push(generate_custom_directive(custom_directive));
+            }
+
+            if !is_component {
+                if let Some(ref v_html) = directives.v_html {
+                    data.dom_props.add("innerHTML".into(), v_html.to_owned());
+                }
+                if let Some(ref v_text) = directives.v_text {
+                    data.dom_props
+                        .add("textContent".into(), vm_call("_s", [v_text.to_owned()]));
+                }
+            }
+        }
+
+        let mut props = Vec::new();
+        if !data.directives.is_empty() {
+            props.push(key_value("directives", array(data.directives)));
+        }
+        if is_dynamic_component {
+            props.push(key_value("tag", str_lit("component")));
+        }
+        if let Some(key) = data.key {
+            props.push(key_value("key", key));
+        }
+        if let Some(ref_value) = data.ref_value {
+            props.push(key_value("ref", ref_value));
+            if self.for_depth > 0 {
+                props.push(key_value("refInFor", bool_lit(true)));
+            }
+        }
+        let optional_props = [
+            ("staticClass", data.static_class),
+            ("class", data.class),
+            ("staticStyle", data.static_style),
+            ("style", data.style),
+            ("attrs", data.attrs.into_expr()),
+            ("domProps", data.dom_props.into_expr()),
+            ("on", data.on.into_expr()),
+            ("nativeOn", data.native_on.into_expr()),
+            ("scopedSlots", scoped_slots),
+            ("model", data.model),
+        ];
+        for (key, value) in optional_props {
+            if let Some(value) = value {
+                props.push(key_value(key, value));
+            }
+        }
+
+        if props.is_empty() && data.bind_object.is_none() && data.listeners_object.is_none() {
+            return None;
+        }
+
+        let mut data_expr = object(props);
+
+        // `_vm._b(data, "div", obj, false)`
+        if let Some((bind_object, is_prop)) = data.bind_object {
+            data_expr = vm_call(
+                "_b",
+                [
+                    data_expr,
+                    str_lit(tag_name.to_owned()),
+                    bind_object,
+                    bool_lit(is_prop),
+                ],
+            );
+        }
+
+        // `_vm._g(data, listeners)`
+        if let Some(listeners_object) = data.listeners_object {
+            data_expr = vm_call("_g", [data_expr, listeners_object]);
+        }
+
+        Some(data_expr)
+    }
+
+    /// Listeners are keyed by the event name with the prefixes of the options,
+    /// e.g. `"&~scroll"` for `.passive.once`. The other modifiers are checked by a wrapping function
+    fn generate_listener(
+        &mut self,
+        v_on: &VOnDirective,
+        is_component: bool,
+        data: &mut DataObject,
+    ) {
+        let Some(ref event) = v_on.event else {
+            // `v-on="listeners"`
+            if let Some(ref handler) = v_on.handler {
+                data.listeners_object = Some(handler.to_owned());
+            }
+            return;
+        };
+
+        let has_modifier = |name: &str| v_on.modifiers.iter().any(|modifier| modifier == name);
+        let is_native = is_component && has_modifier("native");
+
+        // `@click.right` and `@click.middle` listen to the other events
+        let mut skip_right = false;
+        let event = match event {
+            StrOrExpr::Str(name) if name == "click" && has_modifier("right") => {
+                skip_right = true;
+                StrOrExpr::Str("contextmenu".into())
+            }
+            StrOrExpr::Str(name) if name == "click" && has_modifier("middle") => {
+                StrOrExpr::Str("mouseup".into())
+            }
+            _ => event.to_owned(),
+        };
+
+        let mut prefix = String::new();
+        if has_modifier("passive") {
+            prefix.push('&');
+        }
+        if has_modifier("once") {
+            prefix.push('~');
+        }
+        if has_modifier("capture") {
+            prefix.push('!');
+        }
+
+        let mut keys = Vec::new();
+        let mut guards = Vec::new();
+        for modifier in v_on.modifiers.iter() {
+            let modifier = modifier.as_ref();
+            match modifier {
+                "native" | "capture" | "once" | "passive" => {}
+                "stop" => guards.push(expr_stmt(call(
+                    member(ident("$event"), "stopPropagation"),
+                    [],
+                ))),
+                "prevent" => guards.push(expr_stmt(call(
+                    member(ident("$event"), "preventDefault"),
+                    [],
+                ))),
+                "self" => guards.push(guard(bin(
+                    BinaryOp::NotEqEq,
+                    member(ident("$event"), "target"),
+                    member(ident("$event"), "currentTarget"),
+                ))),
+                "ctrl" | "shift" | "alt" | "meta" => guards.push(guard(not(member(
+                    ident("$event"),
+                    &format!("{modifier}Key"),
+                )))),
+                "exact" => {
+                    let other_modifiers = SYSTEM_MODIFIERS
+                        .iter()
+                        .filter(|system_modifier| !has_modifier(system_modifier))
+                        .map(|system_modifier| {
+                            member(ident("$event"), &format!("{system_modifier}Key"))
+                        })
+                        .reduce(|left, right| bin(BinaryOp::LogicalOr, left, right));
+                    if let Some(other_modifiers) = other_modifiers {
+                        guards.push(guard(other_modifiers));
+                    }
+                }
+                "left" | "middle" | "right" => {
+                    if modifier == "right" && skip_right {
+                        continue;
+                    }
+
+                    let button = match modifier {
+                        "left" => 0.0,
+                        "middle" => 1.0,
+                        _ => 2.0,
+                    };
+                    guards.push(guard(bin(
+                        BinaryOp::LogicalAnd,
+                        bin(BinaryOp::In, str_lit("button"), ident("$event")),
+                        bin(
+                            BinaryOp::NotEqEq,
+                            member(ident("$event"), "button"),
+                            num_lit(button),
+                        ),
+                    )));
+
+                    if modifier != "middle" {
+                        keys.push(modifier);
+                    }
+                }
+                _ => keys.push(modifier),
+            }
+        }
+
+        // Keys are checked before the other modifiers
+        if !keys.is_empty() {
+            let is_key_event = not(call(
+                member(member(ident("$event"), "type"), "indexOf"),
+                [str_lit("key")],
+            ));
+            let key_filter = keys
+                .into_iter()
+                .map(generate_key_filter)
+                .fold(is_key_event, |left, right| {
+                    bin(BinaryOp::LogicalAnd, left, right)
+                });
+            guards.insert(0, guard(key_filter));
+        }
+
+        let handler = if guards.is_empty() {
+            v_on.handler
+                .to_owned()
+                .unwrap_or_else(|| function(vec![], vec![]))
+        } else {
+            // `function($event) { /*guards*/ return handler.apply(null, arguments) }`
+            let mut stmts = guards;
+            if let Some(ref handler) = v_on.handler {
+                let handler = match handler.as_ref() {
+                    Expr::Ident(_) | Expr::Member(_) => handler.to_owned(),
+                    _ => paren(handler.to_owned()),
+                };
+                stmts.push(return_stmt(call(
+                    member(handler, "apply"),
+                    [null(), ident("arguments")],
+                )));
+            }
+            function(vec![param("$event")], stmts)
+        };
+
+        let listeners = if is_native {
+            &mut data.native_on
+        } else {
+            &mut data.on
+        };
+        match event {
+            StrOrExpr::Str(name) => {
+                listeners.add(FervidAtom::from(format!("{prefix}{name}")), handler)
+            }
+            // `_vm._p(name, "~")` prepends the prefix to the dynamic event name
+            StrOrExpr::Expr(name) => {
+                let name = if prefix.is_empty() {
+                    name
+                } else {
+                    vm_call("_p", [name, str_lit(prefix)])
+                };
+                listeners.add_dynamic(name, handler);
+            }
+        }
+    }
+}
+
+/// `_vm._k($event.keyCode, "enter", 13, $event.key, "Enter")`, i.e. the key does not match
+fn generate_key_filter(key: &str) -> Box<Expr> {
+    if let Ok(key_code) = key.parse::<u32>() {
+        return bin(
+            BinaryOp::NotEqEq,
+            member(ident("$event"), "keyCode"),
+            num_lit(key_code as f64),
+        );
+    }
+
+    let alias = KEY_ALIASES.iter().find(|(name, _, _)| *name == key);
+    let (key_code, key_name) = match alias {
+        Some((_, key_codes, key_names)) => (
+            one_or_array(key_codes.iter().map(|&code| num_lit(code as f64))),
+            one_or_array(key_names.iter().map(|&name| str_lit(name))),
+        ),
+        None => (ident("undefined"), ident("undefined")),
+    };
+
+    vm_call(
+        "_k",
+        [
+            member(ident("$event"), "keyCode"),
+            str_lit(key),
+            key_code,
+            member(ident("$event"), "key"),
+            key_name,
+        ],
+    )
+}
+
+/// `model: { value: msg, callback: $event => (msg = $event) }` or
+/// `attrs: { title: title }, on: { "update:title": $event => (title = $event) }` for `v-model:title`
+fn generate_component_model(v_model: &VModelDirective, data: &mut DataObject) {
+    let Some(ref update_handler) = v_model.update_handler else {
+        return;
+    };
+
+    match v_model.argument {
+        Some(StrOrExpr::Str(ref argument)) => {
+            data.attrs
+                .add(argument.to_owned(), v_model.value.to_owned());
+            data.on.add(
+                FervidAtom::from(format!("update:{argument}")),
+                update_handler.to_owned(),
+            );
+        }
+
+        Some(StrOrExpr::Expr(ref argument)) => {
+            data.attrs
+                .add_dynamic(argument.to_owned(), v_model.value.to_owned());
+            data.on.add_dynamic(
+                bin(BinaryOp::Add, str_lit("update:"), argument.to_owned()),
+                update_handler.to_owned(),
+            );
+        }
+
+        None => {
+            let has_modifier = |name: &str| v_model.modifiers.iter().any(|m| m == name);
+
+            // `function($$v) { msg = _vm._n(typeof $$v === "string" ? $$v.trim() : $$v) }`
+            let callback = if has_modifier("trim") || has_modifier("number") {
+                let mut value = ident("$$v");
+                if has_modifier("trim") {
+                    value = Box::new(Expr::Cond(CondExpr {
+                        span: DUMMY_SP,
+                        test: bin(
+                            BinaryOp::EqEqEq,
+                            Box::new(Expr::Unary(UnaryExpr {
+                                span: DUMMY_SP,
+                                op: UnaryOp::TypeOf,
+                                arg: ident("$$v"),
+                            })),
+                            str_lit("string"),
+                        ),
+                        cons: call(member(ident("$$v"), "trim"), []),
+                        alt: ident("$$v"),
+                    }));
+                }
+                if has_modifier("number") {
+                    value = vm_call("_n", [value]);
+                }
+                function(
+                    vec![param("$$v")],
+                    vec![expr_stmt(apply_update_handler(update_handler, value))],
+                )
+            } else {
+                update_handler.to_owned()
+            };
+
+            data.model = Some(object(vec![
+                key_value("value", v_model.value.to_owned()),
+                key_value("callback", callback),
+            ]));
+        }
+    }
+}
+
+/// `v-model` of `<input>`, `<textarea>` and `<select>`, the same as generated by the Vue 2 compiler
+fn generate_element_model(
+    tag_name: &str,
+    input_type: Option<&str>,
+    v_model: &VModelDirective,
+    data: &mut DataObject,
+) {
+    let Some(ref update_handler) = v_model.update_handler else {
+        return;
+    };
+
+    let has_modifier = |name: &str| v_model.modifiers.iter().any(|m| m == name);
+    let value = &v_model.value;
+
+    let to_number = |expr: Box<Expr>| {
+        if has_modifier("number") {
+            vm_call("_n", [expr])
+        } else {
+            expr
+        }
+    };
+
+    let event_target = || member(ident("$event"), "target");
+
+    let model_directive = || {
+        let mut props = vec![
+            key_value("name", str_lit("model")),
+            key_value("rawName", str_lit("v-model")),
+            key_value("value", value.to_owned()),
+        ];
+        if !v_model.modifiers.is_empty() {
+            props.push(key_value("modifiers", modifiers_object(&v_model.modifiers)));
+        }
+        object(props)
+    };
+
+    match (tag_name, input_type) {
+        // `$$selectedVal` are the values of the selected options
+        ("select", _) => {
+            let option_value = {
+                let has_value = bin(BinaryOp::In, str_lit("_value"), ident("o"));
+                let value = Box::new(Expr::Cond(CondExpr {
+                    span: DUMMY_SP,
+                    test: has_value,
+                    cons: member(ident("o"), "_value"),
+                    alt: member(ident("o"), "value"),
+                }));
+                to_number(value)
+            };
+            let selected_options = call(
+                member(
+                    member(member(ident("Array"), "prototype"), "filter"),
+                    "call",
+                ),
+                [
+                    member(event_target(), "options"),
+                    function(
+                        vec![param("o")],
+                        vec![return_stmt(member(ident("o"), "selected"))],
+                    ),
+                ],
+            );
+            let selected_values = call(
+                member(selected_options, "map"),
+                [function(vec![param("o")], vec![return_stmt(option_value)])],
+            );
+            let assigned = Box::new(Expr::Cond(CondExpr {
+                span: DUMMY_SP,
+                test: member(event_target(), "multiple"),
+                cons: ident("$$selectedVal"),
+                alt: Box::new(Expr::Member(MemberExpr {
+                    span: DUMMY_SP,
+                    obj: ident("$$selectedVal"),
+                    prop: MemberProp::Computed(ComputedPropName {
+                        span: DUMMY_SP,
+                        expr: num_lit(0.0),
+                    }),
+                })),
+            }));
+
+            data.directives.push(model_directive());
+            data.on.add(
+                "change".into(),
+                function(
+                    vec![param("$event")],
+                    vec![
+                        var_decl(vec![("$$selectedVal", selected_values)]),
+                        expr_stmt(apply_update_handler(update_handler, assigned)),
+                    ],
+                ),
+            );
+        }
+
+        // `Array.isArray(checked) ? _vm._i(checked, value) > -1 : checked`,
+        // arrays get the value added or removed
+        ("input", Some("checkbox")) => {
+            let value_binding = to_number(bound_attribute(data, "value").unwrap_or_else(null));
+            let true_value = bound_attribute(data, "true-value");
+            let false_value = bound_attribute(data, "false-value");
+
+            let is_array = |expr: Box<Expr>| call(member(ident("Array"), "isArray"), [expr]);
+            let checked = Box::new(Expr::Cond(CondExpr {
+                span: DUMMY_SP,
+                test: is_array(value.to_owned()),
+                cons: bin(
+                    BinaryOp::Gt,
+                    vm_call("_i", [value.to_owned(), value_binding.to_owned()]),
+                    Box::new(Expr::Unary(UnaryExpr {
+                        span: DUMMY_SP,
+                        op: UnaryOp::Minus,
+                        arg: num_lit(1.0),
+                    })),
+                ),
+                alt: match true_value {
+                    Some(ref true_value) => {
+                        vm_call("_q", [value.to_owned(), true_value.to_owned()])
+                    }
+                    None => value.to_owned(),
+                },
+            }));
+
+            let el_checked = || member(ident("$$el"), "checked");
+            let assign = |expr: Box<Expr>| expr_stmt(apply_update_handler(update_handler, expr));
+            let add = call(member(ident("$$a"), "concat"), [array([ident("$$v")])]);
+            let remove = call(
+                member(
+                    call(member(ident("$$a"), "slice"), [num_lit(0.0), ident("$$i")]),
+                    "concat",
+                ),
+                [call(
+                    member(ident("$$a"), "slice"),
+                    [bin(BinaryOp::Add, ident("$$i"), num_lit(1.0))],
+                )],
+            );
+
+            let stmts = vec![
+                var_decl(vec![
+                    ("$$a", value.to_owned()),
+                    ("$$el", event_target()),
+                    (
+                        "$$c",
+                        Box::new(Expr::Cond(CondExpr {
+                            span: DUMMY_SP,
+                            test: el_checked(),
+                            cons: true_value.unwrap_or_else(|| bool_lit(true)),
+                            alt: false_value.unwrap_or_else(|| bool_lit(false)),
+                        })),
+                    ),
+                ]),
+                if_stmt(
+                    is_array(ident("$$a")),
+                    vec![
+                        var_decl(vec![
+                            ("$$v", value_binding),
+                            ("$$i", vm_call("_i", [ident("$$a"), ident("$$v")])),
+                        ]),
+                        if_stmt(
+                            el_checked(),
+                            vec![if_stmt(
+                                bin(BinaryOp::Lt, ident("$$i"), num_lit(0.0)),
+                                vec![assign(add)],
+                                None,
+                            )],
+                            Some(vec![if_stmt(
+                                bin(
+                                    BinaryOp::Gt,
+                                    ident("$$i"),
+                                    Box::new(Expr::Unary(UnaryExpr {
+                                        span: DUMMY_SP,
+                                        op: UnaryOp::Minus,
+                                        arg: num_lit(1.0),
+                                    })),
+                                ),
+                                vec![assign(remove)],
+                                None,
+                            )]),
+                        ),
+                    ],
+                    Some(vec![assign(ident("$$c"))]),
+                ),
+            ];
+
+            data.dom_props.add("checked".into(), checked);
+            data.on
+                .add("change".into(), function(vec![param("$event")], stmts));
+        }
+
+        // `_vm._q(picked, value)`
+        ("input", Some("radio")) => {
+            let value_binding = to_number(bound_attribute(data, "value").unwrap_or_else(null));
+
+            data.dom_props.add(
+                "checked".into(),
+                vm_call("_q", [value.to_owned(), value_binding.to_owned()]),
+            );
+            data.on.add(
+                "change".into(),
+                function(
+                    vec![param("$event")],
+                    vec![expr_stmt(apply_update_handler(
+                        update_handler,
+                        value_binding,
+                    ))],
+                ),
+            );
+        }
+
+        // Text inputs wait for the IME composition to end, unless they are `.lazy`
+        _ => {
+            let is_lazy = has_modifier("lazy");
+            let is_range = input_type == Some("range");
+            let event = if is_lazy {
+                "change"
+            } else if is_range {
+                "__r"
+            } else {
+                "input"
+            };
+
+            let mut target_value = member(event_target(), "value");
+            if has_modifier("trim") {
+                target_value = call(member(target_value, "trim"), []);
+            }
+            let target_value = to_number(target_value);
+
+            let mut stmts = Vec::with_capacity(2);
+            if !is_lazy && !is_range {
+                stmts.push(Stmt::If(IfStmt {
+                    span: DUMMY_SP,
+                    test: member(event_target(), "composing"),
+                    cons: Box::new(Stmt::Return(ReturnStmt {
+                        span: DUMMY_SP,
+                        arg: None,
+                    })),
+                    alt: None,
+                }));
+            }
+            stmts.push(expr_stmt(apply_update_handler(
+                update_handler,
+                target_value,
+            )));
+
+            data.directives.push(model_directive());
+            data.dom_props.add("value".into(), value.to_owned());
+            data.on
+                .add(event.into(), function(vec![param("$event")], stmts));
+
+            // The displayed value is updated to the trimmed or the parsed one
+            if has_modifier("trim") || has_modifier("number") {
+                data.on.add(
+                    "blur".into(),
+                    function(
+                        vec![param("$event")],
+                        vec![return_stmt(vm_call("$forceUpdate", []))],
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// `{ name: "focus", rawName: "v-focus:arg.mod", value: value, arg: "arg", modifiers: { mod: true } }`
+fn generate_custom_directive(custom_directive: &VCustomDirective) -> Box<Expr> {
+    let mut raw_name = format!("v-{}", custom_directive.name);
+    if let Some(StrOrExpr::Str(ref argument)) = custom_directive.argument {
+        raw_name.push(':');
+        raw_name.push_str(argument);
+    }
+    for modifier in custom_directive.modifiers.iter() {
+        raw_name.push('.');
+        raw_name.push_str(modifier);
+    }
+
+    let mut props = vec![
+        key_value("name", str_lit(custom_directive.name.to_owned())),
+        key_value("rawName", str_lit(raw_name)),
+    ];
+    if let Some(ref value) = custom_directive.value {
+        props.push(key_value("value", value.to_owned()));
+    }
+    match custom_directive.argument {
+        Some(StrOrExpr::Str(ref argument)) => {
+            props.push(key_value("arg", str_lit(argument.to_owned())))
+        }
+        Some(StrOrExpr::Expr(ref argument)) => {
+            props.push(key_value("arg", argument.to_owned()));
+            props.push(key_value("isDynamicArg", bool_lit(true)));
+        }
+        None => {}
+    }
+    if !custom_directive.modifiers.is_empty() {
+        props.push(key_value(
+            "modifiers",
+            modifiers_object(&custom_directive.modifiers),
+        ));
+    }
+
+    object(props)
+}
+
+/// `{ trim: true }`
+fn modifiers_object(modifiers: &[FervidAtom]) -> Box<Expr> {
+    object(
+        modifiers
+            .iter()
+            .map(|modifier| key_value(modifier.to_owned(), bool_lit(true)))
+            .collect(),
+    )
+}
+
+/// Value of an attribute which was already added to `attrs` or `domProps`, e.g. `value` of a checkbox
+fn bound_attribute(data: &DataObject, name: &str) -> Option<Box<Expr>> {
+    data.attrs
+        .static_props
+        .iter()
+        .chain(data.dom_props.static_props.iter())
+        .find(|(key, _)| key == name)
+        .and_then(|(_, values)| values.first().cloned())
+}
+
+/// Attributes which Vue 2 sets as the DOM properties,
+/// https://github.com/vuejs/vue/blob/main/src/platforms/web/util/attrs.ts
+fn must_use_prop(tag_name: &str, input_type: Option<&str>, attr: &str) -> bool {
+    match attr {
+        "value" => {
+            matches!(
+                tag_name,
+                "input" | "textarea" | "option" | "select" | "progress"
+            ) && input_type != Some("button")
+        }
+        "selected" => tag_name == "option",
+        "checked" => tag_name == "input",
+        "muted" => tag_name == "video",
+        _ => false,
+    }
+}
+
+/// `color: red; width: 10px` -> `{ "color": "red", "width": "10px" }`
+fn parse_static_style(style: &str) -> Box<Expr> {
+    let mut props = Vec::new();
+    for declaration in style.split(';') {
+        let Some((property, value)) = declaration.split_once(':') else {
+            continue;
+        };
+
+        props.push(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+            key: PropName::Str(Str {
+                span: DUMMY_SP,
+                value: property.trim().into(),
+                raw: None,
+            }),
+            value: str_lit(value.trim()),
+        }))));
+    }
+
+    object(props)
+}
+
+/// The `v-model` update handler made by the transform is `$event => (msg = $event)`,
+/// it is inlined with `$event` replaced by the value, e.g. `msg = $event.target.value`
+fn apply_update_handler(update_handler: &Expr, value: Box<Expr>) -> Box<Expr> {
+    if let Expr::Arrow(arrow) = update_handler {
+        if let ([Pat::Ident(param)], BlockStmtOrExpr::Expr(body)) =
+            (arrow.params.as_slice(), arrow.body.as_ref())
+        {
+            let mut body = body.to_owned();
+            body.visit_mut_with(&mut ParamReplacer {
+                param: &param.id.sym,
+                value: &value,
+            });
+
+            return match *body {
+                Expr::Paren(paren) => paren.expr,
+                body => Box::new(body),
+            };
+        }
+    }
+
+    call(Box::new(update_handler.to_owned()), [value])
+}
+
+struct ParamReplacer<'r> {
+    param: &'r FervidAtom,
+    value: &'r Expr,
+}
+
+impl VisitMut for ParamReplacer<'_> {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        if matches!(expr, Expr::Ident(ident) if ident.sym == *self.param) {
+            *expr = self.value.to_owned();
+            return;
+        }
+
+        expr.visit_mut_children_with(self);
+    }
+}
+
+fn one_or_array(mut exprs: impl ExactSizeIterator<Item = Box<Expr>>) -> Box<Expr> {
+    if exprs.len() == 1 {
+        exprs.next().unwrap_or_else(null)
+    } else {
+        array(exprs)
+    }
+}
+
+fn not(arg: Box<Expr>) -> Box<Expr> {
+    Box::new(Expr::Unary(UnaryExpr {
+        span: DUMMY_SP,
+        op: UnaryOp::Bang,
+        arg,
+    }))
+}
+
+/// `if (test) return null;`
+fn guard(test: Box<Expr>) -> Stmt {
+    if_stmt(test, vec![return_stmt(null())], None)
+}
+
+fn if_stmt(test: Box<Expr>, cons: Vec<Stmt>, alt: Option<Vec<Stmt>>) -> Stmt {
+    let block = |mut stmts: Vec<Stmt>| {
+        if stmts.len() == 1 {
+            return Box::new(stmts.remove(0));
+        }
+
+        Box::new(Stmt::Block(BlockStmt {
+            span: DUMMY_SP,
+            ctxt: Default::default(),
+            stmts,
+        }))
+    };
+
+    Stmt::If(IfStmt {
+        span: DUMMY_SP,
+        test,
+        cons: block(cons),
+        alt: alt.map(block),
+    })
+}
+
+fn var_decl(decls: Vec<(&str, Box<Expr>)>) -> Stmt {
+    Stmt::Decl(Decl::Var(Box::new(VarDecl {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        kind: VarDeclKind::Var,
+        declare: false,
+        decls: decls
+            .into_iter()
+            .map(|(name, init)| VarDeclarator {
+                span: DUMMY_SP,
+                name: param(name),
+                init: Some(init),
+                definite: false,
+            })
+            .collect(),
+    })))
+}
+
+fn expr_stmt(expr: Box<Expr>) -> Stmt {
+    Stmt::Expr(ExprStmt {
+        span: DUMMY_SP,
+        expr,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::{
+        AttributeOrBinding, Node, StrOrExpr, VBindDirective, VCustomDirective, VModelDirective,
+        VueDirectives,
+    };
+    use swc_core::common::DUMMY_SP;
+
+    use super::{generate_key_filter, must_use_prop, parse_static_style};
+    use crate::{
+        test_utils::{js, regular_attribute, to_str, v_bind_attribute},
+        vue2::tests::{component, element, test_out, v_on_with_modifiers},
+    };
+
+    #[test]
+    fn it_generates_checkbox_and_radio_models() {
+        // <input type="checkbox" value="a" v-model="checked">
+        let mut checkbox = element(
+            "input",
+            vec![
+                regular_attribute("type", "checkbox"),
+                regular_attribute("value", "a"),
+            ],
+            vec![],
+        );
+        checkbox.starting_tag.directives = Some(v_model("checked", &[]));
+        test_out(
+            Node::Element(checkbox),
+            r#"_c("input",{attrs:{type:"checkbox",value:"a"},domProps:{checked:Array.isArray(checked)?_vm._i(checked,"a")>-1:checked},on:{change:function($event){var $$a=checked,$$el=$event.target,$$c=$$el.checked?true:false;if(Array.isArray($$a)){var $$v="a",$$i=_vm._i($$a,$$v);if($$el.checked)if($$i<0)checked=$$a.concat([$$v]);else if($$i>-1)checked=$$a.slice(0,$$i).concat($$a.slice($$i+1));}else checked=$$c;}}})"#,
+        );
+
+        // <input type="radio" value="b" v-model.number="picked">
+        let mut radio = element(
+            "input",
+            vec![
+                regular_attribute("type", "radio"),
+                regular_attribute("value", "b"),
+            ],
+            vec![],
+        );
+        radio.starting_tag.directives = Some(v_model("picked", &["number"]));
+        test_out(
+            Node::Element(radio),
+            r#"_c("input",{attrs:{type:"radio",value:"b"},domProps:{checked:_vm._q(picked,_vm._n("b"))},on:{change:function($event){picked=_vm._n("b");}}})"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_select_model() {
+        // <select v-model="selected"></select>
+        let mut select = element("select", vec![], vec![]);
+        select.starting_tag.directives = Some(v_model("selected", &[]));
+        test_out(
+            Node::Element(select),
+            r#"_c("select",{directives:[{name:"model",rawName:"v-model",value:selected}],on:{change:function($event){var $$selectedVal=Array.prototype.filter.call($event.target.options,function(o){return o.selected;}).map(function(o){return"_value"in o?o._value:o.value;});selected=$event.target.multiple?$$selectedVal:$$selectedVal[0];}}})"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_component_model_with_argument() {
+        // <Comp v-model:title="title" />
+        let mut comp = component("Comp", vec![], vec![]);
+        let mut directives = v_model("title", &[]);
+        directives.v_model[0].argument = Some(StrOrExpr::Str("title".into()));
+        comp.starting_tag.directives = Some(directives);
+        test_out(
+            Node::Element(comp),
+            r#"_c(Comp,{attrs:{title:title},on:{"update:title":$event=>(title=$event)}})"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_custom_directives_and_dom_props() {
+        // <div v-focus:[arg].lazy="value" v-html="raw" :value="value" v-bind="obj"></div>
+        let mut div = element(
+            "div",
+            vec![
+                v_bind_attribute("value", "value"),
+                AttributeOrBinding::VBind(VBindDirective {
+                    argument: None,
+                    value: js("obj"),
+                    is_camel: false,
+                    is_prop: false,
+                    is_attr: false,
+                    span: DUMMY_SP,
+                }),
+            ],
+            vec![],
+        );
+        div.starting_tag.directives = Some(Box::new(VueDirectives {
+            custom: vec![VCustomDirective {
+                name: "focus".into(),
+                argument: Some(StrOrExpr::Expr(js("arg"))),
+                modifiers: vec!["lazy".into()],
+                value: Some(js("value")),
+            }],
+            v_html: Some(js("raw")),
+            ..Default::default()
+        }));
+        test_out(
+            Node::Element(div),
+            r#"_c("div",_vm._b({directives:[{name:"focus",rawName:"v-focus.lazy",value:value,arg:arg,isDynamicArg:true,modifiers:{lazy:true}}],attrs:{value:value},domProps:{innerHTML:raw}},"div",obj,false))"#,
+        );
+
+        // <input :value="value" :checked="checked">
+        let input = element(
+            "input",
+            vec![
+                v_bind_attribute("value", "value"),
+                v_bind_attribute("checked", "checked"),
+            ],
+            vec![],
+        );
+        test_out(
+            Node::Element(input),
+            r#"_c("input",{domProps:{value:value,checked:checked}})"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_listener_modifiers() {
+        // <button @click.ctrl.exact.prevent="onClick" @keydown.13="onEnter" @contextmenu.right="onMenu">
+        let button = element(
+            "button",
+            vec![
+                v_on_with_modifiers("click", "onClick", &["ctrl", "exact", "prevent"]),
+                v_on_with_modifiers("keydown", "onEnter", &["13"]),
+                v_on_with_modifiers("click", "onMenu", &["right"]),
+            ],
+            vec![],
+        );
+        test_out(
+            Node::Element(button),
+            r#"_c("button",{on:{click:function($event){if(!$event.ctrlKey)return null;if($event.shiftKey||$event.altKey||$event.metaKey)return null;$event.preventDefault();return onClick.apply(null,arguments);},keydown:function($event){if(!$event.type.indexOf("key")&&$event.keyCode!==13)return null;return onEnter.apply(null,arguments);},contextmenu:onMenu}})"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_key_filters() {
+        assert_eq!(to_str(generate_key_filter("13")), "$event.keyCode!==13");
+        assert_eq!(
+            to_str(generate_key_filter("esc")),
+            r#"_vm._k($event.keyCode,"esc",27,$event.key,["Esc","Escape"])"#
+        );
+        assert_eq!(
+            to_str(generate_key_filter("a")),
+            r#"_vm._k($event.keyCode,"a",undefined,$event.key,undefined)"#
+        );
+    }
+
+    #[test]
+    fn it_parses_static_style() {
+        assert_eq!(
+            to_str(parse_static_style("color: red; width:10px;")),
+            r#"{"color":"red","width":"10px"}"#
+        );
+    }
+
+    #[test]
+    fn it_checks_dom_props() {
+        assert!(must_use_prop("input", Some("text"), "value"));
+        assert!(!must_use_prop("input", Some("button"), "value"));
+        assert!(must_use_prop("option", None, "selected"));
+        assert!(must_use_prop("video", None, "muted"));
+        assert!(!must_use_prop("div", None, "value"));
+    }
+
+    fn v_model(value: &str, modifiers: &[&str]) -> Box<VueDirectives> {
+        Box::new(VueDirectives {
+            v_model: vec![VModelDirective {
+                argument: None,
+                value: js(value),
+                update_handler: Some(js(&format!("$event => ({value} = $event)"))),
+                modifiers: modifiers.iter().map(|&modifier| modifier.into()).collect(),
+                span: DUMMY_SP,
+            }],
+            ..Default::default()
+        })
+    }
+}
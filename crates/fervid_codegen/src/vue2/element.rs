@@ -0,0 +1,600 @@
+use fervid_core::{
+    AttributeOrBinding, BuiltinType, ComponentBinding, ElementKind, ElementNode, FervidAtom, Node,
+    StrOrExpr, VBindDirective, VSlotDirective,
+};
+use swc_core::ecma::ast::{Expr, Pat};
+
+use super::{
+    array, function, key_value, null, num_lit, object, return_stmt, str_lit, vm_call, Vue2Generator,
+};
+use crate::utils::to_camelcase;
+
+impl Vue2Generator<'_> {
+    pub(super) fn generate_element_node(&mut self, element_node: &ElementNode) -> Box<Expr> {
+        if has_v_for(element_node) {
+            return self.generate_for(element_node);
+        }
+
+        self.generate_element_item(element_node)
+    }
+
+    /// Generates an element ignoring its `v-for`
+    fn generate_element_item(&mut self, element_node: &ElementNode) -> Box<Expr> {
+        match element_node.kind {
+            ElementKind::Builtin(BuiltinType::Slot) => self.generate_slot_outlet(element_node),
+            ElementKind::Element if is_template(element_node) => {
+                array(self.generate_children(&element_node.children))
+            }
+            ElementKind::Element => self.generate_element(element_node, None),
+            ElementKind::Builtin(_) | ElementKind::Component => {
+                let tag = self.generate_component_tag(element_node);
+                self.generate_element(element_node, Some(tag))
+            }
+        }
+    }
+
+    /// `_c(tag, data, children, normalizationType)`, `component_tag` is only present for the components
+    fn generate_element(
+        &mut self,
+        element_node: &ElementNode,
+        component_tag: Option<Box<Expr>>,
+    ) -> Box<Expr> {
+        let is_component = component_tag.is_some();
+        let directives = element_node.starting_tag.directives.as_deref();
+
+        // Components get their named and scoped slots in the data, the rest are the children.
+        // `v-html` and `v-text` replace the children of an element
+        let mut child_nodes: Vec<&Node> = Vec::new();
+        let mut slots: Vec<(&VSlotDirective, &[Node])> = Vec::new();
+        if is_component {
+            let component_v_slot = directives.and_then(|directives| directives.v_slot.as_ref());
+            if let Some(v_slot) = component_v_slot {
+                slots.push((v_slot, &element_node.children));
+            } else {
+                for child in element_node.children.iter() {
+                    match child {
+                        Node::Element(child_element) if is_template(child_element) => {
+                            match child_element
+                                .starting_tag
+                                .directives
+                                .as_ref()
+                                .and_then(|directives| directives.v_slot.as_ref())
+                            {
+                                Some(v_slot) => slots.push((v_slot, &child_element.children)),
+                                None => child_nodes.push(child),
+                            }
+                        }
+                        _ => child_nodes.push(child),
+                    }
+                }
+            }
+        } else if directives
+            .is_none_or(|directives| directives.v_html.is_none() && directives.v_text.is_none())
+        {
+            child_nodes.extend(element_node.children.iter());
+        }
+
+        let scoped_slots = self.generate_scoped_slots(&slots);
+        let data = self.generate_data(element_node, is_component, scoped_slots);
+
+        let tag =
+            component_tag.unwrap_or_else(|| str_lit(element_node.starting_tag.tag_name.to_owned()));
+        let mut args = vec![tag];
+        args.extend(data);
+        args.extend(self.generate_children_arg(&child_nodes));
+        vm_call_c(args)
+    }
+
+    /// The children and the normalization type of `_c`.
+    /// A single `v-for` child is passed without the wrapping array
+    #[allow(clippy::vec_box)]
+    fn generate_children_arg(&mut self, child_nodes: &[&Node]) -> Vec<Box<Expr>> {
+        let child_nodes = trim_whitespace(child_nodes.to_vec());
+
+        if let [Node::Element(child_element)] = child_nodes.as_slice() {
+            if has_v_for(child_element) && !needs_normalization(child_element) {
+                let normalization = if is_component(child_element) { 1 } else { 0 };
+                return vec![
+                    self.generate_element_node(child_element),
+                    num_lit(normalization as f64),
+                ];
+            }
+        }
+
+        if child_nodes.is_empty() {
+            return vec![];
+        }
+
+        let normalization = normalization_type(&child_nodes);
+        let mut args = vec![array(self.generate_children(child_nodes))];
+        if normalization != 0 {
+            args.push(num_lit(normalization as f64));
+        }
+        args
+    }
+
+    /// Adjacent texts and interpolations become a single text node,
+    /// comments are skipped and `<template>`s without directives are unwrapped
+    #[allow(clippy::vec_box)]
+    pub(super) fn generate_children<'n>(
+        &mut self,
+        nodes: impl IntoIterator<Item = &'n Node>,
+    ) -> Vec<Box<Expr>> {
+        let mut out = Vec::new();
+        let mut text_parts: Vec<&Node> = Vec::new();
+        self.push_children(nodes, &mut text_parts, &mut out);
+        if !text_parts.is_empty() {
+            out.push(self.generate_text(&text_parts));
+        }
+        out
+    }
+
+    #[allow(clippy::vec_box)]
+    fn push_children<'n>(
+        &mut self,
+        nodes: impl IntoIterator<Item = &'n Node>,
+        text_parts: &mut Vec<&'n Node>,
+        out: &mut Vec<Box<Expr>>,
+    ) {
+        for node in nodes {
+            match node {
+                Node::Text(..) | Node::Interpolation(_) => {
+                    text_parts.push(node);
+                    continue;
+                }
+                Node::Comment(..) => continue,
+                _ => {}
+            }
+
+            if !text_parts.is_empty() {
+                out.push(self.generate_text(text_parts));
+                text_parts.clear();
+            }
+
+            match node {
+                Node::Element(element_node) if is_fragment_template(element_node) => {
+                    self.push_children(element_node.children.iter(), text_parts, out);
+                }
+                _ => out.push(self.generate_node(node)),
+            }
+        }
+    }
+
+    /// `_vm._l(list, function(item, index) { return _c("li") })`
+    fn generate_for(&mut self, element_node: &ElementNode) -> Box<Expr> {
+        let Some(v_for) = element_node
+            .starting_tag
+            .directives
+            .as_ref()
+            .and_then(|directives| directives.v_for.as_ref())
+        else {
+            unreachable!("generate_for is only called for v-for elements")
+        };
+
+        // `(item, index)` -> `function(item, index)`
+        let params = match unwrap_parens(&v_for.itervar) {
+            Expr::Seq(seq) => seq.exprs.iter().map(|expr| expr_to_pat(expr)).collect(),
+            itervar => vec![expr_to_pat(itervar)],
+        };
+
+        self.for_depth += 1;
+        let item = self.generate_element_item(element_node);
+        self.for_depth -= 1;
+
+        vm_call(
+            "_l",
+            [
+                v_for.iterable.to_owned(),
+                function(params, vec![return_stmt(item)]),
+            ],
+        )
+    }
+
+    /// `_vm._t("name", function() { return [fallback] }, { prop: value }, bindObject)`
+    fn generate_slot_outlet(&mut self, element_node: &ElementNode) -> Box<Expr> {
+        let mut name = str_lit("default");
+        let mut props = Vec::new();
+        let mut bind_object = None;
+
+        for attr in element_node.starting_tag.attributes.iter() {
+            let (prop_name, value) = match attr {
+                AttributeOrBinding::RegularAttribute {
+                    name: attr_name,
+                    value,
+                    ..
+                } => (attr_name, str_lit(value.to_owned())),
+                AttributeOrBinding::VBind(VBindDirective {
+                    argument: Some(StrOrExpr::Str(argument)),
+                    value,
+                    ..
+                }) => (argument, value.to_owned()),
+                AttributeOrBinding::VBind(VBindDirective {
+                    argument: None,
+                    value,
+                    ..
+                }) => {
+                    bind_object = Some(value.to_owned());
+                    continue;
+                }
+                _ => continue,
+            };
+
+            if prop_name == "name" {
+                name = value;
+                continue;
+            }
+
+            props.push(key_value(camelize(prop_name), value));
+        }
+
+        let child_nodes = trim_whitespace(element_node.children.iter().collect());
+        let fallback = (!child_nodes.is_empty()).then(|| {
+            let children = self.generate_children(child_nodes);
+            function(vec![], vec![return_stmt(array(children))])
+        });
+
+        let mut args = vec![name];
+        match (fallback, props.is_empty(), bind_object) {
+            (fallback, _, Some(bind_object)) => {
+                args.push(fallback.unwrap_or_else(null));
+                args.push(if props.is_empty() {
+                    null()
+                } else {
+                    object(props)
+                });
+                args.push(bind_object);
+            }
+            (fallback, false, None) => {
+                args.push(fallback.unwrap_or_else(null));
+                args.push(object(props));
+            }
+            (Some(fallback), true, None) => args.push(fallback),
+            (None, true, None) => {}
+        }
+
+        vm_call("_t", args)
+    }
+
+    /// Resolved components are referenced directly, the rest are resolved by the Vue 2 runtime by name
+    fn generate_component_tag(&mut self, element_node: &ElementNode) -> Box<Expr> {
+        let tag_name = &element_node.starting_tag.tag_name;
+
+        match element_node.kind {
+            // `<component :is="comp">`
+            ElementKind::Builtin(BuiltinType::Component) => element_node
+                .starting_tag
+                .attributes
+                .iter()
+                .find_map(|attr| match attr {
+                    AttributeOrBinding::RegularAttribute { name, value, .. } if name == "is" => {
+                        Some(str_lit(value.to_owned()))
+                    }
+                    AttributeOrBinding::VBind(VBindDirective {
+                        argument: Some(StrOrExpr::Str(argument)),
+                        value,
+                        ..
+                    }) if argument == "is" => Some(value.to_owned()),
+                    _ => None,
+                })
+                .unwrap_or_else(null),
+
+            ElementKind::Builtin(BuiltinType::KeepAlive) => str_lit("keep-alive"),
+            ElementKind::Builtin(BuiltinType::Transition) => str_lit("transition"),
+            ElementKind::Builtin(BuiltinType::TransitionGroup) => str_lit("transition-group"),
+
+            _ => match self.ctx.bindings_helper.components.get(tag_name) {
+                Some(ComponentBinding::Resolved(component)) => component.to_owned(),
+                _ => str_lit(tag_name.to_owned()),
+            },
+        }
+    }
+
+    /// `_vm._u([{ key: "header", fn: function(props) { return [/*...*/] } }])`.
+    /// The slots without props are `proxy`ed to `$slots` as well
+    fn generate_scoped_slots(&mut self, slots: &[(&VSlotDirective, &[Node])]) -> Option<Box<Expr>> {
+        if slots.is_empty() {
+            return None;
+        }
+
+        let mut has_dynamic_name = false;
+        let mut scoped_slots = Vec::with_capacity(slots.len());
+        for (v_slot, nodes) in slots.iter() {
+            let key = match v_slot.slot_name {
+                Some(StrOrExpr::Str(ref name)) => str_lit(name.to_owned()),
+                Some(StrOrExpr::Expr(ref name)) => {
+                    has_dynamic_name = true;
+                    name.to_owned()
+                }
+                None => str_lit("default"),
+            };
+
+            let params: Vec<Pat> = v_slot
+                .value
+                .as_deref()
+                .map(|value| vec![value.to_owned()])
+                .unwrap_or_default();
+            let is_proxy = params.is_empty();
+
+            let children = self.generate_children(trim_whitespace(nodes.iter().collect()));
+            let mut props = vec![
+                key_value("key", key),
+                key_value("fn", function(params, vec![return_stmt(array(children))])),
+            ];
+            if is_proxy {
+                props.push(key_value("proxy", super::bool_lit(true)));
+            }
+            scoped_slots.push(object(props));
+        }
+
+        // Dynamic slot names force the update of the child
+        let mut args = vec![array(scoped_slots)];
+        if has_dynamic_name {
+            args.push(null());
+            args.push(super::bool_lit(true));
+        }
+
+        Some(vm_call("_u", args))
+    }
+}
+
+/// `_c(args)`
+#[allow(clippy::vec_box)]
+fn vm_call_c(args: Vec<Box<Expr>>) -> Box<Expr> {
+    super::call(super::ident("_c"), args)
+}
+
+pub(super) fn is_template(element_node: &ElementNode) -> bool {
+    matches!(element_node.kind, ElementKind::Element)
+        && element_node.starting_tag.tag_name == "template"
+}
+
+/// `<template>` which only groups its children
+fn is_fragment_template(element_node: &ElementNode) -> bool {
+    is_template(element_node)
+        && element_node
+            .starting_tag
+            .directives
+            .as_ref()
+            .is_none_or(|directives| directives.v_for.is_none() && directives.v_slot.is_none())
+}
+
+fn has_v_for(element_node: &ElementNode) -> bool {
+    element_node
+        .starting_tag
+        .directives
+        .as_ref()
+        .is_some_and(|directives| directives.v_for.is_some())
+}
+
+fn is_component(element_node: &ElementNode) -> bool {
+    matches!(
+        element_node.kind,
+        ElementKind::Component | ElementKind::Builtin(_)
+    ) && !matches!(element_node.kind, ElementKind::Builtin(BuiltinType::Slot))
+}
+
+/// Nodes rendering arrays which are flattened by the runtime
+fn needs_normalization(element_node: &ElementNode) -> bool {
+    is_template(element_node)
+        || matches!(element_node.kind, ElementKind::Builtin(BuiltinType::Slot))
+}
+
+/// `2` when the children render arrays, `1` when a component may be functional and `0` otherwise
+fn normalization_type(child_nodes: &[&Node]) -> u8 {
+    let mut branches = Vec::new();
+    for node in child_nodes {
+        match node {
+            Node::Element(element_node) => branches.push(element_node),
+            Node::ConditionalSeq(conditional_seq) => {
+                branches.push(&conditional_seq.if_node.node);
+                branches.extend(conditional_seq.else_if_nodes.iter().map(|c| &c.node));
+                branches.extend(conditional_seq.else_node.as_deref());
+            }
+            _ => {}
+        }
+    }
+
+    if branches
+        .iter()
+        .any(|element_node| has_v_for(element_node) || needs_normalization(element_node))
+    {
+        2
+    } else if branches
+        .iter()
+        .any(|element_node| is_component(element_node))
+    {
+        1
+    } else {
+        0
+    }
+}
+
+/// Drops the leading and trailing whitespace-only nodes,
+/// whitespace between the other nodes is significant
+fn trim_whitespace(mut nodes: Vec<&Node>) -> Vec<&Node> {
+    while nodes.last().is_some_and(is_whitespace) {
+        nodes.pop();
+    }
+    let leading = nodes.iter().take_while(|node| is_whitespace(node)).count();
+    nodes.drain(..leading);
+    nodes
+}
+
+fn is_whitespace(node: &&Node) -> bool {
+    match node {
+        Node::Text(text, _) => text.trim().is_empty(),
+        Node::Comment(..) => true,
+        _ => false,
+    }
+}
+
+fn unwrap_parens(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Paren(paren) => unwrap_parens(&paren.expr),
+        _ => expr,
+    }
+}
+
+/// `v-for` variables are either identifiers or destructuring patterns
+fn expr_to_pat(expr: &Expr) -> Pat {
+    match expr {
+        Expr::Ident(ident) => Pat::Ident(ident.to_owned().into()),
+        _ => Pat::Expr(Box::new(expr.to_owned())),
+    }
+}
+
+pub(super) fn camelize(name: &str) -> FervidAtom {
+    let mut out = String::with_capacity(name.len());
+    let _ = to_camelcase(name, &mut out);
+    FervidAtom::from(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::{
+        BuiltinType, Conditional, ConditionalNodeSequence, ElementKind, ElementNode, Node,
+        StrOrExpr, VForDirective, VSlotDirective, VueDirectives,
+    };
+    use swc_core::{common::DUMMY_SP, ecma::ast::Pat};
+
+    use super::{camelize, normalization_type, trim_whitespace};
+    use crate::{
+        test_utils::{js, regular_attribute, v_bind_attribute},
+        vue2::tests::{component, element, interpolation, test_out},
+    };
+
+    #[test]
+    fn it_generates_dynamic_and_builtin_components() {
+        // <component :is="view" :msg="msg" />
+        let dynamic = ElementNode {
+            kind: ElementKind::Builtin(BuiltinType::Component),
+            ..element(
+                "component",
+                vec![
+                    v_bind_attribute("is", "view"),
+                    v_bind_attribute("msg", "msg"),
+                ],
+                vec![],
+            )
+        };
+        test_out(
+            Node::Element(dynamic),
+            r#"_c(view,{tag:"component",attrs:{msg:msg}})"#,
+        );
+
+        // <keep-alive><Comp /><unknown-comp /></keep-alive>
+        let keep_alive = ElementNode {
+            kind: ElementKind::Builtin(BuiltinType::KeepAlive),
+            ..element(
+                "keep-alive",
+                vec![],
+                vec![
+                    Node::Element(component("Comp", vec![], vec![])),
+                    Node::Element(component("unknown-comp", vec![], vec![])),
+                ],
+            )
+        };
+        test_out(
+            Node::Element(keep_alive),
+            r#"_c("keep-alive",[_c(Comp),_c("unknown-comp")],1)"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_template_v_for_and_fragments() {
+        // <div><template v-for="item in list"><span>{{ item }}</span></template><template><b>a</b></template></div>
+        let mut for_template = element(
+            "template",
+            vec![],
+            vec![Node::Element(element(
+                "span",
+                vec![],
+                vec![interpolation("item")],
+            ))],
+        );
+        for_template.starting_tag.directives = Some(Box::new(VueDirectives {
+            v_for: Some(VForDirective {
+                iterable: js("list"),
+                itervar: js("item"),
+                patch_flags: Default::default(),
+                span: DUMMY_SP,
+            }),
+            ..Default::default()
+        }));
+        let fragment = element(
+            "template",
+            vec![],
+            vec![Node::Element(element(
+                "b",
+                vec![],
+                vec![Node::Text("a".into(), DUMMY_SP)],
+            ))],
+        );
+
+        let div = element(
+            "div",
+            vec![],
+            vec![Node::Element(for_template), Node::Element(fragment)],
+        );
+        test_out(
+            Node::Element(div),
+            r#"_c("div",[_vm._l(list,function(item){return[_c("span",[_vm._v(_vm._s(item))])];}),_c("b",[_vm._v("a")])],2)"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_dynamic_slot_names() {
+        // <Comp><template #[name]="props">{{ props.text }}</template></Comp>
+        let mut slot = element("template", vec![], vec![interpolation("props.text")]);
+        slot.starting_tag.directives = Some(Box::new(VueDirectives {
+            v_slot: Some(VSlotDirective {
+                slot_name: Some(StrOrExpr::Expr(js("name"))),
+                value: Some(Box::new(Pat::Expr(js("props")))),
+            }),
+            ..Default::default()
+        }));
+        let comp = component("Comp", vec![], vec![Node::Element(slot)]);
+        test_out(
+            Node::Element(comp),
+            r#"_c(Comp,{scopedSlots:_vm._u([{key:name,fn:function(props){return[_vm._v(_vm._s(props.text))];}}],null,true)})"#,
+        );
+    }
+
+    #[test]
+    fn it_computes_normalization_type() {
+        let plain = Node::Element(element("span", vec![], vec![]));
+        let comp = Node::Element(component("Comp", vec![], vec![]));
+        let template = Node::Element(element("template", vec![], vec![]));
+        let conditional_comp = Node::ConditionalSeq(ConditionalNodeSequence {
+            if_node: Box::new(Conditional {
+                condition: *js("ok"),
+                node: element("span", vec![], vec![]),
+            }),
+            else_if_nodes: vec![],
+            else_node: Some(Box::new(component("Comp", vec![], vec![]))),
+        });
+
+        assert_eq!(normalization_type(&[&plain]), 0);
+        assert_eq!(normalization_type(&[&plain, &comp]), 1);
+        assert_eq!(normalization_type(&[&conditional_comp]), 1);
+        assert_eq!(normalization_type(&[&comp, &template]), 2);
+    }
+
+    #[test]
+    fn it_trims_whitespace() {
+        let space = Node::Text("\n  ".into(), DUMMY_SP);
+        let text = Node::Text("a".into(), DUMMY_SP);
+        let span = Node::Element(element("span", vec![regular_attribute("id", "x")], vec![]));
+
+        let trimmed = trim_whitespace(vec![&space, &text, &space, &span, &space]);
+        assert_eq!(trimmed.len(), 3);
+        assert!(matches!(trimmed[1], Node::Text(text, _) if text.trim().is_empty()));
+        assert!(trim_whitespace(vec![&space, &space]).is_empty());
+    }
+
+    #[test]
+    fn it_camelizes() {
+        assert_eq!(camelize("item-data"), "itemData");
+        assert_eq!(camelize("title"), "title");
+    }
+}
@@ -0,0 +1,603 @@
+//! Code generation for Vue 2.7, i.e. the components compiled with `target: Vue2`.
+//!
+//! The template becomes the `render` function of the Vue 2 template compiler:
+//! the nodes are created with `_c(tag, data, children)`, the helpers are called on the instance `_vm`
+//! (e.g. `_vm._v(_vm._s(msg))` for a text) and the scoped slots are passed as `scopedSlots`.
+//! The expressions are transformed in the `RenderFn` mode, their prefixes are renamed afterwards,
+//! e.g. `$setup.msg` -> `_setup.msg` and `_ctx.foo` -> `_vm.foo`.
+
+use fervid_core::{
+    atom_to_propname, ConditionalNodeSequence, FervidAtom, IntoIdent, Node, SfcTemplateBlock,
+};
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::{
+        ast::{
+            ArrayLit, BinExpr, BinaryOp, BindingIdent, BlockStmt, CallExpr, Callee, CondExpr, Decl,
+            Expr, ExprOrSpread, FnExpr, Function, IdentName, KeyValueProp, Lit, MemberExpr,
+            MemberProp, Null, ObjectLit, Param, ParenExpr, Pat, Prop, PropOrSpread, ReturnStmt,
+            Stmt, Str, ThisExpr, VarDecl, VarDeclKind, VarDeclarator,
+        },
+        visit::{VisitMut, VisitMutWith},
+    },
+};
+
+use crate::CodegenContext;
+
+mod data;
+mod element;
+
+impl CodegenContext {
+    /// Generates the render expression of a template transformed for Vue 2.
+    /// Vue 2 components have a single root, the extra roots are reported by the transform.
+    pub fn generate_vue2_template(&mut self, sfc_template: &SfcTemplateBlock) -> Option<Expr> {
+        let root = sfc_template
+            .roots
+            .iter()
+            .find(|root| !matches!(root, Node::Comment(..)))?;
+
+        let mut generator = Vue2Generator {
+            ctx: self,
+            for_depth: 0,
+        };
+        Some(*generator.generate_node(root))
+    }
+
+    /// Wraps the render expression in
+    /// `render() { var _vm = this, _c = _vm._self._c, _setup = _vm._self._setupProxy; return /*...*/ }`
+    pub(crate) fn generate_vue2_render_fn(&mut self, mut template_expr: Expr) -> Function {
+        template_expr.visit_mut_with(&mut PrefixRenamer);
+
+        let self_member = |prop: &str| member(member(ident("_vm"), "_self"), prop);
+        let declarator = |name: &str, init: Box<Expr>| VarDeclarator {
+            span: DUMMY_SP,
+            name: param(name),
+            init: Some(init),
+            definite: false,
+        };
+
+        let vars = Stmt::Decl(Decl::Var(Box::new(VarDecl {
+            span: DUMMY_SP,
+            ctxt: Default::default(),
+            kind: VarDeclKind::Var,
+            declare: false,
+            decls: vec![
+                declarator("_vm", Box::new(Expr::This(ThisExpr { span: DUMMY_SP }))),
+                declarator("_c", self_member("_c")),
+                declarator("_setup", self_member("_setupProxy")),
+            ],
+        })));
+
+        Function {
+            params: vec![],
+            decorators: vec![],
+            span: DUMMY_SP,
+            ctxt: Default::default(),
+            body: Some(BlockStmt {
+                span: DUMMY_SP,
+                ctxt: Default::default(),
+                stmts: vec![vars, return_stmt(Box::new(template_expr))],
+            }),
+            is_generator: false,
+            is_async: false,
+            type_params: None,
+            return_type: None,
+        }
+    }
+}
+
+struct Vue2Generator<'c> {
+    ctx: &'c CodegenContext,
+    /// How many `v-for`s the current node is in, used for `refInFor`
+    for_depth: usize,
+}
+
+impl Vue2Generator<'_> {
+    fn generate_node(&mut self, node: &Node) -> Box<Expr> {
+        match node {
+            Node::Element(element_node) => self.generate_element_node(element_node),
+            Node::ConditionalSeq(conditional_seq) => self.generate_conditional(conditional_seq),
+            Node::Text(..) | Node::Interpolation(_) => self.generate_text(&[node]),
+            Node::Comment(..) => vm_call("_e", []),
+        }
+    }
+
+    /// `ok ? _c("p") : other ? _c("span") : _vm._e()`
+    fn generate_conditional(&mut self, conditional_seq: &ConditionalNodeSequence) -> Box<Expr> {
+        let mut alternate = match conditional_seq.else_node {
+            Some(ref else_node) => self.generate_element_node(else_node),
+            None => vm_call("_e", []),
+        };
+
+        for conditional in conditional_seq
+            .else_if_nodes
+            .iter()
+            .rev()
+            .chain(std::iter::once(conditional_seq.if_node.as_ref()))
+        {
+            alternate = Box::new(Expr::Cond(CondExpr {
+                span: DUMMY_SP,
+                test: Box::new(conditional.condition.to_owned()),
+                cons: self.generate_element_node(&conditional.node),
+                alt: alternate,
+            }));
+        }
+
+        alternate
+    }
+
+    /// `_vm._v("Hello " + _vm._s(msg))`
+    fn generate_text(&mut self, parts: &[&Node]) -> Box<Expr> {
+        let mut result: Option<Box<Expr>> = None;
+        for part in parts {
+            let expr = match part {
+                Node::Text(text, _) => str_lit(crate::text::shorten_whitespace(text)),
+                Node::Interpolation(interpolation) => {
+                    vm_call("_s", [interpolation.value.to_owned()])
+                }
+                _ => continue,
+            };
+
+            result = Some(match result {
+                Some(left) => bin(BinaryOp::Add, left, expr),
+                None => expr,
+            });
+        }
+
+        vm_call("_v", [result.unwrap_or_else(|| str_lit(""))])
+    }
+}
+
+/// Renames the prefixes of the `RenderFn` mode to the variables of the Vue 2 render function
+struct PrefixRenamer;
+
+impl VisitMut for PrefixRenamer {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        expr.visit_mut_children_with(self);
+
+        let Expr::Ident(ref mut expr_ident) = expr else {
+            return;
+        };
+
+        match expr_ident.sym.as_ref() {
+            "$setup" => expr_ident.sym = FervidAtom::from("_setup"),
+            "_ctx" | "$props" | "$data" => expr_ident.sym = FervidAtom::from("_vm"),
+            "$options" => *expr = *member(ident("_vm"), "$options"),
+            _ => {}
+        }
+    }
+}
+
+fn ident(sym: &str) -> Box<Expr> {
+    Box::new(Expr::Ident(FervidAtom::from(sym).into_ident()))
+}
+
+fn param(sym: &str) -> Pat {
+    Pat::Ident(BindingIdent {
+        id: FervidAtom::from(sym).into_ident(),
+        type_ann: None,
+    })
+}
+
+fn str_lit(value: impl Into<FervidAtom>) -> Box<Expr> {
+    Box::new(Expr::Lit(Lit::Str(Str {
+        span: DUMMY_SP,
+        value: value.into(),
+        raw: None,
+    })))
+}
+
+fn num_lit(value: f64) -> Box<Expr> {
+    Box::new(Expr::Lit(Lit::Num(value.into())))
+}
+
+fn bool_lit(value: bool) -> Box<Expr> {
+    Box::new(Expr::Lit(Lit::Bool(value.into())))
+}
+
+fn null() -> Box<Expr> {
+    Box::new(Expr::Lit(Lit::Null(Null { span: DUMMY_SP })))
+}
+
+fn paren(expr: Box<Expr>) -> Box<Expr> {
+    Box::new(Expr::Paren(ParenExpr {
+        span: DUMMY_SP,
+        expr,
+    }))
+}
+
+fn bin(op: BinaryOp, left: Box<Expr>, right: Box<Expr>) -> Box<Expr> {
+    Box::new(Expr::Bin(BinExpr {
+        span: DUMMY_SP,
+        op,
+        left,
+        right,
+    }))
+}
+
+/// `obj.prop`
+fn member(obj: Box<Expr>, prop: &str) -> Box<Expr> {
+    Box::new(Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj,
+        prop: MemberProp::Ident(IdentName {
+            span: DUMMY_SP,
+            sym: FervidAtom::from(prop),
+        }),
+    }))
+}
+
+fn call(callee: Box<Expr>, args: impl IntoIterator<Item = Box<Expr>>) -> Box<Expr> {
+    Box::new(Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        callee: Callee::Expr(callee),
+        args: args
+            .into_iter()
+            .map(|expr| ExprOrSpread { spread: None, expr })
+            .collect(),
+        type_args: None,
+    }))
+}
+
+/// `_vm._helper(args)`
+fn vm_call(helper: &str, args: impl IntoIterator<Item = Box<Expr>>) -> Box<Expr> {
+    call(member(ident("_vm"), helper), args)
+}
+
+fn array(elems: impl IntoIterator<Item = Box<Expr>>) -> Box<Expr> {
+    Box::new(Expr::Array(ArrayLit {
+        span: DUMMY_SP,
+        elems: elems
+            .into_iter()
+            .map(|expr| Some(ExprOrSpread { spread: None, expr }))
+            .collect(),
+    }))
+}
+
+fn object(props: Vec<PropOrSpread>) -> Box<Expr> {
+    Box::new(Expr::Object(ObjectLit {
+        span: DUMMY_SP,
+        props,
+    }))
+}
+
+/// `key: value`, the key is quoted when needed
+fn key_value(key: impl Into<FervidAtom>, value: Box<Expr>) -> PropOrSpread {
+    PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+        key: atom_to_propname(key.into(), DUMMY_SP),
+        value,
+    })))
+}
+
+/// `function(params) { stmts }`, Vue 2 handlers rely on `arguments`
+fn function(params: Vec<Pat>, stmts: Vec<Stmt>) -> Box<Expr> {
+    Box::new(Expr::Fn(FnExpr {
+        ident: None,
+        function: Box::new(Function {
+            params: params
+                .into_iter()
+                .map(|pat| Param {
+                    span: DUMMY_SP,
+                    decorators: vec![],
+                    pat,
+                })
+                .collect(),
+            decorators: vec![],
+            span: DUMMY_SP,
+            ctxt: Default::default(),
+            body: Some(BlockStmt {
+                span: DUMMY_SP,
+                ctxt: Default::default(),
+                stmts,
+            }),
+            is_generator: false,
+            is_async: false,
+            type_params: None,
+            return_type: None,
+        }),
+    }))
+}
+
+fn return_stmt(arg: Box<Expr>) -> Stmt {
+    Stmt::Return(ReturnStmt {
+        span: DUMMY_SP,
+        arg: Some(arg),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::{
+        AttributeOrBinding, BuiltinType, ComponentBinding, Conditional, ElementKind, ElementNode,
+        Interpolation, StartingTag, StrOrExpr, VForDirective, VModelDirective, VOnDirective,
+        VSlotDirective, VueDirectives,
+    };
+
+    use super::*;
+    use crate::test_utils::{js, regular_attribute, to_str, v_bind_attribute, v_on_attribute};
+
+    #[test]
+    fn it_generates_element_with_text_and_bindings() {
+        // <div class="a" :class="b" style="color: red" :id="$setup.id" @click="$setup.onClick">Hello {{ $setup.msg }}!</div>
+        let div = element(
+            "div",
+            vec![
+                regular_attribute("class", "a"),
+                v_bind_attribute("class", "b"),
+                regular_attribute("style", "color: red"),
+                v_bind_attribute("id", "$setup.id"),
+                v_on_attribute("click", "$setup.onClick"),
+            ],
+            vec![
+                Node::Text("Hello ".into(), DUMMY_SP),
+                interpolation("$setup.msg"),
+                Node::Text("!".into(), DUMMY_SP),
+            ],
+        );
+
+        test_out(
+            Node::Element(div),
+            r#"_c("div",{staticClass:"a",class:b,staticStyle:{"color":"red"},attrs:{id:_setup.id},on:{click:_setup.onClick}},[_vm._v("Hello "+_vm._s(_setup.msg)+"!")])"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_event_modifiers() {
+        // <input @keyup.enter.stop="_ctx.submit" @scroll.passive.once="onScroll" @[name]="onDynamic">
+        let input = element(
+            "input",
+            vec![
+                v_on_with_modifiers("keyup", "_ctx.submit", &["enter", "stop"]),
+                v_on_with_modifiers("scroll", "onScroll", &["passive", "once"]),
+                AttributeOrBinding::VOn(VOnDirective {
+                    event: Some(StrOrExpr::Expr(js("name"))),
+                    handler: Some(js("onDynamic")),
+                    modifiers: vec![],
+                    span: DUMMY_SP,
+                }),
+            ],
+            vec![],
+        );
+
+        test_out(
+            Node::Element(input),
+            r#"_c("input",{on:_vm._d({keyup:function($event){if(!$event.type.indexOf("key")&&_vm._k($event.keyCode,"enter",13,$event.key,"Enter"))return null;$event.stopPropagation();return _vm.submit.apply(null,arguments);},"&~scroll":onScroll},[name,onDynamic])})"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_v_if_and_v_for() {
+        // <ul><li v-for="(item, index) in $setup.list" :key="item.id" ref="items">{{ index }}</li></ul>
+        // <p v-if="ok">yes</p><p v-else-if="maybe">maybe</p>
+        let mut li = element(
+            "li",
+            vec![
+                v_bind_attribute("key", "item.id"),
+                regular_attribute("ref", "items"),
+            ],
+            vec![interpolation("index")],
+        );
+        li.starting_tag.directives = Some(Box::new(VueDirectives {
+            v_for: Some(VForDirective {
+                iterable: js("$setup.list"),
+                itervar: js("(item, index)"),
+                patch_flags: Default::default(),
+                span: DUMMY_SP,
+            }),
+            ..Default::default()
+        }));
+
+        let conditional_seq = ConditionalNodeSequence {
+            if_node: Box::new(Conditional {
+                condition: *js("_ctx.ok"),
+                node: element("p", vec![], vec![Node::Text("yes".into(), DUMMY_SP)]),
+            }),
+            else_if_nodes: vec![Conditional {
+                condition: *js("$props.maybe"),
+                node: element("p", vec![], vec![Node::Text("maybe".into(), DUMMY_SP)]),
+            }],
+            else_node: None,
+        };
+
+        let div = element(
+            "div",
+            vec![],
+            vec![
+                Node::Element(element("ul", vec![], vec![Node::Element(li)])),
+                Node::ConditionalSeq(conditional_seq),
+            ],
+        );
+
+        test_out(
+            Node::Element(div),
+            r#"_c("div",[_c("ul",_vm._l(_setup.list,function(item,index){return _c("li",{key:item.id,ref:"items",refInFor:true},[_vm._v(_vm._s(index))]);}),0),_vm.ok?_c("p",[_vm._v("yes")]):_vm.maybe?_c("p",[_vm._v("maybe")]):_vm._e()])"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_component_with_slots_and_model() {
+        // <Comp v-model="$setup.msg" :title="title" @change="onChange" @click.native="onClick">
+        //   <template #header="{ text }">{{ text }}</template>
+        //   <template #footer>footer</template>
+        //   default
+        // </Comp>
+        let mut header = element("template", vec![], vec![interpolation("text")]);
+        header.starting_tag.directives = Some(Box::new(VueDirectives {
+            v_slot: Some(VSlotDirective {
+                slot_name: Some("header".into()),
+                value: Some(Box::new(Pat::Expr(js("{ text }")))),
+            }),
+            ..Default::default()
+        }));
+        let mut footer = element(
+            "template",
+            vec![],
+            vec![Node::Text("footer".into(), DUMMY_SP)],
+        );
+        footer.starting_tag.directives = Some(Box::new(VueDirectives {
+            v_slot: Some(VSlotDirective {
+                slot_name: Some("footer".into()),
+                value: None,
+            }),
+            ..Default::default()
+        }));
+
+        let mut comp = component(
+            "Comp",
+            vec![
+                v_bind_attribute("title", "title"),
+                v_on_attribute("change", "onChange"),
+                v_on_with_modifiers("click", "onClick", &["native"]),
+            ],
+            vec![
+                Node::Element(header),
+                Node::Element(footer),
+                Node::Text("default".into(), DUMMY_SP),
+            ],
+        );
+        comp.starting_tag.directives = Some(Box::new(VueDirectives {
+            v_model: vec![VModelDirective {
+                argument: None,
+                value: js("$setup.msg"),
+                update_handler: Some(js("$event => ($setup.msg = $event)")),
+                modifiers: vec![],
+                span: DUMMY_SP,
+            }],
+            ..Default::default()
+        }));
+
+        test_out(
+            Node::Element(comp),
+            r#"_c(Comp,{attrs:{title:title},on:{change:onChange},nativeOn:{click:onClick},scopedSlots:_vm._u([{key:"header",fn:function({text}){return[_vm._v(_vm._s(text))];}},{key:"footer",fn:function(){return[_vm._v("footer")];},proxy:true}]),model:{value:_setup.msg,callback:$event=>(_setup.msg=$event)}},[_vm._v("default")])"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_element_v_model() {
+        // <input v-model.trim="$setup.msg" v-show="visible">
+        let mut input = element("input", vec![], vec![]);
+        input.starting_tag.directives = Some(Box::new(VueDirectives {
+            v_model: vec![VModelDirective {
+                argument: None,
+                value: js("$setup.msg"),
+                update_handler: Some(js("$event => ($setup.msg = $event)")),
+                modifiers: vec!["trim".into()],
+                span: DUMMY_SP,
+            }],
+            v_show: Some(js("visible")),
+            ..Default::default()
+        }));
+
+        test_out(
+            Node::Element(input),
+            r#"_c("input",{directives:[{name:"show",rawName:"v-show",value:visible},{name:"model",rawName:"v-model",value:_setup.msg,modifiers:{trim:true}}],domProps:{value:_setup.msg},on:{input:function($event){if($event.target.composing)return;_setup.msg=$event.target.value.trim();},blur:function($event){return _vm.$forceUpdate();}}})"#,
+        );
+    }
+
+    #[test]
+    fn it_generates_slot_outlet() {
+        // <slot name="item" :item-data="data">fallback</slot>
+        let slot = ElementNode {
+            kind: ElementKind::Builtin(BuiltinType::Slot),
+            ..element(
+                "slot",
+                vec![
+                    regular_attribute("name", "item"),
+                    v_bind_attribute("item-data", "data"),
+                ],
+                vec![Node::Text("fallback".into(), DUMMY_SP)],
+            )
+        };
+
+        test_out(
+            Node::Element(slot),
+            r#"_vm._t("item",function(){return[_vm._v("fallback")];},{itemData:data})"#,
+        );
+    }
+
+    #[test]
+    fn it_renames_prefixes_in_render_fn() {
+        let mut ctx = CodegenContext::default();
+        let render_fn =
+            ctx.generate_vue2_render_fn(*js("[$setup.a, _ctx.b, $props.c, $data.d, $options.e]"));
+
+        assert_eq!(
+            to_str(Expr::Fn(FnExpr {
+                ident: None,
+                function: Box::new(render_fn),
+            })),
+            "function(){var _vm=this,_c=_vm._self._c,_setup=_vm._self._setupProxy;return[_setup.a,_vm.b,_vm.c,_vm.d,_vm.$options.e];}"
+        );
+    }
+
+    pub(super) fn test_out(root: Node, expected: &str) {
+        let mut ctx = CodegenContext::default();
+        ctx.bindings_helper
+            .components
+            .insert("Comp".into(), ComponentBinding::Resolved(js("Comp")));
+
+        let mut render_expr = ctx
+            .generate_vue2_template(&SfcTemplateBlock {
+                lang: "html".into(),
+                roots: vec![root],
+                span: DUMMY_SP,
+            })
+            .expect("template must not be empty");
+        render_expr.visit_mut_with(&mut PrefixRenamer);
+
+        assert_eq!(to_str(render_expr), expected);
+    }
+
+    pub(super) fn v_on_with_modifiers(
+        event: &str,
+        handler: &str,
+        modifiers: &[&str],
+    ) -> AttributeOrBinding {
+        AttributeOrBinding::VOn(VOnDirective {
+            event: Some(event.into()),
+            handler: Some(js(handler)),
+            modifiers: modifiers.iter().map(|&modifier| modifier.into()).collect(),
+            span: DUMMY_SP,
+        })
+    }
+
+    pub(super) fn interpolation(value: &str) -> Node {
+        Node::Interpolation(Interpolation {
+            value: js(value),
+            template_scope: 0,
+            patch_flag: true,
+            span: DUMMY_SP,
+        })
+    }
+
+    pub(super) fn element(
+        tag_name: &str,
+        attributes: Vec<AttributeOrBinding>,
+        children: Vec<Node>,
+    ) -> ElementNode {
+        ElementNode {
+            kind: ElementKind::Element,
+            starting_tag: StartingTag {
+                tag_name: tag_name.into(),
+                attributes,
+                directives: None,
+            },
+            children,
+            template_scope: 0,
+            patch_hints: Default::default(),
+            span: DUMMY_SP,
+        }
+    }
+
+    pub(super) fn component(
+        tag_name: &str,
+        attributes: Vec<AttributeOrBinding>,
+        children: Vec<Node>,
+    ) -> ElementNode {
+        ElementNode {
+            kind: ElementKind::Component,
+            ..element(tag_name, attributes, children)
+        }
+    }
+}
@@ -2,16 +2,28 @@ use swc_core::{common::Span, ecma::ast::Module};
 
 use crate::{FervidAtom, Node, StartingTag};
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct SfcDescriptor {
     pub template: Option<SfcTemplateBlock>,
     pub script_legacy: Option<SfcScriptBlock>,
     pub script_setup: Option<SfcScriptBlock>,
     pub styles: Vec<SfcStyleBlock>,
     pub custom_blocks: Vec<SfcCustomBlock>,
+    /// Whether the component opted into the Vapor mode, i.e. `<script setup vapor>`
+    pub vapor: bool,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct SfcTemplateBlock {
     pub lang: FervidAtom,
     pub roots: Vec<Node>,
@@ -19,6 +31,11 @@ pub struct SfcTemplateBlock {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct SfcScriptBlock {
     pub content: Box<Module>,
     pub lang: SfcScriptLang,
@@ -27,6 +44,11 @@ pub struct SfcScriptBlock {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct SfcStyleBlock {
     pub lang: FervidAtom,
     pub content: FervidAtom,
@@ -36,6 +58,11 @@ pub struct SfcStyleBlock {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct SfcCustomBlock {
     pub starting_tag: StartingTag,
     pub content: FervidAtom,
@@ -43,6 +70,7 @@ pub struct SfcCustomBlock {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SfcScriptLang {
     Es,
     Typescript,
@@ -1,3 +1,4 @@
+use strum_macros::EnumString;
 use swc_core::{
     common::{Span, DUMMY_SP},
     ecma::{
@@ -42,6 +43,7 @@ impl IntoIdent for FervidAtom {
 
 /// A Node represents a part of the Abstract Syntax Tree (AST).
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Node {
     /// `Element` means that the node is a basic HTML tag node.
     ///
@@ -79,6 +81,11 @@ pub enum Node {
 /// 3. It has a `template_scope` assigned, which is responsible
 ///    for the correct compilation of dynamic bindings and expressions.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct ElementNode {
     /// Marks the node as either an Element (HTML tag), Builtin (Vue) or Component
     pub kind: ElementKind,
@@ -90,6 +97,7 @@ pub struct ElementNode {
 }
 
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ElementKind {
     Builtin(BuiltinType),
     #[default]
@@ -98,6 +106,7 @@ pub enum ElementKind {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BuiltinType {
     Component,
     KeepAlive,
@@ -116,6 +125,11 @@ pub enum BuiltinType {
 /// - 0 or more `v-else-if` `ElementNode`s;
 /// - 0 or 1 `v-else` `ElementNode`.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct ConditionalNodeSequence {
     pub if_node: Box<Conditional>,
     pub else_if_nodes: Vec<Conditional>,
@@ -125,6 +139,11 @@ pub struct ConditionalNodeSequence {
 /// A wrapper around an `ElementNode` with a condition attached to it.
 /// This is used in `v-if` and `v-else-if` nodes.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct Conditional {
     pub condition: Expr,
     pub node: ElementNode,
@@ -133,6 +152,11 @@ pub struct Conditional {
 /// A special Vue `{{ expression }}`,
 /// which would be rendered as a stringified value of executing said expression.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct Interpolation {
     pub value: Box<Expr>,
     pub template_scope: u32,
@@ -142,6 +166,11 @@ pub struct Interpolation {
 
 /// Starting tag represents [`ElementNode`]'s tag name and attributes
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct StartingTag {
     pub tag_name: FervidAtom,
     pub attributes: Vec<AttributeOrBinding>,
@@ -153,6 +182,7 @@ pub struct StartingTag {
 /// because they bind something to DOM.
 /// `v-model` is not covered here because its code generation is not as trivial.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AttributeOrBinding {
     /// `RegularAttribute` is a plain HTML attribute without any associated logic
     RegularAttribute {
@@ -172,6 +202,7 @@ pub enum AttributeOrBinding {
 /// - `:foo="bar"` yields `StrOrExpr::Str("foo")`;
 /// - `:[baz]="qux"` yields `StrOrExpr::Expr(Box::new(Expr::Lit(Lit::Str(Str { value: "baz".into(), .. }))))`
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StrOrExpr {
     Str(FervidAtom),
     Expr(Box<Expr>),
@@ -186,6 +217,11 @@ impl<'s> From<&'s str> for StrOrExpr {
 /// A helper structure attached to `ElementNode`s to handle Patch Flags
 /// and contain the list of dynamic props.
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct PatchHints {
     /// Patch flags
     pub flags: PatchFlagsSet,
@@ -311,6 +347,11 @@ pub type PatchFlagsSet = flagset::FlagSet<PatchFlags>;
 
 /// A structure which stores all the Vue directives of an `ElementNode`.
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct VueDirectives {
     pub custom: Vec<VCustomDirective>,
     pub v_cloak: Option<()>,
@@ -330,6 +371,11 @@ pub struct VueDirectives {
 
 /// `v-for`
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct VForDirective {
     /// `bar` in `v-for="foo in bar"`
     pub iterable: Box<Expr>,
@@ -341,6 +387,11 @@ pub struct VForDirective {
 
 /// `v-on` and its shorthand `@`
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct VOnDirective {
     /// What event to listen to. If None, it is equivalent to `v-on="..."`.
     pub event: Option<StrOrExpr>,
@@ -354,6 +405,11 @@ pub struct VOnDirective {
 
 /// `v-bind` and its shorthand `:`
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct VBindDirective {
     /// Attribute name to bind. If None, it is equivalent to `v-bind="..."`.
     pub argument: Option<StrOrExpr>,
@@ -371,6 +427,11 @@ pub struct VBindDirective {
 
 /// `v-model`
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct VModelDirective {
     /// What to apply v-model to, e.g. `first-name` in `v-model:first-name="first"`
     pub argument: Option<StrOrExpr>,
@@ -385,6 +446,11 @@ pub struct VModelDirective {
 
 /// `v-slot`
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct VSlotDirective {
     pub slot_name: Option<StrOrExpr>,
     /// What bindings are provided to slot children, e.g. `value` in `v-slot="{ value }"`
@@ -393,6 +459,11 @@ pub struct VSlotDirective {
 
 /// A custom directive defined by a user.
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct VCustomDirective {
     /// `foo` in `v-foo`
     pub name: FervidAtom,
@@ -409,6 +480,7 @@ pub struct VCustomDirective {
 ///
 /// <https://github.com/vuejs/core/blob/020851e57d9a9f727c6ea07e9c1575430af02b73/packages/compiler-core/src/options.ts#L76>
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BindingTypes {
     /// returned from data()
     Data,
@@ -463,3 +535,17 @@ pub enum TemplateGenerationMode {
     #[default]
     RenderFn,
 }
+
+/// Vue version which the compiled component runs on.
+/// Can be parsed from a lowercase name, e.g. `vue2`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum CompileTarget {
+    #[default]
+    Vue3,
+
+    /// Vue 2.7, which supports `<script setup>` but has a different runtime.
+    /// The template is compiled to a `render` function using `_c` and `_vm`,
+    /// and is never inlined into `setup`.
+    Vue2,
+}
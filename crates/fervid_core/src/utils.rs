@@ -1,10 +1,17 @@
 use swc_core::{
-    common::Span,
+    common::{BytePos, Span},
     ecma::ast::{ComputedPropName, EsReserved, Ident, IdentName, PropName, Str},
 };
 
 use crate::{AttributeOrBinding, FervidAtom, StrOrExpr, VBindDirective};
 
+/// Span making the code generator emit a `/*#__PURE__*/` comment before the node,
+/// e.g. before the generated `_defineComponent(...)` call
+pub const PURE_SP: Span = Span {
+    lo: BytePos::PURE,
+    hi: BytePos::PURE,
+};
+
 /// Checks whether the attributes name is the same as `expected_name`
 #[inline]
 pub fn check_attribute_name(attr: &AttributeOrBinding, expected_name: &str) -> bool {
@@ -71,3 +78,38 @@ pub fn str_or_expr_to_propname(str_or_expr: StrOrExpr, span: Span) -> PropName {
         StrOrExpr::Expr(expr) => PropName::Computed(ComputedPropName { span, expr }),
     }
 }
+
+/// Finds the span of the content of an SFC block, e.g. the code between `<script ...>` and `</script>`.
+/// `block_span` is the span of the whole block, as produced by the parser (1-based).
+pub fn block_content_span(source: &str, block_span: Span) -> Option<Span> {
+    let lo = (block_span.lo.0 as usize).checked_sub(1)?;
+    let hi = (block_span.hi.0 as usize).checked_sub(1)?;
+    let raw = source.get(lo..hi)?;
+
+    let content_lo = start_tag_len(raw)?;
+    let content_hi = raw.rfind("</")?;
+    if content_hi < content_lo {
+        return None;
+    }
+
+    Some(Span {
+        lo: block_span.lo + BytePos(content_lo as u32),
+        hi: block_span.lo + BytePos(content_hi as u32),
+    })
+}
+
+/// Length of the starting tag, e.g. `<script ...>`.
+/// Attributes may contain `>`, e.g. `generic="T extends Array<string>"`
+pub fn start_tag_len(raw: &str) -> Option<usize> {
+    let mut quote = None;
+    for (idx, ch) in raw.char_indices() {
+        match (quote, ch) {
+            (None, '"' | '\'') => quote = Some(ch),
+            (Some(q), _) if q == ch => quote = None,
+            (None, '>') => return Some(idx + 1),
+            _ => {}
+        }
+    }
+
+    None
+}
@@ -4,25 +4,59 @@ use strum_macros::{AsRefStr, EnumString, IntoStaticStr};
 
 flags! {
     #[derive(AsRefStr, EnumString, IntoStaticStr)]
-    pub enum VueImports: u64 {
+    pub enum VueImports: u128 {
+        #[strum(serialize = "_applyCheckboxModel")]
+        ApplyCheckboxModel,
+        #[strum(serialize = "_applyDynamicModel")]
+        ApplyDynamicModel,
+        #[strum(serialize = "_applyRadioModel")]
+        ApplyRadioModel,
+        #[strum(serialize = "_applySelectModel")]
+        ApplySelectModel,
+        #[strum(serialize = "_applyTextModel")]
+        ApplyTextModel,
+        #[strum(serialize = "_applyVShow")]
+        ApplyVShow,
+        #[strum(serialize = "_child")]
+        Child,
         #[strum(serialize = "_createBlock")]
         CreateBlock,
         #[strum(serialize = "_createCommentVNode")]
         CreateCommentVNode,
+        #[strum(serialize = "_createComponent")]
+        CreateComponent,
+        #[strum(serialize = "_createComponentWithFallback")]
+        CreateComponentWithFallback,
+        #[strum(serialize = "_createDynamicComponent")]
+        CreateDynamicComponent,
         #[strum(serialize = "_createElementBlock")]
         CreateElementBlock,
         #[strum(serialize = "_createElementVNode")]
         CreateElementVNode,
+        #[strum(serialize = "_createFor")]
+        CreateFor,
+        #[strum(serialize = "_createIf")]
+        CreateIf,
         #[strum(serialize = "_createPropsRestProxy")]
         CreatePropsRestProxy,
+        #[strum(serialize = "_createSlot")]
+        CreateSlot,
+        #[strum(serialize = "_createTemplateRefSetter")]
+        CreateTemplateRefSetter,
         #[strum(serialize = "_createTextVNode")]
         CreateTextVNode,
         #[strum(serialize = "_createVNode")]
         CreateVNode,
         #[strum(serialize = "_defineComponent")]
         DefineComponent,
+        #[strum(serialize = "_defineVaporComponent")]
+        DefineVaporComponent,
+        #[strum(serialize = "_delegateEvents")]
+        DelegateEvents,
         #[strum(serialize = "_Fragment")]
         Fragment,
+        #[strum(serialize = "_insert")]
+        Insert,
         #[strum(serialize = "_isMemoSame")]
         IsMemoSame,
         #[strum(serialize = "_isRef")]
@@ -33,12 +67,20 @@ flags! {
         MergeDefaults,
         #[strum(serialize = "_mergeModels")]
         MergeModels,
+        #[strum(serialize = "_next")]
+        Next,
         #[strum(serialize = "_normalizeClass")]
         NormalizeClass,
         #[strum(serialize = "_normalizeStyle")]
         NormalizeStyle,
+        #[strum(serialize = "_nthChild")]
+        NthChild,
+        #[strum(serialize = "_on")]
+        On,
         #[strum(serialize = "_openBlock")]
         OpenBlock,
+        #[strum(serialize = "_renderEffect")]
+        RenderEffect,
         #[strum(serialize = "_renderList")]
         RenderList,
         #[strum(serialize = "_renderSlot")]
@@ -49,12 +91,32 @@ flags! {
         ResolveDirective,
         #[strum(serialize = "_resolveDynamicComponent")]
         ResolveDynamicComponent,
+        #[strum(serialize = "_setAttr")]
+        SetAttr,
         #[strum(serialize = "_setBlockTracking")]
         SetBlockTracking,
+        #[strum(serialize = "_setClass")]
+        SetClass,
+        #[strum(serialize = "_setDOMProp")]
+        SetDOMProp,
+        #[strum(serialize = "_setDynamicEvents")]
+        SetDynamicEvents,
+        #[strum(serialize = "_setDynamicProps")]
+        SetDynamicProps,
+        #[strum(serialize = "_setHtml")]
+        SetHtml,
+        #[strum(serialize = "_setProp")]
+        SetProp,
+        #[strum(serialize = "_setStyle")]
+        SetStyle,
+        #[strum(serialize = "_setText")]
+        SetText,
         #[strum(serialize = "_Suspense")]
         Suspense,
         #[strum(serialize = "_Teleport")]
         Teleport,
+        #[strum(serialize = "_template")]
+        Template,
         #[strum(serialize = "_toDisplayString")]
         ToDisplayString,
         #[strum(serialize = "_toHandlerKey")]
@@ -63,6 +125,8 @@ flags! {
         Transition,
         #[strum(serialize = "_TransitionGroup")]
         TransitionGroup,
+        #[strum(serialize = "_txt")]
+        Txt,
         #[strum(serialize = "_unref")]
         Unref,
         #[strum(serialize = "_useModel")]
@@ -89,6 +153,8 @@ flags! {
         WithMemo,
         #[strum(serialize = "_withModifiers")]
         WithModifiers,
+        #[strum(serialize = "_withVaporDirectives")]
+        WithVaporDirectives,
     }
 }
 
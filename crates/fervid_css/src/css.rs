@@ -7,15 +7,22 @@ use fervid_core::error::Severity;
 use swc_core::common::Span;
 use swc_css_parser::parser::ParserConfig;
 
-pub use codegen::{stringify, StringifyOptions};
+pub use codegen::{stringify, stringify_with_source_map, CssSourceMapConfig, StringifyOptions};
 pub use error::CssError;
 pub use parse::parse_stylesheet;
 pub use transform::ScopedTransformer;
 
 #[derive(Default)]
-pub struct TransformCssConfig {
+pub struct TransformCssConfig<'s> {
     pub parse: ParserConfig,
     pub stringify: StringifyOptions,
+    /// When set, a source map is generated alongside the transformed CSS
+    pub source_map: Option<CssSourceMapConfig<'s>>,
+}
+
+pub struct TransformCssResult {
+    pub code: String,
+    pub source_map: Option<String>,
 }
 
 /// Transforms raw CSS, also handles the scopes.
@@ -25,7 +32,7 @@ pub fn transform_css(
     scope: Option<&str>,
     errors: &mut Vec<CssError>,
     config: TransformCssConfig,
-) -> Option<String> {
+) -> Option<TransformCssResult> {
     // Parse and collect errors
     let mut parse_errors = Vec::new();
     let parse_result = parse_stylesheet(content, span, config.parse, &mut parse_errors);
@@ -50,5 +57,20 @@ pub fn transform_css(
         return None;
     }
 
-    Some(stringify(&stylesheet, config.stringify))
+    let result = match config.source_map {
+        Some(ref source_map_config) => {
+            let (code, source_map) =
+                stringify_with_source_map(&stylesheet, config.stringify, source_map_config);
+            TransformCssResult {
+                code,
+                source_map: Some(source_map),
+            }
+        }
+        None => TransformCssResult {
+            code: stringify(&stylesheet, config.stringify),
+            source_map: None,
+        },
+    };
+
+    Some(result)
 }
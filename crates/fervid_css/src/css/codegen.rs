@@ -1,3 +1,6 @@
+use swc_core::common::{
+    source_map::SourceMapGenConfig, sync::Lrc, BytePos, FileName, LineCol, SourceMap,
+};
 use swc_css_ast::{PseudoClassSelectorChildren, PseudoElementSelectorChildren, Stylesheet};
 use swc_css_codegen::{
     writer::basic::{BasicCssWriter, BasicCssWriterConfig},
@@ -18,10 +21,53 @@ impl Default for StringifyOptions {
     }
 }
 
+/// Options for generating a source map of the stringified CSS
+#[derive(Clone, Copy)]
+pub struct CssSourceMapConfig<'s> {
+    /// Name of the original file, used in `sources` of the map
+    pub filename: &'s str,
+    /// The whole original source, e.g. the SFC.
+    /// Spans of the [`Stylesheet`] must point into this source.
+    pub source: &'s str,
+}
+
 /// Stringifies the [`Stylesheet`]
 pub fn stringify(node: &Stylesheet, options: StringifyOptions) -> String {
+    stringify_impl(node, options, None)
+}
+
+/// Stringifies the [`Stylesheet`] and generates a source map for it.
+/// The map is relative to the original source, e.g. `.vue` file.
+pub fn stringify_with_source_map(
+    node: &Stylesheet,
+    options: StringifyOptions,
+    source_map_config: &CssSourceMapConfig,
+) -> (String, String) {
+    let mut source_map_buf = Vec::new();
+    let code = stringify_impl(node, options, Some(&mut source_map_buf));
+
+    // Spans are relative to the original source, so the whole source is registered
+    let cm: Lrc<SourceMap> = Default::default();
+    cm.new_source_file(
+        Lrc::new(FileName::Custom(source_map_config.filename.to_owned())),
+        source_map_config.source.to_owned(),
+    );
+
+    let map = cm.build_source_map(&source_map_buf, None, CssSourceMapGenConfig);
+    let mut buf = vec![];
+    map.to_writer(&mut buf).expect("Failed to write source map");
+    let map = String::from_utf8(buf).expect("Invalid UTF-8 in source map");
+
+    (code, map)
+}
+
+fn stringify_impl(
+    node: &Stylesheet,
+    options: StringifyOptions,
+    source_map_buf: Option<&mut Vec<(BytePos, LineCol)>>,
+) -> String {
     let mut buf = String::new();
-    let writer = BasicCssWriter::new(&mut buf, None, options.basic_css_writer);
+    let writer = BasicCssWriter::new(&mut buf, source_map_buf, options.basic_css_writer);
     let mut codegen = CodeGenerator::new(
         writer,
         CodegenConfig {
@@ -33,6 +79,18 @@ pub fn stringify(node: &Stylesheet, options: StringifyOptions) -> String {
     buf
 }
 
+struct CssSourceMapGenConfig;
+
+impl SourceMapGenConfig for CssSourceMapGenConfig {
+    fn file_name_to_source(&self, f: &FileName) -> String {
+        f.to_string()
+    }
+
+    fn inline_sources_content(&self, _f: &FileName) -> bool {
+        true
+    }
+}
+
 pub fn stringify_pseudo_class_selector_children(nodes: Vec<PseudoClassSelectorChildren>) -> String {
     let mut result = String::new();
     let writer = BasicCssWriter::new(&mut result, None, BasicCssWriterConfig::default());
@@ -1,5 +1,5 @@
 use fervid_core::error::{Severity, SeverityLevel};
-use swc_core::common::{Span, Spanned};
+use swc_core::common::{Span, Spanned, DUMMY_SP};
 use swc_css_parser::error::{Error as ParseError, ErrorKind as ParseErrorKind};
 
 #[derive(Debug)]
@@ -34,6 +34,38 @@ impl CssError {
     }
 }
 
+impl CssErrorKind {
+    /// Stable code of the error, `css-syntax-error` or `css-deep-syntax-error`
+    /// (the latter is inside the argument of `:deep()`)
+    pub fn code(&self) -> &'static str {
+        match self {
+            CssErrorKind::ParseRecoverable(_) | CssErrorKind::ParseUnrecoverable(_) => {
+                "css-syntax-error"
+            }
+            CssErrorKind::ParseDeepRecoverable(_) | CssErrorKind::ParseDeepUnrecoverable(_) => {
+                "css-deep-syntax-error"
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for CssErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (CssErrorKind::ParseRecoverable(kind)
+        | CssErrorKind::ParseUnrecoverable(kind)
+        | CssErrorKind::ParseDeepRecoverable(kind)
+        | CssErrorKind::ParseDeepUnrecoverable(kind)) = self;
+
+        f.write_str(&ParseError::new(DUMMY_SP, kind.clone()).message())
+    }
+}
+
+impl std::fmt::Display for CssError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
 impl Severity for CssError {
     fn get_severity(&self) -> SeverityLevel {
         match &self.kind {
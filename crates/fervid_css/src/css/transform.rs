@@ -1,7 +1,9 @@
+use fervid_core::FervidAtom;
 use swc_core::common::{Span, Spanned, DUMMY_SP};
 use swc_css_ast::{
-    AtRule, AttributeSelector, Combinator, ComplexSelector, ComplexSelectorChildren,
-    ComponentValue, Ident, ListOfComponentValues, PseudoClassSelectorChildren,
+    AtRule, AtRuleName, AtRulePrelude, AttributeSelector, Combinator, ComplexSelector,
+    ComplexSelectorChildren, ComponentValue, Declaration, DeclarationName, DelimiterValue, Ident,
+    KeyframesName, ListOfComponentValues, PseudoClassSelectorChildren,
     PseudoElementSelectorChildren, QualifiedRulePrelude, Rule, SelectorList, SimpleBlock,
     Stylesheet, SubclassSelector, WqName,
 };
@@ -17,6 +19,8 @@ use super::{
 pub struct ScopedTransformer<'s> {
     scope: &'s str,
     errors: Vec<CssError>,
+    /// Original names of `@keyframes` defined in the stylesheet
+    keyframes: Vec<FervidAtom>,
 }
 
 impl<'s> ScopedTransformer<'s> {
@@ -24,10 +28,19 @@ impl<'s> ScopedTransformer<'s> {
         Self {
             scope,
             errors: vec![],
+            keyframes: vec![],
         }
     }
 
     pub fn transform(&mut self, stylesheet: &mut Stylesheet) {
+        // Keyframes are collected beforehand,
+        // because `animation` can reference them before they are defined
+        for rule in stylesheet.rules.iter_mut() {
+            if let Rule::AtRule(at_rule) = rule {
+                self.scope_keyframes(at_rule);
+            }
+        }
+
         for rule in stylesheet.rules.iter_mut() {
             match rule {
                 Rule::QualifiedRule(qualified_rule) => match qualified_rule.prelude {
@@ -51,6 +64,24 @@ impl<'s> ScopedTransformer<'s> {
                 }
             }
         }
+
+        if self.keyframes.is_empty() {
+            return;
+        }
+
+        for rule in stylesheet.rules.iter_mut() {
+            match rule {
+                Rule::QualifiedRule(qualified_rule) => {
+                    self.rewrite_animations_in_block(&mut qualified_rule.block);
+                }
+                Rule::AtRule(at_rule) => {
+                    if let Some(ref mut block) = at_rule.block {
+                        self.rewrite_animations_in_block(block);
+                    }
+                }
+                Rule::ListOfComponentValues(_) => {}
+            }
+        }
     }
 
     pub fn take_errors(&mut self) -> Vec<CssError> {
@@ -225,6 +256,13 @@ impl<'s> ScopedTransformer<'s> {
     }
 
     fn transform_at_rule(&mut self, at_rule: &mut AtRule) {
+        // Keyframe selectors (`from`, `to`, `50%`) are not scoped
+        if is_keyframes_at_rule(at_rule) {
+            return;
+        }
+
+        // `@media`, `@supports`, `@container`, `@layer` and `@scope` only get their rules scoped,
+        // the preludes are left as-is
         if let Some(ref mut at_rule_block) = at_rule.block {
             self.transform_simple_block(at_rule_block);
         };
@@ -282,6 +320,137 @@ impl<'s> ScopedTransformer<'s> {
             self.transform_component_value(component_value);
         }
     }
+
+    /// Suffixes the names of `@keyframes` (including the vendor-prefixed ones)
+    /// with the scope id and remembers the original names.
+    /// Keyframes nested inside other at-rules (e.g. `@media`) are scoped as well.
+    fn scope_keyframes(&mut self, at_rule: &mut AtRule) {
+        if !is_keyframes_at_rule(at_rule) {
+            let Some(ref mut block) = at_rule.block else {
+                return;
+            };
+
+            for component_value in block.value.iter_mut() {
+                if let ComponentValue::AtRule(nested_at_rule) = component_value {
+                    self.scope_keyframes(nested_at_rule);
+                }
+            }
+
+            return;
+        }
+
+        let Some(ref mut prelude) = at_rule.prelude else {
+            return;
+        };
+
+        let AtRulePrelude::KeyframesPrelude(ref mut keyframes_name) = **prelude else {
+            return;
+        };
+
+        match keyframes_name {
+            KeyframesName::CustomIdent(custom_ident) => {
+                let scoped_name = self.get_scoped_keyframes_name(&custom_ident.value);
+                let original_name = std::mem::replace(&mut custom_ident.value, scoped_name);
+                custom_ident.raw = None;
+                self.add_keyframes_name(original_name);
+            }
+            KeyframesName::Str(str) => {
+                let scoped_name = self.get_scoped_keyframes_name(&str.value);
+                let original_name = std::mem::replace(&mut str.value, scoped_name);
+                str.raw = None;
+                self.add_keyframes_name(original_name);
+            }
+            KeyframesName::PseudoPrefix(_) | KeyframesName::PseudoFunction(_) => {}
+        }
+    }
+
+    /// E.g. `fade` -> `fade-abcd1234` for the `data-v-abcd1234` scope (same as in the official compiler)
+    fn get_scoped_keyframes_name(&self, name: &str) -> FervidAtom {
+        let id = self.scope.strip_prefix("data-v-").unwrap_or(self.scope);
+        FervidAtom::from(format!("{name}-{id}"))
+    }
+
+    fn add_keyframes_name(&mut self, name: FervidAtom) {
+        if !self.keyframes.contains(&name) {
+            self.keyframes.push(name);
+        }
+    }
+
+    fn rewrite_animations_in_block(&self, simple_block: &mut SimpleBlock) {
+        for component_value in simple_block.value.iter_mut() {
+            match component_value {
+                ComponentValue::Declaration(declaration) => {
+                    self.rewrite_animation_declaration(declaration);
+                }
+                ComponentValue::QualifiedRule(qualified_rule) => {
+                    self.rewrite_animations_in_block(&mut qualified_rule.block);
+                }
+                ComponentValue::AtRule(at_rule) if !is_keyframes_at_rule(at_rule) => {
+                    if let Some(ref mut block) = at_rule.block {
+                        self.rewrite_animations_in_block(block);
+                    }
+                }
+                ComponentValue::SimpleBlock(simple_block) => {
+                    self.rewrite_animations_in_block(simple_block);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Rewrites the keyframes names in `animation` and `animation-name` (also vendor-prefixed).
+    /// In each comma-separated animation, the first identifier matching a keyframes name is replaced.
+    fn rewrite_animation_declaration(&self, declaration: &mut Declaration) {
+        let DeclarationName::Ident(ref name) = declaration.name else {
+            return;
+        };
+
+        let property = strip_vendor_prefix(&name.value);
+        if !property.eq_ignore_ascii_case("animation")
+            && !property.eq_ignore_ascii_case("animation-name")
+        {
+            return;
+        }
+
+        let mut is_replaced = false;
+        for value in declaration.value.iter_mut() {
+            match value {
+                ComponentValue::Delimiter(delimiter)
+                    if delimiter.value == DelimiterValue::Comma =>
+                {
+                    is_replaced = false;
+                }
+                ComponentValue::Ident(ident)
+                    if !is_replaced && self.keyframes.contains(&ident.value) =>
+                {
+                    ident.value = self.get_scoped_keyframes_name(&ident.value);
+                    ident.raw = None;
+                    is_replaced = true;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Checks for `@keyframes` and the vendor-prefixed variants like `@-webkit-keyframes`
+fn is_keyframes_at_rule(at_rule: &AtRule) -> bool {
+    let AtRuleName::Ident(ref name) = at_rule.name else {
+        return false;
+    };
+
+    strip_vendor_prefix(&name.value).eq_ignore_ascii_case("keyframes")
+}
+
+/// `-webkit-animation` -> `animation`
+fn strip_vendor_prefix(name: &str) -> &str {
+    if let Some(without_dash) = name.strip_prefix('-') {
+        if let Some(idx) = without_dash.find('-') {
+            return &without_dash[(idx + 1)..];
+        }
+    }
+
+    name
 }
 
 // Processes contents of `:deep`
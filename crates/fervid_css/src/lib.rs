@@ -20,7 +20,7 @@
 //! let result = fervid_css::transform_css(input, span, Some("data-v-abcd1234"), &mut errors, Default::default());
 //!
 //! if let Some(transformed_css) = result {
-//!     assert_eq!(".example[data-v-abcd1234]{background:#ff0}", transformed_css);
+//!     assert_eq!(".example[data-v-abcd1234]{background:#ff0}", transformed_css.code);
 //! }
 //! ```
 
@@ -55,7 +55,7 @@ mod tests {
                 &mut errors,
                 Default::default(),
             );
-            assert_eq!(out.ok_or(()), $expected);
+            assert_eq!(out.map(|r| r.code).ok_or(()), $expected);
         };
     }
 
@@ -196,5 +196,98 @@ mod tests {
             "@media screen and (min-width:500px){.foo[data-v-abcd1234]{background:#ff0}}",
             minify_yes!()
         );
+        test_ok!(
+            "@supports (display: grid) { .foo { display: grid } }",
+            "@supports(display:grid){.foo[data-v-abcd1234]{display:grid}}",
+            minify_yes!()
+        );
+        test_ok!(
+            "@container sidebar (min-width: 400px) { .foo { color: red } }",
+            "@container sidebar (min-width:400px){.foo[data-v-abcd1234]{color:red}}",
+            minify_yes!()
+        );
+        test_ok!(
+            "@layer base { .foo { color: red } }",
+            "@layer base{.foo[data-v-abcd1234]{color:red}}",
+            minify_yes!()
+        );
+        test_ok!(
+            "@scope (.card) to (.content) { img { color: red } }",
+            "@scope(.card) to (.content){img[data-v-abcd1234]{color:red}}",
+            minify_yes!()
+        );
+        test_ok!(
+            "@media screen { @supports (display: grid) { .foo { display: grid } } }",
+            "@media screen{@supports(display:grid){.foo[data-v-abcd1234]{display:grid}}}",
+            minify_yes!()
+        );
+
+        //
+        // Keyframes
+        //
+        test_ok!(
+            "@keyframes fade { from { opacity: 0 } to { opacity: 1 } }",
+            "@keyframes fade-abcd1234{from{opacity:0}to{opacity:1}}",
+            minify_yes!()
+        );
+        test_ok!(
+            ".foo { animation: fade 1s ease } @keyframes fade { to { opacity: 1 } }",
+            ".foo[data-v-abcd1234]{animation:fade-abcd1234 1s ease}@keyframes fade-abcd1234{to{opacity:1}}",
+            minify_yes!()
+        );
+        test_ok!(
+            ".foo { animation-name: fade, slide, other } @keyframes fade { to { opacity: 1 } } @keyframes slide { to { opacity: 1 } }",
+            ".foo[data-v-abcd1234]{animation-name:fade-abcd1234,slide-abcd1234,other}@keyframes fade-abcd1234{to{opacity:1}}@keyframes slide-abcd1234{to{opacity:1}}",
+            minify_yes!()
+        );
+        test_ok!(
+            ".foo { -webkit-animation: fade 1s } @-webkit-keyframes fade { to { opacity: 1 } }",
+            ".foo[data-v-abcd1234]{-webkit-animation:fade-abcd1234 1s}@-webkit-keyframes fade-abcd1234{to{opacity:1}}",
+            minify_yes!()
+        );
+        test_ok!(
+            "@media screen { @keyframes fade { to { opacity: 1 } } .foo { animation: fade 1s } }",
+            "@media screen{@keyframes fade-abcd1234{to{opacity:1}}.foo[data-v-abcd1234]{animation:fade-abcd1234 1s}}",
+            minify_yes!()
+        );
+        // Keyframes not defined in the stylesheet are left untouched
+        test_ok!(
+            ".foo { animation: global-fade 1s }",
+            ".foo[data-v-abcd1234]{animation:global-fade 1s}",
+            minify_yes!()
+        );
+    }
+
+    #[test]
+    fn it_generates_source_map_relative_to_sfc() {
+        let sfc = "<template></template>\n<style scoped>\n.foo { background: #ff0 }\n</style>\n";
+        let content = "\n.foo { background: #ff0 }\n";
+        let lo = sfc.find(content).unwrap() as u32 + 1;
+        let span = Span::new(BytePos(lo), BytePos(lo + content.len() as u32));
+
+        let mut errors = Vec::new();
+        let out = css::transform_css(
+            content,
+            span,
+            Some("data-v-abcd1234"),
+            &mut errors,
+            css::TransformCssConfig {
+                source_map: Some(css::CssSourceMapConfig {
+                    filename: "Foo.vue",
+                    source: sfc,
+                }),
+                ..Default::default()
+            },
+        )
+        .expect("Should transform");
+
+        assert!(errors.is_empty());
+        assert_eq!(".foo[data-v-abcd1234]{background:#ff0}", out.code);
+
+        let source_map = out.source_map.expect("Should have a source map");
+        assert!(source_map.contains(r#""sources":["Foo.vue"]"#));
+        assert!(source_map.contains(r#""sourcesContent":["<template></template>"#));
+        // `.foo` is on the third line of the SFC
+        assert!(source_map.contains(r#""mappings":"AAEA"#), "{source_map}");
     }
 }
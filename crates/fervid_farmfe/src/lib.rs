@@ -74,7 +74,7 @@ impl Plugin for FarmPluginVueFervid {
     fn transform(
         &self,
         param: &farmfe_core::plugin::PluginTransformHookParam,
-        _context: &std::sync::Arc<farmfe_core::context::CompilationContext>,
+        context: &std::sync::Arc<farmfe_core::context::CompilationContext>,
     ) -> farmfe_core::error::Result<Option<farmfe_core::plugin::PluginTransformHookResult>> {
         // Guard
         if !matches!(param.module_type, ModuleType::Custom(ref typ) if typ == "vue") {
@@ -92,9 +92,16 @@ impl Plugin for FarmPluginVueFervid {
                 is_custom_element: Some(is_custom_element),
                 props_destructure: None,
                 ssr: None,
+                strip_client_only: None,
+                target: None,
+                module_loader: None,
                 gen_default_as: None,
-                source_map: None,
+                source_map: Some(context.config.sourcemap.enabled(false)),
                 transform_asset_urls: None,
+                inline_template: None,
+                template_plugins: None,
+                custom_blocks: None,
+                error_recovery_mode: None,
             },
         );
 
@@ -124,7 +131,7 @@ impl Plugin for FarmPluginVueFervid {
                         content: style.code,
                         // TODO Determine based on style lang
                         module_type: ModuleType::Css,
-                        source_map: None,
+                        source_map: style.source_map,
                     },
                 );
             }
@@ -0,0 +1,44 @@
+import { describe, expect, test } from 'vitest'
+import { assertCode, compile } from './utils'
+
+describe('comments', () => {
+  test('preserves script comments', () => {
+    const { content, errors } = compile(`
+<script lang="ts">
+/*! Copyright (c) Fervid */
+import { defineAsyncComponent } from 'vue'
+</script>
+<script setup lang="ts">
+/** Number of clicks */
+const count = 0
+const Chart = defineAsyncComponent(() => import(/* webpackChunkName: "chart" */ './Chart.vue'))
+// @ts-expect-error
+const label: string = count
+</script>
+<template>
+  <button>{{ count }}</button>
+</template>
+    `)
+
+    expect(errors).toEqual([])
+    assertCode(content)
+    expect(content).toMatch('/*! Copyright (c) Fervid */')
+    expect(content).toMatch('/** Number of clicks */ const count = 0')
+    expect(content).toMatch(`import(/* webpackChunkName: "chart" */ './Chart.vue')`)
+    expect(content).toMatch(`// @ts-expect-error
+        const label: string = count`)
+  })
+
+  test('annotates generated calls as pure', () => {
+    const { content } = compile(`
+<script setup lang="ts">
+import { defaults } from './defaults'
+const props = withDefaults(defineProps<{ foo?: string }>(), defaults)
+</script>
+    `)
+
+    assertCode(content)
+    expect(content).toMatch('export default /*#__PURE__*/ _defineComponent({')
+    expect(content).toMatch('props: /*#__PURE__*/ _mergeDefaults({')
+  })
+})
@@ -0,0 +1,32 @@
+import { test, expect } from 'vitest'
+
+import { Compiler, CompileResult } from '../index'
+
+test('should compile many files preserving the order', async () => {
+  const inputs = ['foo', 'bar', 'baz'].map((name) => ({
+    source: `<template><div>${name}</div></template>`,
+    options: {
+      filename: `${name}.vue`,
+      id: name,
+    },
+  }))
+
+  const results = (await new Compiler().compileManyAsync(inputs)) as CompileResult[]
+
+  expect(results).toHaveLength(3)
+  results.forEach((result, idx) => {
+    expect(result.errors).toHaveLength(0)
+    expect(result.code).toContain(`"${inputs[idx].options.id}"`)
+  })
+})
+
+test('should report diagnostics per file', async () => {
+  const results = (await new Compiler().compileManyAsync([
+    { source: '<template><div>ok</div></template>', options: { filename: 'ok.vue', id: '' } },
+    { source: '<script setup>export const foo = 1</script>', options: { filename: 'export.vue', id: '' } },
+  ])) as CompileResult[]
+
+  expect(results[0].errors).toHaveLength(0)
+  expect(results[0].code).toContain('"ok"')
+  expect(results[1].errors.length).toBeGreaterThan(0)
+})
@@ -248,9 +248,7 @@ describe('SFC genDefaultAs', () => {
       },
     )
     expect(content).not.toMatch('export default')
-    // TODO https://github.com/phoenix-ru/fervid/issues/23
-    // expect(content).toMatch(`const _sfc_ = /*#__PURE__*/_defineComponent(`)
-    expect(content).toMatch(`const _sfc_ = _defineComponent({`)
+    expect(content).toMatch(`const _sfc_ = /*#__PURE__*/ _defineComponent({`)
     assertCode(content)
   })
 
@@ -271,7 +269,7 @@ describe('SFC genDefaultAs', () => {
       // TODO https://github.com/phoenix-ru/fervid/issues/23
       // There is no need for spreading, because Fervid merges trivial objects
       // `const _sfc_ = /*#__PURE__*/_defineComponent({\n  ...__default__`,
-      `const _sfc_ = _defineComponent({\n    __name:`,
+      `const _sfc_ = /*#__PURE__*/ _defineComponent({\n    __name:`,
     )
     assertCode(content)
   })
@@ -0,0 +1,129 @@
+import { describe, test, expect } from 'vitest'
+
+import { BindingTypes, Compiler, FervidCompileOptions } from '../index'
+
+const source = `<script setup>
+import { ref } from 'vue'
+import Foo from './Foo.vue'
+const count = ref(0)
+</script>
+
+<template>
+  <Foo :count="count" @click="count++" />
+</template>
+
+<style scoped>
+.foo { color: red }
+</style>`
+
+const options: FervidCompileOptions = {
+  filename: 'Counter.vue',
+  id: '',
+}
+
+describe('split compilation', () => {
+  test('should parse a descriptor', () => {
+    const { descriptor, errors } = new Compiler().parseDescriptor(source)
+
+    expect(errors).toHaveLength(0)
+    expect(descriptor.source).toBe(source)
+    expect(descriptor.script).toBeNull()
+    expect(descriptor.scriptSetup).toMatchObject({
+      type: 'script',
+      lang: 'js',
+      setup: true,
+    })
+    expect(descriptor.scriptSetup?.content).toContain('const count = ref(0)')
+    expect(descriptor.template?.content.trim()).toBe(
+      '<Foo :count="count" @click="count++" />'
+    )
+    expect(descriptor.styles).toHaveLength(1)
+    expect(descriptor.styles[0]).toMatchObject({
+      type: 'style',
+      lang: 'css',
+      scoped: true,
+    })
+  })
+
+  test('should compile script and template separately', () => {
+    const compiler = new Compiler()
+    const { descriptor } = compiler.parseDescriptor(source)
+
+    const script = compiler.compileScript(descriptor, options)
+    expect(script.errors).toHaveLength(0)
+    expect(script.bindings).toStrictEqual({
+      Foo: BindingTypes.IMPORTED,
+      count: BindingTypes.SETUP_REF,
+    })
+    expect(script.code).not.toContain('render')
+    expect(script.code).toContain('__scopeId')
+
+    const template = compiler.compileTemplate(descriptor, options, script.bindings)
+    expect(template.errors).toHaveLength(0)
+    expect(template.code).toContain('export function render(')
+    expect(template.code).toContain('$setup.Foo')
+    expect(template.code).toContain('count: $setup.count')
+  })
+
+  test('should inline the template into script', () => {
+    const compiler = new Compiler()
+    const { descriptor } = compiler.parseDescriptor(source)
+
+    const script = compiler.compileScript(descriptor, {
+      ...options,
+      inlineTemplate: true,
+    })
+    expect(script.errors).toHaveLength(0)
+    expect(script.code).toContain('return (_ctx, _cache)=>')
+    expect(script.code).toContain('count: count.value')
+  })
+
+  test('should keep the comments', () => {
+    const compiler = new Compiler()
+    const { descriptor } = compiler.parseDescriptor(`<script setup>
+// Store of the component
+const store = /*#__PURE__*/ createStore()
+</script>
+<template><div :title="/* hint */ store.title">{{ store.name }}</div></template>`)
+
+    const script = compiler.compileScript(descriptor, options)
+    expect(script.code).toContain('// Store of the component')
+    expect(script.code).toContain('/*#__PURE__*/ createStore()')
+
+    const template = compiler.compileTemplate(descriptor, options, script.bindings)
+    expect(template.code).toContain('/* hint */')
+  })
+
+  test('should compile a vapor script the same way as compile', () => {
+    const vaporSource = `<script setup vapor>
+import { ref } from 'vue'
+const msg = ref('hello')
+</script>
+<template><div @click="msg = 'bye'">{{ msg }}</div></template>`
+
+    const compiler = new Compiler()
+    const { descriptor } = compiler.parseDescriptor(vaporSource)
+
+    const script = compiler.compileScript(descriptor, options)
+    expect(script.errors).toHaveLength(0)
+    expect(script.code).toContain('_defineVaporComponent(')
+    expect(script.code).not.toContain('_openBlock')
+    expect(script.code).toBe(compiler.compileSync(vaporSource, options).code)
+  })
+
+  test('should compile style with the same scope as script', () => {
+    const compiler = new Compiler()
+    const { descriptor } = compiler.parseDescriptor(source)
+
+    const script = compiler.compileScript(descriptor, options)
+    const scopeId = script.code.match(/__scopeId: "(data-v-[0-9a-f]+)"/)?.[1]
+    expect(scopeId).toBeDefined()
+
+    const { style, errors } = compiler.compileStyle(descriptor, 0, options)
+    expect(errors).toHaveLength(0)
+    expect(style.isCompiled).toBe(true)
+    expect(style.code).toBe(`.foo[${scopeId}]{color:red}`)
+
+    expect(() => compiler.compileStyle(descriptor, 1, options)).toThrow()
+  })
+})
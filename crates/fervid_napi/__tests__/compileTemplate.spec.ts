@@ -117,3 +117,49 @@ test('dynamic v-on + static v-on should merged', () => {
 
   expect(result.code).toMatchSnapshot()
 })
+
+test('preprocess pug', () => {
+  const compiler = new Compiler()
+  const result = compiler.compileSync(
+    `<template lang="pug">
+body
+  h1 Pug Examples
+  div.container(:class="{ active }")
+    p(v-if="ok") Cool Pug example! {{ msg }}
+</template>`,
+    { filename: 'example.vue', id: '' },
+  )
+
+  expect(result.errors.length).toBe(0)
+  expect(result.code).toMatch(`_createElementVNode("h1", null, "Pug Examples")`)
+  expect(result.code).toMatch(`"container"`)
+  expect(result.code).toMatch(`_toDisplayString(_ctx.msg)`)
+})
+
+test('should report unsupported pug features', () => {
+  const compiler = new Compiler()
+  const source = `<template lang="pug">
+div
+  each item in items
+    p= item
+</template>`
+  const result = compiler.compileSync(source, { filename: 'example.vue', id: '' })
+
+  expect(result.errors.length).toBe(1)
+  const [error] = result.errors
+  expect(source.slice(error.lo - 1, error.hi - 1)).toBe('each item in items')
+})
+
+test('template is parsed with the Vue rules instead of the HTML ones', () => {
+  const result = compile({
+    filename: 'example.vue',
+    source: `<table><MyRow v-for="row in rows" :key="row.id"/></table><p><div>{{ a < b }}</div></p>`
+  })
+
+  // `<div>` inside `<p>` is only reported as a warning
+  expect(result.errors.filter((error) => error.severity === 'error').length).toBe(0)
+  expect(result.code).toMatch(`_resolveComponent("MyRow")`)
+  expect(result.code).toMatch(`_createElementVNode("table", null, [`)
+  expect(result.code).toMatch(`_createElementVNode("p", null, [`)
+  expect(result.code).toMatch(`_ctx.a < _ctx.b`)
+})
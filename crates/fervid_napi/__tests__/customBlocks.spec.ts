@@ -0,0 +1,87 @@
+import { describe, expect, test } from 'vitest'
+import { Compiler, FervidCompileOptions } from '..'
+
+const options: FervidCompileOptions = {
+  filename: 'anonymous.vue',
+  id: 'xxxxxxxx',
+}
+
+function compile(source: string, i18n = true) {
+  const compiler = new Compiler({
+    customBlocks: { i18n },
+  })
+
+  return compiler.compileSync(source, options)
+}
+
+describe('custom blocks', () => {
+  test('custom blocks are emitted with their spans', () => {
+    const source = `<template><div/></template>
+<docs>
+# Docs
+</docs>
+<route lang="json">{ "name": "home" }</route>`
+    const { customBlocks, errors } = compile(source)
+
+    expect(errors.length).toBe(0)
+    expect(customBlocks.map((block) => block.tagName)).toEqual(['docs', 'route'])
+    for (const block of customBlocks) {
+      expect(source.slice(block.lo - 1, block.hi - 1)).toMatch(new RegExp(`^<${block.tagName}[\\s\\S]*</${block.tagName}>$`))
+    }
+    expect(customBlocks[0].content).toBe('\n# Docs\n')
+  })
+
+  test('i18n json', () => {
+    const { code, errors } = compile(`<script setup></script>
+<i18n>
+{ "en": { "hello": "Hello!" } }
+</i18n>`)
+
+    expect(errors.length).toBe(0)
+    expect(code).toMatch(`const _sfc_main = `)
+    expect(code).toMatch(`"hello": "Hello!"`)
+    expect(code).toMatch(`_Component.__i18n.push(`)
+    expect(code).toMatch(`if (typeof _sfc_block0 === "function") _sfc_block0(_sfc_main);`)
+    expect(code).toMatch(`export default _sfc_main;`)
+  })
+
+  test('i18n yaml with locale and global', () => {
+    const { code, errors } = compile(`<i18n lang="yaml" locale="ja" global>
+hello: こんにちは
+items:
+  - one
+  - two
+</i18n>`)
+
+    expect(errors.length).toBe(0)
+    expect(code).toMatch(`_Component.__i18nGlobal.push(`)
+    expect(code).toMatch(`"locale": "ja"`)
+    expect(code).toMatch(`"hello": "こんにちは"`)
+  })
+
+  test('i18n src', () => {
+    const { code, errors } = compile(`<i18n src="./locales.json"></i18n>`)
+
+    expect(errors.length).toBe(0)
+    expect(code).toMatch(`import _sfc_block0_resource from "./locales.json";`)
+    expect(code).toMatch(`const resource = _sfc_block0_resource;`)
+  })
+
+  test('i18n errors point into the block', () => {
+    const source = `<i18n lang="yaml">
+hello: [1, 2]
+</i18n>`
+    const { code, errors } = compile(source)
+
+    expect(errors.length).toBe(1)
+    expect(source.slice(errors[0].lo - 1)).toMatch(/^\[1, 2\]/)
+    expect(code).not.toMatch(`__i18n`)
+  })
+
+  test('i18n is not compiled by default', () => {
+    const { code, customBlocks } = compile(`<i18n>{}</i18n>`, false)
+
+    expect(code).not.toMatch(`__i18n`)
+    expect(customBlocks.length).toBe(1)
+  })
+})
@@ -43,8 +43,7 @@ const emit = defineEmits(['a', 'b'])
 //   emits: ['a', 'b'],
 //   setup(__props, { expose: __expose, emit: __emit }) {`)
 
-    // TODO PURE
-    expect(content).toMatch(`export default _defineComponent({
+    expect(content).toMatch(`export default /*#__PURE__*/ _defineComponent({
     emits: [
         'a',
         'b'
@@ -281,7 +280,7 @@ const emit = defineEmits(['a', 'b'])
         </script>`)
 
       expect(errors.length).toBe(1)
-      expect(errors[0].message).toMatch('DefineEmitsTypeAndNonTypeArguments')
+      expect(errors[0].code).toBe('define-emits-type-and-non-type-arguments')
     })
 
     test('mixed usage of property / call signature', () => {
@@ -293,7 +292,7 @@ const emit = defineEmits(['a', 'b'])
         </script>`)
 
       expect(errors.length).toBe(1)
-      expect(errors[0].message).toMatch('DefineEmitsMixedCallAndPropertySyntax')
+      expect(errors[0].code).toBe('define-emits-mixed-call-and-property-syntax')
     })
   })
 })
@@ -92,7 +92,7 @@ describe('defineModel()', () => {
     )
     assertCode(content)
     // TODO Also merge arrays into objects (refer to `_mergeModels` implementation)
-    expect(content).toMatch(`props: _mergeModels([
+    expect(content).toMatch(`props: /*#__PURE__*/ _mergeModels([
         'foo',
         'bar'
     ], {
@@ -372,7 +372,7 @@ describe('defineModel()', () => {
     )
     
     expect(errors.length).toBe(1)
-    expect(errors[0].message).toMatch('DuplicateDefineModelName')
+    expect(errors[0].code).toBe('duplicate-define-model-name')
   })
 
   test('error on duplicate model name: user', () => {
@@ -386,6 +386,6 @@ describe('defineModel()', () => {
     )
     
     expect(errors.length).toBe(1)
-    expect(errors[0].message).toMatch('DuplicateDefineModelName')
+    expect(errors[0].code).toBe('duplicate-define-model-name')
   })
 })
@@ -38,7 +38,7 @@ describe('defineOptions()', () => {
             </script>
         `)
         expect(errors.length).toBe(1)
-        expect(errors[0].message).toMatch('DuplicateDefineOptions')
+        expect(errors[0].code).toBe('duplicate-define-options')
     })
 
     it('should emit an error with props or emits property', () => {
@@ -49,7 +49,7 @@ describe('defineOptions()', () => {
                 </script>
             `)
             expect(errors.length).toBe(1)
-            expect(errors[0].message).toMatch('DefineOptionsProps')
+            expect(errors[0].code).toBe('define-options-props')
         }
 
         {
@@ -59,7 +59,7 @@ describe('defineOptions()', () => {
                 </script>
             `)
             expect(errors.length).toBe(1)
-            expect(errors[0].message).toMatch('DefineOptionsEmits')
+            expect(errors[0].code).toBe('define-options-emits')
         }
 
         {
@@ -69,7 +69,7 @@ describe('defineOptions()', () => {
                 </script>
             `)
             expect(errors.length).toBe(1)
-            expect(errors[0].message).toMatch('DefineOptionsExpose')
+            expect(errors[0].code).toBe('define-options-expose')
         }
 
         {
@@ -79,7 +79,7 @@ describe('defineOptions()', () => {
                 </script>
             `)
             expect(errors.length).toBe(1)
-            expect(errors[0].message).toMatch('DefineOptionsSlots')
+            expect(errors[0].code).toBe('define-options-slots')
         }
     })
 
@@ -90,7 +90,7 @@ describe('defineOptions()', () => {
             </script>
         `)
         expect(errors.length).toBe(1)
-        expect(errors[0].message).toMatch('DefineOptionsTypeArguments')
+        expect(errors[0].code).toBe('define-options-type-arguments')
     })
 
     it('should emit an error with type assertion', () => {
@@ -100,7 +100,7 @@ describe('defineOptions()', () => {
             </script>
         `)
         expect(errors.length).toBe(1)
-        expect(errors[0].message).toMatch('DefineOptionsProps')
+        expect(errors[0].code).toBe('define-options-props')
     })
 
     it('should emit an error with declaring props/emits/slots/expose', () => {
@@ -111,7 +111,7 @@ describe('defineOptions()', () => {
                   </script>
             `)
             expect(errors.length).toBe(1)
-            expect(errors[0].message).toMatch('DefineOptionsProps')
+            expect(errors[0].code).toBe('define-options-props')
         }
 
         {
@@ -121,7 +121,7 @@ describe('defineOptions()', () => {
                   </script>
             `)
             expect(errors.length).toBe(1)
-            expect(errors[0].message).toMatch('DefineOptionsEmits')
+            expect(errors[0].code).toBe('define-options-emits')
         }
 
         {
@@ -131,7 +131,7 @@ describe('defineOptions()', () => {
                   </script>
             `)
             expect(errors.length).toBe(1)
-            expect(errors[0].message).toMatch('DefineOptionsExpose')
+            expect(errors[0].code).toBe('define-options-expose')
         }
 
         {
@@ -141,7 +141,7 @@ describe('defineOptions()', () => {
                   </script>
             `)
             expect(errors.length).toBe(1)
-            expect(errors[0].message).toMatch('DefineOptionsSlots')
+            expect(errors[0].code).toBe('define-options-slots')
         }
     })
 })
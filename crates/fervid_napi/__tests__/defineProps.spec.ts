@@ -65,8 +65,7 @@ const props = defineProps({ foo: String })
 </script>
     `)
         assertCode(content)
-        // TODO /*@__PURE__*/
-        expect(content).toMatch(`export default _defineComponent({
+        expect(content).toMatch(`export default /*#__PURE__*/ _defineComponent({
     props: {
         foo: String
     },
@@ -849,7 +848,7 @@ const props = defineProps({ foo: String })
             },
         )
         expect(errors.length).toBe(1)
-        expect(errors[0].message).toMatch('DefinePropsDestructureForbidden')
+        expect(errors[0].code).toBe('define-props-destructure-forbidden')
     })
 
     describe('errors', () => {
@@ -859,7 +858,7 @@ const props = defineProps({ foo: String })
             </script>`)
 
             expect(errors.length).toBe(1)
-            expect(errors[0].message).toMatch('DefinePropsTypeAndNonTypeArguments')
+            expect(errors[0].code).toBe('define-props-type-and-non-type-arguments')
         })
     })
 
@@ -79,8 +79,7 @@ describe('sfc reactive props destructure', () => {
         // literals can be used as-is, non-literals are always returned from a
         // function
         // functions need to be marked with a skip marker
-        // TODO /*@__PURE__*/
-        expect(content).toMatch(`props: _mergeDefaults([
+        expect(content).toMatch(`props: /*#__PURE__*/ _mergeDefaults([
         'foo',
         'bar',
         'baz'
@@ -105,8 +104,7 @@ describe('sfc reactive props destructure', () => {
         // functions need to be marked with a skip marker since we cannot always
         // safely infer whether runtime type is Function (e.g. if the runtime decl
         // is imported, or spreads another object)
-        // TODO /*@__PURE__*/
-        expect(content).toMatch(`props: _mergeDefaults({
+        expect(content).toMatch(`props: /*#__PURE__*/ _mergeDefaults({
         foo: Number,
         bar: Object,
         func: Function,
@@ -138,9 +136,8 @@ describe('sfc reactive props destructure', () => {
             fooBar: BindingTypes.PROPS_ALIASED,
         })
 
-        // TODO /*@__PURE__*/
         expect(content).toMatch(`
-    props: _mergeDefaults([
+    props: /*#__PURE__*/ _mergeDefaults([
         'foo',
         'foo:bar'
     ], {
@@ -462,7 +459,7 @@ describe('sfc reactive props destructure', () => {
                     `<script setup>const { foo: [bar] } = defineProps(['foo'])</script>`,
                 )
                 expect(errors.length).toBe(1)
-                expect(errors[0].message).toMatch('DefinePropsDestructureUnsupportedNestedPattern')
+                expect(errors[0].code).toBe('define-props-destructure-unsupported-nested-pattern')
             }
 
             {
@@ -470,7 +467,7 @@ describe('sfc reactive props destructure', () => {
                     `<script setup>const { foo: { bar } } = defineProps(['foo'])</script>`,
                 )
                 expect(errors.length).toBe(1)
-                expect(errors[0].message).toMatch('DefinePropsDestructureUnsupportedNestedPattern')
+                expect(errors[0].code).toBe('define-props-destructure-unsupported-nested-pattern')
             }
         })
 
@@ -479,7 +476,7 @@ describe('sfc reactive props destructure', () => {
                 `<script setup>const { [foo]: bar } = defineProps(['foo'])</script>`,
             )
             expect(errors.length).toBe(1)
-            expect(errors[0].message).toMatch('DefinePropsDestructureCannotUseComputedKey')
+            expect(errors[0].code).toBe('define-props-destructure-cannot-use-computed-key')
         })
 
         test('should warn when used with withDefaults', () => {
@@ -490,7 +487,7 @@ describe('sfc reactive props destructure', () => {
             )
 
             expect(errors.length).toBe(1)
-            expect(errors[0].message).toMatch('DefinePropsDestructureUnnecessaryWithDefaults')
+            expect(errors[0].code).toBe('define-props-destructure-unnecessary-with-defaults')
         })
 
         // TODO
@@ -518,7 +515,7 @@ describe('sfc reactive props destructure', () => {
                     </script>`,
                 )
                 expect(errors.length).toBe(1)
-                expect(errors[0].message).toMatch('DefinePropsDestructureCannotAssignToReadonly')
+                expect(errors[0].code).toBe('define-props-destructure-cannot-assign-to-readonly')
             }
 
             {
@@ -529,7 +526,7 @@ describe('sfc reactive props destructure', () => {
                     </script>`,
                 )
                 expect(errors.length).toBe(1)
-                expect(errors[0].message).toMatch('DefinePropsDestructureCannotAssignToReadonly')
+                expect(errors[0].code).toBe('define-props-destructure-cannot-assign-to-readonly')
             }
         })
 
@@ -543,7 +540,7 @@ describe('sfc reactive props destructure', () => {
                     </script>`,
                 )
                 expect(errors.length).toBe(1)
-                expect(errors[0].message).toMatch('DefinePropsDestructureShouldNotPassToWatch')
+                expect(errors[0].code).toBe('define-props-destructure-should-not-pass-to-watch')
             }
 
             {
@@ -555,7 +552,7 @@ describe('sfc reactive props destructure', () => {
                     </script>`,
                 )
                 expect(errors.length).toBe(1)
-                expect(errors[0].message).toMatch('DefinePropsDestructureShouldNotPassToWatch')
+                expect(errors[0].code).toBe('define-props-destructure-should-not-pass-to-watch')
             }
 
             {
@@ -567,7 +564,7 @@ describe('sfc reactive props destructure', () => {
                     </script>`,
                 )
                 expect(errors.length).toBe(1)
-                expect(errors[0].message).toMatch('DefinePropsDestructureShouldNotPassToToRef')
+                expect(errors[0].code).toBe('define-props-destructure-should-not-pass-to-to-ref')
             }
 
             {
@@ -579,7 +576,7 @@ describe('sfc reactive props destructure', () => {
                     </script>`,
                 )
                 expect(errors.length).toBe(1)
-                expect(errors[0].message).toMatch('DefinePropsDestructureShouldNotPassToToRef')
+                expect(errors[0].code).toBe('define-props-destructure-should-not-pass-to-to-ref')
             }
         })
 
@@ -592,7 +589,7 @@ describe('sfc reactive props destructure', () => {
             )
 
             expect(errors.length).toBe(1)
-            expect(errors[0].message).toMatch('DefinePropsDestructureDeclaredTypeMismatch')
+            expect(errors[0].code).toBe('define-props-destructure-declared-type-mismatch')
         })
 
         // #8017
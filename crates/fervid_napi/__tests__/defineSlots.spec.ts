@@ -47,7 +47,7 @@ describe('defineSlots()', () => {
       </script>
     `)
     expect(errors.length).toBe(1)
-    expect(errors[0].message).toMatch('DuplicateDefineSlots')
+    expect(errors[0].code).toBe('duplicate-define-slots')
   })
 
   test('error on arguments', () => {
@@ -59,6 +59,6 @@ describe('defineSlots()', () => {
       </script>
     `)
     expect(errors.length).toBe(1)
-    expect(errors[0].message).toMatch('DefineSlotsArguments')
+    expect(errors[0].code).toBe('define-slots-arguments')
   })
 })
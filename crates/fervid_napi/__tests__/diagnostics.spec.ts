@@ -0,0 +1,123 @@
+import { describe, expect, test } from 'vitest'
+import { Compiler, FervidCompileOptions } from '..'
+
+const options: FervidCompileOptions = {
+  filename: 'anonymous.vue',
+  id: 'xxxxxxxx',
+}
+
+const source = `<script setup>
+import { ref } from 'vue'
+defineOptions({
+  props: { foo: String }
+})
+</script>`
+
+describe('diagnostics', () => {
+  test('errors have a code and a message', () => {
+    const { errors } = new Compiler().compileSync(source, options)
+
+    expect(errors.length).toBe(1)
+    expect(errors[0].code).toBe('define-options-props')
+    expect(errors[0].message).toBe(
+      'defineOptions() cannot be used to declare props. Use defineProps() instead.',
+    )
+    expect(errors[0].codeFrame).toBeUndefined()
+    expect(errors[0].startLineNumber).toBe(0)
+  })
+
+  test('lines, columns and code frames', () => {
+    const compiler = new Compiler({
+      diagnostics: { errorLinesColumns: true, codeFrames: true },
+    })
+    const { errors } = compiler.compileSync(source, options)
+
+    expect(errors.length).toBe(1)
+    expect(errors[0].startLineNumber).toBe(4)
+    expect(errors[0].startColumn).toBe(2)
+    expect(errors[0].endLineNumber).toBe(4)
+    expect(errors[0].endColumn).toBe(24)
+    expect(errors[0].codeFrame).toBe(`  |
+2 | import { ref } from 'vue'
+3 | defineOptions({
+4 |   props: { foo: String }
+  |   ^^^^^^^^^^^^^^^^^^^^^^
+5 | })
+6 | </script>`)
+  })
+
+  test('parse errors', () => {
+    const compiler = new Compiler({ diagnostics: { codeFrames: true } })
+    const { errors } = compiler.compileSync(
+      `<template><div :[foo></div></template>`,
+      options,
+    )
+
+    expect(errors[0].code).toBe('directive-syntax-dynamic-argument')
+    expect(errors[0].codeFrame).toBe(`  |
+1 | <template><div :[foo></div></template>
+  |                ^^^^^`)
+  })
+
+  test('code frames of CRLF sources', () => {
+    const compiler = new Compiler({
+      diagnostics: { errorLinesColumns: true, codeFrames: true },
+    })
+    const { errors } = compiler.compileSync(
+      `<script>\r\nimport Mixi\r\nn from "x"\r\n</script>`,
+      options,
+    )
+
+    const syntaxError = errors.find((e) => e.code === 'ecma-syntax-error')
+    expect(syntaxError?.startLineNumber).toBe(3)
+    expect(syntaxError?.startColumn).toBe(0)
+    expect(syntaxError?.codeFrame).toBe(`  |
+1 | <script>
+2 | import Mixi
+3 | n from "x"
+  | ^
+4 | </script>`)
+  })
+
+  test('error recovery modes', () => {
+    const source = `<template><div :[foo></div>{{ a. }}</template>`
+
+    expect(() =>
+      new Compiler({ diagnostics: { errorRecoveryMode: 'hard-fail-on-error' } }).compileSync(
+        source,
+        options,
+      ),
+    ).toThrow()
+
+    const reportAll = new Compiler({
+      diagnostics: { errorRecoveryMode: 'soft-report-all' },
+    }).compileSync(source, options)
+    expect(reportAll.code).toBe('')
+    expect(reportAll.errors.map((e) => e.code)).toEqual([
+      'directive-syntax-dynamic-argument',
+      'ecma-syntax-error',
+    ])
+
+    const recoverUnsafe = new Compiler({
+      diagnostics: { errorRecoveryMode: 'soft-recover-unsafe' },
+    }).compileSync(`<template>{{ a. }}</template>`, options)
+    expect(recoverUnsafe.errors.length).toBe(1)
+    expect(recoverUnsafe.code).toContain('_ctx.a')
+  })
+
+  test('unknown option values are errors', () => {
+    const source = `<template><div /></template>`
+
+    // @ts-expect-error a typo of 'vue2'
+    const typoTarget = new Compiler({ target: 'vue-2' })
+    expect(() => typoTarget.compileSync(source, options)).toThrow(
+      'Unknown value of `target`: "vue-2"',
+    )
+
+    // @ts-expect-error not a recovery mode
+    const typoMode = new Compiler({ diagnostics: { errorRecoveryMode: 'soft' } })
+    expect(() => typoMode.compileSync(source, options)).toThrow(
+      'Unknown value of `diagnostics.errorRecoveryMode`: "soft"',
+    )
+  })
+})
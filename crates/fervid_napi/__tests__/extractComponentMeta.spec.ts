@@ -0,0 +1,33 @@
+import { test, expect } from 'vitest'
+
+import { Compiler } from '../index'
+
+test('should extract component meta', () => {
+  const meta = new Compiler().extractComponentMeta(
+    `<script setup lang="ts">
+interface Props {
+  /** Text of the button */
+  label: string
+  size?: 'sm' | 'md'
+}
+withDefaults(defineProps<Props>(), { size: 'md' })
+defineEmits<{ click: [e: MouseEvent] }>()
+const open = defineModel<boolean>('open')
+</script>
+
+<template>
+  <button><slot name="icon" /><slot /></button>
+</template>`,
+    'src/MyButton.vue',
+  )
+
+  expect(meta.errors).toHaveLength(0)
+  expect(meta.name).toBe('MyButton')
+  expect(meta.props.map((p) => [p.name, p.tsType, p.required, p.default, p.description])).toEqual([
+    ['label', 'string', true, undefined, 'Text of the button'],
+    ['size', `'sm' | 'md'`, false, `'md'`, undefined],
+  ])
+  expect(meta.events).toMatchObject([{ name: 'click', payload: ['MouseEvent'] }])
+  expect(meta.models).toMatchObject([{ name: 'open', tsType: 'boolean', runtimeTypes: ['Boolean'] }])
+  expect(meta.slots.map((s) => s.name)).toEqual(['icon', 'default'])
+})
@@ -0,0 +1,28 @@
+import { test, expect } from 'vitest'
+
+import { Compiler } from '../index'
+
+test('should generate virtual ts with mappings', () => {
+  const source = `<script setup lang="ts">
+import Child from './Child.vue'
+const items = [1, 2, 3]
+</script>
+
+<template>
+  <Child v-for="item in items" :value="item * 2" />
+</template>`
+
+  const result = new Compiler().generateVirtualTs(source, 'src/Parent.vue')
+
+  expect(result.errors).toHaveLength(0)
+  expect(result.code).toContain('for (const [item] of __VLS_vFor(items)) {')
+  expect(result.code).toContain('__VLS_component(Child)({ "value": item * 2 });')
+
+  // Every mapping points to the same text in both files (sources are ASCII)
+  expect(result.mappings.length).toBeGreaterThan(0)
+  for (const { generatedOffset, sourceOffset, length } of result.mappings) {
+    expect(result.code.slice(generatedOffset, generatedOffset + length)).toBe(
+      source.slice(sourceOffset, sourceOffset + length),
+    )
+  }
+})
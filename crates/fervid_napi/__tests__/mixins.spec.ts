@@ -0,0 +1,50 @@
+import { mkdtempSync, writeFileSync } from 'node:fs'
+import { tmpdir } from 'node:os'
+import { join } from 'node:path'
+import { describe, expect, test } from 'vitest'
+import { assertCode, compile } from './utils'
+
+describe('mixins and extends', () => {
+  test('resolves the template bindings of local mixins', () => {
+    const { content, errors } = compile(`<script>
+const base = { props: ['foo'] }
+const mixin = { data: () => ({ bar: 1 }), methods: { baz() {} } }
+export default { extends: base, mixins: [mixin] }
+</script>
+<template><div @click="baz">{{ foo }} {{ bar }}</div></template>`)
+
+    expect(errors).toEqual([])
+    expect(content).toMatch('$props.foo')
+    expect(content).toMatch('$data.bar')
+    expect(content).toMatch('$options.baz')
+    assertCode(content)
+  })
+
+  test('leaves imported mixins unresolved', () => {
+    const { content } = compile(`<script>
+import mixin from './mixin'
+export default { mixins: [mixin] }
+</script>
+<template><div>{{ bar }}</div></template>`)
+
+    expect(content).toMatch('_ctx.bar')
+  })
+
+  test('reads imported mixins from the file system with `resolveMixins`', () => {
+    const dir = mkdtempSync(join(tmpdir(), 'fervid-mixins-'))
+    writeFileSync(join(dir, 'mixin.js'), 'export default { data: () => ({ bar: 1 }) }')
+
+    const { content, errors } = compile(
+      `<script>
+import mixin from './mixin'
+export default { mixins: [mixin] }
+</script>
+<template><div>{{ bar }}</div></template>`,
+      { filename: join(dir, 'Comp.vue') },
+      { script: { resolveMixins: true } },
+    )
+
+    expect(errors).toEqual([])
+    expect(content).toMatch('$data.bar')
+  })
+})
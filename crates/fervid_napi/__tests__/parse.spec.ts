@@ -0,0 +1,53 @@
+import { test, expect } from 'vitest'
+
+import { BindingTypes, Compiler } from '../index'
+
+const source = `<script setup>
+import { ref } from 'vue'
+const count = ref(0)
+</script>
+
+<template>
+  <button v-if="count > 0" @click="count++">{{ count }}</button>
+</template>`
+
+test('should parse SFC into a descriptor', () => {
+  const result = new Compiler().parse(source)
+
+  expect(result.errors).toHaveLength(0)
+  expect(result.setupBindings).toBeUndefined()
+
+  const { template, scriptSetup } = result.descriptor
+  expect(template.lang).toBe('html')
+  expect(scriptSetup.isSetup).toBe(true)
+  expect(scriptSetup.content.type).toBe('Module')
+
+  const button = template.roots.find((node: any) => 'Element' in node).Element
+  expect(button.startingTag.tagName).toBe('button')
+  expect(button.startingTag.directives.vIf.type).toBe('BinaryExpression')
+})
+
+test('should parse and transform SFC', () => {
+  const result = new Compiler().parse(source, { filename: 'Counter.vue', id: '' })
+
+  expect(result.errors).toHaveLength(0)
+  expect(result.setupBindings).toStrictEqual({
+    count: BindingTypes.SETUP_REF,
+  })
+
+  // `v-if` is transformed into a conditional sequence
+  const { template } = result.descriptor
+  expect(template.roots.some((node: any) => 'ConditionalSeq' in node)).toBe(true)
+})
+
+test('should report all parsing errors', () => {
+  const result = new Compiler({
+    diagnostics: { errorRecoveryMode: 'soft-report-all' },
+  }).parse(`<template><div :[foo></div>{{ a. }}</template>`, { filename: 'Broken.vue', id: '' })
+
+  expect(result.errors.map((e) => e.code)).toEqual([
+    'directive-syntax-dynamic-argument',
+    'ecma-syntax-error',
+  ])
+  expect(result.descriptor.template).toBeNull()
+})
@@ -0,0 +1,34 @@
+import { describe, expect, test } from 'vitest'
+import { compile } from './utils'
+
+const source = `<script setup>
+import { onMounted, onServerPrefetch, ref } from 'vue'
+const count = ref(0)
+onMounted(() => window.alert(count.value))
+onServerPrefetch(() => {})
+</script>
+<template>
+  <button @click="count++">{{ count }}</button>
+  <MyComp @change="count++" />
+</template>`
+
+describe('strip client-only code', () => {
+  test('strips lifecycle hooks and native listeners for the server', () => {
+    const { content, errors } = compile(source, {}, { ssr: true, stripClientOnly: true })
+
+    expect(errors).toEqual([])
+    expect(content).not.toMatch('onMounted(')
+    expect(content).not.toMatch('onClick')
+    expect(content).toMatch('onServerPrefetch(')
+    expect(content).toMatch('onChange')
+  })
+
+  test('keeps everything by default and without ssr', () => {
+    for (const options of [{ ssr: true }, { stripClientOnly: true }]) {
+      const { content } = compile(source, {}, options)
+
+      expect(content).toMatch('onMounted(')
+      expect(content).toMatch('onClick')
+    }
+  })
+})
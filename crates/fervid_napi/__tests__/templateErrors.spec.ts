@@ -0,0 +1,79 @@
+import { describe, expect, test } from 'vitest'
+import { compile } from './utils'
+
+function errorCodes(template: string) {
+  return compile(`<template>${template}</template>`).errors.map((error) => error.code)
+}
+
+describe('template errors', () => {
+  test('v-else without adjacent v-if', () => {
+    expect(errorCodes(`<div v-else/>`)).toEqual(['v-else-no-adjacent-if'])
+    expect(errorCodes(`<div v-if="a"/><span/><div v-else-if="b"/>`)).toEqual(['v-else-no-adjacent-if'])
+    expect(errorCodes(`<div v-if="a"/><!-- comment --><div v-else/>`)).toEqual([])
+  })
+
+  test('missing and malformed expressions', () => {
+    expect(errorCodes(`<div v-if/>`)).toEqual(['v-if-no-expression'])
+    expect(errorCodes(`<div v-show=""/>`)).toEqual(['v-show-no-expression'])
+    expect(errorCodes(`<div v-for="item"/>`)).toEqual(['v-for-malformed-expression'])
+    expect(errorCodes(`<input v-model="foo()">`)).toEqual(['v-model-malformed-expression'])
+  })
+
+  test('v-slot', () => {
+    expect(errorCodes(`<div v-slot="{ foo }"/>`)).toEqual(['v-slot-misplaced'])
+    expect(errorCodes(`<Comp v-slot><template #foo/></Comp>`)).toEqual(['v-slot-mixed-slot-usage'])
+    expect(errorCodes(`<Comp><template #foo/><template #foo/></Comp>`)).toEqual([
+      'v-slot-duplicate-slot-names',
+    ])
+    expect(errorCodes(`<Comp><template #default/><div/></Comp>`)).toEqual([
+      'v-slot-extraneous-default-slot-children',
+    ])
+    expect(errorCodes(`<Comp><template #foo v-if="a"/><template #foo v-else/></Comp>`)).toEqual([])
+  })
+
+  test('v-model on plain elements', () => {
+    expect(errorCodes(`<div v-model="foo"/>`)).toEqual(['v-model-on-invalid-element'])
+    expect(errorCodes(`<input v-model:value="foo">`)).toEqual(['v-model-arg-on-element'])
+    expect(errorCodes(`<textarea v-model="foo"></textarea><Comp v-model:value="foo"/>`)).toEqual([])
+  })
+
+  test('<template v-for> key placement', () => {
+    expect(errorCodes(`<template v-for="i in list"><div :key="i"/></template>`)).toEqual([
+      'v-for-template-key-placement',
+    ])
+    expect(errorCodes(`<template v-for="i in list" :key="i"><div/></template>`)).toEqual([])
+  })
+
+  test('malformed markup', () => {
+    expect(errorCodes(`<div></span></div>`)).toEqual(['invalid-end-tag'])
+    expect(errorCodes(`<div><span></div>`)).toEqual(['missing-end-tag'])
+    expect(errorCodes(`<div id="a" id="b"/>`)).toEqual(['duplicate-attribute'])
+    // Parsed as written, the nesting is only a warning
+    expect(errorCodes(`<table><MyRow/></table><p><div/></p><textarea/>`)).toEqual([
+      'invalid-html-nesting',
+    ])
+  })
+
+  test('invalid HTML nesting is a warning', () => {
+    const { errors } = compile(`<template><p><div/></p><a><span><a/></span></a></template>`)
+    expect(errors.map((error) => [error.code, error.severity, error.message])).toEqual([
+      [
+        'invalid-html-nesting',
+        'warning',
+        '<div> cannot be child of <p>, according to HTML specifications. This can cause hydration errors or potentially disrupt future functionality.',
+      ],
+      [
+        'invalid-html-nesting',
+        'warning',
+        '<a> cannot be nested inside another <a>, according to HTML specifications. This can cause hydration errors or potentially disrupt future functionality.',
+      ],
+    ])
+
+    expect(errorCodes(`<table><tr/></table><button><input></button>`)).toEqual([
+      'invalid-html-nesting',
+      'invalid-html-nesting',
+    ])
+    expect(errorCodes(`<table><tbody><tr><td/></tr></tbody></table><ul><slot><li/></slot></ul>`)).toEqual([])
+    expect(compile(`<template><div v-else/></template>`).errors[0].severity).toBe('error')
+  })
+})
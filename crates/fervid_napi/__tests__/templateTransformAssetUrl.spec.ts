@@ -82,6 +82,24 @@ describe('compiler sfc: transform asset url', () => {
     expect(code).toMatchSnapshot()
   })
 
+  test('with aliases', () => {
+    const { code } = compileWithAssetUrls(
+      `<img src="@/logo.png"/>` + // -> /src/logo.png
+        `<img src="~@/logo.png"/>` + // -> /src/logo.png
+        `<img src="@assets/logo.png"/>` + // -> longest alias wins
+        `<img src="@theme/logo.png"/>`, // -> not aliased, still converts to import
+      {
+        aliases: {
+          '@': '/src',
+          '@assets': './assets',
+        },
+      },
+    )
+    expect(code).toMatch(`import _imports_0 from "/src/logo.png"`)
+    expect(code).toMatch(`import _imports_1 from "./assets/logo.png"`)
+    expect(code).toMatch(`import _imports_2 from "@theme/logo.png"`)
+  })
+
   test('with includeAbsolute: true', () => {
     const { code } = compileWithAssetUrls(
       `<img src="./bar.png"/>` +
@@ -0,0 +1,80 @@
+import { describe, expect, test } from 'vitest'
+import { Compiler, FervidCompileOptions, FervidTransformAssetUrlsOptions } from '..'
+
+const mockId = 'xxxxxxxx'
+
+function compileWithSrcset(template: string, options?: FervidTransformAssetUrlsOptions) {
+  const normalizedOptions: FervidCompileOptions = {
+    filename: 'anonymous.vue',
+    id: mockId,
+  }
+
+  const compiler = new Compiler({
+    template: {
+      transformAssetUrls: options,
+    },
+  })
+
+  return compiler.compileSync(`<template>${template}</template>`, normalizedOptions)
+}
+
+const src = `
+<img src="./logo.png" srcset=""/>
+<img src="./logo.png" srcset="./logo.png"/>
+<img src="./logo.png" srcset="./logo.png 2x"/>
+<img src="./logo.png" srcset="./logo.png 2x, ./logo.png 3x"/>
+<img src="/logo.png" srcset="/logo.png, /logo.png 2x"/>
+<img src="https://example.com/logo.png" srcset="https://example.com/logo.png, https://example.com/logo.png 2x"/>
+<img src="data:image/png;base64,i" srcset="data:image/png;base64,i 1x, data:image/png;base64,i 2x"/>
+`
+
+describe('compiler sfc: transform srcset', () => {
+  test('transform srcset', () => {
+    const { code } = compileWithSrcset(src)
+
+    expect(code).toMatch(`import _imports_0 from "./logo.png"`)
+    expect(code).toMatch(`srcset: _imports_0 + " 2x"`)
+    expect(code).toMatch(`srcset: _imports_0 + " 2x, " + _imports_0 + " 3x"`)
+    // Absolute, external and data URLs are left as-is
+    expect(code).toMatch(`srcset: "/logo.png, /logo.png 2x"`)
+    expect(code).toMatch(`srcset: "https://example.com/logo.png, https://example.com/logo.png 2x"`)
+    expect(code).toMatch(`srcset: "data:image/png;base64,i 1x, data:image/png;base64,i 2x"`)
+  })
+
+  test('transform srcset w/ base', () => {
+    const { code } = compileWithSrcset(src, {
+      base: '/foo',
+    })
+
+    expect(code).not.toMatch(`import _imports_0`)
+    expect(code).toMatch(`srcset: "/foo/logo.png 2x, /foo/logo.png 3x"`)
+  })
+
+  test('transform srcset w/ includeAbsolute: true', () => {
+    const { code } = compileWithSrcset(src, {
+      includeAbsolute: true,
+    })
+
+    expect(code).toMatch(`import _imports_1 from "/logo.png"`)
+    expect(code).toMatch(`srcset: _imports_1 + ", " + _imports_1 + " 2x"`)
+  })
+
+  test('transform srcset w/ module requests and aliases', () => {
+    const { code } = compileWithSrcset(
+      `<img srcset="~fixtures/logo.png 2x, @theme/logo.png 3x"/>` +
+        `<source srcset="@/logo.png 1x, @assets/logo.png 2x"/>`,
+      {
+        aliases: {
+          '@': '/src',
+          '@assets': '/src/assets',
+        },
+      },
+    )
+
+    expect(code).toMatch(`import _imports_0 from "fixtures/logo.png"`)
+    expect(code).toMatch(`import _imports_1 from "@theme/logo.png"`)
+    expect(code).toMatch(`import _imports_2 from "/src/logo.png"`)
+    expect(code).toMatch(`import _imports_3 from "/src/assets/logo.png"`)
+    expect(code).toMatch(`srcset: _imports_2 + " 1x, " + _imports_3 + " 2x"`)
+  })
+})
@@ -0,0 +1,58 @@
+import { describe, expect, test } from 'vitest'
+import { assertCode, compile } from './utils'
+
+const source = (attrs: string) => `<script setup ${attrs}>
+import { ref } from 'vue'
+const msg = ref('hello')
+const list = ref([1, 2])
+function onClick() {}
+</script>
+<template>
+  <div @click="onClick">{{ msg }}</div>
+  <p v-for="item in list" :key="item">{{ item }}</p>
+</template>`
+
+describe('vapor mode', () => {
+  test('compiles `<script setup vapor>` to a Vapor component', () => {
+    const { content, errors } = compile(source('vapor'))
+
+    expect(errors).toEqual([])
+    expect(content).toMatch('export default /*#__PURE__*/ _defineVaporComponent({')
+    expect(content).toMatch('const t0 = _template("<div> </div>", true)')
+    expect(content).toMatch('_delegateEvents("click")')
+    expect(content).toMatch('n0.$evtclick = onClick')
+    expect(content).toMatch('_renderEffect(')
+    expect(content).toMatch('_createFor(')
+    expect(content).not.toMatch('_openBlock')
+    assertCode(content)
+  })
+
+  test('sets `v-once` content once and applies custom directives', () => {
+    const { content, errors } = compile(`<script setup vapor>
+import { ref } from 'vue'
+const msg = ref('hello')
+const vFocus = { mounted: (el) => el.focus() }
+</script>
+<template>
+  <div v-once :id="msg">{{ msg }}</div>
+  <input v-focus v-tooltip:top="msg">
+  <p>{{ unknown }}</p>
+</template>`)
+
+    expect(errors).toEqual([])
+    expect(content).toMatch('_setText(x1, _toDisplayString(msg.value))')
+    expect(content).toMatch('_setProp(n0, "id", msg.value)')
+    expect(content).toMatch('const _directive_tooltip = _resolveDirective("tooltip")')
+    expect(content).toMatch('_withVaporDirectives(n2, [')
+    expect(content).toMatch('_renderEffect(()=>_setText(x4, _toDisplayString(unknown)))')
+    expect(content).not.toMatch('_ctx')
+    assertCode(content)
+  })
+
+  test('keeps the virtual DOM without `vapor`', () => {
+    const { content } = compile(source(''))
+
+    expect(content).not.toMatch('_defineVaporComponent')
+    expect(content).toMatch('_openBlock')
+  })
+})
@@ -0,0 +1,61 @@
+import { describe, expect, test } from 'vitest'
+import { assertCode, compile } from './utils'
+
+const source = `<script setup>
+import { ref } from 'vue'
+import Comp from './Comp.vue'
+const msg = ref('hello')
+function onClick() {}
+</script>
+<template>
+  <div class="a" @click.stop="onClick">
+    {{ msg }}
+    <Comp v-model="msg">
+      <template #header="{ title }">{{ title }}</template>
+    </Comp>
+  </div>
+</template>`
+
+describe('vue 2 target', () => {
+  test('compiles to a Vue 2.7 render function', () => {
+    const { content, errors } = compile(source, {}, { target: 'vue2' })
+
+    expect(errors).toEqual([])
+    expect(content).toMatch('var _vm = this, _c = _vm._self._c, _setup = _vm._self._setupProxy')
+    expect(content).toMatch('_c("div"')
+    expect(content).toMatch('_vm._v(')
+    expect(content).toMatch('_vm._s(_setup.msg)')
+    expect(content).toMatch('scopedSlots')
+    expect(content).toMatch('__sfc: true')
+    expect(content).not.toMatch('_openBlock')
+    assertCode(content)
+  })
+
+  test('requires a single root element', () => {
+    const { errors } = compile(`<template><div /><p /></template>`, {}, { target: 'vue2' })
+
+    expect(errors.length).toBe(1)
+  })
+
+  test('reports `defineModel`, which needs Vue 3', () => {
+    const { content, errors } = compile(
+      `<script setup>
+const model = defineModel()
+</script>
+<template><input v-model="model"></template>`,
+      {},
+      { target: 'vue2' },
+    )
+
+    expect(errors.length).toBe(1)
+    expect(errors[0].code).toBe('vue2-unsupported-macro')
+    expect(content).not.toMatch('useModel')
+  })
+
+  test('keeps the Vue 3 output by default', () => {
+    const { content } = compile(source)
+
+    expect(content).toMatch('_openBlock')
+    expect(content).not.toMatch('_vm._self._c')
+  })
+})
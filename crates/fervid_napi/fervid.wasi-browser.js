@@ -58,4 +58,6 @@ const {
 export default __napiModule.exports
 export const Compiler = __napiModule.exports.Compiler
 export const FervidJsCompiler = __napiModule.exports.FervidJsCompiler
+export const SfcDescriptor = __napiModule.exports.SfcDescriptor
+export const SfcDescriptorHandle = __napiModule.exports.SfcDescriptorHandle
 export const BindingTypes = __napiModule.exports.BindingTypes
@@ -110,4 +110,6 @@ const { instance: __napiInstance, module: __wasiModule, napiModule: __napiModule
 module.exports = __napiModule.exports
 module.exports.Compiler = __napiModule.exports.Compiler
 module.exports.FervidJsCompiler = __napiModule.exports.FervidJsCompiler
+module.exports.SfcDescriptor = __napiModule.exports.SfcDescriptor
+module.exports.SfcDescriptorHandle = __napiModule.exports.SfcDescriptorHandle
 module.exports.BindingTypes = __napiModule.exports.BindingTypes
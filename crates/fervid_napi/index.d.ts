@@ -6,9 +6,60 @@ export declare class Compiler {
   constructor(options?: FervidJsCompilerOptions | undefined | null)
   compileSync(source: string, options: FervidCompileOptions): CompileResult
   compileAsync(source: string, options: FervidCompileOptions, signal?: AbortSignal | undefined | null): Promise<unknown>
+  /**
+   * Compiles multiple files in parallel.
+   * Results are in the same order as `inputs`.
+   */
+  compileManyAsync(inputs: Array<FervidCompileManyItem>, signal?: AbortSignal | undefined | null): Promise<unknown>
+  /**
+   * Parses an SFC and returns its descriptor, e.g. for AST explorers.
+   * When `options` are provided, the template and styles are transformed like in `compileSync`.
+   */
+  parse(source: string, options?: FervidCompileOptions | undefined | null): ParseResult
+  /**
+   * Extracts props, events, slots, models and exposed members of a component
+   * without compiling it.
+   */
+  extractComponentMeta(source: string, filename: string): ComponentMeta
+  /**
+   * Generates a virtual TypeScript file for type-checking the SFC template and scripts.
+   * `mappings` point from the generated code back to the `source`.
+   */
+  generateVirtualTs(source: string, filename: string): VirtualTs
+  /**
+   * Parses an SFC into a descriptor which can be compiled block by block
+   * using `compileScript`, `compileTemplate` and `compileStyle`.
+   */
+  parseDescriptor(source: string): SfcParseResult
+  /**
+   * Compiles `<script>` and `<script setup>` into the component module.
+   * Unless the template is inlined, returned `bindings` need to be passed to `compileTemplate`.
+   */
+  compileScript(descriptor: SfcDescriptor, options: FervidCompileOptions): CompileScriptResult
+  /** Compiles `<template>` into a module exporting the `render` function. */
+  compileTemplate(descriptor: SfcDescriptor, options: FervidCompileOptions, bindings?: Record<string, BindingTypes> | undefined | null): CompileTemplateResult
+  /**
+   * Compiles the `<style>` block at `index`.
+   * Only scoped CSS is transformed, other styles are returned as-is.
+   */
+  compileStyle(descriptor: SfcDescriptor, index: number, options: FervidCompileOptions): CompileStyleResult
 }
 export type FervidJsCompiler = Compiler
 
+/**
+ * Parsed SFC kept on the Rust side, so that its blocks can be compiled separately without re-parsing.
+ * Obtained from `Compiler.parseDescriptor`.
+ */
+export declare class SfcDescriptor {
+  get source(): string
+  get template(): SfcBlock | null
+  get script(): SfcBlock | null
+  get scriptSetup(): SfcBlock | null
+  get styles(): Array<SfcBlock>
+  get customBlocks(): Array<SfcBlock>
+}
+export type SfcDescriptorHandle = SfcDescriptor
+
 /**
  * This is a copied enum from `fervid_core` with `napi` implementation to avoid littering the core crate.
  *
@@ -66,6 +117,86 @@ export interface CompileResult {
   setupBindings?: Record<string, BindingTypes> | undefined
 }
 
+export interface CompileScriptResult {
+  code: string
+  sourceMap?: string
+  /** Bindings of the scripts, to be passed to `compileTemplate` */
+  bindings: Record<string, BindingTypes>
+  errors: Array<SerializedError>
+}
+
+export interface CompileStyleResult {
+  style: Style
+  errors: Array<SerializedError>
+}
+
+export interface CompileTemplateResult {
+  code: string
+  sourceMap?: string
+  errors: Array<SerializedError>
+}
+
+export interface ComponentEvent {
+  name: string
+  payload: Array<string>
+  description?: string
+  lo: number
+  hi: number
+}
+
+export interface ComponentExposed {
+  name: string
+  description?: string
+  lo: number
+  hi: number
+}
+
+/**
+ * Public interface of a component, for documentation tools.
+ * Types and default values are kept as they are written in the source.
+ */
+export interface ComponentMeta {
+  name?: string
+  props: Array<ComponentProp>
+  events: Array<ComponentEvent>
+  slots: Array<ComponentSlot>
+  models: Array<ComponentModel>
+  exposed: Array<ComponentExposed>
+  errors: Array<SerializedError>
+}
+
+export interface ComponentModel {
+  name: string
+  tsType?: string
+  runtimeTypes: Array<string>
+  required: boolean
+  default?: string
+  description?: string
+  lo: number
+  hi: number
+}
+
+export interface ComponentProp {
+  name: string
+  tsType?: string
+  /** Runtime types, e.g. `["String", "Number"]`. Empty when any type is accepted */
+  runtimeTypes: Array<string>
+  required: boolean
+  default?: string
+  /** Text of the JSDoc comment */
+  description?: string
+  lo: number
+  hi: number
+}
+
+export interface ComponentSlot {
+  name: string
+  propsType?: string
+  description?: string
+  lo: number
+  hi: number
+}
+
 export interface CustomBlock {
   content: string
   lo: number
@@ -73,6 +204,12 @@ export interface CustomBlock {
   tagName: string
 }
 
+/** A single file for `compileManyAsync` */
+export interface FervidCompileManyItem {
+  source: string
+  options: FervidCompileOptions
+}
+
 export interface FervidCompileOptions {
   /** Scope ID for prefixing injected CSS variables */
   id: string
@@ -90,6 +227,11 @@ export interface FervidCompileOptions {
   propsDestructure?: boolean | 'error'
   /** Whether setup bindings need to be serialized */
   outputSetupBindings?: boolean
+  /**
+   * Inline the template into `<script setup>` instead of generating a `render` function.
+   * Default: `isProduction`
+   */
+  inlineTemplate?: boolean
 }
 
 /** Raw options passed from the Node.js side */
@@ -101,6 +243,16 @@ export interface FervidJsCompilerOptions {
    * Enable SSR. Default: false
    */
   ssr?: boolean
+  /**
+   * Together with `ssr`, drop the client-only lifecycle hooks (e.g. `onMounted`)
+   * of `<script setup>` and the `v-on` listeners of the plain elements. Default: false
+   */
+  stripClientOnly?: boolean
+  /**
+   * Vue version the component is compiled for, `vue3` or `vue2`.
+   * Vue 2.7 components get a `render` function using `_c` and `_vm`. Default: `vue3`
+   */
+  target?: 'vue3' | 'vue2'
   /**
    * TODO Find a performant solution to source-maps
    * TODO Implement source-maps
@@ -113,6 +265,8 @@ export interface FervidJsCompilerOptions {
   template?: FervidJsCompilerOptionsTemplate
   /** Style compilation options */
   style?: FervidJsCompilerOptionsStyle
+  /** Custom blocks compilation options */
+  customBlocks?: FervidJsCompilerOptionsCustomBlocks
   /**
    * TODO Regex handling logic is needed (plus sanitation)
    * TODO Implement custom element mode (low priority)
@@ -125,8 +279,29 @@ export interface FervidJsCompilerOptions {
   diagnostics?: FervidJsCompilerOptionsDiagnostics
 }
 
+export interface FervidJsCompilerOptionsCustomBlocks {
+  /**
+   * Compile `<i18n>` blocks (JSON or YAML) and attach them to the component for `vue-i18n`.
+   * Default: false
+   */
+  i18n?: boolean
+}
+
 export interface FervidJsCompilerOptionsDiagnostics {
   errorLinesColumns?: boolean
+  /** Render a code frame with the error highlighted, see `SerializedError.codeFrame` */
+  codeFrames?: boolean
+  /**
+   * How the parser proceeds after encountering an error:
+   * - `hard-fail-on-error`: any error fails the compilation, only the first error is reported;
+   * - `soft-report-all`: any error fails the compilation (empty `code`), all the errors are reported;
+   * - `soft-recover-safe`: broken parts are reported and skipped;
+   * - `soft-recover-unsafe`: broken parts are reported and recovered as much as possible,
+   *   e.g. for editors and dev servers where the code is being typed.
+   *
+   * Default: `soft-recover-safe`
+   */
+  errorRecoveryMode?: 'hard-fail-on-error' | 'soft-report-all' | 'soft-recover-safe' | 'soft-recover-unsafe'
 }
 
 export interface FervidJsCompilerOptionsScript {
@@ -139,6 +314,12 @@ export interface FervidJsCompilerOptionsScript {
   hoistStatic?: boolean
   /** Produce source maps */
   sourceMap?: boolean
+  /**
+   * Read the relative imports of the Options API `mixins` and `extends` from the file system,
+   * so that their bindings are resolved in the template.
+   * Imports are resolved against `filename`, which must be a path on disk. Default: false
+   */
+  resolveMixins?: boolean
 }
 
 export interface FervidJsCompilerOptionsStyle {
@@ -155,21 +336,83 @@ export interface FervidTransformAssetUrlsOptions {
   base?: string
   includeAbsolute?: boolean
   tags?: Record<string, Array<string>>
+  aliases?: Record<string, string>
+}
+
+export interface ParseResult {
+  /**
+   * Serialized `SfcDescriptor`.
+   * Template nodes and script modules are embedded as swc AST nodes
+   */
+  descriptor: Record<string, any>
+  /** Only present when the SFC was transformed */
+  setupBindings?: Record<string, BindingTypes> | undefined
+  errors: Array<SerializedError>
 }
 
 export interface SerializedError {
   lo: number
   hi: number
   message: string
+  /** Stable code of the error, e.g. `duplicate-define-props` */
+  code: string
+  /** `error` or `warning`. Warnings, e.g. invalid HTML nesting, do not affect the generated code */
+  severity: 'error' | 'warning'
+  /**
+   * Source lines around the error with the error underlined.
+   * Only present when diagnostics.code_frames is enabled
+   */
+  codeFrame?: string
   startLineNumber: number
   endLineNumber: number
   startColumn: number
   endColumn: number
 }
 
+/** A root block of the SFC, e.g. `<template>` or `<style scoped>` */
+export interface SfcBlock {
+  /** `template`, `script`, `style` or the tag name of a custom block */
+  type: string
+  /** Raw content between the starting and the ending tags */
+  content: string
+  lang?: string
+  /** Only set for `<script>` */
+  setup?: boolean
+  /** Only set for `<style>` */
+  scoped?: boolean
+  /** Only set for `<style>` */
+  module?: boolean
+  /** Span of the whole block, including the tags */
+  lo: number
+  hi: number
+}
+
+export interface SfcParseResult {
+  descriptor: SfcDescriptor
+  errors: Array<SerializedError>
+}
+
 export interface Style {
   code: string
   isCompiled: boolean
   lang: string
   isScoped: boolean
+  sourceMap?: string
+}
+
+/** Virtual TypeScript file generated for type-checking an SFC */
+export interface VirtualTs {
+  code: string
+  mappings: Array<VirtualTsMapping>
+  errors: Array<SerializedError>
+}
+
+/**
+ * A range of `code` which is a copy of the same-length range in the SFC source.
+ * Offsets are 0-based and in bytes.
+ */
+export interface VirtualTsMapping {
+  generatedOffset: number
+  sourceOffset: number
+  length: number
 }
@@ -510,4 +510,6 @@ if (!nativeBinding) {
 module.exports = nativeBinding
 module.exports.Compiler = nativeBinding.Compiler
 module.exports.FervidJsCompiler = nativeBinding.FervidJsCompiler
+module.exports.SfcDescriptor = nativeBinding.SfcDescriptor
+module.exports.SfcDescriptorHandle = nativeBinding.SfcDescriptorHandle
 module.exports.BindingTypes = nativeBinding.BindingTypes
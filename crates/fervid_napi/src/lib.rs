@@ -4,17 +4,23 @@
 // #[global_allocator]
 // static ALLOC: mimalloc_rust::GlobalMiMalloc = mimalloc_rust::GlobalMiMalloc;
 
-use std::{borrow::Cow, marker::PhantomData, rc::Rc, sync::Arc};
+use std::{borrow::Cow, collections::HashMap, marker::PhantomData, sync::Arc};
 
-use fervid_transform::{PropsDestructureConfig, TransformAssetUrlsConfig};
+use fervid_transform::{CustomBlockHandlers, PropsDestructureConfig, TransformAssetUrlsConfig};
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
 
-use fervid::{compile, errors::CompileError, CompileOptions};
+use fervid::{
+    block_content_span, compile, compile_many,
+    errors::{lookup_location, render_code_frame, CompileError},
+    CompileOptions, FsModuleLoader, ModuleLoader, SetupBinding, SfcScriptBlock, SfcScriptLang,
+};
 use structs::{
-    BindingTypes, CompileResult, FervidCompileOptions, FervidJsCompiler, FervidJsCompilerOptions,
+    BindingTypes, CompileResult, CompileScriptResult, CompileStyleResult, CompileTemplateResult,
+    ComponentMeta, FervidCompileManyItem, FervidCompileOptions, FervidJsCompiler,
+    FervidJsCompilerOptions, ParseResult, SfcBlock, SfcDescriptorHandle, SfcParseResult, VirtualTs,
 };
-use swc_core::common::{sync::Lrc, BytePos, SourceMap};
+use swc_core::common::{Span, Spanned};
 
 use crate::structs::SerializedError;
 
@@ -25,8 +31,16 @@ impl FervidJsCompiler {
     #[napi(constructor)]
     pub fn new(options: Option<FervidJsCompilerOptions>) -> Self {
         let options = options.unwrap_or_default();
+        let module_loader = options
+            .script
+            .as_ref()
+            .and_then(|v| v.resolve_mixins)
+            .filter(|&resolve_mixins| resolve_mixins)
+            .map(|_| Arc::new(FsModuleLoader) as Arc<dyn ModuleLoader>);
+
         FervidJsCompiler {
             options: Arc::new(options),
+            module_loader,
         }
     }
 
@@ -56,6 +70,309 @@ impl FervidJsCompiler {
         };
         AsyncTask::with_optional_signal(task, signal)
     }
+
+    /// Compiles multiple files in parallel.
+    /// Results are in the same order as `inputs`.
+    #[napi]
+    pub fn compile_many_async(
+        &self,
+        inputs: Vec<FervidCompileManyItem>,
+        signal: Option<AbortSignal>,
+    ) -> AsyncTask<CompileManyTask> {
+        let task = CompileManyTask {
+            compiler: self.to_owned(),
+            inputs,
+            env: PhantomData,
+        };
+        AsyncTask::with_optional_signal(task, signal)
+    }
+
+    /// Parses an SFC and returns its descriptor, e.g. for AST explorers.
+    /// When `options` are provided, the template and styles are transformed like in `compileSync`.
+    #[napi]
+    pub fn parse<'env>(
+        &self,
+        env: Env,
+        source: String,
+        options: Option<FervidCompileOptions>,
+    ) -> Result<ParseResult<'env>> {
+        let compile_options = options
+            .as_ref()
+            .map(|options| create_compile_options(self, options))
+            .transpose()?;
+        let result = fervid::parse(&source, compile_options.as_ref())
+            .map_err(|e| Error::from_reason(e.to_string()))?;
+
+        let descriptor = serde_json::to_value(&result.descriptor)
+            .map_err(|e| Error::from_reason(e.to_string()))?;
+
+        let setup_bindings = if options.is_some() {
+            let mut obj = Object::new(&env)?;
+            for binding in result.setup_bindings {
+                obj.set(
+                    binding.sym.as_str(),
+                    BindingTypes::from(binding.binding_type),
+                )?;
+            }
+            Some(obj)
+        } else {
+            None
+        };
+
+        Ok(ParseResult {
+            descriptor,
+            setup_bindings,
+            errors: convert_errors(result.errors, &self.options, &source),
+        })
+    }
+
+    /// Extracts props, events, slots, models and exposed members of a component
+    /// without compiling it.
+    #[napi]
+    pub fn extract_component_meta(
+        &self,
+        source: String,
+        filename: String,
+    ) -> Result<ComponentMeta> {
+        let result = fervid::extract_component_meta(&source, &filename)
+            .map_err(|e| Error::from_reason(e.to_string()))?;
+        let meta = result.meta;
+
+        Ok(ComponentMeta {
+            name: meta.name,
+            props: meta.props.into_iter().map(From::from).collect(),
+            events: meta.events.into_iter().map(From::from).collect(),
+            slots: meta.slots.into_iter().map(From::from).collect(),
+            models: meta.models.into_iter().map(From::from).collect(),
+            exposed: meta.exposed.into_iter().map(From::from).collect(),
+            errors: convert_errors(result.errors, &self.options, &source),
+        })
+    }
+
+    /// Generates a virtual TypeScript file for type-checking the SFC template and scripts.
+    /// `mappings` point from the generated code back to the `source`.
+    #[napi]
+    pub fn generate_virtual_ts(&self, source: String, filename: String) -> Result<VirtualTs> {
+        let result = fervid::generate_virtual_ts(&source, &filename)
+            .map_err(|e| Error::from_reason(e.to_string()))?;
+
+        Ok(VirtualTs {
+            code: result.virtual_ts.code,
+            mappings: result
+                .virtual_ts
+                .mappings
+                .into_iter()
+                .map(From::from)
+                .collect(),
+            errors: convert_errors(result.errors, &self.options, &source),
+        })
+    }
+
+    /// Parses an SFC into a descriptor which can be compiled block by block
+    /// using `compileScript`, `compileTemplate` and `compileStyle`.
+    #[napi]
+    pub fn parse_descriptor(&self, source: String) -> Result<SfcParseResult> {
+        let result = fervid::parse(&source, None).map_err(|e| Error::from_reason(e.to_string()))?;
+
+        Ok(SfcParseResult {
+            errors: convert_errors(result.errors, &self.options, &source),
+            descriptor: SfcDescriptorHandle {
+                descriptor: Arc::new(result.descriptor),
+                source: Arc::new(source),
+                comments: result.comments,
+            },
+        })
+    }
+
+    /// Compiles `<script>` and `<script setup>` into the component module.
+    /// Unless the template is inlined, returned `bindings` need to be passed to `compileTemplate`.
+    #[napi]
+    pub fn compile_script<'env>(
+        &self,
+        env: Env,
+        descriptor: &SfcDescriptorHandle,
+        options: FervidCompileOptions,
+    ) -> Result<CompileScriptResult<'env>> {
+        let compile_options = create_compile_options(self, &options)?;
+        let result = fervid::compile_script(
+            &descriptor.source,
+            &descriptor.descriptor,
+            Some(&descriptor.comments),
+            &compile_options,
+        );
+
+        let mut bindings = Object::new(&env)?;
+        for binding in result.bindings {
+            bindings.set(
+                binding.sym.as_str(),
+                BindingTypes::from(binding.binding_type),
+            )?;
+        }
+
+        Ok(CompileScriptResult {
+            code: result.code,
+            source_map: result.source_map,
+            bindings,
+            errors: convert_errors(result.errors, &self.options, &descriptor.source),
+        })
+    }
+
+    /// Compiles `<template>` into a module exporting the `render` function.
+    #[napi]
+    pub fn compile_template(
+        &self,
+        descriptor: &SfcDescriptorHandle,
+        options: FervidCompileOptions,
+        bindings: Option<HashMap<String, BindingTypes>>,
+    ) -> Result<CompileTemplateResult> {
+        let compile_options = create_compile_options(self, &options)?;
+        let bindings = bindings
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(sym, binding_type)| SetupBinding::new(sym.into(), binding_type.into()))
+            .collect();
+
+        let result = fervid::compile_template(
+            &descriptor.source,
+            &descriptor.descriptor,
+            Some(&descriptor.comments),
+            &compile_options,
+            bindings,
+        );
+
+        Ok(CompileTemplateResult {
+            code: result.code,
+            source_map: result.source_map,
+            errors: convert_errors(result.errors, &self.options, &descriptor.source),
+        })
+    }
+
+    /// Compiles the `<style>` block at `index`.
+    /// Only scoped CSS is transformed, other styles are returned as-is.
+    #[napi]
+    pub fn compile_style(
+        &self,
+        descriptor: &SfcDescriptorHandle,
+        index: u32,
+        options: FervidCompileOptions,
+    ) -> Result<CompileStyleResult> {
+        let Some(style_block) = descriptor.descriptor.styles.get(index as usize) else {
+            return Err(Error::from_reason(format!(
+                "Style block at index {index} does not exist"
+            )));
+        };
+
+        let compile_options = create_compile_options(self, &options)?;
+        let result = fervid::compile_style(&descriptor.source, style_block, &compile_options);
+
+        Ok(CompileStyleResult {
+            style: result.style.into(),
+            errors: convert_errors(result.errors, &self.options, &descriptor.source),
+        })
+    }
+}
+
+#[napi]
+impl SfcDescriptorHandle {
+    #[napi(getter)]
+    pub fn source(&self) -> String {
+        self.source.as_ref().to_owned()
+    }
+
+    #[napi(getter)]
+    pub fn template(&self) -> Option<SfcBlock> {
+        let template = self.descriptor.template.as_ref()?;
+        Some(SfcBlock {
+            block_type: "template".to_owned(),
+            content: self.block_content(template.span),
+            lang: Some(template.lang.to_string()),
+            setup: None,
+            scoped: None,
+            module: None,
+            lo: template.span.lo.0,
+            hi: template.span.hi.0,
+        })
+    }
+
+    #[napi(getter)]
+    pub fn script(&self) -> Option<SfcBlock> {
+        self.descriptor
+            .script_legacy
+            .as_ref()
+            .map(|script| self.script_block(script))
+    }
+
+    #[napi(getter)]
+    pub fn script_setup(&self) -> Option<SfcBlock> {
+        self.descriptor
+            .script_setup
+            .as_ref()
+            .map(|script| self.script_block(script))
+    }
+
+    #[napi(getter)]
+    pub fn styles(&self) -> Vec<SfcBlock> {
+        self.descriptor
+            .styles
+            .iter()
+            .map(|style| SfcBlock {
+                block_type: "style".to_owned(),
+                content: style.content.to_string(),
+                lang: Some(style.lang.to_string()),
+                setup: None,
+                scoped: Some(style.is_scoped),
+                module: Some(style.is_module),
+                lo: style.span.lo.0,
+                hi: style.span.hi.0,
+            })
+            .collect()
+    }
+
+    #[napi(getter)]
+    pub fn custom_blocks(&self) -> Vec<SfcBlock> {
+        self.descriptor
+            .custom_blocks
+            .iter()
+            .map(|block| SfcBlock {
+                block_type: block.starting_tag.tag_name.to_string(),
+                content: block.content.to_string(),
+                lang: None,
+                setup: None,
+                scoped: None,
+                module: None,
+                lo: block.span.lo.0,
+                hi: block.span.hi.0,
+            })
+            .collect()
+    }
+
+    fn script_block(&self, script: &SfcScriptBlock) -> SfcBlock {
+        let lang = match script.lang {
+            SfcScriptLang::Es => "js",
+            SfcScriptLang::Typescript => "ts",
+        };
+
+        SfcBlock {
+            block_type: "script".to_owned(),
+            content: self.block_content(script.span),
+            lang: Some(lang.to_owned()),
+            setup: Some(script.is_setup),
+            scoped: None,
+            module: None,
+            lo: script.span.lo.0,
+            hi: script.span.hi.0,
+        }
+    }
+
+    fn block_content(&self, span: Span) -> String {
+        block_content_span(&self.source, span)
+            .and_then(|span| {
+                self.source
+                    .get((span.lo.0 - 1) as usize..(span.hi.0 - 1) as usize)
+            })
+            .unwrap_or_default()
+            .to_owned()
+    }
 }
 
 fn compile_impl(
@@ -63,6 +380,15 @@ fn compile_impl(
     source: &str,
     options: &FervidCompileOptions,
 ) -> Result<fervid::CompileResult> {
+    let compile_options = create_compile_options(compiler, options)?;
+    compile(source, compile_options).map_err(|e| Error::from_reason(e.to_string()))
+}
+
+/// Normalizes options to the ones defined in fervid
+fn create_compile_options<'o>(
+    compiler: &'o FervidJsCompiler,
+    options: &'o FervidCompileOptions,
+) -> Result<CompileOptions<'o>> {
     let props_destructure = match options.props_destructure {
         Some(Either::A(true)) => Some(PropsDestructureConfig::True),
         Some(Either::A(false)) => Some(PropsDestructureConfig::False),
@@ -79,28 +405,58 @@ fn compile_impl(
                 Some(Either::A(true)) => Some(TransformAssetUrlsConfig::EnabledDefault),
                 Some(Either::A(false)) => Some(TransformAssetUrlsConfig::Disabled),
                 Some(Either::B(options)) => Some(TransformAssetUrlsConfig::EnabledOptions(
-                    Rc::new(options.to_owned().into()),
+                    Arc::new(options.to_owned().into()),
                 )),
                 None => None,
             });
 
-    // Normalize options to the ones defined in fervid
-    let compile_options = CompileOptions {
+    Ok(CompileOptions {
         filename: Cow::Borrowed(&options.filename),
         id: Cow::Borrowed(&options.id),
         is_prod: compiler.options.is_production,
         is_custom_element: options.is_custom_element,
         props_destructure,
         ssr: compiler.options.ssr,
+        strip_client_only: compiler.options.strip_client_only,
+        target: parse_option("target", compiler.options.target.as_deref())?,
         gen_default_as: options
             .gen_default_as
             .as_ref()
             .map(|v| Cow::Borrowed(v.as_str())),
         source_map: compiler.options.source_map,
         transform_asset_urls,
-    };
+        inline_template: options.inline_template,
+        template_plugins: None,
+        custom_blocks: compiler
+            .options
+            .custom_blocks
+            .as_ref()
+            .and_then(|v| v.i18n)
+            .and_then(|i18n| i18n.then(CustomBlockHandlers::with_i18n)),
+        module_loader: compiler.module_loader.clone(),
+        error_recovery_mode: parse_option(
+            "diagnostics.errorRecoveryMode",
+            compiler
+                .options
+                .diagnostics
+                .as_ref()
+                .and_then(|v| v.error_recovery_mode.as_deref()),
+        )?,
+    })
+}
 
-    compile(source, compile_options).map_err(|e| Error::from_reason(e.to_string()))
+/// Parses a string option, an unknown value is an error instead of the silent default
+fn parse_option<T: std::str::FromStr>(name: &str, value: Option<&str>) -> Result<Option<T>> {
+    value
+        .map(|value| {
+            value.parse().map_err(|_| {
+                Error::new(
+                    Status::InvalidArg,
+                    format!("Unknown value of `{name}`: \"{value}\""),
+                )
+            })
+        })
+        .transpose()
 }
 
 fn convert<'env>(
@@ -150,30 +506,33 @@ fn convert_errors(
     compiler_options: &FervidJsCompilerOptions,
     source: &str,
 ) -> Vec<SerializedError> {
-    let mut errors: Vec<SerializedError> = compile_errors.into_iter().map(Into::into).collect();
-
     let Some(ref diagnostics) = compiler_options.diagnostics else {
-        return errors;
+        return compile_errors.into_iter().map(Into::into).collect();
     };
 
-    if let Some(true) = diagnostics.error_lines_columns {
-        let cm: Lrc<SourceMap> = Default::default();
-        cm.new_source_file(
-            Lrc::new(swc_core::common::FileName::Anon),
-            source.to_owned(),
-        );
+    let lines_columns = matches!(diagnostics.error_lines_columns, Some(true));
+    let code_frames = matches!(diagnostics.code_frames, Some(true));
 
-        for error in errors.iter_mut() {
-            let start = cm.lookup_char_pos(BytePos(error.lo));
-            let end = cm.lookup_char_pos(BytePos(error.hi));
-            error.start_line_number = start.line as u32;
-            error.end_line_number = end.line as u32;
-            error.start_column = start.col.0 as u32;
-            error.end_column = end.col.0 as u32;
-        }
-    }
+    compile_errors
+        .into_iter()
+        .map(|compile_error| {
+            let span = compile_error.span();
+            let code_frame = code_frames.then(|| render_code_frame(source, span));
+            let mut error: SerializedError = compile_error.into();
+            error.code_frame = code_frame;
+
+            if lines_columns {
+                let start = lookup_location(source, span.lo);
+                let end = lookup_location(source, span.hi);
+                error.start_line_number = start.line as u32;
+                error.end_line_number = end.line as u32;
+                error.start_column = start.column as u32;
+                error.end_column = end.column as u32;
+            }
 
-    errors
+            error
+        })
+        .collect()
 }
 
 pub struct CompileTask<'env> {
@@ -202,3 +561,56 @@ impl<'env> Task for CompileTask<'env> {
         ))
     }
 }
+
+pub struct CompileManyTask<'env> {
+    compiler: FervidJsCompiler,
+    inputs: Vec<FervidCompileManyItem>,
+    env: PhantomData<&'env ()>,
+}
+
+#[napi]
+impl<'env> Task for CompileManyTask<'env> {
+    type JsValue = Vec<CompileResult<'env>>;
+    type Output = Vec<std::result::Result<fervid::CompileResult, CompileError>>;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|input| {
+                create_compile_options(&self.compiler, &input.options)
+                    .map(|options| (input.source.as_str(), options))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(compile_many(inputs))
+    }
+
+    fn resolve(&mut self, env: Env, results: Self::Output) -> napi::Result<Self::JsValue> {
+        let converted = results
+            .into_iter()
+            .zip(self.inputs.iter())
+            .map(|(result, input)| match result {
+                Ok(result) => convert(
+                    env,
+                    result,
+                    &input.options,
+                    &self.compiler.options,
+                    &input.source,
+                ),
+
+                // An unrecoverable error is reported as the only diagnostic of the file
+                Err(error) => CompileResult {
+                    code: String::new(),
+                    styles: vec![],
+                    errors: convert_errors(vec![error], &self.compiler.options, &input.source),
+                    custom_blocks: vec![],
+                    source_map: None,
+                    setup_bindings: None,
+                },
+            })
+            .collect();
+
+        Ok(converted)
+    }
+}
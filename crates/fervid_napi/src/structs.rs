@@ -1,17 +1,19 @@
 use std::{collections::HashMap, sync::Arc};
 
-use fervid::FervidAtom;
+use fervid::{FervidAtom, ModuleLoader};
 use fervid_transform::TransformAssetUrlsConfigOptions;
 use fxhash::FxHashMap;
 use napi::{bindgen_prelude::Object, Either};
 use napi_derive::napi;
-use swc_core::common::Spanned;
+use swc_core::common::{comments::SingleThreadedComments, Spanned};
 
 /// Fervid: a compiler for Vue.js written in Rust
 #[napi(js_name = "Compiler")]
 #[derive(Clone)]
 pub struct FervidJsCompiler {
     pub options: Arc<FervidJsCompilerOptions>,
+    /// Shared by all the compilations, so that `compileManyAsync` loads a module only once
+    pub(crate) module_loader: Option<Arc<dyn ModuleLoader>>,
 }
 
 /// Raw options passed from the Node.js side
@@ -25,6 +27,14 @@ pub struct FervidJsCompilerOptions {
     /// Enable SSR. Default: false
     pub ssr: Option<bool>,
 
+    /// Together with `ssr`, drop the client-only lifecycle hooks (e.g. `onMounted`)
+    /// of `<script setup>` and the `v-on` listeners of the plain elements. Default: false
+    pub strip_client_only: Option<bool>,
+
+    /// Vue version the component is compiled for, `vue3` or `vue2`.
+    /// Vue 2.7 components get a `render` function using `_c` and `_vm`. Default: `vue3`
+    pub target: Option<String>,
+
     /// TODO Find a performant solution to source-maps
     /// TODO Implement source-maps
     /// Enable source maps
@@ -39,6 +49,9 @@ pub struct FervidJsCompilerOptions {
     /// Style compilation options
     pub style: Option<FervidJsCompilerOptionsStyle>,
 
+    /// Custom blocks compilation options
+    pub custom_blocks: Option<FervidJsCompilerOptionsCustomBlocks>,
+
     /// TODO Regex handling logic is needed (plus sanitation)
     /// TODO Implement custom element mode (low priority)
     /// Transform Vue SFCs into custom elements.
@@ -49,7 +62,7 @@ pub struct FervidJsCompilerOptions {
     // Ignored
     // pub compiler: Option<()>,
 
-    // Ignored, will be determined automatically based on `is_production` and `script` tags
+    // Set per file in `FervidCompileOptions`, defaults to `is_production`
     // pub inline_template: Option<bool>,
     pub diagnostics: Option<FervidJsCompilerOptionsDiagnostics>,
 }
@@ -72,6 +85,10 @@ pub struct FervidJsCompilerOptionsScript {
     pub hoist_static: Option<bool>,
     /// Produce source maps
     pub source_map: Option<bool>,
+    /// Read the relative imports of the Options API `mixins` and `extends` from the file system,
+    /// so that their bindings are resolved in the template.
+    /// Imports are resolved against `filename`, which must be a path on disk. Default: false
+    pub resolve_mixins: Option<bool>,
 }
 
 #[napi(object)]
@@ -81,10 +98,29 @@ pub struct FervidJsCompilerOptionsStyle {
     pub trim: Option<bool>,
 }
 
+#[napi(object)]
+#[derive(Clone)]
+pub struct FervidJsCompilerOptionsCustomBlocks {
+    /// Compile `<i18n>` blocks (JSON or YAML) and attach them to the component for `vue-i18n`.
+    /// Default: false
+    pub i18n: Option<bool>,
+}
+
 #[napi(object)]
 #[derive(Clone)]
 pub struct FervidJsCompilerOptionsDiagnostics {
     pub error_lines_columns: Option<bool>,
+    /// Render a code frame with the error highlighted, see `SerializedError.codeFrame`
+    pub code_frames: Option<bool>,
+    /// How the parser proceeds after encountering an error:
+    /// - `hard-fail-on-error`: any error fails the compilation, only the first error is reported;
+    /// - `soft-report-all`: any error fails the compilation (empty `code`), all the errors are reported;
+    /// - `soft-recover-safe`: broken parts are reported and skipped;
+    /// - `soft-recover-unsafe`: broken parts are reported and recovered as much as possible,
+    ///   e.g. for editors and dev servers where the code is being typed.
+    ///
+    /// Default: `soft-recover-safe`
+    pub error_recovery_mode: Option<String>,
 }
 
 #[napi(object)]
@@ -110,6 +146,18 @@ pub struct FervidCompileOptions {
 
     /// Whether setup bindings need to be serialized
     pub output_setup_bindings: Option<bool>,
+
+    /// Inline the template into `<script setup>` instead of generating a `render` function.
+    /// Default: `isProduction`
+    pub inline_template: Option<bool>,
+}
+
+/// A single file for `compileManyAsync`
+#[napi(object)]
+#[derive(Clone, Debug)]
+pub struct FervidCompileManyItem {
+    pub source: String,
+    pub options: FervidCompileOptions,
 }
 
 #[napi(object)]
@@ -118,6 +166,7 @@ pub struct FervidTransformAssetUrlsOptions {
     pub base: Option<String>,
     pub include_absolute: Option<bool>,
     pub tags: Option<HashMap<String, Vec<String>>>,
+    pub aliases: Option<HashMap<String, String>>,
 }
 
 #[napi(object)]
@@ -137,6 +186,7 @@ pub struct Style {
     pub is_compiled: bool,
     pub lang: String,
     pub is_scoped: bool,
+    pub source_map: Option<String>,
 }
 
 #[napi(object)]
@@ -152,6 +202,14 @@ pub struct SerializedError {
     pub lo: u32,
     pub hi: u32,
     pub message: String,
+    /// Stable code of the error, e.g. `duplicate-define-props`
+    pub code: String,
+    /// `error` or `warning`. Warnings, e.g. invalid HTML nesting, do not affect the generated code
+    #[napi(ts_type = "'error' | 'warning'")]
+    pub severity: String,
+    /// Source lines around the error with the error underlined.
+    /// Only present when diagnostics.code_frames is enabled
+    pub code_frame: Option<String>,
 
     // Would be set to 0 unless diagnostics.error_lines_columns is enabled
     pub start_line_number: u32,
@@ -160,6 +218,159 @@ pub struct SerializedError {
     pub end_column: u32,
 }
 
+#[napi(object)]
+pub struct ParseResult<'env> {
+    /// Serialized `SfcDescriptor`.
+    /// Template nodes and script modules are embedded as swc AST nodes
+    #[napi(ts_type = "Record<string, any>")]
+    pub descriptor: serde_json::Value,
+    /// Only present when the SFC was transformed
+    #[napi(ts_type = "Record<string, BindingTypes> | undefined")]
+    pub setup_bindings: Option<Object<'env>>,
+    pub errors: Vec<SerializedError>,
+}
+
+/// Public interface of a component, for documentation tools.
+/// Types and default values are kept as they are written in the source.
+#[napi(object)]
+pub struct ComponentMeta {
+    pub name: Option<String>,
+    pub props: Vec<ComponentProp>,
+    pub events: Vec<ComponentEvent>,
+    pub slots: Vec<ComponentSlot>,
+    pub models: Vec<ComponentModel>,
+    pub exposed: Vec<ComponentExposed>,
+    pub errors: Vec<SerializedError>,
+}
+
+#[napi(object)]
+pub struct ComponentProp {
+    pub name: String,
+    pub ts_type: Option<String>,
+    /// Runtime types, e.g. `["String", "Number"]`. Empty when any type is accepted
+    pub runtime_types: Vec<String>,
+    pub required: bool,
+    pub default: Option<String>,
+    /// Text of the JSDoc comment
+    pub description: Option<String>,
+    pub lo: u32,
+    pub hi: u32,
+}
+
+#[napi(object)]
+pub struct ComponentEvent {
+    pub name: String,
+    pub payload: Vec<String>,
+    pub description: Option<String>,
+    pub lo: u32,
+    pub hi: u32,
+}
+
+#[napi(object)]
+pub struct ComponentSlot {
+    pub name: String,
+    pub props_type: Option<String>,
+    pub description: Option<String>,
+    pub lo: u32,
+    pub hi: u32,
+}
+
+#[napi(object)]
+pub struct ComponentModel {
+    pub name: String,
+    pub ts_type: Option<String>,
+    pub runtime_types: Vec<String>,
+    pub required: bool,
+    pub default: Option<String>,
+    pub description: Option<String>,
+    pub lo: u32,
+    pub hi: u32,
+}
+
+#[napi(object)]
+pub struct ComponentExposed {
+    pub name: String,
+    pub description: Option<String>,
+    pub lo: u32,
+    pub hi: u32,
+}
+
+/// Virtual TypeScript file generated for type-checking an SFC
+#[napi(object)]
+pub struct VirtualTs {
+    pub code: String,
+    pub mappings: Vec<VirtualTsMapping>,
+    pub errors: Vec<SerializedError>,
+}
+
+/// A range of `code` which is a copy of the same-length range in the SFC source.
+/// Offsets are 0-based and in bytes.
+#[napi(object)]
+pub struct VirtualTsMapping {
+    pub generated_offset: u32,
+    pub source_offset: u32,
+    pub length: u32,
+}
+
+/// Parsed SFC kept on the Rust side, so that its blocks can be compiled separately without re-parsing.
+/// Obtained from `Compiler.parseDescriptor`.
+#[napi(js_name = "SfcDescriptor")]
+pub struct SfcDescriptorHandle {
+    pub(crate) descriptor: Arc<fervid::SfcDescriptor>,
+    pub(crate) source: Arc<String>,
+    /// Comments collected by the parser, they are attached to the spans of the descriptor
+    pub(crate) comments: SingleThreadedComments,
+}
+
+#[napi(object, object_from_js = false)]
+pub struct SfcParseResult {
+    pub descriptor: SfcDescriptorHandle,
+    pub errors: Vec<SerializedError>,
+}
+
+/// A root block of the SFC, e.g. `<template>` or `<style scoped>`
+#[napi(object)]
+pub struct SfcBlock {
+    /// `template`, `script`, `style` or the tag name of a custom block
+    #[napi(js_name = "type")]
+    pub block_type: String,
+    /// Raw content between the starting and the ending tags
+    pub content: String,
+    pub lang: Option<String>,
+    /// Only set for `<script>`
+    pub setup: Option<bool>,
+    /// Only set for `<style>`
+    pub scoped: Option<bool>,
+    /// Only set for `<style>`
+    pub module: Option<bool>,
+    /// Span of the whole block, including the tags
+    pub lo: u32,
+    pub hi: u32,
+}
+
+#[napi(object)]
+pub struct CompileScriptResult<'env> {
+    pub code: String,
+    pub source_map: Option<String>,
+    /// Bindings of the scripts, to be passed to `compileTemplate`
+    #[napi(ts_type = "Record<string, BindingTypes>")]
+    pub bindings: Object<'env>,
+    pub errors: Vec<SerializedError>,
+}
+
+#[napi(object)]
+pub struct CompileTemplateResult {
+    pub code: String,
+    pub source_map: Option<String>,
+    pub errors: Vec<SerializedError>,
+}
+
+#[napi(object)]
+pub struct CompileStyleResult {
+    pub style: Style,
+    pub errors: Vec<SerializedError>,
+}
+
 /// This is a copied enum from `fervid_core` with `napi` implementation to avoid littering the core crate.
 ///
 /// The type of a binding (or identifier) which is used to show where this binding came from,
@@ -240,6 +451,7 @@ impl From<fervid::CompileEmittedStyle> for Style {
             is_compiled: value.is_compiled,
             lang: value.lang,
             is_scoped: value.is_scoped,
+            source_map: value.source_map,
         }
     }
 }
@@ -251,6 +463,9 @@ impl From<fervid::errors::CompileError> for SerializedError {
             lo: span.lo.0,
             hi: span.hi.0,
             message: value.to_string(),
+            code: value.code().to_string(),
+            severity: <&str>::from(value.severity()).to_string(),
+            code_frame: None,
             end_column: 0,
             end_line_number: 0,
             start_column: 0,
@@ -270,10 +485,107 @@ impl From<fervid::CompileEmittedAsset> for CustomBlock {
     }
 }
 
+impl From<fervid::ComponentPropMeta> for ComponentProp {
+    fn from(value: fervid::ComponentPropMeta) -> Self {
+        ComponentProp {
+            name: value.name,
+            ts_type: value.ts_type,
+            runtime_types: value.runtime_types,
+            required: value.required,
+            default: value.default,
+            description: value.description,
+            lo: value.span.lo.0,
+            hi: value.span.hi.0,
+        }
+    }
+}
+
+impl From<fervid::ComponentEventMeta> for ComponentEvent {
+    fn from(value: fervid::ComponentEventMeta) -> Self {
+        ComponentEvent {
+            name: value.name,
+            payload: value.payload,
+            description: value.description,
+            lo: value.span.lo.0,
+            hi: value.span.hi.0,
+        }
+    }
+}
+
+impl From<fervid::ComponentSlotMeta> for ComponentSlot {
+    fn from(value: fervid::ComponentSlotMeta) -> Self {
+        ComponentSlot {
+            name: value.name,
+            props_type: value.props_type,
+            description: value.description,
+            lo: value.span.lo.0,
+            hi: value.span.hi.0,
+        }
+    }
+}
+
+impl From<fervid::ComponentModelMeta> for ComponentModel {
+    fn from(value: fervid::ComponentModelMeta) -> Self {
+        ComponentModel {
+            name: value.name,
+            ts_type: value.ts_type,
+            runtime_types: value.runtime_types,
+            required: value.required,
+            default: value.default,
+            description: value.description,
+            lo: value.span.lo.0,
+            hi: value.span.hi.0,
+        }
+    }
+}
+
+impl From<fervid::ComponentExposedMeta> for ComponentExposed {
+    fn from(value: fervid::ComponentExposedMeta) -> Self {
+        ComponentExposed {
+            name: value.name,
+            description: value.description,
+            lo: value.span.lo.0,
+            hi: value.span.hi.0,
+        }
+    }
+}
+
+impl From<fervid::VirtualTsMapping> for VirtualTsMapping {
+    fn from(value: fervid::VirtualTsMapping) -> Self {
+        Self {
+            generated_offset: value.generated_offset,
+            source_offset: value.source_offset,
+            length: value.length,
+        }
+    }
+}
+
 //
 // Input De-Serialization
 //
 
+impl From<BindingTypes> for fervid::BindingTypes {
+    fn from(value: BindingTypes) -> Self {
+        match value {
+            BindingTypes::DATA => fervid::BindingTypes::Data,
+            BindingTypes::PROPS => fervid::BindingTypes::Props,
+            BindingTypes::PROPS_ALIASED => fervid::BindingTypes::PropsAliased,
+            BindingTypes::SETUP_LET => fervid::BindingTypes::SetupLet,
+            BindingTypes::SETUP_CONST => fervid::BindingTypes::SetupConst,
+            BindingTypes::SETUP_REACTIVE_CONST => fervid::BindingTypes::SetupReactiveConst,
+            BindingTypes::SETUP_MAYBE_REF => fervid::BindingTypes::SetupMaybeRef,
+            BindingTypes::SETUP_REF => fervid::BindingTypes::SetupRef,
+            BindingTypes::OPTIONS => fervid::BindingTypes::Options,
+            BindingTypes::LITERAL_CONST => fervid::BindingTypes::LiteralConst,
+            BindingTypes::COMPONENT => fervid::BindingTypes::Component,
+            BindingTypes::IMPORTED => fervid::BindingTypes::Imported,
+            BindingTypes::TEMPLATE_LOCAL => fervid::BindingTypes::TemplateLocal,
+            BindingTypes::JS_GLOBAL => fervid::BindingTypes::JsGlobal,
+            BindingTypes::UNRESOLVED => fervid::BindingTypes::Unresolved,
+        }
+    }
+}
+
 impl From<FervidTransformAssetUrlsOptions> for TransformAssetUrlsConfigOptions {
     fn from(value: FervidTransformAssetUrlsOptions) -> TransformAssetUrlsConfigOptions {
         let tags = if let Some(napi_tags) = value.tags {
@@ -295,6 +607,10 @@ impl From<FervidTransformAssetUrlsOptions> for TransformAssetUrlsConfigOptions {
             base: value.base,
             include_absolute: value.include_absolute.unwrap_or_default(),
             tags,
+            aliases: value
+                .aliases
+                .map(|aliases| aliases.into_iter().collect())
+                .unwrap_or_default(),
         }
     }
 }
@@ -42,16 +42,7 @@ impl SfcParser<'_, '_, '_> {
             return true;
         }
 
-        for mut raw_attribute in raw_attributes.into_iter() {
-            // Use raw names for attributes, otherwise SWC transforms them to lowercase
-            // `-1` is needed because SWC spans start from 1
-            let raw_idx_start = raw_attribute.span.lo.0 as usize - 1;
-            let raw_idx_end = raw_idx_start
-                + raw_attribute.name.len()
-                + raw_attribute.prefix.as_ref().map_or(0, |v| v.len() + 1); // 1 for `:` in e.g. `xmlns:xlink`
-
-            raw_attribute.name = FervidAtom::from(&self.input[raw_idx_start..raw_idx_end]);
-
+        for raw_attribute in raw_attributes.into_iter() {
             match self.try_parse_directive(raw_attribute, attrs_or_bindings, vue_directives) {
                 Ok(()) => {
                     // do nothing, we are good already
@@ -108,10 +99,11 @@ impl SfcParser<'_, '_, '_> {
             };
         }
 
-        // TODO Should the span be narrower? (It can be narrowed with lo = lo + name.len() + 1 and hi = hi - 1)
         let span = raw_attribute.span;
+        // Span of the attribute value (without the name and quotes), used for the JS inside it
+        let value_span = self.attribute_value_span(&raw_attribute);
         let raw_name: &str = &raw_attribute.name;
-        let mut chars_iter = raw_name.chars().enumerate();
+        let mut chars_iter = raw_name.char_indices();
 
         enum ParsingMode {
             DirectivePrefix,
@@ -321,13 +313,17 @@ impl SfcParser<'_, '_, '_> {
             }
         };
 
-        /// Unwrapping the value or failing
+        /// Unwrapping the value or failing, an empty value is the same as no value
         macro_rules! expect_value {
             () => {
-                if let Some(ref value) = raw_attribute.value {
-                    value
-                } else {
-                    bail!(ParseErrorKind::DirectiveSyntax);
+                expect_value!(ParseErrorKind::DirectiveSyntax)
+            };
+            ($err_kind: expr) => {
+                match raw_attribute.value {
+                    Some(ref value) if !value.trim().is_empty() => value,
+                    _ => {
+                        bail!($err_kind);
+                    }
                 }
             };
         }
@@ -347,7 +343,7 @@ impl SfcParser<'_, '_, '_> {
 
         macro_rules! push_directive_js {
             ($key: ident, $value: expr) => {
-                match self.parse_expr($value, ts!(), span) {
+                match self.parse_expr($value, ts!(), value_span) {
                     Ok(parsed) => {
                         let directives = get_directives!();
                         directives.$key = Some(parsed);
@@ -389,7 +385,7 @@ impl SfcParser<'_, '_, '_> {
                     }
                 };
 
-                let parsed_expr = match self.parse_expr(&value, ts!(), span) {
+                let parsed_expr = match self.parse_expr(&value, ts!(), value_span) {
                     Ok(parsed) => parsed,
                     Err(expr_err) => {
                         bail!(js, expr_err);
@@ -408,7 +404,7 @@ impl SfcParser<'_, '_, '_> {
 
             "on" => {
                 let handler = match raw_attribute.value {
-                    Some(ref value) => match self.parse_expr(value, ts!(), span) {
+                    Some(ref value) => match self.parse_expr(value, ts!(), value_span) {
                         Ok(parsed) => Some(parsed),
                         Err(expr_err) => {
                             bail!(js, expr_err);
@@ -426,12 +422,12 @@ impl SfcParser<'_, '_, '_> {
             }
 
             "if" => {
-                let value = expect_value!();
+                let value = expect_value!(ParseErrorKind::VIfNoExpression);
                 push_directive_js!(v_if, &value);
             }
 
             "else-if" => {
-                let value = expect_value!();
+                let value = expect_value!(ParseErrorKind::VIfNoExpression);
                 push_directive_js!(v_else_if, &value);
             }
 
@@ -440,12 +436,12 @@ impl SfcParser<'_, '_, '_> {
             }
 
             "for" => {
-                let value = expect_value!();
+                let value = expect_value!(ParseErrorKind::VForNoExpression);
 
                 let Some(((itervar, itervar_span), (iterable, iterable_span))) =
-                    split_itervar_and_iterable(value, span)
+                    split_itervar_and_iterable(value, value_span)
                 else {
-                    bail!(ParseErrorKind::DirectiveSyntax);
+                    bail!(ParseErrorKind::VForMalformedExpression, value_span);
                 };
 
                 match self.parse_expr(itervar, ts!(), itervar_span) {
@@ -468,31 +464,35 @@ impl SfcParser<'_, '_, '_> {
             }
 
             "model" => {
-                let value = expect_value!();
+                let value = expect_value!(ParseErrorKind::VModelNoExpression);
 
-                if let Ok(model_binding) = self.parse_expr(value, ts!(), span) {
-                    // v-model value must be a valid JavaScript member expression
-                    if !matches!(*model_binding, Expr::Member(_) | Expr::Ident(_)) {
-                        // TODO Report an error
-                        bail!();
+                let model_binding = match self.parse_expr(value, ts!(), value_span) {
+                    Ok(parsed) => parsed,
+                    Err(expr_err) => {
+                        bail!(js, expr_err);
                     }
+                };
 
-                    let directives = get_directives!();
-                    directives.v_model.push(VModelDirective {
-                        argument,
-                        value: model_binding,
-                        update_handler: None,
-                        modifiers,
-                        span,
-                    });
+                // v-model value must be a valid JavaScript member expression
+                if !matches!(*model_binding, Expr::Member(_) | Expr::Ident(_)) {
+                    bail!(ParseErrorKind::VModelMalformedExpression, value_span);
                 }
+
+                let directives = get_directives!();
+                directives.v_model.push(VModelDirective {
+                    argument,
+                    value: model_binding,
+                    update_handler: None,
+                    modifiers,
+                    span,
+                });
             }
 
             "slot" => {
                 let value =
                     raw_attribute
                         .value
-                        .and_then(|v| match self.parse_pat(&v, ts!(), span) {
+                        .and_then(|v| match self.parse_pat(&v, ts!(), value_span) {
                             Ok(value) => Some(Box::new(value)),
                             Result::Err(_) => None,
                         });
@@ -506,7 +506,7 @@ impl SfcParser<'_, '_, '_> {
             }
 
             "show" => {
-                let value = expect_value!();
+                let value = expect_value!(ParseErrorKind::VShowNoExpression);
                 push_directive_js!(v_show, &value);
             }
 
@@ -552,7 +552,7 @@ impl SfcParser<'_, '_, '_> {
                 };
 
                 // If there is a value, try parsing it and only include the successfully parsed values
-                match self.parse_expr(&value, ts!(), span) {
+                match self.parse_expr(&value, ts!(), value_span) {
                     Ok(parsed) => {
                         let directives = get_directives!();
                         directives.custom.push(VCustomDirective {
@@ -569,6 +569,49 @@ impl SfcParser<'_, '_, '_> {
 
         Ok(())
     }
+
+    /// Narrows the attribute span down to its value, i.e. `bar` in `:foo="bar"`.
+    /// Falls back to the whole attribute span when the value cannot be located in the input.
+    fn attribute_value_span(&self, raw_attribute: &Attribute) -> Span {
+        let span = raw_attribute.span;
+
+        // Spans are 1-based
+        let (Some(lo), Some(hi)) = (
+            (span.lo.0 as usize).checked_sub(1),
+            (span.hi.0 as usize).checked_sub(1),
+        ) else {
+            return span;
+        };
+        let Some(raw) = self.input.get(lo..hi) else {
+            return span;
+        };
+
+        // Value starts after the `=` which follows the name
+        let Some(eq_idx) = raw
+            .get(raw_attribute.name.len()..)
+            .and_then(|after_name| after_name.find('='))
+            .map(|idx| idx + raw_attribute.name.len())
+        else {
+            return span;
+        };
+
+        let after_eq = &raw[eq_idx + 1..];
+        let value = after_eq.trim_start();
+        let mut value_lo = hi - value.len();
+        let mut value_hi = hi;
+
+        if let Some(quote @ ('"' | '\'')) = value.chars().next() {
+            value_lo += 1;
+            if value.len() > 1 && value.ends_with(quote) {
+                value_hi -= 1;
+            }
+        }
+
+        Span {
+            lo: BytePos(value_lo as u32 + 1),
+            hi: BytePos(value_hi as u32 + 1),
+        }
+    }
 }
 
 /// Creates `AttributeOrBinding::RegularAttribute`
@@ -653,6 +696,7 @@ fn to_camel_case(raw: &str, out: &mut String) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use swc_core::common::Spanned;
 
     #[test]
     fn it_parses_regular_attr() {
@@ -1193,6 +1237,103 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn it_narrows_directive_value_spans() {
+        let input = r#":foo="bar + 1" v-for='item in list'"#;
+        let mut errors = Vec::new();
+        let mut parser = SfcParser::new(input, &mut errors);
+
+        let mut attrs_or_bindings = Vec::new();
+        let mut vue_directives = None;
+        let make_attr = |name: &str, value: &str, lo: usize, hi: usize| Attribute {
+            span: Span {
+                lo: BytePos(lo as u32 + 1),
+                hi: BytePos(hi as u32 + 1),
+            },
+            namespace: None,
+            prefix: None,
+            name: FervidAtom::from(name),
+            raw_name: None,
+            value: Some(FervidAtom::from(value)),
+            raw_value: None,
+        };
+
+        let bind = make_attr(":foo", "bar + 1", 0, 14);
+        let v_for = make_attr("v-for", "item in list", 15, input.len());
+        assert!(parser
+            .try_parse_directive(bind, &mut attrs_or_bindings, &mut vue_directives)
+            .is_ok());
+        assert!(parser
+            .try_parse_directive(v_for, &mut attrs_or_bindings, &mut vue_directives)
+            .is_ok());
+
+        let source_of = |span: Span| &input[span.lo.0 as usize - 1..span.hi.0 as usize - 1];
+
+        let Some(AttributeOrBinding::VBind(VBindDirective { value, .. })) = attrs_or_bindings.pop()
+        else {
+            panic!("Expected v-bind")
+        };
+        assert_eq!("bar + 1", source_of(value.span()));
+
+        let v_for = vue_directives
+            .and_then(|d| d.v_for)
+            .expect("Expected v-for");
+        assert_eq!("item", source_of(v_for.itervar.span()));
+        assert_eq!("list", source_of(v_for.iterable.span()));
+    }
+
+    #[test]
+    fn it_reports_malformed_directive_expressions() {
+        fn test_parse_into_error(name: &str, value: Option<&str>) -> ParseErrorKind {
+            let mut errors = Vec::new();
+            let mut parser = SfcParser::new("", &mut errors);
+
+            let mut attrs_or_bindings = Vec::new();
+            let mut vue_directives = None;
+            let result = parser.try_parse_directive(
+                Attribute {
+                    span: Span {
+                        lo: BytePos(1),
+                        hi: BytePos((name.len() + value.map_or(0, |v| v.len() + 3)) as u32),
+                    },
+                    namespace: None,
+                    prefix: None,
+                    name: FervidAtom::from(name),
+                    raw_name: None,
+                    value: value.map(FervidAtom::from),
+                    raw_value: None,
+                },
+                &mut attrs_or_bindings,
+                &mut vue_directives,
+            );
+            assert!(result.is_err());
+            assert!(vue_directives.is_none());
+            assert_eq!(1, errors.len());
+
+            errors.pop().expect("Error should exist").kind
+        }
+
+        macro_rules! assert_error {
+            ($name: expr, $value: expr, $kind: pat) => {
+                let value: Option<&str> = $value;
+                let kind = test_parse_into_error($name, value);
+                assert!(matches!(kind, $kind), "{} {:?}: {:?}", $name, value, kind);
+            };
+        }
+
+        assert_error!("v-if", None, ParseErrorKind::VIfNoExpression);
+        assert_error!("v-if", Some("  "), ParseErrorKind::VIfNoExpression);
+        assert_error!("v-else-if", Some(""), ParseErrorKind::VIfNoExpression);
+        assert_error!("v-show", None, ParseErrorKind::VShowNoExpression);
+        assert_error!("v-for", Some(""), ParseErrorKind::VForNoExpression);
+        assert_error!("v-for", Some("item"), ParseErrorKind::VForMalformedExpression);
+        assert_error!("v-for", Some("in list"), ParseErrorKind::VForMalformedExpression);
+        assert_error!("v-model", None, ParseErrorKind::VModelNoExpression);
+        assert_error!("v-model", Some("foo()"), ParseErrorKind::VModelMalformedExpression);
+        assert_error!("v-model", Some("a + b"), ParseErrorKind::VModelMalformedExpression);
+        assert_error!("v-model", Some("foo."), ParseErrorKind::EcmaSyntaxError(_));
+    }
+
     fn test_parse_into_attr_or_binding(name: &str, value: &str) -> Option<AttributeOrBinding> {
         let mut errors = Vec::new();
         let mut parser = SfcParser::new("", &mut errors);
@@ -5,7 +5,7 @@ use crate::SfcParser;
 
 impl SfcParser<'_, '_, '_> {
     pub fn parse_sfc_custom_block_element(&mut self, element: Element) -> Option<SfcCustomBlock> {
-        let attributes = element
+        let attributes: Vec<AttributeOrBinding> = element
             .attributes
             .into_iter()
             .map(|attr| AttributeOrBinding::RegularAttribute {
@@ -15,8 +15,16 @@ impl SfcParser<'_, '_, '_> {
             })
             .collect();
 
-        let (raw_content, _) =
-            self.use_rawtext_content(element.content.as_ref(), &element.children)?;
+        // Same as in the official compiler, empty blocks are kept when they have a `src`
+        let has_src = attributes.iter().any(
+            |attr| matches!(attr, AttributeOrBinding::RegularAttribute { name, .. } if name == "src"),
+        );
+        let raw_content =
+            match self.use_rawtext_content(element.content.as_ref(), &element.children) {
+                Some((raw_content, _)) => raw_content,
+                None if has_src => "",
+                None => return None,
+            };
 
         Some(SfcCustomBlock {
             starting_tag: StartingTag {
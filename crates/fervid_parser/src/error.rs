@@ -1,4 +1,23 @@
-use swc_core::common::{Span, Spanned};
+use strum_macros::{EnumString, IntoStaticStr};
+use swc_core::common::{Span, Spanned, DUMMY_SP};
+
+/// How the parser proceeds after encountering an error.
+/// Can be parsed from a kebab-case name, e.g. `soft-recover-unsafe`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ErrorRecoveryMode {
+    /// Any error fails the parsing, only the first error is reported
+    HardFailOnError,
+    /// Any error fails the parsing, but it continues as far as possible to report all the errors
+    SoftReportAll,
+    /// Errors are reported and the broken parts are skipped,
+    /// e.g. an interpolation which failed to parse is not included in the template
+    #[default]
+    SoftRecoverSafe,
+    /// Errors are reported, but the broken parts are recovered as much as possible,
+    /// e.g. `{{ foo. }}` is treated as `{{ foo }}`. Useful for the editors and dev servers
+    SoftRecoverUnsafe,
+}
 
 #[derive(Debug)]
 pub struct ParseError {
@@ -6,7 +25,8 @@ pub struct ParseError {
     pub span: Span,
 }
 
-#[derive(Debug)]
+#[derive(Debug, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
 pub enum ParseErrorKind {
     /// Malformed directive (e.g. `:`, `@`)
     DirectiveSyntax,
@@ -26,18 +46,44 @@ pub enum ParseErrorKind {
     DuplicateScriptSetup,
     /// More than one `<template>`
     DuplicateTemplate,
-    /// More than one attribute with the same name on a root element
+    /// More than one attribute with the same name on an element
     DuplicateAttribute,
     /// Error while parsing EcmaScript/TypeScript
     EcmaSyntaxError(Box<swc_ecma_parser::error::SyntaxError>),
+    /// Template ended inside a comment (e.g. `<!-- foo`)
+    EofInComment,
+    /// Template ended inside a tag (e.g. `<div class="foo"`)
+    EofInTag,
+    /// End tag without a matching start tag in a template (e.g. `<div></span></div>`)
+    InvalidEndTag,
     /// Unrecoverable error while parsing HTML
     InvalidHtml(Box<swc_html_parser::error::ErrorKind>),
+    /// Element in a template was not closed (e.g. `<div><span></div>`)
+    MissingEndTag,
+    /// Interpolation was not closed (e.g. `{{ foo`)
+    MissingInterpolationEnd,
     /// Both `<template>` and `<script>` are missing
     MissingTemplateOrScript,
+    /// Malformed Pug template (e.g. unclosed attributes, inconsistent indentation)
+    PugSyntax,
+    /// Pug feature which needs a JavaScript runtime (e.g. code, mixins, conditionals)
+    PugUnsupportedFeature,
     /// `<script>`/`<style>` content was not Text
     UnexpectedNonRawTextContent,
     /// Language not supported
     UnsupportedLang,
+    /// `v-for` without a value (e.g. `v-for=""`)
+    VForNoExpression,
+    /// `v-for` value is not `item in items` or `item of items`
+    VForMalformedExpression,
+    /// `v-if` or `v-else-if` without a value
+    VIfNoExpression,
+    /// `v-model` value is not assignable (e.g. `v-model="foo()"`)
+    VModelMalformedExpression,
+    /// `v-model` without a value
+    VModelNoExpression,
+    /// `v-show` without a value
+    VShowNoExpression,
 }
 
 impl From<swc_ecma_parser::error::Error> for ParseError {
@@ -51,15 +97,81 @@ impl From<swc_ecma_parser::error::Error> for ParseError {
     }
 }
 
+impl ParseError {
+    /// Stable code of the error, e.g. `duplicate-template`
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+}
+
+impl ParseErrorKind {
+    /// Stable code of the error, e.g. `duplicate-template`
+    pub fn code(&self) -> &'static str {
+        self.into()
+    }
+}
+
 impl std::fmt::Display for ParseErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        let message = match self {
+            ParseErrorKind::DirectiveSyntax => "Malformed directive.",
+            ParseErrorKind::DirectiveSyntaxDirectiveName => "Legal directive name was expected.",
+            ParseErrorKind::DirectiveSyntaxArgument => "Directive argument was expected.",
+            ParseErrorKind::DirectiveSyntaxDynamicArgument => {
+                "End bracket for dynamic directive argument was not found. Note that dynamic directive argument cannot contain spaces."
+            }
+            ParseErrorKind::DirectiveSyntaxUnexpectedCharacterAfterDynamicArgument => {
+                "Unexpected character after dynamic directive argument, expected modifiers or `=`."
+            }
+            ParseErrorKind::DirectiveSyntaxModifier => "Directive modifier was expected.",
+            ParseErrorKind::DuplicateScriptOptions => {
+                "Single file component can contain only one <script> element"
+            }
+            ParseErrorKind::DuplicateScriptSetup => {
+                "Single file component can contain only one <script setup> element"
+            }
+            ParseErrorKind::DuplicateTemplate => {
+                "Single file component can contain only one <template> element"
+            }
+            ParseErrorKind::DuplicateAttribute => "Duplicate attribute.",
+            ParseErrorKind::EcmaSyntaxError(e) => return f.write_str(&e.msg()),
+            ParseErrorKind::EofInComment => "Unexpected EOF in comment.",
+            ParseErrorKind::EofInTag => "Unexpected EOF in tag.",
+            ParseErrorKind::InvalidEndTag => "Invalid end tag.",
+            ParseErrorKind::InvalidHtml(e) => {
+                let e = swc_html_parser::error::Error::new(DUMMY_SP, *e.clone());
+                return f.write_str(&e.message());
+            }
+            ParseErrorKind::MissingEndTag => "Element is missing end tag.",
+            ParseErrorKind::MissingInterpolationEnd => "Interpolation end sign was not found.",
+            ParseErrorKind::MissingTemplateOrScript => {
+                "At least one <template> or <script> is required in a single file component."
+            }
+            ParseErrorKind::PugSyntax => "Malformed Pug template.",
+            ParseErrorKind::PugUnsupportedFeature => {
+                "Pug feature is not supported, only static templates can be compiled."
+            }
+            ParseErrorKind::UnexpectedNonRawTextContent => {
+                "Expected the content of the block to be raw text."
+            }
+            ParseErrorKind::UnsupportedLang => "Unsupported lang.",
+            ParseErrorKind::VForNoExpression => "v-for is missing expression.",
+            ParseErrorKind::VForMalformedExpression => "v-for has invalid expression.",
+            ParseErrorKind::VIfNoExpression => "v-if/v-else-if is missing expression.",
+            ParseErrorKind::VModelMalformedExpression => {
+                "v-model value must be a valid JavaScript member expression."
+            }
+            ParseErrorKind::VModelNoExpression => "v-model is missing expression.",
+            ParseErrorKind::VShowNoExpression => "v-show is missing expression.",
+        };
+
+        f.write_str(message)
     }
 }
 
 impl std::fmt::Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        self.kind.fmt(f)
     }
 }
 
@@ -0,0 +1,862 @@
+//! Parser for the HTML flavour used in Vue templates.
+//!
+//! Unlike the spec HTML parser, it follows the looser rules of `@vue/compiler-core`:
+//! - tag and attribute names keep their case, so `<MyComponent>` is not lowercased;
+//! - any element can be self-closing, e.g. `<MyComponent />`;
+//! - there is no tree construction magic, i.e. no foster-parenting of table content
+//!   and no implicit closing of `<p>` by block elements;
+//! - only `<script>`/`<style>` (raw text) and `<textarea>`/`<title>` (escapable raw text)
+//!   have special content, and only outside of `<svg>` and `<math>`;
+//! - interpolations are opaque, so `{{ a < b }}` does not start a tag.
+
+use std::borrow::Cow;
+
+use fervid_core::{ElementNode, FervidAtom, Node, PatchHints};
+use swc_core::common::{BytePos, Span};
+use swc_html_ast::Attribute;
+use swc_html_parser::error::ErrorKind;
+use swc_html_utils::HTML_ENTITIES;
+
+use crate::{
+    error::{ErrorRecoveryMode, ParseError, ParseErrorKind},
+    SfcParser,
+};
+
+/// Elements which never have content
+const VOID_TAGS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+impl<'i> SfcParser<'i, '_, '_> {
+    /// Parses the `<template>` root block located at `template_span` and returns its children
+    pub fn parse_html_template(&mut self, template_span: Span) -> Vec<Node> {
+        let html_nodes = self.parse_html_nodes(template_span);
+
+        // The root block itself is the only element, everything else is ignored
+        let root_children = html_nodes
+            .into_iter()
+            .find_map(|html_node| match html_node {
+                HtmlNode::Element(root) => Some(root.children),
+                _ => None,
+            });
+
+        match root_children {
+            Some(children) => self.process_html_nodes(children),
+            None => vec![],
+        }
+    }
+
+    /// Parses the template markup located at `span` of the input, e.g. literal HTML in Pug
+    pub fn parse_template_fragment(&mut self, span: Span) -> Vec<Node> {
+        let html_nodes = self.parse_html_nodes(span);
+        self.process_html_nodes(html_nodes)
+    }
+
+    fn parse_html_nodes(&mut self, span: Span) -> Vec<HtmlNode<'i>> {
+        let input = self.input;
+        let (Some(lo), Some(hi)) = (
+            (span.lo.0 as usize).checked_sub(1),
+            (span.hi.0 as usize).checked_sub(1),
+        ) else {
+            return vec![];
+        };
+        let Some(src) = input.get(lo..hi) else {
+            return vec![];
+        };
+
+        let mut html_parser = HtmlParser::new(
+            src,
+            span.lo.0,
+            self.interpolation_start_pat,
+            self.interpolation_end_pat,
+        );
+        html_parser.keep_unclosed_tags = matches!(
+            self.error_recovery_mode,
+            ErrorRecoveryMode::SoftRecoverUnsafe
+        );
+        let html_nodes = html_parser.parse_children();
+        self.errors.append(&mut html_parser.errors);
+
+        html_nodes
+    }
+
+    fn process_html_nodes(&mut self, html_nodes: Vec<HtmlNode>) -> Vec<Node> {
+        let mut out = Vec::with_capacity(html_nodes.len());
+
+        for html_node in html_nodes {
+            match html_node {
+                HtmlNode::Element(html_element) => {
+                    out.push(self.process_html_element(html_element))
+                }
+                HtmlNode::Text { raw, span } => self.process_text(raw, span, &mut out),
+                HtmlNode::RawText { raw, span } => {
+                    out.push(Node::Text(FervidAtom::from(raw), span))
+                }
+                HtmlNode::Comment { value, span } => {
+                    out.push(Node::Comment(FervidAtom::from(value), span))
+                }
+            }
+        }
+
+        out
+    }
+
+    fn process_html_element(&mut self, html_element: HtmlElement) -> Node {
+        let HtmlElement {
+            tag_name,
+            attributes,
+            children,
+            span,
+        } = html_element;
+
+        // Save old `v-pre` (restored at the end of the function)
+        let old_is_pre = self.is_pre;
+
+        let starting_tag = self.create_starting_tag(FervidAtom::from(tag_name), attributes);
+
+        let result = Node::Element(ElementNode {
+            kind: fervid_core::ElementKind::Element,
+            starting_tag,
+            children: self.process_html_nodes(children),
+            template_scope: 0,
+            patch_hints: PatchHints::default(),
+            span,
+        });
+
+        self.is_pre = old_is_pre;
+        result
+    }
+}
+
+enum HtmlNode<'s> {
+    Element(HtmlElement<'s>),
+    /// Text which may contain interpolations and character references
+    Text {
+        raw: &'s str,
+        span: Span,
+    },
+    /// Content of `<script>` and `<style>`, taken as-is
+    RawText {
+        raw: &'s str,
+        span: Span,
+    },
+    Comment {
+        value: &'s str,
+        span: Span,
+    },
+}
+
+struct HtmlElement<'s> {
+    tag_name: &'s str,
+    attributes: Vec<Attribute>,
+    children: Vec<HtmlNode<'s>>,
+    span: Span,
+}
+
+/// Kind of the element content
+enum ContentKind {
+    Normal,
+    /// `<script>` and `<style>`
+    RawText,
+    /// `<textarea>` and `<title>`
+    EscapableRawText,
+}
+
+struct HtmlParser<'s, 'p> {
+    src: &'s str,
+    /// Position of `src` in the whole input
+    offset: u32,
+    /// Current byte index in `src`
+    pos: usize,
+    interpolation_start_pat: &'p str,
+    interpolation_end_pat: &'p str,
+    /// Tag names of the currently open elements, the innermost is the last
+    open_elements: Vec<&'s str>,
+    /// Inside `<svg>` or `<math>`
+    in_foreign: bool,
+    /// Inside an element with `v-pre`, interpolations are not recognized
+    in_v_pre: bool,
+    /// Keep the elements whose start tag was cut by the end of input
+    keep_unclosed_tags: bool,
+    errors: Vec<ParseError>,
+}
+
+impl<'s, 'p> HtmlParser<'s, 'p> {
+    fn new(
+        src: &'s str,
+        offset: u32,
+        interpolation_start_pat: &'p str,
+        interpolation_end_pat: &'p str,
+    ) -> Self {
+        Self {
+            src,
+            offset,
+            pos: 0,
+            interpolation_start_pat,
+            interpolation_end_pat,
+            open_elements: vec![],
+            in_foreign: false,
+            in_v_pre: false,
+            keep_unclosed_tags: false,
+            errors: vec![],
+        }
+    }
+
+    /// Parses the nodes until the end of input or an end tag of any open element
+    fn parse_children(&mut self) -> Vec<HtmlNode<'s>> {
+        let mut nodes = Vec::new();
+
+        while self.pos < self.src.len() {
+            let rest = &self.src[self.pos..];
+
+            if let Some(after_solidus) = rest.strip_prefix("</") {
+                let name_len = scan_tag_name(after_solidus);
+
+                // `</>` and `</ foo>` are ignored
+                if name_len == 0 {
+                    self.pos = self.find_tag_end(self.pos + 2);
+                    continue;
+                }
+
+                // End tags are matched case-insensitively like in `compiler-core`
+                let name = &after_solidus[..name_len];
+                if self
+                    .open_elements
+                    .iter()
+                    .any(|open_element| open_element.eq_ignore_ascii_case(name))
+                {
+                    break;
+                }
+
+                // Stray end tag, e.g. `</div>` without `<div>`. `</br>` is silently ignored
+                let tag_lo = self.pos;
+                self.pos = self.find_tag_end(self.pos + 2);
+                if !VOID_TAGS.contains(&name) {
+                    self.report(ParseErrorKind::InvalidEndTag, tag_lo, self.pos);
+                }
+            } else if let Some(after_comment_start) = rest.strip_prefix("<!--") {
+                let lo = self.pos;
+                let value_lo = lo + 4;
+                let (value, hi) = match after_comment_start.find("-->") {
+                    Some(idx) => (&after_comment_start[..idx], value_lo + idx + 3),
+                    None => {
+                        self.report(ParseErrorKind::EofInComment, lo, self.src.len());
+                        (after_comment_start, self.src.len())
+                    }
+                };
+
+                nodes.push(HtmlNode::Comment {
+                    value,
+                    span: self.span(lo, hi),
+                });
+                self.pos = hi;
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                // Doctype, CDATA and processing instructions are not a part of templates
+                let lo = self.pos;
+                self.pos = self.find_tag_end(self.pos + 2);
+
+                let kind = if rest
+                    .get(2..9)
+                    .is_some_and(|s| s.eq_ignore_ascii_case("doctype"))
+                {
+                    ErrorKind::StrayDoctype
+                } else if rest.starts_with("<![CDATA[") {
+                    ErrorKind::CdataInHtmlContent
+                } else if rest.starts_with("<?") {
+                    ErrorKind::UnexpectedQuestionMarkInsteadOfTagName
+                } else {
+                    ErrorKind::IncorrectlyOpenedComment
+                };
+                self.report(ParseErrorKind::InvalidHtml(Box::new(kind)), lo, self.pos);
+            } else if is_tag_start(rest) {
+                if let Some(html_element) = self.parse_element() {
+                    nodes.push(HtmlNode::Element(html_element));
+                }
+            } else {
+                let lo = self.pos;
+                let hi = self.scan_text(lo);
+                nodes.push(HtmlNode::Text {
+                    raw: &self.src[lo..hi],
+                    span: self.span(lo, hi),
+                });
+                self.pos = hi;
+            }
+        }
+
+        nodes
+    }
+
+    /// Parses an element starting at `self.pos` together with its children.
+    /// Returns `None` when the input ends inside the start tag.
+    fn parse_element(&mut self) -> Option<HtmlElement<'s>> {
+        let lo = self.pos;
+        let name_lo = lo + 1;
+        let name_hi = name_lo + scan_tag_name(&self.src[name_lo..]);
+        let tag_name = &self.src[name_lo..name_hi];
+        self.pos = name_hi;
+
+        let mut attributes = Vec::new();
+        let Some(is_self_closing) = self.parse_attributes(&mut attributes) else {
+            self.report(ParseErrorKind::EofInTag, lo, self.src.len());
+            self.pos = self.src.len();
+
+            // Most likely the tag is being typed, thus it is kept
+            return self.keep_unclosed_tags.then(|| HtmlElement {
+                tag_name,
+                attributes,
+                children: vec![],
+                span: self.span(lo, self.pos),
+            });
+        };
+
+        if is_self_closing || VOID_TAGS.contains(&tag_name) {
+            return Some(HtmlElement {
+                tag_name,
+                attributes,
+                children: vec![],
+                span: self.span(lo, self.pos),
+            });
+        }
+
+        let content_kind = match tag_name {
+            _ if self.in_foreign => ContentKind::Normal,
+            "script" | "style" => ContentKind::RawText,
+            "textarea" | "title" => ContentKind::EscapableRawText,
+            _ => ContentKind::Normal,
+        };
+
+        let mut children = match content_kind {
+            ContentKind::Normal => {
+                let old_in_foreign = self.in_foreign;
+                let old_in_v_pre = self.in_v_pre;
+                self.in_foreign |= matches!(tag_name, "svg" | "math");
+                self.in_v_pre |= attributes.iter().any(|attr| attr.name == "v-pre");
+
+                self.open_elements.push(tag_name);
+                let children = self.parse_children();
+                self.open_elements.pop();
+
+                self.in_foreign = old_in_foreign;
+                self.in_v_pre = old_in_v_pre;
+                children
+            }
+            ContentKind::RawText | ContentKind::EscapableRawText => {
+                let content_lo = self.pos;
+                let content_hi = self.find_raw_text_end(tag_name);
+                self.pos = content_hi;
+
+                let raw = &self.src[content_lo..content_hi];
+                let span = self.span(content_lo, content_hi);
+                match (raw.is_empty(), content_kind) {
+                    (true, _) => vec![],
+                    (false, ContentKind::RawText) => vec![HtmlNode::RawText { raw, span }],
+                    (false, _) => vec![HtmlNode::Text { raw, span }],
+                }
+            }
+        };
+
+        // A leading newline is ignored, same as in browsers
+        if matches!(tag_name, "pre" | "textarea") {
+            strip_leading_newline(&mut children);
+        }
+
+        // Consume the end tag if it belongs to this element
+        let is_closed = self.src[self.pos..]
+            .strip_prefix("</")
+            .map(|after_solidus| &after_solidus[..scan_tag_name(after_solidus)])
+            .is_some_and(|end_tag_name| end_tag_name.eq_ignore_ascii_case(tag_name));
+
+        if is_closed {
+            self.pos = self.find_tag_end(self.pos + 2);
+        } else {
+            self.report(ParseErrorKind::MissingEndTag, name_lo, name_hi);
+        }
+
+        Some(HtmlElement {
+            tag_name,
+            attributes,
+            children,
+            span: self.span(lo, self.pos),
+        })
+    }
+
+    /// Parses the attributes until the end of the start tag.
+    /// Returns whether the tag is self-closing, or `None` if the input ended.
+    fn parse_attributes(&mut self, out: &mut Vec<Attribute>) -> Option<bool> {
+        loop {
+            self.skip_whitespace();
+            let rest = &self.src[self.pos..];
+
+            if rest.is_empty() {
+                return None;
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                return Some(false);
+            }
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Some(true);
+            }
+            if rest.starts_with('/') {
+                self.pos += 1;
+                continue;
+            }
+
+            // Name, `=` is only allowed as the first character
+            let name_lo = self.pos;
+            let first_char_len = rest.chars().next().map_or(1, char::len_utf8);
+            let name_hi = name_lo + first_char_len + scan_attribute_name(&rest[first_char_len..]);
+            let name = &self.src[name_lo..name_hi];
+            self.pos = name_hi;
+
+            // Value
+            self.skip_whitespace();
+            let mut value_hi = name_hi;
+            let mut value = None;
+            if self.src[self.pos..].starts_with('=') {
+                self.pos += 1;
+                self.skip_whitespace();
+                let rest = &self.src[self.pos..];
+
+                let raw_value = match rest.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let quoted = &rest[1..];
+                        let value_len = quoted.find(quote)?;
+                        value_hi = self.pos + value_len + 2;
+                        &quoted[..value_len]
+                    }
+                    _ => {
+                        let value_len = rest
+                            .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                            .unwrap_or(rest.len());
+                        value_hi = self.pos + value_len;
+                        &rest[..value_len]
+                    }
+                };
+
+                value = Some(FervidAtom::from(decode_entities(raw_value)));
+                self.pos = value_hi;
+            }
+
+            if out.iter().any(|attr| attr.name == name) {
+                self.report(ParseErrorKind::DuplicateAttribute, name_lo, value_hi);
+                continue;
+            }
+
+            out.push(Attribute {
+                span: self.span(name_lo, value_hi),
+                namespace: None,
+                prefix: None,
+                name: name.into(),
+                raw_name: None,
+                value,
+                raw_value: None,
+            });
+        }
+    }
+
+    /// Finds the end of a text starting at `from`, skipping over the interpolations
+    fn scan_text(&self, from: usize) -> usize {
+        let bytes = self.src.as_bytes();
+        let start_pat = self.interpolation_start_pat.as_bytes();
+        let end_pat = self.interpolation_end_pat.as_bytes();
+
+        let mut idx = from;
+        while idx < bytes.len() {
+            if !self.in_v_pre && bytes[idx..].starts_with(start_pat) {
+                let content_lo = idx + start_pat.len();
+                if let Some(content_len) = bytes[content_lo..]
+                    .windows(end_pat.len())
+                    .position(|window| window == end_pat)
+                {
+                    idx = content_lo + content_len + end_pat.len();
+                    continue;
+                }
+            }
+
+            // The first character is always text, even if it is `<`
+            if idx > from && bytes[idx] == b'<' && is_markup_start(&self.src[idx..]) {
+                break;
+            }
+
+            idx += 1;
+        }
+
+        idx
+    }
+
+    /// Finds the end tag of raw text content and returns the content end
+    fn find_raw_text_end(&mut self, tag_name: &str) -> usize {
+        let mut search_from = self.pos;
+
+        while let Some(idx) = self.src[search_from..].find("</") {
+            let end_tag_lo = search_from + idx;
+            let after_solidus = &self.src[end_tag_lo + 2..];
+            let name_len = scan_tag_name(after_solidus);
+
+            if after_solidus[..name_len].eq_ignore_ascii_case(tag_name) {
+                return end_tag_lo;
+            }
+
+            search_from = end_tag_lo + 2;
+        }
+
+        self.src.len()
+    }
+
+    /// Returns the index after the next `>`, or the end of input
+    fn find_tag_end(&self, from: usize) -> usize {
+        self.src[from..]
+            .find('>')
+            .map_or(self.src.len(), |idx| from + idx + 1)
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.src[self.pos..];
+        self.pos += rest.len()
+            - rest
+                .trim_start_matches(|c: char| c.is_ascii_whitespace())
+                .len();
+    }
+
+    fn span(&self, lo: usize, hi: usize) -> Span {
+        Span {
+            lo: BytePos(self.offset + lo as u32),
+            hi: BytePos(self.offset + hi as u32),
+        }
+    }
+
+    fn report(&mut self, kind: ParseErrorKind, lo: usize, hi: usize) {
+        let span = self.span(lo, hi);
+        self.errors.push(ParseError { kind, span });
+    }
+}
+
+/// `<` followed by a letter
+fn is_tag_start(raw: &str) -> bool {
+    raw.strip_prefix('<')
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(|c| c.is_ascii_alphabetic())
+}
+
+/// Start of a tag, an end tag, a comment or a declaration
+fn is_markup_start(raw: &str) -> bool {
+    is_tag_start(raw) || raw.starts_with("</") || raw.starts_with("<!") || raw.starts_with("<?")
+}
+
+fn scan_tag_name(raw: &str) -> usize {
+    if !raw.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return 0;
+    }
+
+    raw.find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+        .unwrap_or(raw.len())
+}
+
+fn scan_attribute_name(raw: &str) -> usize {
+    raw.find(|c: char| c.is_ascii_whitespace() || matches!(c, '/' | '>' | '='))
+        .unwrap_or(raw.len())
+}
+
+fn strip_leading_newline(children: &mut [HtmlNode]) {
+    let Some(HtmlNode::Text { raw, span }) = children.first_mut() else {
+        return;
+    };
+
+    let newline_len = if raw.starts_with("\r\n") {
+        2
+    } else if raw.starts_with('\n') {
+        1
+    } else {
+        return;
+    };
+
+    *raw = &raw[newline_len..];
+    span.lo = BytePos(span.lo.0 + newline_len as u32);
+}
+
+/// Decodes the character references, e.g. `&amp;` or `&#38;`.
+/// Also normalizes the newlines.
+pub(crate) fn decode_entities(raw: &str) -> Cow<'_, str> {
+    if !raw.contains(['&', '\r']) {
+        return Cow::Borrowed(raw);
+    }
+
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+
+    while let Some(idx) = rest.find(['&', '\r']) {
+        out.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+
+        if let Some(after_cr) = rest.strip_prefix('\r') {
+            out.push('\n');
+            rest = after_cr.strip_prefix('\n').unwrap_or(after_cr);
+            continue;
+        }
+
+        match decode_character_reference(rest) {
+            Some((decoded, len)) => {
+                out.push_str(&decoded);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+/// Decodes a character reference at the start of `raw` (which starts with `&`).
+/// Returns the decoded characters and the length of the reference.
+fn decode_character_reference(raw: &str) -> Option<(Cow<'static, str>, usize)> {
+    let after_amp = &raw[1..];
+
+    // Numeric, `&#38;` or `&#x26;`
+    if let Some(numeric) = after_amp.strip_prefix('#') {
+        let (digits_start, radix) = match numeric.strip_prefix(['x', 'X']) {
+            Some(_) => (1, 16),
+            None => (0, 10),
+        };
+        let digits = &numeric[digits_start..];
+        let digits_len = digits
+            .find(|c: char| !c.is_digit(radix))
+            .unwrap_or(digits.len());
+        if digits_len == 0 {
+            return None;
+        }
+
+        let code_point = u32::from_str_radix(&digits[..digits_len], radix).unwrap_or(0);
+        let c = match char::from_u32(code_point) {
+            Some('\0') | None => char::REPLACEMENT_CHARACTER,
+            Some(c) => c,
+        };
+
+        let mut len = 2 + digits_start + digits_len;
+        if raw[len..].starts_with(';') {
+            len += 1;
+        }
+        return Some((Cow::Owned(c.to_string()), len));
+    }
+
+    // Named, `&amp;` or the legacy `&amp`
+    let name_len = after_amp
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(after_amp.len());
+    if name_len == 0 {
+        return None;
+    }
+
+    let len = if after_amp[name_len..].starts_with(';') {
+        name_len + 2
+    } else {
+        name_len + 1
+    };
+
+    HTML_ENTITIES
+        .get(&raw[..len])
+        .map(|entity| (Cow::Owned(entity.characters.clone()), len))
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::{AttributeOrBinding, SfcTemplateBlock};
+
+    use super::*;
+
+    #[test]
+    fn it_preserves_case_and_allows_self_closing() {
+        let source = r#"<template><MyComponent :foo="bar"/><my-component>text</my-component><Foo.Bar /><DIV></DIV></template>"#;
+        let (template, errors) = parse_template(source);
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(4, template.roots.len());
+
+        let tag_names: Vec<&str> = template
+            .roots
+            .iter()
+            .map(|node| element(node).starting_tag.tag_name.as_str())
+            .collect();
+        assert_eq!(
+            vec!["MyComponent", "my-component", "Foo.Bar", "DIV"],
+            tag_names
+        );
+
+        // Self-closing elements have no children and are not swallowing the siblings
+        let my_component = element(&template.roots[0]);
+        assert!(my_component.children.is_empty());
+        assert_eq!(
+            r#"<MyComponent :foo="bar"/>"#,
+            source_of(source, my_component.span)
+        );
+        assert_eq!(
+            "<my-component>text</my-component>",
+            source_of(source, element(&template.roots[1]).span)
+        );
+        assert_eq!(
+            "<Foo.Bar />",
+            source_of(source, element(&template.roots[2]).span)
+        );
+    }
+
+    #[test]
+    fn it_does_not_apply_html_tree_construction() {
+        let source = r#"<template><table><MyRow v-for="row in rows" /><tr><td>1</td></tr></table><p><div>block</div></p><ul><li>one<li>two</ul></template>"#;
+        let (template, errors) = parse_template(source);
+
+        // `<li>`s are not implicitly closed
+        assert_eq!(2, errors.len());
+        assert!(errors
+            .iter()
+            .all(|e| matches!(e.kind, ParseErrorKind::MissingEndTag)));
+        assert_eq!(3, template.roots.len());
+
+        // No foster-parenting
+        let table = element(&template.roots[0]);
+        assert_eq!(2, table.children.len());
+        assert!(element(&table.children[0]).starting_tag.tag_name == "MyRow");
+        assert!(element(&table.children[1]).starting_tag.tag_name == "tr");
+
+        // `<p>` is not auto-closed
+        let p = element(&template.roots[1]);
+        assert_eq!(1, p.children.len());
+        assert!(element(&p.children[0]).starting_tag.tag_name == "div");
+        assert_eq!("<p><div>block</div></p>", source_of(source, p.span));
+    }
+
+    #[test]
+    fn it_parses_raw_text_and_escapable_raw_text() {
+        let source = r#"<template><style>.a > .b { }</style><textarea>
+{{ msg }} &lt;b&gt;</textarea><svg><title><b>bold</b></title><style><i/></style></svg></template>"#;
+        let (template, errors) = parse_template(source);
+        assert!(errors.is_empty(), "{errors:?}");
+
+        // `<style>` content is kept as-is
+        let style = element(&template.roots[0]);
+        assert!(matches!(&style.children[..], [Node::Text(t, _)] if t == ".a > .b { }"));
+
+        // `<textarea>` content has interpolations and entities, but no tags.
+        // The leading newline is ignored.
+        let textarea = element(&template.roots[1]);
+        let [Node::Interpolation(interpolation), Node::Text(text, _)] = &textarea.children[..]
+        else {
+            panic!("Unexpected children {:?}", textarea.children);
+        };
+        assert_eq!(" msg ", source_of(source, interpolation.span));
+        assert!(text == " <b>");
+
+        // Inside `<svg>`, `<title>` and `<style>` have regular content
+        let svg = element(&template.roots[2]);
+        let title = element(&svg.children[0]);
+        assert!(element(&title.children[0]).starting_tag.tag_name == "b");
+        let style = element(&svg.children[1]);
+        assert!(element(&style.children[0]).starting_tag.tag_name == "i");
+    }
+
+    #[test]
+    fn it_parses_text_interpolations_and_comments() {
+        let source = r#"<template><div title="a &amp; b">{{ a < b }} &copy; &#x41;&#66;<!-- comment --></div></template>"#;
+        let (template, errors) = parse_template(source);
+        assert!(errors.is_empty(), "{errors:?}");
+
+        let div = element(&template.roots[0]);
+        assert!(matches!(
+            div.starting_tag.attributes.first(),
+            Some(AttributeOrBinding::RegularAttribute { name, value, .. }) if name == "title" && value == "a & b"
+        ));
+
+        // `<` inside an interpolation does not start a tag
+        let [Node::Interpolation(interpolation), Node::Text(text, text_span), Node::Comment(comment, comment_span)] =
+            &div.children[..]
+        else {
+            panic!("Unexpected children {:?}", div.children);
+        };
+        assert!(interpolation.value.is_bin());
+        assert_eq!(" a < b ", source_of(source, interpolation.span));
+        assert!(text == " © AB");
+        assert_eq!(" &copy; &#x41;&#66;", source_of(source, *text_span));
+        assert!(comment == " comment ");
+        assert_eq!("<!-- comment -->", source_of(source, *comment_span));
+    }
+
+    #[test]
+    fn it_reports_malformed_markup() {
+        let source =
+            r#"<template><div></span><b>bold</div><i a="1" a="2"></i><!-- unclosed </template>"#;
+        let (template, errors) = parse_template(source);
+
+        let reported: Vec<(&str, &str)> = errors
+            .iter()
+            .map(|e| (e.code(), source_of(source, e.span)))
+            .collect();
+        assert_eq!(
+            vec![
+                ("invalid-end-tag", "</span>"),
+                ("missing-end-tag", "b"),
+                ("duplicate-attribute", r#"a="2""#),
+                ("eof-in-comment", "<!-- unclosed </template>"),
+                ("missing-end-tag", "template")
+            ],
+            reported
+        );
+
+        // Recovered elements
+        let div = element(&template.roots[0]);
+        let b = element(&div.children[0]);
+        assert_eq!("<b>bold", source_of(source, b.span));
+        let i = element(&template.roots[1]);
+        assert_eq!(1, i.starting_tag.attributes.len());
+    }
+
+    #[test]
+    fn it_reports_doctype_cdata_and_processing_instructions() {
+        let source = r#"<template><!DOCTYPE html><div><![CDATA[x]]></div><?xml version="1.0"?><!foo></template>"#;
+        let (template, errors) = parse_template(source);
+
+        let kinds: Vec<&ErrorKind> = errors
+            .iter()
+            .map(|e| match e.kind {
+                ParseErrorKind::InvalidHtml(ref kind) => kind.as_ref(),
+                ref kind => panic!("Unexpected error {kind:?}"),
+            })
+            .collect();
+        assert!(matches!(
+            kinds.as_slice(),
+            [
+                ErrorKind::StrayDoctype,
+                ErrorKind::CdataInHtmlContent,
+                ErrorKind::UnexpectedQuestionMarkInsteadOfTagName,
+                ErrorKind::IncorrectlyOpenedComment
+            ]
+        ));
+        assert_eq!("<!DOCTYPE html>", source_of(source, errors[0].span));
+
+        // The rest of the template is kept
+        assert_eq!(1, template.roots.len());
+        assert!(element(&template.roots[0]).starting_tag.tag_name == "div");
+    }
+
+    fn parse_template(source: &str) -> (SfcTemplateBlock, Vec<ParseError>) {
+        let mut errors = Vec::new();
+        let mut parser = SfcParser::new(source, &mut errors);
+        let descriptor = parser.parse_sfc().expect("Should parse");
+        let template = descriptor.template.expect("Should have template");
+        (template, errors)
+    }
+
+    fn element(node: &Node) -> &ElementNode {
+        match node {
+            Node::Element(element) => element,
+            _ => panic!("Expected element, got {node:?}"),
+        }
+    }
+
+    fn source_of(source: &str, span: Span) -> &str {
+        &source[span.lo.0 as usize - 1..span.hi.0 as usize - 1]
+    }
+}
@@ -1,12 +1,14 @@
 mod attributes;
 mod custom_block;
 mod error;
+mod html;
+mod pug;
 mod script;
 mod sfc;
 mod style;
 mod template;
 
-pub use error::ParseError;
+pub use error::{ErrorRecoveryMode, ParseError};
 use swc_core::common::comments::SingleThreadedComments;
 
 // Default patterns for interpolation
@@ -22,17 +24,11 @@ pub struct SfcParser<'i, 'e, 'p> {
     interpolation_start_pat: &'p str,
     interpolation_end_pat: &'p str,
     pub ignore_empty: bool,
+    pub error_recovery_mode: ErrorRecoveryMode,
 }
 
 impl<'i, 'e> SfcParser<'i, 'e, 'static> {
     pub fn new(input: &'i str, errors: &'e mut Vec<ParseError>) -> Self {
-        // TODO When should it fail? What do we do with errors?..
-        // I was thinking of 4 strategies:
-        // `HARD_FAIL_ON_ERROR` (any error = fail (incl. recoverable), stop at the first error),
-        // `SOFT_REPORT_ALL` (any error = fail (incl. recoverable), continue as far as possible and report after),
-        // `SOFT_RECOVER_SAFE` (try to ignore recoverable, report the rest),
-        // `SOFT_RECOVER_UNSAFE` (ignore as much as is possible, but still report).
-
         SfcParser {
             input,
             errors,
@@ -41,16 +37,27 @@ impl<'i, 'e> SfcParser<'i, 'e, 'static> {
             interpolation_start_pat: INTERPOLATION_START_PAT_DEFAULT,
             interpolation_end_pat: INTERPOLATION_END_PAT_DEFAULT,
             ignore_empty: true,
+            error_recovery_mode: ErrorRecoveryMode::default(),
         }
     }
 }
 
+impl SfcParser<'_, '_, '_> {
+    /// Comments of the scripts, keyed by the positions in the SFC
+    pub fn comments(&self) -> &SingleThreadedComments {
+        &self.comments
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use fervid_core::{Node, SfcDescriptor, SfcScriptLang};
-    use swc_core::ecma::ast::{ModuleDecl, ModuleItem};
+    use swc_core::{
+        common::Spanned,
+        ecma::ast::{ModuleDecl, ModuleItem},
+    };
 
-    use crate::{error::ParseErrorKind, ParseError, SfcParser};
+    use crate::{error::ParseErrorKind, ErrorRecoveryMode, ParseError, SfcParser};
 
     const SHOULD_EXIST: &str = "Should exist";
 
@@ -319,8 +326,9 @@ h1 { color: red }
 
     #[test]
     fn template_with_preprocessor_lang_should_be_treated_as_plain_text() {
+        // Pug has a built-in frontend, other preprocessors are kept as-is
         let content = r#"p(v-if="1 < 2") test <div/>"#;
-        let source = format!("<template lang=\"pug\">{content}</template>");
+        let source = format!("<template lang=\"slm\">{content}</template>");
 
         let (descriptor, errors) = parse_with_errors(&source);
         assert!(errors.is_empty());
@@ -412,6 +420,13 @@ h1 { color: red }
         assert!(errors.is_empty());
     }
 
+    #[test]
+    fn detects_vapor_mode() {
+        assert!(parse("<script setup vapor>console.log(1)</script>").vapor);
+        assert!(parse("<script vapor>console.log(1)</script><script setup></script>").vapor);
+        assert!(!parse("<script setup>console.log(1)</script>").vapor);
+    }
+
     #[test]
     fn should_throw_error_if_no_template_or_script_is_present() {
         let (_, errors) = parse_with_errors("import { ref } from 'vue'");
@@ -420,6 +435,179 @@ h1 { color: red }
             .any(|e| matches!(&e.kind, ParseErrorKind::MissingTemplateOrScript)));
     }
 
+    #[test]
+    fn errors_have_codes_and_messages() {
+        let (_, errors) =
+            parse_with_errors("<template><div/></template><template><div/></template>");
+        let error = errors
+            .iter()
+            .find(|e| matches!(&e.kind, ParseErrorKind::DuplicateTemplate))
+            .expect(SHOULD_EXIST);
+        assert_eq!("duplicate-template", error.code());
+        assert_eq!(
+            "Single file component can contain only one <template> element",
+            error.to_string()
+        );
+
+        let (_, errors) = parse_with_errors("<template><div :[foo></div></template>");
+        let error = errors.first().expect(SHOULD_EXIST);
+        assert_eq!("directive-syntax-dynamic-argument", error.code());
+
+        let (_, errors) = parse_with_errors("<script>const a = </script>");
+        let error = errors.first().expect(SHOULD_EXIST);
+        assert_eq!("ecma-syntax-error", error.code());
+        assert!(!error.to_string().contains("EcmaSyntaxError"));
+    }
+
+    #[test]
+    fn script_spans_of_crlf_sources() {
+        let source = "<template>\r\n<div/>\r\n</template>\r\n<script>\r\nconst a = 1\r\nconst b = 2\r\n</script>";
+        let script = parse(source).script_legacy.expect(SHOULD_EXIST);
+        let span = script.content.body[1].span();
+        assert_eq!(
+            "const b = 2",
+            &source[span.lo.0 as usize - 1..span.hi.0 as usize - 1]
+        );
+
+        let (result, errors) = parse_with_mode(
+            "<script>\r\nimport Mixi\r\nn from 'x'\r\n</script>",
+            ErrorRecoveryMode::SoftReportAll,
+        );
+        let error = result
+            .err()
+            .into_iter()
+            .chain(errors)
+            .find(|e| e.code() == "ecma-syntax-error")
+            .expect(SHOULD_EXIST);
+        assert_eq!(24, error.span.lo.0);
+    }
+
+    #[test]
+    fn error_recovery_modes() {
+        let source = "<template><div :[foo></div>{{ a. }}</template>";
+
+        let (result, errors) = parse_with_mode(source, ErrorRecoveryMode::HardFailOnError);
+        let error = result.expect_err("Should fail");
+        assert!(matches!(
+            error.kind,
+            ParseErrorKind::DirectiveSyntaxDynamicArgument
+        ));
+        assert!(errors.is_empty());
+
+        let (result, errors) = parse_with_mode(source, ErrorRecoveryMode::SoftReportAll);
+        let error = result.expect_err("Should fail");
+        assert!(matches!(
+            error.kind,
+            ParseErrorKind::DirectiveSyntaxDynamicArgument
+        ));
+        assert_eq!(1, errors.len());
+
+        let (result, errors) = parse_with_mode(source, ErrorRecoveryMode::SoftRecoverSafe);
+        let template = result.expect(SHOULD_EXIST).template.expect(SHOULD_EXIST);
+        assert_eq!(1, template.roots.len());
+        assert_eq!(2, errors.len());
+    }
+
+    #[test]
+    fn error_recovery_unsafe_template() {
+        let (result, errors) = parse_with_mode(
+            "<template><div>{{ foo. }}</div>{{ bar</template>",
+            ErrorRecoveryMode::SoftRecoverUnsafe,
+        );
+        let template = result.expect(SHOULD_EXIST).template.expect(SHOULD_EXIST);
+        assert_eq!(2, errors.len());
+        assert!(matches!(
+            errors[1].kind,
+            ParseErrorKind::MissingInterpolationEnd
+        ));
+
+        let Node::Element(ref div) = template.roots[0] else {
+            panic!("Expected an element");
+        };
+        assert!(matches!(div.children[0], Node::Interpolation(_)));
+        assert!(matches!(template.roots[1], Node::Interpolation(_)));
+
+        // Unclosed start tag is kept
+        let (result, errors) = parse_with_mode(
+            "<template><div class=\"a\"",
+            ErrorRecoveryMode::SoftRecoverUnsafe,
+        );
+        let template = result.expect(SHOULD_EXIST).template.expect(SHOULD_EXIST);
+        assert!(!errors.is_empty());
+        assert!(matches!(template.roots[0], Node::Element(_)));
+    }
+
+    #[test]
+    fn error_recovery_unsafe_script() {
+        let (result, errors) = parse_with_mode(
+            "<script setup>\nconst a = 1\nconst b = \nconst c = 2\n</script>",
+            ErrorRecoveryMode::SoftRecoverUnsafe,
+        );
+        let script = result
+            .expect(SHOULD_EXIST)
+            .script_setup
+            .expect(SHOULD_EXIST);
+        assert!(!errors.is_empty());
+        assert!(!script.content.body.is_empty());
+
+        let (result, _) = parse_with_mode(
+            "<script setup>\nconst a = 1\nconst b = \nconst c = 2\n</script>",
+            ErrorRecoveryMode::SoftRecoverSafe,
+        );
+        assert!(result.expect(SHOULD_EXIST).script_setup.is_none());
+    }
+
+    #[test]
+    fn truncated_input_does_not_panic() {
+        let document = include_str!("../../fervid/benches/fixtures/input.vue");
+
+        for (idx, _) in document.char_indices().step_by(7) {
+            for mode in [
+                ErrorRecoveryMode::HardFailOnError,
+                ErrorRecoveryMode::SoftRecoverSafe,
+                ErrorRecoveryMode::SoftRecoverUnsafe,
+            ] {
+                let _ = parse_with_mode(&document[..idx], mode);
+            }
+        }
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let mut src = String::from(include_str!("../../fervid/benches/fixtures/input.vue"));
+        src.push_str(
+            r#"
+<script lang="ts">
+export default { name: 'Foo' }
+</script>
+<style scoped lang="scss">
+.a { color: red }
+</style>
+<i18n lang="json">{ "en": { "hello": "Hello" } }</i18n>
+"#,
+        );
+
+        let descriptor = parse(&src);
+        let serialized = serde_json::to_value(&descriptor).expect("Should serialize");
+
+        // Spot-check the shape which is relied upon by the JS side
+        assert!(serialized["template"]["roots"].is_array());
+        assert!(serialized["scriptSetup"]["content"]["body"].is_array());
+        assert_eq!(serialized["scriptLegacy"]["lang"], "Typescript");
+        assert_eq!(serialized["styles"][0]["isScoped"], true);
+        assert_eq!(
+            serialized["customBlocks"][0]["startingTag"]["tagName"],
+            "i18n"
+        );
+
+        let deserialized: SfcDescriptor =
+            serde_json::from_value(serialized.clone()).expect("Should deserialize");
+        assert_eq!(
+            serialized,
+            serde_json::to_value(&deserialized).expect("Should serialize")
+        );
+    }
+
     fn parse(source: &str) -> SfcDescriptor {
         let mut errors = Vec::new();
         let mut parser = SfcParser::new(source, &mut errors);
@@ -435,6 +623,18 @@ h1 { color: red }
         (descriptor, errors)
     }
 
+    fn parse_with_mode(
+        source: &str,
+        mode: ErrorRecoveryMode,
+    ) -> (Result<SfcDescriptor, ParseError>, Vec<ParseError>) {
+        let mut errors = Vec::new();
+        let mut parser = SfcParser::new(source, &mut errors);
+        parser.error_recovery_mode = mode;
+        let result = parser.parse_sfc();
+        let errors = std::mem::take(parser.errors);
+        (result, errors)
+    }
+
     fn padding() -> (String, usize) {
         // No random
         let padding = 4;
@@ -0,0 +1,1014 @@
+//! Frontend for `<template lang="pug">`.
+//!
+//! Pug is parsed directly into the template IR instead of being compiled to HTML first.
+//! All the spans point to the original Pug source, which keeps diagnostics and source maps accurate.
+//! Only the markup subset of Pug is supported: tags, `.class`/`#id` shorthands, attributes,
+//! block expansion, text (inline, piped and blocks), comments and literal HTML.
+//! JavaScript-powered features (code, conditionals, iteration, mixins, includes)
+//! have Vue equivalents and are reported as unsupported.
+
+use fervid_core::{fervid_atom, AttributeOrBinding, ElementNode, FervidAtom, Node, PatchHints};
+use swc_core::common::{BytePos, Span};
+use swc_html_ast::Attribute;
+
+use crate::{
+    error::{ParseError, ParseErrorKind},
+    SfcParser,
+};
+
+/// Pug keywords which require a JavaScript runtime and are not supported
+const UNSUPPORTED_KEYWORDS: [&str; 17] = [
+    "append", "block", "case", "default", "doctype", "each", "else", "extends", "for", "if",
+    "include", "mixin", "prepend", "unless", "when", "while", "yield",
+];
+
+impl SfcParser<'_, '_, '_> {
+    /// Parses the Pug template content located at `content_span` of the input
+    pub fn parse_pug_template(&mut self, content_span: Span) -> Vec<Node> {
+        let input = self.input;
+        let (Some(lo), Some(hi)) = (
+            (content_span.lo.0 as usize).checked_sub(1),
+            (content_span.hi.0 as usize).checked_sub(1),
+        ) else {
+            return vec![];
+        };
+        let Some(src) = input.get(lo..hi) else {
+            return vec![];
+        };
+
+        let mut pug_parser = PugParser::new(src, content_span.lo.0);
+        let pug_nodes = pug_parser.parse_children(None);
+        self.errors.append(&mut pug_parser.errors);
+
+        self.process_pug_nodes(pug_nodes)
+    }
+
+    fn process_pug_nodes(&mut self, pug_nodes: Vec<PugNode>) -> Vec<Node> {
+        let mut out = Vec::with_capacity(pug_nodes.len());
+
+        for pug_node in pug_nodes {
+            match pug_node {
+                PugNode::Element(pug_element) => out.push(self.process_pug_element(pug_element)),
+                PugNode::Text { raw, span } => self.process_pug_text(raw, span, &mut out),
+                PugNode::Comment { value, span } => {
+                    out.push(Node::Comment(FervidAtom::from(value), span))
+                }
+            }
+        }
+
+        out
+    }
+
+    fn process_pug_element(&mut self, pug_element: PugElement) -> Node {
+        let PugElement {
+            tag_name,
+            classes,
+            id,
+            mut attributes,
+            children,
+            span,
+        } = pug_element;
+
+        // Shorthands are merged into the static attributes if they are present,
+        // e.g. `a.foo(class="bar")` is `<a class="foo bar">`
+        let mut shorthand_attributes = Vec::with_capacity(2);
+        if let Some((classes, classes_span)) = classes {
+            match attributes
+                .iter_mut()
+                .find(|attr| attr.name == "class" && attr.value.is_some())
+            {
+                Some(class_attr) => {
+                    let value = class_attr.value.as_deref().unwrap_or_default();
+                    class_attr.value = Some(FervidAtom::from(format!("{classes} {value}")));
+                }
+                None => shorthand_attributes.push(AttributeOrBinding::RegularAttribute {
+                    name: fervid_atom!("class"),
+                    value: FervidAtom::from(classes),
+                    span: classes_span,
+                }),
+            }
+        }
+        if let Some((id, id_span)) = id {
+            if !attributes.iter().any(|attr| attr.name == "id") {
+                shorthand_attributes.push(AttributeOrBinding::RegularAttribute {
+                    name: fervid_atom!("id"),
+                    value: FervidAtom::from(id),
+                    span: id_span,
+                });
+            }
+        }
+
+        // Save old `v-pre` (restored at the end of the function)
+        let old_is_pre = self.is_pre;
+
+        let mut starting_tag = self.create_starting_tag(FervidAtom::from(tag_name), attributes);
+        if !shorthand_attributes.is_empty() {
+            shorthand_attributes.append(&mut starting_tag.attributes);
+            starting_tag.attributes = shorthand_attributes;
+        }
+
+        let result = Node::Element(ElementNode {
+            kind: fervid_core::ElementKind::Element,
+            starting_tag,
+            children: self.process_pug_nodes(children),
+            template_scope: 0,
+            patch_hints: PatchHints::default(),
+            span,
+        });
+
+        self.is_pre = old_is_pre;
+        result
+    }
+
+    /// Pug text is output as-is, thus it may contain HTML, entities and interpolations
+    fn process_pug_text(&mut self, raw: &str, span: Span, out: &mut Vec<Node>) {
+        if raw.contains('<') {
+            out.extend(self.parse_template_fragment(span));
+        } else {
+            self.process_text(raw, span, out);
+        }
+    }
+}
+
+enum PugNode<'s> {
+    Element(PugElement<'s>),
+    /// Text or literal HTML, always a contiguous slice of the source
+    Text {
+        raw: &'s str,
+        span: Span,
+    },
+    Comment {
+        value: String,
+        span: Span,
+    },
+}
+
+struct PugElement<'s> {
+    tag_name: &'s str,
+    /// Space-separated classes from the `.foo.bar` shorthands
+    classes: Option<(String, Span)>,
+    /// Id from the `#foo` shorthand
+    id: Option<(&'s str, Span)>,
+    attributes: Vec<Attribute>,
+    children: Vec<PugNode<'s>>,
+    span: Span,
+}
+
+impl PugNode<'_> {
+    fn span_hi(&self) -> BytePos {
+        match self {
+            PugNode::Element(pug_element) => pug_element.span.hi,
+            PugNode::Text { span, .. } | PugNode::Comment { span, .. } => span.hi,
+        }
+    }
+}
+
+struct PugParser<'s> {
+    src: &'s str,
+    /// Position of `src` in the whole input
+    offset: u32,
+    /// Current byte index in `src`
+    pos: usize,
+    errors: Vec<ParseError>,
+}
+
+impl<'s> PugParser<'s> {
+    fn new(src: &'s str, offset: u32) -> Self {
+        Self {
+            src,
+            offset,
+            pos: 0,
+            errors: vec![],
+        }
+    }
+
+    /// Parses the lines indented deeper than `parent_indent`
+    fn parse_children(&mut self, parent_indent: Option<usize>) -> Vec<PugNode<'s>> {
+        let mut nodes = Vec::new();
+        let mut children_indent = None;
+
+        while let Some((line_start, indent)) = self.peek_line() {
+            if parent_indent.is_some_and(|parent_indent| indent <= parent_indent) {
+                break;
+            }
+
+            // All the children must have the same indentation
+            match children_indent {
+                None => children_indent = Some(indent),
+                Some(children_indent) if children_indent != indent => {
+                    let line_end = self.line_end(line_start);
+                    self.report(ParseErrorKind::PugSyntax, line_start + indent, line_end);
+                }
+                _ => {}
+            }
+
+            self.pos = line_start + indent;
+            self.parse_line(indent, &mut nodes);
+        }
+
+        nodes
+    }
+
+    /// Parses a line starting at `self.pos` (after the indentation) and the lines nested in it
+    fn parse_line(&mut self, indent: usize, out: &mut Vec<PugNode<'s>>) {
+        let line_start = self.pos;
+        let line_end = self.line_end(line_start);
+        let line = &self.src[line_start..line_end];
+
+        // Unbuffered comment, nothing is output
+        if line.starts_with("//-") {
+            self.advance_line();
+            self.skip_block(indent);
+            return;
+        }
+
+        // Buffered comment, output as HTML comment
+        if let Some(comment) = line.strip_prefix("//") {
+            self.advance_line();
+            let mut value = comment.to_owned();
+            let mut hi = line_end;
+            for (text, text_hi) in self.take_block_text(indent) {
+                value.push('\n');
+                value.push_str(text);
+                hi = text_hi;
+            }
+
+            out.push(PugNode::Comment {
+                value,
+                span: self.span(line_start, hi),
+            });
+            return;
+        }
+
+        // Piped text
+        if let Some(text) = line.strip_prefix('|') {
+            let text = text.strip_prefix(' ').unwrap_or(text);
+            let text_start = line_end - text.len();
+
+            // Consecutive texts are separated by newlines
+            if matches!(out.last(), Some(PugNode::Text { .. })) {
+                self.push_newline_before(line_start, out);
+            }
+            if !text.is_empty() {
+                out.push(PugNode::Text {
+                    raw: text,
+                    span: self.span(text_start, line_end),
+                });
+            }
+
+            self.advance_line();
+            return;
+        }
+
+        // Literal HTML spanning possibly multiple lines
+        if line.starts_with('<') {
+            let mut hi = line_end;
+            self.advance_line();
+            while let Some((next_line_start, next_indent)) = self.peek_line() {
+                if next_indent < indent
+                    || !self.src[next_line_start + next_indent..].starts_with('<')
+                {
+                    break;
+                }
+
+                hi = self.line_end(next_line_start);
+                self.pos = next_line_start;
+                self.advance_line();
+            }
+
+            out.push(PugNode::Text {
+                raw: &self.src[line_start..hi],
+                span: self.span(line_start, hi),
+            });
+            return;
+        }
+
+        // Code, mixins, filters and keywords
+        let first_word_len = line
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+            .unwrap_or(line.len());
+        if line.starts_with(['-', '=', '+', ':'])
+            || line.starts_with("!=")
+            || UNSUPPORTED_KEYWORDS.contains(&&line[..first_word_len])
+        {
+            self.report(ParseErrorKind::PugUnsupportedFeature, line_start, line_end);
+            self.advance_line();
+            self.skip_block(indent);
+            return;
+        }
+
+        match self.parse_element(indent) {
+            Some(pug_element) => out.push(PugNode::Element(pug_element)),
+            None => {
+                self.advance_line();
+                self.skip_block(indent);
+            }
+        }
+    }
+
+    /// Parses an element starting at `self.pos` together with its children.
+    /// Returns `None` when the tag is malformed.
+    fn parse_element(&mut self, indent: usize) -> Option<PugElement<'s>> {
+        let lo = self.pos;
+
+        // Tag name, `div` is implied when only shorthands are used
+        let tag_name_len = self.src[lo..]
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | ':')))
+            .unwrap_or(self.src.len() - lo);
+        // Tag name cannot end with `:` (block expansion) or `-`
+        let tag_name = self.src[lo..lo + tag_name_len].trim_end_matches([':', '-']);
+        self.pos += tag_name.len();
+
+        let tag_name = if !tag_name.is_empty() {
+            tag_name
+        } else if self.src[self.pos..].starts_with(['.', '#']) {
+            "div"
+        } else {
+            let line_end = self.line_end(lo);
+            self.report(ParseErrorKind::PugSyntax, lo, line_end);
+            return None;
+        };
+
+        let mut pug_element = PugElement {
+            tag_name,
+            classes: None,
+            id: None,
+            attributes: vec![],
+            children: vec![],
+            span: self.span(lo, lo),
+        };
+
+        // Shorthands and attributes
+        loop {
+            let rest = &self.src[self.pos..];
+            if let Some(class) = rest.strip_prefix('.').and_then(shorthand_name) {
+                let class_lo = self.pos;
+                self.pos += 1 + class.len();
+                let class_span = self.span(class_lo, self.pos);
+                match pug_element.classes {
+                    Some((ref mut classes, ref mut classes_span)) => {
+                        classes.push(' ');
+                        classes.push_str(class);
+                        classes_span.hi = class_span.hi;
+                    }
+                    None => pug_element.classes = Some((class.to_owned(), class_span)),
+                }
+            } else if let Some(id) = rest.strip_prefix('#').and_then(shorthand_name) {
+                let id_lo = self.pos;
+                self.pos += 1 + id.len();
+                pug_element.id = Some((id, self.span(id_lo, self.pos)));
+            } else if rest.starts_with('(') {
+                self.parse_attributes(&mut pug_element.attributes);
+            } else if rest.starts_with("&attributes") {
+                let attributes_lo = self.pos;
+                self.pos += "&attributes".len();
+                if self.src[self.pos..].starts_with('(') {
+                    self.parse_attributes(&mut vec![]);
+                }
+                self.report(
+                    ParseErrorKind::PugUnsupportedFeature,
+                    attributes_lo,
+                    self.pos,
+                );
+            } else {
+                break;
+            }
+        }
+
+        let line_end = self.line_end(self.pos);
+        let rest = &self.src[self.pos..line_end];
+        let mut is_self_closing = false;
+        let mut is_block_text = false;
+
+        if let Some(after_slash) = rest.strip_prefix('/') {
+            is_self_closing = true;
+            if !after_slash.trim().is_empty() {
+                self.report(ParseErrorKind::PugSyntax, self.pos, line_end);
+            }
+        } else if rest
+            .strip_prefix('.')
+            .is_some_and(|it| it.trim().is_empty())
+        {
+            is_block_text = true;
+        } else if let Some(expanded) = rest.strip_prefix(": ") {
+            // Block expansion, the nested element takes the rest of the line and the children
+            self.pos = line_end - expanded.trim_start().len();
+            if let Some(child) = self.parse_element(indent) {
+                pug_element.span.hi = child.span.hi;
+                pug_element.children.push(PugNode::Element(child));
+            }
+            return Some(pug_element);
+        } else if rest.starts_with('=') || rest.starts_with("!=") {
+            self.report(ParseErrorKind::PugUnsupportedFeature, self.pos, line_end);
+        } else if let Some(text) = rest.strip_prefix(' ') {
+            if !text.is_empty() {
+                let text_start = line_end - text.len();
+                pug_element.children.push(PugNode::Text {
+                    raw: text,
+                    span: self.span(text_start, line_end),
+                });
+            }
+        } else if !rest.is_empty() {
+            self.report(ParseErrorKind::PugSyntax, self.pos, line_end);
+        }
+
+        pug_element.span.hi = self.span(line_end, line_end).hi;
+        self.pos = line_end;
+        self.advance_line();
+
+        if is_block_text {
+            let mut is_first = true;
+            for (text, text_hi) in self.take_block_text(indent) {
+                let text_lo = text_hi - text.len();
+                if !is_first {
+                    self.push_newline_before(text_lo, &mut pug_element.children);
+                }
+                is_first = false;
+
+                pug_element.children.push(PugNode::Text {
+                    raw: text,
+                    span: self.span(text_lo, text_hi),
+                });
+            }
+        } else {
+            let children = self.parse_children(Some(indent));
+            if is_self_closing && !children.is_empty() {
+                self.report(ParseErrorKind::PugSyntax, lo, self.pos);
+            }
+            pug_element.children.extend(children);
+        }
+
+        if let Some(last_child) = pug_element.children.last() {
+            pug_element.span.hi = pug_element.span.hi.max(last_child.span_hi());
+        }
+
+        Some(pug_element)
+    }
+
+    /// Parses attributes in parentheses, e.g. `(foo="bar", :baz="qux" disabled)`.
+    /// Attributes may span multiple lines.
+    fn parse_attributes(&mut self, out: &mut Vec<Attribute>) {
+        let open_paren_pos = self.pos;
+        self.pos += 1;
+
+        loop {
+            // Separators
+            let rest = &self.src[self.pos..];
+            let separators_len = rest
+                .find(|c: char| !(c.is_whitespace() || c == ','))
+                .unwrap_or(rest.len());
+            self.pos += separators_len;
+
+            let rest = &self.src[self.pos..];
+            let Some(first_char) = rest.chars().next() else {
+                self.report(
+                    ParseErrorKind::PugSyntax,
+                    open_paren_pos,
+                    open_paren_pos + 1,
+                );
+                return;
+            };
+
+            if first_char == ')' {
+                self.pos += 1;
+                return;
+            }
+
+            // Name, either quoted or not
+            let (name_lo, name_hi) = if matches!(first_char, '"' | '\'') {
+                let Some(name_len) = rest[1..].find(first_char) else {
+                    self.report(ParseErrorKind::PugSyntax, self.pos, self.src.len());
+                    return;
+                };
+                self.pos += name_len + 2;
+                (self.pos - name_len - 1, self.pos - 1)
+            } else {
+                let name_len = scan_attribute_name(rest);
+                if name_len == 0 {
+                    self.report(ParseErrorKind::PugSyntax, self.pos, self.pos + 1);
+                    self.pos += first_char.len_utf8();
+                    continue;
+                }
+                self.pos += name_len;
+                (self.pos - name_len, self.pos)
+            };
+            let name = &self.src[name_lo..name_hi];
+
+            // Value
+            let rest = &self.src[self.pos..];
+            let after_whitespace = rest.trim_start();
+            let after_eq = after_whitespace
+                .strip_prefix('=')
+                .or_else(|| after_whitespace.strip_prefix("!="));
+
+            let (value, value_hi) = match after_eq {
+                Some(after_eq) => {
+                    let value_str = after_eq.trim_start();
+                    let value_lo = self.src.len() - value_str.len();
+                    let (value, value_len) = scan_attribute_value(value_str);
+                    if value_len == 0 {
+                        self.report(ParseErrorKind::PugSyntax, name_lo, value_lo);
+                    }
+                    (Some(value), value_lo + value_len)
+                }
+                None => (None, name_hi),
+            };
+            self.pos = value_hi;
+
+            // Unquoted `true`/`false` of static attributes are booleans
+            let is_directive = name.starts_with([':', '@', '#', '.']) || name.starts_with("v-");
+            let value = match value {
+                Some(AttributeValue::Unquoted("true")) if !is_directive => None,
+                Some(AttributeValue::Unquoted("false")) if !is_directive => continue,
+                Some(value) => Some(FervidAtom::from(value.into_string(is_directive))),
+                None => None,
+            };
+
+            out.push(Attribute {
+                span: self.span(name_lo, value_hi),
+                namespace: None,
+                prefix: None,
+                name: name.into(),
+                raw_name: None,
+                value,
+                raw_value: None,
+            });
+        }
+    }
+
+    /// Takes the lines of a text block (indented deeper than `indent`).
+    /// Returns the texts without the common indentation and their end positions.
+    fn take_block_text(&mut self, indent: usize) -> Vec<(&'s str, usize)> {
+        let mut lines = Vec::new();
+        let mut block_indent = None;
+
+        while let Some((line_start, line_indent)) = self.peek_line() {
+            if line_indent <= indent {
+                break;
+            }
+
+            let block_indent = *block_indent.get_or_insert(line_indent);
+            let line_end = self.line_end(line_start);
+            let text_start = line_start + line_indent.min(block_indent);
+            lines.push((&self.src[text_start..line_end], line_end));
+
+            self.pos = line_start;
+            self.advance_line();
+        }
+
+        lines
+    }
+
+    /// Skips the lines nested deeper than `indent`
+    fn skip_block(&mut self, indent: usize) {
+        while let Some((line_start, line_indent)) = self.peek_line() {
+            if line_indent <= indent {
+                break;
+            }
+            self.pos = line_start;
+            self.advance_line();
+        }
+    }
+
+    /// Finds the next non-blank line and returns its start and indentation
+    fn peek_line(&self) -> Option<(usize, usize)> {
+        let mut line_start = self.pos;
+
+        while line_start < self.src.len() {
+            let line_end = self.line_end(line_start);
+            let line = &self.src[line_start..line_end];
+            let content = line.trim_start_matches([' ', '\t']);
+            if !content.trim().is_empty() {
+                return Some((line_start, line.len() - content.len()));
+            }
+
+            line_start = line_end + 1;
+        }
+
+        None
+    }
+
+    /// Moves `self.pos` to the start of the next line
+    fn advance_line(&mut self) {
+        self.pos = match self.src[self.pos..].find('\n') {
+            Some(idx) => self.pos + idx + 1,
+            None => self.src.len(),
+        };
+    }
+
+    /// Position of the line end, excluding the trailing whitespace and `\r`
+    fn line_end(&self, from: usize) -> usize {
+        let line_end = self.src[from..]
+            .find('\n')
+            .map_or(self.src.len(), |idx| from + idx);
+        from + self.src[from..line_end].trim_end().len()
+    }
+
+    /// Separates consecutive texts by the newline preceding `line_start`
+    fn push_newline_before(&self, line_start: usize, out: &mut Vec<PugNode<'s>>) {
+        if let Some(newline_idx) = self.src[..line_start].rfind('\n') {
+            out.push(PugNode::Text {
+                raw: &self.src[newline_idx..newline_idx + 1],
+                span: self.span(newline_idx, newline_idx + 1),
+            });
+        }
+    }
+
+    fn span(&self, lo: usize, hi: usize) -> Span {
+        Span {
+            lo: BytePos(self.offset + lo as u32),
+            hi: BytePos(self.offset + hi as u32),
+        }
+    }
+
+    fn report(&mut self, kind: ParseErrorKind, lo: usize, hi: usize) {
+        let span = self.span(lo, hi);
+        self.errors.push(ParseError { kind, span });
+    }
+}
+
+enum AttributeValue<'s> {
+    /// Value without quotes, e.g. `foo` in `:class=foo`
+    Unquoted(&'s str),
+    /// Quoted value (without the quotes), e.g. `foo` in `class="foo"`
+    Quoted(&'s str, char),
+}
+
+impl AttributeValue<'_> {
+    /// Directive values are kept as-is, so that the expression spans match the source
+    fn into_string(self, is_directive: bool) -> String {
+        match self {
+            AttributeValue::Unquoted(value) => value.to_owned(),
+            // Template literals are kept as-is
+            AttributeValue::Quoted(value, '`') => value.to_owned(),
+            AttributeValue::Quoted(value, _) if is_directive => value.to_owned(),
+            AttributeValue::Quoted(value, quote) => {
+                // Pug values are JavaScript strings, unescape them
+                let mut result = String::with_capacity(value.len());
+                let mut chars = value.chars();
+                while let Some(c) = chars.next() {
+                    match (c, chars.clone().next()) {
+                        ('\\', Some(next)) if next == quote || next == '\\' => {
+                            result.push(next);
+                            chars.next();
+                        }
+                        _ => result.push(c),
+                    }
+                }
+                result
+            }
+        }
+    }
+}
+
+/// Name of a class or an id shorthand, e.g. `foo` in `.foo`
+fn shorthand_name(raw: &str) -> Option<&str> {
+    let len = raw
+        .find(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_')))
+        .unwrap_or(raw.len());
+
+    // Cannot start with a digit
+    if len == 0 || raw.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Some(&raw[..len])
+}
+
+/// Attribute name ends on whitespace, `,`, `)`, `=` or `!=`.
+/// Square brackets of dynamic arguments may contain anything, e.g. `:[foo ? 'a' : 'b']`
+fn scan_attribute_name(raw: &str) -> usize {
+    let mut bracket_depth = 0usize;
+
+    for (idx, c) in raw.char_indices() {
+        match c {
+            '[' => bracket_depth += 1,
+            ']' => bracket_depth = bracket_depth.saturating_sub(1),
+            _ if bracket_depth > 0 => {}
+            '=' | ',' | ')' => return idx,
+            '!' if raw[idx + 1..].starts_with('=') => return idx,
+            _ if c.is_whitespace() => return idx,
+            _ => {}
+        }
+    }
+
+    raw.len()
+}
+
+/// Returns the value and its length in the source (including the quotes).
+/// Unquoted values are JavaScript expressions which end on the top-level whitespace, `,` or `)`.
+fn scan_attribute_value(raw: &str) -> (AttributeValue, usize) {
+    if let Some(quote @ ('"' | '\'' | '`')) = raw.chars().next() {
+        let mut is_escaped = false;
+        for (idx, c) in raw.char_indices().skip(1) {
+            match c {
+                '\\' if !is_escaped => is_escaped = true,
+                _ if c == quote && !is_escaped => {
+                    return (AttributeValue::Quoted(&raw[1..idx], quote), idx + 1);
+                }
+                _ => is_escaped = false,
+            }
+        }
+
+        // Unterminated, take everything
+        return (AttributeValue::Quoted(&raw[1..], quote), raw.len());
+    }
+
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut is_escaped = false;
+
+    for (idx, c) in raw.char_indices() {
+        if let Some(q) = quote {
+            match c {
+                '\\' if !is_escaped => is_escaped = true,
+                _ if c == q && !is_escaped => quote = None,
+                _ => is_escaped = false,
+            }
+            continue;
+        }
+
+        match c {
+            '"' | '\'' | '`' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' if depth == 0 => return (AttributeValue::Unquoted(&raw[..idx]), idx),
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => return (AttributeValue::Unquoted(&raw[..idx]), idx),
+            _ if c.is_whitespace() && depth == 0 => {
+                return (AttributeValue::Unquoted(&raw[..idx]), idx)
+            }
+            _ => {}
+        }
+    }
+
+    (AttributeValue::Unquoted(raw), raw.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::{SfcTemplateBlock, StrOrExpr};
+    use swc_core::common::Spanned;
+
+    use super::*;
+
+    #[test]
+    fn it_parses_tags_and_nesting() {
+        let source = r#"<template lang="pug">
+div#app.container.fluid
+  h1.title Hello {{ name }}
+  ul
+    li(v-for="item in items" :key="item.id") {{ item.text }}
+  img(src="./logo.png", alt='Logo')/
+</template>"#;
+        let (template, errors) = parse_template(source);
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(1, template.roots.len());
+
+        let div = element(&template.roots[0]);
+        assert!(div.starting_tag.tag_name == "div");
+        assert_eq!(
+            vec![("class", "container fluid"), ("id", "app")],
+            regular_attributes(div)
+        );
+        assert_eq!(3, div.children.len());
+
+        // h1.title Hello {{ name }}
+        let h1 = element(&div.children[0]);
+        assert!(h1.starting_tag.tag_name == "h1");
+        assert_eq!(vec![("class", "title")], regular_attributes(h1));
+        assert!(matches!(&h1.children[0], Node::Text(text, _) if text == "Hello "));
+        let Node::Interpolation(interpolation) = &h1.children[1] else {
+            panic!("Expected interpolation")
+        };
+        assert_eq!(" name ", source_of(source, interpolation.span));
+
+        // li(v-for="item in items" :key="item.id")
+        let li = element(&element(&div.children[1]).children[0]);
+        let v_for = li
+            .starting_tag
+            .directives
+            .as_ref()
+            .and_then(|directives| directives.v_for.as_ref())
+            .expect("Should have v-for");
+        assert_eq!("item", source_of(source, v_for.itervar.span()));
+        assert_eq!("items", source_of(source, v_for.iterable.span()));
+        let Some(AttributeOrBinding::VBind(key)) = li.starting_tag.attributes.first() else {
+            panic!("Expected v-bind")
+        };
+        assert!(matches!(&key.argument, Some(StrOrExpr::Str(arg)) if arg == "key"));
+        assert_eq!("item.id", source_of(source, key.value.span()));
+
+        // img(src="./logo.png", alt='Logo')/
+        let img = element(&div.children[2]);
+        assert_eq!(
+            vec![("src", "./logo.png"), ("alt", "Logo")],
+            regular_attributes(img)
+        );
+        assert!(img.children.is_empty());
+
+        // Spans point to the Pug source
+        assert!(source_of(source, div.span).starts_with("div#app"));
+        assert!(source_of(source, div.span).ends_with("alt='Logo')/"));
+        assert_eq!("h1.title Hello {{ name }}", source_of(source, h1.span));
+    }
+
+    #[test]
+    fn it_parses_attributes() {
+        let source = r#"<template lang="pug">
+button.btn(
+  class="primary"
+  type="button"
+  title='It\'s'
+  disabled
+  hidden=false
+  @click.prevent="onClick($event, 'a b')"
+  :[dynamicKey]=value
+  :class="{ active: isActive, 'is-big': big }"
+  v-bind:title='"It\'s " + title'
+) Click
+</template>"#;
+        let (template, errors) = parse_template(source);
+        assert!(errors.is_empty(), "{errors:?}");
+
+        let button = element(&template.roots[0]);
+        assert_eq!(
+            vec![
+                ("class", "btn primary"),
+                ("type", "button"),
+                ("title", "It's"),
+                ("disabled", "")
+            ],
+            regular_attributes(button)
+        );
+
+        let bindings: Vec<&str> = button
+            .starting_tag
+            .attributes
+            .iter()
+            .filter_map(|attr| match attr {
+                AttributeOrBinding::VBind(v_bind) => Some(source_of(source, v_bind.value.span())),
+                AttributeOrBinding::VOn(v_on) => v_on
+                    .handler
+                    .as_ref()
+                    .map(|handler| source_of(source, handler.span())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                "onClick($event, 'a b')",
+                "value",
+                "{ active: isActive, 'is-big': big }",
+                r#""It\'s " + title"#
+            ],
+            bindings
+        );
+
+        let Some(Node::Text(text, _)) = button.children.first() else {
+            panic!("Expected text")
+        };
+        assert!(text == "Click");
+    }
+
+    #[test]
+    fn it_parses_text_and_comments() {
+        let source = r##"<template lang="pug">
+p
+  | Piped
+  | text
+pre.
+  block
+    text
+ul: li: a(href="#") Expanded
+// Buffered comment
+//- Unbuffered comment
+  which is nested
+p Inline <b>HTML</b> &amp; entities
+<div class="literal">
+  <span>HTML</span>
+</div>
+</template>"##;
+        let (template, errors) = parse_template(source);
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(6, template.roots.len());
+
+        // Piped text is separated by a newline
+        let p = element(&template.roots[0]);
+        assert_eq!(vec!["Piped", "\n", "text"], texts(p));
+
+        // Block text keeps the relative indentation
+        let pre = element(&template.roots[1]);
+        assert_eq!(vec!["block", "\n", "  text"], texts(pre));
+
+        // Block expansion
+        let ul = element(&template.roots[2]);
+        let li = element(&ul.children[0]);
+        let a = element(&li.children[0]);
+        assert!(ul.starting_tag.tag_name == "ul");
+        assert!(li.starting_tag.tag_name == "li");
+        assert_eq!(vec![("href", "#")], regular_attributes(a));
+        assert_eq!(vec!["Expanded"], texts(a));
+        assert_eq!(
+            r##"ul: li: a(href="#") Expanded"##,
+            source_of(source, ul.span)
+        );
+
+        // Only the buffered comment is kept
+        assert!(matches!(&template.roots[3], Node::Comment(c, _) if c == " Buffered comment"));
+
+        // HTML inside text
+        let p = element(&template.roots[4]);
+        assert!(matches!(&p.children[0], Node::Text(t, _) if t == "Inline "));
+        let b = element(&p.children[1]);
+        assert!(b.starting_tag.tag_name == "b");
+        assert_eq!("<b>HTML</b>", source_of(source, b.span));
+        assert!(matches!(&p.children[2], Node::Text(t, _) if t == " & entities"));
+
+        // Literal HTML
+        let div = element(&template.roots[5]);
+        assert_eq!(vec![("class", "literal")], regular_attributes(div));
+        assert!(source_of(source, div.span).starts_with("<div class=\"literal\">"));
+    }
+
+    #[test]
+    fn it_reports_unsupported_features_and_syntax_errors() {
+        let source = r#"<template lang="pug">
+div
+  if visible
+    p Hidden
+  each item in items
+    p= item
+  +mixin()
+  - const foo = 1
+  p(class="unclosed"
+</template>"#;
+        let (template, errors) = parse_template(source);
+
+        let unsupported: Vec<&str> = errors
+            .iter()
+            .filter(|e| matches!(e.kind, ParseErrorKind::PugUnsupportedFeature))
+            .map(|e| source_of(source, e.span))
+            .collect();
+        assert_eq!(
+            vec![
+                "if visible",
+                "each item in items",
+                "+mixin()",
+                "- const foo = 1"
+            ],
+            unsupported
+        );
+        assert!(errors.iter().any(
+            |e| matches!(e.kind, ParseErrorKind::PugSyntax) && source_of(source, e.span) == "("
+        ));
+
+        // Supported parts are still parsed
+        let div = element(&template.roots[0]);
+        assert_eq!(1, div.children.len());
+    }
+
+    fn parse_template(source: &str) -> (SfcTemplateBlock, Vec<ParseError>) {
+        let mut errors = Vec::new();
+        let mut parser = SfcParser::new(source, &mut errors);
+        let descriptor = parser.parse_sfc().expect("Should parse");
+        let template = descriptor.template.expect("Should have template");
+        assert!(template.lang == "pug");
+        (template, errors)
+    }
+
+    fn element(node: &Node) -> &ElementNode {
+        match node {
+            Node::Element(element) => element,
+            _ => panic!("Expected element, got {node:?}"),
+        }
+    }
+
+    fn regular_attributes(element: &ElementNode) -> Vec<(&str, &str)> {
+        element
+            .starting_tag
+            .attributes
+            .iter()
+            .filter_map(|attr| match attr {
+                AttributeOrBinding::RegularAttribute { name, value, .. } => {
+                    Some((name.as_str(), value.as_str()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn texts(element: &ElementNode) -> Vec<&str> {
+        element
+            .children
+            .iter()
+            .filter_map(|node| match node {
+                Node::Text(text, _) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn source_of(source: &str, span: Span) -> &str {
+        &source[span.lo.0 as usize - 1..span.hi.0 as usize - 1]
+    }
+}
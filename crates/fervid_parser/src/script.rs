@@ -1,12 +1,15 @@
 use fervid_core::{SfcScriptBlock, SfcScriptLang};
 use swc_core::{
-    common::Span,
+    common::{BytePos, Span, Spanned},
     ecma::ast::{Expr, Module, Pat},
 };
 use swc_ecma_parser::{lexer::Lexer, EsSyntax, Parser, StringInput, Syntax, TsSyntax};
 use swc_html_ast::{Child, Element};
 
-use crate::{error::ParseErrorKind, ParseError, SfcParser};
+use crate::{
+    error::{ErrorRecoveryMode, ParseErrorKind},
+    ParseError, SfcParser,
+};
 
 impl SfcParser<'_, '_, '_> {
     /// Parses the `<script>` and `<script setup>`, both in EcmaScript and TypeScript
@@ -86,8 +89,16 @@ impl SfcParser<'_, '_, '_> {
             return Ok(None);
         }
 
+        // Parse the raw content, because the HTML parser normalizes `\r\n`
+        // and the positions in the normalized text drift from the source
+        let span = script_content.span;
+        let raw = self
+            .input
+            .get((span.lo.0 as usize).saturating_sub(1)..(span.hi.0 as usize).saturating_sub(1))
+            .unwrap_or(&script_content.data);
+
         let module_content = self.parse_module(
-            &script_content.data,
+            raw,
             if matches!(lang, SfcScriptLang::Typescript) {
                 Syntax::Typescript(TsSyntax::default())
             } else {
@@ -126,7 +137,26 @@ impl SfcParser<'_, '_, '_> {
         self.errors
             .extend(parser.take_errors().into_iter().map(From::from));
 
-        parse_result.map_err(From::from)
+        match parse_result {
+            Ok(module) => Ok(module),
+            Err(e) => {
+                let error = ParseError::from(e);
+                if !matches!(
+                    self.error_recovery_mode,
+                    ErrorRecoveryMode::SoftRecoverUnsafe
+                ) {
+                    return Err(error);
+                }
+
+                match recover_module(raw, syntax, span, error.span.lo) {
+                    Some(module) => {
+                        self.report_error(error);
+                        Ok(module)
+                    }
+                    None => Err(error),
+                }
+            }
+        }
     }
 
     pub fn parse_expr(
@@ -150,7 +180,26 @@ impl SfcParser<'_, '_, '_> {
         self.errors
             .extend(parser.take_errors().into_iter().map(From::from));
 
-        parse_result.map_err(From::from)
+        match parse_result {
+            Ok(expr) => Ok(expr),
+            Err(e) => {
+                let error = ParseError::from(e);
+                if !matches!(
+                    self.error_recovery_mode,
+                    ErrorRecoveryMode::SoftRecoverUnsafe
+                ) {
+                    return Err(error);
+                }
+
+                match recover_expr(raw, syntax, span, error.span.lo) {
+                    Some(expr) => {
+                        self.report_error(error);
+                        Ok(expr)
+                    }
+                    None => Err(error),
+                }
+            }
+        }
     }
 
     pub fn parse_pat(&mut self, raw: &str, syntax: Syntax, span: Span) -> Result<Pat, ParseError> {
@@ -172,3 +221,127 @@ impl SfcParser<'_, '_, '_> {
         parse_result.map_err(From::from)
     }
 }
+
+/// How many times the recovery is attempted before giving up
+const MAX_RECOVERY_ATTEMPTS: usize = 16;
+
+/// Best-effort recovery of a broken module.
+/// Lines with errors are blanked out one by one until the rest parses,
+/// so that the positions of the remaining code are unchanged.
+fn recover_module(raw: &str, syntax: Syntax, span: Span, error_pos: BytePos) -> Option<Module> {
+    let mut source = raw.as_bytes().to_vec();
+    let mut error_idx = (error_pos.0.saturating_sub(span.lo.0) as usize).min(source.len());
+
+    for _ in 0..MAX_RECOVERY_ATTEMPTS {
+        // Errors at blank lines (e.g. unexpected end of input) are caused by the code above
+        let mut line_start = source[..error_idx]
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |idx| idx + 1);
+        while line_start > 0 && is_blank(&source[line_start..line_end(&source, line_start)]) {
+            line_start = source[..line_start - 1]
+                .iter()
+                .rposition(|&b| b == b'\n')
+                .map_or(0, |idx| idx + 1);
+        }
+
+        let line_hi = line_end(&source, line_start);
+        if is_blank(&source[line_start..line_hi]) {
+            return None;
+        }
+        source[line_start..line_hi].fill(b' ');
+
+        // Only ASCII newlines were kept, thus the source is still valid UTF-8
+        let attempt = std::str::from_utf8(&source).ok()?;
+        let lexer = Lexer::new(
+            syntax,
+            Default::default(),
+            StringInput::new(attempt, span.lo, span.hi),
+            None,
+        );
+        match Parser::new_from(lexer).parse_module() {
+            Ok(module) => return Some(module),
+            Err(e) => {
+                error_idx = (e.span().lo.0.saturating_sub(span.lo.0) as usize).min(source.len())
+            }
+        }
+    }
+
+    None
+}
+
+/// Best-effort recovery of a broken expression.
+/// The expression is cut at the error and the dangling operators are removed,
+/// e.g. `foo.bar +` becomes `foo.bar`.
+fn recover_expr(raw: &str, syntax: Syntax, span: Span, error_pos: BytePos) -> Option<Box<Expr>> {
+    let mut end = (error_pos.0.saturating_sub(span.lo.0) as usize).min(raw.len());
+
+    for _ in 0..MAX_RECOVERY_ATTEMPTS {
+        while !raw.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        let attempt = raw[..end].trim_end_matches(|c: char| {
+            c.is_whitespace()
+                || matches!(
+                    c,
+                    '.' | ','
+                        | ';'
+                        | ':'
+                        | '?'
+                        | '='
+                        | '+'
+                        | '-'
+                        | '*'
+                        | '/'
+                        | '%'
+                        | '&'
+                        | '|'
+                        | '^'
+                        | '!'
+                        | '~'
+                        | '<'
+                        | '>'
+                        | '('
+                        | '['
+                        | '{'
+                )
+        });
+        if attempt.trim().is_empty() {
+            return None;
+        }
+
+        let lexer = Lexer::new(
+            syntax,
+            Default::default(),
+            StringInput::new(attempt, span.lo, span.lo + BytePos(attempt.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        match parser.parse_expr() {
+            Ok(expr) if parser.take_errors().is_empty() => return Some(expr),
+            Ok(_) => end = attempt.len() - 1,
+            Err(e) => {
+                let error_idx = e.span().lo.0.saturating_sub(span.lo.0) as usize;
+                end = if error_idx < attempt.len() {
+                    error_idx
+                } else {
+                    attempt.len() - 1
+                };
+            }
+        }
+    }
+
+    None
+}
+
+fn line_end(source: &[u8], line_start: usize) -> usize {
+    source[line_start..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(source.len(), |idx| line_start + idx)
+}
+
+fn is_blank(line: &[u8]) -> bool {
+    line.iter().all(|b| b.is_ascii_whitespace())
+}
@@ -9,7 +9,7 @@ use swc_html_parser::{
 };
 
 use crate::{
-    error::{ParseError, ParseErrorKind},
+    error::{ErrorRecoveryMode, ParseError, ParseErrorKind},
     SfcParser,
 };
 
@@ -17,8 +17,11 @@ type SwcHtmlParserError = swc_html_parser::error::Error;
 
 impl SfcParser<'_, '_, '_> {
     /// Parses `self.input` as an SFC, producing an `SfcDescriptor`.
-    /// When `Err(ParseError)` is returned, that means unrecoverable error was discovered.
+    /// When `Err(ParseError)` is returned, that means unrecoverable error was discovered,
+    /// or any error was discovered in the strict `ErrorRecoveryMode`s.
     pub fn parse_sfc(&mut self) -> Result<SfcDescriptor, ParseError> {
+        let errors_start = self.errors.len();
+
         let parsed_html = self.parse_html_document_fragment().map_err(|e| {
             let kind = e.into_inner().1;
 
@@ -26,7 +29,7 @@ impl SfcParser<'_, '_, '_> {
                 kind: ParseErrorKind::InvalidHtml(Box::new(kind)),
                 span: Span {
                     lo: BytePos(1),
-                    hi: BytePos(self.input.len() as u32),
+                    hi: BytePos(self.input.len() as u32 + 1),
                 },
             }
         })?;
@@ -66,6 +69,11 @@ impl SfcParser<'_, '_, '_> {
 
                 sfc_descriptor.template = template_result;
             } else if tag_name.eq("script") {
+                let is_vapor = root_element
+                    .attributes
+                    .iter()
+                    .any(|attr| attr.name == "vapor");
+
                 let sfc_script_block = match self.parse_sfc_script_element(root_element) {
                     Ok(Some(v)) => v,
                     Ok(None) => continue,
@@ -75,6 +83,8 @@ impl SfcParser<'_, '_, '_> {
                     }
                 };
 
+                sfc_descriptor.vapor |= is_vapor;
+
                 if sfc_script_block.is_setup {
                     // Check if already present
                     if sfc_descriptor.script_setup.is_some() {
@@ -119,16 +129,47 @@ impl SfcParser<'_, '_, '_> {
             )
         });
 
+        // Strict modes fail on any error, the earliest one is returned
+        if matches!(
+            self.error_recovery_mode,
+            ErrorRecoveryMode::HardFailOnError | ErrorRecoveryMode::SoftReportAll
+        ) {
+            let earliest_error_idx = self.errors[errors_start..]
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, e)| e.span.lo)
+                .map(|(idx, _)| errors_start + idx);
+
+            if let Some(idx) = earliest_error_idx {
+                let error = self.errors.remove(idx);
+                if let ErrorRecoveryMode::HardFailOnError = self.error_recovery_mode {
+                    self.errors.truncate(errors_start);
+                }
+                return Err(error);
+            }
+        }
+
         Ok(sfc_descriptor)
     }
 
     /// Adapted from `swc_html_parser`
     #[inline]
     pub fn parse_html_document_fragment(&mut self) -> Result<DocumentFragment, SwcHtmlParserError> {
+        let input = self.input;
+        self.parse_html_fragment_at(input, BytePos(1))
+    }
+
+    /// Parses a part of the input as HTML.
+    /// `start_pos` is the position of `raw` in the input, so that the spans are relative to the whole input.
+    pub fn parse_html_fragment_at(
+        &mut self,
+        raw: &str,
+        start_pos: BytePos,
+    ) -> Result<DocumentFragment, SwcHtmlParserError> {
         let lexer = Lexer::new(StringInput::new(
-            self.input,
-            BytePos(1),
-            BytePos(self.input.len() as u32),
+            raw,
+            start_pos,
+            BytePos(start_pos.0 + raw.len() as u32),
         ));
 
         let parser_config = ParserConfig {
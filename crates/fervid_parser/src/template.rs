@@ -1,12 +1,16 @@
 use fervid_core::{
-    fervid_atom, is_html_tag, AttributeOrBinding, ElementNode, FervidAtom, Interpolation, Node,
-    PatchHints, SfcTemplateBlock, StartingTag, VueDirectives,
+    fervid_atom, AttributeOrBinding, FervidAtom, Interpolation, Node, SfcTemplateBlock,
+    StartingTag, VueDirectives,
 };
 use swc_core::common::{BytePos, Span};
 use swc_ecma_parser::{Syntax, TsSyntax};
-use swc_html_ast::{Child, Element, Text};
+use swc_html_ast::{Attribute, Element};
 
-use crate::SfcParser;
+use crate::{
+    error::{ErrorRecoveryMode, ParseError, ParseErrorKind},
+    html::decode_entities,
+    SfcParser,
+};
 
 impl SfcParser<'_, '_, '_> {
     pub fn parse_template_to_ir(&mut self, root_element: Element) -> Option<SfcTemplateBlock> {
@@ -37,7 +41,23 @@ impl SfcParser<'_, '_, '_> {
             })
             .unwrap_or_else(html_atom);
 
-        // Do not parse non-html templates
+        // Pug is parsed using its own frontend
+        if lang == "pug" || lang == "jade" {
+            let roots = match self
+                .use_rawtext_content(root_element.content.as_ref(), &root_element.children)
+            {
+                Some((_, content_span)) => self.parse_pug_template(content_span),
+                None => vec![],
+            };
+
+            return Some(SfcTemplateBlock {
+                lang,
+                roots,
+                span: root_element.span,
+            });
+        }
+
+        // Do not parse other non-html templates
         if lang != "html" {
             let roots = if let Some((content, content_span)) =
                 self.use_rawtext_content(root_element.content.as_ref(), &root_element.children)
@@ -54,49 +74,31 @@ impl SfcParser<'_, '_, '_> {
             });
         }
 
-        // <template> technically has a `content`
-        let children: Vec<Child> = root_element
-            .content
-            .map(|c| c.children)
-            .unwrap_or_else(|| root_element.children);
+        // Errors of the spec HTML parser do not apply to Vue templates
+        let span = root_element.span;
+        self.errors.retain(|e| !span.contains(e.span));
 
         Some(SfcTemplateBlock {
             lang,
-            roots: self.process_element_children(children),
-            span: root_element.span,
+            roots: self.parse_html_template(span),
+            span,
         })
     }
 
-    fn process_element(&mut self, element: Element) -> Node {
-        let children: Vec<Child> = element
-            .content
-            .map(|c| c.children)
-            .unwrap_or_else(|| element.children);
-
-        // For finer-grained `Span`s and components
-        // TODO Add `tag_name_span` to `StartingTag`
-        let raw_idx_start = element.span.lo.0 as usize;
-        let raw_idx_end = raw_idx_start + element.tag_name.len();
-
-        // Use raw names for custom HTML elements.
-        // Otherwise SWC renames `CustomComponent` to `customcomponent`
-        let tag_name = if is_html_tag(&element.tag_name) {
-            element.tag_name
-        } else {
-            let raw_name = &self.input[raw_idx_start..raw_idx_end];
-            raw_name.into()
-        };
-
-        // Save old `v-pre` (restored at the end of the function)
-        let old_is_pre = self.is_pre;
-
+    /// Processes the attributes and enters the `v-pre` mode when it is discovered.
+    /// Callers are responsible for restoring `self.is_pre` after processing the children.
+    pub(crate) fn create_starting_tag(
+        &mut self,
+        tag_name: FervidAtom,
+        raw_attributes: Vec<Attribute>,
+    ) -> StartingTag {
         // Pre-allocate with excess, assuming all the attributes are not directives
-        let mut attributes: Vec<AttributeOrBinding> = Vec::with_capacity(element.attributes.len());
+        let mut attributes: Vec<AttributeOrBinding> = Vec::with_capacity(raw_attributes.len());
         let mut directives: Option<Box<VueDirectives>> = None;
 
         // Process the attributes
         let has_v_pre =
-            self.process_element_attributes(element.attributes, &mut attributes, &mut directives);
+            self.process_element_attributes(raw_attributes, &mut attributes, &mut directives);
 
         // Add an indicator directive for `v-pre`
         if has_v_pre {
@@ -105,52 +107,24 @@ impl SfcParser<'_, '_, '_> {
             self.is_pre = true;
         }
 
-        let starting_tag = StartingTag {
+        StartingTag {
             tag_name,
             attributes,
             directives,
-        };
-
-        let result = Node::Element(ElementNode {
-            kind: fervid_core::ElementKind::Element,
-            starting_tag,
-            children: self.process_element_children(children),
-            template_scope: 0,
-            patch_hints: PatchHints::default(),
-            span: element.span,
-        });
-
-        self.is_pre = old_is_pre;
-        result
-    }
-
-    fn process_element_children(&mut self, children: Vec<Child>) -> Vec<Node> {
-        let mut out = Vec::with_capacity(children.len());
-
-        for child in children {
-            match child {
-                Child::DocumentType(_) => unimplemented!("Doctype is unsupported"),
-                Child::Element(element) => out.push(self.process_element(element)),
-                Child::Text(text) => self.process_text(text, &mut out),
-                Child::Comment(comment) => out.push(Node::Comment(comment.data, comment.span)),
-            }
         }
-
-        out
     }
 
-    /// Separates a raw text into `Node::Text`s and `Node::Interpolation`s
-    fn process_text(&mut self, text: Text, out: &mut Vec<Node>) {
+    /// Separates a raw text into `Node::Text`s and `Node::Interpolation`s.
+    /// Character references are decoded in the texts, but not in the interpolations.
+    pub(crate) fn process_text(&mut self, raw: &str, span: Span, out: &mut Vec<Node>) {
         // `v-pre` logic
         if self.is_pre {
-            out.push(Node::Text(text.data, text.span));
+            out.push(Node::Text(FervidAtom::from(decode_entities(raw)), span));
             return;
         }
 
         let interpolation_start_pat = self.interpolation_start_pat;
         let interpolation_end_pat = self.interpolation_end_pat;
-        let Text { span, data, .. } = text;
-        let raw: &str = &data;
         let interpolation_start_pat_len = interpolation_start_pat.len();
         let interpolation_end_pat_len = interpolation_end_pat.len();
 
@@ -161,17 +135,32 @@ impl SfcParser<'_, '_, '_> {
 
         // Find interpolation start - `{{` by default
         for (match_idx, _) in raw.match_indices(interpolation_start_pat) {
+            // Skip the matches inside of the previous interpolation
+            if match_idx < text_start_idx {
+                continue;
+            }
+
             let interpolation_start_idx = match_idx + interpolation_start_pat_len;
 
             // Find interpolation end - `}}` by default
-            let Some(interpolation_end_idx) =
-                raw[interpolation_start_idx..].find(interpolation_end_pat)
-            else {
-                continue;
-            };
-
-            // Offset, because we did offset while `find`ing previously
-            let interpolation_end_idx = interpolation_end_idx + interpolation_start_idx;
+            let interpolation_end_idx =
+                match raw[interpolation_start_idx..].find(interpolation_end_pat) {
+                    // Offset, because we did offset while `find`ing previously
+                    Some(idx) => idx + interpolation_start_idx,
+                    None => {
+                        self.report_error(ParseError {
+                            kind: ParseErrorKind::MissingInterpolationEnd,
+                            span: Span::new(BytePos(span.lo.0 + match_idx as u32), span.hi),
+                        });
+
+                        // Most likely the interpolation is being typed, thus the rest of the text is taken
+                        if let ErrorRecoveryMode::SoftRecoverUnsafe = self.error_recovery_mode {
+                            raw.len()
+                        } else {
+                            break;
+                        }
+                    }
+                };
 
             // Add any previous text
             if text_start_idx < match_idx {
@@ -182,7 +171,10 @@ impl SfcParser<'_, '_, '_> {
                     hi: BytePos(offset + text.len() as u32),
                 };
 
-                out.push(Node::Text(FervidAtom::from(text), text_span));
+                out.push(Node::Text(
+                    FervidAtom::from(decode_entities(text)),
+                    text_span,
+                ));
             }
             text_start_idx = interpolation_end_idx + interpolation_end_pat_len;
 
@@ -215,7 +207,7 @@ impl SfcParser<'_, '_, '_> {
         if text_start_idx < raw.len() {
             let text_span = Span::new(BytePos(span.lo.0 + text_start_idx as u32), span.hi);
             out.push(Node::Text(
-                FervidAtom::from(&raw[text_start_idx..]),
+                FervidAtom::from(decode_entities(&raw[text_start_idx..])),
                 text_span,
             ));
         }
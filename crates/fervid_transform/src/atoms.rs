@@ -17,6 +17,9 @@ lazy_static! {
     pub static ref EXPOSE: FervidAtom = fervid_atom!("expose");
     pub static ref NAME: FervidAtom = fervid_atom!("name");
     pub static ref DIRECTIVES: FervidAtom = fervid_atom!("directives");
+    pub static ref MIXINS: FervidAtom = fervid_atom!("mixins");
+    pub static ref EXTENDS: FervidAtom = fervid_atom!("extends");
+    pub static ref DEFINE_COMPONENT: FervidAtom = fervid_atom!("defineComponent");
 
     // Composition API atoms
     // pub static ref COMPUTED: FervidAtom = fervid_atom!("computed");
@@ -0,0 +1,329 @@
+//! Compilation of the custom blocks, e.g. `<i18n>`, `<docs>` or `<route>`.
+//!
+//! A [`CustomBlockHandler`] is registered for a tag name in [`CustomBlockHandlers`]
+//! and turns the content of the block into an ES module.
+//! The default export of that module is a function which receives the component,
+//! the same contract as the custom block loaders of `@vitejs/plugin-vue` and `vue-loader`:
+//!
+//! ```js
+//! export default function (Component) {
+//!   Component.__docs = "..."
+//! }
+//! ```
+//!
+//! The modules are then inlined by the codegen and applied to the exported component.
+//! Blocks without a handler are left as-is, so that a bundler could process them.
+
+use std::sync::Arc;
+
+use fervid_core::{block_content_span, FervidAtom, SfcCustomBlock};
+use fxhash::FxHashMap as HashMap;
+use swc_core::{
+    common::{BytePos, Span, DUMMY_SP},
+    ecma::{
+        ast::{EsVersion, Module},
+        visit::{VisitMut, VisitMutWith},
+    },
+};
+use swc_ecma_parser::{lexer::Lexer, EsSyntax, Parser, StringInput, Syntax};
+
+use crate::error::{CustomBlockError, CustomBlockErrorKind, TransformError};
+
+pub use i18n::I18nBlockHandler;
+
+mod i18n;
+mod yaml;
+
+/// Compiles the content of a custom block into an ES module.
+pub trait CustomBlockHandler: Send + Sync {
+    /// Returns the code of the module, which default-exports a function
+    /// accepting the component, e.g. `export default (Component) => { /*...*/ }`.
+    /// Return `None` to leave the block uncompiled, e.g. after reporting an error.
+    fn transform(&self, block: &SfcCustomBlock, ctx: &mut CustomBlockContext) -> Option<String>;
+}
+
+/// Handlers of the custom blocks by the tag name, e.g. `i18n` for `<i18n>`
+#[derive(Clone, Default)]
+pub struct CustomBlockHandlers {
+    pub handlers: HashMap<FervidAtom, Arc<dyn CustomBlockHandler>>,
+}
+
+/// What a handler has access to when transforming a block
+pub struct CustomBlockContext<'c> {
+    /// Name of the SFC file
+    pub filename: &'c str,
+    /// Span of the block content, without the starting and ending tags
+    pub content_span: Span,
+    errors: &'c mut Vec<TransformError>,
+}
+
+impl CustomBlockHandlers {
+    /// Handlers with the built-in `<i18n>` support, see [`I18nBlockHandler`]
+    pub fn with_i18n() -> Self {
+        let mut handlers = Self::default();
+        handlers.register("i18n", I18nBlockHandler);
+        handlers
+    }
+
+    /// Registers a handler for the tag name, replacing the previous one
+    pub fn register(&mut self, tag_name: &str, handler: impl CustomBlockHandler + 'static) {
+        self.handlers
+            .insert(FervidAtom::from(tag_name), Arc::new(handler));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+}
+
+impl std::fmt::Debug for CustomBlockHandlers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomBlockHandlers")
+            .field("handlers", &self.handlers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl CustomBlockContext<'_> {
+    /// Reports an error of the handler
+    pub fn report_error(&mut self, span: Span, message: impl Into<String>) {
+        self.errors
+            .push(TransformError::CustomBlockError(CustomBlockError {
+                span,
+                kind: CustomBlockErrorKind::Handler(message.into()),
+            }));
+    }
+
+    /// Span of the content located at `offset` bytes from its start.
+    /// The span is empty and points to the end when `offset` is out of bounds.
+    pub fn content_span_at(&self, offset: usize) -> Span {
+        let pos = (self.content_span.lo + BytePos(offset as u32)).min(self.content_span.hi);
+        Span::new(pos, pos)
+    }
+}
+
+/// Compiles the custom blocks which have a handler.
+/// Returns the modules in the order of the blocks, to be applied by the codegen.
+pub fn transform_custom_blocks(
+    custom_blocks: &[SfcCustomBlock],
+    handlers: &CustomBlockHandlers,
+    filename: &str,
+    source: &str,
+    errors: &mut Vec<TransformError>,
+) -> Vec<Module> {
+    if handlers.is_empty() {
+        return Vec::new();
+    }
+
+    let mut modules = Vec::new();
+    for block in custom_blocks {
+        let Some(handler) = handlers.handlers.get(&block.starting_tag.tag_name) else {
+            continue;
+        };
+
+        let mut ctx = CustomBlockContext {
+            filename,
+            content_span: block_content_span(source, block.span).unwrap_or(block.span),
+            errors,
+        };
+        let Some(code) = handler.transform(block, &mut ctx) else {
+            continue;
+        };
+
+        match parse_block_module(&code) {
+            Ok(module) => modules.push(module),
+            Err(message) => errors.push(TransformError::CustomBlockError(CustomBlockError {
+                span: block.span,
+                kind: CustomBlockErrorKind::InvalidModule(message),
+            })),
+        }
+    }
+
+    modules
+}
+
+/// Parses the code generated by a handler.
+/// The spans are dropped, because they do not point into the SFC.
+fn parse_block_module(code: &str) -> Result<Module, String> {
+    let lexer = Lexer::new(
+        Syntax::Es(EsSyntax::default()),
+        EsVersion::EsNext,
+        StringInput::new(code, BytePos(1), BytePos(1 + code.len() as u32)),
+        None,
+    );
+
+    let mut parser = Parser::new_from(lexer);
+    let mut module = parser
+        .parse_module()
+        .map_err(|e| e.kind().msg().into_owned())?;
+
+    if let Some(e) = parser.take_errors().into_iter().next() {
+        return Err(e.kind().msg().into_owned());
+    }
+
+    module.visit_mut_with(&mut DropSpans);
+    Ok(module)
+}
+
+struct DropSpans;
+
+impl VisitMut for DropSpans {
+    fn visit_mut_span(&mut self, span: &mut Span) {
+        *span = DUMMY_SP;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::{fervid_atom, AttributeOrBinding, StartingTag};
+    use swc_core::common::Spanned;
+
+    use crate::test_utils::to_str;
+
+    use super::*;
+
+    struct Docs;
+
+    impl CustomBlockHandler for Docs {
+        fn transform(
+            &self,
+            block: &SfcCustomBlock,
+            ctx: &mut CustomBlockContext,
+        ) -> Option<String> {
+            if block.content.trim().is_empty() {
+                ctx.report_error(ctx.content_span, "empty docs");
+                return None;
+            }
+
+            Some(format!(
+                "export default (Component) => {{ Component.__docs = {:?} }}",
+                block.content.trim()
+            ))
+        }
+    }
+
+    fn block(source: &str, tag_name: &str, lang: Option<&str>) -> SfcCustomBlock {
+        let start = source.find('>').unwrap() + 1;
+        let end = source.rfind("</").unwrap();
+        SfcCustomBlock {
+            starting_tag: StartingTag {
+                tag_name: FervidAtom::from(tag_name),
+                attributes: lang
+                    .map(|lang| AttributeOrBinding::RegularAttribute {
+                        name: fervid_atom!("lang"),
+                        value: FervidAtom::from(lang),
+                        span: DUMMY_SP,
+                    })
+                    .into_iter()
+                    .collect(),
+                directives: None,
+            },
+            content: FervidAtom::from(&source[start..end]),
+            span: Span::new(BytePos(1), BytePos(1 + source.len() as u32)),
+        }
+    }
+
+    fn transform(
+        source: &str,
+        block: SfcCustomBlock,
+        handlers: &CustomBlockHandlers,
+    ) -> (Vec<String>, Vec<TransformError>) {
+        let mut errors = Vec::new();
+        let modules = transform_custom_blocks(&[block], handlers, "test.vue", source, &mut errors);
+        (modules.iter().map(to_str).collect(), errors)
+    }
+
+    #[test]
+    fn it_compiles_blocks_with_a_handler() {
+        let mut handlers = CustomBlockHandlers::default();
+        handlers.register("docs", Docs);
+
+        let source = "<docs>\n# Hello\n</docs>";
+        let (modules, errors) = transform(source, block(source, "docs", None), &handlers);
+        assert!(errors.is_empty());
+        assert_eq!(
+            modules,
+            vec![r##"export default Component=>{Component.__docs="# Hello";};"##]
+        );
+
+        // Other blocks are left as-is
+        let source = "<route>{}</route>";
+        let (modules, errors) = transform(source, block(source, "route", None), &handlers);
+        assert!(errors.is_empty());
+        assert!(modules.is_empty());
+    }
+
+    #[test]
+    fn it_reports_handler_errors() {
+        let mut handlers = CustomBlockHandlers::default();
+        handlers.register("docs", Docs);
+
+        let source = "<docs>  </docs>";
+        let (modules, errors) = transform(source, block(source, "docs", None), &handlers);
+        assert!(modules.is_empty());
+        assert_eq!(errors.len(), 1);
+        let TransformError::CustomBlockError(ref error) = errors[0] else {
+            panic!("Expected a custom block error");
+        };
+        assert!(matches!(error.kind, CustomBlockErrorKind::Handler(_)));
+        assert_eq!(error.span, Span::new(BytePos(7), BytePos(9)));
+    }
+
+    #[test]
+    fn it_compiles_i18n_json() {
+        let source = r#"<i18n>
+{ "en": { "hello": "Hello!" }, "ja": { "hello": "こんにちは！" } }
+</i18n>"#;
+        let (modules, errors) = transform(
+            source,
+            block(source, "i18n", None),
+            &CustomBlockHandlers::with_i18n(),
+        );
+        assert!(errors.is_empty());
+        assert_eq!(
+            modules,
+            vec![
+                r#"export default function(Component){const _Component=Component;_Component.__i18n=_Component.__i18n||[];_Component.__i18n.push({"locale":"","resource":{"en":{"hello":"Hello!"},"ja":{"hello":"こんにちは！"}}});}"#
+            ]
+        );
+    }
+
+    #[test]
+    fn it_compiles_i18n_yaml() {
+        let source = r#"<i18n lang="yaml">
+en:
+  hello: Hello!   # greeting
+  items:
+    - first
+    - 'second'
+ja:
+  hello: "こんにちは！"
+</i18n>"#;
+        let (modules, errors) = transform(
+            source,
+            block(source, "i18n", Some("yaml")),
+            &CustomBlockHandlers::with_i18n(),
+        );
+        assert!(errors.is_empty());
+        assert_eq!(
+            modules,
+            vec![
+                r#"export default function(Component){const _Component=Component;_Component.__i18n=_Component.__i18n||[];_Component.__i18n.push({"locale":"","resource":{"en":{"hello":"Hello!","items":["first","second"]},"ja":{"hello":"こんにちは！"}}});}"#
+            ]
+        );
+    }
+
+    #[test]
+    fn it_reports_i18n_errors_at_the_content() {
+        let source = "<i18n>\n{ \"en\": { \"hello\" } }\n</i18n>";
+        let (modules, errors) = transform(
+            source,
+            block(source, "i18n", None),
+            &CustomBlockHandlers::with_i18n(),
+        );
+        assert!(modules.is_empty());
+        assert_eq!(errors.len(), 1);
+        let lo = errors[0].span().lo;
+        assert_eq!(&source[lo.0 as usize - 1..], "} }\n</i18n>");
+    }
+}
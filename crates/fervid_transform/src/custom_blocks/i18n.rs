@@ -0,0 +1,115 @@
+use fervid_core::{AttributeOrBinding, SfcCustomBlock};
+use serde_json::Value;
+
+use super::{yaml::parse_yaml, CustomBlockContext, CustomBlockHandler};
+
+/// Built-in handler of `<i18n>` blocks, compatible with `vue-i18n`.
+///
+/// The block content is a JSON (default) or a YAML (`lang="yaml"`) object of locale messages,
+/// which is pushed to `Component.__i18n`, or to `Component.__i18nGlobal` with the `global` attribute.
+/// The `locale` attribute scopes the messages to a single locale,
+/// and `src` imports the messages instead of using the block content.
+pub struct I18nBlockHandler;
+
+impl CustomBlockHandler for I18nBlockHandler {
+    fn transform(&self, block: &SfcCustomBlock, ctx: &mut CustomBlockContext) -> Option<String> {
+        let lang = get_attribute(block, "lang").unwrap_or("json");
+        let locale = get_attribute(block, "locale").unwrap_or_default();
+        let target = if get_attribute(block, "global").is_some() {
+            "__i18nGlobal"
+        } else {
+            "__i18n"
+        };
+
+        // External messages are resolved by the bundler
+        let (import, resource) = if let Some(src) = get_attribute(block, "src") {
+            (
+                format!("import resource from {};", Value::from(src)),
+                String::from("resource"),
+            )
+        } else {
+            let parsed = match lang {
+                "json" => parse_json(&block.content),
+                "yaml" | "yml" => parse_yaml(&block.content),
+                _ => {
+                    ctx.report_error(
+                        ctx.content_span,
+                        format!("Unsupported <i18n> lang \"{lang}\", expected json or yaml"),
+                    );
+                    return None;
+                }
+            };
+
+            match parsed {
+                // Empty block still registers the locale
+                Ok(Value::Null) => (String::new(), String::from("{}")),
+                Ok(resource) => (String::new(), resource.to_string()),
+                Err(e) => {
+                    let span = ctx.content_span_at(e.offset);
+                    ctx.report_error(span, format!("Invalid <i18n> {lang}: {}", e.message));
+                    return None;
+                }
+            }
+        };
+
+        Some(format!(
+            "{import}export default function (Component) {{\
+                const _Component = Component;\
+                _Component.{target} = _Component.{target} || [];\
+                _Component.{target}.push({{ \"locale\": {}, \"resource\": {resource} }});\
+            }}",
+            Value::from(locale)
+        ))
+    }
+}
+
+/// Error of parsing the block content, `offset` is in bytes from the start of the content
+pub(super) struct ContentError {
+    pub offset: usize,
+    pub message: String,
+}
+
+fn parse_json(content: &str) -> Result<Value, ContentError> {
+    if content.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+
+    serde_json::from_str(content).map_err(|e| {
+        // Lines and columns are 1-based, columns are in characters
+        let line_start: usize = content
+            .split_inclusive('\n')
+            .take(e.line().saturating_sub(1))
+            .map(str::len)
+            .sum();
+        let column_offset: usize = content[line_start..]
+            .chars()
+            .take(e.column().saturating_sub(1))
+            .map(char::len_utf8)
+            .sum();
+
+        // Message contains the position as well, which is redundant with the span
+        let message = e.to_string();
+        let message = match message.rfind(" at line ") {
+            Some(idx) => message[..idx].to_string(),
+            None => message,
+        };
+
+        ContentError {
+            offset: line_start + column_offset,
+            message,
+        }
+    })
+}
+
+fn get_attribute<'b>(block: &'b SfcCustomBlock, attr_name: &str) -> Option<&'b str> {
+    block
+        .starting_tag
+        .attributes
+        .iter()
+        .find_map(|attr| match attr {
+            AttributeOrBinding::RegularAttribute { name, value, .. } if name == attr_name => {
+                Some(value.as_str())
+            }
+            _ => None,
+        })
+}
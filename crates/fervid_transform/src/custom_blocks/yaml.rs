@@ -0,0 +1,591 @@
+//! Parser of the YAML subset used for locale messages:
+//! block mappings and sequences, plain and quoted scalars, `|` and `>` block scalars and comments.
+//! Flow collections, anchors, aliases, tags and multi-line flow scalars are reported as errors.
+
+use serde_json::{Map, Number, Value};
+
+use super::i18n::ContentError;
+
+#[derive(Clone, Copy)]
+struct Line<'s> {
+    /// Number of leading spaces
+    indent: usize,
+    /// Line without the indentation and the line break
+    text: &'s str,
+    /// Byte offset of `text` in the source
+    offset: usize,
+}
+
+struct YamlParser<'s> {
+    source: &'s str,
+    lines: Vec<Line<'s>>,
+    pos: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Chomping {
+    Clip,
+    Strip,
+    Keep,
+}
+
+pub(super) fn parse_yaml(source: &str) -> Result<Value, ContentError> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for raw in source.split_inclusive('\n') {
+        let line = raw.trim_end_matches(['\n', '\r']).trim_end();
+        let text = line.trim_start_matches(' ');
+        let indent = line.len() - text.len();
+
+        if text.starts_with('\t') {
+            return Err(error(
+                offset + indent,
+                "tabs are not allowed in the indentation",
+            ));
+        }
+
+        // Document markers and directives do not contribute to the content
+        let is_marker = indent == 0
+            && (text == "---"
+                || text == "..."
+                || text.starts_with("--- ")
+                || text.starts_with('%'));
+
+        lines.push(Line {
+            indent,
+            text: if is_marker { "" } else { text },
+            offset: offset + indent,
+        });
+        offset += raw.len();
+    }
+
+    let mut parser = YamlParser {
+        source,
+        lines,
+        pos: 0,
+    };
+
+    let Some(first) = parser.peek() else {
+        return Ok(Value::Null);
+    };
+    let value = parser.parse_node(first.indent)?;
+
+    if let Some(line) = parser.peek() {
+        return Err(error(line.offset, "unexpected content"));
+    }
+
+    Ok(value)
+}
+
+impl<'s> YamlParser<'s> {
+    /// Next line which is neither blank nor a comment
+    fn peek(&mut self) -> Option<Line<'s>> {
+        while let Some(line) = self.lines.get(self.pos) {
+            if !line.text.is_empty() && !line.text.starts_with('#') {
+                return Some(*line);
+            }
+            self.pos += 1;
+        }
+        None
+    }
+
+    /// Parses the collection or the scalar starting at the next line
+    fn parse_node(&mut self, indent: usize) -> Result<Value, ContentError> {
+        let Some(line) = self.peek() else {
+            return Ok(Value::Null);
+        };
+
+        if is_sequence_item(line.text) {
+            return self.parse_sequence(indent);
+        }
+        if find_mapping_colon(line.text).is_some() {
+            return self.parse_mapping(indent);
+        }
+
+        self.pos += 1;
+        parse_scalar(line.text, line.offset)
+    }
+
+    fn parse_mapping(&mut self, indent: usize) -> Result<Value, ContentError> {
+        let mut map = Map::new();
+
+        while let Some(line) = self.peek() {
+            if line.indent < indent || (line.indent == indent && is_sequence_item(line.text)) {
+                break;
+            }
+            if line.indent > indent {
+                return Err(error(line.offset, "unexpected indentation"));
+            }
+
+            let Some(colon) = find_mapping_colon(line.text) else {
+                return Err(error(line.offset, "expected a `key: value` pair"));
+            };
+            let key = parse_key(&line.text[..colon], line.offset)?;
+            if map.contains_key(&key) {
+                return Err(error(line.offset, format!("duplicate key \"{key}\"")));
+            }
+
+            let rest = &line.text[colon + 1..];
+            let value_text = rest.trim_start();
+            let value_offset = line.offset + colon + 1 + (rest.len() - value_text.len());
+
+            self.pos += 1;
+            let value = if value_text.is_empty() || value_text.starts_with('#') {
+                // Nested collection, sequences are allowed on the same indentation as the key
+                match self.peek() {
+                    Some(next) if next.indent > indent => self.parse_node(next.indent)?,
+                    Some(next) if next.indent == indent && is_sequence_item(next.text) => {
+                        self.parse_sequence(indent)?
+                    }
+                    _ => Value::Null,
+                }
+            } else {
+                self.parse_inline_value(value_text, value_offset, indent)?
+            };
+
+            map.insert(key, value);
+        }
+
+        Ok(Value::Object(map))
+    }
+
+    fn parse_sequence(&mut self, indent: usize) -> Result<Value, ContentError> {
+        let mut items = Vec::new();
+
+        while let Some(line) = self.peek() {
+            if line.indent > indent {
+                return Err(error(line.offset, "unexpected indentation"));
+            }
+            if line.indent < indent || !is_sequence_item(line.text) {
+                break;
+            }
+
+            let rest = line.text[1..].trim_start();
+            let rest_start = line.text.len() - rest.len();
+            let rest_offset = line.offset + rest_start;
+
+            if rest.is_empty() || rest.starts_with('#') {
+                self.pos += 1;
+                items.push(match self.peek() {
+                    Some(next) if next.indent > indent => self.parse_node(next.indent)?,
+                    _ => Value::Null,
+                });
+            } else if is_sequence_item(rest) || find_mapping_colon(rest).is_some() {
+                // Compact collection, e.g. `- key: value`, continues on the following lines
+                // with the same indentation as its first entry
+                self.lines[self.pos] = Line {
+                    indent: indent + rest_start,
+                    text: rest,
+                    offset: rest_offset,
+                };
+                items.push(self.parse_node(indent + rest_start)?);
+            } else {
+                self.pos += 1;
+                items.push(self.parse_inline_value(rest, rest_offset, indent)?);
+            }
+        }
+
+        Ok(Value::Array(items))
+    }
+
+    /// Parses the value after `key:` or `- ` when it is on the same line
+    fn parse_inline_value(
+        &mut self,
+        text: &str,
+        offset: usize,
+        parent_indent: usize,
+    ) -> Result<Value, ContentError> {
+        match text.as_bytes()[0] {
+            b'|' => self.parse_block_scalar(&text[1..], offset, parent_indent, true),
+            b'>' => self.parse_block_scalar(&text[1..], offset, parent_indent, false),
+            _ => parse_scalar(text, offset),
+        }
+    }
+
+    /// Parses `|` (literal) and `>` (folded) block scalars.
+    /// `header` is the rest of the line after the indicator.
+    fn parse_block_scalar(
+        &mut self,
+        header: &str,
+        offset: usize,
+        parent_indent: usize,
+        is_literal: bool,
+    ) -> Result<Value, ContentError> {
+        let chomping = match strip_comment(header) {
+            "" => Chomping::Clip,
+            "-" => Chomping::Strip,
+            "+" => Chomping::Keep,
+            _ => {
+                return Err(error(
+                    offset,
+                    "only `-` and `+` block scalar indicators are supported",
+                ))
+            }
+        };
+
+        let mut content_lines: Vec<&str> = Vec::new();
+        let mut block_indent = None;
+        while let Some(line) = self.lines.get(self.pos) {
+            if line.text.is_empty() {
+                content_lines.push("");
+                self.pos += 1;
+                continue;
+            }
+            if line.indent <= parent_indent {
+                break;
+            }
+
+            // Indentation of the first line is stripped, the rest is kept
+            let block_indent = *block_indent.get_or_insert(line.indent);
+            if line.indent < block_indent {
+                return Err(error(line.offset, "unexpected indentation"));
+            }
+            let lo = line.offset - (line.indent - block_indent);
+            content_lines.push(&self.source[lo..line.offset + line.text.len()]);
+            self.pos += 1;
+        }
+
+        let trailing_blank = content_lines
+            .iter()
+            .rev()
+            .take_while(|line| line.is_empty())
+            .count();
+        let lines = &content_lines[..content_lines.len() - trailing_blank];
+
+        let mut value = if is_literal {
+            lines.join("\n")
+        } else {
+            fold_lines(lines)
+        };
+
+        if !lines.is_empty() {
+            match chomping {
+                Chomping::Clip => value.push('\n'),
+                Chomping::Strip => {}
+                Chomping::Keep => value.push_str(&"\n".repeat(trailing_blank + 1)),
+            }
+        }
+
+        Ok(Value::String(value))
+    }
+}
+
+/// Folded block scalars join the lines with spaces, blank lines become line breaks
+fn fold_lines(lines: &[&str]) -> String {
+    let mut out = String::new();
+    let mut is_prev_text = false;
+    for line in lines {
+        if line.is_empty() {
+            out.push('\n');
+            is_prev_text = false;
+        } else {
+            if is_prev_text {
+                out.push(' ');
+            }
+            out.push_str(line);
+            is_prev_text = true;
+        }
+    }
+    out
+}
+
+fn parse_key(raw: &str, offset: usize) -> Result<String, ContentError> {
+    let raw = raw.trim_end();
+    match raw.as_bytes().first() {
+        Some(b'"') => parse_double_quoted(raw, offset).map(|(key, _)| key),
+        Some(b'\'') => parse_single_quoted(raw, offset).map(|(key, _)| key),
+        Some(b'?' | b'&' | b'*' | b'!') => Err(error(
+            offset,
+            "complex keys, anchors, aliases and tags are not supported",
+        )),
+        _ => Ok(raw.to_string()),
+    }
+}
+
+fn parse_scalar(text: &str, offset: usize) -> Result<Value, ContentError> {
+    let (value, end) = match text.as_bytes()[0] {
+        b'"' => parse_double_quoted(text, offset)?,
+        b'\'' => parse_single_quoted(text, offset)?,
+        b'[' | b'{' => return Err(error(offset, "flow collections are not supported")),
+        b'&' | b'*' | b'!' => {
+            return Err(error(offset, "anchors, aliases and tags are not supported"))
+        }
+        b'|' | b'>' => {
+            return Err(error(
+                offset,
+                "block scalars are only supported as values of collections",
+            ))
+        }
+        _ => return Ok(plain_scalar(strip_comment(text))),
+    };
+
+    let rest = text[end..].trim_start();
+    if !rest.is_empty() && !rest.starts_with('#') {
+        return Err(error(
+            offset + text.len() - rest.len(),
+            "unexpected characters after a quoted scalar",
+        ));
+    }
+
+    Ok(Value::String(value))
+}
+
+/// Resolves a plain scalar the same way as the YAML core schema does
+fn plain_scalar(text: &str) -> Value {
+    match text {
+        "" | "~" | "null" | "Null" | "NULL" => return Value::Null,
+        "true" | "True" | "TRUE" => return Value::Bool(true),
+        "false" | "False" | "FALSE" => return Value::Bool(false),
+        _ => {}
+    }
+
+    let is_numeric = text.bytes().any(|b| b.is_ascii_digit())
+        && text
+            .bytes()
+            .all(|b| b.is_ascii_digit() || matches!(b, b'+' | b'-' | b'.' | b'e' | b'E'));
+    if is_numeric {
+        if let Ok(int) = text.parse::<i64>() {
+            return Value::Number(int.into());
+        }
+        if let Some(float) = text.parse::<f64>().ok().and_then(Number::from_f64) {
+            return Value::Number(float);
+        }
+    }
+
+    Value::String(text.to_string())
+}
+
+/// Parses `"..."` at the start of `text`, returns the value and the end of the scalar
+fn parse_double_quoted(text: &str, offset: usize) -> Result<(String, usize), ContentError> {
+    let mut out = String::new();
+    let mut chars = text.char_indices().skip(1);
+
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '"' => return Ok((out, idx + 1)),
+            '\\' => {
+                let Some((_, escaped)) = chars.next() else {
+                    break;
+                };
+                let hex_len = match escaped {
+                    'x' => 2,
+                    'u' => 4,
+                    'U' => 8,
+                    _ => 0,
+                };
+                if hex_len != 0 {
+                    let hex: String = chars.by_ref().take(hex_len).map(|(_, c)| c).collect();
+                    let Some(c) = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32)
+                    else {
+                        return Err(error(offset + idx, "invalid escape sequence"));
+                    };
+                    out.push(c);
+                    continue;
+                }
+
+                out.push(match escaped {
+                    '0' => '\0',
+                    'a' => '\u{07}',
+                    'b' => '\u{08}',
+                    't' | '\t' => '\t',
+                    'n' => '\n',
+                    'v' => '\u{0B}',
+                    'f' => '\u{0C}',
+                    'r' => '\r',
+                    'e' => '\u{1B}',
+                    ' ' => ' ',
+                    '"' => '"',
+                    '/' => '/',
+                    '\\' => '\\',
+                    'N' => '\u{85}',
+                    '_' => '\u{A0}',
+                    'L' => '\u{2028}',
+                    'P' => '\u{2029}',
+                    _ => return Err(error(offset + idx, "invalid escape sequence")),
+                });
+            }
+            _ => out.push(c),
+        }
+    }
+
+    Err(error(offset, "unterminated quoted scalar"))
+}
+
+/// Parses `'...'` at the start of `text`, returns the value and the end of the scalar
+fn parse_single_quoted(text: &str, offset: usize) -> Result<(String, usize), ContentError> {
+    let mut out = String::new();
+    let mut chars = text.char_indices().skip(1).peekable();
+
+    while let Some((idx, c)) = chars.next() {
+        if c != '\'' {
+            out.push(c);
+            continue;
+        }
+
+        // `''` is an escaped quote
+        if matches!(chars.peek(), Some((_, '\''))) {
+            chars.next();
+            out.push('\'');
+            continue;
+        }
+
+        return Ok((out, idx + 1));
+    }
+
+    Err(error(offset, "unterminated quoted scalar"))
+}
+
+/// Finds the colon of `key: value` or `key:`, e.g. URLs like `http://` are not keys
+fn find_mapping_colon(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+
+    // Quoted keys may contain colons
+    let start = match *bytes.first()? {
+        quote @ (b'"' | b'\'') => {
+            let mut idx = 1;
+            loop {
+                let c = *bytes.get(idx)?;
+                if c == b'\\' && quote == b'"' {
+                    idx += 2;
+                    continue;
+                }
+                if c == quote {
+                    if quote == b'\'' && bytes.get(idx + 1) == Some(&b'\'') {
+                        idx += 2;
+                        continue;
+                    }
+                    break idx + 1;
+                }
+                idx += 1;
+            }
+        }
+        b'[' | b'{' | b'#' => return None,
+        _ => 0,
+    };
+
+    for idx in start..bytes.len() {
+        match bytes[idx] {
+            b':' if matches!(bytes.get(idx + 1), None | Some(b' ' | b'\t')) => return Some(idx),
+            b'#' if idx > 0 && matches!(bytes[idx - 1], b' ' | b'\t') => return None,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn is_sequence_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ") || text.starts_with("-\t")
+}
+
+/// Strips ` # comment` of a plain scalar
+fn strip_comment(text: &str) -> &str {
+    if text.starts_with('#') {
+        return "";
+    }
+
+    let bytes = text.as_bytes();
+    for idx in 1..bytes.len() {
+        if bytes[idx] == b'#' && matches!(bytes[idx - 1], b' ' | b'\t') {
+            return text[..idx].trim_end();
+        }
+    }
+
+    text.trim_end()
+}
+
+fn error(offset: usize, message: impl Into<String>) -> ContentError {
+    ContentError {
+        offset,
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(source: &str) -> String {
+        match parse_yaml(source) {
+            Ok(value) => value.to_string(),
+            Err(e) => panic!("{} at {}", e.message, e.offset),
+        }
+    }
+
+    fn yaml_error(source: &str) -> (usize, String) {
+        match parse_yaml(source) {
+            Ok(value) => panic!("Expected an error, got {value}"),
+            Err(e) => (e.offset, e.message),
+        }
+    }
+
+    #[test]
+    fn it_parses_mappings_and_sequences() {
+        assert_eq!(yaml(""), "null");
+        assert_eq!(yaml("# only a comment\n"), "null");
+        assert_eq!(
+            yaml("en:\n  hello: Hello  # comment\n  url: http://example.com\nja:\n  hello: 'こんにちは'\n"),
+            r#"{"en":{"hello":"Hello","url":"http://example.com"},"ja":{"hello":"こんにちは"}}"#
+        );
+        assert_eq!(
+            yaml("---\nlist:\n- a\n-\n  - b\n- key: 1\n  other: true\n- ~\n"),
+            r#"{"list":["a",["b"],{"key":1,"other":true},null]}"#
+        );
+        assert_eq!(yaml("- - a\n  - b\n"), r#"[["a","b"]]"#);
+        assert_eq!(
+            yaml("\"a: b\": 1.5\n'c''d': x\n"),
+            r#"{"a: b":1.5,"c'd":"x"}"#
+        );
+    }
+
+    #[test]
+    fn it_parses_scalars() {
+        assert_eq!(
+            yaml(r#"a: "line\nbreak \"quoted\" \u00e9 # not a comment""#),
+            r#"{"a":"line\nbreak \"quoted\" é # not a comment"}"#
+        );
+        assert_eq!(yaml("a: 'it''s'"), r#"{"a":"it's"}"#);
+        assert_eq!(
+            yaml("a: 10\nb: -2.5\nc: 1.2.3\nd: false\ne:\n"),
+            r#"{"a":10,"b":-2.5,"c":"1.2.3","d":false,"e":null}"#
+        );
+        assert_eq!(yaml("a: Hello {name}!"), r#"{"a":"Hello {name}!"}"#);
+    }
+
+    #[test]
+    fn it_parses_block_scalars() {
+        assert_eq!(
+            yaml("a: |\n  first\n    indented\n\n  last\n\nb: x\n"),
+            r#"{"a":"first\n  indented\n\nlast\n","b":"x"}"#
+        );
+        assert_eq!(
+            yaml("a: >-\n  folded\n  line\n\n  next\nb: |+\n  kept\n\n"),
+            r#"{"a":"folded line\nnext","b":"kept\n\n"}"#
+        );
+        assert_eq!(yaml("- |\n  # not a comment\n"), "[\"# not a comment\\n\"]");
+    }
+
+    #[test]
+    fn it_reports_errors() {
+        assert_eq!(
+            yaml_error("a: 1\n   b: 2\n"),
+            (8, String::from("unexpected indentation"))
+        );
+        assert_eq!(
+            yaml_error("a: [1, 2]\n"),
+            (3, String::from("flow collections are not supported"))
+        );
+        assert_eq!(
+            yaml_error("a: \"unterminated\n"),
+            (3, String::from("unterminated quoted scalar"))
+        );
+        assert_eq!(
+            yaml_error("a: 1\na: 2\n"),
+            (5, String::from("duplicate key \"a\""))
+        );
+        assert_eq!(
+            yaml_error("a:\n\t- b\n"),
+            (3, String::from("tabs are not allowed in the indentation"))
+        );
+    }
+}
@@ -1,13 +1,24 @@
 use fervid_css::CssError;
+use strum_macros::IntoStaticStr;
 use swc_core::common::{Span, Spanned};
 
 #[derive(Debug)]
 pub enum TransformError {
     CssError(CssError),
+    CustomBlockError(CustomBlockError),
+    MigrationError(MigrationError),
     ScriptError(ScriptError),
     TemplateError(TemplateError),
 }
 
+/// Severity of a [`TransformError`]. Warnings do not affect the generated code
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
 #[derive(Debug)]
 pub struct ScriptError {
     pub span: Span,
@@ -21,6 +32,19 @@ pub struct TemplateError {
 }
 
 #[derive(Debug)]
+pub struct CustomBlockError {
+    pub span: Span,
+    pub kind: CustomBlockErrorKind,
+}
+
+#[derive(Debug)]
+pub struct MigrationError {
+    pub span: Span,
+    pub kind: MigrationErrorKind,
+}
+
+#[derive(Debug, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
 pub enum ScriptErrorKind {
     /// A compiler macro was imported, but it didn't need to
     CompilerMacroImport,
@@ -95,18 +119,358 @@ pub enum ScriptErrorKind {
     ResolveTypeUnsupportedComputedKey,
     /// Disallow non-type exports inside `<script setup>`
     SetupExport,
+    /// A macro which needs the Vue 3 runtime (e.g. `defineModel` and `useModel`) when compiling for Vue 2
+    Vue2UnsupportedMacro,
     /// `withDefaults` only works with type-only `defineProps`
     WithDefaultsNeedsTypeOnlyDefineProps,
     /// `withDefaults` without `defineProps` inside
     WithDefaultsWithoutDefineProps,
 }
 
-#[derive(Debug)]
+#[derive(Debug, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
 pub enum TemplateErrorKind {
-    /// Failed parsing the URL when doing asset URL transform
-    TransformAssetUrlsBaseUrlParseFailed,
+    /// Element nesting which the browser rewrites when parsing the HTML, e.g. `<div>` inside `<p>`.
+    /// This is a warning, because it only causes hydration mismatches
+    InvalidHtmlNesting {
+        tag: String,
+        ancestor: String,
+        /// Whether `ancestor` is the direct parent, e.g. `<a>` may not be nested in `<a>` at any depth
+        is_direct_child: bool,
+    },
     /// Failed parsing the configured base URL when doing asset URL transform
+    TransformAssetUrlsBaseUrlParseFailed,
+    /// Failed parsing the URL when doing asset URL transform
     TransformAssetUrlsUrlParseFailed,
+    /// Error reported by a user template transform
+    Plugin(String),
+    /// `v-else`/`v-else-if` without an adjacent `v-if`/`v-else-if`
+    VElseNoAdjacentIf,
+    /// `key` on a child of `<template v-for>` instead of the `<template>` itself
+    VForTemplateKeyPlacement,
+    /// `v-model:arg` on a plain element
+    VModelArgOnElement,
+    /// `v-model` on a plain element other than `<input>`, `<textarea>` and `<select>`
+    VModelOnInvalidElement,
+    /// Two `<template v-slot>` with the same static name
+    VSlotDuplicateSlotNames,
+    /// Children besides the explicit `<template v-slot:default>`
+    VSlotExtraneousDefaultSlotChildren,
+    /// `v-slot` on an element which is neither a component nor a `<template>` directly inside a component
+    VSlotMisplaced,
+    /// `v-slot` on both the component and a nested `<template>`
+    VSlotMixedSlotUsage,
+    /// More than one root node when compiling for Vue 2
+    Vue2MultipleRoots,
+}
+
+#[derive(Debug, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum CustomBlockErrorKind {
+    /// Module code returned by a custom block handler is not a valid ES module
+    InvalidModule(String),
+    /// Error reported by a custom block handler, e.g. malformed `<i18n>` content
+    Handler(String),
+}
+
+/// Constructs which the Options API to `<script setup>` migration could not convert.
+/// Except for the first two, the component is migrated and these need a manual review
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum MigrationErrorKind {
+    /// `<script>` has no `export default` object
+    NoDefaultExport,
+    /// The component already has `<script setup>`
+    ScriptSetupExists,
+    /// `await` in `created`, which makes the component async
+    AsyncCreated,
+    /// Entry of `data` which is not a plain `key: value`
+    DataEntry,
+    /// `mixins` or `extends` moved to `defineOptions`
+    Mixins,
+    /// Two declarations of `<script setup>` share a name, e.g. `props` and an import named `props`
+    NameConflict,
+    /// Assignment to a prop, e.g. `this.foo = 1`
+    PropAssignment,
+    /// `this.foo` inside a function which declares its own `foo`, e.g. `const count = 5; this.count++`
+    ShadowedBinding,
+    /// An option or its entry without a Composition API counterpart, e.g. `...mapGetters()`
+    UnsupportedOption,
+    /// `this` which is not a member access of a known binding, e.g. `this.$router` or `const vm = this`
+    UnsupportedThis,
+}
+
+impl TransformError {
+    /// Stable code of the error, e.g. `duplicate-define-props`
+    pub fn code(&self) -> &'static str {
+        match self {
+            TransformError::CssError(e) => e.kind.code(),
+            TransformError::CustomBlockError(e) => e.kind.code(),
+            TransformError::MigrationError(e) => e.kind.code(),
+            TransformError::ScriptError(e) => e.kind.code(),
+            TransformError::TemplateError(e) => e.kind.code(),
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            TransformError::TemplateError(TemplateError {
+                kind: TemplateErrorKind::InvalidHtmlNesting { .. },
+                ..
+            }) => Severity::Warning,
+            TransformError::MigrationError(MigrationError {
+                kind:
+                    MigrationErrorKind::NoDefaultExport
+                    | MigrationErrorKind::ScriptSetupExists,
+                ..
+            }) => Severity::Error,
+            TransformError::MigrationError(_) => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl ScriptErrorKind {
+    /// Stable code of the error, e.g. `duplicate-define-props`
+    pub fn code(&self) -> &'static str {
+        self.into()
+    }
+}
+
+impl MigrationErrorKind {
+    /// Stable code of the error, e.g. `unsupported-this`
+    pub fn code(&self) -> &'static str {
+        self.into()
+    }
+}
+
+impl TemplateErrorKind {
+    /// Stable code of the error, e.g. `transform-asset-urls-url-parse-failed`
+    pub fn code(&self) -> &'static str {
+        self.into()
+    }
+}
+
+impl CustomBlockErrorKind {
+    /// Stable code of the error, e.g. `invalid-module`
+    pub fn code(&self) -> &'static str {
+        self.into()
+    }
+}
+
+impl std::fmt::Display for ScriptErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            ScriptErrorKind::CompilerMacroImport => {
+                "Compiler macros are globally available and do not need to be imported."
+            }
+            ScriptErrorKind::DefineEmitsMalformed => "defineEmits() expects a type argument.",
+            ScriptErrorKind::DefineEmitsTypeAndNonTypeArguments => {
+                "defineEmits() cannot accept both type and non-type arguments at the same time. Use one or the other."
+            }
+            ScriptErrorKind::DefineEmitsMixedCallAndPropertySyntax => {
+                "defineEmits() type cannot mixed call signature and property syntax."
+            }
+            ScriptErrorKind::DefinePropsTypeAndNonTypeArguments => {
+                "defineProps() cannot accept both type and non-type arguments at the same time. Use one or the other."
+            }
+            ScriptErrorKind::DefineOptionsTypeArguments => {
+                "defineOptions() cannot accept type arguments"
+            }
+            ScriptErrorKind::DefineOptionsProps => {
+                "defineOptions() cannot be used to declare props. Use defineProps() instead."
+            }
+            ScriptErrorKind::DefineOptionsEmits => {
+                "defineOptions() cannot be used to declare emits. Use defineEmits() instead."
+            }
+            ScriptErrorKind::DefineOptionsExpose => {
+                "defineOptions() cannot be used to declare expose. Use defineExpose() instead."
+            }
+            ScriptErrorKind::DefineOptionsSlots => {
+                "defineOptions() cannot be used to declare slots. Use defineSlots() instead."
+            }
+            ScriptErrorKind::DefinePropsDestructureForbidden => {
+                "Props destructure is explicitly prohibited via config."
+            }
+            ScriptErrorKind::DefinePropsDestructureCannotUseComputedKey => {
+                "Props destructure cannot use computed key."
+            }
+            ScriptErrorKind::DefinePropsDestructureCannotAssignToReadonly => {
+                "Cannot assign to destructured props as they are readonly."
+            }
+            ScriptErrorKind::DefinePropsDestructureShouldNotPassToToRef => {
+                "Destructured prop should not be passed directly to toRef(). Pass a getter () => prop instead."
+            }
+            ScriptErrorKind::DefinePropsDestructureShouldNotPassToWatch => {
+                "Destructured prop should not be passed directly to watch(). Pass a getter () => prop instead."
+            }
+            ScriptErrorKind::DefinePropsDestructureDeclaredTypeMismatch => {
+                "Default value of prop does not match declared type."
+            }
+            ScriptErrorKind::DefinePropsDestructureUnnecessaryWithDefaults => {
+                "withDefaults() is unnecessary when using destructure with defineProps().\nReactive destructure will be disabled when using withDefaults().\nPrefer using destructure default values, e.g. const { foo = 1 } = defineProps(...)."
+            }
+            ScriptErrorKind::DefinePropsDestructureUnsupportedNestedPattern => {
+                "Props destructure does not support nested patterns."
+            }
+            ScriptErrorKind::DefineSlotsArguments => "defineSlots() cannot accept arguments",
+            ScriptErrorKind::DuplicateDefineEmits => "duplicate defineEmits() call",
+            ScriptErrorKind::DuplicateDefineModelName => "duplicate model name",
+            ScriptErrorKind::DuplicateDefineProps => "duplicate defineProps() call",
+            ScriptErrorKind::DuplicateDefineOptions => "duplicate defineOptions() call",
+            ScriptErrorKind::DuplicateDefineSlots => "duplicate defineSlots() call",
+            ScriptErrorKind::DuplicateImport => {
+                "Different imports aliased to the same local name."
+            }
+            ScriptErrorKind::ResolveTypeElementType => "Failed to resolve element type from target type",
+            ScriptErrorKind::ResolveTypeExtendsBaseType => "Failed to resolve extends base type.",
+            ScriptErrorKind::ResolveTypeMissingTypeParam => "Missing type parameter.",
+            ScriptErrorKind::ResolveTypeMissingTypeParams => "Missing type parameters.",
+            ScriptErrorKind::ResolveTypeUnresolvable => "Unresolvable type.",
+            ScriptErrorKind::ResolveTypeUnresolvableIndexType => {
+                "Failed to resolve index type into finite keys"
+            }
+            ScriptErrorKind::ResolveTypeUnsupported => {
+                "Unsupported type in SFC macro. Note that only type references, literal types and their combinations can be resolved."
+            }
+            ScriptErrorKind::ResolveTypeUnsupportedIndexType => {
+                "Unsupported type when resolving index type"
+            }
+            ScriptErrorKind::ResolveTypeUnsupportedComputedKey => {
+                "Computed keys are not supported in types referenced by SFC macros."
+            }
+            ScriptErrorKind::SetupExport => {
+                "<script setup> cannot contain ES module exports. If you are using a previous version of <script setup>, please consult the updated RFC at https://github.com/vuejs/rfcs/pull/227."
+            }
+            ScriptErrorKind::Vue2UnsupportedMacro => {
+                "This macro requires Vue 3 and cannot be used when compiling for Vue 2.7."
+            }
+            ScriptErrorKind::WithDefaultsNeedsTypeOnlyDefineProps => {
+                "withDefaults can only be used with type-based defineProps declaration."
+            }
+            ScriptErrorKind::WithDefaultsWithoutDefineProps => {
+                "The 1st argument of withDefaults must be a defineProps() call."
+            }
+        };
+
+        f.write_str(message)
+    }
+}
+
+impl std::fmt::Display for TemplateErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateErrorKind::InvalidHtmlNesting {
+                tag,
+                ancestor,
+                is_direct_child,
+            } => {
+                if *is_direct_child {
+                    write!(f, "<{tag}> cannot be child of <{ancestor}>")?;
+                } else {
+                    write!(f, "<{tag}> cannot be nested inside another <{ancestor}>")?;
+                }
+                f.write_str(", according to HTML specifications. This can cause hydration errors or potentially disrupt future functionality.")
+            }
+            TemplateErrorKind::TransformAssetUrlsBaseUrlParseFailed => {
+                f.write_str("Failed to parse the base URL of transformAssetUrls.")
+            }
+            TemplateErrorKind::TransformAssetUrlsUrlParseFailed => {
+                f.write_str("Failed to parse the asset URL.")
+            }
+            TemplateErrorKind::Plugin(message) => f.write_str(message),
+            TemplateErrorKind::VElseNoAdjacentIf => {
+                f.write_str("v-else/v-else-if has no adjacent v-if or v-else-if.")
+            }
+            TemplateErrorKind::VForTemplateKeyPlacement => {
+                f.write_str("<template v-for> key should be placed on the <template> tag.")
+            }
+            TemplateErrorKind::VModelArgOnElement => {
+                f.write_str("v-model argument is not supported on plain elements.")
+            }
+            TemplateErrorKind::VModelOnInvalidElement => f.write_str(
+                "v-model can only be used on <input>, <textarea> and <select> elements.",
+            ),
+            TemplateErrorKind::VSlotDuplicateSlotNames => f.write_str("Duplicate slot names found."),
+            TemplateErrorKind::VSlotExtraneousDefaultSlotChildren => f.write_str(
+                "Extraneous children found when component already has explicitly named default slot. These children will be ignored.",
+            ),
+            TemplateErrorKind::VSlotMisplaced => {
+                f.write_str("v-slot can only be used on components or <template> tags.")
+            }
+            TemplateErrorKind::VSlotMixedSlotUsage => f.write_str(
+                "Mixed v-slot usage on both the component and nested <template>. When there are multiple named slots, all slots should use <template> syntax to avoid scope ambiguity.",
+            ),
+            TemplateErrorKind::Vue2MultipleRoots => f.write_str(
+                "Component template should contain exactly one root element. If you are using v-if on multiple elements, use v-else-if to chain them instead.",
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for CustomBlockErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CustomBlockErrorKind::InvalidModule(message) => {
+                write!(
+                    f,
+                    "Custom block handler produced an invalid module: {message}"
+                )
+            }
+            CustomBlockErrorKind::Handler(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::fmt::Display for MigrationErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            MigrationErrorKind::NoDefaultExport => {
+                "<script> has no `export default` object to migrate."
+            }
+            MigrationErrorKind::ScriptSetupExists => {
+                "The component already uses <script setup>. Merge <script> into it manually."
+            }
+            MigrationErrorKind::AsyncCreated => {
+                "`await` in <script setup> makes the component async, it has to be rendered inside <Suspense>."
+            }
+            MigrationErrorKind::DataEntry => {
+                "Only `key: value` entries of data() can be converted to refs."
+            }
+            MigrationErrorKind::Mixins => {
+                "Mixins were moved to defineOptions(), but their members are not accessible in <script setup>. Consider converting them to composables."
+            }
+            MigrationErrorKind::NameConflict => {
+                "Several variables of <script setup> share the same name, e.g. a data entry and an import. Rename one of them."
+            }
+            MigrationErrorKind::PropAssignment => {
+                "Props are readonly, this assignment was not converted."
+            }
+            MigrationErrorKind::ShadowedBinding => {
+                "A local variable shadows the member of the component, this `this` access was not converted. Rename the variable."
+            }
+            MigrationErrorKind::UnsupportedOption => {
+                "This has no Composition API counterpart and was not converted."
+            }
+            MigrationErrorKind::UnsupportedThis => {
+                "`this` is not available in <script setup> and only known members of the component were converted."
+            }
+        };
+
+        f.write_str(message)
+    }
+}
+
+impl std::fmt::Display for TransformError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransformError::CssError(e) => e.kind.fmt(f),
+            TransformError::CustomBlockError(e) => e.kind.fmt(f),
+            TransformError::MigrationError(e) => e.kind.fmt(f),
+            TransformError::ScriptError(e) => e.kind.fmt(f),
+            TransformError::TemplateError(e) => e.kind.fmt(f),
+        }
+    }
 }
 
 impl From<CssError> for TransformError {
@@ -115,6 +479,18 @@ impl From<CssError> for TransformError {
     }
 }
 
+impl From<CustomBlockError> for TransformError {
+    fn from(value: CustomBlockError) -> Self {
+        TransformError::CustomBlockError(value)
+    }
+}
+
+impl From<MigrationError> for TransformError {
+    fn from(value: MigrationError) -> Self {
+        TransformError::MigrationError(value)
+    }
+}
+
 impl From<ScriptError> for TransformError {
     fn from(value: ScriptError) -> Self {
         TransformError::ScriptError(value)
@@ -125,6 +501,8 @@ impl Spanned for TransformError {
     fn span(&self) -> Span {
         match self {
             TransformError::CssError(e) => e.span,
+            TransformError::CustomBlockError(e) => e.span,
+            TransformError::MigrationError(e) => e.span,
             TransformError::ScriptError(e) => e.span,
             TransformError::TemplateError(e) => e.span,
         }
@@ -1,7 +1,11 @@
 use std::{cell::RefCell, rc::Rc};
 
+use custom_blocks::transform_custom_blocks;
 use error::TransformError;
-use fervid_core::{SfcDescriptor, SfcScriptBlock, SfcScriptLang, TemplateGenerationMode};
+use fervid_core::{
+    CompileTarget, SfcDescriptor, SfcScriptBlock, SfcScriptLang, SfcTemplateBlock,
+    TemplateGenerationMode,
+};
 use misc::infer_name;
 use script::transform_and_record_scripts;
 use style::{attach_scope_id, create_style_scope, transform_style_blocks};
@@ -12,8 +16,10 @@ use template::transform_and_record_template;
 extern crate lazy_static;
 
 pub mod atoms;
+pub mod custom_blocks;
 pub mod error;
 pub mod misc;
+pub mod module_loader;
 pub mod script;
 pub mod structs;
 pub mod style;
@@ -22,7 +28,15 @@ pub mod template;
 #[cfg(test)]
 mod test_utils;
 
+pub use custom_blocks::{
+    CustomBlockContext, CustomBlockHandler, CustomBlockHandlers, I18nBlockHandler,
+};
+pub use module_loader::{LoadedModule, ModuleLoader};
+pub use script::component_meta::extract_component_meta;
 pub use structs::*;
+pub use template::plugins::{
+    DirectiveTransform, NodeTransform, TemplatePluginContext, TemplatePlugins,
+};
 
 /// Applies all the necessary transformations to the SFC.
 ///
@@ -64,17 +78,40 @@ pub fn transform_sfc(
     // Transform scoped CSS
     let mut style_blocks = sfc_descriptor.styles;
     let scope = create_style_scope(options.scope_id);
-    let had_scoped_blocks = transform_style_blocks(&mut style_blocks, &scope, errors);
+    let mut style_source_maps = Vec::new();
+    let style_source_map = options
+        .source_map
+        .then_some(fervid_css::CssSourceMapConfig {
+            filename: options.filename,
+            source: options.source,
+        });
+    let had_scoped_blocks = transform_style_blocks(
+        &mut style_blocks,
+        &scope,
+        style_source_map,
+        &mut style_source_maps,
+        errors,
+    );
     if had_scoped_blocks {
         attach_scope_id(&mut transform_result, &scope);
     }
 
+    // Compile custom blocks which have a handler
+    let custom_block_modules = transform_custom_blocks(
+        &sfc_descriptor.custom_blocks,
+        &options.custom_block_handlers,
+        options.filename,
+        options.source,
+        errors,
+    );
+
     // Augment with some metadata
     let mut exported_obj = transform_result.export_obj;
     infer_name(&mut exported_obj, options.filename);
 
     // Temp: extend errors
     errors.extend(ctx.errors);
+    errors.extend(ctx.warnings);
 
     TransformSfcResult {
         bindings_helper: ctx.bindings_helper,
@@ -83,7 +120,35 @@ pub fn transform_sfc(
         setup_fn: transform_result.setup_fn,
         template_block,
         style_blocks,
+        style_source_maps,
         custom_blocks: sfc_descriptor.custom_blocks,
+        custom_block_modules,
+    }
+}
+
+/// Transforms the `<template>` alone, e.g. when it is compiled separately from the scripts.
+///
+/// `bindings` are the ones collected from the scripts (see [`BindingsHelper::setup_bindings`]).
+/// The template is always transformed for a `render` function, because inline templates
+/// are a part of the script.
+pub fn transform_sfc_template(
+    mut template: SfcTemplateBlock,
+    bindings: Vec<SetupBinding>,
+    options: TransformSfcOptions<'_>,
+    errors: &mut Vec<TransformError>,
+) -> TransformSfcTemplateResult {
+    // Without the scripts the context defaults to the `RenderFn` mode
+    let mut ctx = TransformSfcContext::new(&SfcDescriptor::default(), &options);
+    ctx.bindings_helper.setup_bindings = bindings;
+
+    transform_and_record_template(&mut template, &mut ctx);
+
+    errors.extend(ctx.errors);
+    errors.extend(ctx.warnings);
+
+    TransformSfcTemplateResult {
+        bindings_helper: ctx.bindings_helper,
+        template_block: (!template.roots.is_empty()).then_some(template),
     }
 }
 
@@ -111,8 +176,20 @@ impl TransformSfcContext {
                 .as_ref()
                 .is_some_and(recognize_lang);
 
-        // Set inline flag in `BindingsHelper`
-        if bindings_helper.is_prod && sfc_descriptor.script_setup.is_some() {
+        // Vapor mode is only supported for `<script setup>` in Vue 3
+        bindings_helper.target = options.target;
+        bindings_helper.is_vapor = sfc_descriptor.vapor
+            && sfc_descriptor.script_setup.is_some()
+            && matches!(options.target, CompileTarget::Vue3);
+
+        // Set inline flag in `BindingsHelper`.
+        // Vapor components are always inlined, because their `setup` returns the rendered block.
+        // Vue 2 has no inline templates, its `render` accesses the bindings through `_setup`
+        let inline_template = options.inline_template.unwrap_or(bindings_helper.is_prod);
+        if (inline_template || bindings_helper.is_vapor)
+            && sfc_descriptor.script_setup.is_some()
+            && matches!(options.target, CompileTarget::Vue3)
+        {
             bindings_helper.template_generation_mode = TemplateGenerationMode::Inline;
         }
 
@@ -124,8 +201,11 @@ impl TransformSfcContext {
             deps: Default::default(),
             scopes: vec![],
             transform_asset_urls: options.transform_asset_urls.clone(),
+            template_plugins: options.template_plugins.clone(),
             errors: vec![],
             warnings: vec![],
+            strip_client_only: options.strip_client_only,
+            module_loader: options.module_loader.clone(),
         }
     }
 
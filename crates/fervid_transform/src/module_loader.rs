@@ -0,0 +1,29 @@
+//! Access to the modules imported by the SFC, e.g. the Options API `mixins` and `extends`.
+//!
+//! The compiler does not touch the file system by itself.
+//! Instead, a [`ModuleLoader`] is provided by the consumer (a bundler plugin, the CLI, etc.),
+//! which resolves the import specifiers the same way the bundler does.
+
+/// Resolves and reads the modules imported by the SFC or by other loaded modules.
+pub trait ModuleLoader: Send + Sync {
+    /// Loads the module imported as `specifier` from the file `importer`.
+    /// Return `None` when the module cannot be resolved or should not be analyzed,
+    /// e.g. a package from `node_modules`.
+    fn load(&self, specifier: &str, importer: &str) -> Option<LoadedModule>;
+}
+
+/// Module returned by the [`ModuleLoader`]
+#[derive(Clone, Debug)]
+pub struct LoadedModule {
+    /// Resolved name of the file, used as the importer of the nested imports
+    pub filename: String,
+    /// JavaScript or TypeScript code of the module.
+    /// For the `.vue` files this is the content of `<script>`
+    pub source: String,
+}
+
+impl std::fmt::Debug for dyn ModuleLoader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ModuleLoader")
+    }
+}
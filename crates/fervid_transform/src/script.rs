@@ -17,7 +17,9 @@ use self::{
 };
 
 pub mod common;
+pub mod component_meta;
 mod imports;
+pub mod migrate;
 mod options_api;
 mod resolve_type;
 mod setup;
@@ -65,10 +67,13 @@ pub fn transform_and_record_scripts(
             &mut module,
             AnalyzeOptions {
                 collect_top_level_stmts: script_setup.is_some(),
+                module_loader: ctx.module_loader.clone(),
+                filename: ctx.filename.to_owned(),
             },
             &mut ctx.bindings_helper,
             errors,
         );
+        ctx.deps.extend(transform_result.deps);
 
         script_module = Some(module);
         script_default_export = transform_result.default_export_obj;
@@ -0,0 +1,1278 @@
+//! Extraction of the component public interface (props, events, slots, models, expose)
+//! for documentation purposes.
+//!
+//! Unlike the transformation, this is read-only: nothing is generated,
+//! and types and values are reported as they are written in the source.
+
+use fervid_core::{
+    AttributeOrBinding, CompileTarget, ElementNode, FervidAtom, Node, SfcDescriptor,
+    SfcScriptBlock, StrOrExpr,
+};
+use flagset::FlagSet;
+use swc_core::{
+    common::{
+        comments::{CommentKind, Comments, SingleThreadedComments},
+        pass::Either,
+        Span, Spanned,
+    },
+    ecma::ast::{
+        ArrayLit, CallExpr, Callee, Decl, Expr, Lit, Module, ModuleItem, ObjectLit, Pat, Prop,
+        PropOrSpread, Stmt, TsEntityName, TsFnOrConstructorType, TsFnParam, TsMethodSignature,
+        TsType,
+    },
+};
+
+use crate::{
+    atoms::{
+        DEFINE_EMITS, DEFINE_EXPOSE, DEFINE_MODEL, DEFINE_OPTIONS, DEFINE_PROPS, DEFINE_SLOTS,
+        EMITS, EXPOSE, MODEL_VALUE, NAME, PROPS, WITH_DEFAULTS,
+    },
+    error::TransformError,
+    script::{
+        imports::process_imports,
+        options_api::find_default_export_obj,
+        resolve_type::{
+            infer_runtime_type_resolved_prop, infer_runtime_type_type, record_types,
+            resolve_type_elements, ResolvedPropValue, TypeResolveContext, Types, TypesSet,
+        },
+        setup::{
+            define_emits::{extract_event_names, FxIndexSet},
+            macros::collect_macros,
+            utils::unwrap_ts_node_expr,
+        },
+        utils::{get_string_expr, resolve_object_key},
+    },
+    ComponentEventMeta, ComponentExposedMeta, ComponentMeta, ComponentMetaOptions,
+    ComponentModelMeta, ComponentPropMeta, ComponentSlotMeta, TransformAssetUrlsConfig,
+    TransformSfcContext, TransformSfcOptions,
+};
+
+/// Extracts the public interface of a component.
+///
+/// Both `<script setup>` macros and Options API `<script>` are analyzed.
+/// Slots are taken from `defineSlots` or, when it is absent, from `<slot>`s in the `<template>`.
+pub fn extract_component_meta(
+    sfc_descriptor: &SfcDescriptor,
+    options: ComponentMetaOptions<'_>,
+    errors: &mut Vec<TransformError>,
+) -> ComponentMeta {
+    let mut ctx = TransformSfcContext::new(
+        sfc_descriptor,
+        &TransformSfcOptions {
+            is_prod: false,
+            is_ce: false,
+            props_destructure: Default::default(),
+            scope_id: "",
+            filename: options.filename,
+            transform_asset_urls: TransformAssetUrlsConfig::Disabled,
+            template_plugins: Default::default(),
+            custom_block_handlers: Default::default(),
+            source: options.source,
+            source_map: false,
+            inline_template: None,
+            strip_client_only: false,
+            target: CompileTarget::Vue3,
+            module_loader: None,
+        },
+    );
+    let mut meta = ComponentMeta::default();
+    let mut extractor = MetaExtractor {
+        ctx: &mut ctx,
+        source: options.source,
+        comments: options.comments,
+        errors,
+    };
+
+    // Preparation modifies the scripts, but the descriptor is borrowed
+    let mut script_setup = sfc_descriptor.script_setup.clone();
+    let mut script_options = sfc_descriptor.script_legacy.clone();
+
+    // Same preparation as for the transform: imports, props destructure and types
+    extractor.prepare(script_setup.as_mut(), script_options.as_mut());
+
+    if let Some(ref mut script_options) = script_options {
+        extractor.extract_options_api(&mut script_options.content, &mut meta);
+    }
+
+    let mut has_define_slots = false;
+    if let Some(ref script_setup) = script_setup {
+        has_define_slots = extractor.extract_macros(&script_setup.content, &mut meta);
+    }
+
+    if let (false, Some(template)) = (has_define_slots, sfc_descriptor.template.as_ref()) {
+        collect_template_slots(&template.roots, &mut meta.slots);
+    }
+
+    if meta.name.is_none() {
+        let basename = options
+            .filename
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default();
+        let name = basename.strip_suffix(".vue").unwrap_or(basename);
+        if !name.is_empty() {
+            meta.name = Some(name.to_owned());
+        }
+    }
+
+    meta
+}
+
+struct MetaExtractor<'a, 'e> {
+    ctx: &'a mut TypeResolveContext,
+    source: &'a str,
+    comments: Option<&'a SingleThreadedComments>,
+    errors: &'e mut Vec<TransformError>,
+}
+
+impl MetaExtractor<'_, '_> {
+    fn prepare(
+        &mut self,
+        mut script_setup: Option<&mut SfcScriptBlock>,
+        mut script_options: Option<&mut SfcScriptBlock>,
+    ) {
+        if let Some(ref mut script_options) = script_options {
+            process_imports(
+                &mut script_options.content,
+                &mut self.ctx.bindings_helper,
+                false,
+                self.errors,
+            );
+        }
+
+        if let Some(ref mut script_setup) = script_setup {
+            process_imports(
+                &mut script_setup.content,
+                &mut self.ctx.bindings_helper,
+                true,
+                self.errors,
+            );
+            collect_macros(self.ctx, &script_setup.content, self.errors);
+        }
+
+        // Types are always needed, e.g. for `defineModel<T>()` or `defineSlots<T>()`
+        let scope = self.ctx.root_scope();
+        let mut scope = (*scope).borrow_mut();
+        scope
+            .imports
+            .clone_from(&self.ctx.bindings_helper.user_imports);
+        record_types(self.ctx, script_setup, script_options, &mut scope, false);
+    }
+
+    /// Analyzes the `export default` of Options API
+    fn extract_options_api(&mut self, module: &mut Module, meta: &mut ComponentMeta) {
+        let Some(default_export) = find_default_export_obj(module) else {
+            return;
+        };
+
+        for prop in default_export.props.iter() {
+            let PropOrSpread::Prop(prop) = prop else {
+                continue;
+            };
+            let Prop::KeyValue(ref key_value) = **prop else {
+                continue;
+            };
+            let Some(key) = resolve_object_key(&key_value.key) else {
+                continue;
+            };
+
+            let value = unwrap_ts_node_expr(&key_value.value);
+            if key == *PROPS {
+                self.props_from_runtime(value, &mut meta.props);
+            } else if key == *EMITS {
+                self.events_from_runtime(value, &mut meta.events);
+            } else if key == *EXPOSE {
+                if let Expr::Array(arr) = value {
+                    self.exposed_from_array(arr, &mut meta.exposed);
+                }
+            } else if key == *NAME {
+                meta.name = get_string_expr(value).map(|v| v.to_string());
+            }
+        }
+    }
+
+    /// Analyzes the top-level macro calls of `<script setup>`.
+    /// Returns whether `defineSlots` was found.
+    fn extract_macros(&mut self, module: &Module, meta: &mut ComponentMeta) -> bool {
+        let mut has_define_slots = false;
+
+        for module_item in module.body.iter() {
+            let ModuleItem::Stmt(stmt) = module_item else {
+                continue;
+            };
+
+            let exprs: Vec<&Expr> = match stmt {
+                Stmt::Expr(expr_stmt) => vec![&expr_stmt.expr],
+                Stmt::Decl(Decl::Var(var_decl)) if !var_decl.declare => var_decl
+                    .decls
+                    .iter()
+                    .filter_map(|decl| decl.init.as_deref())
+                    .collect(),
+                _ => continue,
+            };
+
+            for expr in exprs {
+                let Some((sym, call_expr)) = as_macro_call(expr) else {
+                    continue;
+                };
+
+                if DEFINE_PROPS.eq(sym) {
+                    self.props_from_define_props(call_expr, None, &mut meta.props);
+                } else if WITH_DEFAULTS.eq(sym) {
+                    let define_props = call_expr
+                        .args
+                        .first()
+                        .and_then(|arg| as_macro_call(&arg.expr))
+                        .filter(|(sym, _)| DEFINE_PROPS.eq(*sym));
+                    if let Some((_, define_props)) = define_props {
+                        let defaults = call_expr.args.get(1).map(|arg| arg.expr.as_ref());
+                        self.props_from_define_props(define_props, defaults, &mut meta.props);
+                    }
+                } else if DEFINE_EMITS.eq(sym) {
+                    if let Some(ts_type) = type_arg(call_expr) {
+                        self.events_from_type(ts_type, &mut meta.events);
+                    } else if let Some(arg) = call_expr.args.first() {
+                        self.events_from_runtime(unwrap_ts_node_expr(&arg.expr), &mut meta.events);
+                    }
+                } else if DEFINE_SLOTS.eq(sym) {
+                    has_define_slots = true;
+                    if let Some(ts_type) = type_arg(call_expr) {
+                        self.slots_from_type(ts_type, &mut meta.slots);
+                    }
+                } else if DEFINE_MODEL.eq(sym) {
+                    let model = self.model_from_define_model(call_expr, stmt.span());
+                    meta.models.push(model);
+                } else if DEFINE_EXPOSE.eq(sym) {
+                    if let Some(Expr::Object(obj)) =
+                        call_expr.args.first().map(|a| unwrap_ts_node_expr(&a.expr))
+                    {
+                        self.exposed_from_object(obj, &mut meta.exposed);
+                    }
+                } else if DEFINE_OPTIONS.eq(sym) {
+                    if let Some(Expr::Object(obj)) =
+                        call_expr.args.first().map(|a| unwrap_ts_node_expr(&a.expr))
+                    {
+                        if let Some(name) = find_object_value(obj, &NAME).and_then(get_string_expr)
+                        {
+                            meta.name = Some(name.to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        has_define_slots
+    }
+
+    //
+    // Props
+    //
+
+    fn props_from_define_props(
+        &mut self,
+        call_expr: &CallExpr,
+        defaults: Option<&Expr>,
+        out: &mut Vec<ComponentPropMeta>,
+    ) {
+        if let Some(ts_type) = type_arg(call_expr) {
+            self.props_from_type(ts_type, defaults, out);
+        } else if let Some(arg) = call_expr.args.first() {
+            self.props_from_runtime(unwrap_ts_node_expr(&arg.expr), out);
+        }
+    }
+
+    fn props_from_type(
+        &mut self,
+        ts_type: &TsType,
+        defaults: Option<&Expr>,
+        out: &mut Vec<ComponentPropMeta>,
+    ) {
+        let elements = match resolve_type_elements(self.ctx, ts_type) {
+            Ok(v) => v,
+            Err(e) => {
+                self.errors.push(TransformError::ScriptError(e));
+                return;
+            }
+        };
+
+        for (key, element) in elements.props {
+            let mut types = infer_runtime_type_resolved_prop(self.ctx, &element);
+
+            // Same logic as in runtime props generation
+            if types.contains(Types::Unknown) {
+                if types.contains(Types::Boolean) || types.contains(Types::Function) {
+                    types -= Types::Unknown;
+                } else {
+                    types = FlagSet::default();
+                }
+            }
+
+            let (span, required, ts_type) = match element.value {
+                ResolvedPropValue::TsPropertySignature(ref s) => (
+                    s.span,
+                    !s.optional,
+                    s.type_ann
+                        .as_ref()
+                        .and_then(|t| self.text(t.type_ann.span())),
+                ),
+                ResolvedPropValue::TsMethodSignature(ref s) => {
+                    (s.span, !s.optional, self.method_signature_type(s))
+                }
+            };
+
+            // Default either comes from `withDefaults` or from props destructure
+            let default = match defaults {
+                Some(Expr::Object(defaults)) => {
+                    find_object_prop(defaults, &key).and_then(|prop| match prop {
+                        Prop::KeyValue(key_value) => self.text(key_value.value.span()),
+                        _ => self.text(prop.span()),
+                    })
+                }
+                _ => self
+                    .ctx
+                    .bindings_helper
+                    .props_destructured_bindings
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .and_then(|(_, binding)| binding.default.as_ref())
+                    .and_then(|default| self.text(default.span())),
+            };
+
+            out.push(ComponentPropMeta {
+                name: key.to_string(),
+                ts_type,
+                runtime_types: runtime_types_to_strings(types),
+                required,
+                default,
+                description: self.description(span),
+                span,
+            });
+        }
+    }
+
+    /// Props declared as `['foo', 'bar']` or `{ foo: String, bar: { type: Number } }`
+    fn props_from_runtime(&mut self, value: &Expr, out: &mut Vec<ComponentPropMeta>) {
+        match value {
+            Expr::Array(arr) => {
+                for (name, span) in string_array_items(arr) {
+                    out.push(ComponentPropMeta {
+                        name,
+                        ts_type: None,
+                        runtime_types: vec![],
+                        required: false,
+                        default: None,
+                        description: self.description(span),
+                        span,
+                    });
+                }
+            }
+            Expr::Object(obj) => {
+                for prop in obj.props.iter() {
+                    let PropOrSpread::Prop(prop) = prop else {
+                        continue;
+                    };
+
+                    let (name, value) = match **prop {
+                        Prop::KeyValue(ref key_value) => {
+                            let Some(name) = resolve_object_key(&key_value.key) else {
+                                continue;
+                            };
+                            (name, Some(key_value.value.as_ref()))
+                        }
+                        Prop::Shorthand(ref ident) => (ident.sym.to_owned(), None),
+                        _ => continue,
+                    };
+
+                    let span = prop.span();
+                    let mut prop_meta = ComponentPropMeta {
+                        name: name.to_string(),
+                        ts_type: None,
+                        runtime_types: vec![],
+                        required: false,
+                        default: None,
+                        description: self.description(span),
+                        span,
+                    };
+                    if let Some(value) = value {
+                        self.read_runtime_prop_options(value, &mut prop_meta);
+                    }
+
+                    out.push(prop_meta);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Reads a runtime prop definition, e.g. `String` or `{ type: String, required: true }`
+    fn read_runtime_prop_options(&self, value: &Expr, out: &mut ComponentPropMeta) {
+        let Expr::Object(options) = unwrap_ts_node_expr(value) else {
+            (out.runtime_types, out.ts_type) = self.runtime_type(value);
+            return;
+        };
+
+        for prop in options.props.iter() {
+            let PropOrSpread::Prop(prop) = prop else {
+                continue;
+            };
+
+            match **prop {
+                Prop::KeyValue(ref key_value) => {
+                    let Some(key) = resolve_object_key(&key_value.key) else {
+                        continue;
+                    };
+
+                    match key.as_str() {
+                        "type" => {
+                            (out.runtime_types, out.ts_type) = self.runtime_type(&key_value.value)
+                        }
+                        "required" => {
+                            out.required = matches!(
+                                unwrap_ts_node_expr(&key_value.value),
+                                Expr::Lit(Lit::Bool(b)) if b.value
+                            )
+                        }
+                        "default" => out.default = self.text(key_value.value.span()),
+                        _ => {}
+                    }
+                }
+
+                // `default() { return [] }`
+                Prop::Method(ref method) => {
+                    if resolve_object_key(&method.key).is_some_and(|k| k == "default") {
+                        out.default = self.text(method.function.span);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+
+    /// Runtime types of `type` prop option.
+    /// TS type is read from `Object as PropType<T>`.
+    fn runtime_type(&self, value: &Expr) -> (Vec<String>, Option<String>) {
+        let ts_type = match value {
+            Expr::TsAs(ts_as) => match ts_as.type_ann.as_ref() {
+                TsType::TsTypeRef(type_ref) if matches!(type_ref.type_name, TsEntityName::Ident(ref i) if i.sym == "PropType") => {
+                    type_ref
+                        .type_params
+                        .as_ref()
+                        .and_then(|p| p.params.first())
+                        .and_then(|t| self.text(t.span()))
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let runtime_types = match unwrap_ts_node_expr(value) {
+            Expr::Ident(ident) => vec![ident.sym.to_string()],
+            Expr::Array(arr) => arr
+                .elems
+                .iter()
+                .flatten()
+                .filter_map(|elem| match unwrap_ts_node_expr(&elem.expr) {
+                    Expr::Ident(ident) => Some(ident.sym.to_string()),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        };
+
+        (runtime_types, ts_type)
+    }
+
+    //
+    // Events
+    //
+
+    fn events_from_type(&mut self, ts_type: &TsType, out: &mut Vec<ComponentEventMeta>) {
+        // `defineEmits<(e: 'foo' | 'bar') => void>()`
+        if let TsType::TsFnOrConstructorType(TsFnOrConstructorType::TsFnType(fn_type)) = ts_type {
+            self.events_from_call_signature(&fn_type.params, fn_type.span, out);
+            return;
+        }
+
+        let elements = match resolve_type_elements(self.ctx, ts_type) {
+            Ok(v) => v,
+            Err(e) => {
+                self.errors.push(TransformError::ScriptError(e));
+                return;
+            }
+        };
+
+        // `defineEmits<{ change: [id: number] }>()`
+        for (key, element) in elements.props {
+            let (span, payload) = match element.value {
+                ResolvedPropValue::TsPropertySignature(ref s) => {
+                    let payload = match s.type_ann.as_ref().map(|t| t.type_ann.as_ref()) {
+                        Some(TsType::TsTupleType(tuple)) => tuple
+                            .elem_types
+                            .iter()
+                            .filter_map(|elem| self.text(elem.ty.span()))
+                            .collect(),
+                        _ => vec![],
+                    };
+                    (s.span, payload)
+                }
+                ResolvedPropValue::TsMethodSignature(ref s) => (
+                    s.span,
+                    s.params
+                        .iter()
+                        .filter_map(|p| self.fn_param_type(p))
+                        .collect(),
+                ),
+            };
+
+            out.push(ComponentEventMeta {
+                name: key.to_string(),
+                payload,
+                description: self.description(span),
+                span,
+            });
+        }
+
+        // `defineEmits<{ (e: 'change', id: number): void }>()`
+        for call in elements.calls {
+            match call {
+                Either::Left(fn_type) => {
+                    self.events_from_call_signature(&fn_type.params, fn_type.span, out)
+                }
+                Either::Right(call_signature) => self.events_from_call_signature(
+                    &call_signature.params,
+                    call_signature.span,
+                    out,
+                ),
+            }
+        }
+    }
+
+    /// First parameter is the event name (or a union of names), the rest is the payload
+    fn events_from_call_signature(
+        &mut self,
+        params: &[TsFnParam],
+        span: Span,
+        out: &mut Vec<ComponentEventMeta>,
+    ) {
+        let Some(first_param) = params.first() else {
+            return;
+        };
+
+        let mut names = FxIndexSet::<FervidAtom>::default();
+        extract_event_names(self.ctx, first_param, &mut names);
+
+        let payload: Vec<String> = params[1..]
+            .iter()
+            .filter_map(|p| self.fn_param_type(p))
+            .collect();
+        let description = self.description(span);
+
+        for name in names {
+            out.push(ComponentEventMeta {
+                name: name.to_string(),
+                payload: payload.clone(),
+                description: description.clone(),
+                span,
+            });
+        }
+    }
+
+    /// Events declared as `['foo', 'bar']` or `{ foo: (id: number) => true }`
+    fn events_from_runtime(&mut self, value: &Expr, out: &mut Vec<ComponentEventMeta>) {
+        match value {
+            Expr::Array(arr) => {
+                for (name, span) in string_array_items(arr) {
+                    out.push(ComponentEventMeta {
+                        name,
+                        payload: vec![],
+                        description: self.description(span),
+                        span,
+                    });
+                }
+            }
+            Expr::Object(obj) => {
+                for prop in obj.props.iter() {
+                    let PropOrSpread::Prop(prop) = prop else {
+                        continue;
+                    };
+
+                    let (name, payload) = match **prop {
+                        Prop::KeyValue(ref key_value) => {
+                            let Some(name) = resolve_object_key(&key_value.key) else {
+                                continue;
+                            };
+                            let payload = match unwrap_ts_node_expr(&key_value.value) {
+                                Expr::Arrow(arrow) => arrow
+                                    .params
+                                    .iter()
+                                    .filter_map(|p| self.pat_type(p))
+                                    .collect(),
+                                Expr::Fn(fn_expr) => fn_expr
+                                    .function
+                                    .params
+                                    .iter()
+                                    .filter_map(|p| self.pat_type(&p.pat))
+                                    .collect(),
+                                _ => vec![],
+                            };
+                            (name, payload)
+                        }
+                        Prop::Method(ref method) => {
+                            let Some(name) = resolve_object_key(&method.key) else {
+                                continue;
+                            };
+                            let payload = method
+                                .function
+                                .params
+                                .iter()
+                                .filter_map(|p| self.pat_type(&p.pat))
+                                .collect();
+                            (name, payload)
+                        }
+                        Prop::Shorthand(ref ident) => (ident.sym.to_owned(), vec![]),
+                        _ => continue,
+                    };
+
+                    let span = prop.span();
+                    out.push(ComponentEventMeta {
+                        name: name.to_string(),
+                        payload,
+                        description: self.description(span),
+                        span,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    //
+    // Slots, models, expose
+    //
+
+    /// `defineSlots<{ default(props: { msg: string }): any }>()`
+    fn slots_from_type(&mut self, ts_type: &TsType, out: &mut Vec<ComponentSlotMeta>) {
+        let elements = match resolve_type_elements(self.ctx, ts_type) {
+            Ok(v) => v,
+            Err(e) => {
+                self.errors.push(TransformError::ScriptError(e));
+                return;
+            }
+        };
+
+        for (key, element) in elements.props {
+            let (span, props_type) = match element.value {
+                ResolvedPropValue::TsMethodSignature(ref s) => {
+                    (s.span, s.params.first().and_then(|p| self.fn_param_type(p)))
+                }
+                ResolvedPropValue::TsPropertySignature(ref s) => {
+                    let props_type = match s.type_ann.as_ref().map(|t| t.type_ann.as_ref()) {
+                        Some(TsType::TsFnOrConstructorType(TsFnOrConstructorType::TsFnType(
+                            fn_type,
+                        ))) => fn_type.params.first().and_then(|p| self.fn_param_type(p)),
+                        _ => None,
+                    };
+                    (s.span, props_type)
+                }
+            };
+
+            out.push(ComponentSlotMeta {
+                name: key.to_string(),
+                props_type,
+                description: self.description(span),
+                span,
+            });
+        }
+    }
+
+    /// `defineModel('name', { required: true })` or `defineModel<T>()`
+    fn model_from_define_model(
+        &mut self,
+        call_expr: &CallExpr,
+        stmt_span: Span,
+    ) -> ComponentModelMeta {
+        let first_arg = call_expr.args.first().map(|a| unwrap_ts_node_expr(&a.expr));
+        let (name, options) = match first_arg {
+            Some(Expr::Lit(Lit::Str(s))) => (
+                s.value.to_string(),
+                call_expr.args.get(1).map(|a| unwrap_ts_node_expr(&a.expr)),
+            ),
+            _ => (MODEL_VALUE.to_string(), first_arg),
+        };
+
+        let mut model = ComponentModelMeta {
+            name,
+            ts_type: None,
+            runtime_types: vec![],
+            required: false,
+            default: None,
+            description: self.description(stmt_span),
+            span: call_expr.span,
+        };
+
+        // Reuse the runtime prop options reading
+        if let Some(options @ Expr::Object(_)) = options {
+            let mut prop_meta = ComponentPropMeta {
+                name: String::new(),
+                ts_type: None,
+                runtime_types: vec![],
+                required: false,
+                default: None,
+                description: None,
+                span: call_expr.span,
+            };
+            self.read_runtime_prop_options(options, &mut prop_meta);
+            model.ts_type = prop_meta.ts_type;
+            model.runtime_types = prop_meta.runtime_types;
+            model.required = prop_meta.required;
+            model.default = prop_meta.default;
+        }
+
+        if let Some(ts_type) = type_arg(call_expr) {
+            let scope = self.ctx.root_scope();
+            let types = infer_runtime_type_type(self.ctx, ts_type, &scope.borrow(), false);
+            if !types.contains(Types::Unknown) {
+                model.runtime_types = runtime_types_to_strings(types);
+            }
+            model.ts_type = self.text(ts_type.span());
+        }
+
+        model
+    }
+
+    /// `defineExpose({ focus, reset: doReset })`
+    fn exposed_from_object(&self, obj: &ObjectLit, out: &mut Vec<ComponentExposedMeta>) {
+        for prop in obj.props.iter() {
+            let PropOrSpread::Prop(prop) = prop else {
+                continue;
+            };
+
+            let name = match **prop {
+                Prop::KeyValue(ref key_value) => resolve_object_key(&key_value.key),
+                Prop::Shorthand(ref ident) => Some(ident.sym.to_owned()),
+                Prop::Method(ref method) => resolve_object_key(&method.key),
+                Prop::Getter(ref getter) => resolve_object_key(&getter.key),
+                _ => None,
+            };
+            let Some(name) = name else {
+                continue;
+            };
+
+            let span = prop.span();
+            out.push(ComponentExposedMeta {
+                name: name.to_string(),
+                description: self.description(span),
+                span,
+            });
+        }
+    }
+
+    /// Options API `expose: ['focus', 'reset']`
+    fn exposed_from_array(&self, arr: &ArrayLit, out: &mut Vec<ComponentExposedMeta>) {
+        for (name, span) in string_array_items(arr) {
+            out.push(ComponentExposedMeta {
+                name,
+                description: self.description(span),
+                span,
+            });
+        }
+    }
+
+    //
+    // Source text helpers
+    //
+
+    /// Source text of a span. Returns `None` for synthesized nodes
+    fn text(&self, span: Span) -> Option<String> {
+        if span.is_dummy() {
+            return None;
+        }
+
+        // Spans are 1-based
+        let lo = (span.lo.0 as usize).checked_sub(1)?;
+        let hi = (span.hi.0 as usize).checked_sub(1)?;
+        self.source.get(lo..hi).map(str::to_owned)
+    }
+
+    /// Text of a `/** JSDoc */` comment directly preceding the span
+    fn description(&self, span: Span) -> Option<String> {
+        if span.is_dummy() {
+            return None;
+        }
+
+        let leading = self.comments?.get_leading(span.lo)?;
+        let comment = leading.last()?;
+        if comment.kind != CommentKind::Block {
+            return None;
+        }
+
+        // Comment text does not include `/*` and `*/`
+        let comment = comment.text.strip_prefix('*')?;
+
+        let description = comment
+            .lines()
+            .map(|line| {
+                let line = line.trim();
+                line.strip_prefix('*').unwrap_or(line).trim()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let description = description.trim();
+
+        (!description.is_empty()).then(|| description.to_owned())
+    }
+
+    /// `(e: MouseEvent): void` from `onClick(e: MouseEvent): void`
+    fn method_signature_type(&self, signature: &TsMethodSignature) -> Option<String> {
+        let text = self.text(Span::new(signature.key.span_hi(), signature.span.hi))?;
+        let text = text.trim_start().trim_start_matches('?');
+        Some(
+            text.trim()
+                .trim_end_matches([';', ','])
+                .trim_end()
+                .to_owned(),
+        )
+    }
+
+    fn fn_param_type(&self, param: &TsFnParam) -> Option<String> {
+        let type_ann = match param {
+            TsFnParam::Ident(ident) => ident.type_ann.as_ref(),
+            TsFnParam::Array(arr) => arr.type_ann.as_ref(),
+            TsFnParam::Rest(rest) => rest.type_ann.as_ref(),
+            TsFnParam::Object(obj) => obj.type_ann.as_ref(),
+        }?;
+        self.text(type_ann.type_ann.span())
+    }
+
+    fn pat_type(&self, pat: &Pat) -> Option<String> {
+        let type_ann = match pat {
+            Pat::Ident(ident) => ident.type_ann.as_ref(),
+            Pat::Array(arr) => arr.type_ann.as_ref(),
+            Pat::Rest(rest) => rest.type_ann.as_ref(),
+            Pat::Object(obj) => obj.type_ann.as_ref(),
+            Pat::Assign(assign) => return self.pat_type(&assign.left),
+            _ => None,
+        }?;
+        self.text(type_ann.type_ann.span())
+    }
+}
+
+/// Collects `<slot>`s used in the template
+fn collect_template_slots(nodes: &[Node], out: &mut Vec<ComponentSlotMeta>) {
+    fn visit_element(element: &ElementNode, out: &mut Vec<ComponentSlotMeta>) {
+        if element.starting_tag.tag_name == "slot" {
+            let mut name = Some("default".to_owned());
+            for attr in element.starting_tag.attributes.iter() {
+                match attr {
+                    AttributeOrBinding::RegularAttribute {
+                        name: attr_name,
+                        value,
+                        ..
+                    } if attr_name == "name" => {
+                        name = Some(value.to_string());
+                    }
+                    AttributeOrBinding::VBind(v_bind) if matches!(v_bind.argument, Some(StrOrExpr::Str(ref s)) if s == "name") =>
+                    {
+                        // Dynamic slot names cannot be documented, but their fallback content can have slots
+                        name = None;
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(name) = name {
+                if !out.iter().any(|slot| slot.name == name) {
+                    out.push(ComponentSlotMeta {
+                        name,
+                        props_type: None,
+                        description: None,
+                        span: element.span,
+                    });
+                }
+            }
+        }
+
+        collect_template_slots(&element.children, out);
+    }
+
+    for node in nodes {
+        match node {
+            Node::Element(element) => visit_element(element, out),
+            Node::ConditionalSeq(seq) => {
+                visit_element(&seq.if_node.node, out);
+                for else_if in seq.else_if_nodes.iter() {
+                    visit_element(&else_if.node, out);
+                }
+                if let Some(ref else_node) = seq.else_node {
+                    visit_element(else_node, out);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Macro name and call, e.g. `defineProps` in `defineProps<T>()`
+fn as_macro_call(expr: &Expr) -> Option<(&FervidAtom, &CallExpr)> {
+    let Expr::Call(call_expr) = unwrap_ts_node_expr(expr) else {
+        return None;
+    };
+    let Callee::Expr(ref callee) = call_expr.callee else {
+        return None;
+    };
+    let Expr::Ident(ref ident) = **callee else {
+        return None;
+    };
+
+    Some((&ident.sym, call_expr))
+}
+
+#[inline]
+fn type_arg(call_expr: &CallExpr) -> Option<&TsType> {
+    call_expr
+        .type_args
+        .as_ref()
+        .and_then(|t| t.params.first())
+        .map(|t| t.as_ref())
+}
+
+fn find_object_prop<'o>(obj: &'o ObjectLit, key: &FervidAtom) -> Option<&'o Prop> {
+    obj.props.iter().find_map(|prop| {
+        let PropOrSpread::Prop(prop) = prop else {
+            return None;
+        };
+
+        let prop_key = match **prop {
+            Prop::KeyValue(ref key_value) => resolve_object_key(&key_value.key),
+            Prop::Shorthand(ref ident) => Some(ident.sym.to_owned()),
+            Prop::Method(ref method) => resolve_object_key(&method.key),
+            Prop::Getter(ref getter) => resolve_object_key(&getter.key),
+            _ => None,
+        };
+
+        (prop_key.as_ref() == Some(key)).then_some(prop.as_ref())
+    })
+}
+
+fn find_object_value<'o>(obj: &'o ObjectLit, key: &FervidAtom) -> Option<&'o Expr> {
+    match find_object_prop(obj, key)? {
+        Prop::KeyValue(key_value) => Some(&key_value.value),
+        _ => None,
+    }
+}
+
+fn string_array_items(arr: &ArrayLit) -> impl Iterator<Item = (String, Span)> + '_ {
+    arr.elems.iter().flatten().filter_map(|elem| {
+        get_string_expr(&elem.expr).map(|name| (name.to_string(), elem.expr.span()))
+    })
+}
+
+fn runtime_types_to_strings(types: TypesSet) -> Vec<String> {
+    types
+        .into_iter()
+        .map(|t| <&'static str>::from(t).to_owned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::{ElementKind, SfcScriptLang, StartingTag, VBindDirective};
+    use swc_core::{
+        common::{BytePos, DUMMY_SP},
+        ecma::ast::Ident,
+    };
+
+    use super::*;
+    use crate::test_utils::parser::parse_typescript_module;
+
+    #[test]
+    fn it_extracts_type_based_macros() {
+        let source = r#"
+interface Props {
+  /** Text of the button */
+  label: string
+  /**
+   * Size of the button
+   */
+  size?: 'sm' | 'md'
+  onClick(e: MouseEvent): void
+}
+
+const props = withDefaults(defineProps<Props>(), {
+  size: 'md'
+})
+
+const emit = defineEmits<{
+  /** Fired on change */
+  change: [id: number, value: string]
+  close: []
+}>()
+
+defineSlots<{
+  /** Main content */
+  default(props: { msg: string }): any
+}>()
+
+/** Checked state */
+const checked = defineModel<boolean>('checked', { required: true })
+
+defineExpose({
+  /** Focuses the button */
+  focus,
+})
+"#;
+        let meta = extract(source, None);
+
+        assert_eq!(meta.name.as_deref(), Some("Button"));
+
+        assert_eq!(meta.props.len(), 3);
+        let label = &meta.props[0];
+        assert_eq!(label.name, "label");
+        assert_eq!(label.ts_type.as_deref(), Some("string"));
+        assert_eq!(label.runtime_types, vec!["String"]);
+        assert!(label.required);
+        assert_eq!(label.default, None);
+        assert_eq!(label.description.as_deref(), Some("Text of the button"));
+
+        let size = &meta.props[1];
+        assert_eq!(size.name, "size");
+        assert_eq!(size.ts_type.as_deref(), Some("'sm' | 'md'"));
+        assert!(!size.required);
+        assert_eq!(size.default.as_deref(), Some("'md'"));
+        assert_eq!(size.description.as_deref(), Some("Size of the button"));
+
+        let on_click = &meta.props[2];
+        assert_eq!(on_click.ts_type.as_deref(), Some("(e: MouseEvent): void"));
+        assert_eq!(on_click.runtime_types, vec!["Function"]);
+
+        assert_eq!(meta.events.len(), 2);
+        assert_eq!(meta.events[0].name, "change");
+        assert_eq!(meta.events[0].payload, vec!["number", "string"]);
+        assert_eq!(
+            meta.events[0].description.as_deref(),
+            Some("Fired on change")
+        );
+        assert_eq!(meta.events[1].name, "close");
+        assert!(meta.events[1].payload.is_empty());
+
+        assert_eq!(meta.slots.len(), 1);
+        assert_eq!(meta.slots[0].name, "default");
+        assert_eq!(meta.slots[0].props_type.as_deref(), Some("{ msg: string }"));
+        assert_eq!(meta.slots[0].description.as_deref(), Some("Main content"));
+
+        assert_eq!(meta.models.len(), 1);
+        assert_eq!(meta.models[0].name, "checked");
+        assert_eq!(meta.models[0].ts_type.as_deref(), Some("boolean"));
+        assert_eq!(meta.models[0].runtime_types, vec!["Boolean"]);
+        assert!(meta.models[0].required);
+        assert_eq!(meta.models[0].description.as_deref(), Some("Checked state"));
+
+        assert_eq!(meta.exposed.len(), 1);
+        assert_eq!(meta.exposed[0].name, "focus");
+        assert_eq!(
+            meta.exposed[0].description.as_deref(),
+            Some("Focuses the button")
+        );
+    }
+
+    #[test]
+    fn it_extracts_call_signature_emits_and_destructure_defaults() {
+        let source = r#"
+const { count = 1 } = defineProps<{ count?: number }>()
+const emit = defineEmits<{
+  (e: 'inc' | 'dec', by: number): void
+}>()
+defineOptions({ name: 'MyCounter' })
+"#;
+        let meta = extract(source, None);
+
+        assert_eq!(meta.name.as_deref(), Some("MyCounter"));
+        assert_eq!(meta.props[0].default.as_deref(), Some("1"));
+        assert_eq!(meta.props[0].runtime_types, vec!["Number"]);
+
+        let names: Vec<&str> = meta.events.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["inc", "dec"]);
+        assert_eq!(meta.events[1].payload, vec!["number"]);
+    }
+
+    #[test]
+    fn it_extracts_runtime_declarations() {
+        let source = r#"
+defineProps({
+  /** The title */
+  title: { type: String, required: true },
+  items: { type: Array as PropType<Item[]>, default: () => [] },
+  value: [String, Number],
+})
+defineEmits(['save', 'cancel'])
+const model = defineModel({ type: Number, default: 0 })
+"#;
+        let meta = extract(source, None);
+
+        assert_eq!(meta.props.len(), 3);
+        assert_eq!(meta.props[0].runtime_types, vec!["String"]);
+        assert!(meta.props[0].required);
+        assert_eq!(meta.props[0].description.as_deref(), Some("The title"));
+        assert_eq!(meta.props[1].runtime_types, vec!["Array"]);
+        assert_eq!(meta.props[1].ts_type.as_deref(), Some("Item[]"));
+        assert_eq!(meta.props[1].default.as_deref(), Some("() => []"));
+        assert_eq!(meta.props[2].runtime_types, vec!["String", "Number"]);
+
+        let names: Vec<&str> = meta.events.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["save", "cancel"]);
+
+        assert_eq!(meta.models[0].name, "modelValue");
+        assert_eq!(meta.models[0].runtime_types, vec!["Number"]);
+        assert_eq!(meta.models[0].default.as_deref(), Some("0"));
+    }
+
+    #[test]
+    fn it_extracts_options_api() {
+        let source = r#"
+export default {
+  name: 'Legacy',
+  props: ['foo'],
+  emits: { submit: (payload: string) => true },
+  expose: ['reset'],
+}
+"#;
+        let meta = extract("", Some(source));
+
+        assert_eq!(meta.name.as_deref(), Some("Legacy"));
+        assert_eq!(meta.props[0].name, "foo");
+        assert_eq!(meta.events[0].name, "submit");
+        assert_eq!(meta.events[0].payload, vec!["string"]);
+        assert_eq!(meta.exposed[0].name, "reset");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn it_round_trips_serde() {
+        use crate::{OptionsApiBindings, SetupBinding};
+        use fervid_core::BindingTypes;
+        use swc_core::common::Span;
+
+        let meta = extract(
+            r#"
+/** The title */
+defineProps<{ title: string; count?: number }>()
+const emit = defineEmits<{ (e: 'change', value: number): void }>()
+defineSlots<{ default(props: { item: string }): any }>()
+const checked = defineModel<boolean>('checked', { default: false })
+defineExpose({ reset() {} })
+"#,
+            None,
+        );
+        let serialized = serde_json::to_string(&meta).expect("Should serialize");
+        assert!(serialized.contains(r#""propsType":"#));
+        let deserialized: ComponentMeta =
+            serde_json::from_str(&serialized).expect("Should deserialize");
+        assert_eq!(meta, deserialized);
+
+        let bindings = OptionsApiBindings {
+            data: vec!["foo".into()],
+            setup: vec![SetupBinding {
+                sym: "bar".into(),
+                binding_type: BindingTypes::SetupMaybeRef,
+                span: Span::new(BytePos(1), BytePos(4)),
+            }],
+            name: Some("Comp".into()),
+            ..Default::default()
+        };
+        let serialized = serde_json::to_string(&bindings).expect("Should serialize");
+        let deserialized: OptionsApiBindings =
+            serde_json::from_str(&serialized).expect("Should deserialize");
+        assert_eq!(bindings, deserialized);
+    }
+
+    #[test]
+    fn it_collects_template_slots_inside_dynamic_slots() {
+        // <slot :name="dynamic"><slot name="fallback" /></slot><slot />
+        let fallback = slot(
+            Some(AttributeOrBinding::RegularAttribute {
+                name: "name".into(),
+                value: "fallback".into(),
+                span: DUMMY_SP,
+            }),
+            vec![],
+        );
+        let dynamic = slot(
+            Some(AttributeOrBinding::VBind(VBindDirective {
+                argument: Some(StrOrExpr::Str("name".into())),
+                value: Box::new(Expr::Ident(Ident::from("dynamic"))),
+                is_camel: false,
+                is_prop: false,
+                is_attr: false,
+                span: DUMMY_SP,
+            })),
+            vec![fallback],
+        );
+
+        let mut slots = Vec::new();
+        collect_template_slots(&[dynamic, slot(None, vec![])], &mut slots);
+
+        let names: Vec<_> = slots.iter().map(|slot| slot.name.as_str()).collect();
+        assert_eq!(names, vec!["fallback", "default"]);
+    }
+
+    fn slot(attr: Option<AttributeOrBinding>, children: Vec<Node>) -> Node {
+        Node::Element(ElementNode {
+            starting_tag: StartingTag {
+                tag_name: "slot".into(),
+                attributes: attr.into_iter().collect(),
+                directives: None,
+            },
+            children,
+            template_scope: 0,
+            kind: ElementKind::Element,
+            patch_hints: Default::default(),
+            span: DUMMY_SP,
+        })
+    }
+
+    fn extract(script_setup: &str, script_options: Option<&str>) -> ComponentMeta {
+        let comments = SingleThreadedComments::default();
+        let script_block = |source: &str, is_setup: bool| {
+            let (module, module_comments) = parse_typescript_module(source, 1, Default::default())
+                .expect("Expected input to be parseable");
+
+            let (leading, _) = module_comments.take_all();
+            for (pos, leading) in leading.take() {
+                comments.add_leading_comments(pos, leading);
+            }
+
+            SfcScriptBlock {
+                content: Box::new(module),
+                lang: SfcScriptLang::Typescript,
+                is_setup,
+                span: DUMMY_SP,
+            }
+        };
+
+        let sfc_descriptor = SfcDescriptor {
+            template: None,
+            script_legacy: script_options.map(|s| script_block(s, false)),
+            script_setup: (!script_setup.is_empty()).then(|| script_block(script_setup, true)),
+            styles: vec![],
+            custom_blocks: vec![],
+            vapor: false,
+        };
+
+        let mut errors = Vec::new();
+        let meta = extract_component_meta(
+            &sfc_descriptor,
+            ComponentMetaOptions {
+                filename: "src/components/Button.vue",
+                source: script_options.unwrap_or(script_setup),
+                comments: Some(&comments),
+            },
+            &mut errors,
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert!(BytePos(1) <= meta.props.first().map_or(BytePos(1), |p| p.span.lo));
+
+        meta
+    }
+}
@@ -0,0 +1,1970 @@
+//! Codemod rewriting an Options API `<script>` into a Composition API `<script setup>`.
+//!
+//! The options of `export default` become the statements of `<script setup>`:
+//! - `props`, `emits` and `expose` become `defineProps()`, `defineEmits()` and `defineExpose()`;
+//! - `data` entries become `ref()`s, `computed` entries become `computed()`s, `methods` become functions;
+//! - `watch`, `inject`, `provide` and the lifecycle hooks become their Composition API counterparts,
+//!   the code of `beforeCreate` and `created` is inlined;
+//! - `name`, `inheritAttrs` and the other options go to `defineOptions()`.
+//!
+//! `this.foo` is rewritten depending on what `foo` is, e.g. `foo.value` for `data` and `computed`,
+//! `props.foo` for `props` and `foo` for `methods`. Everything which cannot be converted
+//! is reported as a [`MigrationError`] and left as-is for a manual review.
+
+use fervid_core::{
+    fervid_atom, AttributeOrBinding, ElementNode, FervidAtom, IntoIdent, Node, StrOrExpr,
+};
+use swc_core::{
+    common::{Span, Spanned, DUMMY_SP},
+    ecma::{
+        ast::{
+            ArrayLit, ArrowExpr, AssignTarget, AwaitExpr, BindingIdent, BlockStmt, BlockStmtOrExpr,
+            Bool, CallExpr, Callee, Class, ClassDecl, ComputedPropName, Decl, Expr, ExprOrSpread,
+            ExprStmt, FnDecl, Function, Ident, IdentName, ImportDecl, ImportNamedSpecifier,
+            ImportPhase, ImportSpecifier, KeyValueProp, Lit, MemberExpr, MemberProp, Module,
+            ModuleDecl, ModuleExportName, ModuleItem, ObjectLit, ParenExpr, Pat, Prop, PropName,
+            PropOrSpread, ReturnStmt, SimpleAssignTarget, Stmt, Str, VarDecl, VarDeclKind,
+            VarDeclarator,
+        },
+        visit::{Visit, VisitMut, VisitMutWith, VisitWith},
+    },
+};
+
+use crate::{
+    atoms::{DEFINE_COMPONENT, VUE},
+    error::{MigrationError, MigrationErrorKind, TransformError},
+    OptionsApiBindings,
+};
+
+use super::{
+    options_api::{analyze_default_export, find_default_export_obj},
+    setup::await_detection::detect_await_module_item,
+    utils::{resolve_object_key, unroll_paren_seq},
+};
+
+/// Options API hooks and their Composition API counterparts
+const LIFECYCLE_HOOKS: &[(&str, &str)] = &[
+    ("beforeMount", "onBeforeMount"),
+    ("mounted", "onMounted"),
+    ("beforeUpdate", "onBeforeUpdate"),
+    ("updated", "onUpdated"),
+    ("activated", "onActivated"),
+    ("deactivated", "onDeactivated"),
+    ("beforeUnmount", "onBeforeUnmount"),
+    ("beforeDestroy", "onBeforeUnmount"),
+    ("unmounted", "onUnmounted"),
+    ("destroyed", "onUnmounted"),
+    ("errorCaptured", "onErrorCaptured"),
+    ("renderTracked", "onRenderTracked"),
+    ("renderTriggered", "onRenderTriggered"),
+    ("serverPrefetch", "onServerPrefetch"),
+];
+
+/// Options which have no `<script setup>` counterpart
+const UNSUPPORTED_OPTIONS: &[&str] = &["setup", "render", "template", "filters", "model"];
+
+/// Rewrites the module of an Options API `<script>` into the module of `<script setup>`.
+///
+/// Returns the bindings of the migrated component, or `None` when the module
+/// has no `export default` object, the module is left untouched then.
+pub fn migrate_options_api(
+    module: &mut Module,
+    errors: &mut Vec<TransformError>,
+) -> Option<OptionsApiBindings> {
+    let original_module = module.clone();
+    let is_define_component = module.body.iter().any(|module_item| {
+        matches!(
+            module_item,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export_default))
+                if matches!(unroll_paren_seq(&export_default.expr), Expr::Call(call_expr)
+                    if matches!(call_expr.callee, Callee::Expr(ref callee)
+                        if matches!(**callee, Expr::Ident(ref ident) if ident.sym == *DEFINE_COMPONENT)))
+        )
+    });
+
+    let Some(options) = find_default_export_obj(module) else {
+        *module = original_module;
+        errors.push(migration_error(
+            module.span,
+            MigrationErrorKind::NoDefaultExport,
+        ));
+        return None;
+    };
+
+    let mut bindings = OptionsApiBindings::default();
+    analyze_default_export(&options, &mut bindings);
+
+    let mut migration = Migration {
+        bindings,
+        errors: Some(errors),
+        ..Default::default()
+    };
+
+    for prop in options.props {
+        migration.migrate_option(prop);
+    }
+
+    let statements = migration.finish();
+
+    if is_define_component {
+        remove_vue_import(module, &DEFINE_COMPONENT);
+    }
+    add_vue_imports(module, &migration.vue_imports);
+    module
+        .body
+        .extend(statements.into_iter().map(ModuleItem::Stmt));
+    migration.check_name_conflicts(module);
+
+    Some(std::mem::take(&mut migration.bindings))
+}
+
+/// Spans of the `$data.` and `$options.` prefixes of the template expressions,
+/// which are removed because `data` and `methods` become the `<script setup>` bindings.
+///
+/// Only the migrated members are covered, i.e. `$data.foo` of a `data` entry
+/// and `$options.foo` of a method. Other members like `$options.name` are kept.
+pub fn template_reference_prefixes(roots: &[Node], bindings: &OptionsApiBindings) -> Vec<Span> {
+    let mut finder = TemplateReferenceFinder {
+        bindings,
+        prefixes: Vec::new(),
+    };
+    for node in roots {
+        finder.visit_node(node);
+    }
+    finder.prefixes
+}
+
+/// How `this.foo` is rewritten
+#[derive(Clone, Copy)]
+enum BindingKind {
+    /// `foo.value`, for `data` and `computed`
+    Ref,
+    /// `props.foo`
+    Prop,
+    /// `foo`, for `methods` and `inject`
+    Plain,
+}
+
+#[derive(Default)]
+struct Migration<'e> {
+    bindings: OptionsApiBindings,
+    /// Vue APIs used by the generated code, e.g. `ref` or `onMounted`
+    vue_imports: Vec<&'static str>,
+    define_options: Vec<PropOrSpread>,
+    define_props: Option<Box<Expr>>,
+    define_emits: Option<Box<Expr>>,
+    define_expose: Vec<FervidAtom>,
+    /// Aliases of `components` and `directives`
+    aliases: Vec<Stmt>,
+    inject: Vec<Stmt>,
+    data: Vec<Stmt>,
+    computed: Vec<Stmt>,
+    methods: Vec<Stmt>,
+    watch: Vec<Stmt>,
+    provide: Vec<Stmt>,
+    created: Vec<Stmt>,
+    hooks: Vec<Stmt>,
+    /// Names of `this.$refs.foo`, declared as the template refs
+    template_refs: Vec<FervidAtom>,
+    /// Events of `this.$emit('foo')`, used when there is no `emits` option
+    emitted_events: Vec<FervidAtom>,
+    uses_props: bool,
+    uses_emit: bool,
+    uses_attrs: bool,
+    uses_slots: bool,
+    errors: Option<&'e mut Vec<TransformError>>,
+}
+
+/// Value of an option, i.e. `foo: value` or `foo() {}`
+enum OptionValue {
+    Expr(Box<Expr>),
+    Method(Box<Function>),
+}
+
+impl<'e> Migration<'e> {
+    fn migrate_option(&mut self, prop: PropOrSpread) {
+        let span = prop.span();
+        let PropOrSpread::Prop(prop) = prop else {
+            return self.report(span, MigrationErrorKind::UnsupportedOption);
+        };
+
+        let (key, value) = match *prop {
+            Prop::KeyValue(key_value) => match resolve_object_key(&key_value.key) {
+                Some(key) => (key, OptionValue::Expr(key_value.value)),
+                None => return self.report(span, MigrationErrorKind::UnsupportedOption),
+            },
+            Prop::Method(method) => match resolve_object_key(&method.key) {
+                Some(key) => (key, OptionValue::Method(method.function)),
+                None => return self.report(span, MigrationErrorKind::UnsupportedOption),
+            },
+            Prop::Shorthand(ident) => (
+                ident.sym.to_owned(),
+                OptionValue::Expr(Box::new(Expr::Ident(ident))),
+            ),
+            _ => return self.report(span, MigrationErrorKind::UnsupportedOption),
+        };
+
+        match key.as_str() {
+            "props" => self.define_props = Some(self.option_expr(value)),
+            "emits" => self.define_emits = Some(self.option_expr(value)),
+            "expose" => self.migrate_expose(value, span),
+            "data" => self.migrate_data(value, span),
+            "computed" => self.migrate_computed(value, span),
+            "methods" => self.migrate_methods(value, span),
+            "watch" => self.migrate_watch(value, span),
+            "inject" => self.migrate_inject(value, span),
+            "provide" => self.migrate_provide(value, span),
+            "components" => self.migrate_aliases(value, span, false),
+            "directives" => self.migrate_aliases(value, span, true),
+            "beforeCreate" | "created" => self.migrate_created(value, span),
+            "mixins" | "extends" => {
+                self.report(span, MigrationErrorKind::Mixins);
+                self.define_options.push(option_prop(key, value));
+            }
+            _ if UNSUPPORTED_OPTIONS.contains(&key.as_str()) => {
+                self.report(span, MigrationErrorKind::UnsupportedOption)
+            }
+            _ => match LIFECYCLE_HOOKS
+                .iter()
+                .find(|(hook, _)| *hook == key.as_str())
+            {
+                Some((_, composition_hook)) => self.migrate_hook(composition_hook, value),
+                None => self.define_options.push(option_prop(key, value)),
+            },
+        }
+    }
+
+    /// `expose: ['foo']` -> `defineExpose({ foo })`
+    fn migrate_expose(&mut self, value: OptionValue, span: Span) {
+        let OptionValue::Expr(expr) = value else {
+            return self.report(span, MigrationErrorKind::UnsupportedOption);
+        };
+        let Expr::Array(array_lit) = *expr else {
+            return self.report(span, MigrationErrorKind::UnsupportedOption);
+        };
+
+        for elem in array_lit.elems.into_iter().flatten() {
+            match *elem.expr {
+                Expr::Lit(Lit::Str(s)) if elem.spread.is_none() => {
+                    self.define_expose.push(s.value.to_owned())
+                }
+                _ => self.report(elem.expr.span(), MigrationErrorKind::UnsupportedOption),
+            }
+        }
+    }
+
+    /// `data() { return { foo: 1 } }` -> `const foo = ref(1)`.
+    /// Statements before `return` are kept
+    fn migrate_data(&mut self, value: OptionValue, span: Span) {
+        let Some((mut stmts, return_expr)) = self.function_return(value, span) else {
+            return;
+        };
+        let Expr::Object(obj_lit) = *return_expr else {
+            return self.report(span, MigrationErrorKind::DataEntry);
+        };
+
+        for stmt in stmts.iter_mut() {
+            self.rewrite_this(stmt);
+        }
+        self.data.append(&mut stmts);
+
+        for prop in obj_lit.props {
+            let prop_span = prop.span();
+            let PropOrSpread::Prop(prop) = prop else {
+                self.report(prop_span, MigrationErrorKind::DataEntry);
+                continue;
+            };
+            let Prop::KeyValue(key_value) = *prop else {
+                self.report(prop_span, MigrationErrorKind::DataEntry);
+                continue;
+            };
+            let Some(key) = resolve_object_key(&key_value.key).filter(|key| is_identifier(key))
+            else {
+                self.report(prop_span, MigrationErrorKind::DataEntry);
+                continue;
+            };
+
+            let mut value = key_value.value;
+            self.rewrite_this(&mut value);
+            let init = self.vue_call("ref", vec![*value]);
+            self.data.push(const_stmt(key, init));
+        }
+    }
+
+    /// `computed: { foo() {} }` -> `const foo = computed(() => {})`
+    fn migrate_computed(&mut self, value: OptionValue, span: Span) {
+        let Some(entries) = self.option_entries(value, span) else {
+            return;
+        };
+
+        for (key, value, entry_span) in entries {
+            let getter = match value {
+                OptionValue::Method(function) => Some(Expr::Arrow(function_to_arrow(*function))),
+                OptionValue::Expr(expr) => match *expr {
+                    Expr::Object(obj_lit) => self.getter_setter(obj_lit),
+                    expr => function_expr_to_arrow(expr).map(Expr::Arrow),
+                },
+            };
+
+            let Some(mut getter) = getter else {
+                self.report(entry_span, MigrationErrorKind::UnsupportedOption);
+                continue;
+            };
+            self.rewrite_this(&mut getter);
+            let init = self.vue_call("computed", vec![getter]);
+            self.computed.push(const_stmt(key, init));
+        }
+    }
+
+    /// `{ get() {}, set(v) {} }` -> `{ get: () => {}, set: (v) => {} }`
+    fn getter_setter(&mut self, obj_lit: ObjectLit) -> Option<Expr> {
+        let mut props = Vec::with_capacity(obj_lit.props.len());
+
+        for prop in obj_lit.props {
+            let PropOrSpread::Prop(prop) = prop else {
+                return None;
+            };
+            let (key, arrow) = match *prop {
+                Prop::Method(method) => (method.key, function_to_arrow(*method.function)),
+                Prop::KeyValue(key_value) => {
+                    (key_value.key, function_expr_to_arrow(*key_value.value)?)
+                }
+                _ => return None,
+            };
+
+            props.push(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                key,
+                value: Box::new(Expr::Arrow(arrow)),
+            }))));
+        }
+
+        Some(Expr::Object(ObjectLit {
+            span: obj_lit.span,
+            props,
+        }))
+    }
+
+    /// `methods: { foo() {} }` -> `function foo() {}`
+    fn migrate_methods(&mut self, value: OptionValue, span: Span) {
+        let Some(entries) = self.option_entries(value, span) else {
+            return;
+        };
+
+        for (key, value, _) in entries {
+            let mut stmt = match value {
+                OptionValue::Method(function) => fn_decl_stmt(key, function),
+                OptionValue::Expr(expr) => match *expr {
+                    Expr::Fn(fn_expr) => fn_decl_stmt(key, fn_expr.function),
+                    expr => const_stmt(key, expr),
+                },
+            };
+            self.rewrite_this(&mut stmt);
+            self.methods.push(stmt);
+        }
+    }
+
+    /// `watch: { foo(v) {} }` -> `watch(foo, (v) => {})`
+    fn migrate_watch(&mut self, value: OptionValue, span: Span) {
+        let Some(entries) = self.option_entries(value, span) else {
+            return;
+        };
+
+        for (key, value, entry_span) in entries {
+            let Some(source) = self.watch_source(&key) else {
+                self.report(entry_span, MigrationErrorKind::UnsupportedOption);
+                continue;
+            };
+
+            let mut options = None;
+            let handler = match value {
+                OptionValue::Method(function) => Some(Expr::Arrow(function_to_arrow(*function))),
+                OptionValue::Expr(expr) => match *expr {
+                    Expr::Object(obj_lit) => {
+                        let (handler, rest) = split_watch_options(obj_lit);
+                        options = (!rest.props.is_empty()).then_some(rest);
+                        handler
+                    }
+                    expr => watch_handler(expr),
+                },
+            };
+
+            let Some(handler) = handler else {
+                self.report(entry_span, MigrationErrorKind::UnsupportedOption);
+                continue;
+            };
+
+            let mut args = vec![source, handler];
+            args.extend(options.map(Expr::Object));
+            let mut call = self.vue_call("watch", args);
+            self.rewrite_this(&mut call);
+            self.watch.push(expr_stmt(call));
+        }
+    }
+
+    /// Source of `watch` for a key, e.g. `foo` for a `data` entry or `() => props.foo.bar`
+    fn watch_source(&mut self, key: &str) -> Option<Expr> {
+        let mut segments = key.split('.');
+        let first = FervidAtom::from(segments.next()?);
+        let rest: Vec<&str> = segments.collect();
+        if rest.iter().any(|segment| !is_identifier(segment)) {
+            return None;
+        }
+
+        let (mut expr, is_ref) = match self.binding_kind(&first)? {
+            BindingKind::Ref => (Expr::Ident(first.into_ident()), true),
+            BindingKind::Prop => {
+                self.uses_props = true;
+                (member(ident_expr("props"), first), false)
+            }
+            BindingKind::Plain => (Expr::Ident(first.into_ident()), false),
+        };
+
+        // A ref itself is a valid source, everything else needs a getter
+        if is_ref && rest.is_empty() {
+            return Some(expr);
+        }
+        if is_ref {
+            expr = member(expr, fervid_atom!("value"));
+        }
+        for segment in rest {
+            expr = member(expr, FervidAtom::from(segment));
+        }
+
+        Some(Expr::Arrow(arrow(
+            vec![],
+            BlockStmtOrExpr::Expr(Box::new(expr)),
+        )))
+    }
+
+    /// `inject: ['foo']` -> `const foo = inject('foo')`
+    fn migrate_inject(&mut self, value: OptionValue, span: Span) {
+        let OptionValue::Expr(expr) = value else {
+            return self.report(span, MigrationErrorKind::UnsupportedOption);
+        };
+
+        match *expr {
+            Expr::Array(array_lit) => {
+                for elem in array_lit.elems.into_iter().flatten() {
+                    match *elem.expr {
+                        Expr::Lit(Lit::Str(s)) if elem.spread.is_none() => {
+                            let init = self.vue_call("inject", vec![str_expr(s.value.to_owned())]);
+                            self.inject.push(const_stmt(s.value, init));
+                        }
+                        _ => self.report(elem.expr.span(), MigrationErrorKind::UnsupportedOption),
+                    }
+                }
+            }
+
+            Expr::Object(obj_lit) => {
+                for (key, value, entry_span) in self.object_entries(obj_lit) {
+                    let OptionValue::Expr(value) = value else {
+                        self.report(entry_span, MigrationErrorKind::UnsupportedOption);
+                        continue;
+                    };
+
+                    let args = match *value {
+                        // `foo: { from: 'bar', default: 'baz' }`
+                        Expr::Object(inject_options) => {
+                            let mut from = str_expr(key.to_owned());
+                            let mut default = None;
+                            for prop in inject_options.props {
+                                let PropOrSpread::Prop(prop) = prop else {
+                                    continue;
+                                };
+                                let Prop::KeyValue(key_value) = *prop else {
+                                    continue;
+                                };
+                                match resolve_object_key(&key_value.key).as_deref() {
+                                    Some("from") => from = *key_value.value,
+                                    Some("default") => default = Some(*key_value.value),
+                                    _ => {}
+                                }
+                            }
+
+                            let mut args = vec![from];
+                            if let Some(default) = default {
+                                // Functions are factories of the default value
+                                let is_factory = matches!(default, Expr::Fn(_) | Expr::Arrow(_));
+                                args.push(default);
+                                if is_factory {
+                                    args.push(bool_expr(true));
+                                }
+                            }
+                            args
+                        }
+
+                        // `foo: 'bar'` or `foo: symbol`
+                        from => vec![from],
+                    };
+
+                    let init = self.vue_call("inject", args);
+                    self.inject.push(const_stmt(key, init));
+                }
+            }
+
+            _ => self.report(span, MigrationErrorKind::UnsupportedOption),
+        }
+    }
+
+    /// `provide: { foo: 'bar' }` -> `provide('foo', 'bar')`.
+    /// The function form keeps the statements before `return`
+    fn migrate_provide(&mut self, value: OptionValue, span: Span) {
+        let (mut stmts, obj_lit) = match value {
+            OptionValue::Expr(expr) if matches!(*expr, Expr::Object(_)) => {
+                let Expr::Object(obj_lit) = *expr else {
+                    unreachable!()
+                };
+                (vec![], obj_lit)
+            }
+            value => {
+                let Some((stmts, return_expr)) = self.function_return(value, span) else {
+                    return;
+                };
+                let Expr::Object(obj_lit) = *return_expr else {
+                    return self.report(span, MigrationErrorKind::UnsupportedOption);
+                };
+                (stmts, obj_lit)
+            }
+        };
+
+        for prop in obj_lit.props {
+            let prop_span = prop.span();
+            let PropOrSpread::Prop(prop) = prop else {
+                self.report(prop_span, MigrationErrorKind::UnsupportedOption);
+                continue;
+            };
+            let (key, value) = match *prop {
+                Prop::KeyValue(KeyValueProp {
+                    key: PropName::Computed(ComputedPropName { expr, .. }),
+                    value,
+                }) => (*expr, *value),
+                Prop::KeyValue(key_value) => match resolve_object_key(&key_value.key) {
+                    Some(key) => (str_expr(key), *key_value.value),
+                    None => {
+                        self.report(prop_span, MigrationErrorKind::UnsupportedOption);
+                        continue;
+                    }
+                },
+                Prop::Shorthand(ident) => (str_expr(ident.sym.to_owned()), Expr::Ident(ident)),
+                _ => {
+                    self.report(prop_span, MigrationErrorKind::UnsupportedOption);
+                    continue;
+                }
+            };
+
+            let call = self.vue_call("provide", vec![key, value]);
+            stmts.push(expr_stmt(call));
+        }
+
+        for stmt in stmts.iter_mut() {
+            self.rewrite_this(stmt);
+        }
+        self.provide.append(&mut stmts);
+    }
+
+    /// `components: { Foo: Bar }` -> `const Foo = Bar`,
+    /// `directives: { focus: Focus }` -> `const vFocus = Focus`
+    fn migrate_aliases(&mut self, value: OptionValue, span: Span, is_directives: bool) {
+        let Some(entries) = self.option_entries(value, span) else {
+            return;
+        };
+
+        for (key, value, entry_span) in entries {
+            let OptionValue::Expr(value) = value else {
+                self.report(entry_span, MigrationErrorKind::UnsupportedOption);
+                continue;
+            };
+
+            let alias = if is_directives {
+                let camelized = camelize(&key);
+                let mut chars = camelized.chars();
+                let capitalized: String = chars
+                    .next()
+                    .map(|c| c.to_ascii_uppercase())
+                    .into_iter()
+                    .chain(chars)
+                    .collect();
+                FervidAtom::from(format!("v{capitalized}"))
+            } else {
+                FervidAtom::from(pascalize(&key))
+            };
+
+            // An imported component is available to the template as-is
+            if matches!(*value, Expr::Ident(ref ident) if ident.sym == alias) {
+                continue;
+            }
+
+            self.aliases.push(const_stmt(alias, *value));
+        }
+    }
+
+    /// Inlines the code of `beforeCreate` and `created`
+    fn migrate_created(&mut self, value: OptionValue, span: Span) {
+        let function = match value {
+            OptionValue::Method(function) => *function,
+            OptionValue::Expr(expr) => match *expr {
+                Expr::Fn(fn_expr) => *fn_expr.function,
+                Expr::Arrow(arrow_expr) => {
+                    let is_async = arrow_expr.is_async;
+                    let stmts = match *arrow_expr.body {
+                        BlockStmtOrExpr::BlockStmt(block_stmt) => block_stmt.stmts,
+                        BlockStmtOrExpr::Expr(expr) => vec![expr_stmt(*expr)],
+                    };
+                    return self.inline_stmts(stmts, is_async, span);
+                }
+                _ => return self.report(span, MigrationErrorKind::UnsupportedOption),
+            },
+        };
+
+        let stmts = function.body.map(|body| body.stmts).unwrap_or_default();
+        self.inline_stmts(stmts, function.is_async, span);
+    }
+
+    fn inline_stmts(&mut self, stmts: Vec<Stmt>, is_async: bool, span: Span) {
+        let mut stmts = stmts;
+        for stmt in stmts.iter_mut() {
+            self.rewrite_this(stmt);
+        }
+
+        let has_await = is_async
+            && stmts
+                .iter()
+                .any(|stmt| detect_await_module_item(&ModuleItem::Stmt(stmt.to_owned())));
+        if has_await {
+            self.report(span, MigrationErrorKind::AsyncCreated);
+        }
+
+        // `return` is not allowed at the top level, such code is wrapped into a function
+        if has_return(&stmts) {
+            let call = Expr::Call(CallExpr {
+                span: DUMMY_SP,
+                ctxt: Default::default(),
+                callee: Callee::Expr(Box::new(Expr::Paren(ParenExpr {
+                    span: DUMMY_SP,
+                    expr: Box::new(Expr::Arrow(ArrowExpr {
+                        is_async: has_await,
+                        ..arrow(vec![], BlockStmtOrExpr::BlockStmt(block(stmts)))
+                    })),
+                }))),
+                args: vec![],
+                type_args: None,
+            });
+            let call = if has_await {
+                Expr::Await(AwaitExpr {
+                    span: DUMMY_SP,
+                    arg: Box::new(call),
+                })
+            } else {
+                call
+            };
+            self.created.push(expr_stmt(call));
+            return;
+        }
+
+        self.created.append(&mut stmts);
+    }
+
+    /// `mounted() {}` -> `onMounted(() => {})`
+    fn migrate_hook(&mut self, composition_hook: &'static str, value: OptionValue) {
+        let mut callback = match value {
+            OptionValue::Method(function) => Expr::Arrow(function_to_arrow(*function)),
+            OptionValue::Expr(expr) => match *expr {
+                Expr::Fn(fn_expr) => Expr::Arrow(function_to_arrow(*fn_expr.function)),
+                expr => expr,
+            },
+        };
+        self.rewrite_this(&mut callback);
+        let call = self.vue_call(composition_hook, vec![callback]);
+        self.hooks.push(expr_stmt(call));
+    }
+
+    /// Assembles the `<script setup>` statements
+    fn finish(&mut self) -> Vec<Stmt> {
+        let mut stmts = Vec::new();
+
+        if !self.define_options.is_empty() {
+            let define_options = std::mem::take(&mut self.define_options);
+            stmts.push(expr_stmt(call_expr(
+                "defineOptions",
+                vec![Expr::Object(ObjectLit {
+                    span: DUMMY_SP,
+                    props: define_options,
+                })],
+            )));
+        }
+
+        stmts.append(&mut self.aliases);
+
+        if let Some(define_props) = self.define_props.take() {
+            let call = call_expr("defineProps", vec![*define_props]);
+            stmts.push(if self.uses_props {
+                const_stmt(fervid_atom!("props"), call)
+            } else {
+                expr_stmt(call)
+            });
+        }
+
+        let define_emits = self.define_emits.take().map(|v| *v).or_else(|| {
+            (!self.emitted_events.is_empty()).then(|| {
+                Expr::Array(ArrayLit {
+                    span: DUMMY_SP,
+                    elems: self
+                        .emitted_events
+                        .iter()
+                        .map(|event| Some(expr_or_spread(str_expr(event.to_owned()))))
+                        .collect(),
+                })
+            })
+        });
+        if let Some(define_emits) = define_emits {
+            let call = call_expr("defineEmits", vec![define_emits]);
+            stmts.push(if self.uses_emit {
+                const_stmt(fervid_atom!("emit"), call)
+            } else {
+                expr_stmt(call)
+            });
+        }
+
+        if self.uses_attrs {
+            let init = self.vue_call("useAttrs", vec![]);
+            stmts.push(const_stmt(fervid_atom!("attrs"), init));
+        }
+        if self.uses_slots {
+            let init = self.vue_call("useSlots", vec![]);
+            stmts.push(const_stmt(fervid_atom!("slots"), init));
+        }
+
+        stmts.append(&mut self.inject);
+        stmts.append(&mut self.data);
+
+        for template_ref in std::mem::take(&mut self.template_refs) {
+            if self.binding_kind(&template_ref).is_none() {
+                let init = self.vue_call("ref", vec![null_expr()]);
+                stmts.push(const_stmt(template_ref, init));
+            }
+        }
+
+        stmts.append(&mut self.computed);
+        stmts.append(&mut self.methods);
+        stmts.append(&mut self.watch);
+        stmts.append(&mut self.provide);
+        stmts.append(&mut self.created);
+        stmts.append(&mut self.hooks);
+
+        if !self.define_expose.is_empty() {
+            let props = self
+                .define_expose
+                .iter()
+                .map(|name| {
+                    PropOrSpread::Prop(Box::new(Prop::Shorthand(name.to_owned().into_ident())))
+                })
+                .collect();
+            stmts.push(expr_stmt(call_expr(
+                "defineExpose",
+                vec![Expr::Object(ObjectLit {
+                    span: DUMMY_SP,
+                    props,
+                })],
+            )));
+        }
+
+        stmts
+    }
+
+    /// Reports the top-level variables of the migrated module which share a name,
+    /// e.g. `const props = defineProps()` and `import props from './props'`
+    fn check_name_conflicts(&mut self, module: &Module) {
+        let mut declared: Vec<Ident> = Vec::new();
+
+        for module_item in module.body.iter() {
+            for ident in top_level_declarations(module_item) {
+                let Some(existing) = declared.iter().find(|existing| existing.sym == ident.sym)
+                else {
+                    declared.push(ident);
+                    continue;
+                };
+
+                // The generated declarations have no span, the ones of the source have
+                let span = [ident.span, existing.span]
+                    .into_iter()
+                    .find(|span| !span.is_dummy())
+                    .unwrap_or(module.span);
+                self.report(span, MigrationErrorKind::NameConflict);
+            }
+        }
+    }
+
+    /// Entries of an object option, e.g. `computed` or `methods`
+    fn option_entries(
+        &mut self,
+        value: OptionValue,
+        span: Span,
+    ) -> Option<Vec<(FervidAtom, OptionValue, Span)>> {
+        match value {
+            OptionValue::Expr(expr) if matches!(*expr, Expr::Object(_)) => {
+                let Expr::Object(obj_lit) = *expr else {
+                    unreachable!()
+                };
+                Some(self.object_entries(obj_lit))
+            }
+            _ => {
+                self.report(span, MigrationErrorKind::UnsupportedOption);
+                None
+            }
+        }
+    }
+
+    /// Entries with the static keys, the rest (e.g. spreads of `mapGetters`) is reported
+    fn object_entries(&mut self, obj_lit: ObjectLit) -> Vec<(FervidAtom, OptionValue, Span)> {
+        let mut entries = Vec::with_capacity(obj_lit.props.len());
+
+        for prop in obj_lit.props {
+            let span = prop.span();
+            let PropOrSpread::Prop(prop) = prop else {
+                self.report(span, MigrationErrorKind::UnsupportedOption);
+                continue;
+            };
+
+            let entry = match *prop {
+                Prop::KeyValue(key_value) => resolve_object_key(&key_value.key)
+                    .map(|key| (key, OptionValue::Expr(key_value.value))),
+                Prop::Method(method) => resolve_object_key(&method.key)
+                    .map(|key| (key, OptionValue::Method(method.function))),
+                Prop::Shorthand(ident) => Some((
+                    ident.sym.to_owned(),
+                    OptionValue::Expr(Box::new(Expr::Ident(ident))),
+                )),
+                _ => None,
+            };
+
+            match entry {
+                Some((key, value)) => entries.push((key, value, span)),
+                None => self.report(span, MigrationErrorKind::UnsupportedOption),
+            }
+        }
+
+        entries
+    }
+
+    /// Statements and the returned expression of `data` or `provide`
+    fn function_return(
+        &mut self,
+        value: OptionValue,
+        span: Span,
+    ) -> Option<(Vec<Stmt>, Box<Expr>)> {
+        let (params_len, body) = match value {
+            OptionValue::Method(function) => (function.params.len(), function.body),
+            OptionValue::Expr(expr) => match *expr {
+                Expr::Fn(fn_expr) => (fn_expr.function.params.len(), fn_expr.function.body),
+                Expr::Arrow(arrow_expr) => match *arrow_expr.body {
+                    BlockStmtOrExpr::BlockStmt(block_stmt) => {
+                        (arrow_expr.params.len(), Some(block_stmt))
+                    }
+                    BlockStmtOrExpr::Expr(expr) if arrow_expr.params.is_empty() => {
+                        return Some((vec![], Box::new(unroll_paren_seq(&expr).to_owned())));
+                    }
+                    BlockStmtOrExpr::Expr(_) => (arrow_expr.params.len(), None),
+                },
+                _ => (0, None),
+            },
+        };
+
+        // `data(vm) {}` uses the instance instead of `this`
+        let mut stmts = match body {
+            Some(body) if params_len == 0 => body.stmts,
+            _ => {
+                self.report(span, MigrationErrorKind::UnsupportedOption);
+                return None;
+            }
+        };
+
+        match stmts.pop() {
+            Some(Stmt::Return(ReturnStmt { arg: Some(arg), .. })) if !has_return(&stmts) => {
+                Some((stmts, Box::new(unroll_paren_seq(&arg).to_owned())))
+            }
+            _ => {
+                self.report(span, MigrationErrorKind::UnsupportedOption);
+                None
+            }
+        }
+    }
+
+    fn option_expr(&mut self, value: OptionValue) -> Box<Expr> {
+        match value {
+            OptionValue::Expr(expr) => expr,
+            OptionValue::Method(function) => Box::new(Expr::Fn(swc_core::ecma::ast::FnExpr {
+                ident: None,
+                function,
+            })),
+        }
+    }
+
+    fn rewrite_this<N>(&mut self, node: &mut N)
+    where
+        N: for<'s> VisitMutWith<ThisRewriter<'e, 's>>,
+    {
+        node.visit_mut_with(&mut ThisRewriter {
+            migration: self,
+            scopes: Vec::new(),
+        });
+    }
+
+    /// Calls a Vue API and records its import
+    fn vue_call(&mut self, name: &'static str, args: Vec<Expr>) -> Expr {
+        self.add_vue_import(name);
+        call_expr(name, args)
+    }
+
+    fn add_vue_import(&mut self, name: &'static str) {
+        if !self.vue_imports.contains(&name) {
+            self.vue_imports.push(name);
+        }
+    }
+
+    /// Same order as the instance proxy: `data`, `props`, then `computed`, `methods` and `inject`
+    fn binding_kind(&self, name: &FervidAtom) -> Option<BindingKind> {
+        let bindings = &self.bindings;
+        if bindings.data.contains(name) {
+            Some(BindingKind::Ref)
+        } else if bindings.props.contains(name) {
+            Some(BindingKind::Prop)
+        } else if bindings.computed.contains(name) {
+            Some(BindingKind::Ref)
+        } else if bindings.methods.contains(name) || bindings.inject.contains(name) {
+            Some(BindingKind::Plain)
+        } else {
+            None
+        }
+    }
+
+    fn report(&mut self, span: Span, kind: MigrationErrorKind) {
+        if let Some(errors) = self.errors.as_mut() {
+            errors.push(migration_error(span, kind));
+        }
+    }
+}
+
+/// Rewrites `this.foo` according to the kind of `foo`
+struct ThisRewriter<'e, 's> {
+    migration: &'s mut Migration<'e>,
+    /// Variables declared by the enclosing functions
+    scopes: Vec<Vec<Ident>>,
+}
+
+impl ThisRewriter<'_, '_> {
+    /// Replacement of `this.foo`
+    fn rewrite_member(&mut self, name: &FervidAtom, span: Span) -> Option<Expr> {
+        if self.is_shadowed(name) {
+            self.migration
+                .report(span, MigrationErrorKind::ShadowedBinding);
+            return None;
+        }
+
+        let migration = &mut *self.migration;
+
+        let replacement = match name.as_str() {
+            "$emit" => {
+                migration.uses_emit = true;
+                ident_expr("emit")
+            }
+            "$props" => {
+                migration.uses_props = true;
+                ident_expr("props")
+            }
+            "$attrs" => {
+                migration.uses_attrs = true;
+                ident_expr("attrs")
+            }
+            "$slots" => {
+                migration.uses_slots = true;
+                ident_expr("slots")
+            }
+            "$nextTick" => {
+                migration.add_vue_import("nextTick");
+                ident_expr("nextTick")
+            }
+            _ => match migration.binding_kind(name) {
+                Some(BindingKind::Ref) => member(
+                    Expr::Ident(name.to_owned().into_ident()),
+                    fervid_atom!("value"),
+                ),
+                Some(BindingKind::Prop) => {
+                    migration.uses_props = true;
+                    member(ident_expr("props"), name.to_owned())
+                }
+                Some(BindingKind::Plain) => Expr::Ident(name.to_owned().into_ident()),
+                None => {
+                    migration.report(span, MigrationErrorKind::UnsupportedThis);
+                    return None;
+                }
+            },
+        };
+
+        Some(replacement)
+    }
+
+    /// Whether the variable which `this.foo` becomes is declared by an enclosing function,
+    /// e.g. `props` for a prop or `emit` for `this.$emit`
+    fn is_shadowed(&self, name: &FervidAtom) -> bool {
+        let local_name = match name.as_str() {
+            "$emit" => "emit",
+            "$props" => "props",
+            "$attrs" => "attrs",
+            "$slots" => "slots",
+            "$nextTick" => "nextTick",
+            _ => match self.migration.binding_kind(name) {
+                Some(BindingKind::Prop) => "props",
+                Some(BindingKind::Ref | BindingKind::Plain) => name.as_str(),
+                None => return false,
+            },
+        };
+
+        self.scopes
+            .iter()
+            .any(|scope| scope.iter().any(|declared| declared.sym == local_name))
+    }
+
+    fn visit_mut_scope<N: VisitMutWith<Self>>(&mut self, node: &mut N, declared: Vec<Ident>) {
+        self.scopes.push(declared);
+        node.visit_mut_children_with(self);
+        self.scopes.pop();
+    }
+}
+
+impl VisitMut for ThisRewriter<'_, '_> {
+    /// `this` of the classes is not the component
+    fn visit_mut_class(&mut self, _: &mut Class) {}
+
+    fn visit_mut_function(&mut self, function: &mut Function) {
+        let mut declared = declared_names(&function.params);
+        declared.extend(declared_names(&function.body));
+        self.visit_mut_scope(function, declared);
+    }
+
+    fn visit_mut_arrow_expr(&mut self, arrow_expr: &mut ArrowExpr) {
+        let mut declared = declared_names(&arrow_expr.params);
+        declared.extend(declared_names(&arrow_expr.body));
+        self.visit_mut_scope(arrow_expr, declared);
+    }
+
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        // `this.$refs.foo` -> `foo.value`
+        if let Expr::Member(outer) = expr {
+            if let Expr::Member(ref inner) = *outer.obj {
+                if is_this_member(inner, "$refs") {
+                    match static_member_prop(&outer.prop) {
+                        Some(name) if self.is_shadowed(&name) => self
+                            .migration
+                            .report(outer.span, MigrationErrorKind::ShadowedBinding),
+                        Some(name) => {
+                            if !self.migration.template_refs.contains(&name) {
+                                self.migration.template_refs.push(name.to_owned());
+                            }
+                            *expr = member(Expr::Ident(name.into_ident()), fervid_atom!("value"));
+                        }
+                        None => self
+                            .migration
+                            .report(outer.span, MigrationErrorKind::UnsupportedThis),
+                    }
+                    return;
+                }
+            }
+        }
+
+        match expr {
+            Expr::Member(member_expr) if matches!(*member_expr.obj, Expr::This(_)) => {
+                let span = member_expr.span;
+                match static_member_prop(&member_expr.prop) {
+                    Some(name) => {
+                        if let Some(replacement) = self.rewrite_member(&name, span) {
+                            *expr = replacement;
+                        }
+                    }
+                    None => self
+                        .migration
+                        .report(span, MigrationErrorKind::UnsupportedThis),
+                }
+            }
+
+            Expr::This(this) => self
+                .migration
+                .report(this.span, MigrationErrorKind::UnsupportedThis),
+
+            // Collect the events of `this.$emit('foo')` for `defineEmits`
+            Expr::Call(call_expr) => {
+                if let Callee::Expr(ref callee) = call_expr.callee {
+                    if let Expr::Member(ref callee_member) = **callee {
+                        if is_this_member(callee_member, "$emit") {
+                            if let Some(Expr::Lit(Lit::Str(event))) =
+                                call_expr.args.first().map(|arg| arg.expr.as_ref())
+                            {
+                                if !self.migration.emitted_events.contains(&event.value) {
+                                    self.migration.emitted_events.push(event.value.to_owned());
+                                }
+                            }
+                        }
+                    }
+                }
+                expr.visit_mut_children_with(self);
+            }
+
+            _ => expr.visit_mut_children_with(self),
+        }
+    }
+
+    fn visit_mut_simple_assign_target(&mut self, target: &mut SimpleAssignTarget) {
+        let SimpleAssignTarget::Member(member_expr) = target else {
+            return target.visit_mut_children_with(self);
+        };
+        if !matches!(*member_expr.obj, Expr::This(_)) {
+            return target.visit_mut_children_with(self);
+        }
+
+        let span = member_expr.span;
+        let Some(name) = static_member_prop(&member_expr.prop) else {
+            return self
+                .migration
+                .report(span, MigrationErrorKind::UnsupportedThis);
+        };
+
+        if self.is_shadowed(&name) {
+            return self
+                .migration
+                .report(span, MigrationErrorKind::ShadowedBinding);
+        }
+
+        match self.migration.binding_kind(&name) {
+            Some(BindingKind::Ref) => {
+                *target = SimpleAssignTarget::Member(MemberExpr {
+                    span,
+                    obj: Box::new(Expr::Ident(name.into_ident())),
+                    prop: MemberProp::Ident(IdentName {
+                        span: DUMMY_SP,
+                        sym: fervid_atom!("value"),
+                    }),
+                })
+            }
+            Some(BindingKind::Prop) => self
+                .migration
+                .report(span, MigrationErrorKind::PropAssignment),
+            Some(BindingKind::Plain) => {
+                *target = SimpleAssignTarget::Ident(BindingIdent {
+                    id: name.into_ident(),
+                    type_ann: None,
+                })
+            }
+            None => self
+                .migration
+                .report(span, MigrationErrorKind::UnsupportedThis),
+        }
+    }
+
+    fn visit_mut_assign_target(&mut self, target: &mut AssignTarget) {
+        target.visit_mut_children_with(self)
+    }
+}
+
+/// Collects the variables declared by a function, except the ones of the nested functions
+#[derive(Default)]
+struct DeclarationCollector {
+    idents: Vec<Ident>,
+}
+
+impl Visit for DeclarationCollector {
+    fn visit_binding_ident(&mut self, binding_ident: &BindingIdent) {
+        self.idents.push(binding_ident.id.to_owned());
+    }
+
+    fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+        self.idents.push(fn_decl.ident.to_owned());
+    }
+
+    fn visit_class_decl(&mut self, class_decl: &ClassDecl) {
+        self.idents.push(class_decl.ident.to_owned());
+    }
+
+    /// `foo = 1` assigns and does not declare
+    fn visit_assign_target(&mut self, _: &AssignTarget) {}
+
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    fn visit_class(&mut self, _: &Class) {}
+}
+
+fn declared_names<N: VisitWith<DeclarationCollector>>(node: &N) -> Vec<Ident> {
+    let mut collector = DeclarationCollector::default();
+    node.visit_with(&mut collector);
+    collector.idents
+}
+
+/// Finds `$data.foo` and `$options.foo` in the expressions of the template nodes
+struct TemplateReferenceFinder<'b> {
+    bindings: &'b OptionsApiBindings,
+    prefixes: Vec<Span>,
+}
+
+impl TemplateReferenceFinder<'_> {
+    fn visit_node(&mut self, node: &Node) {
+        match node {
+            Node::Element(element_node) => self.visit_element(element_node),
+            Node::Interpolation(interpolation) => interpolation.value.visit_with(self),
+            Node::ConditionalSeq(conditional_seq) => {
+                let conditionals = std::iter::once(conditional_seq.if_node.as_ref())
+                    .chain(conditional_seq.else_if_nodes.iter());
+                for conditional in conditionals {
+                    conditional.condition.visit_with(self);
+                    self.visit_element(&conditional.node);
+                }
+                if let Some(ref else_node) = conditional_seq.else_node {
+                    self.visit_element(else_node);
+                }
+            }
+            Node::Text(..) | Node::Comment(..) => {}
+        }
+    }
+
+    fn visit_element(&mut self, element_node: &ElementNode) {
+        let starting_tag = &element_node.starting_tag;
+
+        for attr in starting_tag.attributes.iter() {
+            match attr {
+                AttributeOrBinding::VBind(v_bind) => {
+                    self.visit_str_or_expr(v_bind.argument.as_ref());
+                    v_bind.value.visit_with(self);
+                }
+                AttributeOrBinding::VOn(v_on) => {
+                    self.visit_str_or_expr(v_on.event.as_ref());
+                    v_on.handler.visit_with(self);
+                }
+                AttributeOrBinding::RegularAttribute { .. } => {}
+            }
+        }
+
+        if let Some(ref directives) = starting_tag.directives {
+            for custom in directives.custom.iter() {
+                self.visit_str_or_expr(custom.argument.as_ref());
+                custom.value.visit_with(self);
+            }
+            for v_model in directives.v_model.iter() {
+                self.visit_str_or_expr(v_model.argument.as_ref());
+                v_model.value.visit_with(self);
+            }
+            if let Some(ref v_for) = directives.v_for {
+                v_for.iterable.visit_with(self);
+            }
+            if let Some(ref v_slot) = directives.v_slot {
+                self.visit_str_or_expr(v_slot.slot_name.as_ref());
+            }
+            for expr in [
+                &directives.v_else_if,
+                &directives.v_html,
+                &directives.v_if,
+                &directives.v_memo,
+                &directives.v_show,
+                &directives.v_text,
+            ] {
+                expr.visit_with(self);
+            }
+        }
+
+        for child in element_node.children.iter() {
+            self.visit_node(child);
+        }
+    }
+
+    fn visit_str_or_expr(&mut self, str_or_expr: Option<&StrOrExpr>) {
+        if let Some(StrOrExpr::Expr(expr)) = str_or_expr {
+            expr.visit_with(self);
+        }
+    }
+}
+
+impl Visit for TemplateReferenceFinder<'_> {
+    fn visit_member_expr(&mut self, member_expr: &MemberExpr) {
+        if let (Expr::Ident(obj), MemberProp::Ident(prop)) =
+            (member_expr.obj.as_ref(), &member_expr.prop)
+        {
+            let is_migrated = match obj.sym.as_str() {
+                "$data" => self.bindings.data.contains(&prop.sym),
+                "$options" => self.bindings.methods.contains(&prop.sym),
+                _ => false,
+            };
+            if is_migrated {
+                self.prefixes.push(Span::new(obj.span.lo, prop.span.lo));
+            }
+        }
+
+        member_expr.visit_children_with(self);
+    }
+}
+
+/// Detects `return` outside of the nested functions
+#[derive(Default)]
+struct ReturnDetector {
+    found: bool,
+}
+
+impl Visit for ReturnDetector {
+    fn visit_return_stmt(&mut self, _: &ReturnStmt) {
+        self.found = true;
+    }
+
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    fn visit_class(&mut self, _: &Class) {}
+}
+
+fn has_return(stmts: &[Stmt]) -> bool {
+    let mut detector = ReturnDetector::default();
+    stmts.visit_with(&mut detector);
+    detector.found
+}
+
+/// `{ handler() {}, deep: true }` -> handler and `{ deep: true }`
+fn split_watch_options(obj_lit: ObjectLit) -> (Option<Expr>, ObjectLit) {
+    let mut handler = None;
+    let mut rest = Vec::new();
+
+    for prop in obj_lit.props {
+        let PropOrSpread::Prop(ref inner) = prop else {
+            rest.push(prop);
+            continue;
+        };
+
+        match **inner {
+            Prop::Method(ref method)
+                if resolve_object_key(&method.key).as_deref() == Some("handler") =>
+            {
+                let PropOrSpread::Prop(inner) = prop else {
+                    unreachable!()
+                };
+                let Prop::Method(method) = *inner else {
+                    unreachable!()
+                };
+                handler = Some(Expr::Arrow(function_to_arrow(*method.function)));
+            }
+            Prop::KeyValue(ref key_value)
+                if resolve_object_key(&key_value.key).as_deref() == Some("handler") =>
+            {
+                let PropOrSpread::Prop(inner) = prop else {
+                    unreachable!()
+                };
+                let Prop::KeyValue(key_value) = *inner else {
+                    unreachable!()
+                };
+                handler = watch_handler(*key_value.value);
+            }
+            _ => rest.push(prop),
+        }
+    }
+
+    (
+        handler,
+        ObjectLit {
+            span: obj_lit.span,
+            props: rest,
+        },
+    )
+}
+
+/// Handler of `watch`: a function or a name of a method
+fn watch_handler(expr: Expr) -> Option<Expr> {
+    match expr {
+        Expr::Lit(Lit::Str(s)) if is_identifier(&s.value) => {
+            Some(Expr::Ident(s.value.into_ident()))
+        }
+        expr => function_expr_to_arrow(expr).map(Expr::Arrow),
+    }
+}
+
+/// Adds the imports of the Vue APIs, merging them into an existing `import {} from 'vue'`
+fn add_vue_imports(module: &mut Module, names: &[&'static str]) {
+    if names.is_empty() {
+        return;
+    }
+
+    let existing = module
+        .body
+        .iter_mut()
+        .find_map(|module_item| match module_item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl))
+                if !import_decl.type_only
+                    && import_decl.src.value == *VUE
+                    && import_decl
+                        .specifiers
+                        .iter()
+                        .all(|specifier| matches!(specifier, ImportSpecifier::Named(_))) =>
+            {
+                Some(import_decl)
+            }
+            _ => None,
+        });
+
+    let new_specifiers = |existing: &[ImportSpecifier]| -> Vec<ImportSpecifier> {
+        names
+            .iter()
+            .filter(|name| {
+                !existing.iter().any(|specifier| {
+                    matches!(specifier, ImportSpecifier::Named(named) if named.local.sym == **name)
+                })
+            })
+            .map(|name| {
+                ImportSpecifier::Named(ImportNamedSpecifier {
+                    span: DUMMY_SP,
+                    local: FervidAtom::from(*name).into_ident(),
+                    imported: None,
+                    is_type_only: false,
+                })
+            })
+            .collect()
+    };
+
+    if let Some(import_decl) = existing {
+        let mut specifiers = new_specifiers(&import_decl.specifiers);
+        import_decl.specifiers.append(&mut specifiers);
+        return;
+    }
+
+    let import_decl = ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+        span: DUMMY_SP,
+        specifiers: new_specifiers(&[]),
+        src: Box::new(Str::from(VUE.to_owned())),
+        type_only: false,
+        with: None,
+        phase: ImportPhase::Evaluation,
+    }));
+
+    let position = module
+        .body
+        .iter()
+        .rposition(|module_item| {
+            matches!(module_item, ModuleItem::ModuleDecl(ModuleDecl::Import(_)))
+        })
+        .map_or(0, |idx| idx + 1);
+    module.body.insert(position, import_decl);
+}
+
+/// Removes `name` from `import { name } from 'vue'`, dropping the emptied import
+fn remove_vue_import(module: &mut Module, name: &FervidAtom) {
+    module.body.retain_mut(|module_item| {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) = module_item else {
+            return true;
+        };
+        if import_decl.src.value != *VUE || import_decl.specifiers.is_empty() {
+            return true;
+        }
+
+        import_decl.specifiers.retain(|specifier| match specifier {
+            ImportSpecifier::Named(named) => {
+                let imported = match named.imported {
+                    Some(ModuleExportName::Ident(ref ident)) => &ident.sym,
+                    Some(ModuleExportName::Str(ref s)) => &s.value,
+                    None => &named.local.sym,
+                };
+                imported != name
+            }
+            _ => true,
+        });
+
+        !import_decl.specifiers.is_empty()
+    });
+}
+
+/// Variables declared by a module item, e.g. the locals of an import
+fn top_level_declarations(module_item: &ModuleItem) -> Vec<Ident> {
+    let decl = match module_item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) => {
+            return import_decl
+                .specifiers
+                .iter()
+                .map(|specifier| match specifier {
+                    ImportSpecifier::Named(named) => named.local.to_owned(),
+                    ImportSpecifier::Default(default) => default.local.to_owned(),
+                    ImportSpecifier::Namespace(namespace) => namespace.local.to_owned(),
+                })
+                .collect();
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => &export_decl.decl,
+        ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+        _ => return vec![],
+    };
+
+    match decl {
+        Decl::Var(var_decl) => var_decl
+            .decls
+            .iter()
+            .flat_map(|declarator| declared_names(&declarator.name))
+            .collect(),
+        Decl::Fn(fn_decl) => vec![fn_decl.ident.to_owned()],
+        Decl::Class(class_decl) => vec![class_decl.ident.to_owned()],
+        _ => vec![],
+    }
+}
+
+/// `this.name`
+fn is_this_member(member_expr: &MemberExpr, name: &str) -> bool {
+    matches!(*member_expr.obj, Expr::This(_))
+        && static_member_prop(&member_expr.prop).is_some_and(|prop| prop == name)
+}
+
+/// `foo` in `x.foo` and `x['foo']`
+fn static_member_prop(prop: &MemberProp) -> Option<FervidAtom> {
+    match prop {
+        MemberProp::Ident(ident_name) => Some(ident_name.sym.to_owned()),
+        MemberProp::Computed(ComputedPropName { expr, .. }) => match **expr {
+            Expr::Lit(Lit::Str(ref s)) => Some(s.value.to_owned()),
+            _ => None,
+        },
+        MemberProp::PrivateName(_) => None,
+    }
+}
+
+/// Prop of `defineOptions`, methods are kept as methods
+fn option_prop(key: FervidAtom, value: OptionValue) -> PropOrSpread {
+    let key = PropName::Ident(IdentName {
+        span: DUMMY_SP,
+        sym: key,
+    });
+
+    let prop = match value {
+        OptionValue::Expr(value) => Prop::KeyValue(KeyValueProp { key, value }),
+        OptionValue::Method(function) => {
+            Prop::Method(swc_core::ecma::ast::MethodProp { key, function })
+        }
+    };
+
+    PropOrSpread::Prop(Box::new(prop))
+}
+
+/// Converts `function () {}` and `() => {}`, other expressions are not functions
+fn function_expr_to_arrow(expr: Expr) -> Option<ArrowExpr> {
+    match expr {
+        Expr::Fn(fn_expr) => Some(function_to_arrow(*fn_expr.function)),
+        Expr::Arrow(arrow_expr) => Some(arrow_expr),
+        Expr::Paren(paren_expr) => function_expr_to_arrow(*paren_expr.expr),
+        _ => None,
+    }
+}
+
+fn function_to_arrow(function: Function) -> ArrowExpr {
+    ArrowExpr {
+        span: function.span,
+        ctxt: Default::default(),
+        params: function.params.into_iter().map(|param| param.pat).collect(),
+        body: Box::new(BlockStmtOrExpr::BlockStmt(
+            function.body.unwrap_or_else(|| block(vec![])),
+        )),
+        is_async: function.is_async,
+        is_generator: false,
+        type_params: function.type_params,
+        return_type: function.return_type,
+    }
+}
+
+fn arrow(params: Vec<Pat>, body: BlockStmtOrExpr) -> ArrowExpr {
+    ArrowExpr {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        params,
+        body: Box::new(body),
+        is_async: false,
+        is_generator: false,
+        type_params: None,
+        return_type: None,
+    }
+}
+
+fn block(stmts: Vec<Stmt>) -> BlockStmt {
+    BlockStmt {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        stmts,
+    }
+}
+
+fn fn_decl_stmt(name: FervidAtom, function: Box<Function>) -> Stmt {
+    Stmt::Decl(Decl::Fn(FnDecl {
+        ident: name.into_ident(),
+        declare: false,
+        function,
+    }))
+}
+
+fn const_stmt(name: FervidAtom, init: Expr) -> Stmt {
+    Stmt::Decl(Decl::Var(Box::new(VarDecl {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        kind: VarDeclKind::Const,
+        declare: false,
+        decls: vec![VarDeclarator {
+            span: DUMMY_SP,
+            name: Pat::Ident(BindingIdent {
+                id: name.into_ident(),
+                type_ann: None,
+            }),
+            init: Some(Box::new(init)),
+            definite: false,
+        }],
+    })))
+}
+
+fn expr_stmt(expr: Expr) -> Stmt {
+    Stmt::Expr(ExprStmt {
+        span: DUMMY_SP,
+        expr: Box::new(expr),
+    })
+}
+
+fn call_expr(callee: &str, args: Vec<Expr>) -> Expr {
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        callee: Callee::Expr(Box::new(ident_expr(callee))),
+        args: args.into_iter().map(expr_or_spread).collect(),
+        type_args: None,
+    })
+}
+
+fn expr_or_spread(expr: Expr) -> ExprOrSpread {
+    ExprOrSpread {
+        spread: None,
+        expr: Box::new(expr),
+    }
+}
+
+fn member(obj: Expr, prop: FervidAtom) -> Expr {
+    Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj: Box::new(obj),
+        prop: MemberProp::Ident(IdentName {
+            span: DUMMY_SP,
+            sym: prop,
+        }),
+    })
+}
+
+fn ident_expr(name: &str) -> Expr {
+    Expr::Ident(Ident::from(name))
+}
+
+fn str_expr(value: FervidAtom) -> Expr {
+    Expr::Lit(Lit::Str(Str::from(value)))
+}
+
+fn bool_expr(value: bool) -> Expr {
+    Expr::Lit(Lit::Bool(Bool {
+        span: DUMMY_SP,
+        value,
+    }))
+}
+
+fn null_expr() -> Expr {
+    Expr::Lit(Lit::Null(swc_core::ecma::ast::Null { span: DUMMY_SP }))
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// `foo-bar` -> `fooBar`
+fn camelize(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut uppercase_next = false;
+    for c in s.chars() {
+        if c == '-' {
+            uppercase_next = true;
+        } else if uppercase_next {
+            result.push(c.to_ascii_uppercase());
+            uppercase_next = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// `foo-bar` -> `FooBar`
+fn pascalize(s: &str) -> String {
+    let camelized = camelize(s);
+    let mut chars = camelized.chars();
+    chars
+        .next()
+        .map(|c| c.to_ascii_uppercase())
+        .into_iter()
+        .chain(chars)
+        .collect()
+}
+
+fn migration_error(span: Span, kind: MigrationErrorKind) -> TransformError {
+    TransformError::MigrationError(MigrationError { span, kind })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{js, parser::parse_javascript_module, to_str};
+    use fervid_core::{ElementKind, Interpolation, StartingTag, VOnDirective, VueDirectives};
+
+    fn migrate(input: &str) -> (String, Vec<MigrationErrorKind>) {
+        let mut module = parse_javascript_module(input, 0, Default::default())
+            .expect("migrate expects the input to be parseable")
+            .0;
+        let mut errors = Vec::new();
+        migrate_options_api(&mut module, &mut errors);
+
+        let kinds = errors
+            .into_iter()
+            .map(|error| match error {
+                TransformError::MigrationError(error) => error.kind,
+                _ => unreachable!(),
+            })
+            .collect();
+        (to_str(&module), kinds)
+    }
+
+    #[test]
+    fn it_migrates_data_computed_methods() {
+        let (code, errors) = migrate(
+            r#"
+            import { defineComponent } from 'vue'
+            export default defineComponent({
+                props: ['step'],
+                data() {
+                    return { count: 0 }
+                },
+                computed: {
+                    double() { return this.count * 2 }
+                },
+                methods: {
+                    increment() { this.count += this.step }
+                }
+            })"#,
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            code,
+            "import{ref,computed}from\"vue\";const props=defineProps([\"step\"]);const count=ref(0);const double=computed(()=>{return count.value*2;});function increment(){count.value+=props.step;}"
+        );
+    }
+
+    #[test]
+    fn it_migrates_lifecycle_and_watch() {
+        let (code, errors) = migrate(
+            r#"
+            export default {
+                name: 'Foo',
+                data: () => ({ query: '' }),
+                watch: {
+                    query: { handler(v) { this.search(v) }, immediate: true }
+                },
+                methods: { search(v) {} },
+                created() { this.search(this.query) },
+                mounted() { this.$refs.input.focus() }
+            }"#,
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            code,
+            "import{ref,watch,onMounted}from\"vue\";defineOptions({name:\"Foo\"});const query=ref(\"\");const input=ref(null);function search(v){}watch(query,v=>{search(v);},{immediate:true});search(query.value);onMounted(()=>{input.value.focus();});"
+        );
+    }
+
+    #[test]
+    fn it_migrates_emits_inject_provide() {
+        let (code, errors) = migrate(
+            r#"
+            export default {
+                inject: { theme: { from: 'appTheme', default: () => ({}) } },
+                provide() {
+                    return { parent: this.$attrs.id }
+                },
+                methods: {
+                    select(id) { this.$emit('select', id); this.$nextTick(() => {}) }
+                }
+            }"#,
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            code,
+            "import{inject,provide,nextTick,useAttrs}from\"vue\";const emit=defineEmits([\"select\"]);const attrs=useAttrs();const theme=inject(\"appTheme\",()=>({}),true);function select(id){emit(\"select\",id);nextTick(()=>{});}provide(\"parent\",attrs.id);"
+        );
+    }
+
+    #[test]
+    fn it_reports_unsupported_code() {
+        let (_, errors) = migrate(
+            r#"
+            export default {
+                mixins: [foo],
+                props: ['value'],
+                filters: {},
+                methods: {
+                    update() { this.value = 1; this.$router.push('/') }
+                }
+            }"#,
+        );
+
+        assert_eq!(
+            errors,
+            vec![
+                MigrationErrorKind::Mixins,
+                MigrationErrorKind::UnsupportedOption,
+                MigrationErrorKind::PropAssignment,
+                MigrationErrorKind::UnsupportedThis,
+            ]
+        );
+
+        let (code, errors) = migrate("const foo = 1");
+        assert_eq!(code, "const foo=1;");
+        assert_eq!(errors, vec![MigrationErrorKind::NoDefaultExport]);
+    }
+
+    #[test]
+    fn it_reports_shadowed_bindings() {
+        let (code, errors) = migrate(
+            r#"
+            export default {
+                data: () => ({ count: 0, items: [] }),
+                methods: {
+                    reset() { const count = 5; this.count = count; this.items = [] },
+                    sum() { this.items.forEach((count) => {}); return this.count },
+                    notify(emit) { this.$emit('change', this.count) }
+                }
+            }"#,
+        );
+
+        assert_eq!(
+            errors,
+            vec![
+                MigrationErrorKind::ShadowedBinding,
+                MigrationErrorKind::ShadowedBinding,
+            ]
+        );
+        assert_eq!(
+            code,
+            "import{ref}from\"vue\";defineEmits([\"change\"]);const count=ref(0);const items=ref([]);function reset(){const count=5;this.count=count;items.value=[];}function sum(){items.value.forEach(count=>{});return count.value;}function notify(emit){this.$emit(\"change\",count.value);}"
+        );
+    }
+
+    #[test]
+    fn it_reports_name_conflicts() {
+        let (_, errors) = migrate(
+            r#"
+            import props from './props'
+            export default {
+                props,
+                data: () => ({ count: 0 }),
+                methods: {
+                    count() { return this.$props.step }
+                },
+                created() { const items = [] },
+                computed: {
+                    items() { return [] }
+                }
+            }"#,
+        );
+
+        assert_eq!(
+            errors,
+            vec![
+                MigrationErrorKind::NameConflict,
+                MigrationErrorKind::NameConflict,
+                MigrationErrorKind::NameConflict,
+            ]
+        );
+
+        // `defineProps()` without a variable does not conflict
+        let (_, errors) = migrate(
+            r#"
+            import props from './props'
+            export default { props }"#,
+        );
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn it_finds_template_reference_prefixes() {
+        assert_eq!(
+            strip_template_prefixes(
+                "$data.count + $data.missing + $options.name + a.$data.count",
+                |value| Node::Interpolation(Interpolation {
+                    value,
+                    template_scope: 0,
+                    patch_flag: false,
+                    span: DUMMY_SP,
+                })
+            ),
+            "count + $data.missing + $options.name + a.$data.count"
+        );
+
+        assert_eq!(
+            strip_template_prefixes("$options.increment($data.count)", |handler| {
+                element(
+                    vec![AttributeOrBinding::VOn(VOnDirective {
+                        event: Some("click".into()),
+                        handler: Some(handler),
+                        modifiers: vec![],
+                        span: DUMMY_SP,
+                    })],
+                    None,
+                )
+            }),
+            "increment(count)"
+        );
+
+        assert_eq!(
+            strip_template_prefixes("$data.count > $data['count']", |v_if| {
+                element(
+                    vec![],
+                    Some(VueDirectives {
+                        v_if: Some(v_if),
+                        ..Default::default()
+                    }),
+                )
+            }),
+            "count > $data['count']"
+        );
+    }
+
+    /// Removes the prefixes found in a template node, whose expression is `raw`
+    fn strip_template_prefixes(raw: &str, node: impl FnOnce(Box<Expr>) -> Node) -> String {
+        let bindings = OptionsApiBindings {
+            data: vec![fervid_atom!("count")],
+            methods: vec![fervid_atom!("increment")],
+            ..Default::default()
+        };
+
+        let mut result = raw.to_owned();
+        let prefixes = template_reference_prefixes(&[node(js(raw))], &bindings);
+        for span in prefixes.into_iter().rev() {
+            result.replace_range(span.lo.0 as usize..span.hi.0 as usize, "");
+        }
+        result
+    }
+
+    fn element(attributes: Vec<AttributeOrBinding>, directives: Option<VueDirectives>) -> Node {
+        Node::Element(ElementNode {
+            kind: ElementKind::Element,
+            starting_tag: StartingTag {
+                tag_name: "button".into(),
+                attributes,
+                directives: directives.map(Box::new),
+            },
+            children: vec![],
+            template_scope: 0,
+            patch_hints: Default::default(),
+            span: DUMMY_SP,
+        })
+    }
+}
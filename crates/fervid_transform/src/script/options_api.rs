@@ -6,7 +6,12 @@ use swc_core::{
     },
 };
 
-use crate::{error::TransformError, BindingsHelper};
+use std::sync::Arc;
+
+use crate::{error::TransformError, BindingsHelper, ModuleLoader};
+
+pub(super) use self::analyzer::analyze_default_export;
+use self::mixins::{collect_mixins_bindings, MixinsContext};
 
 mod analyzer;
 mod components;
@@ -18,6 +23,7 @@ mod exports;
 mod expose;
 mod inject;
 mod methods;
+mod mixins;
 mod props;
 mod setup;
 
@@ -30,10 +36,17 @@ pub struct AnalyzeOptions {
     /// In `PROD` mode they are available to the inline template as module globals,
     /// in `DEV` mode they are available under `$setup` because of `__returned` object
     pub collect_top_level_stmts: bool,
+    /// Loads the imported `mixins` and `extends`.
+    /// Without it, only the ones declared in the same `<script>` are analyzed
+    pub module_loader: Option<Arc<dyn ModuleLoader>>,
+    /// Name of the SFC, the importer of the modules loaded by `module_loader`
+    pub filename: String,
 }
 
 pub struct ScriptOptionsTransformResult {
     pub default_export_obj: Option<ObjectLit>,
+    /// Files of the mixins loaded during the analysis
+    pub deps: Vec<String>,
 }
 
 pub fn transform_and_record_script_options_api(
@@ -65,19 +78,28 @@ pub fn transform_and_record_script_options_api(
     }
 
     // TODO The actual transformation?
-    // Analyze the default export
+    // Analyze the default export together with its `mixins` and `extends`
+    let mut mixins_ctx = MixinsContext::new(opts.module_loader.as_deref());
     if let Some(ref default_export) = maybe_default_export {
         let options_api_bindings = get_bindings!();
         analyzer::analyze_default_export(default_export, options_api_bindings);
+        collect_mixins_bindings(
+            default_export,
+            module,
+            &opts.filename,
+            &mut mixins_ctx,
+            options_api_bindings,
+        );
     }
 
     ScriptOptionsTransformResult {
         default_export_obj: maybe_default_export,
+        deps: mixins_ctx.deps,
     }
 }
 
 /// Finds and takes ownership of the `export default` expression
-fn find_default_export_obj(module: &mut Module) -> Option<ObjectLit> {
+pub fn find_default_export_obj(module: &mut Module) -> Option<ObjectLit> {
     let default_export_index = module.body.iter().position(|module_item| {
         matches!(
             module_item,
@@ -844,6 +866,7 @@ mod tests {
     fn it_analyzes_top_level() {
         let opts = AnalyzeOptions {
             collect_top_level_stmts: true,
+            ..Default::default()
         };
 
         // Regular usage
@@ -966,6 +989,7 @@ mod tests {
     fn it_analyzes_top_level_exports() {
         let opts = AnalyzeOptions {
             collect_top_level_stmts: true,
+            ..Default::default()
         };
 
         // Different types of exports
@@ -1013,4 +1037,156 @@ mod tests {
             OptionsApiBindings::default()
         );
     }
+
+    #[test]
+    fn it_analyzes_local_mixins() {
+        test_js_and_ts!(
+            r"
+            import { defineComponent } from 'vue'
+
+            const foo = {
+                data() {
+                    return { bar: 1 }
+                },
+                methods: { baz() {} }
+            }
+
+            export default defineComponent({
+                mixins: [foo, { computed: { qux() {} } }],
+                extends: defineComponent({ props: ['lorem'] }),
+                data() {
+                    return { bar: 2, ipsum: 3 }
+                }
+            })
+            ",
+            OptionsApiBindings {
+                data: vec![fervid_atom!("bar"), fervid_atom!("ipsum")],
+                props: vec![fervid_atom!("lorem")],
+                computed: vec![fervid_atom!("qux")],
+                methods: vec![fervid_atom!("baz")],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn it_ignores_setup_of_mixins() {
+        // `setup` of a mixin is never called, and the own `name` takes precedence
+        test_js_and_ts!(
+            r"
+            export default {
+                name: 'Component',
+                mixins: [{ name: 'Mixin', setup: () => ({ foo: 1 }), inject: ['bar'] }]
+            }
+            ",
+            OptionsApiBindings {
+                name: Some(fervid_atom!("Component")),
+                inject: vec![fervid_atom!("bar")],
+                ..Default::default()
+            }
+        );
+
+        test_js_and_ts!(
+            r"
+            export default {
+                mixins: [{ name: 'Mixin' }]
+            }
+            ",
+            OptionsApiBindings {
+                name: Some(fervid_atom!("Mixin")),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn it_loads_imported_mixins() {
+        struct TestLoader;
+
+        impl ModuleLoader for TestLoader {
+            fn load(&self, specifier: &str, importer: &str) -> Option<crate::LoadedModule> {
+                let source = match (importer, specifier) {
+                    ("/src/Comp.vue", "./base.vue") => "export default { props: ['foo'] }",
+                    ("/src/Comp.vue", "./mixins") => "export { bar } from './bar'",
+                    ("/src/mixins.ts", "./bar") => {
+                        r"
+                        import nested from './nested'
+                        export const bar = { inject: ['bar'], mixins: [nested] }
+                        "
+                    }
+                    ("/src/bar.ts", "./nested") => "export default { emits: ['baz'] }",
+                    ("/src/Comp.vue", "./cycle") => {
+                        r"
+                        import cycle from './cycle'
+                        export default { mixins: [cycle], data: () => ({ qux: 1 }) }
+                        "
+                    }
+                    ("/src/cycle.ts", "./cycle") => return self.load(specifier, "/src/Comp.vue"),
+                    _ => return None,
+                };
+                let filename = format!("/src/{}", specifier.trim_start_matches("./"));
+                let filename = if filename.ends_with(".vue") {
+                    filename
+                } else {
+                    filename + ".ts"
+                };
+
+                Some(crate::LoadedModule {
+                    filename,
+                    source: source.to_string(),
+                })
+            }
+        }
+
+        let mut parsed = parse_javascript_module(
+            r"
+            import Base from './base.vue'
+            import { bar as Bar } from './mixins'
+            import Cycle from './cycle'
+            import External from 'external'
+
+            export default {
+                extends: Base,
+                mixins: [Bar, Cycle, External]
+            }
+            ",
+            0,
+            Default::default(),
+        )
+        .expect("should parse")
+        .0;
+        let mut bindings_helper = BindingsHelper::default();
+
+        let result = transform_and_record_script_options_api(
+            &mut parsed,
+            AnalyzeOptions {
+                module_loader: Some(std::sync::Arc::new(TestLoader)),
+                filename: "/src/Comp.vue".into(),
+                ..Default::default()
+            },
+            &mut bindings_helper,
+            &mut Vec::new(),
+        );
+
+        assert_eq!(
+            *bindings_helper.options_api_bindings.expect("should exist"),
+            OptionsApiBindings {
+                props: vec![fervid_atom!("foo")],
+                inject: vec![fervid_atom!("bar")],
+                emits: vec![fervid_atom!("baz")],
+                data: vec![fervid_atom!("qux")],
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            result.deps,
+            vec![
+                "/src/base.vue",
+                "/src/mixins.ts",
+                "/src/bar.ts",
+                "/src/nested.ts",
+                "/src/cycle.ts"
+            ]
+        );
+    }
 }
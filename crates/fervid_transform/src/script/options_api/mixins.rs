@@ -0,0 +1,593 @@
+//! Bindings contributed by `mixins` and `extends`.
+//!
+//! Vue merges the options of `extends` first, then the options of every mixin in order,
+//! and the options of the component last. However, the template does not care where a binding
+//! came from, it resolves an identifier by its kind in the same order as the component instance:
+//! `setup`, `data`, `props` and then `computed`, `methods` and `inject`.
+//! Therefore the bindings of the mixins are merged with the component ones, except for the
+//! `setup` bindings, because Vue does not call `setup` of the mixins.
+//!
+//! A mixin is followed when it is an object (optionally wrapped in `defineComponent`),
+//! a top-level variable initialized with one, or an import which the [`ModuleLoader`] can load.
+
+use fervid_core::{fervid_atom, FervidAtom};
+use swc_core::{
+    common::BytePos,
+    ecma::ast::{
+        Callee, Decl, EsVersion, ExportSpecifier, Expr, ImportSpecifier, Module, ModuleDecl,
+        ModuleExportName, ModuleItem, ObjectLit, Pat, Prop, PropName, PropOrSpread, Stmt, VarDecl,
+    },
+};
+use swc_ecma_parser::{lexer::Lexer, EsSyntax, Parser, StringInput, Syntax, TsSyntax};
+
+use crate::{
+    atoms::{DEFINE_COMPONENT, EXTENDS, MIXINS},
+    script::utils::unroll_paren_seq,
+    LoadedModule, ModuleLoader, OptionsApiBindings,
+};
+
+use super::analyzer::analyze_default_export;
+
+/// Guards against the cyclic mixins and re-exports
+const MAX_DEPTH: usize = 16;
+
+pub struct MixinsContext<'l> {
+    loader: Option<&'l dyn ModuleLoader>,
+    /// Files loaded while following the imports
+    pub deps: Vec<String>,
+    depth: usize,
+}
+
+impl<'l> MixinsContext<'l> {
+    pub fn new(loader: Option<&'l dyn ModuleLoader>) -> Self {
+        MixinsContext {
+            loader,
+            deps: vec![],
+            depth: 0,
+        }
+    }
+}
+
+/// Collects the bindings of `extends` and `mixins` of the `options` object declared in `module`
+/// and merges them into `out`
+pub fn collect_mixins_bindings(
+    options: &ObjectLit,
+    module: &Module,
+    filename: &str,
+    ctx: &mut MixinsContext,
+    out: &mut OptionsApiBindings,
+) {
+    let mut extends = None;
+    let mut mixins = Vec::new();
+
+    for prop in options.props.iter() {
+        let PropOrSpread::Prop(prop) = prop else {
+            continue;
+        };
+        let Prop::KeyValue(ref key_value) = **prop else {
+            continue;
+        };
+        let key = match key_value.key {
+            PropName::Ident(ref ident) => &ident.sym,
+            PropName::Str(ref s) => &s.value,
+            _ => continue,
+        };
+
+        if *key == *EXTENDS {
+            extends = Some(key_value.value.as_ref());
+        } else if *key == *MIXINS {
+            if let Expr::Array(ref array_lit) = *key_value.value {
+                mixins.extend(
+                    array_lit
+                        .elems
+                        .iter()
+                        .flatten()
+                        .filter(|elem| elem.spread.is_none())
+                        .map(|elem| elem.expr.as_ref()),
+                );
+            }
+        }
+    }
+
+    for mixin in extends.into_iter().chain(mixins) {
+        let mut mixin_bindings = OptionsApiBindings::default();
+        collect_expr_bindings(mixin, module, filename, ctx, &mut mixin_bindings);
+        merge_bindings(out, mixin_bindings);
+    }
+}
+
+/// Analyzes an options object, or follows the identifier referencing it
+fn collect_expr_bindings(
+    expr: &Expr,
+    module: &Module,
+    filename: &str,
+    ctx: &mut MixinsContext,
+    out: &mut OptionsApiBindings,
+) {
+    if ctx.depth >= MAX_DEPTH {
+        return;
+    }
+    ctx.depth += 1;
+
+    match unroll_options_expr(expr) {
+        Expr::Object(obj_lit) => {
+            analyze_default_export(obj_lit, out);
+            collect_mixins_bindings(obj_lit, module, filename, ctx, out);
+        }
+        Expr::Ident(ident) => collect_local_bindings(&ident.sym, module, filename, ctx, out),
+        _ => {}
+    }
+
+    ctx.depth -= 1;
+}
+
+/// Finds the top-level variable or import named `local`
+fn collect_local_bindings(
+    local: &FervidAtom,
+    module: &Module,
+    filename: &str,
+    ctx: &mut MixinsContext,
+    out: &mut OptionsApiBindings,
+) {
+    for module_item in module.body.iter() {
+        match module_item {
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))) => {
+                if let Some(init) = find_var_init(var_decl, local) {
+                    return collect_expr_bindings(init, module, filename, ctx, out);
+                }
+            }
+
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+                if let Decl::Var(ref var_decl) = export_decl.decl {
+                    if let Some(init) = find_var_init(var_decl, local) {
+                        return collect_expr_bindings(init, module, filename, ctx, out);
+                    }
+                }
+            }
+
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) if !import_decl.type_only => {
+                let imported =
+                    import_decl
+                        .specifiers
+                        .iter()
+                        .find_map(|specifier| match specifier {
+                            ImportSpecifier::Default(default_spec)
+                                if default_spec.local.sym == *local =>
+                            {
+                                Some(fervid_atom!("default"))
+                            }
+                            ImportSpecifier::Named(named_spec)
+                                if !named_spec.is_type_only && named_spec.local.sym == *local =>
+                            {
+                                Some(
+                                    named_spec
+                                        .imported
+                                        .as_ref()
+                                        .map_or_else(|| local.to_owned(), export_name),
+                                )
+                            }
+                            _ => None,
+                        });
+
+                if let Some(imported) = imported {
+                    return collect_imported_bindings(
+                        &import_decl.src.value,
+                        &imported,
+                        filename,
+                        ctx,
+                        out,
+                    );
+                }
+            }
+
+            _ => {}
+        }
+    }
+}
+
+/// Loads the module `specifier` imported from `importer` and follows its export named `imported`
+fn collect_imported_bindings(
+    specifier: &str,
+    imported: &FervidAtom,
+    importer: &str,
+    ctx: &mut MixinsContext,
+    out: &mut OptionsApiBindings,
+) {
+    if ctx.depth >= MAX_DEPTH {
+        return;
+    }
+    let Some(loaded) = ctx
+        .loader
+        .and_then(|loader| loader.load(specifier, importer))
+    else {
+        return;
+    };
+    let Some(module) = parse_loaded_module(&loaded) else {
+        return;
+    };
+
+    if !ctx.deps.contains(&loaded.filename) {
+        ctx.deps.push(loaded.filename.to_owned());
+    }
+
+    ctx.depth += 1;
+    collect_exported_bindings(imported, &module, &loaded.filename, ctx, out);
+    ctx.depth -= 1;
+}
+
+/// Finds the export named `exported`, i.e. `export default`, `export const` or `export { foo }`
+fn collect_exported_bindings(
+    exported: &FervidAtom,
+    module: &Module,
+    filename: &str,
+    ctx: &mut MixinsContext,
+    out: &mut OptionsApiBindings,
+) {
+    let is_default = exported == "default";
+
+    for module_item in module.body.iter() {
+        let ModuleItem::ModuleDecl(module_decl) = module_item else {
+            continue;
+        };
+
+        match module_decl {
+            ModuleDecl::ExportDefaultExpr(export_default) if is_default => {
+                return collect_expr_bindings(&export_default.expr, module, filename, ctx, out);
+            }
+
+            ModuleDecl::ExportDecl(export_decl) => {
+                if let Decl::Var(ref var_decl) = export_decl.decl {
+                    if let Some(init) = find_var_init(var_decl, exported) {
+                        return collect_expr_bindings(init, module, filename, ctx, out);
+                    }
+                }
+            }
+
+            ModuleDecl::ExportNamed(named_export) if !named_export.type_only => {
+                for specifier in named_export.specifiers.iter() {
+                    let ExportSpecifier::Named(named_spec) = specifier else {
+                        continue;
+                    };
+                    if named_spec.is_type_only {
+                        continue;
+                    }
+
+                    let orig = export_name(&named_spec.orig);
+                    let name = named_spec
+                        .exported
+                        .as_ref()
+                        .map_or_else(|| orig.to_owned(), export_name);
+                    if name != *exported {
+                        continue;
+                    }
+
+                    // `export { foo } from './foo'` or `export { foo }`
+                    return match named_export.src {
+                        Some(ref src) => {
+                            collect_imported_bindings(&src.value, &orig, filename, ctx, out)
+                        }
+                        None => collect_local_bindings(&orig, module, filename, ctx, out),
+                    };
+                }
+            }
+
+            _ => {}
+        }
+    }
+}
+
+/// Merges the bindings of a mixin into the bindings of the component
+fn merge_bindings(into: &mut OptionsApiBindings, from: OptionsApiBindings) {
+    macro_rules! merge {
+        ($($field: ident),*) => {
+            $(
+                for binding in from.$field {
+                    if !into.$field.contains(&binding) {
+                        into.$field.push(binding);
+                    }
+                }
+            )*
+        };
+    }
+
+    merge!(data, props, inject, emits, components, computed, methods, directives);
+
+    if into.name.is_none() {
+        into.name = from.name;
+    }
+}
+
+/// `foo` in `const foo = {}`
+fn find_var_init<'v>(var_decl: &'v VarDecl, name: &FervidAtom) -> Option<&'v Expr> {
+    var_decl
+        .decls
+        .iter()
+        .find_map(|declarator| match declarator.name {
+            Pat::Ident(ref binding_ident) if binding_ident.sym == *name => {
+                declarator.init.as_deref()
+            }
+            _ => None,
+        })
+}
+
+/// Unwraps `({})`, `{} as Foo` and `defineComponent({})`
+fn unroll_options_expr(expr: &Expr) -> &Expr {
+    let expr = unroll_paren_seq(expr);
+    match expr {
+        Expr::TsAs(ts_as) => unroll_options_expr(&ts_as.expr),
+        Expr::TsSatisfies(ts_satisfies) => unroll_options_expr(&ts_satisfies.expr),
+        Expr::TsConstAssertion(ts_const) => unroll_options_expr(&ts_const.expr),
+        Expr::TsTypeAssertion(ts_type_assertion) => unroll_options_expr(&ts_type_assertion.expr),
+        Expr::TsNonNull(ts_non_null) => unroll_options_expr(&ts_non_null.expr),
+        Expr::Call(call_expr) => {
+            let is_define_component = matches!(
+                call_expr.callee,
+                Callee::Expr(ref callee) if matches!(**callee, Expr::Ident(ref ident) if ident.sym == *DEFINE_COMPONENT)
+            );
+
+            match call_expr.args.first() {
+                Some(arg) if is_define_component && arg.spread.is_none() => {
+                    unroll_options_expr(&arg.expr)
+                }
+                _ => expr,
+            }
+        }
+        _ => expr,
+    }
+}
+
+fn export_name(module_export_name: &ModuleExportName) -> FervidAtom {
+    match module_export_name {
+        ModuleExportName::Ident(ident) => ident.sym.to_owned(),
+        ModuleExportName::Str(s) => s.value.to_owned(),
+    }
+}
+
+/// Parses the loaded module, TypeScript is assumed for the `.vue` files
+fn parse_loaded_module(loaded: &LoadedModule) -> Option<Module> {
+    let filename = loaded.filename.as_str();
+    let is_ts = [".ts", ".mts", ".cts", ".tsx", ".vue"]
+        .iter()
+        .any(|ext| filename.ends_with(ext));
+
+    let syntax = if is_ts {
+        Syntax::Typescript(TsSyntax {
+            tsx: filename.ends_with(".tsx"),
+            ..Default::default()
+        })
+    } else {
+        Syntax::Es(EsSyntax {
+            jsx: filename.ends_with(".jsx"),
+            ..Default::default()
+        })
+    };
+
+    let source = loaded.source.as_str();
+    let lexer = Lexer::new(
+        syntax,
+        EsVersion::EsNext,
+        StringInput::new(source, BytePos(1), BytePos(1 + source.len() as u32)),
+        None,
+    );
+
+    Parser::new_from(lexer).parse_module().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::parser::*;
+
+    /// Loads the modules by the `(importer, specifier)` pairs
+    struct MapLoader(Vec<(&'static str, &'static str, &'static str, &'static str)>);
+
+    impl ModuleLoader for MapLoader {
+        fn load(&self, specifier: &str, importer: &str) -> Option<LoadedModule> {
+            self.0
+                .iter()
+                .find(|(from, spec, _, _)| *from == importer && *spec == specifier)
+                .map(|(_, _, filename, source)| LoadedModule {
+                    filename: filename.to_string(),
+                    source: source.to_string(),
+                })
+        }
+    }
+
+    fn collect(
+        input: &str,
+        loader: Option<&dyn ModuleLoader>,
+    ) -> (OptionsApiBindings, Vec<String>) {
+        let module = parse_typescript_module(input, 0, Default::default())
+            .expect("Should be parseable")
+            .0;
+        let options = module
+            .body
+            .iter()
+            .find_map(|module_item| match module_item {
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export_default)) => {
+                    match unroll_options_expr(&export_default.expr) {
+                        Expr::Object(obj_lit) => Some(obj_lit),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+            .expect("Should have an object default export");
+
+        let mut ctx = MixinsContext::new(loader);
+        let mut out = OptionsApiBindings::default();
+        collect_mixins_bindings(options, &module, "/src/Comp.vue", &mut ctx, &mut out);
+        (out, ctx.deps)
+    }
+
+    #[test]
+    fn it_collects_local_mixins_in_order() {
+        let (bindings, deps) = collect(
+            r"
+            const base = defineComponent({ props: ['a'], data: () => ({ shared: 1 }) })
+            export const exported = { methods: { fromExported() {} } } as const
+            export default {
+                extends: base,
+                mixins: [(exported), { data: () => ({ shared: 2, own: 3 }) }, ...spread],
+            }
+            ",
+            None,
+        );
+
+        assert_eq!(bindings.props, vec![fervid_atom!("a")]);
+        assert_eq!(
+            bindings.data,
+            vec![fervid_atom!("shared"), fervid_atom!("own")]
+        );
+        assert_eq!(bindings.methods, vec![fervid_atom!("fromExported")]);
+        assert!(deps.is_empty());
+    }
+
+    #[test]
+    fn it_skips_imports_without_a_loader() {
+        let (bindings, _) = collect(
+            r"
+            import mixin from './mixin'
+            export default { mixins: [mixin, unknown] }
+            ",
+            None,
+        );
+
+        assert_eq!(bindings.data, Vec::<FervidAtom>::new());
+    }
+
+    #[test]
+    fn it_follows_imports_and_re_exports() {
+        let loader = MapLoader(vec![
+            (
+                "/src/Comp.vue",
+                "./mixins",
+                "/src/mixins/index.ts",
+                "export { default as first, second as renamed } from './first'\nexport { local }\nconst local = { computed: { fromLocal() {} } }",
+            ),
+            (
+                "/src/mixins/index.ts",
+                "./first",
+                "/src/mixins/first.js",
+                "export default { data: () => ({ fromFirst: 1 }) }\nexport const second = { inject: ['fromSecond'] }",
+            ),
+        ]);
+
+        let (bindings, deps) = collect(
+            r"
+            import { first, renamed as second, local } from './mixins'
+            import type { typeOnly } from './mixins'
+            export default { mixins: [first, second, local] }
+            ",
+            Some(&loader),
+        );
+
+        assert_eq!(bindings.data, vec![fervid_atom!("fromFirst")]);
+        assert_eq!(bindings.inject, vec![fervid_atom!("fromSecond")]);
+        assert_eq!(bindings.computed, vec![fervid_atom!("fromLocal")]);
+        assert_eq!(deps, vec!["/src/mixins/index.ts", "/src/mixins/first.js"]);
+    }
+
+    #[test]
+    fn it_stops_on_cyclic_mixins() {
+        let loader = MapLoader(vec![
+            (
+                "/src/Comp.vue",
+                "./cycle",
+                "/src/cycle.js",
+                "import self from './cycle'\nexport default { mixins: [self], data: () => ({ cyclic: 1 }) }",
+            ),
+            (
+                "/src/cycle.js",
+                "./cycle",
+                "/src/cycle.js",
+                "import self from './cycle'\nexport default { mixins: [self], data: () => ({ cyclic: 1 }) }",
+            ),
+        ]);
+
+        let (bindings, deps) = collect(
+            r"
+            import cycle from './cycle'
+            export default { mixins: [cycle] }
+            ",
+            Some(&loader),
+        );
+
+        assert_eq!(bindings.data, vec![fervid_atom!("cyclic")]);
+        assert_eq!(deps, vec!["/src/cycle.js"]);
+    }
+
+    #[test]
+    fn it_merges_bindings() {
+        let mut into = OptionsApiBindings {
+            data: vec![fervid_atom!("a")],
+            name: Some(fervid_atom!("Own")),
+            ..Default::default()
+        };
+        merge_bindings(
+            &mut into,
+            OptionsApiBindings {
+                data: vec![fervid_atom!("a"), fervid_atom!("b")],
+                directives: vec![fervid_atom!("focus")],
+                name: Some(fervid_atom!("Mixin")),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(into.data, vec![fervid_atom!("a"), fervid_atom!("b")]);
+        assert_eq!(into.directives, vec![fervid_atom!("focus")]);
+        assert_eq!(into.name, Some(fervid_atom!("Own")));
+
+        let mut unnamed = OptionsApiBindings::default();
+        merge_bindings(
+            &mut unnamed,
+            OptionsApiBindings {
+                name: Some(fervid_atom!("Mixin")),
+                ..Default::default()
+            },
+        );
+        assert_eq!(unnamed.name, Some(fervid_atom!("Mixin")));
+    }
+
+    #[test]
+    fn it_unrolls_options_expr() {
+        macro_rules! test {
+            ($input: literal, $is_object: literal) => {
+                let expr = parse_typescript_expr($input, 0, Default::default())
+                    .expect("Should be parseable")
+                    .0;
+                assert_eq!(
+                    matches!(unroll_options_expr(&expr), Expr::Object(_)),
+                    $is_object,
+                    "{}",
+                    $input
+                );
+            };
+        }
+
+        test!("({})", true);
+        test!("defineComponent({} as Foo)", true);
+        test!("(<Foo>{})!", true);
+        test!("({} satisfies Foo)", true);
+        test!("extendComponent({})", false);
+        test!("defineComponent(...args)", false);
+    }
+
+    #[test]
+    fn it_parses_loaded_modules_by_extension() {
+        let parse = |filename: &str, source: &str| {
+            parse_loaded_module(&LoadedModule {
+                filename: filename.to_owned(),
+                source: source.to_owned(),
+            })
+            .is_some()
+        };
+
+        assert!(parse("/a.ts", "export default {} as Foo"));
+        assert!(parse("/a.vue", "export default {} as Foo"));
+        assert!(parse("/a.tsx", "export default <div />"));
+        assert!(parse("/a.jsx", "export default <div />"));
+        assert!(parse("/a.js", "export default {}"));
+        assert!(!parse("/a.js", "export default {} as Foo"));
+    }
+}
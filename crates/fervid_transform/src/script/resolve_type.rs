@@ -4127,6 +4127,7 @@ mod tests {
             }),
             styles: vec![],
             custom_blocks: vec![],
+            vapor: false,
         };
         let mut ctx = TypeResolveContext::new(
             &sfc_descriptor,
@@ -4137,6 +4138,14 @@ mod tests {
                 scope_id: "test",
                 filename: "./Test.vue",
                 transform_asset_urls: crate::TransformAssetUrlsConfig::default(),
+                template_plugins: Default::default(),
+                custom_block_handlers: Default::default(),
+                source: "",
+                source_map: false,
+                inline_template: None,
+                strip_client_only: false,
+                target: fervid_core::CompileTarget::Vue3,
+                module_loader: None,
             },
         );
 
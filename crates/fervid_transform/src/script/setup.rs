@@ -11,7 +11,7 @@ use swc_core::{
 };
 
 use crate::{
-    atoms::{EMIT, EMITS, EMIT_HELPER, EXPOSE, EXPOSE_HELPER, PROPS, PROPS_HELPER},
+    atoms::{EMIT, EMITS, EMIT_HELPER, EXPOSE, EXPOSE_HELPER, PROPS, PROPS_HELPER, VUE},
     error::{ScriptError, ScriptErrorKind, TransformError},
     script::{
         common::{
@@ -22,18 +22,18 @@ use crate::{
         utils::is_static,
     },
     structs::SfcExportedObjectHelper,
-    SetupBinding, TransformSfcContext,
+    BindingsHelper, SetupBinding, TransformSfcContext,
 };
 
-mod await_detection;
-mod define_emits;
+pub(super) mod await_detection;
+pub(super) mod define_emits;
 mod define_model;
 mod define_options;
 mod define_props;
 pub(super) mod define_props_destructure;
 mod define_slots;
 pub(super) mod macros;
-mod utils;
+pub(super) mod utils;
 
 use self::{
     await_detection::detect_await_module_item,
@@ -57,8 +57,6 @@ pub fn transform_and_record_script_setup(
     script_setup: SfcScriptBlock,
     errors: &mut Vec<TransformError>,
 ) -> TransformScriptSetupResult {
-    let span = script_setup.content.span;
-
     let mut module_items = Vec::<ModuleItem>::new();
     let mut sfc_object_helper = SfcExportedObjectHelper::default();
 
@@ -118,8 +116,15 @@ pub fn transform_and_record_script_setup(
                     }
 
                     TransformMacroResult::NotAMacro => {
-                        // No analysis necessary, return the same statement
-                        Some(Stmt::Expr(expr_stmt))
+                        // Client-only hooks are never called on the server
+                        if ctx.strip_client_only
+                            && is_client_only_hook_call(&expr_stmt.expr, &ctx.bindings_helper)
+                        {
+                            None
+                        } else {
+                            // No analysis necessary, return the same statement
+                            Some(Stmt::Expr(expr_stmt))
+                        }
                     }
 
                     TransformMacroResult::Error(err) => {
@@ -183,13 +188,15 @@ pub fn transform_and_record_script_setup(
     }
 
     // Should we check that this function was not assigned anywhere else?
+    // Spans are dummy, because the comments leading the first statement
+    // share its position with the script and would be emitted before `setup`
     let setup_fn = Some(Box::new(Function {
         params: get_setup_fn_params(&sfc_object_helper),
         decorators: vec![],
-        span,
+        span: DUMMY_SP,
         ctxt: Default::default(),
         body: Some(BlockStmt {
-            span,
+            span: DUMMY_SP,
             ctxt: Default::default(),
             stmts: setup_body_stmts,
         }),
@@ -386,6 +393,42 @@ fn check_export(module_decl: &ModuleDecl) -> Option<Span> {
     }
 }
 
+/// Lifecycle hooks which are not called during the server-side rendering
+const CLIENT_ONLY_HOOKS: [&str; 10] = [
+    "onBeforeMount",
+    "onMounted",
+    "onBeforeUpdate",
+    "onUpdated",
+    "onBeforeUnmount",
+    "onUnmounted",
+    "onActivated",
+    "onDeactivated",
+    "onRenderTracked",
+    "onRenderTriggered",
+];
+
+/// Checks if the expression is a call of a client-only hook imported from `vue`, e.g. `onMounted(() => {})`
+fn is_client_only_hook_call(expr: &Expr, bindings_helper: &BindingsHelper) -> bool {
+    let Expr::Call(CallExpr {
+        callee: Callee::Expr(callee_expr),
+        ..
+    }) = expr
+    else {
+        return false;
+    };
+
+    let Expr::Ident(callee_ident) = callee_expr.as_ref() else {
+        return false;
+    };
+
+    bindings_helper
+        .user_imports
+        .get(&callee_ident.sym)
+        .is_some_and(|import| {
+            import.source == *VUE && CLIENT_ONLY_HOOKS.contains(&import.imported.as_ref())
+        })
+}
+
 /// Used to populate the params to `setup()`, such as `__props`, `emit`, etc.
 fn get_setup_fn_params(sfc_object_helper: &SfcExportedObjectHelper) -> Vec<Param> {
     let has_ctx_param =
@@ -452,10 +495,10 @@ mod tests {
         error::{ScriptError, ScriptErrorKind, TransformError},
         script::imports::process_imports,
         span,
-        test_utils::parser::*,
+        test_utils::{parser::*, to_str},
         SetupBinding, TransformSfcContext,
     };
-    use fervid_core::{fervid_atom, BindingTypes, SfcScriptBlock};
+    use fervid_core::{fervid_atom, BindingTypes, CompileTarget, SfcScriptBlock, VueImports};
     use swc_core::common::{BytePos, Span, DUMMY_SP};
 
     use super::transform_and_record_script_setup;
@@ -960,6 +1003,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_strips_client_only_hooks() {
+        let input = r#"
+import { onMounted as mounted, onServerPrefetch, ref } from 'vue'
+import { onUpdated } from './hooks'
+const count = ref(0)
+mounted(() => window.alert(count.value))
+onServerPrefetch(() => {})
+onUpdated(() => {})
+"#;
+
+        let strip = |strip_client_only: bool| {
+            let mut module = parse_javascript_module(input, 0, Default::default())
+                .expect("Should be parseable")
+                .0;
+            let mut ctx = TransformSfcContext::anonymous();
+            ctx.strip_client_only = strip_client_only;
+            let mut errors = Vec::new();
+            process_imports(&mut module, &mut ctx.bindings_helper, true, &mut errors);
+
+            let script_setup = SfcScriptBlock {
+                content: Box::new(module),
+                lang: fervid_core::SfcScriptLang::Es,
+                is_setup: true,
+                span: DUMMY_SP,
+            };
+            let result = transform_and_record_script_setup(&mut ctx, script_setup, &mut errors);
+            let setup_fn = result.setup_fn.expect("Should exist");
+            to_str(setup_fn.body.as_ref().expect("Should exist"))
+        };
+
+        assert_eq!(
+            "{__expose();const count=ref(0);onServerPrefetch(()=>{});onUpdated(()=>{});}",
+            strip(true)
+        );
+        assert!(strip(false).contains("mounted(()=>window.alert(count.value));"));
+    }
+
+    #[test]
+    fn it_reports_define_model_for_vue2() {
+        let transform = |target: CompileTarget| {
+            let module = parse_javascript_module(
+                "const model = defineModel()\nconst props = defineProps(['msg'])",
+                0,
+                Default::default(),
+            )
+            .expect("Should be parseable")
+            .0;
+            let mut ctx = TransformSfcContext::anonymous();
+            ctx.bindings_helper.target = target;
+            let mut errors = Vec::new();
+
+            let script_setup = SfcScriptBlock {
+                content: Box::new(module),
+                lang: fervid_core::SfcScriptLang::Es,
+                is_setup: true,
+                span: DUMMY_SP,
+            };
+            transform_and_record_script_setup(&mut ctx, script_setup, &mut errors);
+            (errors, ctx.bindings_helper.vue_imports)
+        };
+
+        let (errors, vue_imports) = transform(CompileTarget::Vue2);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0],
+            TransformError::ScriptError(ScriptError {
+                kind: ScriptErrorKind::Vue2UnsupportedMacro,
+                span
+            }) if span == span!(14, 27)
+        ));
+        assert!(!vue_imports.contains(VueImports::UseModel));
+
+        let (errors, vue_imports) = transform(CompileTarget::Vue3);
+        assert!(errors.is_empty());
+        assert!(vue_imports.contains(VueImports::UseModel));
+    }
+
     // https://github.com/vuejs/core/blob/140a7681cc3bba22f55d97fd85a5eafe97a1230f/packages/compiler-sfc/__tests__/compileScript.spec.ts#L871-L890
     #[test]
     fn non_type_named_exports() {
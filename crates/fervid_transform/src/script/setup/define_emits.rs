@@ -21,7 +21,7 @@ use crate::{
 
 use super::macros::{TransformMacroResult, VarDeclHelper};
 
-type FxIndexSet<T> = IndexSet<T, FxBuildHasher>;
+pub type FxIndexSet<T> = IndexSet<T, FxBuildHasher>;
 
 pub fn process_define_emits(
     ctx: &mut TypeResolveContext,
@@ -160,7 +160,7 @@ fn extract_runtime_emits(
 }
 
 /// Adapted from https://github.com/vuejs/core/blob/0ac0f2e338f6f8f0bea7237db539c68bfafb88ae/packages/compiler-sfc/src/script/defineEmits.ts#L105-L128
-fn extract_event_names(
+pub fn extract_event_names(
     ctx: &mut TypeResolveContext,
     event_name: &TsFnParam,
     emits: &mut FxIndexSet<FervidAtom>,
@@ -1,5 +1,6 @@
 use fervid_core::{
-    atom_to_propname, fervid_atom, str_to_propname, BindingTypes, FervidAtom, IntoIdent, VueImports,
+    atom_to_propname, fervid_atom, str_to_propname, BindingTypes, FervidAtom, IntoIdent,
+    VueImports, PURE_SP,
 };
 use flagset::FlagSet;
 use swc_core::{
@@ -799,9 +800,8 @@ fn wrap_in_merge_defaults(
     let merge_defaults_helper = VueImports::MergeDefaults;
     ctx.bindings_helper.vue_imports |= merge_defaults_helper;
 
-    // TODO /*#__PURE__*/ comment
     Box::new(Expr::Call(CallExpr {
-        span: DUMMY_SP,
+        span: PURE_SP,
         ctxt: Default::default(),
         callee: Callee::Expr(Box::new(Expr::Ident(
             merge_defaults_helper.as_atom().into_ident(),
@@ -1,4 +1,4 @@
-use fervid_core::{IntoIdent, VueImports};
+use fervid_core::{CompileTarget, IntoIdent, VueImports, PURE_SP};
 use swc_core::{
     common::DUMMY_SP,
     ecma::ast::{
@@ -12,7 +12,7 @@ use crate::{
         DEFINE_EMITS, DEFINE_EXPOSE, DEFINE_MODEL, DEFINE_OPTIONS, DEFINE_PROPS, DEFINE_SLOTS,
         EXPOSE_HELPER, MERGE_MODELS_HELPER, WITH_DEFAULTS,
     },
-    error::TransformError,
+    error::{ScriptError, ScriptErrorKind, TransformError},
     script::{
         resolve_type::TypeResolveContext,
         setup::{
@@ -233,10 +233,6 @@ pub fn transform_script_setup_macro_expr(
         bail!();
     };
 
-    // TODO We can also strip out `onMounted` and `onUnmounted` for SSR here
-    // Not only that, but we can remove any DOM-related listeners in template,
-    // e.g. `<button @click="onClick">`. This should apply to all Node::Element (not components).
-
     // We do a bit of a juggle here to use `string_cache`s fast comparisons
     let sym = &callee_ident.sym;
     let span = call_expr.span;
@@ -266,6 +262,14 @@ pub fn transform_script_setup_macro_expr(
             type_args: None,
         }))))
     } else if DEFINE_MODEL.eq(sym) {
+        // `useModel` does not exist in Vue 2.7
+        if matches!(bindings_helper.target, CompileTarget::Vue2) {
+            return TransformMacroResult::Error(TransformError::ScriptError(ScriptError {
+                span,
+                kind: ScriptErrorKind::Vue2UnsupportedMacro,
+            }));
+        }
+
         process_define_model(call_expr, var_decl, sfc_object_helper, bindings_helper)
     } else if DEFINE_SLOTS.eq(sym) {
         process_define_slots(
@@ -318,7 +322,7 @@ pub fn postprocess_macros(
                 let merge_models_ident = MERGE_MODELS_HELPER.to_owned();
 
                 let new_props = Expr::Call(CallExpr {
-                    span: DUMMY_SP,
+                    span: PURE_SP,
                     ctxt: Default::default(),
                     callee: Callee::Expr(Box::new(Expr::Ident(merge_models_ident.into_ident()))),
                     args: vec![
@@ -369,7 +373,7 @@ pub fn postprocess_macros(
                 let merge_models_ident = MERGE_MODELS_HELPER.to_owned();
 
                 let new_emits = Expr::Call(CallExpr {
-                    span: DUMMY_SP,
+                    span: PURE_SP,
                     ctxt: Default::default(),
                     callee: Callee::Expr(Box::new(Expr::Ident(merge_models_ident.into_ident()))),
                     args: vec![
@@ -1,22 +1,25 @@
 //! Exports data structs used by the crate
 
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, rc::Rc, sync::Arc};
 
 use fervid_core::{
-    fervid_atom, BindingTypes, ComponentBinding, CustomDirectiveBinding, FervidAtom,
+    fervid_atom, BindingTypes, CompileTarget, ComponentBinding, CustomDirectiveBinding, FervidAtom,
     SfcCustomBlock, SfcStyleBlock, SfcTemplateBlock, TemplateGenerationMode, VueImportsSet,
 };
 use fxhash::{FxHashMap as HashMap, FxHashSet as HashSet};
 use smallvec::SmallVec;
 use swc_core::{
-    common::{Span, DUMMY_SP},
+    common::{comments::SingleThreadedComments, Span, DUMMY_SP},
     ecma::ast::{
         Decl, Expr, ExprOrSpread, Function, Id, ImportDecl, Module, ObjectLit, PropOrSpread, Str,
         TsType,
     },
 };
 
-use crate::error::TransformError;
+use crate::{
+    custom_blocks::CustomBlockHandlers, error::TransformError, module_loader::ModuleLoader,
+    template::plugins::TemplatePlugins,
+};
 
 /// Context object. Currently very minimal but may grow over time.
 pub struct TransformSfcContext {
@@ -29,9 +32,15 @@ pub struct TransformSfcContext {
     pub bindings_helper: BindingsHelper,
     pub deps: HashSet<String>,
     pub transform_asset_urls: TransformAssetUrlsConfig,
+    /// User transforms of the template
+    pub template_plugins: TemplatePlugins,
     pub scopes: Vec<TypeScopeContainer>,
     pub errors: Vec<TransformError>,
     pub warnings: Vec<TransformError>,
+    /// See [`TransformSfcOptions::strip_client_only`]
+    pub strip_client_only: bool,
+    /// See [`TransformSfcOptions::module_loader`]
+    pub module_loader: Option<Arc<dyn ModuleLoader>>,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -46,7 +55,7 @@ pub enum PropsDestructureConfig {
 pub enum TransformAssetUrlsConfig {
     #[default]
     EnabledDefault,
-    EnabledOptions(Rc<TransformAssetUrlsConfigOptions>),
+    EnabledOptions(Arc<TransformAssetUrlsConfigOptions>),
     Disabled,
 }
 
@@ -68,6 +77,11 @@ pub struct TransformAssetUrlsConfigOptions {
     ///   use: ["xlink:href", "href"],
     /// }
     pub tags: HashMap<FervidAtom, Vec<FervidAtom>>,
+    /// Aliases of module requests, the longest matching prefix gets replaced.
+    /// URLs matching an alias are always transformed to imports.
+    /// Example: { "@": "/src", "@assets": "./assets" }
+    /// Default: {}
+    pub aliases: HashMap<String, String>,
 }
 
 #[derive(Debug)]
@@ -91,6 +105,10 @@ pub struct BindingsHelper {
     pub is_prod: bool,
     /// Is Typescript or Javascript used
     pub is_ts: bool,
+    /// Is the template compiled for the Vapor mode, i.e. `<script setup vapor>`
+    pub is_vapor: bool,
+    /// Vue version the component is compiled for
+    pub target: CompileTarget,
     /// Scopes of the `<template>` for in-template variable resolutions
     pub template_scopes: Vec<TemplateScope>,
     /// Used for props destructure
@@ -153,6 +171,11 @@ pub type TypeScopeContainer = Rc<RefCell<TypeScope>>;
 
 // Todo maybe use SmallVec?
 #[derive(Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct OptionsApiBindings {
     pub data: Vec<FervidAtom>,
     pub setup: Vec<SetupBinding>,
@@ -171,6 +194,11 @@ pub struct OptionsApiBindings {
 
 /// Identifier plus a binding type
 #[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct SetupBinding {
     pub sym: FervidAtom,
     pub binding_type: BindingTypes,
@@ -255,6 +283,26 @@ pub struct TransformSfcOptions<'s> {
     pub scope_id: &'s str,
     pub filename: &'s str,
     pub transform_asset_urls: TransformAssetUrlsConfig,
+    /// User transforms of the template, see [`crate::template::plugins`]
+    pub template_plugins: TemplatePlugins,
+    /// Compilers of the custom blocks, see [`crate::custom_blocks`]
+    pub custom_block_handlers: CustomBlockHandlers,
+    /// Original SFC source which the spans point into
+    pub source: &'s str,
+    /// Whether to generate source maps for the transformed style blocks
+    pub source_map: bool,
+    /// Whether the template is rendered inline as the last statement of `setup`.
+    /// Only takes effect with `<script setup>`, defaults to `is_prod`.
+    pub inline_template: Option<bool>,
+    /// Drop the code which only makes sense in the browser, i.e. the client-only lifecycle hooks
+    /// of `<script setup>` and the native DOM listeners of the template. Meant for the server builds
+    pub strip_client_only: bool,
+    /// Vue version the component is compiled for.
+    /// Vue 2 components always use a `render` function and do not support the Vapor mode
+    pub target: CompileTarget,
+    /// Loads the modules imported by `<script>`, so that the Options API `mixins` and `extends`
+    /// defined in other files contribute their bindings, see [`crate::module_loader`]
+    pub module_loader: Option<Arc<dyn ModuleLoader>>,
 }
 
 pub struct TransformSfcResult {
@@ -270,8 +318,129 @@ pub struct TransformSfcResult {
     pub template_block: Option<SfcTemplateBlock>,
     /// Transformed style blocks
     pub style_blocks: Vec<SfcStyleBlock>,
+    /// Source maps of the transformed style blocks, in the same order as `style_blocks`.
+    /// Empty when source maps were not requested or no block was transformed
+    pub style_source_maps: Vec<Option<String>>,
     /// Custom blocks
     pub custom_blocks: Vec<SfcCustomBlock>,
+    /// Modules compiled from the custom blocks which have a handler, in the order of the blocks.
+    /// Their default exports are meant to be applied to the component
+    pub custom_block_modules: Vec<Module>,
+}
+
+pub struct TransformSfcTemplateResult {
+    /// Helper with the bindings used by the template, its Vue imports and asset URL imports
+    pub bindings_helper: BindingsHelper,
+    /// Transformed template block, `None` when it ended up empty
+    pub template_block: Option<SfcTemplateBlock>,
+}
+
+pub struct ComponentMetaOptions<'s> {
+    /// Used for inferring the component name when it is not defined explicitly
+    pub filename: &'s str,
+    /// Original SFC source which the spans point into.
+    /// Types and default values are read from it
+    pub source: &'s str,
+    /// Comments collected when parsing the scripts.
+    /// JSDoc descriptions are read from them, without them there are no descriptions
+    pub comments: Option<&'s SingleThreadedComments>,
+}
+
+/// Public interface of a component, as declared by its macros or Options API fields.
+///
+/// Types and default values are kept as source text,
+/// because they are meant for documentation and not for code generation.
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct ComponentMeta {
+    /// Name from `defineOptions`, Options API `name` or inferred from the filename
+    pub name: Option<String>,
+    pub props: Vec<ComponentPropMeta>,
+    pub events: Vec<ComponentEventMeta>,
+    pub slots: Vec<ComponentSlotMeta>,
+    /// Models from `defineModel`. They are not duplicated into `props` and `events`
+    pub models: Vec<ComponentModelMeta>,
+    pub exposed: Vec<ComponentExposedMeta>,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct ComponentPropMeta {
+    pub name: String,
+    /// TS type as written in the source, e.g. `'sm' | 'md'`
+    pub ts_type: Option<String>,
+    /// Runtime types, e.g. `["String", "Number"]`. Empty when any type is accepted
+    pub runtime_types: Vec<String>,
+    pub required: bool,
+    /// Default value as written in the source
+    pub default: Option<String>,
+    /// Text of the JSDoc comment preceding the declaration
+    pub description: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct ComponentEventMeta {
+    pub name: String,
+    /// Types of the event arguments as written in the source
+    pub payload: Vec<String>,
+    pub description: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct ComponentSlotMeta {
+    pub name: String,
+    /// Type of the slot props as written in `defineSlots`
+    pub props_type: Option<String>,
+    pub description: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct ComponentModelMeta {
+    pub name: String,
+    pub ts_type: Option<String>,
+    pub runtime_types: Vec<String>,
+    pub required: bool,
+    pub default: Option<String>,
+    pub description: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct ComponentExposedMeta {
+    pub name: String,
+    pub description: Option<String>,
+    pub span: Span,
 }
 
 impl SetupBinding {
@@ -304,8 +473,11 @@ impl TransformSfcContext {
             deps: HashSet::default(),
             scopes: vec![],
             transform_asset_urls: TransformAssetUrlsConfig::default(),
+            template_plugins: TemplatePlugins::default(),
             errors: vec![],
             warnings: vec![],
+            strip_client_only: false,
+            module_loader: None,
         }
     }
 }
@@ -365,6 +537,7 @@ impl Default for TransformAssetUrlsConfigOptions {
             base: None,
             include_absolute: false,
             tags,
+            aliases: HashMap::default(),
         }
     }
 }
@@ -35,9 +35,16 @@ pub fn create_style_scope(file_hash: &str) -> String {
     scope
 }
 
+/// Transforms the scoped style blocks in-place.
+/// Returns `true` if any of the blocks was transformed.
+///
+/// When `source_map` is provided, `source_maps` is filled with a source map
+/// for each of the `style_blocks` (`None` for the blocks which were not transformed).
 pub fn transform_style_blocks(
     style_blocks: &mut [SfcStyleBlock],
     scope: &str,
+    source_map: Option<CssSourceMapConfig>,
+    source_maps: &mut Vec<Option<String>>,
     errors: &mut Vec<TransformError>,
 ) -> bool {
     // Check work
@@ -45,24 +52,34 @@ pub fn transform_style_blocks(
         return false;
     }
 
+    if source_map.is_some() {
+        source_maps.resize(style_blocks.len(), None);
+    }
+
     // TODO Config
     // TODO Allow minifying CSS
 
     // Map errors from `fervid_css` to `fervid_transform`
     let mut css_errors = Vec::new();
 
-    for style_block in style_blocks.iter_mut() {
+    for (idx, style_block) in style_blocks.iter_mut().enumerate() {
         if style_block.is_scoped && style_block.lang == "css" {
             let result = transform_css(
                 &style_block.content,
                 style_block.span,
                 Some(scope),
                 &mut css_errors,
-                TransformCssConfig::default(),
+                TransformCssConfig {
+                    source_map,
+                    ..Default::default()
+                },
             );
 
             if let Some(transformed) = result {
-                style_block.content = transformed.into();
+                style_block.content = transformed.code.into();
+                if let Some(block_source_map) = source_maps.get_mut(idx) {
+                    *block_source_map = transformed.source_map;
+                }
             }
         }
     }
@@ -3,6 +3,7 @@ use std::{fmt::Write, path::PathBuf};
 use fervid_core::{
     fervid_atom, AttributeOrBinding, ElementNode, FervidAtom, StrOrExpr, VBindDirective,
 };
+use fxhash::FxHashMap;
 use swc_core::{
     common::{Span, DUMMY_SP},
     ecma::ast::{
@@ -59,8 +60,9 @@ fn transform_element_asset_urls(
     let empty = vec![];
     let attrs = tags.get(&element.starting_tag.tag_name).unwrap_or(&empty);
     let wild_card_attrs = tags.get(&fervid_atom!("*")).unwrap_or(&empty);
+    let is_srcset_tag = matches!(element.starting_tag.tag_name.as_ref(), "img" | "source");
 
-    if attrs.is_empty() && wild_card_attrs.is_empty() {
+    if attrs.is_empty() && wild_card_attrs.is_empty() && !is_srcset_tag {
         return;
     }
 
@@ -69,117 +71,41 @@ fn transform_element_asset_urls(
             continue;
         };
 
-        if (!attrs.contains(name) && !wild_card_attrs.contains(name))
-            || value.trim().is_empty()
-            || is_external_url(value)
-            || is_data_url(value)
-            || value.starts_with('#')
-            || (!options.include_absolute && !is_relative_url(value))
-        {
-            continue;
-        }
-
-        macro_rules! bail {
-            ($err_kind: ident) => {{
-                errors.push(TransformError::TemplateError(TemplateError {
-                    kind: TemplateErrorKind::$err_kind,
-                    span: *span,
-                }));
-                continue;
-            }};
-        }
-
-        if let (Some(base_str), Some('.')) = (options.base.as_ref(), value.chars().next()) {
-            // explicit base - directly rewrite relative urls into absolute url
-            // to avoid generating extra imports
-            // Allow for full hostnames provided in options.base
-            let Ok(base) = parse_url(base_str) else {
-                bail!(TransformAssetUrlsBaseUrlParseFailed)
-            };
-
-            // Match the behavior of the official compiler as close as possible
-            let mut final_result = String::new();
-
-            // Because `url::Url` parses with a dummy base of `http://_`, we need to check if the result is user-provided or a dummy
-            let base_starts_with_double_slash = base_str.starts_with("//");
-            let is_dummy = base.scheme() == "http"
-                && !base_str.starts_with("http")
-                && !base_starts_with_double_slash;
-
-            // Add protocol and host
-            if !is_dummy {
-                final_result.reserve(base_str.len());
-
-                let protocol = base.scheme();
-                if let Some(host_str) = base.host_str() {
-                    if base_starts_with_double_slash {
-                        // Special handling of `//` user strings, e.g. `//example.com`
-                        final_result.push_str("//");
-                    } else {
-                        final_result.push_str(protocol);
-                        final_result.push_str("://");
-                    }
-                    final_result.push_str(host_str);
-                    if let Some(port) = base.port() {
-                        let _ = write!(final_result, ":{}", port);
-                    }
+        let import_expr = if is_srcset_tag && name == "srcset" {
+            match transform_srcset(value, *span, options, imports) {
+                Ok(Some(v)) => v,
+                Ok(None) => continue,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
                 }
             }
+        } else {
+            if (!attrs.contains(name) && !wild_card_attrs.contains(name))
+                || !should_process_url(value, options)
+            {
+                continue;
+            }
 
-            // Join the path using `PathBuf` instead of `Url` to mimic the official compiler
-            let path_buf: PathBuf = [base.path(), strip_prefix(value)].iter().collect();
-
-            for path_cmp in path_buf.components() {
-                match path_cmp {
-                    std::path::Component::Prefix(prefix_component) => {
-                        let Some(s) = prefix_component.as_os_str().to_str() else {
-                            bail!(TransformAssetUrlsUrlParseFailed)
-                        };
-                        final_result.push_str(s);
-                    }
-                    std::path::Component::RootDir => {}
-                    // `PathBuf::components` normalizes `.` away - if it was left, it is likely at the beginning
-                    std::path::Component::CurDir => final_result.push('.'),
-                    // Push the parent directory because
-                    std::path::Component::ParentDir => final_result.push_str(".."),
-                    std::path::Component::Normal(os_str) => {
-                        let Some(s) = os_str.to_str() else {
-                            bail!(TransformAssetUrlsUrlParseFailed)
-                        };
-                        final_result.push('/');
-                        final_result.push_str(s);
-                    }
+            if let (Some(base_str), Some('.')) = (options.base.as_ref(), value.chars().next()) {
+                // explicit base - directly rewrite relative urls into absolute url
+                // to avoid generating extra imports
+                match rewrite_with_base(base_str, value) {
+                    Ok(rewritten) => *value = FervidAtom::from(rewritten),
+                    Err(kind) => errors.push(TransformError::TemplateError(TemplateError {
+                        kind,
+                        span: *span,
+                    })),
                 }
+                continue;
             }
 
-            // let Some(path_buf_str) = path_buf.to_str() else {
-            //     bail!(TransformAssetUrlsUrlParseFailed)
-            // };
-
-            // dbg!(path_buf_str);
-
-            // final_result.push_str(path_buf_str);
-
-            *value = FervidAtom::from(final_result);
-            continue;
-        }
-
-        // There is no good solution for parsing `value` while preserving the original directory signifiers.
-        // Parsing using `url::Url` will actively remove any prefix `.` symbols or similar.
-        // Unfortunately, reproducing `Node.js`s non-standard `url.parse` in Rust is not possible/feasible,
-        // thus we assume that passed string is a valid path already.
-        let mut path = strip_prefix(value);
-        let mut hash = None;
-        if let Some(hash_pos) = path.find('#') {
-            hash = Some(&path[hash_pos..]);
-            path = &path[..hash_pos];
-        }
-
-        let import_expr = match get_import_expression(path, hash, *span, imports) {
-            Ok(v) => v,
-            Err(e) => {
-                errors.push(e);
-                continue;
+            match get_url_import_expression(value, *span, options, imports) {
+                Ok(v) => v,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
             }
         };
 
@@ -194,6 +120,190 @@ fn transform_element_asset_urls(
     }
 }
 
+/// Transforms `srcset` of `<img>` and `<source>` into a string concatenation of imports,
+/// e.g. `srcset="./foo.png 2x, ./bar.png 3x"` -> `_imports_0 + ' 2x, ' + _imports_1 + ' 3x'`.
+/// Returns `None` when no transformation to an expression is needed.
+///
+/// Adapted from https://github.com/vuejs/core/blob/3f27c58ffbd4309df369bc89493fdc284dc540bb/packages/compiler-sfc/src/template/transformSrcset.ts
+fn transform_srcset(
+    value: &mut FervidAtom,
+    span: Span,
+    options: &TransformAssetUrlsConfigOptions,
+    imports: &mut Vec<ImportDecl>,
+) -> Result<Option<Box<Expr>>, TransformError> {
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let mut image_candidates: Vec<(String, Option<&str>)> = value
+        .split(',')
+        .map(|candidate| {
+            let mut parts = candidate.split_whitespace();
+            let url = parts.next().unwrap_or_default().to_owned();
+            (url, parts.next())
+        })
+        .collect();
+
+    // Data URLs contain a comma after the encoding, so they need to be re-merged
+    let mut idx = 0;
+    while idx + 1 < image_candidates.len() {
+        if is_data_url(&image_candidates[idx].0) {
+            let (data_url, _) = image_candidates.remove(idx);
+            let next_url = &mut image_candidates[idx].0;
+            *next_url = format!("{data_url},{next_url}");
+        }
+        idx += 1;
+    }
+
+    // When srcset does not contain any qualified URLs, skip transforming
+    if !image_candidates
+        .iter()
+        .any(|(url, _)| should_process_url(url, options))
+    {
+        return Ok(None);
+    }
+
+    if let Some(ref base_str) = options.base {
+        let mut set = Vec::with_capacity(image_candidates.len());
+        let mut need_import_transform = false;
+
+        for (url, descriptor) in image_candidates.iter_mut() {
+            if url.starts_with('.') {
+                *url = rewrite_with_base(base_str, url)
+                    .map_err(|kind| TransformError::TemplateError(TemplateError { kind, span }))?;
+            } else if should_process_url(url, options) {
+                need_import_transform = true;
+                continue;
+            }
+
+            match descriptor {
+                Some(descriptor) => set.push(format!("{url} {descriptor}")),
+                None => set.push(url.to_owned()),
+            }
+        }
+
+        if !need_import_transform {
+            *value = FervidAtom::from(set.join(", "));
+            return Ok(None);
+        }
+    }
+
+    let candidates_len = image_candidates.len();
+    let mut parts: Vec<Box<Expr>> = Vec::with_capacity(candidates_len * 2);
+    for (idx, (url, descriptor)) in image_candidates.iter().enumerate() {
+        if should_process_url(url, options) {
+            parts.push(get_url_import_expression(url, span, options, imports)?);
+        } else {
+            parts.push(str_expr(url, span));
+        }
+
+        let is_not_last = idx + 1 < candidates_len;
+        let separator = match (descriptor, is_not_last) {
+            (Some(descriptor), true) => format!(" {descriptor}, "),
+            (Some(descriptor), false) => format!(" {descriptor}"),
+            (None, true) => ", ".to_owned(),
+            (None, false) => continue,
+        };
+        parts.push(str_expr(&separator, span));
+    }
+
+    Ok(parts.into_iter().reduce(|left, right| {
+        Box::new(Expr::Bin(BinExpr {
+            span,
+            op: BinaryOp::Add,
+            left,
+            right,
+        }))
+    }))
+}
+
+/// Rewrites a relative URL into an absolute one using the user-provided `base`
+fn rewrite_with_base(base_str: &str, value: &str) -> Result<String, TemplateErrorKind> {
+    // Allow for full hostnames provided in options.base
+    let Ok(base) = parse_url(base_str) else {
+        return Err(TemplateErrorKind::TransformAssetUrlsBaseUrlParseFailed);
+    };
+
+    // Match the behavior of the official compiler as close as possible
+    let mut final_result = String::new();
+
+    // Because `url::Url` parses with a dummy base of `http://_`, we need to check if the result is user-provided or a dummy
+    let base_starts_with_double_slash = base_str.starts_with("//");
+    let is_dummy =
+        base.scheme() == "http" && !base_str.starts_with("http") && !base_starts_with_double_slash;
+
+    // Add protocol and host
+    if !is_dummy {
+        final_result.reserve(base_str.len());
+
+        let protocol = base.scheme();
+        if let Some(host_str) = base.host_str() {
+            if base_starts_with_double_slash {
+                // Special handling of `//` user strings, e.g. `//example.com`
+                final_result.push_str("//");
+            } else {
+                final_result.push_str(protocol);
+                final_result.push_str("://");
+            }
+            final_result.push_str(host_str);
+            if let Some(port) = base.port() {
+                let _ = write!(final_result, ":{}", port);
+            }
+        }
+    }
+
+    // Join the path using `PathBuf` instead of `Url` to mimic the official compiler
+    let path_buf: PathBuf = [base.path(), strip_prefix(value)].iter().collect();
+
+    for path_cmp in path_buf.components() {
+        match path_cmp {
+            std::path::Component::Prefix(prefix_component) => {
+                let Some(s) = prefix_component.as_os_str().to_str() else {
+                    return Err(TemplateErrorKind::TransformAssetUrlsUrlParseFailed);
+                };
+                final_result.push_str(s);
+            }
+            std::path::Component::RootDir => {}
+            // `PathBuf::components` normalizes `.` away - if it was left, it is likely at the beginning
+            std::path::Component::CurDir => final_result.push('.'),
+            // Push the parent directory because
+            std::path::Component::ParentDir => final_result.push_str(".."),
+            std::path::Component::Normal(os_str) => {
+                let Some(s) = os_str.to_str() else {
+                    return Err(TemplateErrorKind::TransformAssetUrlsUrlParseFailed);
+                };
+                final_result.push('/');
+                final_result.push_str(s);
+            }
+        }
+    }
+
+    Ok(final_result)
+}
+
+/// Creates an import for the URL (resolving the aliases) and returns an expression to use it
+fn get_url_import_expression(
+    url: &str,
+    span: Span,
+    options: &TransformAssetUrlsConfigOptions,
+    imports: &mut Vec<ImportDecl>,
+) -> Result<Box<Expr>, TransformError> {
+    // There is no good solution for parsing `value` while preserving the original directory signifiers.
+    // Parsing using `url::Url` will actively remove any prefix `.` symbols or similar.
+    // Unfortunately, reproducing `Node.js`s non-standard `url.parse` in Rust is not possible/feasible,
+    // thus we assume that passed string is a valid path already.
+    let url = strip_prefix(url);
+    let resolved = resolve_alias(url, &options.aliases);
+    let mut path = resolved.as_deref().unwrap_or(url);
+    let mut hash = None;
+    if let Some(hash_pos) = path.find('#') {
+        hash = Some(&path[hash_pos..]);
+        path = &path[..hash_pos];
+    }
+
+    get_import_expression(path, hash, span, imports)
+}
+
 fn get_import_expression(
     path: &str,
     hash: Option<&str>,
@@ -271,6 +381,14 @@ fn get_import_expression(
     Ok(hash_exp)
 }
 
+fn str_expr(value: &str, span: Span) -> Box<Expr> {
+    Box::new(Expr::Lit(Lit::Str(Str {
+        span,
+        value: FervidAtom::from(value),
+        raw: None,
+    })))
+}
+
 fn parse_url(url: &str) -> Result<Url, url::ParseError> {
     BASE_URL.clone().join(strip_prefix(url))
 }
@@ -287,17 +405,206 @@ fn strip_prefix(url: &str) -> &str {
     url
 }
 
-fn is_relative_url(url: &FervidAtom) -> bool {
+/// Replaces the longest matching alias prefix of the URL, e.g. `@/logo.png` -> `/src/logo.png`
+/// for the `{ "@": "/src" }` alias map.
+/// Like in bundlers, an alias matches either the whole URL or a prefix followed by `/`.
+fn resolve_alias(url: &str, aliases: &FxHashMap<String, String>) -> Option<String> {
+    let (find, replacement) = aliases
+        .iter()
+        .filter(|(find, _)| {
+            !find.is_empty()
+                && url.starts_with(find.as_str())
+                && (url.len() == find.len()
+                    || find.ends_with('/')
+                    || url[find.len()..].starts_with('/'))
+        })
+        .max_by_key(|(find, _)| find.len())?;
+
+    Some(format!("{replacement}{}", &url[find.len()..]))
+}
+
+/// https://github.com/vuejs/core/blob/3f27c58ffbd4309df369bc89493fdc284dc540bb/packages/compiler-sfc/src/template/transformSrcset.ts#L55-L62
+fn should_process_url(url: &str, options: &TransformAssetUrlsConfigOptions) -> bool {
+    if url.trim().is_empty() || is_external_url(url) || is_data_url(url) {
+        return false;
+    }
+
+    if resolve_alias(strip_prefix(url), &options.aliases).is_some() {
+        return true;
+    }
+
+    // Hash fragments are references inside the document (e.g. `<use href="#circle">`)
+    !url.starts_with('#') && (options.include_absolute || is_relative_url(url))
+}
+
+fn is_relative_url(url: &str) -> bool {
     let first_char = url.chars().next();
     matches!(first_char, Some('.' | '~' | '@'))
 }
 
 /// https://github.com/vuejs/core/blob/3f27c58ffbd4309df369bc89493fdc284dc540bb/packages/compiler-sfc/src/template/templateUtils.ts#L9-L12
-fn is_external_url(url: &FervidAtom) -> bool {
+fn is_external_url(url: &str) -> bool {
     url.starts_with("http://") || url.starts_with("https://") || url.starts_with("//")
 }
 
 /// https://github.com/vuejs/core/blob/3f27c58ffbd4309df369bc89493fdc284dc540bb/packages/compiler-sfc/src/template/templateUtils.ts#L14-L17
-fn is_data_url(url: &FervidAtom) -> bool {
+fn is_data_url(url: &str) -> bool {
     url.trim_start().starts_with("data:")
 }
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::{ElementKind, StartingTag};
+
+    use crate::test_utils::to_str;
+
+    use super::*;
+
+    #[test]
+    fn it_transforms_srcset() {
+        let (attr, imports) = transform_attr(
+            "img",
+            "srcset",
+            "./logo.png 2x, ./logo.png 3x, /absolute.png, ~fixtures/logo.png",
+            &DEFAULT_OPTIONS,
+        );
+
+        assert_eq!(
+            r#"_imports_0+" 2x, "+_imports_0+" 3x, "+"/absolute.png"+", "+_imports_1"#,
+            attr
+        );
+        assert_eq!(vec!["./logo.png", "fixtures/logo.png"], imports);
+    }
+
+    #[test]
+    fn it_transforms_srcset_with_data_urls() {
+        let (attr, imports) = transform_attr(
+            "source",
+            "srcset",
+            "data:image/png;base64,i 1x, ./logo.png 2x",
+            &DEFAULT_OPTIONS,
+        );
+
+        assert_eq!(
+            r#""data:image/png;base64,i"+" 1x, "+_imports_0+" 2x""#,
+            attr
+        );
+        assert_eq!(vec!["./logo.png"], imports);
+    }
+
+    #[test]
+    fn it_skips_srcset_without_qualified_urls() {
+        let (attr, imports) = transform_attr(
+            "img",
+            "srcset",
+            "https://example.com/logo.png 2x, /logo.png 3x",
+            &DEFAULT_OPTIONS,
+        );
+
+        assert_eq!("https://example.com/logo.png 2x, /logo.png 3x", attr);
+        assert!(imports.is_empty());
+    }
+
+    #[test]
+    fn it_rewrites_srcset_with_base() {
+        let options = TransformAssetUrlsConfigOptions {
+            base: Some("/foo".into()),
+            ..Default::default()
+        };
+
+        let (attr, imports) =
+            transform_attr("img", "srcset", "./logo.png 2x, /bar.png 3x", &options);
+        assert_eq!("/foo/logo.png 2x, /bar.png 3x", attr);
+        assert!(imports.is_empty());
+
+        // Module requests still need imports
+        let (attr, imports) = transform_attr(
+            "img",
+            "srcset",
+            "./logo.png 2x, @theme/bar.png 3x",
+            &options,
+        );
+        assert_eq!(r#""/foo/logo.png"+" 2x, "+_imports_0+" 3x""#, attr);
+        assert_eq!(vec!["@theme/bar.png"], imports);
+    }
+
+    #[test]
+    fn it_resolves_aliases() {
+        let mut aliases = FxHashMap::default();
+        aliases.insert("@".to_owned(), "/src".to_owned());
+        aliases.insert("@assets".to_owned(), "/src/assets".to_owned());
+        aliases.insert("#images/".to_owned(), "./images/".to_owned());
+        let options = TransformAssetUrlsConfigOptions {
+            aliases,
+            ..Default::default()
+        };
+
+        let (attr, imports) = transform_attr("img", "src", "@/logo.png", &options);
+        assert_eq!("_imports_0", attr);
+        assert_eq!(vec!["/src/logo.png"], imports);
+
+        // Longest prefix wins
+        let (_, imports) = transform_attr("img", "src", "~@assets/logo.png", &options);
+        assert_eq!(vec!["/src/assets/logo.png"], imports);
+
+        // Aliased URLs are transformed even when they do not look relative
+        let (_, imports) = transform_attr("img", "src", "#images/logo.png", &options);
+        assert_eq!(vec!["./images/logo.png"], imports);
+
+        // `@theme` is not `@` followed by a slash
+        let (_, imports) = transform_attr("img", "src", "@theme/logo.png", &options);
+        assert_eq!(vec!["@theme/logo.png"], imports);
+
+        let (attr, imports) = transform_attr(
+            "img",
+            "srcset",
+            "@/logo.png 2x, @assets/logo.png 3x",
+            &options,
+        );
+        assert_eq!(r#"_imports_0+" 2x, "+_imports_1+" 3x""#, attr);
+        assert_eq!(vec!["/src/logo.png", "/src/assets/logo.png"], imports);
+    }
+
+    /// Transforms a single attribute and returns either its new value or the bound expression,
+    /// together with the import sources
+    fn transform_attr(
+        tag_name: &str,
+        attr_name: &str,
+        attr_value: &str,
+        options: &TransformAssetUrlsConfigOptions,
+    ) -> (String, Vec<String>) {
+        let mut element = ElementNode {
+            starting_tag: StartingTag {
+                tag_name: tag_name.into(),
+                attributes: vec![AttributeOrBinding::RegularAttribute {
+                    name: attr_name.into(),
+                    value: attr_value.into(),
+                    span: DUMMY_SP,
+                }],
+                directives: None,
+            },
+            children: vec![],
+            template_scope: 0,
+            kind: ElementKind::Element,
+            patch_hints: Default::default(),
+            span: DUMMY_SP,
+        };
+
+        let mut errors = Vec::new();
+        let mut imports = Vec::new();
+        transform_element_asset_urls(&mut element, options, &mut errors, &mut imports);
+        assert!(errors.is_empty());
+
+        let attr = match element.starting_tag.attributes.pop() {
+            Some(AttributeOrBinding::RegularAttribute { value, .. }) => value.to_string(),
+            Some(AttributeOrBinding::VBind(v_bind)) => to_str(&v_bind.value),
+            _ => panic!("Unexpected attribute"),
+        };
+        let imports = imports
+            .into_iter()
+            .map(|import| import.src.value.to_string())
+            .collect();
+
+        (attr, imports)
+    }
+}
@@ -1,8 +1,9 @@
 use fervid_core::{
     check_attribute_name, fervid_atom, is_from_default_slot, is_html_tag, AttributeOrBinding,
-    BindingTypes, BuiltinType, Conditional, ConditionalNodeSequence, ElementKind, ElementNode,
-    FervidAtom, Interpolation, IntoIdent, Node, PatchFlags, PatchHints, SfcTemplateBlock,
-    StartingTag, StrOrExpr, TemplateGenerationMode, VBindDirective, VSlotDirective, VUE_BUILTINS,
+    BindingTypes, BuiltinType, CompileTarget, Conditional, ConditionalNodeSequence, ElementKind,
+    ElementNode, FervidAtom, Interpolation, IntoIdent, Node, PatchFlags, PatchHints,
+    SfcTemplateBlock, StartingTag, StrOrExpr, TemplateGenerationMode, VBindDirective,
+    VSlotDirective, VUE_BUILTINS,
 };
 use smallvec::SmallVec;
 use swc_core::{
@@ -10,11 +11,17 @@ use swc_core::{
     ecma::ast::{Bool, Expr, Lit},
 };
 
-use crate::{TemplateScope, TransformSfcContext};
+use crate::{
+    error::{TemplateError, TemplateErrorKind, TransformError},
+    TemplateScope, TransformSfcContext,
+};
 
 use super::{
-    asset_urls::transform_asset_urls, collect_vars::collect_variables,
+    asset_urls::transform_asset_urls,
+    collect_vars::collect_variables,
     expr_transform::BindingsHelperTransform,
+    html_nesting::validate_html_nesting,
+    validation::{validate_children_slots, validate_element},
 };
 
 pub struct TemplateVisitor<'s> {
@@ -33,12 +40,46 @@ pub fn transform_and_record_template(
     template: &mut SfcTemplateBlock,
     ctx: &mut TransformSfcContext,
 ) {
+    // Invalid nesting is only a warning, it does not change the output
+    validate_html_nesting(&template.roots, &mut ctx.warnings);
+
+    // `<template v-slot>` is not allowed in the root
+    validate_children_slots(
+        &mut template.roots,
+        ElementKind::Element,
+        false,
+        &mut ctx.errors,
+    );
+
     // Optimize conditional sequences within template root
-    optimize_children(&mut template.roots, ElementKind::Element);
+    optimize_children(&mut template.roots, ElementKind::Element, &mut ctx.errors);
+
+    // Vue 2 has no fragments, the roots are neither wrapped nor all rendered
+    if matches!(ctx.bindings_helper.target, CompileTarget::Vue2) {
+        if let Some(extra_root) = template
+            .roots
+            .iter()
+            .filter(|root| !matches!(root, Node::Comment(_, _)))
+            .nth(1)
+        {
+            let span = match extra_root {
+                Node::Element(element_node) => element_node.span,
+                Node::ConditionalSeq(conditional_seq) => conditional_seq.if_node.node.span,
+                Node::Text(_, span) | Node::Comment(_, span) => *span,
+                Node::Interpolation(interpolation) => interpolation.span,
+            };
+            ctx.errors
+                .push(TransformError::TemplateError(TemplateError {
+                    span,
+                    kind: TemplateErrorKind::Vue2MultipleRoots,
+                }));
+        }
+    }
 
     // Merge more than 1 child into a separate `<template>` element so that Fragment gets generated.
     // #11: Do this only when not all children are `TextNode`s.
     if template.roots.len() > 1
+        && matches!(ctx.bindings_helper.target, CompileTarget::Vue3)
         && !template
             .roots
             .iter()
@@ -83,8 +124,13 @@ pub fn transform_and_record_template(
 }
 
 /// Optimizes the children by removing whitespace in between `ElementNode`s,
-/// as well as folding `v-if`/`v-else-if`/`v-else` sequences into a `ConditionalNodeSequence`
-fn optimize_children(children: &mut Vec<Node>, element_kind: ElementKind) {
+/// as well as folding `v-if`/`v-else-if`/`v-else` sequences into a `ConditionalNodeSequence`.
+/// `v-else-if`/`v-else` without a preceding `v-if` are reported and left as regular elements.
+fn optimize_children(
+    children: &mut Vec<Node>,
+    element_kind: ElementKind,
+    errors: &mut Vec<TransformError>,
+) {
     let children_len = children.len();
 
     // Discard children mask, limited to 128 children. 0 means to preserve the node, 1 to discard
@@ -198,7 +244,7 @@ fn optimize_children(children: &mut Vec<Node>, element_kind: ElementKind) {
             // Check for `v-else-if`
             if let Some(v_else_if) = directives.v_else_if.take() {
                 let Some(ref mut seq) = seq else {
-                    // This must be a warning, v-else-if without v-if
+                    report_no_adjacent_if(&child, errors);
                     finish_seq!(child);
                     continue;
                 };
@@ -213,7 +259,8 @@ fn optimize_children(children: &mut Vec<Node>, element_kind: ElementKind) {
             // Check for `v-else`
             if directives.v_else.is_some() {
                 let Some(ref mut cond_seq) = seq else {
-                    // This must be a warning, v-else without v-if
+                    directives.v_else = None;
+                    report_no_adjacent_if(&child, errors);
                     finish_seq!(child);
                     continue;
                 };
@@ -235,6 +282,17 @@ fn optimize_children(children: &mut Vec<Node>, element_kind: ElementKind) {
     }
 }
 
+fn report_no_adjacent_if(node: &Node, errors: &mut Vec<TransformError>) {
+    let Node::Element(element) = node else {
+        return;
+    };
+
+    errors.push(TransformError::TemplateError(TemplateError {
+        span: element.span,
+        kind: TemplateErrorKind::VElseNoAdjacentIf,
+    }));
+}
+
 // Optimize combined usage of conditional directives and `v-for`
 // https://github.com/vuejs/core/blob/438a74aad840183286fbdb488178510f37218a73/packages/compiler-core/src/transforms/vIf.ts#L260
 fn optimize_v_if_plus_v_for(mut parent: ElementNode) -> ElementNode {
@@ -296,6 +354,9 @@ trait VisitMut {
 
 impl Visitor for TemplateVisitor<'_> {
     fn visit_element_node(&mut self, element_node: &mut ElementNode) {
+        // User transforms go first
+        self.apply_plugins_before(element_node);
+
         let parent_scope = self.current_scope;
         let mut scope_to_use = parent_scope;
 
@@ -304,6 +365,16 @@ impl Visitor for TemplateVisitor<'_> {
         let is_component = matches!(element_kind, ElementKind::Component);
         element_node.kind = element_kind;
 
+        validate_element(element_node, &mut self.ctx.errors);
+
+        // Native DOM listeners are never attached on the server
+        if self.ctx.strip_client_only && matches!(element_kind, ElementKind::Element) {
+            element_node
+                .starting_tag
+                .attributes
+                .retain(|attr| !matches!(attr, AttributeOrBinding::VOn(_)));
+        }
+
         if is_component {
             self.maybe_resolve_component(&element_node.starting_tag.tag_name);
         }
@@ -624,7 +695,11 @@ impl Visitor for TemplateVisitor<'_> {
         }
 
         // Merge conditional nodes and clean up whitespace
-        optimize_children(&mut element_node.children, element_kind);
+        optimize_children(
+            &mut element_node.children,
+            element_kind,
+            &mut self.ctx.errors,
+        );
 
         // Patch flag for HTML elements which only contain interpolation and text,
         // e.g. `<p>{{ msg }}</p>`.
@@ -698,6 +773,9 @@ impl Visitor for TemplateVisitor<'_> {
             patch_hints.flags |= PatchFlags::Text;
         }
 
+        // User transforms go last
+        self.apply_plugins_after(element_node);
+
         // Restore the parent scope
         self.current_scope = parent_scope;
     }
@@ -1373,6 +1451,85 @@ mod tests {
         };
     }
 
+    #[test]
+    fn it_strips_native_listeners() {
+        // <div @click="foo" id="a"><MyComp @click="foo" /></div>
+        let on_click = || {
+            AttributeOrBinding::VOn(fervid_core::VOnDirective {
+                event: Some(StrOrExpr::Str("click".into())),
+                handler: Some(js("foo")),
+                modifiers: vec![],
+                span: DUMMY_SP,
+            })
+        };
+        let create_template = || SfcTemplateBlock {
+            lang: "html".into(),
+            roots: vec![Node::Element(ElementNode {
+                starting_tag: StartingTag {
+                    tag_name: "div".into(),
+                    attributes: vec![
+                        on_click(),
+                        AttributeOrBinding::RegularAttribute {
+                            name: "id".into(),
+                            value: "a".into(),
+                            span: DUMMY_SP,
+                        },
+                    ],
+                    directives: None,
+                },
+                children: vec![Node::Element(ElementNode {
+                    starting_tag: StartingTag {
+                        tag_name: "MyComp".into(),
+                        attributes: vec![on_click()],
+                        directives: None,
+                    },
+                    children: vec![],
+                    template_scope: 0,
+                    kind: ElementKind::Element,
+                    patch_hints: Default::default(),
+                    span: DUMMY_SP,
+                })],
+                template_scope: 0,
+                kind: ElementKind::Element,
+                patch_hints: Default::default(),
+                span: DUMMY_SP,
+            })],
+            span: DUMMY_SP,
+        };
+
+        let count_listeners = |element: &ElementNode| {
+            element
+                .starting_tag
+                .attributes
+                .iter()
+                .filter(|attr| matches!(attr, AttributeOrBinding::VOn(_)))
+                .count()
+        };
+
+        for strip_client_only in [false, true] {
+            let mut sfc_template = create_template();
+            let mut ctx = TransformSfcContext::anonymous();
+            ctx.strip_client_only = strip_client_only;
+            transform_and_record_template(&mut sfc_template, &mut ctx);
+
+            let Node::Element(ref div) = sfc_template.roots[0] else {
+                panic!("Root is not an element")
+            };
+            let Node::Element(ref component) = div.children[0] else {
+                panic!("Child is not an element")
+            };
+
+            // Only the listeners of the plain elements are stripped
+            let expected_div_listeners = if strip_client_only { 0 } else { 1 };
+            assert_eq!(expected_div_listeners, count_listeners(div));
+            assert_eq!(
+                1 + expected_div_listeners,
+                div.starting_tag.attributes.len()
+            );
+            assert_eq!(1, count_listeners(component));
+        }
+    }
+
     // text
     fn text_node() -> Node {
         Node::Text("text".into(), DUMMY_SP)
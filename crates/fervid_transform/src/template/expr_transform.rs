@@ -794,6 +794,12 @@ impl TransformVisitor<'_> {
             });
         }
 
+        // Vapor render is inlined to `setup`, which has no `_ctx` to read unknown bindings from
+        if self.bindings_helper.is_vapor && matches!(binding_type, BindingTypes::Unresolved) {
+            self.has_js_bindings = true;
+            return IdentTransformStrategy::LeaveUnchanged;
+        }
+
         // Get the prefix which fits the scope (e.g. `_ctx.` for unknown scopes, `$setup.` for setup scope)
         if let Some(prefix) = get_prefix(&binding_type, self.is_inline) {
             self.has_js_bindings = true;
@@ -1297,6 +1303,32 @@ mod tests {
         test!("\"string\"", "\"string\"");
     }
 
+    #[test]
+    fn it_leaves_unresolved_unchanged_in_vapor() {
+        let mut helper = BindingsHelper {
+            template_generation_mode: TemplateGenerationMode::Inline,
+            is_vapor: true,
+            ..Default::default()
+        };
+        helper.setup_bindings.push(SetupBinding::new(
+            FervidAtom::from("count"),
+            BindingTypes::SetupRef,
+        ));
+
+        macro_rules! test {
+            ($expr: literal, $expected: literal) => {
+                let mut expr = js($expr);
+                helper.transform_expr(&mut expr, 0);
+
+                assert_eq!(to_str(&expr), $expected);
+            };
+        }
+
+        test!("unknown", "unknown");
+        test!("count + unknown", "count.value+unknown");
+        test!("x => fn(x, count)", "x=>fn(x,count.value)");
+    }
+
     #[test]
     fn it_works_with_template_scope_hierarchy() {
         let v_root = FervidAtom::from("root");
@@ -0,0 +1,394 @@
+//! Validation of the element nesting against the HTML content model, mirroring `validateHtmlNesting` of `@vue/compiler-dom`.
+//! Browsers silently rewrite invalid nesting (e.g. `<div>` inside `<p>`) when parsing the server-rendered markup,
+//! which then does not match the virtual DOM during hydration. The validation only produces warnings.
+
+use fervid_core::{is_html_tag, ElementNode, Node, VUE_BUILTINS};
+
+use crate::error::{TemplateError, TemplateErrorKind, TransformError};
+
+/// Validates the nesting of the plain HTML elements in the template.
+/// Must be called before the conditional sequences are folded.
+///
+/// Components and built-ins other than `<slot>` render their children elsewhere,
+/// therefore the children are validated as if they were roots.
+/// `<template>` and `<slot>` are transparent, i.e. their children are validated against their parent.
+pub fn validate_html_nesting(roots: &[Node], warnings: &mut Vec<TransformError>) {
+    let mut ancestors = Vec::new();
+    validate_children(roots, &mut ancestors, warnings);
+}
+
+fn validate_children<'a>(
+    children: &'a [Node],
+    ancestors: &mut Vec<&'a str>,
+    warnings: &mut Vec<TransformError>,
+) {
+    for child in children.iter() {
+        let Node::Element(element_node) = child else {
+            continue;
+        };
+
+        let tag_name: &str = &element_node.starting_tag.tag_name;
+        if tag_name == "template" || tag_name == "slot" {
+            validate_children(&element_node.children, ancestors, warnings);
+            continue;
+        }
+
+        if !is_plain_element(tag_name) {
+            validate_children(&element_node.children, &mut Vec::new(), warnings);
+            continue;
+        }
+
+        validate_element(element_node, ancestors, warnings);
+
+        ancestors.push(tag_name);
+        validate_children(&element_node.children, ancestors, warnings);
+        ancestors.pop();
+    }
+}
+
+fn validate_element(
+    element_node: &ElementNode,
+    ancestors: &[&str],
+    warnings: &mut Vec<TransformError>,
+) {
+    let tag_name: &str = &element_node.starting_tag.tag_name;
+    let Some((&parent, rest)) = ancestors.split_last() else {
+        return;
+    };
+
+    let invalid_ancestor = if !is_valid_html_nesting(parent, tag_name) {
+        Some((parent, true))
+    } else if is_never_nested(tag_name) && rest.contains(&tag_name) {
+        // `<a>`, `<form>` and `<button>` are closed or dropped by the browser at any depth
+        Some((tag_name, false))
+    } else {
+        None
+    };
+
+    if let Some((ancestor, is_direct_child)) = invalid_ancestor {
+        warnings.push(TransformError::TemplateError(TemplateError {
+            span: element_node.span,
+            kind: TemplateErrorKind::InvalidHtmlNesting {
+                tag: tag_name.to_owned(),
+                ancestor: ancestor.to_owned(),
+                is_direct_child,
+            },
+        }));
+    }
+}
+
+fn is_plain_element(tag_name: &str) -> bool {
+    !VUE_BUILTINS.contains_key(tag_name) && is_html_tag(tag_name)
+}
+
+fn is_never_nested(tag_name: &str) -> bool {
+    matches!(tag_name, "a" | "form" | "button")
+}
+
+/// Checks if `child` can be a direct child of `parent` according to the HTML content model.
+/// Only the nesting which makes the browser rewrite the markup is considered invalid.
+///
+/// https://github.com/vuejs/core/blob/main/packages/compiler-dom/src/htmlNesting.ts
+pub fn is_valid_html_nesting(parent: &str, child: &str) -> bool {
+    // The only children which are valid for the parent
+    if let Some(valid_children) = only_valid_children(parent) {
+        return valid_children.contains(&child);
+    }
+
+    // The only parents which are valid for the child
+    if let Some(valid_parents) = only_valid_parents(child) {
+        return valid_parents.contains(&parent);
+    }
+
+    if known_invalid_children(parent).contains(&child) {
+        return false;
+    }
+
+    !known_invalid_parents(child).contains(&parent)
+}
+
+const HEADINGS: &[&str] = &["h1", "h2", "h3", "h4", "h5", "h6"];
+
+fn only_valid_children(parent: &str) -> Option<&'static [&'static str]> {
+    let valid_children: &[&str] = match parent {
+        "head" => &[
+            "base",
+            "basefront",
+            "bgsound",
+            "link",
+            "meta",
+            "title",
+            "noscript",
+            "noframes",
+            "style",
+            "script",
+            "template",
+        ],
+        "optgroup" => &["option"],
+        "select" => &["optgroup", "option", "hr"],
+        // Table
+        "table" => &["caption", "colgroup", "tbody", "tfoot", "thead"],
+        "tr" => &["td", "th"],
+        "colgroup" => &["col"],
+        "tbody" | "thead" | "tfoot" => &["tr"],
+        // These elements can not have any children elements
+        "script" | "iframe" | "option" | "textarea" | "style" | "title" => &[],
+        _ => return None,
+    };
+
+    Some(valid_children)
+}
+
+fn only_valid_parents(child: &str) -> Option<&'static [&'static str]> {
+    let valid_parents: &[&str] = match child {
+        // Sections
+        "html" => &[],
+        "body" | "head" => &["html"],
+        // Table
+        "td" | "th" => &["tr"],
+        "colgroup" | "caption" | "tbody" | "tfoot" | "thead" => &["table"],
+        "col" => &["colgroup"],
+        "tr" => &["tbody", "thead", "tfoot"],
+        // Data list
+        "dd" | "dt" => &["dl", "div"],
+        // Other
+        "figcaption" => &["figure"],
+        "summary" => &["details"],
+        "area" => &["map"],
+        _ => return None,
+    };
+
+    Some(valid_parents)
+}
+
+fn known_invalid_children(parent: &str) -> &'static [&'static str] {
+    match parent {
+        "p" => &[
+            "address",
+            "article",
+            "aside",
+            "blockquote",
+            "center",
+            "details",
+            "dialog",
+            "dir",
+            "div",
+            "dl",
+            "fieldset",
+            "figure",
+            "footer",
+            "form",
+            "h1",
+            "h2",
+            "h3",
+            "h4",
+            "h5",
+            "h6",
+            "header",
+            "hgroup",
+            "hr",
+            "li",
+            "main",
+            "nav",
+            "menu",
+            "ol",
+            "p",
+            "pre",
+            "section",
+            "table",
+            "ul",
+        ],
+        "svg" => &[
+            "b",
+            "blockquote",
+            "br",
+            "code",
+            "dd",
+            "div",
+            "dl",
+            "dt",
+            "em",
+            "embed",
+            "h1",
+            "h2",
+            "h3",
+            "h4",
+            "h5",
+            "h6",
+            "hr",
+            "i",
+            "img",
+            "li",
+            "menu",
+            "meta",
+            "ol",
+            "p",
+            "pre",
+            "ruby",
+            "s",
+            "small",
+            "span",
+            "strong",
+            "sub",
+            "sup",
+            "table",
+            "u",
+            "ul",
+            "var",
+        ],
+        // Interactive content
+        "button" => &[
+            "a", "button", "details", "embed", "iframe", "input", "label", "select", "textarea",
+        ],
+        _ => &[],
+    }
+}
+
+fn known_invalid_parents(child: &str) -> &'static [&'static str] {
+    match child {
+        "a" => &["a"],
+        "button" => &["button"],
+        "dd" | "dt" => &["dd", "dt"],
+        "form" => &["form"],
+        "li" => &["li"],
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => HEADINGS,
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::{ElementKind, StartingTag};
+    use swc_core::common::{BytePos, Span};
+
+    use super::*;
+
+    #[test]
+    fn it_follows_the_content_model() {
+        assert!(!is_valid_html_nesting("p", "div"));
+        assert!(!is_valid_html_nesting("a", "a"));
+        assert!(!is_valid_html_nesting("table", "tr"));
+        assert!(!is_valid_html_nesting("button", "input"));
+        assert!(!is_valid_html_nesting("h1", "h2"));
+        assert!(!is_valid_html_nesting("ul", "td"));
+
+        assert!(is_valid_html_nesting("p", "span"));
+        assert!(is_valid_html_nesting("tbody", "tr"));
+        assert!(is_valid_html_nesting("div", "dd"));
+        assert!(is_valid_html_nesting("ul", "li"));
+        assert!(is_valid_html_nesting("div", "p"));
+    }
+
+    #[test]
+    fn it_reports_invalid_nesting() {
+        // <p><div></div></p>
+        // <table><tr></tr></table>
+        let warnings = validate(vec![
+            element("p", vec![element("div", vec![], span(2))], span(1)),
+            element("table", vec![element("tr", vec![], span(4))], span(3)),
+        ]);
+
+        assert_eq!(vec!["<div> in <p> 20", "<tr> in <table> 40"], warnings);
+    }
+
+    #[test]
+    fn it_reports_nested_anchors_at_any_depth() {
+        // <a><span><a></a></span></a>
+        let warnings = validate(vec![element(
+            "a",
+            vec![element(
+                "span",
+                vec![element("a", vec![], span(3))],
+                span(2),
+            )],
+            span(1),
+        )]);
+
+        assert_eq!(vec!["<a> inside <a> 30"], warnings);
+    }
+
+    #[test]
+    fn it_skips_components_and_looks_through_templates() {
+        // <table><MyRow/></table>
+        // <table><template><tr></tr></template></table>
+        // <p><MyComp><div></div></MyComp></p>
+        // <ul><slot><li></li></slot></ul>
+        let warnings = validate(vec![
+            element("table", vec![element("MyRow", vec![], span(2))], span(1)),
+            element(
+                "table",
+                vec![element(
+                    "template",
+                    vec![element("tr", vec![], span(5))],
+                    span(4),
+                )],
+                span(3),
+            ),
+            element(
+                "p",
+                vec![element(
+                    "MyComp",
+                    vec![element("div", vec![], span(8))],
+                    span(7),
+                )],
+                span(6),
+            ),
+            element(
+                "ul",
+                vec![element(
+                    "slot",
+                    vec![element("li", vec![], span(11))],
+                    span(10),
+                )],
+                span(9),
+            ),
+        ]);
+
+        assert_eq!(vec!["<tr> in <table> 50"], warnings);
+    }
+
+    fn validate(roots: Vec<Node>) -> Vec<String> {
+        let mut warnings = Vec::new();
+        validate_html_nesting(&roots, &mut warnings);
+
+        warnings
+            .into_iter()
+            .map(|warning| match warning {
+                TransformError::TemplateError(TemplateError {
+                    span,
+                    kind:
+                        TemplateErrorKind::InvalidHtmlNesting {
+                            tag,
+                            ancestor,
+                            is_direct_child,
+                        },
+                }) => format!(
+                    "<{tag}> {} <{ancestor}> {}",
+                    if is_direct_child { "in" } else { "inside" },
+                    span.lo.0
+                ),
+                e => panic!("Unexpected warning {e:?}"),
+            })
+            .collect()
+    }
+
+    /// Distinct span to identify the node
+    fn span(n: u32) -> Span {
+        Span::new(BytePos(n * 10), BytePos(n * 10 + 1))
+    }
+
+    fn element(tag_name: &str, children: Vec<Node>, span: Span) -> Node {
+        Node::Element(ElementNode {
+            kind: ElementKind::Element,
+            starting_tag: StartingTag {
+                tag_name: tag_name.into(),
+                attributes: vec![],
+                directives: None,
+            },
+            children,
+            template_scope: 0,
+            patch_hints: Default::default(),
+            span,
+        })
+    }
+}
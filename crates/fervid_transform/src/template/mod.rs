@@ -2,8 +2,11 @@ pub mod asset_urls;
 pub mod ast_transform;
 pub mod collect_vars;
 pub mod expr_transform;
+pub mod html_nesting;
 pub mod js_builtins;
+pub mod plugins;
 pub mod resolutions;
 pub mod utils;
 pub mod v_on;
+pub mod validation;
 pub use ast_transform::transform_and_record_template;
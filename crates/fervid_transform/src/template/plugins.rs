@@ -0,0 +1,414 @@
+//! User transforms of the `<template>`, the compile-time equivalents
+//! of `nodeTransforms` and `directiveTransforms` of `@vue/compiler-core`.
+//!
+//! Plugins are registered in [`TemplatePlugins`] and run for every element of the template:
+//! 1. [`NodeTransform::enter_element`] of all the node transforms, in the registration order;
+//! 2. [`DirectiveTransform`]s of the custom directives found on the element;
+//! 3. built-in transforms of the element (expressions, patch flags, asset URLs, etc.);
+//! 4. the same steps for the children of the element;
+//! 5. [`NodeTransform::exit_element`] of all the node transforms, in the reverse order.
+//!
+//! Expressions added before the built-in transforms are transformed as usual,
+//! e.g. `foo` becomes `$setup.foo`, while the ones added on exit are kept as-is.
+
+use std::sync::Arc;
+
+use fervid_core::{BindingTypes, ElementNode, FervidAtom, VCustomDirective};
+use fxhash::FxHashMap as HashMap;
+use swc_core::{
+    common::Span,
+    ecma::ast::{Expr, ImportDecl, ImportSpecifier},
+};
+
+use crate::{
+    error::{TemplateError, TemplateErrorKind, TransformError},
+    BindingsHelper,
+};
+
+use super::{ast_transform::TemplateVisitor, expr_transform::BindingsHelperTransform};
+
+/// Transform of the template elements, e.g. to strip `data-testid` attributes in production.
+pub trait NodeTransform: Send + Sync {
+    /// Called before the built-in transforms of the element.
+    /// `ctx.scope` is the scope of the parent, because `v-for` and `v-slot` are not processed yet.
+    fn enter_element(&self, _element: &mut ElementNode, _ctx: &mut TemplatePluginContext) {}
+
+    /// Called after the element and all of its children were transformed.
+    fn exit_element(&self, _element: &mut ElementNode, _ctx: &mut TemplatePluginContext) {}
+}
+
+/// Compile-time transform of a custom directive, e.g. `v-t` for i18n.
+pub trait DirectiveTransform: Send + Sync {
+    /// Called with the directive taken out of the `element`, before the built-in transforms.
+    /// Return `true` to keep the directive as a runtime directive, or `false` to drop it.
+    fn transform(
+        &self,
+        directive: &mut VCustomDirective,
+        element: &mut ElementNode,
+        ctx: &mut TemplatePluginContext,
+    ) -> bool;
+}
+
+/// User transforms applied to the template
+#[derive(Clone, Default)]
+pub struct TemplatePlugins {
+    /// Transforms applied to every element
+    pub node_transforms: Vec<Arc<dyn NodeTransform>>,
+    /// Transforms of custom directives by their name, e.g. `t` for `v-t`
+    pub directive_transforms: HashMap<FervidAtom, Arc<dyn DirectiveTransform>>,
+}
+
+/// What a plugin has access to when transforming an element
+pub struct TemplatePluginContext<'c> {
+    /// Bindings of the SFC, as well as the Vue imports used by the template
+    pub bindings_helper: &'c mut BindingsHelper,
+    /// Template scope in which the element is located
+    pub scope: u32,
+    errors: &'c mut Vec<TransformError>,
+}
+
+impl TemplatePlugins {
+    pub fn is_empty(&self) -> bool {
+        self.node_transforms.is_empty() && self.directive_transforms.is_empty()
+    }
+}
+
+impl std::fmt::Debug for TemplatePlugins {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TemplatePlugins")
+            .field("node_transforms", &self.node_transforms.len())
+            .field(
+                "directive_transforms",
+                &self.directive_transforms.keys().collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl TemplatePluginContext<'_> {
+    /// Adds an import to the module.
+    /// Its local names are not resolved by the expression transform, thus can be used directly.
+    pub fn add_import(&mut self, import: ImportDecl) {
+        for specifier in import.specifiers.iter() {
+            let local = match specifier {
+                ImportSpecifier::Named(s) => &s.local,
+                ImportSpecifier::Default(s) => &s.local,
+                ImportSpecifier::Namespace(s) => &s.local,
+            };
+
+            self.bindings_helper
+                .used_bindings
+                .insert(local.sym.to_owned(), BindingTypes::JsGlobal);
+        }
+
+        self.bindings_helper.imports.push(import);
+    }
+
+    /// Transforms the expression the same way as built-in transforms do.
+    /// Returns `true` when the expression references any bindings.
+    ///
+    /// Only needed in [`NodeTransform::exit_element`],
+    /// because the expressions added earlier are transformed by the built-in transforms.
+    pub fn transform_expr(&mut self, expr: &mut Expr) -> bool {
+        self.bindings_helper.transform_expr(expr, self.scope)
+    }
+
+    /// Reports an error of the plugin
+    pub fn report_error(&mut self, span: Span, message: impl Into<String>) {
+        self.errors
+            .push(TransformError::TemplateError(TemplateError {
+                span,
+                kind: TemplateErrorKind::Plugin(message.into()),
+            }));
+    }
+}
+
+impl TemplateVisitor<'_> {
+    /// Applies node enter transforms and directive transforms
+    pub(crate) fn apply_plugins_before(&mut self, element_node: &mut ElementNode) {
+        let plugins = &self.ctx.template_plugins;
+        if plugins.is_empty() {
+            return;
+        }
+
+        let mut plugin_ctx = TemplatePluginContext {
+            bindings_helper: &mut self.ctx.bindings_helper,
+            scope: self.current_scope,
+            errors: &mut self.ctx.errors,
+        };
+
+        for node_transform in plugins.node_transforms.iter() {
+            node_transform.enter_element(element_node, &mut plugin_ctx);
+        }
+
+        // Directives are taken out so that transforms could freely modify the element
+        let custom_directives = match element_node.starting_tag.directives {
+            Some(ref mut directives)
+                if !plugins.directive_transforms.is_empty() && !directives.custom.is_empty() =>
+            {
+                std::mem::take(&mut directives.custom)
+            }
+            _ => return,
+        };
+
+        let mut kept_directives = Vec::with_capacity(custom_directives.len());
+        for mut directive in custom_directives {
+            let should_keep = match plugins.directive_transforms.get(&directive.name) {
+                Some(directive_transform) => {
+                    directive_transform.transform(&mut directive, element_node, &mut plugin_ctx)
+                }
+                None => true,
+            };
+
+            if should_keep {
+                kept_directives.push(directive);
+            }
+        }
+
+        // Transforms may have added their own directives
+        let directives = element_node
+            .starting_tag
+            .directives
+            .get_or_insert_with(Default::default);
+        kept_directives.append(&mut directives.custom);
+        directives.custom = kept_directives;
+    }
+
+    /// Applies node exit transforms in the reverse order
+    pub(crate) fn apply_plugins_after(&mut self, element_node: &mut ElementNode) {
+        let plugins = &self.ctx.template_plugins;
+        if plugins.node_transforms.is_empty() {
+            return;
+        }
+
+        let mut plugin_ctx = TemplatePluginContext {
+            bindings_helper: &mut self.ctx.bindings_helper,
+            scope: element_node.template_scope,
+            errors: &mut self.ctx.errors,
+        };
+
+        for node_transform in plugins.node_transforms.iter().rev() {
+            node_transform.exit_element(element_node, &mut plugin_ctx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::{
+        fervid_atom, AttributeOrBinding, ElementKind, Interpolation, IntoIdent, Node,
+        SfcTemplateBlock, StartingTag, VueDirectives,
+    };
+    use swc_core::{
+        common::DUMMY_SP,
+        ecma::ast::{CallExpr, Callee, ExprOrSpread, ImportNamedSpecifier, ImportSpecifier, Str},
+    };
+
+    use crate::{
+        template::transform_and_record_template,
+        test_utils::{js, to_str},
+        SetupBinding, TransformSfcContext,
+    };
+
+    use super::*;
+
+    struct StripTestIds;
+
+    impl NodeTransform for StripTestIds {
+        fn enter_element(&self, element: &mut ElementNode, _ctx: &mut TemplatePluginContext) {
+            element.starting_tag.attributes.retain(|attr| {
+                !matches!(attr, AttributeOrBinding::RegularAttribute { name, .. } if name == "data-testid")
+            });
+        }
+    }
+
+    /// `<p v-t="msg">` -> `<p>{{ _t(msg) }}</p>`
+    struct VT;
+
+    impl DirectiveTransform for VT {
+        fn transform(
+            &self,
+            directive: &mut VCustomDirective,
+            element: &mut ElementNode,
+            ctx: &mut TemplatePluginContext,
+        ) -> bool {
+            let Some(value) = directive.value.take() else {
+                ctx.report_error(element.span, "v-t requires a value");
+                return false;
+            };
+
+            ctx.add_import(ImportDecl {
+                span: DUMMY_SP,
+                specifiers: vec![ImportSpecifier::Named(ImportNamedSpecifier {
+                    span: DUMMY_SP,
+                    local: fervid_atom!("_t").into_ident(),
+                    imported: Some(fervid_atom!("t").into_ident().into()),
+                    is_type_only: false,
+                })],
+                src: Box::new(Str {
+                    span: DUMMY_SP,
+                    value: fervid_atom!("vue-i18n"),
+                    raw: None,
+                }),
+                type_only: false,
+                with: None,
+                phase: Default::default(),
+            });
+
+            element.children = vec![Node::Interpolation(Interpolation {
+                value: Box::new(Expr::Call(CallExpr {
+                    span: DUMMY_SP,
+                    ctxt: Default::default(),
+                    callee: Callee::Expr(Box::new(Expr::Ident(fervid_atom!("_t").into_ident()))),
+                    args: vec![ExprOrSpread {
+                        spread: None,
+                        expr: value,
+                    }],
+                    type_args: None,
+                })),
+                template_scope: 0,
+                patch_flag: false,
+                span: DUMMY_SP,
+            })];
+
+            false
+        }
+    }
+
+    fn element(
+        tag_name: &str,
+        attributes: Vec<AttributeOrBinding>,
+        directives: Option<Box<VueDirectives>>,
+    ) -> Node {
+        Node::Element(ElementNode {
+            kind: ElementKind::Element,
+            starting_tag: StartingTag {
+                tag_name: tag_name.into(),
+                attributes,
+                directives,
+            },
+            children: vec![],
+            template_scope: 0,
+            patch_hints: Default::default(),
+            span: DUMMY_SP,
+        })
+    }
+
+    fn transform(
+        roots: Vec<Node>,
+        plugins: TemplatePlugins,
+    ) -> (SfcTemplateBlock, TransformSfcContext) {
+        let mut ctx = TransformSfcContext::anonymous();
+        ctx.template_plugins = plugins;
+        ctx.bindings_helper.setup_bindings.push(SetupBinding::new(
+            fervid_atom!("msg"),
+            BindingTypes::SetupConst,
+        ));
+
+        let mut template = SfcTemplateBlock {
+            lang: "html".into(),
+            roots,
+            span: DUMMY_SP,
+        };
+        transform_and_record_template(&mut template, &mut ctx);
+
+        (template, ctx)
+    }
+
+    #[test]
+    fn it_applies_node_transforms() {
+        let mut plugins = TemplatePlugins::default();
+        plugins.node_transforms.push(Arc::new(StripTestIds));
+
+        let (template, _) = transform(
+            vec![element(
+                "div",
+                vec![
+                    AttributeOrBinding::RegularAttribute {
+                        name: "data-testid".into(),
+                        value: "foo".into(),
+                        span: DUMMY_SP,
+                    },
+                    AttributeOrBinding::RegularAttribute {
+                        name: "class".into(),
+                        value: "bar".into(),
+                        span: DUMMY_SP,
+                    },
+                ],
+                None,
+            )],
+            plugins,
+        );
+
+        let Node::Element(ref div) = template.roots[0] else {
+            panic!("Root is not an element")
+        };
+        assert_eq!(1, div.starting_tag.attributes.len());
+        assert!(matches!(
+            div.starting_tag.attributes[0],
+            AttributeOrBinding::RegularAttribute { ref name, .. } if name == "class"
+        ));
+    }
+
+    #[test]
+    fn it_applies_directive_transforms() {
+        let mut plugins = TemplatePlugins::default();
+        plugins
+            .directive_transforms
+            .insert(fervid_atom!("t"), Arc::new(VT));
+
+        let directive = |name: &str, value: Option<Box<Expr>>| VCustomDirective {
+            name: name.into(),
+            value,
+            ..Default::default()
+        };
+        let (template, ctx) = transform(
+            vec![
+                element(
+                    "p",
+                    vec![],
+                    Some(Box::new(VueDirectives {
+                        custom: vec![directive("t", Some(js("msg"))), directive("focus", None)],
+                        ..Default::default()
+                    })),
+                ),
+                element(
+                    "p",
+                    vec![],
+                    Some(Box::new(VueDirectives {
+                        custom: vec![directive("t", None)],
+                        ..Default::default()
+                    })),
+                ),
+            ],
+            plugins,
+        );
+
+        // Two roots are merged into a fragment
+        let Node::Element(ref fragment) = template.roots[0] else {
+            panic!("Root is not an element")
+        };
+        let Node::Element(ref p) = fragment.children[0] else {
+            panic!("Child is not an element")
+        };
+
+        // `v-t` was removed, while `v-focus` is kept as a runtime directive
+        let directives = p
+            .starting_tag
+            .directives
+            .as_ref()
+            .expect("Should have directives");
+        assert_eq!(1, directives.custom.len());
+        assert_eq!(directives.custom[0].name, "focus");
+
+        // Imported `_t` is kept as-is, while `msg` is transformed
+        let Node::Interpolation(ref interpolation) = p.children[0] else {
+            panic!("Child is not an interpolation")
+        };
+        assert_eq!("_t($setup.msg)", to_str(&interpolation.value));
+        assert_eq!(1, ctx.bindings_helper.imports.len());
+
+        // Second `v-t` has no value
+        assert_eq!(1, ctx.errors.len());
+    }
+}
@@ -1,5 +1,6 @@
 use fervid_core::{
-    fervid_atom, BindingTypes, FervidAtom, IntoIdent, StrOrExpr, VOnDirective, VueImports,
+    fervid_atom, BindingTypes, CompileTarget, FervidAtom, IntoIdent, StrOrExpr, VOnDirective,
+    VueImports,
 };
 use swc_core::{
     common::DUMMY_SP,
@@ -17,7 +18,17 @@ use super::{
 
 impl TemplateVisitor<'_> {
     pub fn transform_v_on(&mut self, v_on: &mut VOnDirective, scope_to_use: u32) {
+        // Vue 2 listeners are keyed by the plain event names, e.g. `on: { click: handler }`
+        let is_vue2 = matches!(self.ctx.bindings_helper.target, CompileTarget::Vue2);
+
         match v_on.event.as_mut() {
+            Some(StrOrExpr::Str(_)) if is_vue2 => {}
+            Some(StrOrExpr::Expr(dynamic_event)) if is_vue2 => {
+                self.ctx
+                    .bindings_helper
+                    .transform_expr(dynamic_event, scope_to_use);
+            }
+
             Some(StrOrExpr::Str(static_event)) => {
                 transform_v_on_static_event(static_event);
             }
@@ -0,0 +1,470 @@
+//! Semantic checks of the template, mirroring the errors of `@vue/compiler-core`.
+//! The checks only report errors, except for removing the misplaced `v-slot`s of `<template>`s,
+//! so that they are not reported twice (the template roots may be checked again when wrapped in a fragment).
+
+use fervid_core::{
+    check_attribute_name, fervid_atom, AttributeOrBinding, BuiltinType, ElementKind, ElementNode,
+    FervidAtom, Node, StrOrExpr,
+};
+use swc_core::common::Span;
+
+use crate::error::{TemplateError, TemplateErrorKind, TransformError};
+
+macro_rules! report {
+    ($errors: expr, $kind: ident, $span: expr) => {
+        $errors.push(TransformError::TemplateError(TemplateError {
+            span: $span,
+            kind: TemplateErrorKind::$kind,
+        }))
+    };
+}
+
+/// Validates the element itself and the `v-slot` usage of its children.
+/// Must be called before the children are optimized, i.e. before the conditional sequences are folded.
+pub fn validate_element(element_node: &mut ElementNode, errors: &mut Vec<TransformError>) {
+    let directives = element_node.starting_tag.directives.as_deref();
+    let is_template = element_node.starting_tag.tag_name == "template";
+
+    // `<template v-slot>` is validated by its parent
+    if let Some(directives) = directives {
+        if directives.v_slot.is_some() && !is_template && !is_component(element_node.kind) {
+            report!(errors, VSlotMisplaced, element_node.span);
+        }
+    }
+
+    // `v-model` on plain elements
+    if let (ElementKind::Element, Some(directives)) = (element_node.kind, directives) {
+        let tag_name = &element_node.starting_tag.tag_name;
+        let is_valid_element = matches!(tag_name.as_ref(), "input" | "textarea" | "select");
+
+        for v_model in directives.v_model.iter() {
+            if !is_valid_element {
+                report!(errors, VModelOnInvalidElement, v_model.span);
+            } else if v_model.argument.is_some() {
+                report!(errors, VModelArgOnElement, v_model.span);
+            }
+        }
+    }
+
+    // `<template v-for>` keys
+    if is_template && directives.is_some_and(|d| d.v_for.is_some()) {
+        for child in element_node.children.iter() {
+            let Node::Element(child_element) = child else {
+                continue;
+            };
+
+            let key = child_element
+                .starting_tag
+                .attributes
+                .iter()
+                .find(|attr| check_attribute_name(attr, "key"));
+            if let Some(key) = key {
+                report!(errors, VForTemplateKeyPlacement, attribute_span(key));
+            }
+        }
+    }
+
+    let has_v_slot = directives.is_some_and(|d| d.v_slot.is_some());
+    validate_children_slots(
+        &mut element_node.children,
+        element_node.kind,
+        has_v_slot,
+        errors,
+    );
+}
+
+/// Validates `<template v-slot>` children of an element of `parent_kind`
+pub fn validate_children_slots(
+    children: &mut [Node],
+    parent_kind: ElementKind,
+    parent_has_v_slot: bool,
+    errors: &mut Vec<TransformError>,
+) {
+    let parent_is_component = is_component(parent_kind);
+    let mut seen_slot_names: Vec<FervidAtom> = Vec::new();
+    let mut has_explicit_default_slot = false;
+    let mut first_implicit_default_child: Option<Span> = None;
+
+    for child in children.iter_mut() {
+        let slot_template = match child {
+            Node::Element(child_element)
+                if child_element.starting_tag.tag_name == "template"
+                    && child_element
+                        .starting_tag
+                        .directives
+                        .as_ref()
+                        .is_some_and(|d| d.v_slot.is_some()) =>
+            {
+                Some(child_element)
+            }
+            _ => None,
+        };
+
+        let Some(child_element) = slot_template else {
+            if first_implicit_default_child.is_none() {
+                first_implicit_default_child = implicit_default_child_span(child);
+            }
+            continue;
+        };
+
+        let Some(ref mut directives) = child_element.starting_tag.directives else {
+            continue;
+        };
+
+        if !parent_is_component {
+            directives.v_slot = None;
+            report!(errors, VSlotMisplaced, child_element.span);
+            continue;
+        }
+
+        let Some(ref v_slot) = directives.v_slot else {
+            continue;
+        };
+
+        if parent_has_v_slot {
+            report!(errors, VSlotMixedSlotUsage, child_element.span);
+            continue;
+        }
+
+        // Conditional, iterated and dynamically named slots may share the names
+        let is_dynamic = directives.v_if.is_some()
+            || directives.v_else_if.is_some()
+            || directives.v_else.is_some()
+            || directives.v_for.is_some();
+        if is_dynamic {
+            continue;
+        }
+
+        // `v-slot` without an argument is `v-slot:default`
+        let slot_name = match v_slot.slot_name {
+            None => fervid_atom!("default"),
+            Some(StrOrExpr::Str(ref name)) => name.to_owned(),
+            Some(StrOrExpr::Expr(_)) => continue,
+        };
+
+        if slot_name == "default" {
+            has_explicit_default_slot = true;
+        }
+
+        if seen_slot_names.contains(&slot_name) {
+            report!(errors, VSlotDuplicateSlotNames, child_element.span);
+        } else {
+            seen_slot_names.push(slot_name);
+        }
+    }
+
+    if let (true, true, Some(span)) = (
+        parent_is_component,
+        has_explicit_default_slot,
+        first_implicit_default_child,
+    ) {
+        report!(errors, VSlotExtraneousDefaultSlotChildren, span);
+    }
+}
+
+/// Whether `v-slot` can be used on the element or its `<template>` children
+fn is_component(kind: ElementKind) -> bool {
+    !matches!(
+        kind,
+        ElementKind::Element | ElementKind::Builtin(BuiltinType::Slot)
+    )
+}
+
+/// Span of a child which would go to the default slot, unless it is whitespace or a comment
+fn implicit_default_child_span(child: &Node) -> Option<Span> {
+    match child {
+        Node::Element(element) => Some(element.span),
+        Node::Text(text, span) if !text.trim().is_empty() => Some(*span),
+        Node::Interpolation(interpolation) => Some(interpolation.span),
+        Node::ConditionalSeq(seq) => Some(seq.if_node.node.span),
+        Node::Text(_, _) | Node::Comment(_, _) => None,
+    }
+}
+
+fn attribute_span(attr: &AttributeOrBinding) -> Span {
+    match attr {
+        AttributeOrBinding::RegularAttribute { span, .. } => *span,
+        AttributeOrBinding::VBind(v_bind) => v_bind.span,
+        AttributeOrBinding::VOn(v_on) => v_on.span,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::{
+        SfcTemplateBlock, StartingTag, VModelDirective, VSlotDirective, VueDirectives,
+    };
+    use swc_core::common::{BytePos, DUMMY_SP};
+
+    use crate::{template::transform_and_record_template, test_utils::js, TransformSfcContext};
+
+    use super::*;
+
+    #[test]
+    fn it_reports_v_else_without_v_if() {
+        // <div v-else>a</div>
+        // <p v-if="foo">b</p>
+        // <span>c</span>
+        // <i v-else>d</i>
+        let errors = transform(vec![
+            element_with(
+                "div",
+                VueDirectives {
+                    v_else: Some(()),
+                    ..Default::default()
+                },
+                vec![],
+                span(1),
+            ),
+            element_with(
+                "p",
+                VueDirectives {
+                    v_if: Some(js("foo")),
+                    ..Default::default()
+                },
+                vec![],
+                span(2),
+            ),
+            element("span", vec![], vec![], span(3)),
+            element_with(
+                "i",
+                VueDirectives {
+                    v_else_if: Some(js("bar")),
+                    ..Default::default()
+                },
+                vec![],
+                span(4),
+            ),
+        ]);
+
+        assert_errors(
+            &errors,
+            &[
+                (TemplateErrorKind::VElseNoAdjacentIf, span(1)),
+                (TemplateErrorKind::VElseNoAdjacentIf, span(4)),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_reports_misplaced_v_slot() {
+        // <div v-slot></div>
+        // <template #foo></template>
+        let errors = transform(vec![
+            element_with("div", v_slot(None), vec![], span(1)),
+            element_with("template", v_slot(Some("foo")), vec![], span(2)),
+        ]);
+
+        assert_errors(
+            &errors,
+            &[
+                (TemplateErrorKind::VSlotMisplaced, span(2)),
+                (TemplateErrorKind::VSlotMisplaced, span(1)),
+            ],
+        );
+
+        // <Comp v-slot><template #foo></template></Comp>
+        let errors = transform(vec![element_with(
+            "Comp",
+            v_slot(None),
+            vec![element_with(
+                "template",
+                v_slot(Some("foo")),
+                vec![],
+                span(2),
+            )],
+            span(1),
+        )]);
+        assert_errors(
+            &errors,
+            &[(TemplateErrorKind::VSlotMixedSlotUsage, span(2))],
+        );
+    }
+
+    #[test]
+    fn it_reports_duplicate_and_extraneous_slots() {
+        // <Comp>
+        //   <template #foo></template>
+        //   <template #foo></template>
+        //   <template #default></template>
+        //   <template v-slot></template>
+        //   <template #bar v-if="ok"></template>
+        //   <template #bar v-else></template>
+        //   <div></div>
+        // </Comp>
+        let mut conditional = v_slot(Some("bar"));
+        conditional.v_if = Some(js("ok"));
+        let mut alternate = v_slot(Some("bar"));
+        alternate.v_else = Some(());
+
+        let errors = transform(vec![element(
+            "Comp",
+            vec![],
+            vec![
+                element_with("template", v_slot(Some("foo")), vec![], span(1)),
+                element_with("template", v_slot(Some("foo")), vec![], span(2)),
+                element_with("template", v_slot(Some("default")), vec![], span(3)),
+                element_with("template", v_slot(None), vec![], span(4)),
+                element_with("template", conditional, vec![], span(5)),
+                element_with("template", alternate, vec![], span(6)),
+                element("div", vec![], vec![], span(7)),
+            ],
+            DUMMY_SP,
+        )]);
+
+        assert_errors(
+            &errors,
+            &[
+                (TemplateErrorKind::VSlotDuplicateSlotNames, span(2)),
+                (TemplateErrorKind::VSlotDuplicateSlotNames, span(4)),
+                (
+                    TemplateErrorKind::VSlotExtraneousDefaultSlotChildren,
+                    span(7),
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_reports_v_model_on_plain_elements() {
+        // <div v-model="foo"></div>
+        // <input v-model:bar="foo">
+        // <select v-model="foo"></select>
+        // <Comp v-model:bar="foo"></Comp>
+        let errors = transform(vec![
+            element_with("div", v_model(None, span(1)), vec![], DUMMY_SP),
+            element_with("input", v_model(Some("bar"), span(2)), vec![], DUMMY_SP),
+            element_with("select", v_model(None, span(3)), vec![], DUMMY_SP),
+            element_with("Comp", v_model(Some("bar"), span(4)), vec![], DUMMY_SP),
+        ]);
+
+        assert_errors(
+            &errors,
+            &[
+                (TemplateErrorKind::VModelOnInvalidElement, span(1)),
+                (TemplateErrorKind::VModelArgOnElement, span(2)),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_reports_key_on_template_v_for_children() {
+        // <template v-for="item in list"><div :key="item"></div></template>
+        let errors = transform(vec![element_with(
+            "template",
+            VueDirectives {
+                v_for: Some(fervid_core::VForDirective {
+                    iterable: js("list"),
+                    itervar: js("item"),
+                    patch_flags: Default::default(),
+                    span: DUMMY_SP,
+                }),
+                ..Default::default()
+            },
+            vec![element(
+                "div",
+                vec![AttributeOrBinding::RegularAttribute {
+                    name: "key".into(),
+                    value: "item".into(),
+                    span: span(1),
+                }],
+                vec![],
+                DUMMY_SP,
+            )],
+            DUMMY_SP,
+        )]);
+
+        assert_errors(
+            &errors,
+            &[(TemplateErrorKind::VForTemplateKeyPlacement, span(1))],
+        );
+    }
+
+    fn transform(roots: Vec<Node>) -> Vec<TransformError> {
+        let mut sfc_template = SfcTemplateBlock {
+            lang: "html".into(),
+            roots,
+            span: DUMMY_SP,
+        };
+
+        let mut ctx = TransformSfcContext::anonymous();
+        transform_and_record_template(&mut sfc_template, &mut ctx);
+        ctx.errors
+    }
+
+    fn assert_errors(errors: &[TransformError], expected: &[(TemplateErrorKind, Span)]) {
+        let actual: Vec<String> = errors
+            .iter()
+            .map(|e| match e {
+                TransformError::TemplateError(e) => format!("{:?} {:?}", e.kind, e.span),
+                e => panic!("Unexpected error {e:?}"),
+            })
+            .collect();
+        let expected: Vec<String> = expected
+            .iter()
+            .map(|(kind, span)| format!("{kind:?} {span:?}"))
+            .collect();
+        assert_eq!(expected, actual);
+    }
+
+    /// Distinct span to identify the node
+    fn span(n: u32) -> Span {
+        Span::new(BytePos(n * 10), BytePos(n * 10 + 1))
+    }
+
+    fn element(
+        tag_name: &str,
+        attributes: Vec<AttributeOrBinding>,
+        children: Vec<Node>,
+        span: Span,
+    ) -> Node {
+        Node::Element(ElementNode {
+            kind: ElementKind::Element,
+            starting_tag: StartingTag {
+                tag_name: tag_name.into(),
+                attributes,
+                directives: None,
+            },
+            children,
+            template_scope: 0,
+            patch_hints: Default::default(),
+            span,
+        })
+    }
+
+    fn element_with(
+        tag_name: &str,
+        directives: VueDirectives,
+        children: Vec<Node>,
+        span: Span,
+    ) -> Node {
+        let mut node = element(tag_name, vec![], children, span);
+        if let Node::Element(ref mut element) = node {
+            element.starting_tag.directives = Some(Box::new(directives));
+        }
+        node
+    }
+
+    fn v_slot(slot_name: Option<&str>) -> VueDirectives {
+        VueDirectives {
+            v_slot: Some(VSlotDirective {
+                slot_name: slot_name.map(|name| StrOrExpr::Str(name.into())),
+                value: None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn v_model(argument: Option<&str>, span: Span) -> VueDirectives {
+        VueDirectives {
+            v_model: vec![VModelDirective {
+                argument: argument.map(|name| StrOrExpr::Str(name.into())),
+                value: js("foo"),
+                update_handler: None,
+                modifiers: vec![],
+                span,
+            }],
+            ..Default::default()
+        }
+    }
+}
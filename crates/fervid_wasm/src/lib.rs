@@ -4,76 +4,425 @@
 // #[global_allocator]
 // static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
-use fervid::{compile, CompileOptions, CompileResult, PropsDestructureConfig};
-use swc_core::common::{sync::Lrc, SourceMap, Spanned};
+use fervid::{
+    compile,
+    errors::{lookup_location, render_code_frame, CompileError},
+    CompileOptions, CompileResult, CustomBlockHandlers, PropsDestructureConfig,
+};
+use serde::Serialize;
+use structs::{
+    binding_type_to_str, WasmCompileError, WasmCompileOptions, WasmCompileResult, WasmParseResult,
+};
+use swc_core::common::Spanned;
 use wasm_bindgen::prelude::*;
 
-#[wasm_bindgen(getter_with_clone)]
-#[derive(Clone)]
-pub struct WasmCompileError {
-    pub start_line_number: usize,
-    pub end_line_number: usize,
-    pub start_column: usize,
-    pub end_column: usize,
-    pub message: String,
+mod structs;
+
+/// Kept in sync with the Rust structs by the tests below
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+const TS_TYPES: &str = r#"
+export type BindingTypes =
+  | 'data'
+  | 'props'
+  | 'props-aliased'
+  | 'setup-let'
+  | 'setup-const'
+  | 'setup-reactive-const'
+  | 'setup-maybe-ref'
+  | 'setup-ref'
+  | 'options'
+  | 'literal-const'
+  | 'component'
+  | 'imported'
+  | 'template-local'
+  | 'js-global'
+  | 'unresolved';
+
+export interface TransformAssetUrlsOptions {
+  base?: string;
+  includeAbsolute?: boolean;
+  tags?: Record<string, string[]>;
+  aliases?: Record<string, string>;
+}
+
+export interface WasmCompileOptions {
+  filename?: string;
+  id?: string;
+  isProduction?: boolean;
+  isCustomElement?: boolean;
+  ssr?: boolean;
+  stripClientOnly?: boolean;
+  target?: 'vue3' | 'vue2';
+  sourceMap?: boolean;
+  genDefaultAs?: string;
+  propsDestructure?: boolean | 'error';
+  transformAssetUrls?: boolean | TransformAssetUrlsOptions;
+  i18n?: boolean;
+  outputSetupBindings?: boolean;
+  errorRecoveryMode?: 'hard-fail-on-error' | 'soft-report-all' | 'soft-recover-safe' | 'soft-recover-unsafe';
+}
+
+export interface WasmStyle {
+  code: string;
+  isCompiled: boolean;
+  lang: string;
+  isScoped: boolean;
+  sourceMap?: string;
+}
+
+export interface WasmCustomBlock {
+  content: string;
+  lo: number;
+  hi: number;
+  tagName: string;
+}
+
+export interface WasmCompileError {
+  lo: number;
+  hi: number;
+  message: string;
+  /** Stable code of the error, e.g. `duplicate-define-props` */
+  code: string;
+  /** Warnings, e.g. invalid HTML nesting, do not affect the generated code */
+  severity: 'error' | 'warning';
+  /** Source lines around the error with the error underlined */
+  codeFrame: string;
+  startLineNumber: number;
+  endLineNumber: number;
+  startColumn: number;
+  endColumn: number;
+}
+
+export interface WasmCompileResult {
+  code: string;
+  styles: WasmStyle[];
+  errors: WasmCompileError[];
+  customBlocks: WasmCustomBlock[];
+  sourceMap?: string;
+  setupBindings?: Record<string, BindingTypes>;
+}
+
+export interface WasmParseResult {
+  /** Serialized SFC descriptor with template nodes and swc AST of the scripts */
+  descriptor: Record<string, any>;
+  errors: WasmCompileError[];
+}
+"#;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TYPES_SECTION: &'static str = TS_TYPES;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "WasmCompileOptions")]
+    pub type JsCompileOptions;
+
+    #[wasm_bindgen(typescript_type = "WasmCompileResult")]
+    pub type JsCompileResult;
+
+    #[wasm_bindgen(typescript_type = "WasmParseResult")]
+    pub type JsParseResult;
 }
 
-#[wasm_bindgen(getter_with_clone)]
-pub struct WasmCompileResult {
-    pub code: String,
-    pub errors: Vec<WasmCompileError>,
+/// Compiles a Vue SFC.
+/// Options and result mirror the ones of `@fervid/napi`.
+#[wasm_bindgen]
+pub fn compile_sync(
+    source: &str,
+    options: Option<JsCompileOptions>,
+) -> Result<JsCompileResult, JsError> {
+    let options: WasmCompileOptions = match options {
+        Some(options) => serde_wasm_bindgen::from_value(options.into())?,
+        None => WasmCompileOptions::default(),
+    };
+
+    let output_setup_bindings = options.output_setup_bindings.unwrap_or_default();
+    let compile_options = create_compile_options(options);
+
+    let compiled = compile(source, compile_options).map_err(|e| JsError::new(&e.to_string()))?;
+    let result = convert_compile_result(compiled, source, output_setup_bindings);
+
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+    Ok(result.serialize(&serializer)?.into())
 }
 
+/// Parses a Vue SFC and returns its descriptor, e.g. for showing the AST.
+/// When `options` are provided, the template and styles are transformed like in `compile_sync`.
 #[wasm_bindgen]
-pub fn compile_sync(source: &str, is_prod: Option<bool>) -> Result<WasmCompileResult, String> {
-    // compile_sync_naive(source, is_prod.unwrap_or(false))
-    let compile_result = compile(
-        source,
-        CompileOptions {
-            filename: "anonymous.vue".into(),
-            id: "".into(),
-            is_prod,
-            is_custom_element: Some(false),
-            props_destructure: Some(PropsDestructureConfig::True),
-            ssr: Some(false),
-            gen_default_as: None,
-            source_map: None,
-            transform_asset_urls: None,
-        },
-    );
-
-    match compile_result {
-        Ok(compiled) => Ok(convert_compile_result(compiled, source)),
-
-        Err(e) => Err(e.to_string()),
+pub fn parse(source: &str, options: Option<JsCompileOptions>) -> Result<JsParseResult, JsError> {
+    let compile_options = match options {
+        Some(options) => {
+            let options: WasmCompileOptions = serde_wasm_bindgen::from_value(options.into())?;
+            Some(create_compile_options(options))
+        }
+        None => None,
+    };
+
+    let parsed = fervid::parse(source, compile_options.as_ref())
+        .map_err(|e| JsError::new(&e.to_string()))?;
+    let result = WasmParseResult {
+        descriptor: parsed.descriptor,
+        errors: convert_errors(parsed.errors, source),
+    };
+
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+    Ok(result.serialize(&serializer)?.into())
+}
+
+fn create_compile_options(options: WasmCompileOptions) -> CompileOptions<'static> {
+    CompileOptions {
+        filename: options
+            .filename
+            .unwrap_or_else(|| "anonymous.vue".to_owned())
+            .into(),
+        id: options.id.unwrap_or_default().into(),
+        is_prod: options.is_production,
+        is_custom_element: options.is_custom_element,
+        ssr: options.ssr,
+        strip_client_only: options.strip_client_only,
+        target: options.target,
+        props_destructure: Some(
+            options
+                .props_destructure
+                .map_or(PropsDestructureConfig::True, From::from),
+        ),
+        transform_asset_urls: options.transform_asset_urls.map(From::from),
+        gen_default_as: options.gen_default_as.map(From::from),
+        source_map: options.source_map,
+        inline_template: None,
+        template_plugins: None,
+        custom_blocks: options
+            .i18n
+            .unwrap_or(false)
+            .then(CustomBlockHandlers::with_i18n),
+        module_loader: None,
+        error_recovery_mode: options.error_recovery_mode,
     }
 }
 
-fn convert_compile_result(compiled: CompileResult, source: &str) -> WasmCompileResult {
-    let code = compiled.code;
+fn convert_compile_result(
+    compiled: CompileResult,
+    source: &str,
+    output_setup_bindings: bool,
+) -> WasmCompileResult {
+    let errors = convert_errors(compiled.errors, source);
 
-    let mut errors = vec![];
-    if !compiled.errors.is_empty() {
-        let cm: Lrc<SourceMap> = Default::default();
-        cm.new_source_file(
-            Lrc::new(swc_core::common::FileName::Anon),
-            source.to_owned(),
-        );
-        errors.reserve(compiled.errors.len());
+    let setup_bindings = output_setup_bindings.then(|| {
+        compiled
+            .setup_bindings
+            .into_iter()
+            .map(|binding| {
+                (
+                    binding.sym.to_string(),
+                    binding_type_to_str(binding.binding_type),
+                )
+            })
+            .collect()
+    });
 
-        for error in compiled.errors {
+    WasmCompileResult {
+        code: compiled.code,
+        styles: compiled.styles.into_iter().map(From::from).collect(),
+        errors,
+        custom_blocks: compiled.other_assets.into_iter().map(From::from).collect(),
+        source_map: compiled.source_map,
+        setup_bindings,
+    }
+}
+
+fn convert_errors(compile_errors: Vec<CompileError>, source: &str) -> Vec<WasmCompileError> {
+    compile_errors
+        .into_iter()
+        .map(|error| {
             let span = error.span();
-            let start = cm.lookup_char_pos(span.lo);
-            let end = cm.lookup_char_pos(span.hi);
-            errors.push(WasmCompileError {
+            let start = lookup_location(source, span.lo);
+            let end = lookup_location(source, span.hi);
+            WasmCompileError {
+                lo: span.lo.0,
+                hi: span.hi.0,
+                message: error.to_string(),
+                code: error.code(),
+                severity: error.severity().into(),
+                code_frame: render_code_frame(source, span),
                 start_line_number: start.line,
                 end_line_number: end.line,
-                start_column: start.col.0,
-                end_column: end.col.0,
-                message: error.to_string(),
-            })
+                start_column: start.column,
+                end_column: end.column,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{de, forward_to_deserialize_any, Deserialize};
+
+    use super::*;
+    use crate::structs::WasmTransformAssetUrlsOptions;
+    use fervid::{CompileTarget, ErrorRecoveryMode};
+
+    #[test]
+    fn ts_types_match_options() {
+        assert_interface_fields("WasmCompileOptions", input_fields::<WasmCompileOptions>());
+        assert_interface_fields(
+            "TransformAssetUrlsOptions",
+            input_fields::<WasmTransformAssetUrlsOptions>(),
+        );
+    }
+
+    #[test]
+    fn ts_types_match_results() {
+        let source = r#"<script setup>
+const foo = 1
+defineOptions({ props: {} })
+</script>
+<template><div>{{ foo }}</div></template>
+<style scoped>div { color: red }</style>
+<i18n>{ "en": {} }</i18n>"#;
+
+        let options: WasmCompileOptions = serde_json::from_str(
+            r#"{ "sourceMap": true, "i18n": true, "outputSetupBindings": true }"#,
+        )
+        .unwrap();
+        let compiled = compile(source, create_compile_options(options)).unwrap();
+        let result = convert_compile_result(compiled, source, true);
+        assert!(!result.styles.is_empty() && !result.errors.is_empty());
+
+        let result = serde_json::to_value(&result).unwrap();
+        assert_interface_fields("WasmCompileResult", output_fields(&result));
+        assert_interface_fields("WasmStyle", output_fields(&result["styles"][0]));
+        assert_interface_fields("WasmCompileError", output_fields(&result["errors"][0]));
+        assert_interface_fields("WasmCustomBlock", output_fields(&result["customBlocks"][0]));
+
+        let parsed = fervid::parse(source, None).unwrap();
+        let result = WasmParseResult {
+            descriptor: parsed.descriptor,
+            errors: convert_errors(parsed.errors, source),
+        };
+        let result = serde_json::to_value(&result).unwrap();
+        assert_interface_fields("WasmParseResult", output_fields(&result));
+    }
+
+    #[test]
+    fn it_validates_props_destructure() {
+        macro_rules! props_destructure {
+            ($json: literal) => {
+                serde_json::from_str::<WasmCompileOptions>(concat!(
+                    r#"{ "propsDestructure": "#,
+                    $json,
+                    " }"
+                ))
+                .map(|options| create_compile_options(options).props_destructure)
+            };
+        }
+
+        assert!(matches!(
+            props_destructure!("false"),
+            Ok(Some(PropsDestructureConfig::False))
+        ));
+        assert!(matches!(
+            props_destructure!(r#""error""#),
+            Ok(Some(PropsDestructureConfig::Error))
+        ));
+        assert!(matches!(
+            create_compile_options(WasmCompileOptions::default()).props_destructure,
+            Some(PropsDestructureConfig::True)
+        ));
+
+        let error = props_destructure!(r#""errror""#).err().unwrap();
+        assert!(error
+            .to_string()
+            .contains("`propsDestructure` must be a boolean or 'error'"));
+    }
+
+    #[test]
+    fn it_validates_enum_options() {
+        let options: WasmCompileOptions =
+            serde_json::from_str(r#"{ "target": "vue2", "errorRecoveryMode": "soft-report-all" }"#)
+                .unwrap();
+        let compile_options = create_compile_options(options);
+        assert_eq!(compile_options.target, Some(CompileTarget::Vue2));
+        assert_eq!(
+            compile_options.error_recovery_mode,
+            Some(ErrorRecoveryMode::SoftReportAll)
+        );
+
+        for json in [
+            r#"{ "target": "vue-2" }"#,
+            r#"{ "errorRecoveryMode": "soft" }"#,
+        ] {
+            let error = serde_json::from_str::<WasmCompileOptions>(json)
+                .err()
+                .unwrap();
+            assert!(
+                error.to_string().contains("expected a known value"),
+                "{error}"
+            );
         }
     }
 
-    WasmCompileResult { code, errors }
+    /// Compares the fields of a Rust struct to the ones of a TS interface in `TS_TYPES`
+    fn assert_interface_fields(interface: &str, rust_fields: Vec<String>) {
+        let start = format!("export interface {interface} {{");
+        let body = TS_TYPES
+            .split_once(&start)
+            .and_then(|(_, rest)| rest.split_once("\n}"))
+            .unwrap_or_else(|| panic!("{interface} is missing in TS_TYPES"))
+            .0;
+
+        let mut ts_fields: Vec<String> = body
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("/*") && !line.starts_with('*'))
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, _)| name.trim_end_matches('?').to_owned())
+            .collect();
+
+        let mut rust_fields = rust_fields;
+        ts_fields.sort();
+        rust_fields.sort();
+        assert_eq!(ts_fields, rust_fields, "{interface} is out of sync");
+    }
+
+    fn output_fields(value: &serde_json::Value) -> Vec<String> {
+        value
+            .as_object()
+            .expect("an object")
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// Collects the serialized field names of a struct by asking it to deserialize itself
+    fn input_fields<'de, T: Deserialize<'de>>() -> Vec<String> {
+        let mut introspect = FieldsIntrospector(&[]);
+        let _ = T::deserialize(&mut introspect);
+        introspect.0.iter().map(|f| f.to_string()).collect()
+    }
+
+    struct FieldsIntrospector(&'static [&'static str]);
+
+    impl<'de> de::Deserializer<'de> for &mut FieldsIntrospector {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: de::Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+            Err(de::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V: de::Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            self.0 = fields;
+            Err(de::Error::custom("introspected"))
+        }
+
+        forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map enum identifier ignored_any
+        }
+    }
 }
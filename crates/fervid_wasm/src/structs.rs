@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+
+use fervid::{BindingTypes, CompileTarget, ErrorRecoveryMode, FervidAtom, PropsDestructureConfig};
+use fervid_transform::{TransformAssetUrlsConfig, TransformAssetUrlsConfigOptions};
+use fxhash::FxHashMap;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::{str::FromStr, sync::Arc};
+
+/// Options passed from the JS side.
+/// Mirrors the NAPI `FervidCompileOptions` together with the compiler-wide options.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WasmCompileOptions {
+    /// Filename is used for automatic component name inference and self-referential imports.
+    /// Default: `anonymous.vue`
+    pub filename: Option<String>,
+
+    /// Scope ID for prefixing injected CSS variables
+    pub id: Option<String>,
+
+    /// Apply production optimizations. Default: false
+    pub is_production: Option<bool>,
+
+    /// Is the currently compiled file a custom element
+    pub is_custom_element: Option<bool>,
+
+    /// Enable SSR. Default: false
+    pub ssr: Option<bool>,
+
+    /// Together with `ssr`, drop the client-only lifecycle hooks and native DOM listeners
+    pub strip_client_only: Option<bool>,
+
+    /// Vue version the component is compiled for, `vue3` or `vue2`. Default: `vue3`
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub target: Option<CompileTarget>,
+
+    /// Enable source maps
+    pub source_map: Option<bool>,
+
+    /// Generate a const instead of default export
+    pub gen_default_as: Option<String>,
+
+    /// Enable, disable or error on props destructure
+    pub props_destructure: Option<WasmPropsDestructure>,
+
+    /// Options for transforming asset URLs in template
+    pub transform_asset_urls: Option<WasmTransformAssetUrls>,
+
+    /// Compile `<i18n>` blocks and attach them to the component
+    pub i18n: Option<bool>,
+
+    /// Whether setup bindings need to be serialized
+    pub output_setup_bindings: Option<bool>,
+
+    /// How the parser proceeds after encountering an error, e.g. `soft-recover-unsafe`
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub error_recovery_mode: Option<ErrorRecoveryMode>,
+}
+
+/// Options which are parsed from a string, an unknown value is an error instead of the silent default
+fn deserialize_from_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| {
+            value.parse().map_err(|_| {
+                de::Error::invalid_value(de::Unexpected::Str(&value), &"a known value")
+            })
+        })
+        .transpose()
+}
+
+/// `boolean | 'error'`
+#[derive(Deserialize)]
+#[serde(
+    untagged,
+    expecting = "`propsDestructure` must be a boolean or 'error'"
+)]
+pub enum WasmPropsDestructure {
+    Bool(bool),
+    Mode(WasmPropsDestructureMode),
+}
+
+/// String values of `propsDestructure`
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WasmPropsDestructureMode {
+    Error,
+}
+
+/// `boolean | TransformAssetUrlsOptions`
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum WasmTransformAssetUrls {
+    Bool(bool),
+    Options(WasmTransformAssetUrlsOptions),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WasmTransformAssetUrlsOptions {
+    pub base: Option<String>,
+    pub include_absolute: Option<bool>,
+    pub tags: Option<HashMap<String, Vec<String>>>,
+    pub aliases: Option<HashMap<String, String>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WasmCompileResult {
+    pub code: String,
+    pub styles: Vec<WasmStyle>,
+    pub errors: Vec<WasmCompileError>,
+    pub custom_blocks: Vec<WasmCustomBlock>,
+    pub source_map: Option<String>,
+    pub setup_bindings: Option<HashMap<String, &'static str>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WasmStyle {
+    pub code: String,
+    pub is_compiled: bool,
+    pub lang: String,
+    pub is_scoped: bool,
+    pub source_map: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WasmCustomBlock {
+    pub content: String,
+    pub lo: u32,
+    pub hi: u32,
+    pub tag_name: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WasmCompileError {
+    pub lo: u32,
+    pub hi: u32,
+    pub message: String,
+    pub code: &'static str,
+    pub severity: &'static str,
+    pub code_frame: String,
+    pub start_line_number: usize,
+    pub end_line_number: usize,
+    pub start_column: usize,
+    pub end_column: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WasmParseResult {
+    pub descriptor: fervid::SfcDescriptor,
+    pub errors: Vec<WasmCompileError>,
+}
+
+//
+// Input De-Serialization
+//
+
+impl From<WasmPropsDestructure> for PropsDestructureConfig {
+    fn from(value: WasmPropsDestructure) -> Self {
+        match value {
+            WasmPropsDestructure::Bool(true) => PropsDestructureConfig::True,
+            WasmPropsDestructure::Bool(false) => PropsDestructureConfig::False,
+            WasmPropsDestructure::Mode(WasmPropsDestructureMode::Error) => {
+                PropsDestructureConfig::Error
+            }
+        }
+    }
+}
+
+impl From<WasmTransformAssetUrls> for TransformAssetUrlsConfig {
+    fn from(value: WasmTransformAssetUrls) -> Self {
+        match value {
+            WasmTransformAssetUrls::Bool(true) => TransformAssetUrlsConfig::EnabledDefault,
+            WasmTransformAssetUrls::Bool(false) => TransformAssetUrlsConfig::Disabled,
+            WasmTransformAssetUrls::Options(options) => {
+                TransformAssetUrlsConfig::EnabledOptions(Arc::new(options.into()))
+            }
+        }
+    }
+}
+
+impl From<WasmTransformAssetUrlsOptions> for TransformAssetUrlsConfigOptions {
+    fn from(value: WasmTransformAssetUrlsOptions) -> TransformAssetUrlsConfigOptions {
+        let tags = if let Some(wasm_tags) = value.tags {
+            let mut tags = FxHashMap::default();
+
+            for (tag, attrs) in wasm_tags {
+                tags.insert(
+                    tag.into(),
+                    attrs.into_iter().map(FervidAtom::from).collect(),
+                );
+            }
+
+            tags
+        } else {
+            TransformAssetUrlsConfigOptions::default().tags
+        };
+
+        TransformAssetUrlsConfigOptions {
+            base: value.base,
+            include_absolute: value.include_absolute.unwrap_or_default(),
+            tags,
+            aliases: value
+                .aliases
+                .map(|aliases| aliases.into_iter().collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+//
+// OUTPUT Serialization
+//
+
+impl From<fervid::CompileEmittedStyle> for WasmStyle {
+    fn from(value: fervid::CompileEmittedStyle) -> Self {
+        Self {
+            code: value.code,
+            is_compiled: value.is_compiled,
+            lang: value.lang,
+            is_scoped: value.is_scoped,
+            source_map: value.source_map,
+        }
+    }
+}
+
+impl From<fervid::CompileEmittedAsset> for WasmCustomBlock {
+    fn from(value: fervid::CompileEmittedAsset) -> Self {
+        WasmCustomBlock {
+            content: value.content,
+            lo: value.lo,
+            hi: value.hi,
+            tag_name: value.tag_name,
+        }
+    }
+}
+
+/// Binding types are serialized the same way as in `@vue/compiler-core`,
+/// with the fervid-specific ones following the same naming.
+pub fn binding_type_to_str(binding_type: BindingTypes) -> &'static str {
+    match binding_type {
+        BindingTypes::Data => "data",
+        BindingTypes::Props => "props",
+        BindingTypes::PropsAliased => "props-aliased",
+        BindingTypes::SetupLet => "setup-let",
+        BindingTypes::SetupConst => "setup-const",
+        BindingTypes::SetupReactiveConst => "setup-reactive-const",
+        BindingTypes::SetupMaybeRef => "setup-maybe-ref",
+        BindingTypes::SetupRef => "setup-ref",
+        BindingTypes::Options => "options",
+        BindingTypes::LiteralConst => "literal-const",
+        BindingTypes::Component => "component",
+        BindingTypes::Imported => "imported",
+        BindingTypes::TemplateLocal => "template-local",
+        BindingTypes::JsGlobal => "js-global",
+        BindingTypes::Unresolved => "unresolved",
+    }
+}
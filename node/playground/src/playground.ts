@@ -98,7 +98,7 @@ function mountEditor() {
             endLineNumber: it.endLineNumber,
             startColumn: it.startColumn + 1,
             endColumn: it.endColumn + 1,
-            severity: 8,
+            severity: it.severity === 'warning' ? 4 : 8,
             message: it.message
         }))
 